@@ -0,0 +1,205 @@
+"""Remediation acceptance tests from confirmed bankrun exploits.
+
+A bankrun exploit that confirms a finding is also the test its fix has to
+pass. For every CONFIRMED exploit whose program belongs to the target, the
+orchestrator writes a mocha test that replays the exploit against the
+program built from the fixed source and asserts it no longer works. The
+tests go to the run's output directory (`<output>/acceptance/`); only with
+`--write-acceptance-tests` are they written into the target's Anchor
+workspace (`<workspace>/tests/acceptance/<exploit>.ts`, picked up by
+`anchor test`), and then only when the target is inside one (a directory
+with Anchor.toml) and never over an existing file:
+
+  - exploits confirmed by a transaction that went through assert that the
+    exploit's last transaction is now rejected;
+  - exploits confirmed by the failure they provoked (a panic, a permanently
+    failing constraint) assert that no transaction fails with that error
+    any more — the errors are taken from the original run's output.
+
+The test embeds the exploit source as it was confirmed and records every
+`BanksClient.processTransaction` outcome, so it needs no hand-written
+hooks. An exploit belongs to the target when its PROGRAM_ID is one of the
+target's declare_id! addresses or the binary it loads is one of the
+target's .so files.
+"""
+
+import json
+import os
+import re
+from dataclasses import dataclass, field
+from typing import List, Optional, Tuple
+
+ERROR_RE = re.compile(r"Error processing Instruction \d+: ([^\n\"]+)")
+PROGRAM_ID_RE = re.compile(r"\bconst\s+PROGRAM_ID\s*=\s*new\s+PublicKey\(\s*\"(\w+)\"\s*\)")
+BINARY_RE = re.compile(r"\bname:\s*\"(\w+)\"\s*,\s*programId\b")
+DECLARE_ID_RE = re.compile(r"\bdeclare_id!\s*\(\s*\"(\w+)\"\s*\)")
+MAIN_CALL_RE = re.compile(r"^main\(\)", re.M)
+
+
+@dataclass
+class AcceptanceTest:
+    exploit: str
+    title: str
+    path: str
+    # "rejected": the exploit transaction must fail; "no_longer_fails": the original errors must be gone
+    asserts: str
+    errors: List[str] = field(default_factory=list)
+    # False when a file already existed at `path` and was left as it is
+    written: bool = True
+
+    def to_dict(self) -> dict:
+        return {
+            "exploit": os.path.basename(self.exploit),
+            "title": self.title,
+            "test": self.path,
+            "asserts": self.asserts,
+            "errors": self.errors,
+            "written": self.written,
+        }
+
+
+def write_acceptance_tests(bankrun_results: list, target_path: str, out_dir: str) -> List[AcceptanceTest]:
+    """Write an acceptance test for each confirmed bankrun exploit of the target into `out_dir`.
+
+    Existing files are not overwritten; their tests are returned with written=False.
+    """
+    ids, binaries = target_programs(target_path)
+    written = []
+    for result in bankrun_results:
+        if result.get("status") != "CONFIRMED":
+            continue
+        exploit = result["file"]
+        try:
+            with open(exploit, "r", encoding="utf-8", errors="ignore") as fh:
+                source = fh.read()
+        except OSError:
+            continue
+        if not belongs_to(source, ids, binaries):
+            continue
+        errors = original_errors(result.get("output", ""))
+        name = os.path.basename(exploit).replace("bankrun_exploit_", "")
+        path = os.path.join(out_dir, name)
+        exists = os.path.exists(path)
+        if not exists:
+            os.makedirs(out_dir, exist_ok=True)
+            with open(path, "w") as fh:
+                fh.write(render_test(source, os.path.basename(exploit), result.get("title", name), errors))
+        written.append(AcceptanceTest(
+            exploit=exploit,
+            title=result.get("title", name),
+            path=path,
+            asserts="no_longer_fails" if errors else "rejected",
+            errors=errors,
+            written=not exists,
+        ))
+    return written
+
+
+def target_programs(target_path: str) -> Tuple[set, set]:
+    """(declare_id! addresses, .so binary names) of the programs under the target."""
+    ids, binaries = set(), set()
+    root = target_path if os.path.isdir(target_path) else os.path.dirname(target_path)
+    for dirpath, dirs, files in os.walk(root):
+        dirs[:] = [d for d in dirs if d not in ("node_modules", ".git", "tests")]
+        for f in files:
+            if f.endswith(".so"):
+                binaries.add(f[:-3])
+            elif f.endswith(".rs"):
+                with open(os.path.join(dirpath, f), "r", encoding="utf-8", errors="ignore") as fh:
+                    ids.update(DECLARE_ID_RE.findall(fh.read()))
+    return ids, binaries
+
+
+def belongs_to(exploit_source: str, ids: set, binaries: set) -> bool:
+    program = PROGRAM_ID_RE.search(exploit_source)
+    if program and program.group(1) in ids:
+        return True
+    return any(name in binaries for name in BINARY_RE.findall(exploit_source))
+
+
+def original_errors(output: str) -> List[str]:
+    """Instruction errors of the confirmed run, in order and without repeats."""
+    errors = []
+    for m in ERROR_RE.finditer(output):
+        error = m.group(1).strip()
+        if error not in errors:
+            errors.append(error)
+    return errors
+
+
+def workspace_tests_dir(target_path: str) -> Optional[str]:
+    """`tests/acceptance/` of the Anchor workspace holding the target (the directory with Anchor.toml), else None."""
+    start = target_path if os.path.isdir(target_path) else os.path.dirname(target_path)
+    current = os.path.abspath(start)
+    while True:
+        if os.path.exists(os.path.join(current, "Anchor.toml")):
+            return os.path.join(current, "tests", "acceptance")
+        parent = os.path.dirname(current)
+        if parent == current:
+            return None
+        current = parent
+
+
+def render_test(exploit_source: str, exploit_name: str, title: str, errors: List[str]) -> str:
+    """Mocha test replaying the exploit and asserting it no longer works."""
+    calls = list(MAIN_CALL_RE.finditer(exploit_source))
+    body = exploit_source[:calls[-1].start()] if calls else exploit_source
+    binaries = sorted(set(BINARY_RE.findall(exploit_source)))
+    if errors:
+        check = (
+            f"    const confirmed: string[] = {json.dumps(errors)};\n"
+            "    for (const outcome of exploitOutcomes) {\n"
+            "      for (const error of confirmed) {\n"
+            "        assert.ok(\n"
+            "          !(outcome.error ?? \"\").includes(error),\n"
+            "          `exploit still fails the program with the confirmed error: ${error}`\n"
+            "        );\n"
+            "      }\n"
+            "    }\n"
+        )
+        it = "no transaction fails with the confirmed error"
+    else:
+        check = (
+            "    const last = exploitOutcomes[exploitOutcomes.length - 1];\n"
+            "    assert.notEqual(last.error, null, \"exploit transaction still succeeds\");\n"
+        )
+        it = "exploit transaction is rejected"
+    return (
+        f"/**\n"
+        f" * ACCEPTANCE TEST: {title}\n"
+        f" *\n"
+        f" * Generated by anchor-shield-v2 from the confirmed exploit {exploit_name}.\n"
+        f" * Passes once the fix is in: build the program "
+        f"({', '.join(f'{b}.so' for b in binaries) or 'its .so'}) into target/deploy\n"
+        f" * and run `anchor test`. Written once: later runs keep this file as it is,\n"
+        f" * so it can be edited.\n"
+        f" */\n"
+        f"import {{ BanksClient }} from \"solana-bankrun\";\n"
+        f"import {{ strict as assert }} from \"assert\";\n"
+        f"\n"
+        f"const exploitOutcomes: {{ error: string | null }}[] = [];\n"
+        f"const processTransaction = BanksClient.prototype.processTransaction;\n"
+        f"BanksClient.prototype.processTransaction = async function (...args: any[]) {{\n"
+        f"  try {{\n"
+        f"    const meta = await processTransaction.apply(this, args);\n"
+        f"    exploitOutcomes.push({{ error: null }});\n"
+        f"    return meta;\n"
+        f"  }} catch (err) {{\n"
+        f"    exploitOutcomes.push({{ error: String(err) }});\n"
+        f"    throw err;\n"
+        f"  }}\n"
+        f"}} as any;\n"
+        f"\n"
+        f"// --- exploit as confirmed ({exploit_name}) ---\n"
+        f"{body.rstrip()}\n"
+        f"\n"
+        f"describe({json.dumps('acceptance: ' + title)}, function () {{\n"
+        f"  this.timeout(120_000);\n"
+        f"\n"
+        f"  it({json.dumps(it)}, async () => {{\n"
+        f"    await main();\n"
+        f"    assert.ok(exploitOutcomes.length > 0, \"exploit sent no transaction\");\n"
+        f"{check}"
+        f"  }});\n"
+        f"}});\n"
+    )
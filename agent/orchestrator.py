@@ -4,7 +4,8 @@ Runs the complete anchor-shield-v2 analysis pipeline:
   1. Static regex pattern scanning
   2. Semantic LLM analysis for logic vulnerabilities
   3. Exploit proof-of-concept generation
-  4. Bankrun exploit execution (compiled SBF binary)
+  4. Bankrun exploit execution (compiled SBF binary), with acceptance
+     tests for the fixes of confirmed exploits
   5. Python simulation fallback
   6. Consolidated report generation
 
@@ -33,6 +34,7 @@ if _PROJECT_ROOT not in sys.path:
 from scanner.engine import AnchorShieldEngine
 from semantic.analyzer import SemanticAnalyzer, SemanticFinding
 from adversarial.synthesizer import ExploitSynthesizer, ExploitCode
+from agent.acceptance import workspace_tests_dir, write_acceptance_tests
 
 
 # Terminal formatting
@@ -107,6 +109,7 @@ class SecurityOrchestrator:
         target_path: str,
         execute_exploits: bool = True,
         output_dir: Optional[str] = None,
+        write_tests: bool = False,
     ) -> dict:
         """Run the complete analysis pipeline.
 
@@ -114,6 +117,8 @@ class SecurityOrchestrator:
             target_path: Path to the Anchor program directory or file.
             execute_exploits: Whether to execute generated exploits.
             output_dir: Directory for output files. Defaults to target_path.
+            write_tests: Write acceptance tests into the target's Anchor
+                workspace (tests/acceptance/) instead of output_dir.
 
         Returns:
             Complete security report as a dictionary.
@@ -177,6 +182,7 @@ class SecurityOrchestrator:
 
         # Phase 4: Bankrun exploit execution (if binary available)
         bankrun_results = []
+        acceptance_tests = []
         bankrun_dir = os.path.join(_PROJECT_ROOT, "exploits")
         has_bankrun = self._has_bankrun(bankrun_dir)
         binary_path = self._find_binary(target_path)
@@ -193,6 +199,19 @@ class SecurityOrchestrator:
                 }.get(result["status"], result["status"])
                 short_title = result.get("title", os.path.basename(bx))[:42]
                 print(f"      {short_title:<44} {status_icon}")
+            tests_out = os.path.join(output_dir, "acceptance")
+            if write_tests:
+                workspace_tests = workspace_tests_dir(target_path)
+                if workspace_tests:
+                    tests_out = workspace_tests
+                else:
+                    print(f"      {DIM}No Anchor.toml above the target — acceptance tests go to the output dir{RESET}")
+            acceptance_tests = write_acceptance_tests(bankrun_results, target_path, tests_out)
+            new_tests = [t for t in acceptance_tests if t.written]
+            if acceptance_tests:
+                print(f"      Acceptance tests: {len(new_tests)} written to {tests_out}/")
+            for kept in (t for t in acceptance_tests if not t.written):
+                print(f"      {DIM}Kept existing {kept.path}{RESET}")
             print()
         elif execute_exploits:
             _print_phase(4, 6, "Bankrun exploits...")
@@ -278,6 +297,7 @@ class SecurityOrchestrator:
                 }
                 for r in bankrun_results
             ],
+            "acceptance_tests": [t.to_dict() for t in acceptance_tests],
             "python_exploits": [
                 {
                     "finding_id": exploit.finding_id,
@@ -298,6 +318,7 @@ class SecurityOrchestrator:
                 "exploits_generated": len(exploits),
                 "bankrun_exploits_confirmed": bankrun_confirmed,
                 "python_exploits_simulated": python_simulated,
+                "acceptance_tests_written": sum(1 for t in acceptance_tests if t.written),
                 "exploits_confirmed": confirmed_count,
                 "logic_bugs_missed_by_regex": len(all_semantic_findings),
             },
@@ -518,6 +539,12 @@ def main():
         "--binary",
         help="Path to compiled .so binary for bankrun execution",
     )
+    parser.add_argument(
+        "--write-acceptance-tests",
+        action="store_true",
+        help="Write acceptance tests for confirmed exploits into the target's Anchor "
+             "workspace (tests/acceptance/) instead of the output dir; existing files are kept",
+    )
 
     args = parser.parse_args()
 
@@ -526,6 +553,7 @@ def main():
         target_path=args.target,
         execute_exploits=not args.no_execute,
         output_dir=args.output_dir,
+        write_tests=args.write_acceptance_tests,
     )
 
 
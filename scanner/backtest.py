@@ -0,0 +1,228 @@
+"""
+Backtesting rules against historically exploited code.
+
+A backtest case names a pre-exploit snapshot of a protocol and the rule
+category its root cause belongs to; the harness scans that snapshot with
+only those rules and reports whether the root cause was flagged:
+
+    {
+      "name": "escrow-cancel-without-signer",
+      "target": "../real-world-targets/anchor-escrow",   # dir or git URL
+      "commit": "<pre-exploit sha>",                      # optional
+      "category": "accounts",                  # category, rule ID or glob
+      "root_cause": {"file": "lib.rs", "lines": [163, 163]},
+      "description": "cancel_escrow accepts an unsigned initializer"
+    }
+
+Local targets are resolved relative to the cases file. With a commit, a
+local git target is exported at that commit (`git archive`) and a URL is
+cloned via registry.clone_verified_source. A case without `root_cause`
+counts as caught when any rule of the category fires; with one, a finding
+has to land in that file within LINE_TOLERANCE lines of the range.
+
+The share of caught cases is the coverage-of-history metric that
+`bench report` publishes alongside rule precision.
+"""
+
+import io
+import json
+import os
+import re
+import subprocess
+import tarfile
+import tempfile
+from dataclasses import dataclass, field
+from typing import Optional
+
+from scanner.engine import AnchorShieldEngine
+
+PROJECT_ROOT = os.path.dirname(os.path.dirname(os.path.abspath(__file__)))
+
+DEFAULT_CASES = os.path.join(PROJECT_ROOT, "backtests", "cases.json")
+
+# Findings this many lines outside the documented root cause still count
+LINE_TOLERANCE = 3
+
+
+@dataclass
+class BacktestCase:
+    name: str
+    target: str
+    category: list
+    commit: Optional[str] = None
+    root_cause: Optional[dict] = None
+    description: str = ""
+
+    @classmethod
+    def from_dict(cls, data: dict, base_dir: str = ".") -> "BacktestCase":
+        for key in ("name", "target", "category"):
+            if key not in data:
+                raise ValueError(f"backtest case missing '{key}': {data}")
+        target = data["target"]
+        if not _is_remote(target):
+            target = os.path.normpath(os.path.join(base_dir, target))
+        category = data["category"]
+        return cls(
+            name=data["name"],
+            target=target,
+            category=[category] if isinstance(category, str) else list(category),
+            commit=data.get("commit"),
+            root_cause=data.get("root_cause"),
+            description=data.get("description", ""),
+        )
+
+    def selectors(self) -> list:
+        """Rule selectors for the category: IDs/globs as-is, bare names as a namespace."""
+        return [
+            c if ("::" in c or re.match(r"(?i)anchor-\d", c) or "*" in c) else f"shield::{c}::*"
+            for c in self.category
+        ]
+
+
+@dataclass
+class BacktestResult:
+    case: BacktestCase
+    caught: bool = False
+    matched: list = field(default_factory=list)
+    category_findings: int = 0
+    error: Optional[str] = None
+
+    def to_dict(self) -> dict:
+        return {
+            "name": self.case.name,
+            "target": _display_path(self.case.target),
+            "commit": self.case.commit,
+            "category": self.case.category,
+            "root_cause": self.case.root_cause,
+            "caught": self.caught,
+            "matched": self.matched,
+            "category_findings": self.category_findings,
+            "error": self.error,
+        }
+
+
+@dataclass
+class BacktestReport:
+    results: list = field(default_factory=list)
+
+    @property
+    def evaluated(self) -> list:
+        return [r for r in self.results if r.error is None]
+
+    @property
+    def coverage(self) -> Optional[float]:
+        """Share of evaluated cases whose root cause was flagged."""
+        if not self.evaluated:
+            return None
+        return sum(r.caught for r in self.evaluated) / len(self.evaluated)
+
+    def by_category(self) -> dict:
+        """{category: {"caught", "total"}} over evaluated cases."""
+        table = {}
+        for r in self.evaluated:
+            for category in r.case.category:
+                row = table.setdefault(category, {"caught": 0, "total": 0})
+                row["total"] += 1
+                row["caught"] += int(r.caught)
+        return dict(sorted(table.items()))
+
+    def to_dict(self) -> dict:
+        return {
+            "coverage": round(self.coverage, 4) if self.coverage is not None else None,
+            "caught": sum(r.caught for r in self.evaluated),
+            "evaluated": len(self.evaluated),
+            "errors": len(self.results) - len(self.evaluated),
+            "by_category": self.by_category(),
+            "cases": [r.to_dict() for r in self.results],
+        }
+
+    def to_json(self, indent: int = 2) -> str:
+        return json.dumps(self.to_dict(), indent=indent)
+
+
+def load_cases(path: str) -> list[BacktestCase]:
+    """Read a JSON list of cases (or {"cases": [...]})."""
+    with open(path) as fh:
+        data = json.load(fh)
+    entries = data["cases"] if isinstance(data, dict) else data
+    base_dir = os.path.dirname(os.path.abspath(path))
+    return [BacktestCase.from_dict(e, base_dir) for e in entries]
+
+
+def run_case(case: BacktestCase) -> BacktestResult:
+    """Scan the case's snapshot with its category's rules."""
+    result = BacktestResult(case=case)
+    try:
+        engine = AnchorShieldEngine(split_concatenated=True, only=case.selectors())
+    except ValueError as e:
+        result.error = str(e)
+        return result
+    try:
+        with tempfile.TemporaryDirectory(prefix="anchor-shield-backtest-") as tmp:
+            scan = engine.scan_directory(materialize(case.target, case.commit, tmp))
+    except (OSError, subprocess.SubprocessError, tarfile.TarError) as e:
+        result.error = f"could not check out {case.target}: {e}"
+        return result
+
+    result.category_findings = len(scan.findings)
+    result.matched = [
+        {"id": f.id, "file": f.file, "line": f.line}
+        for f in scan.findings if _hits_root_cause(f, case.root_cause)
+    ]
+    result.caught = bool(result.matched)
+    return result
+
+
+def run_backtests(cases: list[BacktestCase]) -> BacktestReport:
+    return BacktestReport(results=[run_case(c) for c in cases])
+
+
+def _display_path(target: str) -> str:
+    """Targets inside the repo are shown relative to it, so published results stay portable."""
+    if _is_remote(target) or not os.path.abspath(target).startswith(PROJECT_ROOT + os.sep):
+        return target
+    return os.path.relpath(target, PROJECT_ROOT)
+
+
+def _is_remote(target: str) -> bool:
+    return bool(re.match(r"^(?:https?://|git@|ssh://)", target))
+
+
+def materialize(target: str, commit: Optional[str], tmp: str) -> str:
+    """Directory holding target's sources at commit (a local dir or git URL).
+
+    Without a commit a local target is used in place; otherwise the snapshot
+    is written under tmp, which the caller owns.
+    """
+    if _is_remote(target):
+        from scanner.registry import clone_verified_source
+
+        return clone_verified_source(target, commit or "HEAD", os.path.join(tmp, "repo"))
+    if not os.path.exists(target):
+        raise FileNotFoundError(target)
+    if not commit:
+        return target
+    archive = subprocess.run(
+        ["git", "-C", target, "archive", "--format=tar", commit, "."],
+        check=True, capture_output=True, timeout=120,
+    ).stdout
+    with tarfile.open(fileobj=io.BytesIO(archive)) as tar:
+        try:
+            tar.extractall(tmp, filter="data")
+        except TypeError:  # Python without extraction filters
+            tar.extractall(tmp)
+    return tmp
+
+
+def _hits_root_cause(finding, root_cause: Optional[dict]) -> bool:
+    if not root_cause:
+        return True
+    wanted = root_cause.get("file", "").replace(os.sep, "/")
+    path = finding.file.replace(os.sep, "/")
+    if wanted and not (path == wanted or path.endswith("/" + wanted)):
+        return False
+    lines = root_cause.get("lines")
+    if not lines:
+        return True
+    start, end = lines[0], lines[-1]
+    return start - LINE_TOLERANCE <= finding.line <= end + LINE_TOLERANCE
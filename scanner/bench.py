@@ -0,0 +1,321 @@
+"""
+Corpus-wide rule precision benchmark.
+
+Runs every pattern over the annotated corpora shipped with the repo and
+renders per-rule precision into a Markdown + HTML dashboard under
+`bench-results/`, so a detector change can be judged by its effect across
+all targets at once.
+
+Labels are per finding, keyed by (file, line, rule):
+  1. An `annotations.json` file in a target directory listing
+     {"file", "rule", "line", "label": "tp" | "fp"} entries, files relative
+     to the target.
+  2. The checked-in `tests/bench_expectations.json`, the same entries with
+     files relative to the project root. A true positive in a `secure_*`
+     fixture carries a "reason" saying what the fixture leaves unfixed.
+A file being a "vulnerable" fixture does not make every finding in it a
+true positive, so nothing is labelled by file. Findings with no entry are
+counted but excluded from precision; expected true positives that no
+longer fire are counted as missed.
+
+When backtest cases are given (see backtest.py), the dashboard also
+publishes coverage of history: the share of historically exploited root
+causes the current rules flag.
+"""
+
+import html
+import json
+import os
+from dataclasses import dataclass, field
+from typing import Optional
+
+from scanner.backtest import BacktestReport, run_backtests
+from scanner.engine import AnchorShieldEngine
+
+PROJECT_ROOT = os.path.dirname(os.path.dirname(os.path.abspath(__file__)))
+
+DEFAULT_CORPORA = [
+    os.path.join(PROJECT_ROOT, "tests", "test_patterns"),
+    os.path.join(PROJECT_ROOT, "real-world-targets"),
+]
+
+DEFAULT_OUTPUT_DIR = os.path.join(PROJECT_ROOT, "bench-results")
+
+DEFAULT_EXPECTATIONS = os.path.join(PROJECT_ROOT, "tests", "bench_expectations.json")
+
+
+@dataclass
+class RuleStats:
+    """Per-rule counts across the corpus."""
+
+    rule: str
+    name: str = ""
+    tp: int = 0
+    fp: int = 0
+    unlabeled: int = 0
+    missed: int = 0
+    by_target: dict = field(default_factory=dict)
+
+    @property
+    def precision(self) -> Optional[float]:
+        labeled = self.tp + self.fp
+        if labeled == 0:
+            return None
+        return self.tp / labeled
+
+    def to_dict(self) -> dict:
+        return {
+            "rule": self.rule,
+            "name": self.name,
+            "tp": self.tp,
+            "fp": self.fp,
+            "unlabeled": self.unlabeled,
+            "missed": self.missed,
+            "precision": round(self.precision, 4) if self.precision is not None else None,
+            "by_target": self.by_target,
+        }
+
+
+@dataclass
+class BenchReport:
+    """Precision results for every rule over every corpus target."""
+
+    corpora: list
+    targets: list = field(default_factory=list)
+    rules: dict = field(default_factory=dict)
+    history: Optional[BacktestReport] = None
+
+    def to_dict(self) -> dict:
+        return {
+            "corpora": self.corpora,
+            "targets": self.targets,
+            "rules": {k: v.to_dict() for k, v in sorted(self.rules.items())},
+            "history": self.history.to_dict() if self.history else None,
+        }
+
+
+def load_annotations(target_dir: str) -> dict:
+    """Load {(file, rule, line): label} from a target's annotations.json."""
+    return load_expectations(os.path.join(target_dir, "annotations.json"))
+
+
+def load_expectations(path: str = DEFAULT_EXPECTATIONS) -> dict:
+    """Load {(file, rule, line): label} from a labels file; {} when it does not exist."""
+    if not os.path.isfile(path):
+        return {}
+    with open(path) as fh:
+        entries = json.load(fh)
+    return {
+        (e["file"], e["rule"], int(e["line"])): e["label"].lower()
+        for e in entries
+    }
+
+
+def discover_targets(corpus: str) -> list[str]:
+    """Each immediate subdirectory holding .rs files is one target."""
+    targets = []
+    for entry in sorted(os.listdir(corpus)):
+        full = os.path.join(corpus, entry)
+        if not os.path.isdir(full):
+            continue
+        for _, _, files in os.walk(full):
+            if any(f.endswith(".rs") for f in files):
+                targets.append(full)
+                break
+    return targets
+
+
+def run_bench(corpora: Optional[list] = None, backtest_cases: Optional[list] = None,
+              expectations: Optional[dict] = None) -> BenchReport:
+    """Scan every corpus target and aggregate per-rule precision.
+
+    expectations ({(file, rule, line): label}, files relative to the project
+    root) defaults to tests/bench_expectations.json. backtest_cases
+    (BacktestCase list) adds the coverage-of-history metric.
+    """
+    corpora = corpora or DEFAULT_CORPORA
+    expectations = load_expectations() if expectations is None else expectations
+    engine = AnchorShieldEngine()
+    report = BenchReport(corpora=[os.path.relpath(c, PROJECT_ROOT) for c in corpora])
+    for pattern in engine.patterns:
+        report.rules[pattern.id] = RuleStats(rule=pattern.id, name=pattern.name)
+
+    for corpus in corpora:
+        if not os.path.isdir(corpus):
+            continue
+        for target_dir in discover_targets(corpus):
+            target_name = os.path.relpath(target_dir, PROJECT_ROOT)
+            annotations = load_annotations(target_dir)
+            scan = engine.scan_directory(target_dir)
+            found = set()
+            report.targets.append({
+                "target": target_name,
+                "files": scan.files_scanned,
+                "findings": len(scan.findings),
+            })
+            for finding in scan.findings:
+                stats = report.rules.setdefault(finding.id, RuleStats(rule=finding.id, name=finding.name))
+                rel = os.path.relpath(os.path.join(target_dir, finding.file), PROJECT_ROOT).replace(os.sep, "/")
+                found.add((rel, finding.id, finding.line))
+                label = annotations.get((finding.file, finding.id, finding.line))
+                if label is None:
+                    label = expectations.get((rel, finding.id, finding.line))
+                per_target = stats.by_target.setdefault(
+                    target_name, {"tp": 0, "fp": 0, "unlabeled": 0, "missed": 0})
+                if label == "tp":
+                    stats.tp += 1
+                    per_target["tp"] += 1
+                elif label == "fp":
+                    stats.fp += 1
+                    per_target["fp"] += 1
+                else:
+                    stats.unlabeled += 1
+                    per_target["unlabeled"] += 1
+            prefix = target_name.replace(os.sep, "/") + "/"
+            for (rel, rule, line), label in sorted(expectations.items()):
+                if label == "tp" and rel.startswith(prefix) and (rel, rule, line) not in found:
+                    stats = report.rules.setdefault(rule, RuleStats(rule=rule))
+                    stats.missed += 1
+                    stats.by_target.setdefault(
+                        target_name, {"tp": 0, "fp": 0, "unlabeled": 0, "missed": 0})["missed"] += 1
+
+    if backtest_cases:
+        report.history = run_backtests(backtest_cases)
+    return report
+
+
+def _fmt_precision(value: Optional[float]) -> str:
+    return "—" if value is None else f"{value * 100:.1f}%"
+
+
+def render_markdown(report: BenchReport) -> str:
+    """Render the precision dashboard as Markdown."""
+    lines = [
+        "# anchor-shield-v2 Rule Precision",
+        "",
+        f"Corpora: {', '.join(report.corpora)}  ",
+        f"Targets: {len(report.targets)}",
+        "",
+        "## Per-Rule Precision",
+        "",
+        "| Rule | Name | TP | FP | Unlabeled | Missed | Precision |",
+        "|------|------|----|----|-----------|--------|-----------|",
+    ]
+    for rule_id, stats in sorted(report.rules.items()):
+        lines.append(
+            f"| {rule_id} | {stats.name} | {stats.tp} | {stats.fp} | "
+            f"{stats.unlabeled} | {stats.missed} | {_fmt_precision(stats.precision)} |"
+        )
+    lines += [
+        "",
+        "## Findings by Target",
+        "",
+        "| Target | Files | Findings |",
+        "|--------|-------|----------|",
+    ]
+    for t in report.targets:
+        lines.append(f"| {t['target']} | {t['files']} | {t['findings']} |")
+    if report.history:
+        history = report.history
+        lines += [
+            "",
+            "## Coverage of History",
+            "",
+            f"Root causes flagged: {sum(r.caught for r in history.evaluated)} of "
+            f"{len(history.evaluated)} ({_fmt_precision(history.coverage)})",
+            "",
+            "| Case | Category | Caught | Matched |",
+            "|------|----------|--------|---------|",
+        ]
+        for r in history.results:
+            caught = "error" if r.error else ("yes" if r.caught else "**no**")
+            matched = ", ".join(f"{m['id']} {m['file']}:{m['line']}" for m in r.matched) or "—"
+            lines.append(f"| {r.case.name} | {', '.join(r.case.category)} | {caught} | {matched} |")
+    lines.append("")
+    return "\n".join(lines)
+
+
+def render_html(report: BenchReport) -> str:
+    """Render the precision dashboard as standalone HTML."""
+    rows = ""
+    for rule_id, stats in sorted(report.rules.items()):
+        precision = stats.precision
+        cls = "none" if precision is None else ("good" if precision >= 0.8 else "bad")
+        rows += (
+            f"<tr><td>{html.escape(rule_id)}</td><td>{html.escape(stats.name)}</td>"
+            f"<td>{stats.tp}</td><td>{stats.fp}</td><td>{stats.unlabeled}</td><td>{stats.missed}</td>"
+            f"<td class='{cls}'>{_fmt_precision(precision)}</td></tr>\n"
+        )
+    history_html = ""
+    if report.history:
+        history = report.history
+        case_rows = "".join(
+            f"<tr><td>{html.escape(r.case.name)}</td><td>{html.escape(', '.join(r.case.category))}</td>"
+            f"<td class='{'none' if r.error else ('good' if r.caught else 'bad')}'>"
+            f"{'error' if r.error else ('yes' if r.caught else 'no')}</td></tr>\n"
+            for r in history.results
+        )
+        history_html = f"""<h2>Coverage of History</h2>
+    <p class="meta">Root causes flagged: {sum(r.caught for r in history.evaluated)} of
+    {len(history.evaluated)} ({_fmt_precision(history.coverage)})</p>
+    <table>
+        <tr><th>Case</th><th>Category</th><th>Caught</th></tr>
+        {case_rows}
+    </table>"""
+    target_rows = "".join(
+        f"<tr><td>{html.escape(t['target'])}</td><td>{t['files']}</td><td>{t['findings']}</td></tr>\n"
+        for t in report.targets
+    )
+    return f"""<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <title>anchor-shield-v2 Rule Precision</title>
+    <style>
+        body {{ font-family: 'Inter', -apple-system, sans-serif; background: #0F1117;
+               color: #E0E0E0; padding: 2rem; }}
+        h1 {{ color: #9945FF; }}
+        h2 {{ color: #14F195; margin-top: 2rem; }}
+        table {{ border-collapse: collapse; width: 100%; max-width: 1000px; }}
+        th, td {{ padding: 0.4rem 0.8rem; border-bottom: 1px solid #2A2D3E; text-align: left; }}
+        th {{ color: #888; }}
+        .good {{ color: #00C853; }}
+        .bad {{ color: #FF4444; }}
+        .none {{ color: #888; }}
+        .meta {{ color: #888; }}
+    </style>
+</head>
+<body>
+    <h1>anchor-shield-v2 Rule Precision</h1>
+    <p class="meta">{len(report.targets)} targets
+    ({html.escape(', '.join(report.corpora))})</p>
+    <h2>Per-Rule Precision</h2>
+    <table>
+        <tr><th>Rule</th><th>Name</th><th>TP</th><th>FP</th><th>Unlabeled</th><th>Missed</th><th>Precision</th></tr>
+        {rows}
+    </table>
+    <h2>Findings by Target</h2>
+    <table>
+        <tr><th>Target</th><th>Files</th><th>Findings</th></tr>
+        {target_rows}
+    </table>
+    {history_html}
+</body>
+</html>"""
+
+
+def write_bench_report(report: BenchReport, output_dir: str = DEFAULT_OUTPUT_DIR) -> list[str]:
+    """Write precision.md, precision.html and precision.json; return the paths."""
+    os.makedirs(output_dir, exist_ok=True)
+    outputs = {
+        "precision.md": render_markdown(report),
+        "precision.html": render_html(report),
+        "precision.json": json.dumps(report.to_dict(), indent=2),
+    }
+    paths = []
+    for name, content in outputs.items():
+        path = os.path.join(output_dir, name)
+        with open(path, "w") as fh:
+            fh.write(content)
+        paths.append(path)
+    return paths
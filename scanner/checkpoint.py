@@ -0,0 +1,158 @@
+"""
+Checkpoints for long scans.
+
+A scan of a large workspace with deep interprocedural summaries can run
+for a long time, and an interrupted run (laptop sleep, a CI job timing out)
+used to start over from the first file. `scan --checkpoint FILE` records
+each unit's results as it is scanned:
+
+    {
+      "version": 1,
+      "config": "<digest of everything that changes results>",
+      "units": {
+        "programs/pool/src/lib.rs": {
+          "sha256": "<digest of the unit's content>",
+          "findings": [...], "suppressed": [...],
+          "unparsed": [...], "generated": [...]
+        }
+      }
+    }
+
+Re-running the same command reads the file and reuses every unit whose
+content is unchanged; only the rest is scanned. The config digest covers
+the scanner's own sources, the selected rules, guard macros (including
+those found in the target), depth, precedence, baseline, cluster features
+and the suppression date, so a checkpoint written under a different
+configuration is ignored rather than mixed in. Units scanned after the
+resource budget ran out are not recorded: their passes were skipped, and
+a resumed scan runs them in full. Workspace checks (Anchor.toml) are cheap
+and always re-run.
+
+The file is rewritten atomically at most every SAVE_INTERVAL seconds and
+once the last unit is done, so an interruption loses at most that much
+work. It is kept after a complete scan: the next scan of the same target
+only rescans the files that changed.
+"""
+
+import dataclasses
+import hashlib
+import json
+import os
+import time
+from dataclasses import dataclass, field
+from typing import Callable, Optional
+
+from scanner.patterns.base import Finding
+
+CHECKPOINT_VERSION = 1
+# Seconds between checkpoint writes while scanning
+SAVE_INTERVAL = 5.0
+
+SCANNER_DIR = os.path.dirname(os.path.abspath(__file__))
+
+
+class StoredDetails(dict):
+    """A finding's `details` as read back from a checkpoint; renders the same JSON."""
+
+    def to_dict(self) -> dict:
+        return dict(self)
+
+
+def finding_from_dict(data: dict) -> Finding:
+    """Rebuild a Finding from its to_dict() form."""
+    names = {f.name for f in dataclasses.fields(Finding)} - {"details"}
+    finding = Finding(**{k: v for k, v in data.items() if k in names})
+    if data.get("details"):
+        finding.details = StoredDetails(data["details"])
+    return finding
+
+
+def scanner_digest() -> str:
+    """Digest of the scanner's Python sources: results from another version are not reused."""
+    digest = hashlib.sha256()
+    for root, dirs, files in os.walk(SCANNER_DIR):
+        dirs[:] = sorted(d for d in dirs if d != "__pycache__")
+        for name in sorted(files):
+            if name.endswith(".py"):
+                path = os.path.join(root, name)
+                digest.update(os.path.relpath(path, SCANNER_DIR).encode())
+                with open(path, "rb") as fh:
+                    digest.update(fh.read())
+    return digest.hexdigest()
+
+
+def content_digest(content: str) -> str:
+    return hashlib.sha256(content.encode("utf-8", errors="replace")).hexdigest()
+
+
+@dataclass
+class UnitResult:
+    """What scanning one unit added to the scan."""
+
+    findings: list = field(default_factory=list)
+    suppressed: list = field(default_factory=list)
+    unparsed: list = field(default_factory=list)
+    generated: list = field(default_factory=list)
+
+
+@dataclass
+class ScanCheckpoint:
+    path: str
+    config: str
+    # path -> stored unit record
+    units: dict = field(default_factory=dict)
+    # Units taken from the checkpoint instead of being scanned, this run
+    resumed: int = 0
+    clock: Callable[[], float] = time.monotonic
+    _dirty: bool = False
+    _saved_at: Optional[float] = None
+
+    @classmethod
+    def open(cls, path: str, config: str) -> "ScanCheckpoint":
+        """Load the checkpoint at path; start empty if it is missing, unreadable or from another configuration."""
+        checkpoint = cls(path, config)
+        try:
+            with open(path, "r", encoding="utf-8") as fh:
+                data = json.load(fh)
+        except (OSError, ValueError):
+            return checkpoint
+        if isinstance(data, dict) and data.get("version") == CHECKPOINT_VERSION and data.get("config") == config:
+            checkpoint.units = data.get("units", {})
+        return checkpoint
+
+    def lookup(self, unit: str, content: str) -> Optional[UnitResult]:
+        record = self.units.get(unit)
+        if not record or record.get("sha256") != content_digest(content):
+            return None
+        self.resumed += 1
+        return UnitResult(
+            findings=[finding_from_dict(f) for f in record["findings"]],
+            suppressed=[finding_from_dict(f) for f in record["suppressed"]],
+            unparsed=record["unparsed"],
+            generated=record["generated"],
+        )
+
+    def store(self, unit: str, content: str, result: UnitResult) -> None:
+        self.units[unit] = {
+            "sha256": content_digest(content),
+            "findings": [f.to_dict() for f in result.findings],
+            "suppressed": [f.to_dict() for f in result.suppressed],
+            "unparsed": result.unparsed,
+            "generated": result.generated,
+        }
+        self._dirty = True
+        if self._saved_at is None or self.clock() - self._saved_at >= SAVE_INTERVAL:
+            self.save()
+
+    def save(self) -> None:
+        """Write the checkpoint atomically, if anything changed since the last write."""
+        self._saved_at = self.clock()
+        if not self._dirty:
+            return
+        directory = os.path.dirname(os.path.abspath(self.path))
+        os.makedirs(directory, exist_ok=True)
+        temporary = f"{self.path}.tmp"
+        with open(temporary, "w", encoding="utf-8") as fh:
+            json.dump({"version": CHECKPOINT_VERSION, "config": self.config, "units": self.units}, fh)
+        os.replace(temporary, self.path)
+        self._dirty = False
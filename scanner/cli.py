@@ -11,8 +11,14 @@ from rich.panel import Panel
 from rich.text import Text
 from rich import box
 
+from scanner.blame import attribute_findings
+from scanner.budget import DEFAULT_DEPTH
 from scanner.engine import AnchorShieldEngine, ScanReport
-from scanner.report import format_terminal_report, format_json_report, format_html_report
+from scanner.messages import MessageCatalog, localize_report
+from scanner.report import (
+    format_terminal_report, format_json_report, format_html_report, format_markdown_report,
+    redact_report,
+)
 
 console = Console()
 
@@ -26,6 +32,125 @@ BANNER = """[bold purple]
 """
 
 
+def _parse_rule_list(ctx, param, value):
+    """Split a comma-separated rule selector option into a list."""
+    if not value:
+        return None
+    return [v.strip() for v in value.split(",") if v.strip()]
+
+
+def _make_budget(time_budget=None, memory_budget=None):
+    from scanner.budget import ScanBudget
+
+    return ScanBudget(time_limit=time_budget, memory_limit=memory_budget)
+
+
+def _budget_options(fn):
+    """--time-budget/--memory-budget/--interprocedural-depth, shared by commands walking a target."""
+    fn = click.option("--interprocedural-depth", type=click.IntRange(min=1), default=DEFAULT_DEPTH,
+                      show_default=True, help="Calls followed from a handler into helpers")(fn)
+    fn = click.option("--memory-budget", type=click.FloatRange(min=0), default=None,
+                      help="Resident memory (MiB) after which expensive passes are skipped")(fn)
+    fn = click.option("--time-budget", type=click.FloatRange(min=0), default=None,
+                      help="Seconds after which expensive passes are skipped; the scan still finishes")(fn)
+    return fn
+
+
+def _guard_options(fn):
+    """--guard-macro/--guard-macros: project assertion macros treated like require!."""
+    fn = click.option("--guard-macros", "guard_macros_file", type=click.Path(exists=True, dir_okay=False),
+                      default=None, help="File listing project assertion macros (TOML/JSON/text)")(fn)
+    fn = click.option("--guard-macro", "guard_macro", callback=_parse_rule_list, default=None,
+                      help="Assertion macro(s) to treat like require!, e.g. 'require_lte,ensure'")(fn)
+    return fn
+
+
+def _ruleset_options(fn):
+    """--ruleset/--migrate-baseline: pin the rule set findings and baselines come from."""
+    fn = click.option("--migrate-baseline", is_flag=True,
+                      help="Carry a baseline created under another rule set over to the installed one")(fn)
+    fn = click.option("--ruleset", default=None,
+                      help="Refuse to scan unless the installed rule set is this version or hash")(fn)
+    return fn
+
+
+def _parse_prices(ctx, param, value):
+    prices = {}
+    for item in value or ():
+        key, _, usd = item.partition("=")
+        try:
+            prices[key.strip()] = float(usd)
+        except ValueError:
+            raise click.BadParameter(f"expected MINT=USD, got {item!r}")
+    return prices
+
+
+def _guard_macros(names=None, path=None):
+    from scanner.patterns.guards import load_guard_macros
+
+    macros = list(names or [])
+    if path:
+        try:
+            macros += load_guard_macros(path)
+        except (ValueError, json.JSONDecodeError) as e:
+            raise click.BadParameter(str(e), param_hint="--guard-macros")
+    return macros
+
+
+def _load_cluster_features(path):
+    if not path:
+        return None
+    from scanner.features import load_cluster_features
+
+    try:
+        return load_cluster_features(path)
+    except (ValueError, json.JSONDecodeError) as e:
+        raise click.BadParameter(str(e), param_hint="--cluster-features")
+
+
+def _load_precedence(path):
+    if not path:
+        return None
+    from scanner.dedup import load_precedence
+
+    try:
+        return load_precedence(path)
+    except (ValueError, json.JSONDecodeError) as e:
+        raise click.BadParameter(str(e), param_hint="--precedence")
+
+
+def _make_engine(split_concatenated=False, only_rules=None, skip_rules=None,
+                 baseline=None, budget=None, cluster_features=None,
+                 guard_macros=None, precedence=None, stats=False,
+                 include_generated=False, interprocedural_depth=DEFAULT_DEPTH,
+                 checkpoint=None, ruleset=None, migrate_baseline=False,
+                 scan_deps=None) -> AnchorShieldEngine:
+    from scanner.ruleset import RulesetError
+    from scanner.suppression import DEFAULT_BASELINE_PATH
+
+    try:
+        engine = AnchorShieldEngine(
+            split_concatenated=split_concatenated, only=only_rules, skip=skip_rules,
+            baseline=baseline or DEFAULT_BASELINE_PATH, budget=budget,
+            cluster_features=_load_cluster_features(cluster_features),
+            guard_macros=guard_macros, precedence=_load_precedence(precedence), stats=stats,
+            include_generated=include_generated,
+            interprocedural_depth=interprocedural_depth, checkpoint=checkpoint,
+            ruleset=ruleset, migrate=migrate_baseline, scan_deps=scan_deps,
+        )
+    except RulesetError as e:
+        raise click.ClickException(str(e))
+    except ValueError as e:
+        raise click.BadParameter(str(e), param_hint="--only/--skip")
+    except json.JSONDecodeError as e:
+        raise click.BadParameter(f"unreadable baseline: {e}", param_hint="--baseline")
+    if engine.baseline_migration:
+        kept, dropped = engine.baseline_migration
+        console.print(f"[yellow]Migrated baseline to rule set {engine.ruleset.label}: "
+                      f"{kept} entries kept, {dropped} of removed rules dropped[/yellow]")
+    return engine
+
+
 @click.group()
 @click.version_option(version="0.1.0", prog_name="anchor-shield-v2")
 def cli():
@@ -35,18 +160,60 @@ def cli():
 
 @cli.command()
 @click.argument("target")
-@click.option("--format", "output_format", type=click.Choice(["terminal", "json", "html"]),
+@click.option("--format", "output_format", type=click.Choice(["terminal", "json", "html", "markdown"]),
               default="terminal", help="Output format")
 @click.option("--output", "-o", type=click.Path(), help="Output file path")
 @click.option("--verbose", "-v", is_flag=True, help="Verbose output")
-def scan(target, output_format, output, verbose):
+@click.option("--split-concatenated", is_flag=True,
+              help="Split concatenated sources on '// ===== FILE:' markers and report original paths")
+@click.option("--only", "only_rules", callback=_parse_rule_list, default=None,
+              help="Run only these rules (comma-separated IDs or globs, e.g. 'shield::cpi::*')")
+@click.option("--skip", "skip_rules", callback=_parse_rule_list, default=None,
+              help="Skip these rules (comma-separated IDs or globs)")
+@click.option("--redact", is_flag=True,
+              help="Reduce code snippets of Medium/Low findings for sharing outside the team")
+@click.option("--baseline", type=click.Path(dir_okay=False), default=None,
+              help="Baseline of accepted findings (default: .anchor-shield/baseline.json)")
+@click.option("--messages", type=click.Path(exists=True, dir_okay=False), default=None,
+              help="Message catalog (TOML/JSON) with translations or house terminology")
+@click.option("--cluster-features", type=click.Path(exists=True, dir_okay=False), default=None,
+              help="Feature gates active on the target cluster; findings needing others are marked inapplicable")
+@click.option("--precedence", type=click.Path(exists=True, dir_okay=False), default=None,
+              help="Rule precedence table (TOML/JSON) for merging findings reported at the same line")
+@click.option("--stats", "show_stats", is_flag=True,
+              help="Report what was analyzed: programs, instructions, CPIs, LOC, rules run and skipped")
+@click.option("--include-generated", is_flag=True,
+              help="Report findings in generated code (IDL clients, bindgen output), listed last")
+@click.option("--blame", "with_blame", is_flag=True,
+              help="Annotate findings with the commit and author that last touched the flagged line (git)")
+@click.option("--checkpoint", type=click.Path(dir_okay=False), default=None,
+              help="Record per-file results here and resume an interrupted scan from them")
+@click.option("--scan-deps", is_flag=True,
+              help="Read the exact source of path and git dependencies so rules see their helpers")
+@click.option("--dep", "deps", multiple=True,
+              help="Also read this crates.io dependency at its Cargo.lock version (implies --scan-deps)")
+@click.option("--rpc", default=None,
+              help="Network or RPC URL to read vault balances from; ranks findings by value at risk")
+@click.option("--price", "prices", multiple=True, callback=_parse_prices,
+              help="USD price for valuing vaults, MINT=USD or SOL=USD (USDC/USDT default to 1)")
+@_ruleset_options
+@_guard_options
+@_budget_options
+def scan(target, output_format, output, verbose, split_concatenated, only_rules, skip_rules, redact,
+         baseline, messages, cluster_features, precedence, show_stats, include_generated, with_blame,
+         checkpoint, scan_deps, deps, rpc, prices, ruleset, migrate_baseline, guard_macro, guard_macros_file, time_budget,
+         memory_budget, interprocedural_depth):
     """Scan an Anchor program for vulnerability patterns.
 
     TARGET can be a local directory path or a GitHub repository URL.
     """
     console.print(BANNER)
 
-    engine = AnchorShieldEngine()
+    engine = _make_engine(split_concatenated, only_rules, skip_rules, baseline,
+                          _make_budget(time_budget, memory_budget), cluster_features,
+                          _guard_macros(guard_macro, guard_macros_file), precedence, show_stats,
+                          include_generated, interprocedural_depth, checkpoint, ruleset, migrate_baseline,
+                          list(deps) if scan_deps or deps else None)
 
     # Determine if target is a URL or local path
     if target.startswith("https://github.com/") or target.startswith("github.com/"):
@@ -69,21 +236,31 @@ def scan(target, output_format, output, verbose):
                 target=target,
                 files_scanned=len(files),
                 patterns_checked=len(engine.patterns),
+                ruleset=engine.ruleset.to_dict(),
             )
 
             import time
             start = time.time()
+            engine.begin_stats()
             for filepath, content in files.items():
-                for pattern in engine.patterns:
-                    try:
-                        findings = pattern.scan(filepath, content)
-                        report.findings.extend(findings)
-                    except Exception:
-                        pass
+                for src_path, src_content in engine._sources(filepath, content):
+                    report.findings.extend(engine.scan_source(src_path, src_content))
 
             report.scan_time = time.time() - start
+            report.degraded = engine.budget.skipped
+            report.unparsed = engine.unparsed
+            report.generated = engine.generated
             report.security_score = engine._compute_security_score(report.findings)
             report.summary = engine._compute_summary(report.findings)
+            report.stats = engine.surface_stats(report.degraded)
+            if with_blame:
+                console.print("[dim]--blame needs a local checkout; skipped for GitHub URLs[/dim]")
+            if checkpoint:
+                console.print("[dim]--checkpoint applies to local scans; skipped for GitHub URLs[/dim]")
+            if scan_deps or deps:
+                console.print("[dim]--scan-deps needs a local checkout; skipped for GitHub URLs[/dim]")
+            if rpc:
+                console.print("[dim]--rpc needs a local checkout; skipped for GitHub URLs[/dim]")
 
         except Exception as e:
             console.print(f"[red]Error fetching repository: {e}[/red]")
@@ -97,9 +274,32 @@ def scan(target, output_format, output, verbose):
 
         console.print(f"[bold]Scanning local path:[/bold] {target_path}")
         report = engine.scan_directory(target_path)
+        if engine.checkpoint and engine.checkpoint.resumed:
+            console.print(f"[dim]Resumed {engine.checkpoint.resumed} of {report.files_scanned} files "
+                          f"from {checkpoint}[/dim]")
+        if with_blame:
+            attribute_findings(report)
+        if rpc:
+            from scanner.solana_client import SolanaChecker
+            from scanner.value_at_risk import assess_value_at_risk
+
+            with console.status("[bold purple]Reading vault balances...[/bold purple]"):
+                valued = assess_value_at_risk(report, SolanaChecker(network=rpc), prices)
+            console.print(f"[dim]Vault balances found for {valued} of {len(report.findings)} findings[/dim]")
+        for dep in report.dependencies:
+            note = f"not read: {dep['error']}" if dep["error"] else f"{dep['files']} files"
+            console.print(f"[dim]Dependency {dep['name']} ({dep['kind']} {dep['pinned'] or dep['source']}): "
+                          f"{note}[/dim]")
+
+    if redact:
+        report = redact_report(report)
+
+    catalog = _load_catalog(messages)
+    if catalog:
+        report = localize_report(report, catalog)
 
     # Output results
-    _output_report(report, output_format, output)
+    _output_report(report, output_format, output, catalog)
 
 
 @cli.command()
@@ -185,10 +385,38 @@ def check(program_id, network):
 
 @cli.command()
 @click.argument("target")
-@click.option("--format", "output_format", type=click.Choice(["json", "html"]),
+@click.option("--format", "output_format", type=click.Choice(["json", "html", "markdown"]),
               default="json", help="Report format")
 @click.option("--output", "-o", type=click.Path(), required=True, help="Output file path")
-def report(target, output_format, output):
+@click.option("--split-concatenated", is_flag=True,
+              help="Split concatenated sources on '// ===== FILE:' markers and report original paths")
+@click.option("--only", "only_rules", callback=_parse_rule_list, default=None,
+              help="Run only these rules (comma-separated IDs or globs, e.g. 'shield::cpi::*')")
+@click.option("--skip", "skip_rules", callback=_parse_rule_list, default=None,
+              help="Skip these rules (comma-separated IDs or globs)")
+@click.option("--redact", is_flag=True,
+              help="Reduce code snippets of Medium/Low findings for sharing outside the team")
+@click.option("--baseline", type=click.Path(dir_okay=False), default=None,
+              help="Baseline of accepted findings (default: .anchor-shield/baseline.json)")
+@click.option("--messages", type=click.Path(exists=True, dir_okay=False), default=None,
+              help="Message catalog (TOML/JSON) with translations or house terminology")
+@click.option("--cluster-features", type=click.Path(exists=True, dir_okay=False), default=None,
+              help="Feature gates active on the target cluster; findings needing others are marked inapplicable")
+@click.option("--precedence", type=click.Path(exists=True, dir_okay=False), default=None,
+              help="Rule precedence table (TOML/JSON) for merging findings reported at the same line")
+@click.option("--stats", "show_stats", is_flag=True,
+              help="Report what was analyzed: programs, instructions, CPIs, LOC, rules run and skipped")
+@click.option("--include-generated", is_flag=True,
+              help="Report findings in generated code (IDL clients, bindgen output), listed last")
+@click.option("--blame", "with_blame", is_flag=True,
+              help="Annotate findings with the commit and author that last touched the flagged line (git)")
+@_ruleset_options
+@_guard_options
+@_budget_options
+def report(target, output_format, output, split_concatenated, only_rules, skip_rules, redact,
+           baseline, messages, cluster_features, precedence, show_stats, include_generated, with_blame,
+           ruleset, migrate_baseline, guard_macro, guard_macros_file, time_budget, memory_budget,
+           interprocedural_depth):
     """Generate a scan report file.
 
     TARGET is a local directory path or GitHub repo URL.
@@ -196,7 +424,11 @@ def report(target, output_format, output):
     console.print(BANNER)
     console.print(f"[bold]Generating {output_format.upper()} report for:[/bold] {target}")
 
-    engine = AnchorShieldEngine()
+    engine = _make_engine(split_concatenated, only_rules, skip_rules, baseline,
+                          _make_budget(time_budget, memory_budget), cluster_features,
+                          _guard_macros(guard_macro, guard_macros_file), precedence, show_stats,
+                          include_generated, interprocedural_depth, ruleset=ruleset,
+                          migrate_baseline=migrate_baseline)
 
     if target.startswith("https://github.com/"):
         from scanner.github_client import GitHubClient
@@ -209,24 +441,39 @@ def report(target, output_format, output):
             target=target,
             files_scanned=len(files),
             patterns_checked=len(engine.patterns),
+            ruleset=engine.ruleset.to_dict(),
         )
+        engine.begin_stats()
         for filepath, content in files.items():
-            for pattern in engine.patterns:
-                try:
-                    findings = pattern.scan(filepath, content)
-                    scan_report.findings.extend(findings)
-                except Exception:
-                    pass
+            for src_path, src_content in engine._sources(filepath, content):
+                scan_report.findings.extend(engine.scan_source(src_path, src_content))
         scan_report.scan_time = time.time() - start
+        scan_report.degraded = engine.budget.skipped
+        scan_report.unparsed = engine.unparsed
+        scan_report.generated = engine.generated
         scan_report.security_score = engine._compute_security_score(scan_report.findings)
         scan_report.summary = engine._compute_summary(scan_report.findings)
+        scan_report.stats = engine.surface_stats(scan_report.degraded)
+        if with_blame:
+            console.print("[dim]--blame needs a local checkout; skipped for GitHub URLs[/dim]")
     else:
         scan_report = engine.scan_directory(os.path.abspath(target))
+        if with_blame:
+            attribute_findings(scan_report)
+
+    if redact:
+        scan_report = redact_report(scan_report)
+
+    catalog = _load_catalog(messages)
+    if catalog:
+        scan_report = localize_report(scan_report, catalog)
 
     if output_format == "json":
         content = format_json_report(scan_report)
+    elif output_format == "markdown":
+        content = format_markdown_report(scan_report, catalog)
     else:
-        content = format_html_report(scan_report)
+        content = format_html_report(scan_report, catalog)
 
     with open(output, "w") as f:
         f.write(content)
@@ -235,14 +482,729 @@ def report(target, output_format, output):
     console.print(f"[dim]Found {len(scan_report.findings)} findings[/dim]")
 
 
-def _output_report(report: ScanReport, output_format: str, output_path: str | None):
+@cli.command()
+@click.argument("target")
+@click.option("--iterations", type=int, default=200, help="Random inputs per function")
+@click.option("--seed", type=int, default=0x5EED, help="Seed for randomized inputs")
+@click.option("--all-files", is_flag=True, help="Replay math in every file, not only AMM-looking ones")
+@click.option("--format", "output_format", type=click.Choice(["terminal", "json"]),
+              default="terminal", help="Output format")
+def replay(target, iterations, seed, all_files, output_format):
+    """Replay pure AMM math functions natively with boundary inputs.
+
+    TARGET is a local directory or .rs file. Requires rustc.
+    """
+    from scanner.math_replay import replay_target
+
+    if output_format == "json":
+        result = replay_target(target, iterations=iterations, seed=seed, amm_only=not all_files)
+        print(result.to_json())
+        return
+
+    console.print(BANNER)
+    console.print(f"[bold]Replaying math functions in:[/bold] {target}")
+    with console.status("[bold purple]Compiling and replaying...[/bold purple]"):
+        result = replay_target(target, iterations=iterations, seed=seed, amm_only=not all_files)
+
+    if result.error:
+        console.print(f"[red]{result.error}[/red]")
+        sys.exit(1)
+
+    console.print(
+        f"[dim]Replayed {len(result.functions_replayed)} function(s), "
+        f"{result.calls_executed} calls; skipped {len(result.functions_skipped)}[/dim]"
+    )
+    if not result.issues:
+        console.print("[green]No panics or monotonicity violations observed.[/green]")
+        return
+
+    table = Table(title="Arithmetic Replay Issues", box=box.ROUNDED, title_style="bold purple")
+    table.add_column("Function", style="bold")
+    table.add_column("Kind")
+    table.add_column("Location")
+    table.add_column("Inputs")
+    table.add_column("Message")
+    for issue in result.issues:
+        table.add_row(
+            issue.function, issue.kind, f"{issue.file}:{issue.line}",
+            ", ".join(issue.inputs), issue.message,
+        )
+    console.print(table)
+
+
+@cli.command()
+@click.argument("target")
+@click.option("--format", "output_format", type=click.Choice(["terminal", "json", "markdown"]),
+              default="terminal", help="Output format")
+@click.option("--output", "-o", type=click.Path(), help="Output file path")
+@_guard_options
+@_budget_options
+def reachability(target, output_format, output, guard_macro, guard_macros_file, time_budget, memory_budget,
+                 interprocedural_depth):
+    """Show which entrypoints reach each dangerous sink, and through which guards.
+
+    TARGET is a local directory or .rs file.
+    """
+    from scanner.reachability import analyze_target, render_markdown
+
+    result = analyze_target(target, _make_budget(time_budget, memory_budget),
+                            _guard_macros(guard_macro, guard_macros_file), interprocedural_depth)
+    if output_format in ("json", "markdown") or output:
+        content = result.to_json() if output_format == "json" else render_markdown(result)
+        if output:
+            with open(output, "w") as f:
+                f.write(content)
+            console.print(f"[green]Reachability report saved to {output}[/green]")
+        else:
+            print(content)
+        return
+
+    console.print(BANNER)
+    console.print(
+        f"[dim]{result.entrypoints} entrypoints, {result.functions} functions, "
+        f"{len(result.sinks)} sinks[/dim]"
+    )
+    table = Table(title="Sink Reachability", box=box.ROUNDED, title_style="bold purple",
+                  show_lines=True)
+    table.add_column("Sink", style="bold")
+    table.add_column("Kind")
+    table.add_column("Entrypoint")
+    table.add_column("Guards")
+    for reach in result.sinks:
+        location = f"{reach.sink.file}:{reach.sink.line}"
+        if not reach.entrypoints:
+            table.add_row(location, reach.sink.kind, "[dim]unreached[/dim]", "")
+            continue
+        for ep in reach.entrypoints:
+            guards = "\n".join(ep.predicates) or "[red]none[/red]"
+            table.add_row(location, reach.sink.kind, " → ".join(ep.path), guards)
+    console.print(table)
+    if result.degraded:
+        console.print(
+            f"[yellow]Incomplete: {result.degraded[0]['reason']}; traversal limited in "
+            f"{len(result.degraded)} file(s)[/yellow]"
+        )
+
+
+@cli.command()
+@click.argument("target")
+@click.option("--format", "output_format", type=click.Choice(["terminal", "json", "markdown"]),
+              default="terminal", help="Output format")
+@click.option("--output", "-o", type=click.Path(), help="Output file path")
+@click.option("--limit", type=click.IntRange(min=0), default=20, show_default=True,
+              help="Functions to list (0 for all)")
+@click.option("--clean-only", is_flag=True, help="List only functions without findings")
+@_guard_options
+@_budget_options
+def hotspots(target, output_format, output, limit, clean_only, guard_macro, guard_macros_file,
+             time_budget, memory_budget, interprocedural_depth):
+    """Rank functions for manual review: complex, CPI-heavy, tainted, and without findings.
+
+    TARGET is a local directory or .rs file. Complements the findings of scan.
+    """
+    from scanner.hotspots import rank_target, render_markdown
+
+    engine = _make_engine(True, budget=_make_budget(time_budget, memory_budget),
+                          guard_macros=_guard_macros(guard_macro, guard_macros_file),
+                          interprocedural_depth=interprocedural_depth)
+    result = rank_target(target, limit, clean_only, engine)
+    if output_format in ("json", "markdown") or output:
+        content = result.to_json() if output_format == "json" else render_markdown(result)
+        if output:
+            with open(output, "w") as f:
+                f.write(content)
+            console.print(f"[green]Hotspot ranking saved to {output}[/green]")
+        else:
+            print(content)
+        return
+
+    console.print(BANNER)
+    console.print(f"[dim]{result.functions} functions, {result.findings} findings[/dim]")
+    if not result.hotspots:
+        console.print("[green]No function stands out for manual review.[/green]")
+        return
+    table = Table(title="Manual Review Hotspots", box=box.ROUNDED, title_style="bold purple")
+    table.add_column("#")
+    table.add_column("Function", style="bold")
+    table.add_column("Location")
+    table.add_column("Score")
+    table.add_column("Why")
+    for i, h in enumerate(result.hotspots, 1):
+        why = ", ".join(h.reasons())
+        table.add_row(str(i), h.function, f"{h.file}:{h.line}", str(h.score),
+                      why if h.findings else f"[yellow]{why}[/yellow]")
+    console.print(table)
+
+
+@cli.command()
+@click.argument("target")
+@click.option("--format", "output_format", type=click.Choice(["terminal", "json", "markdown"]),
+              default="terminal", help="Output format")
+@click.option("--output", "-o", type=click.Path(), help="Output file path")
+@click.option("--hot-only", is_flag=True, help="List only accounts written by most instructions")
+def contention(target, output_format, output, hot_only):
+    """Show which shared accounts each instruction takes writable.
+
+    TARGET is a local directory or .rs file. Accounts written by most of a
+    program's instructions serialize its transactions; informational only.
+    """
+    from scanner.contention import analyze_target, render_markdown
+
+    result = analyze_target(target)
+    if hot_only:
+        result.accounts = result.hot
+    if output_format in ("json", "markdown") or output:
+        content = result.to_json() if output_format == "json" else render_markdown(result)
+        if output:
+            with open(output, "w") as f:
+                f.write(content)
+            console.print(f"[green]Contention report saved to {output}[/green]")
+        else:
+            print(content)
+        return
+
+    console.print(BANNER)
+    console.print(f"[dim]{len(result.programs)} program(s), {len(result.accounts)} shared account(s), "
+                  f"{len(result.hot)} hot[/dim]")
+    if not result.accounts:
+        console.print("[green]No account is shared between instructions.[/green]")
+        return
+    for program, instructions in result.programs.items():
+        accounts = [a for a in result.accounts if a.program == program]
+        if not accounts:
+            continue
+        table = Table(title=f"Write-Lock Contention — {program or 'program'}", box=box.ROUNDED,
+                      title_style="bold purple")
+        table.add_column("Account", style="bold")
+        table.add_column("Scope")
+        for name in instructions:
+            table.add_column(name, justify="center")
+        table.add_column("Writers")
+        for a in accounts:
+            cells = ["[red]W[/red]" if ix in a.writers else "r" if ix in a.readers else "" for ix in instructions]
+            writers = f"{len(a.writers)}/{a.instructions}"
+            table.add_row(a.account, a.scope, *cells, f"[red]{writers} hot[/red]" if a.hot else writers)
+        console.print(table)
+
+
+@cli.command()
+@click.argument("target")
+@click.option("--format", "output_format", type=click.Choice(["terminal", "json", "markdown"]),
+              default="terminal", help="Output format")
+@click.option("--output", "-o", type=click.Path(), help="Output file path")
+@click.option("--user-favoring", is_flag=True, help="List only sites that round in the user's favor")
+def rounding(target, output_format, output, user_favoring):
+    """List the divisions in value math with the direction each rounds and should round.
+
+    TARGET is a local directory or .rs file. Amounts the user pays should
+    round up, amounts paid to the user down; ANCHOR-052 reports the rest.
+    """
+    from scanner.rounding import analyze_target, render_markdown
+
+    result = analyze_target(target)
+    if user_favoring:
+        result.sites = result.favoring_user
+    if output_format in ("json", "markdown") or output:
+        content = result.to_json() if output_format == "json" else render_markdown(result)
+        if output:
+            with open(output, "w") as f:
+                f.write(content)
+            console.print(f"[green]Rounding sites saved to {output}[/green]")
+        else:
+            print(content)
+        return
+
+    console.print(BANNER)
+    console.print(f"[dim]{len(result.sites)} rounding site(s), {len(result.favoring_user)} favoring the user[/dim]")
+    if not result.sites:
+        console.print("[green]No division with a known beneficiary.[/green]")
+        return
+    table = Table(title="Rounding Sites", box=box.ROUNDED, title_style="bold purple")
+    table.add_column("Site")
+    table.add_column("Function", style="bold")
+    table.add_column("Value")
+    table.add_column("Paid")
+    table.add_column("Rounds")
+    table.add_column("Should")
+    table.add_column("Favors")
+    for s in result.sites:
+        paid = ("by user" if s.beneficiary == "protocol" else "to user") + (", subtracted" if s.subtracted else "")
+        table.add_row(f"{s.file}:{s.line}", s.function, s.value, paid, s.rounds, s.should,
+                      "[red]user[/red]" if s.favors_user else "program")
+    console.print(table)
+
+
+@cli.command()
+@click.argument("target")
+@click.option("--format", "output_format", type=click.Choice(["terminal", "json", "markdown"]),
+              default="terminal", help="Output format")
+@click.option("--output", "-o", type=click.Path(), help="Output file path")
+@_guard_options
+def entrypoints(target, output_format, output, guard_macro, guard_macros_file):
+    """List every entry point, including fallback and manual dispatch, with its guards.
+
+    TARGET is a local directory or .rs file.
+    """
+    from scanner.entrypoints import analyze_target, render_markdown
+
+    result = analyze_target(target, _guard_macros(guard_macro, guard_macros_file))
+    if output_format in ("json", "markdown") or output:
+        content = result.to_json() if output_format == "json" else render_markdown(result)
+        if output:
+            with open(output, "w") as f:
+                f.write(content)
+            console.print(f"[green]Entry-point inventory saved to {output}[/green]")
+        else:
+            print(content)
+        return
+
+    console.print(BANNER)
+    console.print(f"[dim]{len(result.entries)} entries, {len(result.manual)} outside Anchor dispatch[/dim]")
+    table = Table(title="Entry Points", box=box.ROUNDED, title_style="bold purple", show_lines=True)
+    table.add_column("Entry", style="bold")
+    table.add_column("Kind")
+    table.add_column("Selected by")
+    table.add_column("Guards")
+    for e in result.entries:
+        kind = f"{e.kind} via {e.root}" if e.root else e.kind
+        guards = "\n".join(e.guards) or "[red]none[/red]"
+        table.add_row(f"{e.name}\n[dim]{e.file}:{e.line}[/dim]", kind, e.discriminator or "—", guards)
+    console.print(table)
+
+
+@cli.group()
+def rules():
+    """Inspect rules before enabling them."""
+    pass
+
+
+@rules.command("preview")
+@click.argument("rule")
+@click.option("--against", "target", required=True, type=click.Path(exists=True),
+              help="Directory or .rs file to preview the rule on")
+@click.option("--format", "output_format", type=click.Choice(["terminal", "json"]),
+              default="terminal", help="Output format")
+@click.option("--output", "-o", type=click.Path(), help="Output file path")
+@click.option("--split-concatenated", is_flag=True,
+              help="Split concatenated sources on '// ===== FILE:' markers and report original paths")
+@click.option("--only", "only_rules", callback=_parse_rule_list, default=None,
+              help="Rules the current configuration runs (comma-separated IDs or globs)")
+@click.option("--skip", "skip_rules", callback=_parse_rule_list, default=None,
+              help="Rules the current configuration skips (comma-separated IDs or globs)")
+@click.option("--baseline", type=click.Path(dir_okay=False), default=None,
+              help="Baseline of accepted findings (default: .anchor-shield/baseline.json)")
+@click.option("--precedence", type=click.Path(exists=True, dir_okay=False), default=None,
+              help="Rule precedence table (TOML/JSON) for merging findings reported at the same line")
+@_guard_options
+@_budget_options
+def rules_preview(rule, target, output_format, output, split_concatenated, only_rules, skip_rules,
+                  baseline, precedence, guard_macro, guard_macros_file, time_budget, memory_budget,
+                  interprocedural_depth):
+    """Show the findings RULE would add to the current results, without changing them.
+
+    RULE is a rule ID or a selector naming one rule. The current results are
+    those of the configuration given by --only/--skip/--baseline; nothing is
+    written to the baseline or the triage store.
+    """
+    from scanner.preview import preview_rule
+
+    engine = _make_engine(split_concatenated, only_rules, skip_rules, baseline,
+                          _make_budget(time_budget, memory_budget), None,
+                          _guard_macros(guard_macro, guard_macros_file), precedence,
+                          interprocedural_depth=interprocedural_depth)
+    try:
+        result = preview_rule(rule, target, engine)
+    except ValueError as e:
+        raise click.BadParameter(str(e), param_hint="RULE")
+    if output_format == "json" or output:
+        content = result.to_json()
+        if output:
+            with open(output, "w") as f:
+                f.write(content)
+            console.print(f"[green]Rule preview saved to {output}[/green]")
+        else:
+            print(content)
+        return
+
+    console.print(BANNER)
+    console.print(f"[bold]{result.rule}[/bold] {result.name} on {result.target}")
+    if result.enabled:
+        console.print("[dim]Already enabled in this configuration; enabling it changes nothing.[/dim]")
+        return
+    console.print(
+        f"[dim]{result.current} current finding(s); {len(result.merged)} merged into existing findings, "
+        f"{len(result.superseded)} existing superseded, {result.baselined} baselined[/dim]"
+    )
+    if not result.added:
+        console.print("[green]Enabling the rule adds no findings.[/green]")
+        return
+    table = Table(title=f"Would add {len(result.added)} finding(s)", box=box.ROUNDED,
+                  title_style="bold purple", show_lines=True)
+    table.add_column("Severity")
+    table.add_column("Location", style="bold")
+    table.add_column("Description")
+    for f in result.added:
+        style = {"Critical": "red", "High": "red", "Medium": "yellow"}.get(f.severity, "white")
+        table.add_row(f"[{style}]{f.severity}[/{style}]", f"{f.file}:{f.line}", f.description)
+    console.print(table)
+
+
+@rules.command("ruleset")
+@click.option("--record", "version", default=None,
+              help="Add the installed rule set to the bundled manifest under this version (release step)")
+def rules_ruleset(version):
+    """Show the installed rule set and the bundled ones --ruleset can pin to."""
+    from scanner.ruleset import RULESET_MANIFEST, RulesetError, bundled_rulesets, record
+
+    installed = AnchorShieldEngine().ruleset
+    if version:
+        try:
+            added = record(version, installed)
+        except RulesetError as e:
+            raise click.BadParameter(str(e), param_hint="--record")
+        if not added:
+            console.print(f"[dim]Installed rule set is already recorded as {installed.label}[/dim]")
+            return
+        installed.version = version
+        console.print(f"[green]Recorded rule set {installed.label} in {RULESET_MANIFEST}[/green]")
+        return
+
+    console.print(f"[bold]Installed:[/bold] {installed.label}, {len(installed.rules)} rules")
+    console.print(f"[dim]{installed.hash}[/dim]")
+    for entry in bundled_rulesets():
+        marker = " [green](installed)[/green]" if entry["hash"] == installed.hash else ""
+        console.print(f"  {entry['version']:<10} {entry['hash'][:12]}  {len(entry.get('rules', []))} rules{marker}")
+
+
+@cli.group()
+def bench():
+    """Corpus-wide benchmarks for detector changes."""
+    pass
+
+
+@bench.command("report")
+@click.option("--corpus", "corpora", multiple=True, type=click.Path(exists=True, file_okay=False),
+              help="Corpus directory (repeatable). Defaults to the bundled corpora.")
+@click.option("--output-dir", type=click.Path(file_okay=False), default=None,
+              help="Output directory (default: bench-results/)")
+@click.option("--backtests", "cases_path", type=click.Path(exists=True, dir_okay=False), default=None,
+              help="Backtest cases for coverage of history (default: backtests/cases.json)")
+def bench_report(corpora, output_dir, cases_path):
+    """Render per-rule precision across the annotated corpora."""
+    from scanner.backtest import DEFAULT_CASES, load_cases
+    from scanner.bench import run_bench, write_bench_report, DEFAULT_OUTPUT_DIR
+
+    console.print(BANNER)
+    cases_path = cases_path or (DEFAULT_CASES if os.path.isfile(DEFAULT_CASES) else None)
+    with console.status("[bold purple]Scanning corpora...[/bold purple]"):
+        result = run_bench([os.path.abspath(c) for c in corpora] or None,
+                           load_cases(cases_path) if cases_path else None)
+    paths = write_bench_report(result, output_dir or DEFAULT_OUTPUT_DIR)
+
+    table = Table(title="Rule Precision", box=box.ROUNDED, title_style="bold purple")
+    table.add_column("Rule", style="bold")
+    table.add_column("TP")
+    table.add_column("FP")
+    table.add_column("Unlabeled")
+    table.add_column("Missed")
+    table.add_column("Precision")
+    for rule_id, stats in sorted(result.rules.items()):
+        precision = "—" if stats.precision is None else f"{stats.precision * 100:.1f}%"
+        table.add_row(rule_id, str(stats.tp), str(stats.fp), str(stats.unlabeled), str(stats.missed), precision)
+    console.print(table)
+    if result.history and result.history.coverage is not None:
+        history = result.history
+        console.print(
+            f"Coverage of history: {sum(r.caught for r in history.evaluated)}/"
+            f"{len(history.evaluated)} ({history.coverage * 100:.1f}%)"
+        )
+    for path in paths:
+        console.print(f"[green]Wrote {path}[/green]")
+
+
+@cli.command()
+@click.argument("target", required=False)
+@click.option("--commit", default=None, help="Pre-exploit commit to check out (local git repo or URL)")
+@click.option("--category", default=None,
+              help="Exploited rule category, rule ID or glob (comma-separated), e.g. 'accounts'")
+@click.option("--root-cause", default=None,
+              help="Root cause location FILE[:LINE[-END]]; without it any finding of the category counts")
+@click.option("--cases", "cases_path", type=click.Path(exists=True, dir_okay=False), default=None,
+              help="JSON file of backtest cases instead of a single TARGET")
+@click.option("--format", "output_format", type=click.Choice(["terminal", "json"]),
+              default="terminal", help="Output format")
+@click.option("--output", "-o", type=click.Path(), help="Output file path")
+def backtest(target, commit, category, root_cause, cases_path, output_format, output):
+    """Check whether current rules flag the root cause of a past exploit.
+
+    TARGET is a pre-exploit checkout (directory or git URL) of the protocol.
+    """
+    from scanner.backtest import BacktestCase, load_cases, run_backtests
+
+    if cases_path:
+        cases = load_cases(cases_path)
+    elif target and category:
+        location = None
+        if root_cause:
+            path, _, lines = root_cause.partition(":")
+            start, _, end = lines.partition("-")
+            location = {"file": path}
+            if start:
+                location["lines"] = [int(start), int(end or start)]
+        cases = [BacktestCase.from_dict({
+            "name": os.path.basename(target.rstrip("/")) or target,
+            "target": target, "commit": commit, "root_cause": location,
+            "category": [c.strip() for c in category.split(",") if c.strip()],
+        })]
+    else:
+        raise click.UsageError("give TARGET with --category, or --cases FILE")
+
+    result = run_backtests(cases)
+    if output_format == "json" or output:
+        content = result.to_json()
+        if output:
+            with open(output, "w") as f:
+                f.write(content)
+            console.print(f"[green]Backtest results saved to {output}[/green]")
+        else:
+            print(content)
+        return
+
+    console.print(BANNER)
+    table = Table(title="Backtest", box=box.ROUNDED, title_style="bold purple")
+    table.add_column("Case", style="bold")
+    table.add_column("Category")
+    table.add_column("Caught")
+    table.add_column("Matched findings")
+    for r in result.results:
+        if r.error:
+            caught = f"[dim]error: {r.error}[/dim]"
+        else:
+            caught = "[green]yes[/green]" if r.caught else "[red]no[/red]"
+        matched = "\n".join(f"{m['id']} {m['file']}:{m['line']}" for m in r.matched)
+        table.add_row(r.case.name, ", ".join(r.case.category), caught, matched)
+    console.print(table)
+    if result.coverage is not None:
+        console.print(f"Coverage of history: {result.coverage * 100:.1f}% of {len(result.evaluated)} case(s)")
+
+
+@cli.command()
+@click.argument("fork")
+@click.option("--upstream", required=True,
+              help="Known-good upstream: local directory, git repo or git URL")
+@click.option("--upstream-commit", default=None, help="Upstream commit the fork branched from")
+@click.option("--format", "output_format", type=click.Choice(["terminal", "json", "markdown"]),
+              default="terminal", help="Output format")
+@click.option("--output", "-o", type=click.Path(), help="Output file path")
+def forkdiff(fork, upstream, upstream_commit, output_format, output):
+    """List checks and constraints a fork dropped or loosened relative to its upstream.
+
+    FORK is a local directory or .rs file.
+    """
+    import subprocess
+    import tarfile
+
+    from scanner.forkdiff import compare_targets, render_markdown
+
+    try:
+        result = compare_targets(fork, upstream, upstream_commit)
+    except (OSError, subprocess.SubprocessError, tarfile.TarError) as e:
+        raise click.ClickException(f"could not load upstream {upstream}: {e}")
+    if output_format in ("json", "markdown") or output:
+        content = result.to_json() if output_format == "json" else render_markdown(result)
+        if output:
+            with open(output, "w") as f:
+                f.write(content)
+            console.print(f"[green]Fork diff saved to {output}[/green]")
+        else:
+            print(content)
+        return
+
+    console.print(BANNER)
+    console.print(f"[dim]{result.fork} vs {result.upstream}[/dim]")
+    if not result.changes:
+        console.print("[green]No security-relevant divergence from upstream.[/green]")
+        return
+    table = Table(title="Fork Diff", box=box.ROUNDED, title_style="bold purple", show_lines=True)
+    table.add_column("Severity")
+    table.add_column("Kind", style="bold")
+    table.add_column("Location")
+    table.add_column("Upstream")
+    table.add_column("Fork")
+    for c in result.changes:
+        style = {"High": "red", "Medium": "yellow"}.get(c.severity, "white")
+        table.add_row(f"[{style}]{c.severity}[/{style}]", c.kind,
+                      f"{c.file}:{c.line}\n{c.location}", c.upstream or "—", c.fork or "—")
+    console.print(table)
+
+
+@cli.command()
+@click.argument("target")
+@click.option("--baseline", type=click.Path(dir_okay=False), default=None,
+              help="Baseline path (default: .anchor-shield/baseline.json)")
+@click.option("--force", is_flag=True, help="Overwrite an existing anchor-shield.toml and baseline")
+@click.option("--format", "output_format", type=click.Choice(["terminal", "json", "markdown"]),
+              default="terminal", help="Output format")
+@click.option("--output", "-o", type=click.Path(), help="Output file path")
+def onboard(target, baseline, force, output_format, output):
+    """Set up anchor-shield for a workspace and plan its first week of fixes.
+
+    TARGET is a local directory. Detects the program's domain (AMM, lending,
+    staking, NFT), writes anchor-shield.toml with the rule packs that apply,
+    baselines the current findings and lays the most urgent ones out over
+    five days.
+    """
+    from scanner.onboard import onboard as onboard_target, render_markdown
+    from scanner.suppression import DEFAULT_BASELINE_PATH
+
+    if not os.path.isdir(target):
+        raise click.BadParameter(f"{target} is not a directory", param_hint="TARGET")
+    try:
+        result = onboard_target(target, baseline or DEFAULT_BASELINE_PATH, force)
+    except ValueError as e:
+        raise click.ClickException(str(e))
+    if output_format in ("json", "markdown") or output:
+        content = result.to_json() if output_format == "json" else render_markdown(result)
+        if output:
+            with open(output, "w") as f:
+                f.write(content)
+            console.print(f"[green]Onboarding plan saved to {output}[/green]")
+        else:
+            print(content)
+        return
+
+    console.print(BANNER)
+    detected = ", ".join(f"{d.name} [dim]({d.evidence()})[/dim]" for d in result.detected)
+    console.print(f"[bold]Domain:[/bold] {detected or 'none detected'}")
+    for p in result.packs:
+        if p.status != "enabled":
+            color = "yellow" if p.status == "skipped" else "green"
+            console.print(f"  [{color}]{p.status}[/{color}] {p.selector} [dim]({p.reason})[/dim]")
+    kept = "[yellow]kept existing[/yellow] (--force to overwrite)"
+    console.print(f"[bold]Configuration:[/bold] {result.config} "
+                  f"{'[green]written[/green]' if result.config_written else kept}")
+    console.print(f"[bold]Baseline:[/bold] {result.baseline} "
+                  f"{f'[green]{result.baselined} findings[/green]' if result.baselined is not None else kept}")
+    if not result.week:
+        console.print("[green]Nothing to fix this week.[/green]")
+        return
+    table = Table(title="First Week", box=box.ROUNDED, title_style="bold purple")
+    table.add_column("Day")
+    table.add_column("Rule", style="bold")
+    table.add_column("Severity")
+    table.add_column("Effort")
+    table.add_column("Findings")
+    table.add_column("First fix")
+    for item in result.week:
+        table.add_row(str(item.day), f"{item.rule} {item.name}", item.severity, item.effort,
+                      f"{len(item.findings)}\n[dim]{item.findings[0]}[/dim]", item.fix)
+    console.print(table)
+    if result.later:
+        console.print("[dim]After the first week: " + ", ".join(
+            f"{item.rule} ({item.severity}, {len(item.findings)})" for item in result.later) + "[/dim]")
+
+
+@cli.group()
+def triage():
+    """Exchange findings and dispositions with audit tracking sheets."""
+    pass
+
+
+@triage.command("export")
+@click.argument("target")
+@click.option("--format", "output_format", type=click.Choice(["csv", "json"]),
+              default="csv", help="Sheet format")
+@click.option("--output", "-o", type=click.Path(), help="Output file path (default: stdout)")
+@click.option("--store", type=click.Path(dir_okay=False), default=None,
+              help="Triage store path (default: .anchor-shield/triage.json)")
+def triage_export(target, output_format, output, store):
+    """Export findings for TARGET with their current dispositions."""
+    from scanner.triage import TriageStore, export_findings, DEFAULT_STORE_PATH
+
+    engine = AnchorShieldEngine()
+    scan_report = engine.scan_directory(os.path.abspath(target))
+    content = export_findings(scan_report.findings, TriageStore.load(store or DEFAULT_STORE_PATH),
+                              fmt=output_format)
+    if output:
+        with open(output, "w", newline="") as f:
+            f.write(content)
+        console.print(f"[green]Exported {len(scan_report.findings)} findings to {output}[/green]")
+    else:
+        print(content, end="")
+
+
+@triage.command("import")
+@click.argument("sheet", type=click.Path(exists=True, dir_okay=False))
+@click.option("--store", type=click.Path(dir_okay=False), default=None,
+              help="Triage store path (default: .anchor-shield/triage.json)")
+def triage_import(sheet, store):
+    """Apply dispositions from an edited CSV/JSON SHEET to the triage store."""
+    from scanner.triage import TriageStore, import_dispositions, DEFAULT_STORE_PATH
+
+    triage_store = TriageStore.load(store or DEFAULT_STORE_PATH)
+    with open(sheet, newline="") as f:
+        fmt = "json" if sheet.endswith(".json") else None
+        result = import_dispositions(f.read(), triage_store, fmt=fmt)
+    triage_store.save()
+
+    console.print(
+        f"[green]Updated {result['updated']}[/green], unchanged {result['unchanged']}, "
+        f"skipped {result['skipped']} (store: {triage_store.path})"
+    )
+    for err in result["errors"]:
+        console.print(f"  [yellow]! {err}[/yellow]")
+    if result["errors"]:
+        sys.exit(1)
+
+
+@cli.group()
+def baseline():
+    """Accept current findings so only new ones are reported."""
+    pass
+
+
+@baseline.command("create")
+@click.argument("target")
+@click.option("--output", "-o", type=click.Path(dir_okay=False), default=None,
+              help="Baseline path (default: .anchor-shield/baseline.json)")
+@click.option("--expires", default=None, help="Expiry date (YYYY-MM-DD) for every entry")
+@click.option("--reason", default="", help="Why these findings are accepted")
+def baseline_create(target, output, expires, reason):
+    """Record every current finding in TARGET as accepted."""
+    from scanner.suppression import DEFAULT_BASELINE_PATH, parse_expiry, write_baseline
+
+    try:
+        expiry = parse_expiry(expires or "")
+    except ValueError:
+        raise click.BadParameter(f"'{expires}' is not a YYYY-MM-DD date", param_hint="--expires")
+
+    engine = AnchorShieldEngine()
+    scan_report = engine.scan_directory(os.path.abspath(target))
+    path = output or DEFAULT_BASELINE_PATH
+    count = write_baseline(scan_report.findings, path, expires=expiry, reason=reason, ruleset=engine.ruleset)
+    console.print(f"[green]Baselined {count} findings in {path}[/green]")
+
+
+def _load_catalog(path: str | None):
+    if not path:
+        return None
+    try:
+        return MessageCatalog.load(path)
+    except ValueError as e:  # includes TOML/JSON decode errors
+        raise click.BadParameter(str(e), param_hint="--messages")
+
+
+def _output_report(report: ScanReport, output_format: str, output_path: str | None,
+                   catalog: MessageCatalog | None = None):
     """Output the scan report in the specified format."""
     if output_format == "json":
         result = format_json_report(report)
     elif output_format == "html":
-        result = format_html_report(report)
+        result = format_html_report(report, catalog)
+    elif output_format == "markdown":
+        result = format_markdown_report(report, catalog)
     else:
-        result = format_terminal_report(report)
+        result = format_terminal_report(report, catalog)
 
     if output_path:
         with open(output_path, "w") as f:
@@ -0,0 +1,79 @@
+"""
+Splitting of concatenated multi-file sources.
+
+Several real-world targets are a single lib.rs built by pasting many original
+files together, each preceded by a marker comment such as
+
+    // ========== state/mod.rs ==========
+    // ═══ FILE: instructions/stake.rs ═══
+    // ===== FILE: utils/math.rs =====
+
+`split_concatenated` recognizes these markers and returns one virtual file per
+section, so findings can be reported against the original path and line.
+"""
+
+import os
+import re
+from dataclasses import dataclass
+
+MARKER_RE = re.compile(
+    r"^[ \t]*//[ \t]*(?:=+|═+)[ \t]*(?:FILE:[ \t]*)?"
+    r"(?P<path>[\w.\-/]+\.rs)[ \t]*(?:=+|═+)?[ \t]*$",
+    re.MULTILINE,
+)
+
+# Fewer markers than this is an ordinary file with a decorative comment
+MIN_MARKERS = 2
+
+
+@dataclass
+class VirtualFile:
+    """One original source file recovered from a concatenated corpus."""
+
+    path: str
+    content: str
+    # 1-based line in the concatenated file where this section's content starts
+    start_line: int
+
+
+def is_concatenated(content: str) -> bool:
+    return len(MARKER_RE.findall(content)) >= MIN_MARKERS
+
+
+def split_concatenated(content: str, container_path: str = "") -> list[VirtualFile]:
+    """Split a concatenated source into virtual files.
+
+    Paths embedded in markers are taken relative to the container's directory.
+    Non-blank text before the first marker is kept under the container path.
+    Returns an empty list if the content is not a concatenation.
+    """
+    markers = list(MARKER_RE.finditer(content))
+    if len(markers) < MIN_MARKERS:
+        return []
+
+    base_dir = os.path.dirname(container_path)
+    files = []
+
+    preamble = content[:markers[0].start()]
+    if _has_code(preamble):
+        files.append(VirtualFile(path=container_path, content=preamble, start_line=1))
+
+    for i, m in enumerate(markers):
+        body_start = m.end() + 1
+        body_end = markers[i + 1].start() if i + 1 < len(markers) else len(content)
+        body = content[body_start:body_end]
+        path = os.path.normpath(os.path.join(base_dir, m.group("path")))
+        files.append(VirtualFile(
+            path=path,
+            content=body,
+            start_line=content.count("\n", 0, body_start) + 1,
+        ))
+    return files
+
+
+def _has_code(text: str) -> bool:
+    for line in text.splitlines():
+        stripped = line.strip()
+        if stripped and not stripped.startswith("//"):
+            return True
+    return False
@@ -0,0 +1,121 @@
+"""
+Per-project rule options, read from `anchor-shield.toml` at the target root.
+
+Rules that have project-specific settings read them from a table named
+after the rule:
+
+    [rules."ANCHOR-044"]
+    require-tag = true
+
+The engine loads the file once per directory or file scan and hands each
+pattern its table as `pattern.options` (empty when the file or the table
+is missing). A file that does not parse raises ValueError rather than
+silently running the rules with their defaults.
+
+Rule packs (the `shield::<category>::*` namespaces) a project has no use
+for are turned off in the same file, for every scan of the target:
+
+    [packs]
+    skip = ["shield::nft::*"]
+
+The workspace's release profile is read the same way: rules whose impact
+depends on `[profile.release] overflow-checks` get it as
+`pattern.overflow_checks` (see load_overflow_checks).
+"""
+
+import os
+from typing import Optional
+
+try:
+    import tomllib
+except ImportError:  # Python < 3.11
+    import tomli as tomllib
+
+PROJECT_CONFIG = "anchor-shield.toml"
+
+
+def config_path(target: str) -> str:
+    """Where the target's anchor-shield.toml is read from."""
+    root = target if os.path.isdir(target) else os.path.dirname(os.path.abspath(target))
+    return os.path.join(root, PROJECT_CONFIG)
+
+
+def _load_config(target: str) -> tuple[str, dict]:
+    path = config_path(target)
+    if not os.path.isfile(path):
+        return path, {}
+    with open(path, "rb") as fh:
+        try:
+            return path, tomllib.load(fh)
+        except tomllib.TOMLDecodeError as e:
+            raise ValueError(f"{path}: {e}") from e
+
+
+def load_rule_options(target: str) -> dict:
+    """{rule id: {option: value}} from the target's anchor-shield.toml."""
+    path, data = _load_config(target)
+    rules = data.get("rules", {})
+    if not isinstance(rules, dict) or not all(isinstance(v, dict) for v in rules.values()):
+        raise ValueError(f"{path}: [rules] must hold one table per rule id")
+    return {rule_id: dict(options) for rule_id, options in rules.items()}
+
+
+def load_skipped_packs(target: str) -> list:
+    """Rule selectors listed under `[packs] skip` in the target's anchor-shield.toml."""
+    path, data = _load_config(target)
+    packs = data.get("packs", {})
+    skip = packs.get("skip", []) if isinstance(packs, dict) else None
+    if not isinstance(skip, list) or not all(isinstance(s, str) for s in skip):
+        raise ValueError(f"{path}: [packs] skip must be a list of rule selectors")
+    return skip
+
+
+def load_overflow_checks(target: str) -> Optional[bool]:
+    """`[profile.release] overflow-checks` of the target's workspace manifest.
+
+    Cargo reads profiles only from the workspace root: the nearest Cargo.toml
+    with a [workspace] table at or above the target (else the nearest
+    manifest there), or, for a directory with none above it, the shallowest
+    one below. False when the manifest does not set it (Cargo's release
+    default), None when there is no manifest or it does not parse.
+    """
+    path = _workspace_manifest(target)
+    if path is None:
+        return None
+    with open(path, "rb") as fh:
+        try:
+            data = tomllib.load(fh)
+        except tomllib.TOMLDecodeError:
+            return None
+    release = data.get("profile", {}).get("release", {})
+    return bool(release.get("overflow-checks", False)) if isinstance(release, dict) else False
+
+
+def _workspace_manifest(target: str) -> Optional[str]:
+    """Path of the Cargo.toml whose profiles apply to the target, None if none is found."""
+    start = target if os.path.isdir(target) else os.path.dirname(os.path.abspath(target))
+    # Up from the target: the nearest [workspace] manifest, else the nearest manifest
+    nearest = workspace = None
+    directory = os.path.abspath(start)
+    while True:
+        path = os.path.join(directory, "Cargo.toml")
+        if os.path.isfile(path):
+            nearest = nearest or path
+            with open(path, "r", encoding="utf-8", errors="ignore") as fh:
+                if "[workspace]" in fh.read():
+                    workspace = path
+                    break
+        parent = os.path.dirname(directory)
+        if parent == directory:
+            break
+        directory = parent
+    if workspace or nearest:
+        return workspace or nearest
+    if not os.path.isdir(target):
+        return None
+    # Down from a directory: the shallowest manifest
+    for dirpath, dirnames, files in os.walk(target):
+        dirnames[:] = sorted(d for d in dirnames if d not in ("target", "node_modules", ".git"))
+        if "Cargo.toml" in files:
+            return os.path.join(dirpath, "Cargo.toml")
+    return None
@@ -0,0 +1,239 @@
+"""
+Write-lock contention between instructions.
+
+Solana runs transactions in parallel unless they lock the same account
+writable. A program whose every instruction takes one pool or config
+account `mut` serializes all of its traffic on that account — a
+throughput ceiling for performance review, and usually state that should
+be sharded (per-market, per-user) anyway:
+
+    | Account | Scope        | initialize | deposit | borrow | withdraw | liquidate | Writers |
+    |---------|--------------|------------|---------|--------|----------|-----------|---------|
+    | Pool    | per instance | W          | W       | W      | W        | r         | 4/5 hot |
+
+analyze_sources() lists, per program, each shared account and the
+instructions that take it writable (`mut`, `init`, `init_if_needed`,
+`zero`, `close`, `realloc`) or read-only. Accounts are identified across
+instructions by their program account type (`Account<'info, Pool>`), or,
+for untyped and token accounts, by their PDA seeds or `address`. Per-user
+accounts do not contend and are left out: signers, position types (an
+`#[account]` struct with an owner-like Pubkey field, see ANCHOR-027), and
+PDAs whose seeds name a signer or an owner field. Token accounts and other
+accounts without seeds or an address cannot be identified and are left
+out too.
+
+Each account gets a scope: `global` for an `address` or a PDA of constant
+seeds (one account for the whole program), `per pool` for a PDA seeded by
+other accounts, and `per instance` for an account created at a keypair.
+Contention is per account, so a hot `per instance` escrow only serializes
+transactions on the same escrow, while a hot `global` config serializes
+the whole program.
+
+An account written by at least half of its program's instructions, and by
+at least HOT_MIN_WRITERS of them, is hot. The report is informational; it
+produces no findings.
+"""
+
+import json
+import os
+import re
+from dataclasses import dataclass, field
+
+from scanner.engine import AnchorShieldEngine
+from scanner.patterns.base import VulnerabilityPattern
+from scanner.patterns.facts import Facts
+from scanner.patterns.lifecycle import TYPE_RE, normalize_seeds
+from scanner.patterns.position_binding import OWNER_FIELD_RE, STATE_STRUCT_RE
+
+# Constraints that make Anchor take the account writable
+WRITE_KINDS = ("mut", "init", "init_if_needed", "zero", "close", "realloc")
+# Types shared by many unrelated accounts: the type says nothing about which account it is
+GENERIC_TYPES = {"TokenAccount", "Mint"}
+HOT_SHARE = 0.5
+HOT_MIN_WRITERS = 3
+
+
+@dataclass
+class SharedAccount:
+    account: str
+    program: str
+    file: str
+    line: int
+    # global | per <seed accounts> | per instance
+    scope: str = "per instance"
+    writers: list = field(default_factory=list)
+    readers: list = field(default_factory=list)
+    # Instructions of the program
+    instructions: int = 0
+
+    @property
+    def hot(self) -> bool:
+        return len(self.writers) >= HOT_MIN_WRITERS and len(self.writers) >= HOT_SHARE * self.instructions
+
+    def to_dict(self) -> dict:
+        return {
+            "account": self.account, "program": self.program, "file": self.file, "line": self.line,
+            "scope": self.scope, "writers": self.writers, "readers": self.readers,
+            "instructions": self.instructions, "hot": self.hot,
+        }
+
+
+@dataclass
+class ContentionReport:
+    target: str
+    # program -> its instructions, in source order
+    programs: dict = field(default_factory=dict)
+    accounts: list = field(default_factory=list)
+
+    @property
+    def hot(self) -> list:
+        return [a for a in self.accounts if a.hot]
+
+    def to_dict(self) -> dict:
+        return {
+            "target": self.target,
+            "programs": self.programs,
+            "accounts": [a.to_dict() for a in self.accounts],
+            "hot": [a.account for a in self.hot],
+        }
+
+    def to_json(self, indent: int = 2) -> str:
+        return json.dumps(self.to_dict(), indent=indent)
+
+
+def analyze_sources(sources: list, target: str = "") -> ContentionReport:
+    """Contention matrix of (path, content) units."""
+    report = ContentionReport(target=target)
+    shared = {}
+    # State types are often declared in another file than the accounts structs
+    positions = set().union(*(_position_types(content) for _, content in sources))
+    for path, content in sources:
+        facts = Facts(content, path)
+        program = _program(path, content)
+        instructions = [ix for ix in facts.instructions() if ix.is_entrypoint] or facts.instructions()
+        names = report.programs.setdefault(program, [])
+        for ix in instructions:
+            if ix.name in names:
+                continue
+            names.append(ix.name)
+            signers = [a.name for a in facts.accounts_of(ix) if a.is_signer]
+            for account in facts.accounts_of(ix):
+                slot = _slot(account, signers, positions)
+                if not slot:
+                    continue
+                entry = shared.setdefault((program, slot), SharedAccount(slot, program, path, account.line))
+                if entry.scope == "per instance":
+                    entry.scope = _scope(account, {a.name for a in facts.accounts_of(ix)})
+                writable = any(account.has(kind) for kind in WRITE_KINDS)
+                touched = entry.writers if writable else entry.readers
+                if ix.name not in entry.writers + entry.readers:
+                    touched.append(ix.name)
+                elif writable and ix.name in entry.readers:
+                    entry.readers.remove(ix.name)
+                    entry.writers.append(ix.name)
+
+    for entry in shared.values():
+        entry.instructions = len(report.programs[entry.program])
+        if len(entry.writers) + len(entry.readers) >= 2 and entry.writers:
+            report.accounts.append(entry)
+    report.accounts.sort(key=lambda a: (a.program, -len(a.writers), -len(a.readers), a.account))
+    return report
+
+
+def analyze_target(path: str, engine: AnchorShieldEngine = None) -> ContentionReport:
+    """Contention matrix of a directory or .rs file."""
+    engine = engine or AnchorShieldEngine(split_concatenated=True)
+    path = os.path.abspath(path)
+    if os.path.isfile(path):
+        with open(path, "r", encoding="utf-8", errors="ignore") as fh:
+            sources = engine._sources(os.path.basename(path), fh.read())
+    else:
+        sources = engine.load_sources(path)
+    return analyze_sources(sources, path)
+
+
+def _program(path: str, content: str) -> str:
+    """The program a unit belongs to: its `programs/<name>/` directory, else its #[program] module."""
+    m = re.search(r"(?:^|/)programs/([^/]+)/", path.replace(os.sep, "/"))
+    if m:
+        return m.group(1)
+    m = re.search(r"#\[program\]\s*(?:pub\s+)?mod\s+(\w+)", content)
+    return m.group(1) if m else ""
+
+
+def _position_types(content: str) -> set:
+    """#[account] structs owned by one user: an owner-like Pubkey field."""
+    result = set()
+    for m in STATE_STRUCT_RE.finditer(content):
+        end = VulnerabilityPattern._find_block_end(content, m.end() - 1)
+        body = content[m.end():end] if end != -1 else ""
+        if any(OWNER_FIELD_RE.match(n) for n in re.findall(r"pub\s+(\w+)\s*:\s*Pubkey\b", body)):
+            result.add(m.group(1))
+    return result
+
+
+def _slot(account, signers: list, positions: set):
+    """Name identifying the account across instructions, None for per-user or unidentifiable accounts."""
+    if account.is_signer or account.wrapper in ("Program", "Interface", "Sysvar"):
+        return None
+    pda = account.pda
+    seeds = ""
+    if pda:
+        seeds = normalize_seeds(pda.seeds)
+        if pda.is_foreign:
+            seeds += f"@{normalize_seeds(pda.program)}"
+        for seed in seeds.split(","):
+            parts = seed.split(".")
+            if parts[0] in signers or any(OWNER_FIELD_RE.match(p) for p in parts[1:]):
+                return None
+    m = TYPE_RE.search(account.type)
+    if m and m.group(1) not in GENERIC_TYPES:
+        return None if m.group(1) in positions else m.group(1)
+    if seeds:
+        return f"{account.name} [{seeds}]"
+    address = next((c.value for c in account.constraints if c.kind == "address"), "")
+    if address:
+        return f"{account.name} ({address.split('@')[0].strip()})"
+    return None
+
+
+def _scope(account, names: set) -> str:
+    if account.has("address"):
+        return "global"
+    if not account.pda:
+        return "per instance"
+    seeded_by = []
+    for seed in normalize_seeds(account.pda.seeds).split(","):
+        name = seed.split(".")[0]
+        if name in names and name != account.name and name not in seeded_by:
+            seeded_by.append(name)
+    return f"per {', '.join(seeded_by)}" if seeded_by else "global"
+
+
+def render_markdown(report: ContentionReport) -> str:
+    lines = [
+        "# Write-Lock Contention",
+        "",
+        f"Target: {report.target}  ",
+        f"Programs: {len(report.programs)} · Shared accounts: {len(report.accounts)} · Hot: {len(report.hot)}",
+        "",
+        "W = taken writable, r = read-only. Hot accounts are written by at least half of the "
+        "program's instructions; transactions touching them cannot run in parallel.",
+    ]
+    for program, instructions in report.programs.items():
+        accounts = [a for a in report.accounts if a.program == program]
+        if not accounts:
+            continue
+        lines += [
+            "",
+            f"## {program or '(unnamed program)'}",
+            "",
+            "| Account | Scope | " + " | ".join(instructions) + " | Writers |",
+            "|---------|-------|" + "|".join("-" * (len(ix) + 2) for ix in instructions) + "|---------|",
+        ]
+        for a in accounts:
+            cells = ["W" if ix in a.writers else "r" if ix in a.readers else "" for ix in instructions]
+            writers = f"{len(a.writers)}/{a.instructions}" + (" **hot**" if a.hot else "")
+            lines.append(f"| `{a.account}` | {a.scope} | " + " | ".join(cells) + f" | {writers} |")
+    lines.append("")
+    return "\n".join(lines)
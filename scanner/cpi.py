@@ -0,0 +1,166 @@
+"""
+Cross-program invocation front-end.
+
+Recognizes the ways Anchor programs make CPIs so detectors and the
+reachability graph see them as calls to a named program instead of
+unresolved function calls:
+
+  - `declare_program!(name)` clients: `name::cpi::ix(ctx, ..)`
+  - `#[interface]` traits: `pub trait RealizeLock` generates a
+    `realize_lock::is_realized(ctx, ..)` client whose target program is
+    chosen by the caller at runtime
+  - Anchor/SPL helpers: `token::transfer(..)`, `system_program::transfer(..)`
+  - raw `invoke` / `invoke_signed`, resolved through the instruction builder
+    (`spl_token::instruction::transfer(..)`) when one is visible
+"""
+
+import re
+from dataclasses import dataclass
+from typing import Optional
+
+SPL_HELPER_PROGRAMS = {
+    "token": "spl_token",
+    "token_interface": "spl_token_interface",
+    "token_2022": "spl_token_2022",
+    "associated_token": "spl_associated_token",
+    "system_program": "system_program",
+    "stake": "stake_program",
+}
+
+SPL_HELPER_IXS = {
+    "transfer", "transfer_checked", "mint_to", "mint_to_checked", "burn",
+    "burn_checked", "close_account", "approve", "approve_checked", "revoke",
+    "set_authority", "freeze_account", "thaw_account", "sync_native", "create",
+    "create_idempotent", "create_account", "allocate", "assign",
+    "initialize_account", "initialize_account3", "initialize_mint",
+    "initialize_mint2",
+}
+
+DECLARE_PROGRAM_RE = re.compile(r"\bdeclare_program!\s*\(\s*(\w+)\s*\)")
+INTERFACE_TRAIT_RE = re.compile(r"#\[interface\]\s*(?:pub\s+)?trait\s+(\w+)")
+CLIENT_CALL_RE = re.compile(r"\b(\w+)\s*::\s*cpi\s*::\s*(\w+)\s*\(")
+HELPER_CALL_RE = re.compile(
+    r"\b(?:anchor_spl\s*::\s*|anchor_lang\s*::\s*)?(" + "|".join(SPL_HELPER_PROGRAMS) +
+    r")\s*::\s*(\w+)\s*\("
+)
+INVOKE_RE = re.compile(r"\binvoke(_signed)?\s*\(")
+IX_BUILDER_RE = re.compile(r"\b(\w+?)(?:\s*::\s*instruction|_instruction)\s*::\s*(\w+)\s*\(")
+
+
+@dataclass
+class CpiCall:
+    """One cross-program call site within a body of code."""
+
+    program: str
+    instruction: str
+    via: str  # declare_program | anchor-client | interface | spl-helper | invoke
+    signed: bool
+    resolved: bool
+    start: int
+    end: int
+    text: str
+
+    def to_dict(self) -> dict:
+        return {
+            "program": self.program,
+            "instruction": self.instruction,
+            "via": self.via,
+            "signed": self.signed,
+            "resolved": self.resolved,
+        }
+
+
+def declared_programs(content: str) -> set[str]:
+    """Program names brought in with declare_program!."""
+    return set(DECLARE_PROGRAM_RE.findall(content))
+
+
+def interface_modules(content: str) -> dict[str, str]:
+    """Map generated client module (snake_case) -> #[interface] trait name."""
+    return {_snake(name): name for name in INTERFACE_TRAIT_RE.findall(content)}
+
+
+def _snake(name: str) -> str:
+    return re.sub(r"(?<!^)(?=[A-Z])", "_", name).lower()
+
+
+def _call_end(text: str, open_pos: int) -> int:
+    """Index just past the parenthesis group opening at open_pos."""
+    depth = 0
+    for i in range(open_pos, len(text)):
+        if text[i] == "(":
+            depth += 1
+        elif text[i] == ")":
+            depth -= 1
+            if depth == 0:
+                return i + 1
+    return len(text)
+
+
+def find_cpi_calls(body: str, declared: Optional[set] = None,
+                   interfaces: Optional[dict] = None) -> list[CpiCall]:
+    """Find CPI call sites in a function body.
+
+    `declared` and `interfaces` come from declared_programs() and
+    interface_modules() over the whole program, since the declaration is
+    usually in lib.rs while the call sits in an instruction module.
+    """
+    declared = declared or set()
+    interfaces = interfaces or {}
+    calls = []
+    signed_ctx = bool(re.search(r"\bnew_with_signer\s*\(|\.\s*with_signer\s*\(", body))
+
+    for m in CLIENT_CALL_RE.finditer(body):
+        program, ix = m.group(1), m.group(2)
+        if program in declared:
+            via = "declare_program"
+        else:
+            via = "anchor-client"
+        end = _call_end(body, m.end() - 1)
+        calls.append(CpiCall(program, ix, via, signed_ctx, True, m.start(), end, body[m.start():end]))
+
+    for module, trait in interfaces.items():
+        for m in re.finditer(rf"\b{re.escape(module)}\s*::\s*(\w+)\s*\(", body):
+            end = _call_end(body, m.end() - 1)
+            # Interface CPIs go to whichever program the caller supplies
+            calls.append(CpiCall(
+                f"<{trait} implementor>", m.group(1), "interface", signed_ctx, False,
+                m.start(), end, body[m.start():end],
+            ))
+
+    for m in HELPER_CALL_RE.finditer(body):
+        module, ix = m.group(1), m.group(2)
+        if ix not in SPL_HELPER_IXS:
+            continue
+        # Instruction builders are arguments to invoke, not CPIs themselves
+        if re.match(r"\s*::", body[m.end():m.end() + 3]) or "instruction" in body[m.start():m.end()]:
+            continue
+        end = _call_end(body, m.end() - 1)
+        calls.append(CpiCall(
+            SPL_HELPER_PROGRAMS[module], ix, "spl-helper", signed_ctx, True,
+            m.start(), end, body[m.start():end],
+        ))
+
+    for m in INVOKE_RE.finditer(body):
+        end = _call_end(body, m.end() - 1)
+        text = body[m.start():end]
+        program, ix, resolved = "<unresolved>", "", False
+        builder = IX_BUILDER_RE.search(text)
+        if not builder:
+            # `invoke(&ix, ..)` with `let ix = path::instruction::f(..)` earlier
+            arg = re.match(r"invoke(?:_signed)?\s*\(\s*&?\s*(\w+)\s*[,)]", text)
+            if arg:
+                let = list(re.finditer(
+                    rf"\blet\s+(?:mut\s+)?{re.escape(arg.group(1))}\b[^=]*=\s*([^;]*);",
+                    body[:m.start()],
+                ))
+                if let:
+                    builder = IX_BUILDER_RE.search(let[-1].group(1))
+        if builder:
+            program, ix, resolved = builder.group(1), builder.group(2), True
+        calls.append(CpiCall(
+            program, ix, "invoke", bool(m.group(1)), resolved, m.start(), end, text,
+        ))
+
+    calls.sort(key=lambda c: c.start)
+    return calls
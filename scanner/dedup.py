@@ -0,0 +1,120 @@
+"""
+Deduplication of findings that several rules report at one location.
+
+A specific rule and a generic one often fire on the same line: the
+staking-time rule (ANCHOR-018) and generic unit confusion (ANCHOR-012) on
+one expression, a documented-but-unenforced owner (ANCHOR-015) and a
+missing owner check (ANCHOR-006) on one field. Reporting both doubles the
+triage work for one fix.
+
+A precedence table maps a preferred rule to the rules it supersedes:
+
+    [precedence]
+    "ANCHOR-018" = ["ANCHOR-012", "ANCHOR-011"]
+    "shield::lifecycle::ANCHOR-013" = ["shield::accounts::*"]
+
+Keys and values are rule selectors (IDs or namespace globs, as for
+`--only`). When findings of a preferred rule and of a rule it supersedes
+share a file and line, only the preferred finding is kept; the others are
+listed under its `supporting` entry (with their fingerprints, so a baseline
+written from the kept finding covers them too), and it takes the highest
+severity of the group so merging never hides a High behind a Medium.
+
+The table is read with `--precedence FILE` (TOML/JSON, `precedence = {..}`
+or a bare JSON object). An empty table turns deduplication off.
+"""
+
+import json
+
+try:
+    import tomllib
+except ImportError:  # Python < 3.11
+    import tomli as tomllib
+
+from scanner.patterns import ALL_PATTERNS
+
+SEVERITY_RANK = {"Low": 0, "Medium": 1, "High": 2, "Critical": 3}
+
+# Specific rule -> generic rules it supersedes at the same location
+DEFAULT_PRECEDENCE = {
+    "ANCHOR-018": ["ANCHOR-012", "ANCHOR-011"],
+    "ANCHOR-013": ["ANCHOR-006"],
+    "ANCHOR-015": ["ANCHOR-006", "ANCHOR-004"],
+    "ANCHOR-022": ["ANCHOR-020", "ANCHOR-035"],
+    "ANCHOR-038": ["ANCHOR-035"],
+    "ANCHOR-049": ["ANCHOR-046", "ANCHOR-050"],
+    "ANCHOR-050": ["ANCHOR-046", "ANCHOR-052"],
+    "ANCHOR-057": ["ANCHOR-056"],
+    "ANCHOR-059": ["ANCHOR-051"],
+}
+
+
+def deduplicate(findings: list, patterns: list, precedence: dict) -> list:
+    """Fold superseded findings into the preferred one at each location."""
+    if not precedence:
+        return findings
+    by_id = {p.id: p for p in patterns}
+    groups = {}
+    for finding in findings:
+        groups.setdefault((finding.file, finding.line), []).append(finding)
+
+    absorbed = set()
+    for group in groups.values():
+        if len({f.id for f in group}) < 2:
+            continue
+        for keeper in group:
+            if id(keeper) in absorbed:
+                continue
+            superseded = _superseded_by(by_id.get(keeper.id), precedence)
+            for other in group:
+                if other is keeper or id(other) in absorbed or other.id == keeper.id:
+                    continue
+                pattern = by_id.get(other.id)
+                if pattern is None or not any(pattern.matches(s) for s in superseded):
+                    continue
+                _merge(keeper, other)
+                absorbed.add(id(other))
+    return [f for f in findings if id(f) not in absorbed]
+
+
+def _superseded_by(pattern, precedence: dict) -> list:
+    if pattern is None:
+        return []
+    return [s for key, values in precedence.items() if pattern.matches(key) for s in values]
+
+
+def _merge(keeper, other) -> None:
+    keeper.supporting.append({
+        "id": other.id,
+        "name": other.name,
+        "severity": other.severity,
+        "description": other.description,
+        "fingerprint": other.fingerprint(),
+    })
+    keeper.supporting.extend(other.supporting)
+    if SEVERITY_RANK.get(other.severity, 0) > SEVERITY_RANK.get(keeper.severity, 0):
+        keeper.severity = other.severity
+
+
+def load_precedence(path: str) -> dict:
+    """Read a precedence table; selectors matching no rule raise ValueError."""
+    with open(path, "rb") as f:
+        text = f.read().decode("utf-8")
+    if path.endswith(".toml"):
+        data = tomllib.loads(text)
+    else:
+        data = json.loads(text)
+    table = data.get("precedence", data) if isinstance(data, dict) else None
+    if not isinstance(table, dict):
+        raise ValueError("precedence must be a table of rule -> [superseded rules]")
+    table = {key: [values] if isinstance(values, str) else list(values) for key, values in table.items()}
+    validate_precedence(table)
+    return table
+
+
+def validate_precedence(table: dict) -> None:
+    patterns = [PatternClass() for PatternClass in ALL_PATTERNS]
+    selectors = set(table) | {s for values in table.values() for s in values}
+    unknown = sorted(s for s in selectors if not any(p.matches(s) for p in patterns))
+    if unknown:
+        raise ValueError(f"No rule matches selector(s) {', '.join(unknown)} in precedence table")
@@ -0,0 +1,310 @@
+"""
+Dependency sources pulled into the scan scope (scan --scan-deps).
+
+Programs keep their fixed-point math in crates of their own, referenced by
+path or git, or in forks of anchor-spl:
+
+    [dependencies]
+    amm-math = { path = "../../libs/amm-math" }
+    anchor-spl = { git = "https://github.com/org/anchor", rev = "4f1c2e0" }
+
+Without their source a call like `MulDiv::mul_div_floor` is opaque: rules
+fall back to the stub summaries in LIBRARY_HELPERS and any overflow inside
+the crate is lost at the crate boundary. With --scan-deps the engine reads
+the exact source of the program's path and git dependencies, plus the
+crates.io crates named with --dep, and rules see their helpers:
+
+  - path:      the directory the manifest points at (left out when it is
+               already under the scanned target)
+  - git:       the commit Cargo.lock pins (else rev / tag / branch), from
+               cargo's checkouts or cloned into the cache directory
+  - crates.io: the version Cargo.lock pins, from cargo's registry sources
+               or downloaded from static.crates.io into the cache directory
+
+Only a crate's src/ is read. Dependency files are not reported on; their
+functions are summarized once per scan (see patterns/math_summary.py) and
+the summaries are handed to rules as `pattern.dependencies`. A dependency
+that cannot be resolved is listed with the reason instead of failing the
+scan.
+"""
+
+import glob
+import io
+import os
+import re
+import subprocess
+import tarfile
+import urllib.request
+from dataclasses import dataclass, field, replace
+from typing import Optional
+
+try:
+    import tomllib
+except ImportError:  # Python < 3.11
+    import tomli as tomllib
+
+from scanner.config import _workspace_manifest
+
+CRATES_IO = "https://static.crates.io/crates/{name}/{name}-{version}.crate"
+SKIP_DIRS = ("target", "node_modules", ".git")
+
+
+def cache_root() -> str:
+    base = os.environ.get("XDG_CACHE_HOME") or os.path.join(os.path.expanduser("~"), ".cache")
+    return os.path.join(base, "anchor-shield", "deps")
+
+
+def cargo_home() -> str:
+    return os.environ.get("CARGO_HOME") or os.path.join(os.path.expanduser("~"), ".cargo")
+
+
+@dataclass
+class Dependency:
+    """One dependency as declared in a manifest, and where its source ended up."""
+
+    # Package name (a renamed dependency's `package`)
+    name: str
+    # "path", "git" or "registry"
+    kind: str
+    # Directory for path dependencies, repository URL for git ones
+    source: str = ""
+    # Version requirement, or the rev / tag / branch asked for
+    spec: str = ""
+    # Exact version or commit that was read, from Cargo.lock when it has one
+    pinned: str = ""
+    # Crate directory the sources were read from
+    root: Optional[str] = None
+    files: int = 0
+    error: str = ""
+
+    def to_dict(self) -> dict:
+        return {
+            "name": self.name,
+            "kind": self.kind,
+            "source": self.source,
+            "pinned": self.pinned or self.spec,
+            "files": self.files,
+            "error": self.error,
+        }
+
+    @property
+    def label(self) -> str:
+        pinned = self.pinned or self.spec
+        return f"{self.name} ({self.kind}{', ' + pinned[:12] if pinned else ''})"
+
+
+# Compared by identity: math_summaries caches per scope
+@dataclass(eq=False)
+class DependencyScope:
+    """Sources of the resolved dependencies, and their helper summaries."""
+
+    dependencies: list = field(default_factory=list)
+    # (display path, content) per dependency source file
+    sources: list = field(default_factory=list)
+
+    def __post_init__(self):
+        self._summaries = {}
+
+    def summaries(self, depth: int) -> dict:
+        """{function name: MathSummary} over every dependency file, facts tagged with their file."""
+        if depth not in self._summaries:
+            from scanner.patterns.math_summary import math_summaries
+
+            merged = {}
+            for path, content in self.sources:
+                for name, summary in math_summaries(content, depth).items():
+                    if name not in merged:
+                        merged[name] = replace(summary, facts={
+                            kind: replace(fact, file=fact.file or path) for kind, fact in summary.facts.items()
+                        })
+            self._summaries[depth] = merged
+        return self._summaries[depth]
+
+    def digest(self) -> list:
+        return [[d.name, d.kind, d.pinned or d.spec, d.files] for d in self.dependencies]
+
+
+def load_dependencies(target: str, include=(), cache_dir: Optional[str] = None) -> DependencyScope:
+    """Resolve the target's path and git dependencies, and the crates.io crates in include."""
+    root = target if os.path.isdir(target) else os.path.dirname(os.path.abspath(target))
+    root = os.path.abspath(root)
+    cache_dir = cache_dir or cache_root()
+    locked = _lockfile(target)
+    scope = DependencyScope()
+    for dep in declared_dependencies(target):
+        if dep.kind == "registry" and dep.name not in include:
+            continue
+        if dep.kind == "path" and _inside(dep.source, root):
+            continue
+        try:
+            dep.root = _resolve(dep, locked, cache_dir)
+        except (OSError, ValueError, subprocess.SubprocessError, tarfile.TarError) as e:
+            dep.error = str(e) or e.__class__.__name__
+        if dep.root:
+            files = _crate_sources(dep)
+            dep.files = len(files)
+            scope.sources.extend(files)
+        scope.dependencies.append(dep)
+    for name in include:
+        if not any(d.name == name for d in scope.dependencies):
+            scope.dependencies.append(Dependency(name, "registry", error="not a dependency of the target"))
+    return scope
+
+
+def declared_dependencies(target: str) -> list[Dependency]:
+    """Dependencies of every manifest under the target, once per package name."""
+    manifests = []
+    root = target if os.path.isdir(target) else os.path.dirname(os.path.abspath(target))
+    for dirpath, dirnames, files in os.walk(root):
+        dirnames[:] = sorted(d for d in dirnames if d not in SKIP_DIRS)
+        if "Cargo.toml" in files:
+            manifests.append(os.path.join(dirpath, "Cargo.toml"))
+    workspace_path = _workspace_manifest(target)
+    if workspace_path and workspace_path not in manifests:
+        manifests.insert(0, workspace_path)
+    inherited = {}
+    if workspace_path:
+        data = _read_toml(workspace_path)
+        table = data.get("workspace", {}).get("dependencies", {})
+        inherited = {alias: (entry, os.path.dirname(workspace_path)) for alias, entry in table.items()}
+
+    deps = {}
+    for manifest in manifests:
+        data = _read_toml(manifest)
+        tables = [data.get("dependencies", {})]
+        tables += [t.get("dependencies", {}) for t in data.get("target", {}).values() if isinstance(t, dict)]
+        for table in tables:
+            for alias, entry in table.items():
+                base = os.path.dirname(manifest)
+                if isinstance(entry, dict) and entry.get("workspace") and alias in inherited:
+                    entry, base = inherited[alias]
+                dep = _dependency(alias, entry, base)
+                if dep and dep.name not in deps:
+                    deps[dep.name] = dep
+    return list(deps.values())
+
+
+def _dependency(alias: str, entry, base: str) -> Optional[Dependency]:
+    if isinstance(entry, str):
+        return Dependency(alias, "registry", spec=entry)
+    if not isinstance(entry, dict):
+        return None
+    name = entry.get("package", alias)
+    if "path" in entry:
+        return Dependency(name, "path", source=os.path.normpath(os.path.join(base, entry["path"])))
+    if "git" in entry:
+        spec = entry.get("rev") or entry.get("tag") or entry.get("branch") or ""
+        return Dependency(name, "git", source=entry["git"], spec=spec)
+    return Dependency(name, "registry", spec=str(entry.get("version", "")))
+
+
+def _read_toml(path: str) -> dict:
+    try:
+        with open(path, "rb") as fh:
+            return tomllib.load(fh)
+    except (OSError, tomllib.TOMLDecodeError):
+        return {}
+
+
+def _lockfile(target: str) -> dict:
+    """{package name: [(version, source)]} from the workspace's Cargo.lock."""
+    manifest = _workspace_manifest(target)
+    if not manifest:
+        return {}
+    data = _read_toml(os.path.join(os.path.dirname(manifest), "Cargo.lock"))
+    locked = {}
+    for package in data.get("package", []):
+        locked.setdefault(package.get("name"), []).append((package.get("version", ""), package.get("source", "")))
+    return locked
+
+
+def _inside(path: str, root: str) -> bool:
+    path = os.path.abspath(path)
+    return path == root or path.startswith(root + os.sep)
+
+
+def _resolve(dep: Dependency, locked: dict, cache_dir: str) -> Optional[str]:
+    """Directory holding the dependency's exact source."""
+    if dep.kind == "path":
+        if not os.path.isfile(os.path.join(dep.source, "Cargo.toml")):
+            raise ValueError(f"no Cargo.toml at {dep.source}")
+        return dep.source
+    if dep.kind == "git":
+        return _git_source(dep, locked, cache_dir)
+    return _registry_source(dep, locked, cache_dir)
+
+
+def _git_source(dep: Dependency, locked: dict, cache_dir: str) -> str:
+    url = _normalize_url(dep.source)
+    for _, source in locked.get(dep.name, []):
+        if source.startswith("git+") and _normalize_url(source[4:].split("?")[0].split("#")[0]) == url:
+            dep.pinned = source.rsplit("#", 1)[-1]
+            break
+    commit = dep.pinned or dep.spec
+    if commit and re.fullmatch(r"[0-9a-f]{7,40}", commit):
+        for checkout in glob.glob(os.path.join(cargo_home(), "git", "checkouts", "*", commit[:7])):
+            crate = _find_crate(checkout, dep.name)
+            if crate:
+                return crate
+    checkout = os.path.join(cache_dir, f"{dep.name}-{(commit or 'HEAD')[:12]}")
+    if not os.path.isdir(os.path.join(checkout, ".git")):
+        subprocess.run(["git", "clone", "--quiet", dep.source, checkout], check=True, capture_output=True, timeout=300)
+        if commit:
+            subprocess.run(["git", "-C", checkout, "checkout", "--quiet", commit],
+                           check=True, capture_output=True, timeout=60)
+    if not dep.pinned:
+        head = subprocess.run(["git", "-C", checkout, "rev-parse", "HEAD"], capture_output=True, text=True, timeout=30)
+        dep.pinned = head.stdout.strip()
+    crate = _find_crate(checkout, dep.name)
+    if not crate:
+        raise ValueError(f"no package {dep.name} in {dep.source}")
+    return crate
+
+
+def _normalize_url(url: str) -> str:
+    return re.sub(r"(?:\.git)?/?$", "", url.lower())
+
+
+def _registry_source(dep: Dependency, locked: dict, cache_dir: str) -> str:
+    versions = [v for v, source in locked.get(dep.name, []) if source.startswith("registry+")]
+    if not versions and re.fullmatch(r"=?\s*\d+\.\d+\.\d+\S*", dep.spec):
+        versions = [dep.spec.lstrip("= ")]
+    if not versions:
+        raise ValueError("no Cargo.lock entry pins the version")
+    dep.pinned = versions[0]
+    folder = f"{dep.name}-{dep.pinned}"
+    for path in glob.glob(os.path.join(cargo_home(), "registry", "src", "*", folder)):
+        return path
+    path = os.path.join(cache_dir, folder)
+    if not os.path.isdir(path):
+        with urllib.request.urlopen(CRATES_IO.format(name=dep.name, version=dep.pinned), timeout=60) as resp:
+            data = resp.read()
+        with tarfile.open(fileobj=io.BytesIO(data), mode="r:gz") as archive:
+            members = [m for m in archive.getmembers()
+                       if m.isfile() and m.name.startswith(folder + "/") and ".." not in m.name.split("/")]
+            archive.extractall(cache_dir, members=members)
+    return path
+
+
+def _find_crate(checkout: str, name: str) -> Optional[str]:
+    """Directory of the package named `name` in a repository checkout."""
+    for dirpath, dirnames, files in os.walk(checkout):
+        dirnames[:] = sorted(d for d in dirnames if d not in SKIP_DIRS)
+        if "Cargo.toml" in files:
+            package = _read_toml(os.path.join(dirpath, "Cargo.toml")).get("package", {})
+            if package.get("name") == name:
+                return dirpath
+    return None
+
+
+def _crate_sources(dep: Dependency) -> list[tuple[str, str]]:
+    sources = []
+    src = os.path.join(dep.root, "src")
+    for dirpath, dirnames, files in os.walk(src):
+        dirnames.sort()
+        for f in sorted(files):
+            if f.endswith(".rs"):
+                path = os.path.join(dirpath, f)
+                with open(path, "r", encoding="utf-8", errors="ignore") as fh:
+                    sources.append((f"{dep.name}/{os.path.relpath(path, dep.root)}", fh.read()))
+    return sources
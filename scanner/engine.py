@@ -1,15 +1,35 @@
 """Core scanning engine for anchor-shield-v2."""
 
+import hashlib
 import os
 import re
 import json
 import time
+from datetime import date
 from dataclasses import dataclass, field
 from typing import Optional
 from pathlib import Path
 
+from scanner.budget import DEFAULT_DEPTH, ScanBudget
+from scanner.checkpoint import ScanCheckpoint, UnitResult, scanner_digest
+from scanner.concat import split_concatenated
+from scanner.config import PROJECT_CONFIG, load_overflow_checks, load_rule_options, load_skipped_packs
+from scanner.dedup import DEFAULT_PRECEDENCE, deduplicate
+from scanner.dependencies import load_dependencies
+from scanner.features import finding_features, missing_features
+from scanner.flows import annotate_flows
+from scanner.generated import generated_reason, linguist_generated
+from scanner.macros import expand_macros
+from scanner.narrative import narrate
 from scanner.patterns import ALL_PATTERNS
-from scanner.patterns.base import Finding
+from scanner.patterns.base import EFFORT_LEVELS, Finding, enclosing_items, scope_findings
+from scanner.patterns.guards import local_guard_macros
+from scanner.recovery import recover
+from scanner.ruleset import RulesetError, installed_ruleset, pin, same_ruleset
+from scanner.stats import SurfaceStats
+from scanner.suppression import (
+    apply_suppressions, baseline_ruleset, inline_suppressions, load_baseline, migrate_baseline,
+)
 
 
 @dataclass
@@ -24,6 +44,22 @@ class ScanReport:
     anchor_version: Optional[str] = None
     security_score: str = "A"
     summary: dict = field(default_factory=dict)
+    # Files scanned with passes skipped because the resource budget ran out
+    degraded: list = field(default_factory=list)
+    # Source regions left out because they do not parse; see scanner/recovery.py
+    unparsed: list = field(default_factory=list)
+    # Generated units with findings, {"file", "reason", "findings"}; see scanner/generated.py
+    generated: list = field(default_factory=list)
+    # What was analyzed (scan --stats); see scanner/stats.py
+    stats: Optional[dict] = None
+    # Findings per author (scan --blame), {"author", "email", "findings", "by_severity"};
+    # see scanner/blame.py
+    owners: list = field(default_factory=list)
+    # Rule set the findings come from, {"version", "hash"}; see scanner/ruleset.py
+    ruleset: Optional[dict] = None
+    # Dependencies pulled into scope (scan --scan-deps), {"name", "kind", "source",
+    # "pinned", "files", "error"}; see scanner/dependencies.py
+    dependencies: list = field(default_factory=list)
 
     def to_dict(self) -> dict:
         return {
@@ -32,8 +68,15 @@ class ScanReport:
             "files_scanned": self.files_scanned,
             "patterns_checked": self.patterns_checked,
             "anchor_version": self.anchor_version,
+            "ruleset": self.ruleset,
             "security_score": self.security_score,
             "summary": self.summary,
+            "degraded": self.degraded,
+            "unparsed": self.unparsed,
+            "generated": self.generated,
+            "stats": self.stats,
+            "owners": self.owners,
+            "dependencies": self.dependencies,
             "findings": [f.to_dict() for f in self.findings],
         }
 
@@ -41,23 +84,269 @@ class ScanReport:
         return json.dumps(self.to_dict(), indent=indent)
 
 
+def select_patterns(patterns: list, only: Optional[list[str]] = None,
+                    skip: Optional[list[str]] = None) -> list:
+    """Filter patterns by rule selectors (IDs or globs such as 'shield::cpi::*').
+
+    Raises ValueError for a selector that matches no pattern, so a typo does
+    not silently run the full rule set.
+    """
+    for selector in (only or []) + (skip or []):
+        if not any(p.matches(selector) for p in patterns):
+            raise ValueError(f"No rule matches selector '{selector}'")
+    if only:
+        patterns = [p for p in patterns if any(p.matches(s) for s in only)]
+    if skip:
+        patterns = [p for p in patterns if not any(p.matches(s) for s in skip)]
+    return patterns
+
+
 class AnchorShieldEngine:
     """Main scanning engine that runs vulnerability patterns against Anchor code."""
 
-    def __init__(self):
-        self.patterns = [PatternClass() for PatternClass in ALL_PATTERNS]
-
-    def scan_directory(self, path: str) -> ScanReport:
-        """Scan all .rs files in a directory for vulnerability patterns."""
-        start = time.time()
-        path = os.path.abspath(path)
+    def __init__(self, split_concatenated: bool = False,
+                 only: Optional[list[str]] = None, skip: Optional[list[str]] = None,
+                 baseline: Optional[str] = None, today: Optional[date] = None,
+                 budget: Optional[ScanBudget] = None,
+                 cluster_features: Optional[set] = None,
+                 guard_macros: Optional[list] = None,
+                 precedence: Optional[dict] = None,
+                 stats: bool = False,
+                 include_generated: bool = False,
+                 interprocedural_depth: int = DEFAULT_DEPTH,
+                 checkpoint: Optional[str] = None,
+                 ruleset: Optional[str] = None,
+                 migrate: bool = False,
+                 scan_deps: Optional[list] = None):
+        all_patterns = [PatternClass() for PatternClass in ALL_PATTERNS]
+        self._all_patterns = all_patterns
+        self.patterns = select_patterns(all_patterns, only, skip)
+        self.all_rule_ids = [p.id for p in all_patterns]
+        # Installed rule set; a --ruleset pin naming another one is refused
+        self.ruleset = installed_ruleset(self.all_rule_ids)
+        if ruleset:
+            pin(ruleset, self.ruleset)
+        # Report findings in concatenated corpora against the embedded file paths
+        self.split_concatenated = split_concatenated
+        # (kept, dropped) entries when a baseline from another rule set was migrated
+        self.baseline_migration = None
+        recorded = baseline_ruleset(baseline)
+        if not same_ruleset(recorded, self.ruleset):
+            if not migrate:
+                raise RulesetError(
+                    f"baseline {baseline} was created under rule set "
+                    f"{recorded.get('version') or recorded.get('hash', '')[:12]}, not the installed "
+                    f"{self.ruleset.label}; review it and pass --migrate-baseline to carry it over"
+                )
+            self.baseline_migration = migrate_baseline(baseline, self.ruleset)
+        self.baseline = load_baseline(baseline) if baseline else {}
+        # Reference date for suppression expiry; fixed in tests
+        self.today = today or date.today()
+        # Soft time/memory limits; unlimited unless given
+        self.budget = budget or ScanBudget()
+        # Activated feature gates on the target cluster; None = assume all
+        self.cluster_features = cluster_features
+        # Project assertion macros rules treat as guards, plus guard-like
+        # macro_rules! found anywhere in the current target
+        self.guard_macros = set(guard_macros or ())
+        self._target_guards = set()
+        # Rule options from the current target's anchor-shield.toml
+        self._rule_options = {}
+        # Rule packs the current target's anchor-shield.toml skips
+        self._skipped_packs = ()
+        # The target workspace's release overflow-checks setting
+        self._overflow_checks = None
+        # Specific rule -> generic rules it supersedes at the same line
+        self.precedence = DEFAULT_PRECEDENCE if precedence is None else precedence
+        # Scanned-surface statistics, collected per scan when enabled
+        self.collect_stats = stats
+        self._stats = None
+        # {"file", "lines", "reason"} per region recovery had to leave out
+        self.unparsed = []
+        # Report findings in generated code instead of only counting them;
+        # linguist-generated globs come from the target's .gitattributes
+        self.include_generated = include_generated
+        self._generated_globs = ()
+        self.generated = []
+        # Calls deep that rules follow helper summaries from a handler
+        self.interprocedural_depth = interprocedural_depth
+        # Per-unit results file directory scans resume from; see scanner/checkpoint.py
+        self.checkpoint_path = checkpoint
+        self.checkpoint = None
+        # crates.io crates to read besides the path and git dependencies;
+        # None leaves dependencies out of scope (see scanner/dependencies.py)
+        self.scan_deps = scan_deps
+        self.dependencies = None
+
+    def scan_source(self, path: str, content: str,
+                    suppressed: Optional[list] = None) -> list[Finding]:
+        """Run every selected pattern over one source unit.
+
+        Pattern errors are swallowed so one broken detector cannot abort a scan.
+        Findings covered by an active suppression are left out of the result
+        and appended to `suppressed` when a list is given. Once the resource
+        budget is spent, macro expansion and expensive patterns are skipped
+        and the skipped passes recorded on the budget. Findings that a more
+        specific rule also reports at the same line are folded into it
+        (see scanner/dedup.py). A unit that does not parse as a whole is
+        scanned item by item, and the regions left out are recorded in
+        `unparsed` (see scanner/recovery.py). Findings in a generated unit
+        are marked and, unless include_generated is set, left out and
+        counted in `generated` (see scanner/generated.py). Critical findings
+        with a resolved taint path get an exploit narrative (see
+        scanner/narrative.py), and findings in handlers that move tokens or
+        lamports get the handler's account flow (see scanner/flows.py).
+        Every finding records its enclosing item, which its fingerprint
+        includes (see scope_findings in scanner/patterns/base.py).
+        """
+        findings = []
+        skipped = []
+        degraded = self.budget.exhausted() is not None
+        inline = inline_suppressions(content)
+        recovery = recover(content)
+        self.unparsed.extend({"file": path, **region} for region in recovery.regions)
+        text = recovery.text
+        # Local macro_rules! invocations are expanded so generated structs are visible
+        expansion = None
+        if "macro_rules!" in text:
+            if degraded:
+                skipped.append("macro-expansion")
+            else:
+                expansion = expand_macros(text)
+        if self._stats is not None:
+            self._stats.add_unit(path, content, recovery)
+        guards = tuple(sorted(self.guard_macros | self._target_guards | local_guard_macros(content)))
+        items = None
+        for pattern in self._active_patterns():
+            pattern.guard_macros = guards
+            pattern.max_depth = self.interprocedural_depth
+            pattern.options = self._rule_options.get(pattern.id, {})
+            pattern.overflow_checks = self._overflow_checks
+            pattern.dependencies = self.dependencies
+            if degraded and pattern.cost == "expensive":
+                skipped.append(pattern.id)
+                continue
+            try:
+                results = pattern.scan(path, expansion.text if expansion else text)
+            except Exception as e:
+                if self._stats is not None:
+                    self._stats.add_error(path, pattern.id, e)
+                continue
+            if expansion:
+                self._map_expanded(pattern, results, content, expansion)
+            elif text is not content:
+                for finding in results:
+                    finding.code_snippet = pattern._extract_snippet(content, finding.line)
+            if items is None:
+                items = enclosing_items(content)
+            scope_findings(results, content, items)
+            reported, hidden = apply_suppressions(
+                pattern, self._annotate(pattern, results), inline, self.baseline, self.today,
+            )
+            findings.extend(reported)
+            if suppressed is not None:
+                suppressed.extend(hidden)
+        self.budget.record(path, skipped)
+        findings = deduplicate(findings, self._active_patterns(), self.precedence)
+        try:
+            narrate(findings, text)
+            annotate_flows(findings, text)
+        except Exception:
+            pass
+        reason = generated_reason(path, content, self._generated_globs)
+        if reason and findings:
+            self.generated.append({"file": path, "reason": reason, "findings": len(findings)})
+            if not self.include_generated:
+                return []
+            for finding in findings:
+                finding.generated = reason
+        return findings
+
+    def _scan_unit(self, path: str, content: str, suppressed: list) -> list[Finding]:
+        """scan_source, or the unit's results from the checkpoint when its content is unchanged."""
+        if self.checkpoint is None:
+            return self.scan_source(path, content, suppressed)
+        stored = self.checkpoint.lookup(path, content)
+        if stored:
+            if self._stats is not None:
+                self._stats.add_unit(path, content, recover(content))
+            self.unparsed.extend(stored.unparsed)
+            self.generated.extend(stored.generated)
+            suppressed.extend(stored.suppressed)
+            return stored.findings
+        marks = len(self.unparsed), len(self.generated), len(self.budget.skipped)
+        hidden = []
+        findings = self.scan_source(path, content, hidden)
+        suppressed.extend(hidden)
+        # A unit scanned with passes skipped is scanned again in full on resume
+        if len(self.budget.skipped) == marks[2]:
+            self.checkpoint.store(path, content, UnitResult(
+                findings, hidden, self.unparsed[marks[0]:], self.generated[marks[1]:],
+            ))
+        return findings
+
+    def _config_digest(self) -> str:
+        """Digest of everything besides the source that changes a unit's results."""
+        config = {
+            "scanner": scanner_digest(),
+            "rules": [p.id for p in self._active_patterns()],
+            "guards": sorted(self.guard_macros | self._target_guards),
+            "depth": self.interprocedural_depth,
+            "options": self._rule_options,
+            "overflow_checks": self._overflow_checks,
+            "precedence": repr(sorted(self.precedence.items())),
+            "baseline": repr(sorted((fp, repr(s)) for fp, s in self.baseline.items())),
+            "today": self.today.isoformat(),
+            "cluster_features": sorted(self.cluster_features) if self.cluster_features is not None else None,
+            "generated": [list(self._generated_globs), self.include_generated],
+            "split_concatenated": self.split_concatenated,
+            "dependencies": self.dependencies.digest() if self.dependencies else None,
+        }
+        return hashlib.sha256(json.dumps(config, sort_keys=True, default=str).encode()).hexdigest()
 
-        if not os.path.isdir(path):
-            if os.path.isfile(path):
-                return self.scan_file(path)
-            raise FileNotFoundError(f"Path not found: {path}")
+    def scan_workspace(self, root: str, suppressed: Optional[list] = None) -> list[Finding]:
+        """Run workspace-level checks (Anchor.toml, manifests) over a directory."""
+        findings = []
+        for pattern in self._active_patterns():
+            try:
+                results = pattern.scan_workspace(root)
+            except Exception:
+                continue
+            reported, hidden = apply_suppressions(
+                pattern, self._annotate(pattern, results), {}, self.baseline, self.today,
+            )
+            findings.extend(reported)
+            if suppressed is not None:
+                suppressed.extend(hidden)
+        return findings
 
-        # Find all .rs files
+    @staticmethod
+    def _map_expanded(pattern, findings: list[Finding], content: str, expansion) -> None:
+        """Point findings in macro-expanded text back at the original source."""
+        for finding in findings:
+            macro = expansion.macro_at(finding.line)
+            finding.line = expansion.original_line(finding.line)
+            if macro:
+                # Keep the expanded snippet: it shows the code the rule saw
+                finding.description += f" (in code generated by local macro `{macro}!`)"
+            else:
+                finding.code_snippet = pattern._extract_snippet(content, finding.line)
+
+    def _annotate(self, pattern, findings: list[Finding]) -> list[Finding]:
+        for finding in findings:
+            if not finding.remediation_effort:
+                finding.remediation_effort = pattern.estimate_effort(finding)
+            finding.feature_gates = finding_features(pattern, finding)
+            if self.cluster_features is not None:
+                finding.inapplicable = missing_features(finding.feature_gates, self.cluster_features)
+        return findings
+
+    def load_sources(self, path: str) -> list[tuple[str, str]]:
+        """Read every .rs file under path as (relative path, content) units.
+
+        Skips target/ and node_modules/. Concatenated files are split into
+        their virtual files when split_concatenated is enabled.
+        """
         rs_files = []
         for root, _, files in os.walk(path):
             # Skip target/ and node_modules/
@@ -67,44 +356,105 @@ class AnchorShieldEngine:
                 if f.endswith(".rs"):
                     rs_files.append(os.path.join(root, f))
 
-        # Detect Anchor version
-        anchor_version = self._detect_anchor_version(path)
-
-        # Scan each file
-        all_findings = []
+        sources = []
         for rs_file in rs_files:
             try:
                 with open(rs_file, "r", encoding="utf-8", errors="ignore") as fh:
                     content = fh.read()
             except (OSError, IOError):
                 continue
-
             # Make path relative for display
             rel_path = os.path.relpath(rs_file, path)
+            sources.extend(self._sources(rel_path, content))
+        return sources
+
+    def _sources(self, path: str, content: str) -> list[tuple[str, str]]:
+        """Return the (path, content) units to scan for one file on disk."""
+        if self.split_concatenated:
+            parts = split_concatenated(content, path)
+            if parts:
+                return [(p.path, p.content) for p in parts]
+        return [(path, content)]
+
+    def scan_directory(self, path: str) -> ScanReport:
+        """Scan all .rs files in a directory for vulnerability patterns."""
+        start = time.time()
+        path = os.path.abspath(path)
+
+        if not os.path.isdir(path):
+            if os.path.isfile(path):
+                return self.scan_file(path)
+            raise FileNotFoundError(f"Path not found: {path}")
 
-            for pattern in self.patterns:
-                try:
-                    findings = pattern.scan(rel_path, content)
-                    all_findings.extend(findings)
-                except Exception:
-                    pass
+        self.budget.start()
+        mark = len(self.budget.skipped)
+        self.begin_stats()
+
+        # Detect Anchor version
+        anchor_version = self._detect_anchor_version(path)
+
+        # Scan each file
+        all_findings = []
+        suppressed = []
+        files_scanned = 0
+        sources = self.load_sources(path)
+        self._generated_globs = linguist_generated(path)
+        self._rule_options = load_rule_options(path)
+        self._skipped_packs = self._load_skipped_packs(path)
+        self._overflow_checks = load_overflow_checks(path)
+        self.dependencies = self._load_dependencies(path)
+        # A guard macro defined in lib.rs is used from every other file
+        self._target_guards = set().union(*(local_guard_macros(c) for _, c in sources))
+        self.checkpoint = ScanCheckpoint.open(self.checkpoint_path, self._config_digest()) \
+            if self.checkpoint_path else None
+        for src_path, src_content in sources:
+            files_scanned += 1
+            all_findings.extend(self._scan_unit(src_path, src_content, suppressed))
+        if self.checkpoint:
+            self.checkpoint.save()
+
+        all_findings.extend(self.scan_workspace(path, suppressed))
 
         elapsed = time.time() - start
 
         report = ScanReport(
             target=path,
             scan_time=elapsed,
-            files_scanned=len(rs_files),
-            patterns_checked=len(self.patterns),
+            files_scanned=files_scanned,
+            patterns_checked=len(self._active_patterns()),
+            ruleset=self.ruleset.to_dict(),
             findings=all_findings,
             anchor_version=anchor_version,
+            degraded=self.budget.skipped[mark:],
+            unparsed=list(self.unparsed),
+            generated=list(self.generated),
+            dependencies=self._dependency_list(),
         )
 
         report.security_score = self._compute_security_score(all_findings)
-        report.summary = self._compute_summary(all_findings)
+        report.summary = self._compute_summary(all_findings, suppressed)
+        report.stats = self.surface_stats(report.degraded)
 
         return report
 
+    def _load_skipped_packs(self, target: str) -> tuple:
+        """The target's [packs] skip selectors; one matching no rule raises ValueError."""
+        selectors = tuple(load_skipped_packs(target))
+        for selector in selectors:
+            if not any(p.matches(selector) for p in self._all_patterns):
+                raise ValueError(f"{PROJECT_CONFIG}: no rule matches skip selector '{selector}'")
+        return selectors
+
+    def _active_patterns(self) -> list:
+        """The selected patterns minus the packs the current target skips."""
+        return [p for p in self.patterns if not any(p.matches(s) for s in self._skipped_packs)]
+
+    def _load_dependencies(self, target: str):
+        return load_dependencies(target, self.scan_deps) if self.scan_deps is not None else None
+
+    def _dependency_list(self) -> list:
+        return [d.to_dict() for d in self.dependencies.dependencies] if self.dependencies else []
+
     def scan_file(self, file_path: str) -> ScanReport:
         """Scan a single .rs file."""
         start = time.time()
@@ -113,40 +463,56 @@ class AnchorShieldEngine:
         with open(file_path, "r", encoding="utf-8", errors="ignore") as fh:
             content = fh.read()
 
+        self.budget.start()
+        mark = len(self.budget.skipped)
+        self.begin_stats()
         all_findings = []
-        for pattern in self.patterns:
-            try:
-                findings = pattern.scan(os.path.basename(file_path), content)
-                all_findings.extend(findings)
-            except Exception:
-                pass
+        suppressed = []
+        sources = self._sources(os.path.basename(file_path), content)
+        self._generated_globs = ()
+        self._rule_options = load_rule_options(file_path)
+        self._skipped_packs = self._load_skipped_packs(file_path)
+        self._overflow_checks = load_overflow_checks(file_path)
+        self.dependencies = self._load_dependencies(file_path)
+        self._target_guards = set().union(*(local_guard_macros(c) for _, c in sources))
+        for src_path, src_content in sources:
+            all_findings.extend(self.scan_source(src_path, src_content, suppressed))
 
         elapsed = time.time() - start
 
         report = ScanReport(
             target=file_path,
             scan_time=elapsed,
-            files_scanned=1,
-            patterns_checked=len(self.patterns),
+            files_scanned=len(sources),
+            patterns_checked=len(self._active_patterns()),
+            ruleset=self.ruleset.to_dict(),
             findings=all_findings,
+            degraded=self.budget.skipped[mark:],
+            unparsed=list(self.unparsed),
+            generated=list(self.generated),
+            dependencies=self._dependency_list(),
         )
 
         report.security_score = self._compute_security_score(all_findings)
-        report.summary = self._compute_summary(all_findings)
+        report.summary = self._compute_summary(all_findings, suppressed)
+        report.stats = self.surface_stats(report.degraded)
 
         return report
 
     def scan_content(self, content: str, filename: str = "<input>") -> ScanReport:
         """Scan raw content string."""
         start = time.time()
-        all_findings = []
-
-        for pattern in self.patterns:
-            try:
-                findings = pattern.scan(filename, content)
-                all_findings.extend(findings)
-            except Exception:
-                pass
+        self.budget.start()
+        mark = len(self.budget.skipped)
+        self.begin_stats()
+        suppressed = []
+        self._generated_globs = ()
+        self._rule_options = {}
+        self._skipped_packs = ()
+        self._overflow_checks = None
+        self.dependencies = None
+        self._target_guards = set()
+        all_findings = self.scan_source(filename, content, suppressed)
 
         elapsed = time.time() - start
 
@@ -154,15 +520,32 @@ class AnchorShieldEngine:
             target=filename,
             scan_time=elapsed,
             files_scanned=1,
-            patterns_checked=len(self.patterns),
+            patterns_checked=len(self._active_patterns()),
+            ruleset=self.ruleset.to_dict(),
             findings=all_findings,
+            degraded=self.budget.skipped[mark:],
+            unparsed=list(self.unparsed),
+            generated=list(self.generated),
         )
 
         report.security_score = self._compute_security_score(all_findings)
-        report.summary = self._compute_summary(all_findings)
+        report.summary = self._compute_summary(all_findings, suppressed)
+        report.stats = self.surface_stats(report.degraded)
 
         return report
 
+    def begin_stats(self) -> None:
+        """Start a new scan's surface record: unparsed regions, generated units and, if enabled, statistics."""
+        self.unparsed = []
+        self.generated = []
+        self._stats = SurfaceStats() if self.collect_stats else None
+
+    def surface_stats(self, degraded: list) -> Optional[dict]:
+        """Statistics of the scan since begin_stats(), or None when disabled."""
+        if self._stats is None:
+            return None
+        return self._stats.to_dict(self.all_rule_ids, [p.id for p in self._active_patterns()], degraded)
+
     def _detect_anchor_version(self, path: str) -> Optional[str]:
         """Detect Anchor version from Cargo.toml files."""
         for root, _, files in os.walk(path):
@@ -191,7 +574,8 @@ class AnchorShieldEngine:
 
     @staticmethod
     def _compute_security_score(findings: list[Finding]) -> str:
-        """Compute an overall security score based on findings."""
+        """Compute an overall security score based on hand-written, applicable findings."""
+        findings = [f for f in findings if not f.inapplicable and not f.generated]
         if not findings:
             return "A"
 
@@ -212,17 +596,30 @@ class AnchorShieldEngine:
             return "B+"
 
     @staticmethod
-    def _compute_summary(findings: list[Finding]) -> dict:
+    def _compute_summary(findings: list[Finding], suppressed: Optional[list] = None) -> dict:
         """Compute summary statistics."""
         by_severity = {"Critical": 0, "High": 0, "Medium": 0, "Low": 0}
         by_pattern = {}
+        by_effort = {level: 0 for level in EFFORT_LEVELS}
 
+        inapplicable = [f for f in findings if f.inapplicable]
+        generated = [f for f in findings if f.generated and not f.inapplicable]
+        findings = [f for f in findings if not f.inapplicable and not f.generated]
         for f in findings:
             by_severity[f.severity] = by_severity.get(f.severity, 0) + 1
             by_pattern[f.id] = by_pattern.get(f.id, 0) + 1
+            if f.remediation_effort:
+                by_effort[f.remediation_effort] = by_effort.get(f.remediation_effort, 0) + 1
 
         return {
             "total": len(findings),
             "by_severity": by_severity,
             "by_pattern": by_pattern,
+            "by_effort": by_effort,
+            "suppressed": len(suppressed or []),
+            "expired_suppressions": sum(
+                1 for f in findings if f.suppression and f.suppression.get("expired")
+            ),
+            "inapplicable": len(inapplicable),
+            "generated": len(generated),
         }
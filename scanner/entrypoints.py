@@ -0,0 +1,236 @@
+"""
+Entry-point inventory and permission matrix.
+
+Anchor routes an instruction to a `#[program]` function by the 8-byte
+sighash of its name. Programs that also route by hand accept instructions
+no `#[program]` function describes, so an inventory built from Anchor
+handlers alone is incomplete exactly where review matters most. Every way
+into the program is listed with the checks on the way:
+
+  - anchor:   a `#[program]` function taking Context<T>; guards are its
+              accounts struct (signers, has_one, constraint, seeds,
+              address), `#[access_control]` and the handler's requires
+  - fallback: `fallback` in the `#[program]` module, which Anchor calls for
+              instruction data no handler's sighash matches
+  - native:   the function named by `entrypoint!(..)` or marked
+              `#[entrypoint]`, or a `process_instruction(program_id,
+              accounts, data)` (see reachability.py)
+  - routed:   one arm of a manual dispatch in a fallback or native entry,
+              or in a function it calls: `match data[0] { 0 => .. }`,
+              `match Ix::try_from_slice(data)? { Ix::Withdraw { .. } => .. }`,
+              `if data[..8] == WITHDRAW_DISCRIMINATOR { .. }`,
+              `data.starts_with(&X)`; guards are the checks before the
+              dispatch, in the arm and in the handler it calls
+
+Arms that only return an error (`_ => Err(..)`) are not entries. An entry
+without any guard is marked in the matrix; whether it needs one is for the
+reviewer — a permissionless crank is fine, a permissionless withdraw is not.
+"""
+
+import json
+import os
+import re
+from dataclasses import dataclass, field
+
+from scanner.engine import AnchorShieldEngine
+from scanner.patterns.base import VulnerabilityPattern
+from scanner.reachability import ReachabilityAnalyzer, _collapse, _dedupe, body_predicates
+
+# Expressions a manual dispatch switches on: the instruction data or what it decodes to
+DISPATCH_SUBJECT_RE = re.compile(
+    r"\b(?:instruction_data|ix_data|data|input|tag|discriminator|instruction|ix|variant)\b|"
+    r"\b(?:try_from_slice|unpack|deserialize|split_first|split_at)\b"
+)
+DISCRIMINATOR_TEST_RE = re.compile(
+    r"\bstarts_with\s*\(|\[\s*(?:0\s*)?\.\.\s*(?:8|4|1)\s*\]\s*==|\[\s*0\s*\]\s*==|==\s*\w*DISCRIMINATOR\w*"
+)
+ERROR_ONLY_RE = re.compile(r"^\{?\s*(?:return\s+)?(?:Err\s*\(|err!\s*\(|panic!\s*\()[^;]*;?\s*\}?$", re.DOTALL)
+# Callees searched for the dispatch when the root itself only delegates
+DISPATCH_DEPTH = 2
+
+
+@dataclass
+class Entry:
+    name: str
+    kind: str            # "anchor" | "fallback" | "native" | "routed"
+    file: str
+    line: int
+    # Instruction data that selects the entry
+    discriminator: str = ""
+    # Function the entry runs: the Anchor handler, or the callee of a routed arm
+    handler: str = ""
+    # fallback/native function a routed entry is dispatched from
+    root: str = ""
+    guards: list = field(default_factory=list)
+
+    def to_dict(self) -> dict:
+        return {
+            "name": self.name, "kind": self.kind, "file": self.file, "line": self.line,
+            "discriminator": self.discriminator, "handler": self.handler, "root": self.root,
+            "guards": self.guards,
+        }
+
+
+@dataclass
+class EntrypointInventory:
+    target: str
+    entries: list = field(default_factory=list)
+
+    @property
+    def manual(self) -> list:
+        return [e for e in self.entries if e.kind != "anchor"]
+
+    def to_dict(self) -> dict:
+        return {
+            "target": self.target,
+            "entries": [e.to_dict() for e in self.entries],
+            "manual": len(self.manual),
+            "unguarded": [e.name for e in self.entries if not e.guards],
+        }
+
+    def to_json(self, indent: int = 2) -> str:
+        return json.dumps(self.to_dict(), indent=indent)
+
+
+def dispatch_arms(body: str) -> list[tuple[int, str, int, int]]:
+    """(offset, discriminator, arm start, arm end) of each manual dispatch arm in a function body."""
+    arms = []
+    for m in re.finditer(r"\bmatch\s+([^{;]+?)\s*\{", body):
+        if not DISPATCH_SUBJECT_RE.search(m.group(1)):
+            continue
+        end = VulnerabilityPattern._find_block_end(body, m.end() - 1)
+        if end == -1:
+            continue
+        arms += [(m.start(), p, s, e) for p, s, e in _match_arms(body, m.end(), end - 1)]
+    for m in re.finditer(r"\bif\s+([^{;]+?)\s*\{", body):
+        if not DISCRIMINATOR_TEST_RE.search(m.group(1)):
+            continue
+        end = VulnerabilityPattern._find_block_end(body, m.end() - 1)
+        if end != -1:
+            arms.append((m.start(), _collapse(m.group(1), 60), m.end() - 1, end))
+    arms = [a for a in arms if not ERROR_ONLY_RE.match(body[a[2]:a[3]].strip())]
+    return sorted(arms, key=lambda a: a[2])
+
+
+def _match_arms(body: str, start: int, end: int) -> list[tuple[str, int, int]]:
+    """(pattern, body start, body end) of the arms between a match's braces."""
+    arms = []
+    i, arm_start, depth = start, start, 0
+    while i < end:
+        ch = body[i]
+        if ch in "([{":
+            depth += 1
+        elif ch in ")]}":
+            depth -= 1
+        elif depth == 0 and body.startswith("=>", i):
+            pattern = " ".join(body[arm_start:i].split()).lstrip(",").strip()
+            j = i + 2
+            while j < end and body[j].isspace():
+                j += 1
+            if j < end and body[j] == "{":
+                close = VulnerabilityPattern._find_block_end(body, j)
+                close = end if close == -1 else close
+            else:
+                close, d = j, 0
+                while close < end and not (d == 0 and body[close] == ","):
+                    d += body[close] in "([{"
+                    d -= body[close] in ")]}"
+                    close += 1
+            arms.append((pattern, j, close))
+            i = arm_start = close
+            continue
+        i += 1
+    return arms
+
+
+class InventoryBuilder:
+    """Entries of a set of sources, on top of the reachability call graph."""
+
+    def __init__(self, sources: list[tuple[str, str]], guard_macros=()):
+        self.analyzer = ReachabilityAnalyzer(sources, guard_macros)
+
+    def build(self, target: str = "") -> EntrypointInventory:
+        inventory = EntrypointInventory(target=target)
+        roots = sorted((f for f in self.analyzer.functions if f.is_entrypoint), key=lambda f: (f.file, f.line))
+        for fn in roots:
+            if fn.entry_kind == "anchor":
+                inventory.entries.append(Entry(
+                    name=fn.name, kind="anchor", file=fn.file, line=fn.line,
+                    discriminator=f"sighash(\"global:{fn.name}\")", handler=fn.name,
+                    guards=_dedupe(self.analyzer._entry_predicates(fn) + body_predicates(fn.body, None,
+                                                                                      self.analyzer.guards)),
+                ))
+                continue
+            routed = self._routed(fn)
+            # The root's own checks stop where it dispatches
+            arms = dispatch_arms(fn.body)
+            inventory.entries.append(Entry(
+                name=fn.name, kind=fn.entry_kind, file=fn.file, line=fn.line,
+                discriminator="any data no handler matches" if fn.entry_kind == "fallback" else "",
+                handler=fn.name,
+                guards=body_predicates(fn.body, min(a[0] for a in arms) if arms else None, self.analyzer.guards),
+            ))
+            inventory.entries += routed
+        return inventory
+
+    def _routed(self, root) -> list[Entry]:
+        """Routed entries of a fallback/native root: its own dispatch, else its callees'."""
+        frontier, seen = [(root, [])], {root.key}
+        for _ in range(DISPATCH_DEPTH + 1):
+            following = []
+            for fn, preds in frontier:
+                arms = dispatch_arms(fn.body)
+                if arms:
+                    return [self._entry(root, fn, preds, arm) for arm in arms]
+                for offset, callee in self.analyzer.calls(fn):
+                    if callee.key not in seen:
+                        seen.add(callee.key)
+                        following.append((callee, preds + body_predicates(fn.body, offset, self.analyzer.guards)))
+            frontier = following
+        return []
+
+    def _entry(self, root, fn, preds: list, arm) -> Entry:
+        offset, discriminator, start, end = arm
+        guards = preds + body_predicates(fn.body, offset, self.analyzer.guards)
+        guards += body_predicates(fn.body[start:end], None, self.analyzer.guards)
+        handler = next((callee for at, callee in self.analyzer.calls(fn) if start <= at < end), None)
+        if handler is not None:
+            guards += body_predicates(handler.body, None, self.analyzer.guards)
+        line = fn.content.count("\n", 0, fn.body_start + start) + 1
+        return Entry(
+            name=handler.name if handler else f"{fn.name}[{discriminator}]",
+            kind="routed", file=fn.file, line=line, discriminator=discriminator,
+            handler=handler.name if handler else "", root=root.name, guards=_dedupe(guards),
+        )
+
+
+def analyze_target(path: str, guard_macros=()) -> EntrypointInventory:
+    """Inventory of a directory or .rs file (concatenated sources are split)."""
+    engine = AnchorShieldEngine(split_concatenated=True)
+    path = os.path.abspath(path)
+    if os.path.isfile(path):
+        with open(path, "r", encoding="utf-8", errors="ignore") as fh:
+            sources = engine._sources(os.path.basename(path), fh.read())
+    else:
+        sources = engine.load_sources(path)
+    return InventoryBuilder(sources, guard_macros).build(target=path)
+
+
+def render_markdown(inventory: EntrypointInventory) -> str:
+    """One row per entry: how it is selected, what runs, and what stops a caller."""
+    lines = [
+        "# Entry Points",
+        "",
+        f"Target: {inventory.target}  ",
+        f"Entries: {len(inventory.entries)} · Outside Anchor dispatch: {len(inventory.manual)}",
+        "",
+        "| Entry | Kind | Selected by | Handler | Guards |",
+        "|-------|------|-------------|---------|--------|",
+    ]
+    for e in inventory.entries:
+        guards = "<br>".join(g.replace("|", "\\|") for g in e.guards) or "**none**"
+        kind = f"{e.kind} via {e.root}" if e.root else e.kind
+        selected = (e.discriminator or "—").replace("|", "\\|")
+        lines.append(f"| `{e.file}:{e.line}` {e.name} | {kind} | `{selected}` | {e.handler or '—'} | {guards} |")
+    lines.append("")
+    return "\n".join(lines)
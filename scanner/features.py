@@ -0,0 +1,88 @@
+"""
+Runtime feature gates and program versions that findings depend on.
+
+Some findings only matter where a cluster has a given capability: a
+transfer-hook re-entrancy needs Token-2022 with the transfer-hook extension,
+a stale-delegation issue needs the stake program's redelegate instruction.
+Each finding carries the gates it depends on in `feature_gates`:
+
+  - rule-wide gates from the pattern's `feature_gates` attribute, and
+  - gates detected from the flagged code (FEATURE_MARKERS below).
+
+Given the features activated on the target cluster (`--cluster-features`),
+findings with an inactive gate are marked inapplicable: they stay in the
+report, listed apart, but do not count towards totals or the score.
+
+Cluster feature files list gate names, as TOML/JSON (`features = [...]`, or a
+bare JSON list) or plain text with one name per line and `#` comments.
+"""
+
+import json
+import re
+
+try:
+    import tomllib
+except ImportError:  # Python < 3.11
+    import tomli as tomllib
+
+# Gate name -> what it stands for
+KNOWN_FEATURES = {
+    "token-2022": "Token-2022 program deployed",
+    "token-2022:transfer-hook": "Token-2022 transfer-hook extension",
+    "token-2022:transfer-fee": "Token-2022 transfer-fee extension",
+    "token-2022:confidential-transfer": "Token-2022 confidential-transfer extension",
+    "token-2022:permanent-delegate": "Token-2022 permanent-delegate extension",
+    "token-2022:metadata-pointer": "Token-2022 metadata-pointer extension",
+    "stake:redelegate": "Stake program Redelegate instruction",
+}
+
+# Code that only does something where the gate is active. An extension
+# implies Token-2022 itself.
+FEATURE_MARKERS = [
+    ("token-2022", re.compile(r"\bspl_token_2022\b|\bToken2022\b|\btoken_2022\b")),
+    ("token-2022:transfer-hook", re.compile(r"\btransfer_hook\b|\bTransferHook\w*")),
+    ("token-2022:transfer-fee", re.compile(r"\btransfer_fee\w*|\bTransferFee\w*|\btransfer_checked_with_fee\b")),
+    ("token-2022:confidential-transfer", re.compile(r"\bconfidential_transfer\w*|\bConfidentialTransfer\w*")),
+    ("token-2022:permanent-delegate", re.compile(r"\bpermanent_delegate\b|\bPermanentDelegate\b")),
+    ("token-2022:metadata-pointer", re.compile(r"\bmetadata_pointer\b|\bMetadataPointer\b")),
+    ("stake:redelegate", re.compile(r"\bredelegate\b|\bRedelegate\b")),
+]
+
+
+def detect_features(code: str) -> set:
+    """Gates referenced by a piece of code."""
+    found = {name for name, regex in FEATURE_MARKERS if regex.search(code)}
+    if any(name.startswith("token-2022:") for name in found):
+        found.add("token-2022")
+    return found
+
+
+def finding_features(pattern, finding) -> list:
+    """Rule-wide gates plus gates detected in the finding's snippet."""
+    return sorted(set(pattern.feature_gates) | detect_features(finding.code_snippet))
+
+
+def load_cluster_features(path: str) -> set:
+    """Read the set of activated gates; unknown names raise ValueError."""
+    with open(path, "rb") as f:
+        text = f.read().decode("utf-8")
+    if path.endswith(".json"):
+        data = json.loads(text)
+        names = data if isinstance(data, list) else data.get("features", [])
+    elif path.endswith(".toml"):
+        names = tomllib.loads(text).get("features", [])
+    else:
+        names = [line.split("#", 1)[0].strip() for line in text.splitlines()]
+    names = {n.strip().lower() for n in names if n and n.strip()}
+    unknown = names - set(KNOWN_FEATURES)
+    if unknown:
+        raise ValueError(
+            f"unknown feature(s) {', '.join(sorted(unknown))}; "
+            f"expected {', '.join(KNOWN_FEATURES)}"
+        )
+    return names
+
+
+def missing_features(gates: list, active: set) -> list:
+    """Gates a finding depends on that the cluster has not activated."""
+    return [g for g in gates if g not in active]
@@ -0,0 +1,296 @@
+"""
+Account flow diagrams for findings that move value.
+
+A finding in a handler that transfers tokens or lamports is easier to
+review with the handler's money flow next to it: which account pays,
+which receives, and who can authorize each leg. The legs come from the
+same CPI model the rules use (scanner/cpi.py) plus direct lamport
+arithmetic:
+
+    depositor (signer) --transfer amount · depositor: caller signs--> vault (PDA)
+    vault (PDA) --transfer amount · vault: program signs [b"vault", ..]--> withdrawer (signer)
+
+Each leg records its source, destination and authority, and who controls
+the authority:
+
+  - `signer` — the caller signs for it (a Signer or `#[account(signer)]`)
+  - `program` — the program signs with PDA seeds (`new_with_signer`,
+    `invoke_signed`); the seeds are given when the account declares them
+  - `owner` — the program debits lamports of an account it owns directly
+  - `transaction` — not signed by the program and not declared a signer:
+    whoever signed the transaction for it
+
+Legs are set on `Finding.flow` for every finding in the handler (or its
+accounts struct), exported as `flow` in the JSON report, and drawn as an
+inline SVG in the HTML report and a Mermaid flowchart in the Markdown one.
+"""
+
+import html
+import re
+from dataclasses import asdict, dataclass
+
+from scanner.cpi import find_cpi_calls
+from scanner.narrative import ALIAS_RE, _instructions_at
+from scanner.patterns.base import VulnerabilityPattern
+from scanner.patterns.facts import Facts
+
+# Instruction -> (source field, destination field, authority field) of its accounts struct
+CPI_FIELDS = {
+    "transfer": ("from", "to", "authority"),
+    "transfer_checked": ("from", "to", "authority"),
+    "mint_to": ("mint", "to", "authority"),
+    "mint_to_checked": ("mint", "to", "authority"),
+    "burn": ("from", "mint", "authority"),
+    "burn_checked": ("from", "mint", "authority"),
+}
+# Positional accounts of raw instruction builders: (source, destination, authority, amount)
+BUILDER_ARGS = {
+    ("system", "transfer"): (0, 1, 0, 2),
+    ("spl_token", "transfer"): (1, 2, 3, 5),
+    ("spl_token", "transfer_checked"): (1, 3, 4, 7),
+    ("spl_token", "mint_to"): (1, 2, 3, 5),
+    ("spl_token", "burn"): (1, 2, 3, 5),
+}
+CPI_STRUCT_RE = re.compile(r"\b(?:Transfer|TransferChecked|MintTo|Burn)\s*\{")
+LAMPORTS_RE = re.compile(
+    r"\*\*\s*(?:ctx\s*\.\s*accounts\s*\.\s*)?(\w+)(?:[\w.\s]|\(\s*\))*?(?:try_borrow_mut_lamports\s*\(\s*\)\s*\?|lamports\s*\.\s*borrow_mut\s*\(\s*\))"
+    r"\s*([-+]?)=\s*([^;]+);"
+)
+KINDS = {"mint_to": "mint", "mint_to_checked": "mint", "burn": "burn", "burn_checked": "burn"}
+
+
+@dataclass
+class FlowLeg:
+    """One movement of tokens or lamports inside a handler."""
+
+    kind: str  # transfer | mint | burn | lamports
+    source: str
+    destination: str
+    authority: str
+    control: str  # signer | program | owner | transaction
+    amount: str
+    line: int
+    seeds: str = ""
+    source_role: str = ""  # signer | PDA | program-owned
+    destination_role: str = ""
+
+    def to_dict(self) -> dict:
+        return asdict(self)
+
+
+def annotate_flows(findings: list, content: str) -> int:
+    """Set `flow` on findings in handlers that move value; returns how many got one."""
+    if not findings:
+        return 0
+    facts = Facts(content)
+    annotated = 0
+    cache = {}
+    for finding in findings:
+        for ix in _instructions_at(facts, content, finding.line):
+            if ix.name not in cache:
+                cache[ix.name] = [leg.to_dict() for leg in flow_legs(facts, content, ix)]
+            if cache[ix.name]:
+                finding.flow = cache[ix.name]
+                annotated += 1
+                break
+    return annotated
+
+
+def flow_legs(facts: Facts, content: str, ix) -> list[FlowLeg]:
+    """Token and lamport movements of one handler, in source order."""
+    accounts = {a.name: a for a in facts.accounts_of(ix)}
+    aliases = {m.group(1): m.group(2) for m in ALIAS_RE.finditer(ix.body)}
+
+    def name(expr: str) -> str:
+        expr = re.sub(r"\b(?:ctx\s*\.\s*accounts|self)\s*\.\s*|[&*]|\bmut\b", "", expr).strip()
+        m = re.match(r"(\w+)", expr)
+        return aliases.get(m.group(1), m.group(1)) if m else ""
+
+    def control(authority: str, signed: bool) -> tuple:
+        account = accounts.get(authority)
+        if account and account.is_signer:
+            return "signer", ""
+        if signed:
+            seeds = " ".join(account.pda.seeds.split()) if account and account.pda else ""
+            return "program", re.sub(r"\[\s+|,?\s+\]", lambda m: m.group(0).strip(" ,"), seeds)
+        return "transaction", ""
+
+    def role(account: str) -> str:
+        declared = accounts.get(account)
+        if declared and declared.is_signer:
+            return "signer"
+        return "PDA" if declared and declared.pda else ""
+
+    legs = []
+    for call in find_cpi_calls(ix.body):
+        if call.instruction not in CPI_FIELDS:
+            continue
+        line = VulnerabilityPattern._get_line_number(content, ix.body_start + call.start)
+        signed = call.signed or call.via == "invoke" and "invoke_signed" in call.text
+        if call.via == "invoke":
+            leg = _builder_leg(call, ix.body, name)
+        else:
+            leg = _struct_leg(call, ix.body, name)
+        if not leg:
+            continue
+        source, destination, authority, amount = leg
+        if call.program == "system_program":
+            authority = source
+        who, seeds = control(authority, signed)
+        legs.append(FlowLeg(KINDS.get(call.instruction, "transfer"), source, destination, authority,
+                            who, amount, line, seeds, role(source), role(destination)))
+
+    # Direct lamport moves: `**from.try_borrow_mut_lamports()? -= n; **to... += n;`, or
+    # assignments: `**account.lamports.borrow_mut() = 0` closing it into `dest.checked_add(..)`
+    pending = {"-": [], "+": []}
+    for m in LAMPORTS_RE.finditer(ix.body):
+        account, op, amount = name(m.group(1)), m.group(2), " ".join(m.group(3).split())
+        if not op:
+            if amount == "0":
+                op, amount = "-", "all lamports"
+            else:
+                added = re.search(r"\.\s*checked_add\s*\(|\+", amount)
+                op = "+" if added else "-" if re.search(r"\.\s*checked_sub\s*\(|-", amount) else ""
+        if op not in pending:
+            continue
+        other = pending["+" if op == "-" else "-"]
+        if not other:
+            pending[op].append((account, amount, m.start()))
+            continue
+        matched, matched_amount, matched_start = other.pop(0)
+        (source, debited, start), destination = \
+            ((account, amount, m.start()), matched) if op == "-" else ((matched, matched_amount, matched_start), account)
+        line = VulnerabilityPattern._get_line_number(content, ix.body_start + start)
+        legs.append(FlowLeg("lamports", source, destination, source, "owner", debited, line, "",
+                            role(source) or "program-owned", role(destination)))
+    legs.sort(key=lambda leg: leg.line)
+    return legs
+
+
+def _args(text: str, open_pos: int) -> list:
+    """Top-level comma-separated arguments of the group opening at open_pos."""
+    args, depth, start = [], 0, open_pos + 1
+    for i in range(open_pos, len(text)):
+        ch = text[i]
+        if ch in "([{":
+            depth += 1
+        elif ch in ")]}":
+            depth -= 1
+            if depth == 0:
+                args.append(text[start:i])
+                break
+        elif ch == "," and depth == 1:
+            args.append(text[start:i])
+            start = i + 1
+    return [" ".join(a.split()) for a in args if a.strip()]
+
+
+def _struct_leg(call, body: str, name):
+    """(source, destination, authority, amount) of a `token::transfer(ctx, amount)`-style call."""
+    fields = CPI_FIELDS[call.instruction]
+    literal = CPI_STRUCT_RE.search(call.text)
+    if literal:
+        text = call.text[literal.start():]
+    else:
+        # `let cpi_accounts = Transfer { .. };` built before the call
+        before = list(CPI_STRUCT_RE.finditer(body[:call.start]))
+        if not before:
+            return None
+        text = body[before[-1].start():call.start]
+    found = [re.search(rf"\b{f}\s*:\s*([^,}}]+)", text) for f in fields]
+    if not found[0] or not found[1]:
+        return None
+    endpoints = [name(m.group(1)) if m else "" for m in found]
+    args = _args(call.text, call.text.find("("))
+    amount = args[1] if len(args) > 1 else ""
+    return endpoints[0], endpoints[1], endpoints[2], amount
+
+
+def _builder_leg(call, body: str, name):
+    """(source, destination, authority, amount) of `invoke(&spl_token::instruction::transfer(..), ..)`."""
+    builder = re.search(r"\b(\w+?)(?:\s*::\s*instruction|_instruction)\s*::\s*(\w+)\s*\(", call.text)
+    text = call.text
+    if not builder:
+        arg = re.match(r"invoke(?:_signed)?\s*\(\s*&?\s*(\w+)", call.text)
+        lets = arg and re.findall(rf"\blet\s+(?:mut\s+)?{arg.group(1)}\b[^=]*=\s*([^;]*);", body[:call.start])
+        if not lets:
+            return None
+        text = lets[-1]
+        builder = re.search(r"\b(\w+?)(?:\s*::\s*instruction|_instruction)\s*::\s*(\w+)\s*\(", text)
+        if not builder:
+            return None
+    positions = BUILDER_ARGS.get((builder.group(1), builder.group(2)))
+    if not positions:
+        return None
+    args = _args(text, builder.end() - 1)
+    if len(args) <= max(positions):
+        return None
+    source, destination, authority, amount = (args[i] for i in positions)
+    return name(source), name(destination), name(authority), amount
+
+
+CONTROL_LABELS = {
+    "signer": "caller signs",
+    "program": "program signs",
+    "owner": "program debits",
+    "transaction": "transaction signer",
+}
+
+
+def _node(leg: dict, end: str) -> str:
+    """Account label with its role: `vault (PDA)`, `depositor (signer)`."""
+    role = leg.get(f"{end}_role")
+    return f"{leg[end]} ({role})" if role else leg[end]
+
+
+def _edge(leg: dict) -> str:
+    """Arrow label: amount, then who authorizes the leg."""
+    who = CONTROL_LABELS[leg["control"]]
+    if leg["seeds"]:
+        who += f" {leg['seeds']}"
+    authority = f"{leg['authority']}: {who}" if leg["authority"] else who
+    amount = f"{leg['kind']} {leg['amount']}".strip()
+    return f"{amount} · {authority}"
+
+
+def render_mermaid(flow: list) -> str:
+    """A Mermaid flowchart of the legs, for Markdown reports."""
+    ids = {}
+
+    def node(label: str) -> str:
+        if label not in ids:
+            ids[label] = f"n{len(ids)}"
+        return ids[label]
+
+    lines = ["```mermaid", "flowchart LR"]
+    for leg in flow:
+        source, destination = _node(leg, "source"), _node(leg, "destination")
+        label = _edge(leg).replace('"', "'")
+        lines.append(f'    {node(source)}["{source}"] -- "{label}" --> {node(destination)}["{destination}"]')
+    lines.append("```")
+    return "\n".join(lines)
+
+
+def render_svg(flow: list) -> str:
+    """An inline SVG with one row per leg: source box, labelled arrow, destination box."""
+    row, width = 56, 680
+    height = row * len(flow)
+    parts = [f'<svg class="flow" width="{width}" height="{height}" '
+             f'viewBox="0 0 {width} {height}" xmlns="http://www.w3.org/2000/svg" role="img">',
+             '<defs><marker id="flow-arrow" markerWidth="8" markerHeight="8" refX="7" refY="4" orient="auto">'
+             '<path d="M0,0 L8,4 L0,8 z" fill="#14F195"/></marker></defs>']
+    for i, leg in enumerate(flow):
+        y = i * row + 8
+        for x, end in ((4, "source"), (476, "destination")):
+            parts.append(f'<rect x="{x}" y="{y}" width="200" height="40" rx="6" fill="#0F1117" stroke="#9945FF"/>')
+            parts.append(f'<text x="{x + 100}" y="{y + 25}" text-anchor="middle" fill="#E0E0E0" '
+                         f'font-size="12">{html.escape(_node(leg, end))}</text>')
+        parts.append(f'<line x1="206" y1="{y + 20}" x2="472" y2="{y + 20}" stroke="#14F195" '
+                     f'stroke-width="1.5" marker-end="url(#flow-arrow)"/>')
+        amount, authority = _edge(leg).split(" · ", 1)
+        parts.append(f'<text x="339" y="{y + 14}" text-anchor="middle" fill="#ccc" font-size="11">'
+                     f'{html.escape(amount)} (line {leg["line"]})</text>')
+        parts.append(f'<text x="339" y="{y + 36}" text-anchor="middle" fill="#888" font-size="10">'
+                     f'{html.escape(authority)}</text>')
+    parts.append("</svg>")
+    return "".join(parts)
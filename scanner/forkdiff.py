@@ -0,0 +1,303 @@
+"""
+Differential comparison of a fork against its upstream.
+
+Most audited programs are forks (CLMM forks of Raydium, vault forks of
+Marinade...), and the highest-signal question for a fork is what it dropped
+or added relative to code that was already reviewed. This module matches
+the two trees by name — functions, accounts structs and their fields — and
+reports:
+
+  - removed-check        a require!/assert!/`if ... return Err` condition
+                         present in an upstream function and missing from
+                         the fork's function of the same name
+  - loosened-constraint  a security-relevant #[account(...)] clause
+                         (has_one, constraint, seeds, owner, address, token::*,
+                         ...) missing from the fork's field, or changed
+  - weakened-type        Signer<'info> / Account<'info, T> / Program<...>
+                         replaced by AccountInfo or UncheckedAccount
+  - added-admin-path     a fork-only entrypoint that writes an authority-like
+                         field or looks administrative by name
+
+Items are matched by name, not path, so moved modules do not show up as
+removals. Functions and fields that disappeared entirely are not reported,
+as a refactor often just renames them.
+"""
+
+import json
+import os
+import re
+import tempfile
+from dataclasses import dataclass, field
+from typing import Optional
+
+from scanner.backtest import materialize
+from scanner.engine import AnchorShieldEngine
+from scanner.patterns.account_types import account_type
+from scanner.patterns.base import VulnerabilityPattern
+from scanner.patterns.facts import Facts
+
+# Clauses whose removal or change widens who can call or what can be passed
+SECURITY_KINDS = re.compile(
+    r"^(?:has_one|constraint|signer|owner|address|seeds|bump|executable|zero|"
+    r"token::\w+|associated_token::\w+|mint::\w+|seeds::program)$"
+)
+
+# Wrappers whose replacement by a raw one (AccountInfo, UncheckedAccount) drops checks
+STRONG_WRAPPERS = ("Signer", "Account", "InterfaceAccount", "AccountLoader", "Program", "Interface", "Sysvar")
+
+AUTHORITY_WRITE_RE = re.compile(r"\.\s*(\w*(?:authority|admin|owner|operator|manager)\w*)\s*=(?!=)")
+ADMIN_NAME_RE = re.compile(
+    r"(?:^|_)(?:admin|set_\w*(?:authority|owner|admin|fee|config)|update_\w*config|"
+    r"emergency|withdraw_\w*fee|collect_\w*fee|transfer_\w*(?:authority|ownership)|migrate|upgrade)"
+)
+
+SEVERITY = {
+    "removed-check": "High",
+    "loosened-constraint": "High",
+    "weakened-type": "High",
+    "added-admin-path": "Medium",
+}
+
+
+@dataclass
+class ForkChange:
+    kind: str
+    severity: str
+    file: str
+    line: int
+    location: str       # "fn name" or "Struct.field"
+    upstream: str
+    fork: str
+    detail: str
+
+    def to_dict(self) -> dict:
+        return {
+            "kind": self.kind, "severity": self.severity, "file": self.file,
+            "line": self.line, "location": self.location, "upstream": self.upstream,
+            "fork": self.fork, "detail": self.detail,
+        }
+
+
+@dataclass
+class ForkDiffReport:
+    fork: str
+    upstream: str
+    changes: list = field(default_factory=list)
+
+    def by_kind(self) -> dict:
+        counts = {}
+        for c in self.changes:
+            counts[c.kind] = counts.get(c.kind, 0) + 1
+        return counts
+
+    def to_dict(self) -> dict:
+        return {
+            "fork": self.fork,
+            "upstream": self.upstream,
+            "summary": self.by_kind(),
+            "changes": [c.to_dict() for c in self.changes],
+        }
+
+    def to_json(self, indent: int = 2) -> str:
+        return json.dumps(self.to_dict(), indent=indent)
+
+
+@dataclass
+class _Tree:
+    """Per-name index over every source unit of one side."""
+
+    functions: dict = field(default_factory=dict)   # key -> (path, fn dict, content)
+    fields: dict = field(default_factory=dict)      # (struct, field) -> (path, Account)
+    entrypoints: dict = field(default_factory=dict)  # name -> (path, Instruction, Facts)
+
+
+def compare_sources(fork_sources: list, upstream_sources: list,
+                    fork: str = "", upstream: str = "") -> ForkDiffReport:
+    """Diff two lists of (path, content) units."""
+    ours, theirs = _index(fork_sources), _index(upstream_sources)
+    report = ForkDiffReport(fork=fork, upstream=upstream)
+    report.changes += _removed_checks(ours, theirs)
+    report.changes += _constraint_changes(ours, theirs)
+    report.changes += _added_admin_paths(ours, theirs)
+    report.changes.sort(key=lambda c: (c.file, c.line, c.kind))
+    return report
+
+
+def compare_targets(fork: str, upstream: str, upstream_commit: Optional[str] = None) -> ForkDiffReport:
+    """Diff a fork directory against an upstream directory, git commit or URL."""
+    engine = AnchorShieldEngine(split_concatenated=True)
+    fork = os.path.abspath(fork)
+    with tempfile.TemporaryDirectory(prefix="anchor-shield-upstream-") as tmp:
+        upstream_dir = materialize(upstream, upstream_commit, tmp)
+        upstream_sources = engine.load_sources(upstream_dir)
+    label = f"{upstream}@{upstream_commit}" if upstream_commit else upstream
+    return compare_sources(engine.load_sources(fork), upstream_sources, fork, label)
+
+
+# ─── Indexing ───────────────────────────────────────────────────────
+
+def _index(sources: list) -> _Tree:
+    tree = _Tree()
+    for path, content in sources:
+        impls = VulnerabilityPattern._find_impl_blocks(content)
+        for fn in VulnerabilityPattern._find_functions(content):
+            owner = next((name for s, e, name in impls if s <= fn["body_start"] < e), "")
+            key = f"{owner}::{fn['name']}" if owner else fn["name"]
+            tree.functions.setdefault(key, (path, fn, content))
+        facts = Facts(content, path)
+        for struct in facts.accounts_structs():
+            for account in struct.accounts:
+                tree.fields.setdefault((struct.name, account.name), (path, account))
+        for ix in facts.instructions():
+            if ix.is_entrypoint:
+                tree.entrypoints.setdefault(ix.name, (path, ix, facts))
+    return tree
+
+
+def _checks(body: str) -> dict:
+    """Normalized condition -> offset for every guard in a function body."""
+    found = {}
+    for m in re.finditer(r"\b(?:require\w*|assert\w*)!\s*\(", body):
+        args = _balanced(body, m.end() - 1)
+        cond = _split_top(args)
+        name = m.group(0).split("!")[0].strip()
+        # require_keys_eq!(a, b, E) and friends compare their first two arguments
+        if re.search(r"_(?:eq|neq|gt|gte|lt|lte)$", name) and len(cond) >= 2:
+            text = f"{name}({cond[0]}, {cond[1]})"
+        else:
+            text = cond[0] if cond else args
+        found.setdefault(_norm(text), m.start())
+    for m in re.finditer(r"\bif\s+([^{;]+?)\s*\{\s*return\s+(?:Err|err!)", body):
+        found.setdefault(_norm(f"if {m.group(1)}"), m.start())
+    return found
+
+
+def _balanced(text: str, open_pos: int) -> str:
+    depth = 0
+    for i in range(open_pos, len(text)):
+        if text[i] in "([{":
+            depth += 1
+        elif text[i] in ")]}":
+            depth -= 1
+            if depth == 0:
+                return text[open_pos + 1:i]
+    return text[open_pos + 1:]
+
+
+def _split_top(args: str) -> list:
+    parts, depth, current = [], 0, ""
+    for ch in args:
+        if ch in "([{":
+            depth += 1
+        elif ch in ")]}":
+            depth -= 1
+        if ch == "," and depth == 0:
+            parts.append(current.strip())
+            current = ""
+        else:
+            current += ch
+    if current.strip():
+        parts.append(current.strip())
+    return parts
+
+
+def _norm(text: str) -> str:
+    return re.sub(r"\s+", "", text)
+
+
+# ─── Comparisons ────────────────────────────────────────────────────
+
+def _removed_checks(ours: _Tree, theirs: _Tree) -> list[ForkChange]:
+    changes = []
+    for key, (up_path, up_fn, _) in theirs.functions.items():
+        upstream_checks = _checks(up_fn["body"])
+        if not upstream_checks or key not in ours.functions:
+            continue
+        path, fn, content = ours.functions[key]
+        fork_checks = _checks(fn["body"])
+        for cond, offset in upstream_checks.items():
+            if cond in fork_checks:
+                continue
+            original = _collapse(up_fn["body"][offset:offset + 160])
+            changes.append(ForkChange(
+                kind="removed-check", severity=SEVERITY["removed-check"], file=path,
+                line=fn["line"], location=f"fn {key}", upstream=original, fork="",
+                detail=f"upstream {up_path}:{up_fn['line']} checks this; the fork's {key} does not",
+            ))
+    return changes
+
+
+def _constraint_changes(ours: _Tree, theirs: _Tree) -> list[ForkChange]:
+    changes = []
+    for key, (up_path, up_acc) in theirs.fields.items():
+        if key not in ours.fields:
+            continue
+        path, acc = ours.fields[key]
+        location = f"{key[0]}.{key[1]}"
+        if account_type(up_acc.type).wrapper in STRONG_WRAPPERS and account_type(acc.type).is_raw:
+            changes.append(ForkChange(
+                kind="weakened-type", severity=SEVERITY["weakened-type"], file=path, line=acc.line,
+                location=location, upstream=up_acc.type, fork=acc.type,
+                detail="typed account replaced by an unchecked one; owner/discriminator/signer checks are gone",
+            ))
+        fork_clauses = {(c.kind, _norm(c.value)) for c in acc.constraints}
+        fork_kinds = {c.kind for c in acc.constraints}
+        for c in up_acc.constraints:
+            if not SECURITY_KINDS.match(c.kind) or (c.kind, _norm(c.value)) in fork_clauses:
+                continue
+            if c.kind == "signer" and acc.is_signer:
+                continue
+            changed = c.kind in fork_kinds and c.kind not in ("constraint", "has_one")
+            fork_text = ", ".join(x.text for x in acc.constraints if x.kind == c.kind) if changed else ""
+            changes.append(ForkChange(
+                kind="loosened-constraint", severity=SEVERITY["loosened-constraint"], file=path,
+                line=acc.line, location=location, upstream=c.text, fork=fork_text,
+                detail=("clause changed from upstream" if changed else "clause removed from upstream")
+                + f" ({up_path}:{c.line})",
+            ))
+    return changes
+
+
+def _added_admin_paths(ours: _Tree, theirs: _Tree) -> list[ForkChange]:
+    changes = []
+    for name, (path, ix, facts) in ours.entrypoints.items():
+        if name in theirs.entrypoints:
+            continue
+        writes = sorted(set(AUTHORITY_WRITE_RE.findall(ix.body)))
+        if not writes and not ADMIN_NAME_RE.search(name):
+            continue
+        signers = [a.name for a in facts.accounts_of(ix) if a.is_signer]
+        detail = f"writes {', '.join(writes)}" if writes else "administrative by name"
+        detail += f"; signers: {', '.join(signers) or 'none'}"
+        changes.append(ForkChange(
+            kind="added-admin-path", severity=SEVERITY["added-admin-path"], file=path,
+            line=ix.line, location=f"fn {name}", upstream="", fork=f"{name}(Context<{ix.accounts_struct}>)",
+            detail=f"entrypoint not present upstream; {detail}",
+        ))
+    return changes
+
+
+def _collapse(text: str, limit: int = 100) -> str:
+    text = " ".join(text.split())
+    m = re.match(r"(.*?\)\s*;|.*?\{)", text)
+    text = m.group(1) if m else text
+    return text if len(text) <= limit else text[:limit - 1] + "…"
+
+
+def render_markdown(report: ForkDiffReport) -> str:
+    lines = [
+        "# Fork Diff",
+        "",
+        f"Fork: {report.fork}  ",
+        f"Upstream: {report.upstream}  ",
+        "Changes: " + (", ".join(f"{k} {v}" for k, v in sorted(report.by_kind().items())) or "none"),
+        "",
+        "| Severity | Kind | Location | Upstream | Fork | Detail |",
+        "|----------|------|----------|----------|------|--------|",
+    ]
+    for c in report.changes:
+        cells = [c.severity, c.kind, f"`{c.file}:{c.line}` {c.location}",
+                 f"`{c.upstream}`" if c.upstream else "—", f"`{c.fork}`" if c.fork else "—", c.detail]
+        lines.append("| " + " | ".join(x.replace("|", "\\|") for x in cells) + " |")
+    lines.append("")
+    return "\n".join(lines)
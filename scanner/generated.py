@@ -0,0 +1,88 @@
+"""
+Detection of generated source files.
+
+Programs vendor clients generated from other programs' IDLs (anchor-gen,
+Codama/Kinobi, Solita), FFI bindings from rust-bindgen, and
+build-script output. Their code is not the program's logic: findings there
+are fixed by regenerating or not at all, and they crowd out the hand-written
+code the report is for.
+
+A unit is generated when:
+
+  - its header (the comments before the first item) carries a generator
+    marker: `@generated`, `DO NOT EDIT`, "automatically generated",
+    "generated by <tool>", or the explicit `// anchor-shield: generated`
+  - it is rust-bindgen output (`__bindgen` items)
+  - its path is a generated location: a `generated/` or `codegen/`
+    directory, or a `*_generated.rs` / `*.generated.rs` file
+  - `.gitattributes` at the scan root marks it `linguist-generated`
+
+Findings in generated units are left out of the report and counted in its
+`generated` list; `--include-generated` reports them, marked and ordered
+after the hand-written findings, without counting them in the score.
+"""
+
+import fnmatch
+import os
+import re
+
+HEADER_LINES = 40
+MARKER_RE = re.compile(
+    r"@generated\b|\bDO NOT EDIT\b|\bauto(?:matically|-)?\s*generated\b"
+    r"|\bgenerated\s+(?:by|using|with|from)\b|\banchor-shield:\s*generated\b",
+    re.I,
+)
+BINDGEN_RE = re.compile(r"\b__bindgen\w*")
+GENERATED_DIRS = {"generated", "codegen", "__generated__"}
+GENERATED_FILE_RE = re.compile(r"[._]generated\.rs$")
+
+
+def generated_reason(path: str, content: str, globs: tuple = ()) -> str:
+    """Why the unit at `path` is generated code, or "" if it is hand-written."""
+    marker = _header_marker(content)
+    if marker:
+        return f"header marker '{marker}'"
+    if BINDGEN_RE.search(content):
+        return "rust-bindgen output"
+    parts = re.split(r"[\\/]", path)
+    if GENERATED_DIRS & {p.lower() for p in parts[:-1]} or GENERATED_FILE_RE.search(parts[-1]):
+        return "generated path"
+    normalized = "/".join(parts)
+    for glob in globs:
+        if fnmatch.fnmatch(normalized, glob) or fnmatch.fnmatch(parts[-1], glob):
+            return f".gitattributes linguist-generated ({glob})"
+    return ""
+
+
+def _header_marker(content: str) -> str:
+    """Generator marker in the leading comments of the unit."""
+    for line in content.split("\n")[:HEADER_LINES]:
+        stripped = line.strip()
+        if not stripped:
+            continue
+        if not stripped.startswith(("//", "/*", "*", "#![")):
+            break
+        m = MARKER_RE.search(stripped)
+        if m:
+            return m.group(0)
+    return ""
+
+
+def linguist_generated(root: str) -> tuple:
+    """Patterns `.gitattributes` at `root` marks linguist-generated."""
+    path = os.path.join(root, ".gitattributes")
+    globs = []
+    try:
+        with open(path, "r", encoding="utf-8", errors="ignore") as fh:
+            lines = fh.read().split("\n")
+    except (OSError, IOError):
+        return ()
+    for line in lines:
+        fields = line.split("#")[0].split()
+        if len(fields) < 2:
+            continue
+        pattern, attributes = fields[0], fields[1:]
+        if any(a in ("linguist-generated", "linguist-generated=true") for a in attributes):
+            # Paths are matched from the scan root; a trailing slash marks a directory
+            globs.append(pattern.lstrip("/") + ("*" if pattern.endswith("/") else ""))
+    return tuple(globs)
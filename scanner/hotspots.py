@@ -0,0 +1,225 @@
+"""
+Manual-review hotspots: code the rules cannot judge.
+
+A finding list says where the rules fired; it says nothing about the
+functions nobody's rules understand. A 200-line tick-crossing routine with
+a dozen branches, three CPIs and instruction data flowing into account
+state, and no finding at all, is either very well written or outside what
+pattern matching can see — either way it is where a reviewer's hour goes
+furthest.
+
+rank_sources() scores every function in the target on:
+
+  - complexity:  cyclomatic complexity (1 + `if` / `while` / `for` / `loop`,
+                 `&&` / `||`, and each `match` arm after the first)
+  - CPIs:        cross-program calls made in the body (cpi.py)
+  - taint:       paths from the function's parameters (an instruction's
+                 arguments after ctx) to account writes, CPIs and transfers
+                 (Facts.taint_paths), per 10 lines of body; bodies shorter
+                 than 10 lines count as 10, so one-line setters do not
+                 outrank real logic
+
+    score = complexity + 3 * cpis + 5 * taint density
+
+and divides the score of a function that already has findings by 1 + its
+finding count, so complex-but-clean functions rank first. Functions with
+complexity 2 or less, no CPI and no taint path are left out, as are
+`#[test]` functions. The ranking complements the findings list; it is not
+a finding itself.
+"""
+
+import json
+import os
+import re
+from dataclasses import dataclass, field
+
+from scanner.cpi import declared_programs, find_cpi_calls, interface_modules
+from scanner.engine import AnchorShieldEngine
+from scanner.patterns.base import VulnerabilityPattern
+from scanner.patterns.facts import Facts, Instruction
+from scanner.patterns.validation import _param_names
+
+DECISION_RE = re.compile(r"\b(?:if|while|for|loop)\b|&&|\|\|")
+# Effects instruction data should not reach unchecked
+EFFECT_SINK = (
+    r"\.\s*\w+\s*(?:[+\-*/]|<<|>>)?=(?!=)|\binvoke(?:_signed)?\s*\(|\bcpi\s*::|"
+    r"\b(?:transfer|transfer_checked|mint_to|burn|close_account|set_authority)\s*\("
+)
+TEST_ATTR_RE = re.compile(r"#\[(?:tokio::)?test\]\s*(?:#\[[^\]]*\]\s*)*$")
+
+CPI_WEIGHT, TAINT_WEIGHT = 3, 5
+
+
+@dataclass
+class Hotspot:
+    function: str
+    file: str
+    line: int
+    end_line: int
+    complexity: int
+    cpis: int
+    taint_paths: int
+    findings: int
+    score: float
+
+    @property
+    def loc(self) -> int:
+        return self.end_line - self.line + 1
+
+    @property
+    def taint_density(self) -> float:
+        """Taint paths per 10 lines of body, counting short bodies as 10 lines."""
+        return round(self.taint_paths * 10 / max(self.loc, 10), 2)
+
+    @property
+    def clean(self) -> bool:
+        return self.findings == 0
+
+    def reasons(self) -> list[str]:
+        reasons = [f"complexity {self.complexity}"]
+        if self.cpis:
+            reasons.append(f"{self.cpis} CPI{'s' if self.cpis > 1 else ''}")
+        if self.taint_paths:
+            reasons.append(f"{self.taint_paths} taint path{'s' if self.taint_paths > 1 else ''} "
+                           f"from parameters to effects")
+        reasons.append("no findings" if self.clean else f"{self.findings} finding(s)")
+        return reasons
+
+    def to_dict(self) -> dict:
+        return {
+            "function": self.function, "file": self.file, "line": self.line,
+            "end_line": self.end_line, "loc": self.loc, "complexity": self.complexity,
+            "cpis": self.cpis, "taint_paths": self.taint_paths,
+            "taint_density": self.taint_density, "findings": self.findings,
+            "clean": self.clean, "score": self.score, "reasons": self.reasons(),
+        }
+
+
+@dataclass
+class HotspotReport:
+    target: str
+    functions: int = 0
+    findings: int = 0
+    hotspots: list = field(default_factory=list)
+
+    def to_dict(self) -> dict:
+        return {
+            "target": self.target,
+            "functions": self.functions,
+            "findings": self.findings,
+            "hotspots": [h.to_dict() for h in self.hotspots],
+        }
+
+    def to_json(self, indent: int = 2) -> str:
+        return json.dumps(self.to_dict(), indent=indent)
+
+
+def rank_sources(sources: list, findings: list, target: str = "",
+                 limit: int = 20, clean_only: bool = False) -> HotspotReport:
+    """Rank the functions of (path, content) units, given the scan's findings on them."""
+    report = HotspotReport(target=target, findings=len(findings))
+    declared, interfaces = set(), {}
+    for _, content in sources:
+        declared |= declared_programs(content)
+        interfaces.update(interface_modules(content))
+
+    hotspots = []
+    for path, content in sources:
+        facts = Facts(content, path)
+        for fn in VulnerabilityPattern._find_functions(content):
+            start = content.rfind(f"fn {fn['name']}", 0, fn["body_start"])
+            if TEST_ATTR_RE.search(content[max(0, start - 200):content.rfind("\n", 0, start) + 1]):
+                continue
+            report.functions += 1
+            end_line = VulnerabilityPattern._get_line_number(content, fn["end"] - 1)
+            body = _blank(fn["body"])
+            complexity = _complexity(body)
+            cpis = len(find_cpi_calls(fn["body"], declared, interfaces))
+            taint = _taint_paths(facts, fn)
+            if complexity <= 2 and not cpis and not taint:
+                continue
+            hits = sum(1 for f in findings if f.file == path and fn["line"] <= f.line <= end_line)
+            spot = Hotspot(fn["name"], path, fn["line"], end_line, complexity, cpis, taint, hits, 0.0)
+            raw = complexity + CPI_WEIGHT * cpis + TAINT_WEIGHT * spot.taint_density
+            spot.score = round(raw / (1 + hits), 2)
+            hotspots.append(spot)
+
+    if clean_only:
+        hotspots = [h for h in hotspots if h.clean]
+    hotspots.sort(key=lambda h: (-h.score, h.file, h.line))
+    report.hotspots = hotspots[:limit] if limit else hotspots
+    return report
+
+
+def rank_target(path: str, limit: int = 20, clean_only: bool = False,
+                engine: AnchorShieldEngine = None) -> HotspotReport:
+    """Scan the target with every rule, then rank its functions."""
+    engine = engine or AnchorShieldEngine(split_concatenated=True)
+    path = os.path.abspath(path)
+    if os.path.isfile(path):
+        with open(path, "r", encoding="utf-8", errors="ignore") as fh:
+            sources = engine._sources(os.path.basename(path), fh.read())
+    else:
+        sources = engine.load_sources(path)
+    findings = []
+    for src_path, content in sources:
+        findings.extend(engine.scan_source(src_path, content))
+    return rank_sources(sources, findings, path, limit, clean_only)
+
+
+def _complexity(body: str) -> int:
+    arms = sum(max(_match_arms(body, m.end()) - 1, 0) for m in re.finditer(r"\bmatch\b[^{;]*\{", body))
+    return 1 + len(DECISION_RE.findall(body)) + arms
+
+
+def _match_arms(body: str, open_end: int) -> int:
+    """`=>` at the top level of the match block opening just before open_end."""
+    depth, arms = 1, 0
+    for i in range(open_end, len(body)):
+        ch = body[i]
+        if ch in "{([":
+            depth += 1
+        elif ch in "})]":
+            depth -= 1
+            if depth == 0:
+                break
+        elif ch == "=" and depth == 1 and body.startswith("=>", i):
+            arms += 1
+    return arms
+
+
+def _taint_paths(facts: Facts, fn: dict) -> int:
+    params = [name for name, t in _param_names(fn["params"]) if not re.match(r"&?\s*(?:mut\s+)?Context\b", t)]
+    if not params:
+        return 0
+    unit = Instruction(fn["name"], "", fn["params"], fn["body"], fn["body_start"], fn["line"])
+    return sum(len(facts.taint_paths(name, EFFECT_SINK, unit)) for name in params)
+
+
+def _blank(text: str) -> str:
+    """Comments and string literals replaced by spaces."""
+    return re.sub(r'//[^\n]*|/\*.*?\*/|"(?:\\.|[^"\\])*"',
+                  lambda m: re.sub(r"[^\n]", " ", m.group(0)), text, flags=re.S)
+
+
+def render_markdown(report: HotspotReport) -> str:
+    lines = [
+        "# Manual Review Hotspots",
+        "",
+        f"Target: {report.target}  ",
+        f"Functions: {report.functions} · Findings: {report.findings} · Ranked: {len(report.hotspots)}",
+        "",
+        "Functions ranked by complexity, CPIs and taint density, with functions the rules "
+        "already flagged ranked down. Complements the findings list.",
+        "",
+        "| # | Function | Location | Score | Complexity | CPIs | Taint / 10 LOC | Findings |",
+        "|---|----------|----------|-------|------------|------|----------------|----------|",
+    ]
+    for i, h in enumerate(report.hotspots, 1):
+        findings = "**none**" if h.clean else str(h.findings)
+        lines.append(
+            f"| {i} | {h.function} | `{h.file}:{h.line}` | {h.score} | {h.complexity} | "
+            f"{h.cpis} | {h.taint_density} | {findings} |"
+        )
+    lines.append("")
+    return "\n".join(lines)
@@ -0,0 +1,486 @@
+"""
+Local `macro_rules!` expansion for analysis.
+
+Forked AMM codebases often stamp out accounts structs and state types with
+declarative macros:
+
+    macro_rules! swap_accounts {
+        ($name:ident, $pool:ty) => {
+            #[derive(Accounts)]
+            pub struct $name<'info> {
+                #[account(mut)]
+                pub pool: Account<'info, $pool>,
+            }
+        };
+    }
+    swap_accounts!(SwapBaseIn, AmmPool);
+
+Detectors are text-based and cannot see `SwapBaseIn` until the macro is
+expanded. expand_macros() expands invocations of macros defined in the same
+source, blanks the definitions (so template text is not scanned as code),
+and returns a line map so findings point back at the invocation.
+
+Supported matcher fragments: ident, lifetime, literal, tt, ty, path, expr,
+pat, vis, block, item, stmt, meta; `$( ... ) sep? *|+|?` repetitions.
+Anything the matcher cannot handle is left unexpanded.
+"""
+
+import re
+from dataclasses import dataclass, field
+from typing import Optional
+
+MAX_DEPTH = 4
+
+DEFINITION_RE = re.compile(r"(?:#\[macro_export\]\s*)?\bmacro_rules!\s*(\w+)\s*([({\[])")
+TOKEN_RE = re.compile(
+    r"\s+|//[^\n]*|/\*.*?\*/"
+    r"|(?P<lifetime>'[A-Za-z_]\w*(?!'))"
+    r"|(?P<literal>b?\"(?:\\.|[^\"\\])*\"|b?'(?:\\.|[^'\\])'|\d[\w.]*)"
+    r"|(?P<ident>[A-Za-z_]\w*)"
+    r"|(?P<frag>\$\w+)"
+    r"|(?P<punct>::|=>|->|==|!=|<=|>=|&&|\|\||\.\.=?|[+\-*/%^!&|=<>@.,;:#$?~])"
+    r"|(?P<open>[({\[])|(?P<close>[)}\]])",
+    re.S,
+)
+PAIRS = {"(": ")", "[": "]", "{": "}"}
+
+
+@dataclass
+class Token:
+    kind: str  # ident | lifetime | literal | punct | frag | group
+    text: str
+    start: int
+    end: int
+    children: list = field(default_factory=list)  # for groups: inner tokens
+    delim: str = ""
+
+
+@dataclass
+class MacroRule:
+    matcher: list
+    transcriber: str  # raw text between the transcriber delimiters
+
+
+@dataclass
+class Expansion:
+    text: str
+    # line_map[i] = original line for expanded line i + 1
+    line_map: list
+    # expanded line -> macro name, for lines produced by an expansion
+    origins: dict
+
+    def original_line(self, line: int) -> int:
+        if 1 <= line <= len(self.line_map):
+            return self.line_map[line - 1]
+        return line
+
+    def macro_at(self, line: int) -> Optional[str]:
+        return self.origins.get(line)
+
+
+def tokenize(text: str, start: int = 0, end: Optional[int] = None) -> list:
+    """Tokenize into token trees (groups hold their inner tokens)."""
+    end = len(text) if end is None else end
+    stack = [[]]
+    opens = []
+    pos = start
+    while pos < end:
+        m = TOKEN_RE.match(text, pos)
+        if not m:
+            pos += 1
+            continue
+        pos = m.end()
+        kind = m.lastgroup
+        if kind is None:
+            continue
+        if kind == "open":
+            opens.append((m.group(), m.start()))
+            stack.append([])
+        elif kind == "close":
+            if not opens:
+                continue
+            delim, open_start = opens.pop()
+            children = stack.pop()
+            stack[-1].append(Token("group", text[open_start:m.end()], open_start, m.end(),
+                                   children, delim))
+        else:
+            stack[-1].append(Token(kind, m.group(), m.start(), m.end()))
+    while opens:  # unbalanced input: flatten what we have
+        opens.pop()
+        children = stack.pop()
+        stack[-1].extend(children)
+    return stack[0]
+
+
+def find_definitions(content: str) -> dict:
+    """Map macro name -> (list of MacroRule, (def_start, def_end))."""
+    macros = {}
+    for m in DEFINITION_RE.finditer(content):
+        close = _group_end(content, m.end() - 1)
+        if close is None:
+            continue
+        rules = _parse_rules(content, m.end(), close - 1)
+        if rules:
+            macros[m.group(1)] = (rules, (m.start(), close))
+    return macros
+
+
+def _group_end(text: str, open_pos: int) -> Optional[int]:
+    opener = text[open_pos]
+    closer = PAIRS[opener]
+    depth = 0
+    i = open_pos
+    while i < len(text):
+        ch = text[i]
+        if ch == '"':
+            j = i + 1
+            while j < len(text) and text[j] != '"':
+                j += 2 if text[j] == "\\" else 1
+            i = j
+        elif ch == "/" and text.startswith("//", i):
+            nl = text.find("\n", i)
+            i = len(text) if nl == -1 else nl
+        elif ch == opener:
+            depth += 1
+        elif ch == closer:
+            depth -= 1
+            if depth == 0:
+                return i + 1
+        i += 1
+    return None
+
+
+def _parse_rules(content: str, start: int, end: int) -> list:
+    tokens = tokenize(content, start, end)
+    rules = []
+    i = 0
+    while i + 2 < len(tokens):
+        matcher, arrow, body = tokens[i], tokens[i + 1], tokens[i + 2]
+        if matcher.kind != "group" or arrow.text != "=>" or body.kind != "group":
+            break
+        rules.append(MacroRule(
+            matcher=_parse_matcher(matcher.children),
+            transcriber=content[body.start + 1:body.end - 1],
+        ))
+        i += 3
+        if i < len(tokens) and tokens[i].text == ";":
+            i += 1
+    return rules
+
+
+def _parse_matcher(tokens: list) -> list:
+    """Matcher elements: ('frag', name, kind) | ('rep', elems, sep, op) | ('lit', token)."""
+    elems = []
+    i = 0
+    while i < len(tokens):
+        tok = tokens[i]
+        if tok.kind == "frag" and i + 2 < len(tokens) and tokens[i + 1].text == ":":
+            elems.append(("frag", tok.text[1:], tokens[i + 2].text))
+            i += 3
+        elif tok.text == "$" and i + 1 < len(tokens) and tokens[i + 1].kind == "group":
+            inner = _parse_matcher(tokens[i + 1].children)
+            i += 2
+            sep = None
+            if i < len(tokens) and tokens[i].text not in ("*", "+", "?"):
+                sep = tokens[i].text
+                i += 1
+            op = tokens[i].text if i < len(tokens) else "*"
+            elems.append(("rep", inner, sep, op))
+            i += 1
+        elif tok.kind == "group":
+            elems.append(("group", tok.delim, _parse_matcher(tok.children)))
+            i += 1
+        else:
+            elems.append(("lit", tok.text))
+            i += 1
+    return elems
+
+
+# ── matching ───────────────────────────────────────────────────────────────
+
+def _match(elems: list, tokens: list, text: str) -> Optional[dict]:
+    bindings = {}
+    pos = _match_seq(elems, 0, tokens, 0, text, bindings)
+    if pos is None or pos != len(tokens):
+        return None
+    return bindings
+
+
+def _match_seq(elems, ei, tokens, ti, text, bindings) -> Optional[int]:
+    if ei == len(elems):
+        return ti
+    elem = elems[ei]
+    kind = elem[0]
+    if kind == "lit":
+        if ti < len(tokens) and tokens[ti].kind != "group" and tokens[ti].text == elem[1]:
+            return _match_seq(elems, ei + 1, tokens, ti + 1, text, bindings)
+        return None
+    if kind == "group":
+        if ti < len(tokens) and tokens[ti].kind == "group" and tokens[ti].delim == elem[1]:
+            inner = {}
+            end = _match_seq(elem[2], 0, tokens[ti].children, 0, text, inner)
+            if end == len(tokens[ti].children):
+                bindings.update(inner)
+                return _match_seq(elems, ei + 1, tokens, ti + 1, text, bindings)
+        return None
+    if kind == "frag":
+        follow = _follow_literal(elems, ei + 1)
+        for end in _fragment_ends(elem[2], tokens, ti, follow):
+            trial = dict(bindings)
+            trial[elem[1]] = _span(tokens, ti, end, text)
+            rest = _match_seq(elems, ei + 1, tokens, end, text, trial)
+            if rest is not None:
+                bindings.clear()
+                bindings.update(trial)
+                return rest
+        return None
+    # repetition: greedy, each iteration binds into lists
+    _, inner, sep, op = elem
+    reps = []
+    pos = ti
+    while True:
+        start = pos
+        if reps and sep:
+            if pos < len(tokens) and tokens[pos].text == sep:
+                start = pos + 1
+            else:
+                break
+        iteration = {}
+        end = _match_seq(inner, 0, tokens, start, text, iteration)
+        if end is None or end == start:
+            break
+        reps.append(iteration)
+        pos = end
+        if op == "?":
+            break
+    if op == "+" and not reps:
+        return None
+    trial = dict(bindings)
+    for name in _frag_names(inner):
+        trial[name] = [r.get(name, "") for r in reps]
+    rest = _match_seq(elems, ei + 1, tokens, pos, text, trial)
+    if rest is None:
+        return None
+    bindings.clear()
+    bindings.update(trial)
+    return rest
+
+
+def _frag_names(elems) -> list:
+    names = []
+    for elem in elems:
+        if elem[0] == "frag":
+            names.append(elem[1])
+        elif elem[0] == "rep":
+            names.extend(_frag_names(elem[1]))
+        elif elem[0] == "group":
+            names.extend(_frag_names(elem[2]))
+    return names
+
+
+def _follow_literal(elems, ei) -> Optional[str]:
+    if ei < len(elems):
+        elem = elems[ei]
+        if elem[0] == "lit":
+            return elem[1]
+        if elem[0] == "rep" and elem[2]:
+            return None
+    return None
+
+
+def _fragment_ends(kind: str, tokens: list, ti: int, follow: Optional[str]):
+    """Candidate end indices (exclusive) for a fragment starting at ti, longest first."""
+    if kind in ("ident", "lifetime", "literal", "tt"):
+        if ti >= len(tokens):
+            return []
+        tok = tokens[ti]
+        ok = {
+            "ident": tok.kind == "ident",
+            "lifetime": tok.kind == "lifetime",
+            "literal": tok.kind == "literal",
+            "tt": True,
+        }[kind]
+        return [ti + 1] if ok else []
+    if kind == "block":
+        ok = ti < len(tokens) and tokens[ti].kind == "group" and tokens[ti].delim == "{"
+        return [ti + 1] if ok else []
+    if kind == "vis":
+        if ti < len(tokens) and tokens[ti].text == "pub":
+            if ti + 1 < len(tokens) and tokens[ti + 1].kind == "group" and tokens[ti + 1].delim == "(":
+                return [ti + 2]
+            return [ti + 1]
+        return [ti]
+    # ty / path / expr / pat / item / stmt / meta: run to the follow token at
+    # top level (tracking <> for generics), then allow shorter prefixes
+    angle = 0
+    end = ti
+    while end < len(tokens):
+        tok = tokens[end]
+        if tok.kind == "punct":
+            if tok.text == "<":
+                angle += 1
+            elif tok.text == ">" and angle:
+                angle -= 1
+            elif angle == 0 and (tok.text == follow or (follow is None and tok.text in (",", ";", "=>"))):
+                break
+        end += 1
+    return list(range(end, ti, -1))
+
+
+def _span(tokens, start, end, text) -> str:
+    if start >= end:
+        return ""
+    return text[tokens[start].start:tokens[end - 1].end]
+
+
+# ── transcription ─────────────────────────────────────────────────────────
+
+def _transcribe(template: str, bindings: dict) -> str:
+    out = []
+    i = 0
+    while i < len(template):
+        if template.startswith("$(", i):
+            close = _group_end(template, i + 1)
+            if close is None:
+                out.append(template[i:])
+                break
+            body = template[i + 2:close - 1]
+            j = close
+            sep = ""
+            if j < len(template) and template[j] not in "*+?":
+                sep = template[j]
+                j += 1
+            j += 1  # repetition operator
+            names = [n for n in re.findall(r"\$(\w+)", body) if isinstance(bindings.get(n), list)]
+            count = max((len(bindings[n]) for n in names), default=0)
+            pieces = []
+            for k in range(count):
+                scoped = dict(bindings)
+                for n in names:
+                    if k < len(bindings[n]):
+                        scoped[n] = bindings[n][k]
+                pieces.append(_transcribe(body, scoped))
+            out.append(sep.join(pieces))
+            i = j
+            continue
+        m = re.match(r"\$(\w+)", template[i:])
+        if m and m.group(1) in bindings and isinstance(bindings[m.group(1)], str):
+            out.append(bindings[m.group(1)])
+            i += m.end()
+            continue
+        if m and m.group(1) == "crate":
+            out.append("crate")
+            i += m.end()
+            continue
+        out.append(template[i])
+        i += 1
+    return "".join(out)
+
+
+def _expand_invocation(macros: dict, name: str, args_text: str) -> Optional[str]:
+    rules = macros[name][0]
+    tokens = tokenize(args_text)
+    for rule in rules:
+        bindings = _match(rule.matcher, tokens, args_text)
+        if bindings is not None:
+            return _transcribe(rule.transcriber, bindings)
+    return None
+
+
+# ── driver ────────────────────────────────────────────────────────────────
+
+def expand_macros(content: str) -> Optional[Expansion]:
+    """Expand local macro_rules! invocations. Returns None if nothing to expand."""
+    macros = find_definitions(content)
+    if not macros:
+        return None
+    invoke_re = re.compile(r"\b(" + "|".join(map(re.escape, macros)) + r")!\s*([({\[])")
+    def_spans = [span for _, span in macros.values()]
+
+    # (text, origin, original offset): origin None = original source,
+    # "pad" = newlines standing in for the invocation, ("macro", name, line)
+    pieces = []
+    pos = 0
+    expanded_any = False
+    for m in invoke_re.finditer(content):
+        if m.start() < pos or any(s <= m.start() < e for s, e in def_spans):
+            continue
+        close = _group_end(content, m.end() - 1)
+        if close is None:
+            continue
+        text = _expand_invocation(macros, m.group(1), content[m.end():close - 1])
+        if text is None:
+            continue
+        for _ in range(MAX_DEPTH):
+            inner = _expand_nested(macros, invoke_re, text)
+            if inner == text:
+                break
+            text = inner
+        # `name!(..);` at item level: drop the trailing semicolon with the call
+        end = close
+        if content[end:end + 1] == ";" and m.group(2) != "{":
+            end += 1
+        pieces.append((content[pos:m.start()], None, pos))
+        pieces.append((text, ("macro", m.group(1), content.count("\n", 0, m.start()) + 1), m.start()))
+        # keep the original line count of the invocation site
+        pieces.append(("\n" * content.count("\n", m.start(), end), "pad", m.start()))
+        pos = end
+        expanded_any = True
+    if not expanded_any:
+        return None
+    pieces.append((content[pos:], None, pos))
+
+    # Blank macro definitions, keeping newlines, so template text is not scanned
+    out_text = []
+    line_map = []
+    origins = {}
+    orig_line = 1
+    for text, origin, offset in pieces:
+        if origin is None:
+            blanked = _blank_spans(text, offset, def_spans)
+            out_text.append(blanked)
+            for _ in range(blanked.count("\n")):
+                line_map.append(orig_line)
+                orig_line += 1
+        elif origin == "pad":
+            out_text.append(text)
+            for _ in range(text.count("\n")):
+                line_map.append(orig_line)
+                orig_line += 1
+        else:
+            _, name, line = origin
+            out_text.append(text)
+            for _ in range(text.count("\n")):
+                origins[len(line_map) + 1] = name
+                line_map.append(line)
+    line_map.append(orig_line)
+    return Expansion(text="".join(out_text), line_map=line_map, origins=origins)
+
+
+def _expand_nested(macros: dict, invoke_re, text: str) -> str:
+    out = []
+    pos = 0
+    for m in invoke_re.finditer(text):
+        if m.start() < pos:
+            continue
+        close = _group_end(text, m.end() - 1)
+        if close is None:
+            continue
+        expanded = _expand_invocation(macros, m.group(1), text[m.end():close - 1])
+        if expanded is None:
+            continue
+        end = close + 1 if text[close:close + 1] == ";" and m.group(2) != "{" else close
+        out.append(text[pos:m.start()])
+        out.append(expanded)
+        pos = end
+    out.append(text[pos:])
+    return "".join(out)
+
+
+def _blank_spans(text: str, offset: int, spans: list) -> str:
+    chars = list(text)
+    for s, e in spans:
+        for i in range(max(s - offset, 0), min(e - offset, len(text))):
+            if chars[i] != "\n":
+                chars[i] = " "
+    return "".join(chars)
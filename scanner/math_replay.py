@@ -0,0 +1,537 @@
+"""
+Concrete arithmetic replay for AMM / fixed-point math modules.
+
+Extracts pure integer functions (tick math, mul_div chains, fee growth
+helpers) from Rust sources, compiles them natively with overflow checks
+enabled, and replays them with boundary and randomized inputs. Reports
+panics (including arithmetic overflow) and monotonicity violations without
+deploying anything.
+
+Requires `rustc` on PATH. Functions returning `Result` replay against a
+stub error type, with `require!`, `err!` and `error!` defined for it.
+Functions that do not compile standalone (they reference crate types,
+other Anchor macros, or account state) are skipped and listed in the
+result.
+"""
+
+import json
+import os
+import re
+import shutil
+import subprocess
+import tempfile
+from dataclasses import dataclass, field
+from typing import Optional
+
+from scanner.patterns.base import VulnerabilityPattern
+
+INT_TYPES = {
+    "u8": (0, 2**8 - 1), "u16": (0, 2**16 - 1), "u32": (0, 2**32 - 1),
+    "u64": (0, 2**64 - 1), "u128": (0, 2**128 - 1), "usize": (0, 2**64 - 1),
+    "i8": (-2**7, 2**7 - 1), "i16": (-2**15, 2**15 - 1),
+    "i32": (-2**31, 2**31 - 1), "i64": (-2**63, 2**63 - 1),
+    "i128": (-2**127, 2**127 - 1),
+}
+
+# Return types the harness knows how to print and compare; a `Result` error
+# type resolves to the harness's own error, so any path is accepted there
+RETURN_RE = re.compile(
+    r"^(?:Option\s*<\s*(\w+)\s*>|Result\s*<\s*(\w+|\(\s*\))\s*(?:,\s*[\w:]+\s*)?>|(\w+)|\(\s*\))$"
+)
+
+# Anchor error plumbing the harness stubs out for functions returning `Result`
+RESULT_MACROS_RE = re.compile(r"\brequire!|\berr!|\berror!")
+ERROR_VARIANT_RE = re.compile(r"\bErrorCode::(\w+)")
+
+# Prepended to every harness: a single error type standing in for Anchor's,
+# and the macros that produce it
+HARNESS_PRELUDE = """\
+#[derive(Debug)]
+struct ReplayError;
+type Error = ReplayError;
+type Result<T, E = ReplayError> = std::result::Result<T, E>;
+mod anchor_lang { pub mod error { pub type Error = crate::ReplayError; } }
+macro_rules! require { ($c:expr, $e:expr $(,)?) => { if !($c) { return Err(error!($e)); } }; }
+macro_rules! error { ($e:expr) => { { let _ = $e; ReplayError } }; }
+macro_rules! err { ($e:expr) => { Err(error!($e)) }; }
+"""
+
+# Function names that mark the file as containing AMM math worth replaying
+MATH_NAME_RE = re.compile(
+    r"tick|sqrt_price|mul_div|fee_growth|liquidity|price|shares|proportional|amount_delta",
+    re.IGNORECASE,
+)
+
+COMPILE_TIMEOUT = 120
+RUN_TIMEOUT = 60
+DEFAULT_ITERATIONS = 200
+MONOTONIC_THRESHOLD = 0.9
+
+
+@dataclass
+class PureFunction:
+    """A self-contained integer function extracted from source."""
+
+    name: str
+    params: list
+    ret: str
+    source: str
+    file: str
+    line: int
+
+    def to_dict(self) -> dict:
+        return {
+            "name": self.name,
+            "params": self.params,
+            "ret": self.ret,
+            "file": self.file,
+            "line": self.line,
+        }
+
+
+@dataclass
+class ReplayIssue:
+    """A panic or monotonicity violation observed during replay."""
+
+    function: str
+    kind: str
+    file: str
+    line: int
+    inputs: list = field(default_factory=list)
+    message: str = ""
+
+    def to_dict(self) -> dict:
+        return {
+            "function": self.function,
+            "kind": self.kind,
+            "file": self.file,
+            "line": self.line,
+            "inputs": self.inputs,
+            "message": self.message,
+        }
+
+
+@dataclass
+class ReplayResult:
+    """Aggregated replay results for a target."""
+
+    target: str
+    functions_replayed: list = field(default_factory=list)
+    functions_skipped: list = field(default_factory=list)
+    issues: list = field(default_factory=list)
+    calls_executed: int = 0
+    error: Optional[str] = None
+
+    def to_dict(self) -> dict:
+        return {
+            "target": self.target,
+            "functions_replayed": [f.to_dict() for f in self.functions_replayed],
+            "functions_skipped": self.functions_skipped,
+            "calls_executed": self.calls_executed,
+            "issues": [i.to_dict() for i in self.issues],
+            "error": self.error,
+        }
+
+    def to_json(self, indent: int = 2) -> str:
+        return json.dumps(self.to_dict(), indent=indent)
+
+
+def extract_pure_functions(file_path: str, content: str) -> list[PureFunction]:
+    """Extract free functions whose parameters and return type are plain integers."""
+    functions = []
+    for fn in VulnerabilityPattern._find_functions(content):
+        params = _parse_params(fn["params"])
+        if params is None or not params:
+            continue
+        ret = fn["ret"] or "()"
+        ret_match = RETURN_RE.match(ret.strip())
+        if not ret_match:
+            continue
+        inner = ret_match.group(1) or ret_match.group(2) or ret_match.group(3)
+        if inner and inner not in INT_TYPES and inner != "bool" and not inner.startswith("("):
+            continue
+        body = fn["body"]
+        if ret_match.group(2):
+            # The harness stubs `require!`, `err!` and `error!` for Result-returning math
+            body = RESULT_MACROS_RE.sub("", body)
+        # Handlers and helpers touching accounts or Anchor macros never compile standalone
+        if re.search(r"\bctx\b|\bmsg!|\brequire\w*!|\berr!|\berror!|\bClock\b|\bself\b", body):
+            continue
+        start = content.rfind("\n", 0, content.rfind("fn", 0, fn["body_start"])) + 1
+        source = content[start:fn["end"]]
+        source = re.sub(r"^\s*pub(?:\([^)]*\))?\s+", "", source)
+        functions.append(PureFunction(
+            name=fn["name"],
+            params=params,
+            ret=ret.strip(),
+            source=source.strip(),
+            file=file_path,
+            line=fn["line"],
+        ))
+    return functions
+
+
+def extract_int_constants(content: str) -> list[str]:
+    """Extract integer const items so replayed functions can reference them."""
+    consts = []
+    for m in re.finditer(
+        r"^\s*(?:pub(?:\([^)]*\))?\s+)?const\s+(\w+)\s*:\s*(\w+)\s*=\s*([^;]+);",
+        content, re.MULTILINE,
+    ):
+        if m.group(2) in INT_TYPES:
+            consts.append(f"const {m.group(1)}: {m.group(2)} = {m.group(3).strip()};")
+    return consts
+
+
+def is_amm_math(content: str) -> bool:
+    """Heuristic: does this source contain AMM-style math helpers?"""
+    return any(MATH_NAME_RE.search(fn["name"]) for fn in VulnerabilityPattern._find_functions(content))
+
+
+def boundary_values(type_name: str, constants: dict) -> list[int]:
+    """Boundary inputs for a type: extremes, small values, and tick bounds."""
+    lo, hi = INT_TYPES[type_name]
+    values = {lo, lo + 1, hi, hi - 1, hi // 2, 0, 1, 2, 10, 1 << (hi.bit_length() // 2)}
+    if lo < 0:
+        values.update({-1, -2, lo // 2})
+    for name, value in constants.items():
+        if "TICK" in name or "PRICE" in name:
+            for v in (value - 1, value, value + 1):
+                if lo <= v <= hi:
+                    values.add(v)
+    return sorted(v for v in values if lo <= v <= hi)
+
+
+def replay_target(
+    path: str,
+    iterations: int = DEFAULT_ITERATIONS,
+    seed: int = 0x5EED,
+    amm_only: bool = True,
+) -> ReplayResult:
+    """Extract, compile, and replay pure math functions under a path."""
+    result = ReplayResult(target=path)
+    if not shutil.which("rustc"):
+        result.error = "rustc not found on PATH — native replay unavailable"
+        return result
+
+    sources = _collect_sources(path)
+    functions = []
+    consts = []
+    const_values = {}
+    for rel_path, content in sources.items():
+        if amm_only and not is_amm_math(content):
+            continue
+        functions.extend(extract_pure_functions(rel_path, content))
+        for c in extract_int_constants(content):
+            # Keep the first definition of each constant, like functions below
+            if _const_name(c) not in {_const_name(k) for k in consts}:
+                consts.append(c)
+        const_values.update(_evaluate_constants(content))
+
+    # Keep the first definition of each name; duplicated helpers won't link
+    seen = set()
+    unique = []
+    for fn in functions:
+        if fn.name in seen:
+            result.functions_skipped.append({"name": fn.name, "reason": "duplicate name"})
+            continue
+        seen.add(fn.name)
+        unique.append(fn)
+
+    if not unique:
+        return result
+
+    workdir = tempfile.mkdtemp(prefix="anchor-shield-replay-")
+    try:
+        compiled, skipped, binary = _compile_harness(
+            unique, consts, const_values, iterations, seed, workdir,
+        )
+        result.functions_skipped.extend(skipped)
+        result.functions_replayed = compiled
+        if binary is None:
+            return result
+        _run_harness(binary, compiled, result)
+    finally:
+        shutil.rmtree(workdir, ignore_errors=True)
+
+    return result
+
+
+def _parse_params(params: str) -> Optional[list]:
+    """Parse `a: u64, b: i32` into [(name, type)]; None if any param is not an integer."""
+    parsed = []
+    for part in [p.strip() for p in params.split(",") if p.strip()]:
+        m = re.match(r"^(?:mut\s+)?(\w+)\s*:\s*(\w+)$", part)
+        if not m or m.group(2) not in INT_TYPES:
+            return None
+        parsed.append((m.group(1), m.group(2)))
+    return parsed
+
+
+def _collect_sources(path: str) -> dict:
+    """Read .rs files under a path (or a single file) keyed by relative path."""
+    if os.path.isfile(path):
+        with open(path, "r", encoding="utf-8", errors="ignore") as fh:
+            return {os.path.basename(path): fh.read()}
+    sources = {}
+    for root, dirs, files in os.walk(path):
+        dirs[:] = [d for d in dirs if d not in ("target", "node_modules", ".git")]
+        for f in files:
+            if f.endswith(".rs"):
+                full = os.path.join(root, f)
+                try:
+                    with open(full, "r", encoding="utf-8", errors="ignore") as fh:
+                        sources[os.path.relpath(full, path)] = fh.read()
+                except OSError:
+                    continue
+    return sources
+
+
+def _evaluate_constants(content: str) -> dict:
+    """Best-effort numeric evaluation of simple integer constants (e.g. MIN_TICK)."""
+    values = {}
+    for m in re.finditer(
+        r"const\s+(\w+)\s*:\s*(\w+)\s*=\s*(-?[0-9_]+)\s*;", content,
+    ):
+        if m.group(2) in INT_TYPES:
+            values[m.group(1)] = int(m.group(3).replace("_", ""))
+    for m in re.finditer(r"const\s+(\w+)\s*:\s*\w+\s*=\s*-\s*(\w+)\s*;", content):
+        if m.group(2) in values:
+            values[m.group(1)] = -values[m.group(2)]
+    return values
+
+
+def _const_name(const: str) -> str:
+    return const.split(":", 1)[0].split()[-1]
+
+
+def _int_output(ret: str) -> Optional[str]:
+    """Pattern binding `out` to an integer return value, or None if there is none."""
+    if ret in INT_TYPES:
+        return "Ok(out)"
+    m = re.match(r"^Result\s*<\s*(\w+)\s*[,>]", ret)
+    if m and m.group(1) in INT_TYPES:
+        # An Err is a rejected input, not a value to compare
+        return "Ok(Ok(out))"
+    return None
+
+
+def _rust_literal(value: int, type_name: str) -> str:
+    if value < 0:
+        return f"({value}{type_name})"
+    return f"{value}{type_name}"
+
+
+def _render_harness(functions, consts, const_values, iterations, seed) -> tuple[str, dict]:
+    """Render main.rs and return (source, {line: function name or constant}) for error mapping."""
+    lines = [
+        "#![allow(dead_code, unused, clippy::all)]",
+        "use std::panic;",
+        "",
+    ]
+    lines.extend(HARNESS_PRELUDE.splitlines())
+    variants = sorted({v for fn in functions for v in ERROR_VARIANT_RE.findall(fn.source)})
+    if variants:
+        lines.append(f"#[derive(Debug)] enum ErrorCode {{ {', '.join(variants)} }}")
+        lines.append("impl From<ErrorCode> for ReplayError { fn from(_: ErrorCode) -> Self { ReplayError } }")
+    lines.append("")
+    owner = {}
+    for const in consts:
+        start = len(lines) + 1
+        lines.extend(const.split("\n"))
+        for ln in range(start, len(lines) + 1):
+            owner[ln] = const
+    lines.append("")
+    for fn in functions:
+        start = len(lines) + 1
+        lines.extend(fn.source.split("\n"))
+        for ln in range(start, len(lines) + 1):
+            owner[ln] = fn.name
+        lines.append("")
+
+    lines.append(f"static mut STATE: u64 = {seed};")
+    lines.append(
+        "fn next() -> u128 { unsafe { STATE ^= STATE << 13; STATE ^= STATE >> 7; "
+        "STATE ^= STATE << 17; ((STATE as u128) << 64) | (STATE.rotate_left(29) as u128) } }"
+    )
+    lines.append("fn main() {")
+    lines.append("    panic::set_hook(Box::new(|_| {}));")
+    for fn in functions:
+        start = len(lines) + 1
+        arg_names = [p[0] for p in fn.params]
+        args = ", ".join(arg_names)
+        fmt_args = ", ".join(f"{{:?}}" for _ in arg_names)
+        lines.append(f"    // replay {fn.name}")
+        lines.append("    {")
+        for name, ty in fn.params:
+            vals = ", ".join(_rust_literal(v, ty) for v in boundary_values(ty, const_values))
+            lines.append(f"        let mut v_{name}: Vec<{ty}> = vec![{vals}];")
+            lines.append(
+                f"        for _ in 0..{iterations} {{ v_{name}.push(next() as {ty}); }}"
+            )
+        # Boundary cross product is capped: pair each index across params
+        lines.append(f"        let n = v_{arg_names[0]}.len();")
+        lines.append("        for idx in 0..n {")
+        for k, (name, _) in enumerate(fn.params):
+            lines.append(f"            let {name} = v_{name}[(idx + {k} * 7) % v_{name}.len()];")
+        lines.append(f"            let r = panic::catch_unwind(|| {fn.name}({args}));")
+        lines.append("            match r {")
+        returned = _int_output(fn.ret) if len(fn.params) == 1 else None
+        if returned:
+            lines.append(
+                f"                {returned} => println!(\"OUT\\t{fn.name}\\t{{}}\\t{{}}\", {arg_names[0]}, out),"
+            )
+        if returned != "Ok(out)":
+            lines.append("                Ok(_) => {}")
+        lines.append("                Err(e) => {")
+        lines.append(
+            "                    let msg = e.downcast_ref::<&str>().map(|s| s.to_string())"
+            ".or_else(|| e.downcast_ref::<String>().cloned()).unwrap_or_default();"
+        )
+        lines.append(
+            f"                    println!(\"PANIC\\t{fn.name}\\t[{fmt_args}]\\t{{}}\", {args}, "
+            "msg.replace('\\t', \" \").replace('\\n', \" \"));"
+        )
+        lines.append("                }")
+        lines.append("            }")
+        lines.append("        }")
+        lines.append(f"        println!(\"CALLS\\t{fn.name}\\t{{}}\", n);")
+        lines.append("    }")
+        for ln in range(start, len(lines) + 1):
+            owner[ln] = fn.name
+    lines.append("}")
+    return "\n".join(lines) + "\n", owner
+
+
+def _compile_harness(functions, consts, const_values, iterations, seed, workdir):
+    """Compile the harness, dropping functions that fail to build standalone."""
+    remaining = list(functions)
+    skipped = []
+    main_rs = os.path.join(workdir, "main.rs")
+    binary = os.path.join(workdir, "replay")
+    while remaining:
+        source, owner = _render_harness(remaining, consts, const_values, iterations, seed)
+        with open(main_rs, "w") as fh:
+            fh.write(source)
+        proc = subprocess.run(
+            ["rustc", "--edition", "2021", "-C", "overflow-checks=on", "-C", "opt-level=1",
+             "--error-format=json", "-o", binary, main_rs],
+            capture_output=True, text=True, timeout=COMPILE_TIMEOUT,
+        )
+        if proc.returncode == 0:
+            return remaining, skipped, binary
+
+        failing = set()
+        for raw in proc.stderr.splitlines():
+            try:
+                diag = json.loads(raw)
+            except ValueError:
+                continue
+            if diag.get("level") != "error":
+                continue
+            for span in diag.get("spans", []):
+                name = owner.get(span.get("line_start"))
+                if name:
+                    failing.add(name)
+        bad_consts = failing.intersection(consts)
+        if bad_consts:
+            # A constant referencing crate types fails on its own line; drop just those
+            consts = [c for c in consts if c not in bad_consts]
+            continue
+        if not failing:
+            # Errors outside any function or constant — drop the constants once
+            if consts:
+                consts = []
+                continue
+            for fn in remaining:
+                skipped.append({"name": fn.name, "reason": "harness failed to compile"})
+            return [], skipped, None
+        for fn in remaining:
+            if fn.name in failing:
+                skipped.append({"name": fn.name, "reason": "does not compile standalone"})
+        remaining = [fn for fn in remaining if fn.name not in failing]
+    return [], skipped, None
+
+
+def _run_harness(binary: str, functions: list, result: ReplayResult) -> None:
+    """Execute the harness and translate its output into issues."""
+    by_name = {fn.name: fn for fn in functions}
+    try:
+        proc = subprocess.run(
+            [binary], capture_output=True, text=True, timeout=RUN_TIMEOUT,
+        )
+    except subprocess.TimeoutExpired:
+        result.error = f"replay harness exceeded {RUN_TIMEOUT}s"
+        return
+
+    panics = {}
+    outputs = {}
+    for line in proc.stdout.splitlines():
+        parts = line.split("\t")
+        if parts[0] == "PANIC" and len(parts) == 4:
+            name, inputs, message = parts[1], parts[2], parts[3]
+            panics.setdefault((name, _panic_kind(message)), (inputs, message))
+        elif parts[0] == "OUT" and len(parts) == 4:
+            outputs.setdefault(parts[1], []).append((int(parts[2]), int(parts[3])))
+        elif parts[0] == "CALLS" and len(parts) == 3:
+            result.calls_executed += int(parts[2])
+
+    for (name, kind), (inputs, message) in panics.items():
+        fn = by_name[name]
+        result.issues.append(ReplayIssue(
+            function=name,
+            kind=kind,
+            file=fn.file,
+            line=fn.line,
+            inputs=[s.strip() for s in inputs.strip("[]").split(",")],
+            message=message,
+        ))
+
+    for name, points in outputs.items():
+        violation = _monotonicity_violation(sorted(set(points)))
+        if violation:
+            fn = by_name[name]
+            (x1, y1), (x2, y2) = violation
+            result.issues.append(ReplayIssue(
+                function=name,
+                kind="monotonicity",
+                file=fn.file,
+                line=fn.line,
+                inputs=[str(x1), str(x2)],
+                message=f"f({x1}) = {y1} but f({x2}) = {y2} against the dominant trend",
+            ))
+
+
+def _panic_kind(message: str) -> str:
+    if "overflow" in message:
+        return "overflow"
+    if "divide by zero" in message or "remainder with a divisor of zero" in message:
+        return "division-by-zero"
+    return "panic"
+
+
+def _monotonicity_violation(points: list) -> Optional[tuple]:
+    """Return the first step against an otherwise dominant monotonic trend.
+
+    Functions that are monotone on at least MONOTONIC_THRESHOLD of adjacent
+    samples are assumed to be intended monotone (tick -> sqrt price, shares
+    from value); any step the other way is reported.
+    """
+    if len(points) < 4:
+        return None
+    ups = downs = 0
+    for (_, a), (_, b) in zip(points, points[1:]):
+        if b > a:
+            ups += 1
+        elif b < a:
+            downs += 1
+    moves = ups + downs
+    if moves == 0 or min(ups, downs) == 0:
+        return None
+    if max(ups, downs) / moves < MONOTONIC_THRESHOLD:
+        return None
+    increasing = ups > downs
+    for p, q in zip(points, points[1:]):
+        if (increasing and q[1] < p[1]) or (not increasing and q[1] > p[1]):
+            return p, q
+    return None
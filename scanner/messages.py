@@ -0,0 +1,117 @@
+"""
+Message catalog: localized and organization-specific report wording.
+
+A catalog file (TOML or JSON) overrides rule text and report labels without
+touching the rule definitions:
+
+    locale = "es"
+
+    [terms]                      # applied to every finding's prose
+    "oracle account" = "observation account"
+
+    [labels]                     # report headings
+    "Findings" = "Hallazgos"
+    "Fix" = "Corrección"
+
+    [rules.ANCHOR-006]           # per-rule overrides
+    name = "Falta verificación de propietario"
+    root_cause = "..."
+    description = "{original}"   # {original}, {file}, {line} are available
+
+Finding descriptions are generated per finding, so a rule's `description`
+override is a template; without one, only `[terms]` apply to it.
+"""
+
+import copy
+import json
+import re
+from dataclasses import dataclass, field
+
+try:
+    import tomllib
+except ImportError:  # Python < 3.11
+    import tomli as tomllib
+
+# Finding fields a [rules.<ID>] table may override
+RULE_FIELDS = ("name", "description", "root_cause", "exploit_scenario", "fix_recommendation")
+
+# Prose fields that [terms] substitutions are applied to
+PROSE_FIELDS = ("name", "description", "root_cause", "exploit_scenario", "fix_recommendation")
+
+
+@dataclass
+class MessageCatalog:
+    """Loaded translations/terminology for rendering reports."""
+
+    locale: str = "en"
+    terms: dict = field(default_factory=dict)
+    labels: dict = field(default_factory=dict)
+    rules: dict = field(default_factory=dict)
+
+    @classmethod
+    def load(cls, path: str) -> "MessageCatalog":
+        with open(path, "rb") as f:
+            raw = f.read()
+        if path.endswith(".json"):
+            data = json.loads(raw.decode("utf-8"))
+        else:
+            data = tomllib.loads(raw.decode("utf-8"))
+        rules = {}
+        for rule_id, overrides in data.get("rules", {}).items():
+            unknown = set(overrides) - set(RULE_FIELDS)
+            if unknown:
+                raise ValueError(
+                    f"rules.{rule_id}: unknown field(s) {', '.join(sorted(unknown))}; "
+                    f"expected {', '.join(RULE_FIELDS)}"
+                )
+            rules[rule_id.upper()] = overrides
+        return cls(
+            locale=data.get("locale", "en"),
+            terms=data.get("terms", {}),
+            labels=data.get("labels", {}),
+            rules=rules,
+        )
+
+    def label(self, text: str) -> str:
+        """Report heading/label in the catalog's wording."""
+        return self.labels.get(text, text)
+
+    def apply_terms(self, text: str) -> str:
+        """Replace configured terms, longest first, keeping leading capitalization."""
+        if not text or not self.terms:
+            return text
+        for term in sorted(self.terms, key=len, reverse=True):
+            replacement = self.terms[term]
+
+            def sub(m, replacement=replacement):
+                if m.group(0)[:1].isupper():
+                    return replacement[:1].upper() + replacement[1:]
+                return replacement
+
+            text = re.sub(rf"\b{re.escape(term)}\b", sub, text, flags=re.IGNORECASE)
+        return text
+
+    def localize_finding(self, finding) -> None:
+        """Apply rule overrides and terminology to a finding in place."""
+        overrides = self.rules.get(finding.id.upper(), {})
+        for name in RULE_FIELDS:
+            if name not in overrides:
+                continue
+            value = overrides[name]
+            if name == "description":
+                value = value.format(original=finding.description, file=finding.file,
+                                     line=finding.line)
+            setattr(finding, name, value)
+        for name in PROSE_FIELDS:
+            setattr(finding, name, self.apply_terms(getattr(finding, name)))
+        finding.ecosystem_recommendations = [
+            self.apply_terms(r) for r in finding.ecosystem_recommendations
+        ]
+
+
+def localize_report(report, catalog: MessageCatalog):
+    """Return a copy of the report with every finding rendered through catalog."""
+    localized = copy.deepcopy(report)
+    for finding in localized.findings:
+        catalog.localize_finding(finding)
+    return localized
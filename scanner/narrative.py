@@ -0,0 +1,162 @@
+"""
+Exploit narratives for critical findings.
+
+A finding says what is wrong at one line; turning that into an attack
+still means reading the handler. For Critical and High findings in an
+instruction where a caller-chosen argument reaches a signed transfer out of
+a program account (a resolved taint path, see Facts.taint_paths), the
+report spells the attack out from the same facts:
+
+    1. Call `withdraw(amount)` signing as `withdrawer`, with `user_account` set to an
+       account the attacker chooses (the flagged account, line 156); `vault` is the
+       PDA [b"vault", pool.key().as_ref()]
+    2. Choose `amount` so the only check on it passes:
+       require!(user.deposited >= amount, LendingError::InsufficientBalance) (line 72)
+    3. State changes: user_account.deposited decreases by amount (line 74);
+       pool.total_deposits decreases by amount (line 75)
+    4. `vault` transfers amount to `withdrawer` signed by the program (line 80)
+    5. The attacker gains up to user_account.deposited per call out of `vault`,
+       into `withdrawer`, its own account
+
+The instruction is the one whose body holds the flagged line or whose
+accounts struct does. Paths whose transfer is paid by the signer (a
+deposit) are not attacks and are not narrated. The narrative is a list of
+steps on `Finding.narrative`, exported as `narrative` in the JSON report
+and rendered under the finding's description.
+"""
+
+import re
+from typing import Optional
+
+from scanner.patterns.base import VulnerabilityPattern
+from scanner.patterns.facts import Facts
+
+# Findings that get a narrative; no rule reports Critical yet, so High is included
+NARRATED_SEVERITIES = ("Critical", "High")
+# Calls that move value out of an account
+VALUE_SINK = r"\b(?:transfer|transfer_checked|mint_to)\s*\(|\binvoke_signed\s*\("
+ALIAS_RE = re.compile(r"\blet\s+(?:mut\s+)?(\w+)\s*(?::[^=]+)?=\s*&?\s*(?:mut\s+)?ctx\s*\.\s*accounts\s*\.\s*(\w+)\s*;")
+CHECK_RE = re.compile(r"^\s*(?:(?:require\w*|assert\w*)!|if\b)")
+WRITE_RE = re.compile(r"^\s*([\w.]+)\s*([+\-*/]?=)(?!=)\s*(.+)$", re.DOTALL)
+ENDPOINT_RE = r"\b{}\s*:\s*(?:ctx\s*\.\s*accounts\s*\.\s*)?&?\s*(\w+)"
+OPERATIONS = {"-=": "decreases by", "+=": "increases by", "*=": "is multiplied by", "/=": "is divided by"}
+
+
+def narrate(findings: list, content: str) -> int:
+    """Set `narrative` on the critical findings of one unit; returns how many got one."""
+    candidates = [f for f in findings if f.severity in NARRATED_SEVERITIES and not f.narrative]
+    if not candidates:
+        return 0
+    facts = Facts(content)
+    narrated = 0
+    for finding in candidates:
+        steps = exploit_narrative(facts, content, finding.line)
+        if steps:
+            finding.narrative = steps
+            narrated += 1
+    return narrated
+
+
+def exploit_narrative(facts: Facts, content: str, line: int) -> Optional[list]:
+    """Steps of the attack through the instruction around `line`, None without a resolved taint path."""
+    for ix in _instructions_at(facts, content, line):
+        for arg in ix.args:
+            for path in facts.taint_paths(arg, VALUE_SINK, ix):
+                steps = _steps(facts, ix, arg, path, line)
+                if steps:
+                    return steps
+    return None
+
+
+def _instructions_at(facts: Facts, content: str, line: int) -> list:
+    """Instructions whose body or accounts struct holds `line`, entrypoints first."""
+    structs = {s.name: s for s in facts.accounts_structs()}
+    result = []
+    for ix in facts.instructions():
+        end = VulnerabilityPattern._get_line_number(content, ix.body_start + len(ix.body))
+        struct = structs.get(ix.accounts_struct)
+        in_struct = struct and (line == struct.line or any(a.line == line for a in struct.accounts))
+        if ix.line <= line <= end or in_struct:
+            result.append(ix)
+    return sorted(result, key=lambda ix: not ix.is_entrypoint)
+
+
+def _steps(facts: Facts, ix, arg: str, path, line: int) -> Optional[list]:
+    accounts = {a.name: a for a in facts.accounts_of(ix)}
+    aliases = {m.group(1): m.group(2) for m in ALIAS_RE.finditer(ix.body) if m.group(2) in accounts}
+    source = re.search(ENDPOINT_RE.format("from"), path.sink_text)
+    target = re.search(ENDPOINT_RE.format("to"), path.sink_text)
+    payer = source and aliases.get(source.group(1), source.group(1))
+    payee = target and aliases.get(target.group(1), target.group(1))
+    # The caller paying in (a deposit) is not an attack
+    if payer in accounts and accounts[payer].is_signer:
+        return None
+
+    def plain(text):
+        text = re.sub(r"\bctx\s*\.\s*accounts\s*\.\s*", "", " ".join(text.split()))
+        return re.sub(r"\b(\w+)(?=\s*\.)", lambda m: aliases.get(m.group(1), m.group(1)), text)
+
+    signers = [a.name for a in accounts.values() if a.is_signer]
+    call = f"Call `{ix.name}({', '.join(ix.args)})`"
+    if signers:
+        call += f" signing as `{signers[0]}`"
+    flagged = next((a for a in accounts.values() if a.line == line), None)
+    passing = []
+    if flagged:
+        passing.append(f"with `{flagged.name}` set to an account the attacker chooses "
+                       f"(the flagged account, line {line})")
+    if payer in accounts and accounts[payer].pda:
+        passing.append(f"`{payer}` is the PDA {accounts[payer].pda.seeds}")
+    steps = [call + (", " + "; ".join(passing) if passing else "")]
+
+    checks, writes = [], []
+    for step_line, text in path.steps[:-1]:
+        if CHECK_RE.match(text):
+            checks.append((step_line, text))
+            continue
+        m = WRITE_RE.match(text)
+        if m and "." in m.group(1):
+            change = OPERATIONS.get(m.group(2), "is set to")
+            writes.append(f"{plain(m.group(1))} {change} {plain(m.group(3))} (line {step_line})")
+    if checks:
+        listed = "; ".join(f"{_short(text)} (line {n})" for n, text in checks)
+        only = "the only check on it passes" if len(checks) == 1 else "its checks pass"
+        steps.append(f"Choose `{arg}` so {only}: {listed}")
+    else:
+        steps.append(f"Choose any `{arg}`: nothing in `{ix.name}` checks it before the transfer")
+    if writes:
+        steps.append("State changes: " + "; ".join(writes))
+
+    amount = arg if re.search(rf"\b{re.escape(arg)}\b", path.sink_text) else f"an amount derived from {arg}"
+    signed = " signed by the program" if re.search(r"\bnew_with_signer\b|\binvoke_signed\b", path.sink_text) else ""
+    if payer and payee:
+        steps.append(f"`{payer}` transfers {amount} to `{payee}`{signed} (line {path.sink_line})")
+    else:
+        steps.append(f"The program moves {amount}{signed}: {_short(path.sink_text)} (line {path.sink_line})")
+
+    bound = _bound(checks, arg, plain)
+    gain = f"up to {bound} per call" if bound else f"whatever {arg} the source holds"
+    if payee in accounts and accounts[payee].is_signer:
+        into = f"into `{payee}`, its own account"
+    elif payee:
+        into = f"into `{payee}`, an account the attacker passes"
+    else:
+        into = ""
+    steps.append(f"The attacker gains {gain}" + (f" out of `{payer}`" if payer else "") + (f", {into}" if into else ""))
+    return steps
+
+
+def _bound(checks: list, arg: str, plain) -> str:
+    """The expression an argument is capped by, from `x >= arg` or `arg <= x` checks."""
+    name = re.escape(arg)
+    for _, text in checks:
+        m = re.search(rf"\(\s*([\w.]+(?:\s*[+\-*/]\s*[\w.]+)*)\s*>=\s*{name}\b", text) or \
+            re.search(rf"\b{name}\s*<=\s*([\w.]+(?:\s*[+\-*/]\s*[\w.]+)*)", text)
+        if m:
+            return plain(m.group(1))
+    return ""
+
+
+def _short(text: str, limit: int = 100) -> str:
+    text = " ".join(text.split())
+    return text if len(text) <= limit else text[:limit - 3] + "..."
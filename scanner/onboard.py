@@ -0,0 +1,356 @@
+"""
+Project onboarding: a first configuration, baseline and plan for a workspace.
+
+The first scan of an unfamiliar program reports everything at once, from
+rules written for protocols it is not. onboard() turns that scan into a
+starting point:
+
+  1. Domain. The workspace's dependencies and its `#[account]` state are
+     matched against AMM, lending, staking and NFT signals: a known
+     protocol crate (3 points), a state type name (2 points per type) and
+     a state field name (1 point per field, at most 3). A domain with
+     DETECT_SCORE points or more is detected.
+  2. Rule packs. Every `shield::<category>::*` namespace is a pack. The
+     packs that only make sense for one domain (DOMAIN_PACKS) are skipped
+     when that domain has no signal at all; the packs the detected domains
+     depend on most are marked as focus.
+  3. Configuration. `anchor-shield.toml` is written at the target root with
+     the domains and packs as comments and the skipped packs under
+     `[packs] skip` (see config.py). An existing file is kept unless
+     `force` is given.
+  4. Baseline. The target is scanned with that configuration and every
+     finding is written to the baseline (see suppression.py), so later
+     scans report only new findings.
+  5. Plan. The baselined findings, grouped by rule, are ordered by
+     severity, then remediation effort, then count, and laid out over
+     WEEK_DAYS days of DAY_POINTS effort points each: a mechanical fix
+     costs 1 point, a localized one 2 and an architectural one 4, plus one
+     per five further findings of the rule. Low findings and what does not
+     fit the week are listed after it.
+
+    | Day | Rule       | Severity | Effort     | Findings | First fix                                   |
+    |-----|------------|----------|------------|----------|---------------------------------------------|
+    | 1   | ANCHOR-046 | High     | mechanical | 6        | Use checked arithmetic and surface overflow |
+    | 2   | ANCHOR-027 | High     | localized  | 3        | Derive the position from the pool and ...   |
+"""
+
+import json
+import os
+import re
+from dataclasses import dataclass, field
+from typing import Optional
+
+from scanner.config import config_path
+from scanner.dependencies import declared_dependencies
+from scanner.engine import AnchorShieldEngine
+from scanner.patterns.layout import Layout
+from scanner.patterns.lending import positions
+from scanner.suppression import write_baseline
+
+
+@dataclass(frozen=True)
+class DomainSignals:
+    # Substrings of dependency names
+    crates: tuple
+    # Regexes matched against #[account] type names and their field names
+    states: str
+    fields: str
+
+
+DOMAINS = {
+    "amm": DomainSignals(
+        crates=("raydium", "whirlpool", "orca", "spl-token-swap", "openbook", "serum", "phoenix", "meteora"),
+        states=r"(?i)pool|amm|swap|tick|observation",
+        fields=r"(?i)sqrt_price|liquidity|tick|fee_growth|swap_fee|protocol_fee|amm_config|token_(?:mint|vault)_[01ab]\b",
+    ),
+    "lending": DomainSignals(
+        crates=("solend", "kamino", "klend", "marginfi", "port-variable", "jet-", "mango"),
+        states=r"(?i)obligation|lending|loan|borrow|reserve|margin",
+        fields=r"(?i)collateral|debt|borrow|interest|ltv|liquidat|health",
+    ),
+    "staking": DomainSignals(
+        crates=("stake-pool", "marinade", "jito", "sanctum", "stake"),
+        states=r"(?i)stak(?:e|ing)|validator|reward|vest|lockup|farm|unbond",
+        fields=r"(?i)stak(?:e|ed|ing)|reward|delegat|unbond|cooldown|lockup|vesting|validator",
+    ),
+    "nft": DomainSignals(
+        crates=("mpl-token-metadata", "mpl-core", "mpl-bubblegum", "mpl-candy", "mpl-token-auth"),
+        states=r"(?i)nft|collection|edition|listing|candy|auction",
+        fields=r"(?i)nft|collection|metadata|edition|creator|royalt|^uri$",
+    ),
+}
+CRATE_POINTS, STATE_POINTS, FIELD_POINTS, MAX_FIELD_POINTS = 3, 2, 1, 3
+DETECT_SCORE = 3
+# Names quoted per kind of evidence
+EVIDENCE_SHOWN = 4
+
+# Packs written for one domain; skipped when the domain has no signal
+DOMAIN_PACKS = {"lending": "lending", "nft": "nft"}
+# Packs each domain leans on most
+FOCUS_PACKS = {
+    "amm": ("arithmetic", "economics"),
+    "lending": ("lending", "arithmetic"),
+    "staking": ("arithmetic", "economics", "lifecycle"),
+    "nft": ("nft", "tokens"),
+}
+
+SEVERITY_ORDER = ("Critical", "High", "Medium", "Low")
+EFFORT_POINTS = {"mechanical": 1, "localized": 2, "architectural": 4}
+DAY_POINTS = 4
+WEEK_DAYS = 5
+
+
+@dataclass
+class Domain:
+    name: str
+    score: int = 0
+    crates: list = field(default_factory=list)
+    states: list = field(default_factory=list)
+    fields: list = field(default_factory=list)
+
+    @property
+    def detected(self) -> bool:
+        return self.score >= DETECT_SCORE
+
+    def evidence(self) -> str:
+        parts = []
+        for names, kind in ((self.crates, "crate"), (self.states, "state"), (self.fields, "field")):
+            if names:
+                more = f" and {len(names) - EVIDENCE_SHOWN} more" if len(names) > EVIDENCE_SHOWN else ""
+                parts.append(f"{', '.join(names[:EVIDENCE_SHOWN])}{more} {kind}{'s' if len(names) > 1 else ''}")
+        return "; ".join(parts)
+
+    def to_dict(self) -> dict:
+        return {
+            "name": self.name, "score": self.score, "detected": self.detected,
+            "crates": self.crates, "states": self.states, "fields": self.fields,
+        }
+
+
+@dataclass
+class Pack:
+    selector: str
+    rules: list = field(default_factory=list)
+    # enabled | focus | skipped
+    status: str = "enabled"
+    reason: str = ""
+
+    def to_dict(self) -> dict:
+        return {"selector": self.selector, "rules": self.rules, "status": self.status, "reason": self.reason}
+
+
+@dataclass
+class PlanItem:
+    rule: str
+    name: str
+    severity: str
+    effort: str
+    findings: list = field(default_factory=list)
+    fix: str = ""
+    # 1..WEEK_DAYS, 0 once the week is full or for Low findings
+    day: int = 0
+
+    @property
+    def points(self) -> int:
+        return min(DAY_POINTS, EFFORT_POINTS.get(self.effort, 2) + (len(self.findings) - 1) // 5)
+
+    def to_dict(self) -> dict:
+        return {
+            "day": self.day, "rule": self.rule, "name": self.name, "severity": self.severity,
+            "effort": self.effort, "points": self.points, "findings": self.findings, "fix": self.fix,
+        }
+
+
+@dataclass
+class OnboardingReport:
+    target: str
+    domains: list = field(default_factory=list)
+    packs: list = field(default_factory=list)
+    config: str = ""
+    config_written: bool = False
+    baseline: str = ""
+    baselined: Optional[int] = None
+    plan: list = field(default_factory=list)
+
+    @property
+    def detected(self) -> list:
+        return [d for d in self.domains if d.detected]
+
+    @property
+    def week(self) -> list:
+        return [item for item in self.plan if item.day]
+
+    @property
+    def later(self) -> list:
+        return [item for item in self.plan if not item.day]
+
+    def to_dict(self) -> dict:
+        return {
+            "target": self.target,
+            "domains": [d.to_dict() for d in self.domains],
+            "packs": [p.to_dict() for p in self.packs],
+            "config": self.config,
+            "config_written": self.config_written,
+            "baseline": self.baseline,
+            "baselined": self.baselined,
+            "plan": [item.to_dict() for item in self.plan],
+        }
+
+    def to_json(self, indent: int = 2) -> str:
+        return json.dumps(self.to_dict(), indent=indent)
+
+
+def detect_domains(sources: list, dependencies: list) -> list[Domain]:
+    """Score every domain on (path, content) units and dependency names, highest first."""
+    states = {}
+    # Structs lending.py reads as positions (collateral and debt side by side) count as lending state
+    shaped = set()
+    for _, content in sources:
+        for struct in Layout(content).structs.values():
+            if struct.account:
+                states.setdefault(struct.name, [f.name for f in struct.fields])
+        shaped |= {p.struct for p in positions(content)}
+    names = sorted({d.lower() for d in dependencies})
+    domains = []
+    for name, signals in DOMAINS.items():
+        domain = Domain(name)
+        domain.crates = [d for d in names if any(c in d for c in signals.crates)]
+        domain.states = sorted(s for s in states
+                               if re.search(signals.states, s) or (name == "lending" and s in shaped))
+        domain.fields = sorted({f for fields in states.values() for f in fields if re.search(signals.fields, f)})
+        domain.score = (CRATE_POINTS * bool(domain.crates) + STATE_POINTS * len(domain.states)
+                        + min(MAX_FIELD_POINTS, FIELD_POINTS * len(domain.fields)))
+        domains.append(domain)
+    domains.sort(key=lambda d: (-d.score, d.name))
+    return domains
+
+
+def propose_packs(domains: list, patterns: list) -> list[Pack]:
+    """One pack per rule category, with its status for the given domains."""
+    by_name = {d.name: d for d in domains}
+    focus = {pack: d.name for d in domains if d.detected for pack in FOCUS_PACKS.get(d.name, ())}
+    packs = []
+    for category in sorted({p.category for p in patterns}):
+        pack = Pack(f"shield::{category}::*", sorted(p.id for p in patterns if p.category == category))
+        owner = next((d for d, c in DOMAIN_PACKS.items() if c == category), None)
+        if owner and not by_name.get(owner, Domain(owner)).score:
+            pack.status, pack.reason = "skipped", f"no {owner} crate, state or field"
+        elif category in focus:
+            pack.status, pack.reason = "focus", f"{focus[category]} program"
+        packs.append(pack)
+    return packs
+
+
+def render_config(domains: list, packs: list) -> str:
+    """anchor-shield.toml text for the proposed packs."""
+    detected = [d for d in domains if d.detected]
+    lines = ["# Generated by `anchor-shield-v2 onboard`; edit freely."]
+    if detected:
+        lines.append("# Domains:")
+        lines += [f"#   {d.name}: {d.evidence()}" for d in detected]
+    else:
+        lines.append("# Domains: none detected")
+    focus = [p.selector for p in packs if p.status == "focus"]
+    if focus:
+        lines.append(f"# Focus packs: {', '.join(focus)}")
+    skipped = [p for p in packs if p.status == "skipped"]
+    lines += ["", "[packs]"]
+    lines += [f"# {p.selector}: {p.reason}" for p in skipped]
+    lines.append("skip = [" + ", ".join(json.dumps(p.selector) for p in skipped) + "]")
+    lines.append("")
+    return "\n".join(lines)
+
+
+def plan_remediation(findings: list) -> list[PlanItem]:
+    """Findings grouped by rule, ordered and laid out over the first week."""
+    items = {}
+    for f in findings:
+        item = items.setdefault(f.id, PlanItem(f.id, f.name, f.severity, f.remediation_effort or "localized",
+                                               fix=f.fix_recommendation.split("\n")[0].rstrip(":")))
+        if _rank(f.severity) < _rank(item.severity):
+            item.severity = f.severity
+        if EFFORT_POINTS.get(f.remediation_effort, 0) > EFFORT_POINTS.get(item.effort, 0):
+            item.effort = f.remediation_effort
+        item.findings.append(f"{f.file}:{f.line}")
+    plan = sorted(items.values(), key=lambda i: (_rank(i.severity), EFFORT_POINTS.get(i.effort, 2),
+                                                 -len(i.findings), i.rule))
+    day, used = 1, 0
+    for item in plan:
+        if item.severity == "Low" or day > WEEK_DAYS:
+            continue
+        if used + item.points > DAY_POINTS:
+            day, used = day + 1, 0
+            if day > WEEK_DAYS:
+                continue
+        item.day = day
+        used += item.points
+    plan.sort(key=lambda i: i.day or WEEK_DAYS + 1)
+    return plan
+
+
+def onboard(target: str, baseline: str, force: bool = False,
+            engine: Optional[AnchorShieldEngine] = None) -> OnboardingReport:
+    """Detect the target's domains, write its configuration and baseline, and plan its first week."""
+    engine = engine or AnchorShieldEngine()
+    path = os.path.abspath(target)
+    domains = detect_domains(engine.load_sources(path), [d.name for d in declared_dependencies(path)])
+    packs = propose_packs(domains, engine.patterns)
+    result = OnboardingReport(target=path, domains=domains, packs=packs, config=config_path(path),
+                              baseline=baseline)
+    if force or not os.path.isfile(result.config):
+        with open(result.config, "w") as fh:
+            fh.write(render_config(domains, packs))
+        result.config_written = True
+    scan = engine.scan_directory(path)
+    if force or not os.path.isfile(baseline):
+        write_baseline(scan.findings, baseline, reason="onboarding", ruleset=engine.ruleset)
+        result.baselined = len(scan.findings)
+    result.plan = plan_remediation(scan.findings)
+    return result
+
+
+def render_markdown(report: OnboardingReport) -> str:
+    lines = [
+        "# Onboarding",
+        "",
+        f"Target: {report.target}  ",
+        "Domains: " + (", ".join(f"{d.name} ({d.evidence()})" for d in report.detected) or "none detected"),
+        "",
+        "## Rule Packs",
+        "",
+        "| Pack | Rules | Status |",
+        "|------|-------|--------|",
+    ]
+    for p in report.packs:
+        status = f"{p.status} ({p.reason})" if p.reason else p.status
+        lines.append(f"| `{p.selector}` | {len(p.rules)} | {status} |")
+    config = "written" if report.config_written else "kept (exists; pass --force to overwrite)"
+    baselined = (f"{report.baselined} findings" if report.baselined is not None
+                 else "kept (exists; pass --force to overwrite)")
+    lines += [
+        "",
+        f"Configuration: {report.config} — {config}  ",
+        f"Baseline: {report.baseline} — {baselined}",
+        "",
+        "## First Week",
+        "",
+    ]
+    if not report.week:
+        lines.append("Nothing to fix this week.")
+    else:
+        lines += [
+            "| Day | Rule | Severity | Effort | Findings | First fix |",
+            "|-----|------|----------|--------|----------|-----------|",
+        ]
+        for item in report.week:
+            lines.append(f"| {item.day} | {item.rule} {item.name} | {item.severity} | {item.effort} | "
+                         f"{len(item.findings)} ({item.findings[0]}) | {item.fix} |")
+    if report.later:
+        lines += ["", "After the first week: " + ", ".join(
+            f"{item.rule} ({item.severity}, {len(item.findings)})" for item in report.later)]
+    lines.append("")
+    return "\n".join(lines)
+
+
+def _rank(severity: str) -> int:
+    return SEVERITY_ORDER.index(severity) if severity in SEVERITY_ORDER else len(SEVERITY_ORDER)
@@ -7,6 +7,63 @@ from scanner.patterns.realloc_payer import ReallocPayerPattern
 from scanner.patterns.type_cosplay import TypeCosplayPattern
 from scanner.patterns.close_reinit import CloseReinitPattern
 from scanner.patterns.missing_owner import MissingOwnerPattern
+from scanner.patterns.cpi_toctou import CpiToctouPattern
+from scanner.patterns.stale_constraint_ref import StaleConstraintRefPattern
+from scanner.patterns.crank_idempotency import CrankIdempotencyPattern
+from scanner.patterns.anchor_toml import AnchorTomlPattern
+from scanner.patterns.adhoc_percentage import AdHocPercentagePattern
+from scanner.patterns.unit_confusion import UnitConfusionPattern
+from scanner.patterns.ownership_transition import OwnershipTransitionPattern
+from scanner.patterns.sequencing_flag import SequencingFlagPattern
+from scanner.patterns.doc_constraint import DocConstraintPattern
+from scanner.patterns.privileged_signer import PrivilegedSignerPattern
+from scanner.patterns.vault_creation import VaultCreationPattern
+from scanner.patterns.time_units import TimeUnitPattern
+from scanner.patterns.account_aliasing import AccountAliasingPattern
+from scanner.patterns.constraint_anomaly import ConstraintAnomalyPattern
+from scanner.patterns.sysvar_spoofing import SysvarSpoofingPattern
+from scanner.patterns.state_binding import StateBindingPattern
+from scanner.patterns.state_reinit import StateReinitPattern
+from scanner.patterns.economic_params import EconomicParamsPattern
+from scanner.patterns.close_destination import CloseDestinationPattern
+from scanner.patterns.constant_drift import ConstantDriftPattern
+from scanner.patterns.position_binding import PositionBindingPattern
+from scanner.patterns.packed_zero_copy import PackedZeroCopyPattern
+from scanner.patterns.account_space import AccountSpacePattern
+from scanner.patterns.access_control import AccessControlPattern
+from scanner.patterns.pda_collision import PdaSeedCollisionPattern
+from scanner.patterns.signer_seeds import SignerSeedsPattern
+from scanner.patterns.reward_groups import RewardGroupPattern
+from scanner.patterns.authority_init import AuthorityInitPattern
+from scanner.patterns.token_constraints import TokenConstraintsPattern
+from scanner.patterns.nft_mint import NftMintPattern
+from scanner.patterns.nft_metadata import NftMetadataPattern
+from scanner.patterns.nft_custody import NftCustodyPattern
+from scanner.patterns.mint_screening import MintScreeningPattern
+from scanner.patterns.constraint_logic import ConstraintLogicPattern
+from scanner.patterns.token_extensions import TokenExtensionsPattern
+from scanner.patterns.client_enforcement import ClientEnforcementPattern
+from scanner.patterns.token_program_mix import TokenProgramMixPattern
+from scanner.patterns.check_comment import CheckCommentPattern
+from scanner.patterns.fixed_point import FixedPointPattern
+from scanner.patterns.unchecked_arithmetic import UncheckedArithmeticPattern
+from scanner.patterns.zero_division import ZeroDivisionPattern
+from scanner.patterns.amount_caps import AmountCapsPattern
+from scanner.patterns.mul_chain import MulChainPattern
+from scanner.patterns.operation_order import OperationOrderPattern
+from scanner.patterns.truncating_cast import TruncatingCastPattern
+from scanner.patterns.rounding_direction import RoundingDirectionPattern
+from scanner.patterns.has_one_binding import HasOneBindingPattern
+from scanner.patterns.saturating_sub import SaturatingSubPattern
+from scanner.patterns.wrapping_arithmetic import WrappingArithmeticPattern
+from scanner.patterns.handler_panic import HandlerPanicPattern
+from scanner.patterns.checked_unwrap import CheckedUnwrapPattern
+from scanner.patterns.loop_accumulator import LoopAccumulatorPattern
+from scanner.patterns.signed_time import SignedTimeCastPattern
+from scanner.patterns.lending_borrow import BorrowCollateralCheckPattern
+from scanner.patterns.lending_withdraw import WithdrawDebtCheckPattern
+from scanner.patterns.lending_health import HealthFormulaPattern
+from scanner.patterns.lending_liquidation import LiquidationIncompletePattern
 
 ALL_PATTERNS = [
     InitIfNeededPattern,
@@ -15,6 +72,63 @@ ALL_PATTERNS = [
     TypeCosplayPattern,
     CloseReinitPattern,
     MissingOwnerPattern,
+    CpiToctouPattern,
+    StaleConstraintRefPattern,
+    CrankIdempotencyPattern,
+    AnchorTomlPattern,
+    AdHocPercentagePattern,
+    UnitConfusionPattern,
+    OwnershipTransitionPattern,
+    SequencingFlagPattern,
+    DocConstraintPattern,
+    PrivilegedSignerPattern,
+    VaultCreationPattern,
+    TimeUnitPattern,
+    AccountAliasingPattern,
+    ConstraintAnomalyPattern,
+    SysvarSpoofingPattern,
+    StateBindingPattern,
+    StateReinitPattern,
+    EconomicParamsPattern,
+    CloseDestinationPattern,
+    ConstantDriftPattern,
+    PositionBindingPattern,
+    PackedZeroCopyPattern,
+    AccountSpacePattern,
+    AccessControlPattern,
+    PdaSeedCollisionPattern,
+    SignerSeedsPattern,
+    RewardGroupPattern,
+    AuthorityInitPattern,
+    TokenConstraintsPattern,
+    NftMintPattern,
+    NftMetadataPattern,
+    NftCustodyPattern,
+    MintScreeningPattern,
+    ConstraintLogicPattern,
+    TokenExtensionsPattern,
+    ClientEnforcementPattern,
+    TokenProgramMixPattern,
+    CheckCommentPattern,
+    FixedPointPattern,
+    UncheckedArithmeticPattern,
+    ZeroDivisionPattern,
+    AmountCapsPattern,
+    MulChainPattern,
+    OperationOrderPattern,
+    TruncatingCastPattern,
+    RoundingDirectionPattern,
+    HasOneBindingPattern,
+    SaturatingSubPattern,
+    WrappingArithmeticPattern,
+    HandlerPanicPattern,
+    CheckedUnwrapPattern,
+    LoopAccumulatorPattern,
+    SignedTimeCastPattern,
+    BorrowCollateralCheckPattern,
+    WithdrawDebtCheckPattern,
+    HealthFormulaPattern,
+    LiquidationIncompletePattern,
 ]
 
 __all__ = [
@@ -27,4 +141,61 @@ __all__ = [
     "TypeCosplayPattern",
     "CloseReinitPattern",
     "MissingOwnerPattern",
+    "CpiToctouPattern",
+    "StaleConstraintRefPattern",
+    "CrankIdempotencyPattern",
+    "AnchorTomlPattern",
+    "AdHocPercentagePattern",
+    "UnitConfusionPattern",
+    "OwnershipTransitionPattern",
+    "SequencingFlagPattern",
+    "DocConstraintPattern",
+    "PrivilegedSignerPattern",
+    "VaultCreationPattern",
+    "TimeUnitPattern",
+    "AccountAliasingPattern",
+    "ConstraintAnomalyPattern",
+    "SysvarSpoofingPattern",
+    "StateBindingPattern",
+    "StateReinitPattern",
+    "EconomicParamsPattern",
+    "CloseDestinationPattern",
+    "ConstantDriftPattern",
+    "PositionBindingPattern",
+    "PackedZeroCopyPattern",
+    "AccountSpacePattern",
+    "AccessControlPattern",
+    "PdaSeedCollisionPattern",
+    "SignerSeedsPattern",
+    "RewardGroupPattern",
+    "AuthorityInitPattern",
+    "TokenConstraintsPattern",
+    "NftMintPattern",
+    "NftMetadataPattern",
+    "NftCustodyPattern",
+    "MintScreeningPattern",
+    "ConstraintLogicPattern",
+    "TokenExtensionsPattern",
+    "ClientEnforcementPattern",
+    "TokenProgramMixPattern",
+    "CheckCommentPattern",
+    "FixedPointPattern",
+    "UncheckedArithmeticPattern",
+    "ZeroDivisionPattern",
+    "AmountCapsPattern",
+    "MulChainPattern",
+    "OperationOrderPattern",
+    "TruncatingCastPattern",
+    "RoundingDirectionPattern",
+    "HasOneBindingPattern",
+    "SaturatingSubPattern",
+    "WrappingArithmeticPattern",
+    "HandlerPanicPattern",
+    "CheckedUnwrapPattern",
+    "LoopAccumulatorPattern",
+    "SignedTimeCastPattern",
+    "BorrowCollateralCheckPattern",
+    "WithdrawDebtCheckPattern",
+    "HealthFormulaPattern",
+    "LiquidationIncompletePattern",
 ]
@@ -0,0 +1,261 @@
+"""
+ANCHOR-030: Bypassable Access Control
+
+Anchor runs the functions named in `#[access_control(...)]` before the
+handler and aborts the instruction when one returns an error. The attribute
+only gates the handler as well as the guard checks something the caller
+cannot choose:
+
+    #[access_control(only_admin(&ctx))]
+    pub fn set_fee(ctx: Context<SetFee>, fee_bps: u16) -> Result<()> {
+        ctx.accounts.config.fee_bps = fee_bps;
+        Ok(())
+    }
+
+    fn only_admin(ctx: &Context<SetFee>) -> Result<()> {
+        msg!("admin action");                       // never fails
+        Ok(())
+    }
+
+The rule maps every `#[access_control]` attribute to the guard functions it
+calls, resolved by name in the unit, and classifies each guard:
+
+  - binding: it can fail, and what it checks is account state or the
+    signer — an argument built from `ctx` or `self`, `is_signer`, a key
+    comparison (`not_burned(&ctx.accounts.check)`, ido-pool's phase checks)
+  - arguments only: every argument comes from the instruction data
+    (`validate_ido_times(ido_times)`); input validation, not access control
+  - never fails: no error return, no guard macro, no failing call
+
+It reports:
+
+  - a guard that never fails, on every handler it is attached to
+  - an instruction that writes admin-controlled state (authority, fee,
+    pause fields — see ANCHOR-016) while taking a signer that nothing binds
+    to the state: no binding guard, no has_one / constraint / seeds /
+    address naming the signer, and no key comparison or `is_signer` check
+    in the handler or the helpers it calls (up to --interprocedural-depth)
+
+Instructions that take no signer at all, or pass the stored authority as
+an unsigned account, are ANCHOR-016's and are not reported again here.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+from scanner.patterns.guards import ERROR_EXIT_RE
+from scanner.patterns.privileged_signer import AUTHORITY_FIELD, PrivilegedSignerPattern
+from scanner.patterns.validation import CALL_RE, _balanced, _param_names, _split_top
+
+ACCESS_RE = re.compile(
+    r"#\[access_control\((.*)\)\]\s*(?:#\[[^\]]*\]\s*)*(?:pub(?:\([^)]*\))?\s+)?fn\s+(\w+)"
+)
+ACCOUNTS_TYPE_RE = re.compile(r"'info\b|\b(?:Context|AccountInfo|Account|Signer)\b")
+GUARD_CALL_RE = re.compile(r"\s*((?:\w+\s*::\s*)*)(\w+)\s*\(")
+KEY_TERM_RE = re.compile(rf"\bkey\b|\b{AUTHORITY_FIELD}\b")
+
+BINDING, ARGUMENTS_ONLY, NEVER_FAILS = "binding", "arguments-only", "never-fails"
+
+
+class AccessControlPattern(VulnerabilityPattern):
+    id = "ANCHOR-030"
+    name = "Bypassable Access Control"
+    severity = "High"
+    category = "accounts"
+    remediation_effort = "localized"
+    description = (
+        "An #[access_control] guard never fails, or an instruction writes "
+        "admin-controlled state with a signer that no guard, constraint or "
+        "inline check binds to the stored authority."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        facts = Facts(content)
+        functions = self._find_functions(content)
+        impls = self._find_impl_blocks(content)
+        guards = self._guards()
+        # Guarded function name -> [(guard call, kind, attribute line)]
+        attached = {}
+        for m in ACCESS_RE.finditer(content):
+            line = self._get_line_number(content, m.start())
+            # Parameters of the guarded function that carry accounts rather than instruction data
+            guarded = next((fn for fn in functions if fn["body_start"] > m.end()), None)
+            accounts = {p for p, t in _param_names(guarded["params"] if guarded else "")
+                        if ACCOUNTS_TYPE_RE.search(t)}
+            for call in _split_top(m.group(1)):
+                g = GUARD_CALL_RE.match(call)
+                if not g:
+                    continue
+                guard = self._resolve(g.group(2), g.group(1), functions, impls)
+                args = _split_top(_balanced(call, g.end() - 1))
+                kind = self._classify(guard, args, accounts, functions, guards) if guard else BINDING
+                attached.setdefault(m.group(2), []).append((" ".join(call.split()), kind, line))
+
+        for name, entries in attached.items():
+            for call, kind, line in entries:
+                if kind == NEVER_FAILS:
+                    findings.append(self._finding(
+                        file_path, content, line, "Medium",
+                        f"The #[access_control] guard `{call}` on '{name}' never returns an error: "
+                        f"it has no error return, guard macro or failing call, so the handler runs "
+                        f"for any caller as if it were unguarded",
+                    ))
+
+        privileged = PrivilegedSignerPattern()
+        authorities = privileged._state_authorities(content)
+        structs = {s.name: s for s in facts.accounts_structs()}
+        gated = {ix.accounts_struct for ix in facts.instructions()
+                 if any(kind == BINDING for _, kind, _ in attached.get(ix.name, []))}
+        reported = set()
+        for ix in facts.instructions():
+            if ix.accounts_struct in gated or ix.accounts_struct in reported:
+                continue
+            accounts = {a.name: a for a in facts.accounts_of(ix)}
+            signers = [a.name for a in accounts.values() if a.is_signer]
+            if not signers or any(a.wrapper in structs
+                                  for a in accounts.values()):
+                continue
+            for target, fields, offset in privileged._admin_writes(ix, accounts):
+                account = accounts[target]
+                if account.has("init") or account.has("zero"):
+                    continue
+                if privileged._missing_signer(account, accounts, authorities):
+                    continue
+                if self._bound(signers, accounts) or self._checks_inline(ix.body, functions, guards,
+                                                                          self.max_depth, {ix.name}):
+                    break
+                line = self._get_line_number(content, ix.body_start + offset)
+                weak = [f"`{call}` ({kind})" for call, kind, _ in attached.get(ix.name, [])]
+                gate = (f"its #[access_control] guard {', '.join(weak)} checks nothing about the caller"
+                        if weak else "it has no #[access_control] guard")
+                findings.append(self._finding(
+                    file_path, content, line, self.severity,
+                    f"Instruction '{ix.name}' writes {', '.join(fields)} on '{account.name}', but {gate}, "
+                    f"and its signer {', '.join(signers)} is not tied to the stored authority by a "
+                    f"constraint or an inline check: any wallet can sign",
+                ))
+                reported.add(ix.accounts_struct)
+                break
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    @staticmethod
+    def _resolve(name: str, path: str, functions: list, impls: list):
+        """The guard's function: in `impl Type` for `Type::name`, else the first of that name."""
+        candidates = [fn for fn in functions if fn["name"] == name]
+        owner = path.rstrip(": ").split("::")[-1].strip() if path else ""
+        for fn in candidates:
+            if owner and any(s <= fn["body_start"] < e and t == owner for s, e, t in impls):
+                return fn
+        return candidates[0] if candidates else None
+
+    def _classify(self, guard: dict, args: list, accounts: set, functions: list, guards: str) -> str:
+        if not self._can_fail(guard["body"], functions, guards, self.max_depth, {guard["name"]}):
+            return NEVER_FAILS
+        body = guard["body"]
+        state = [p for (p, _), arg in zip(_param_names(guard["params"]), args)
+                 if re.search(r"\b(?:ctx|self)\b", arg) or set(re.findall(r"\w+", arg)) & accounts]
+        if re.search(r"\bself\b", guard["params"]) or re.search(r"\bis_signer\b|\bctx\s*\.\s*accounts\b", body):
+            return BINDING
+        if any(re.search(rf"\b{re.escape(p)}\b", body) for p in state):
+            return BINDING
+        return ARGUMENTS_ONLY
+
+    def _can_fail(self, body: str, functions: list, guards: str, depth: int, seen: set) -> bool:
+        """An error return, a guard macro, or `?` on a local call that can fail itself."""
+        if ERROR_EXIT_RE.search(body) or re.search(rf"\b{guards}!\s*\(", body):
+            return True
+        if depth <= 1:
+            return False
+        for m in CALL_RE.finditer(body):
+            fn = next((f for f in functions if f["name"] == m.group(1) and f["name"] not in seen), None)
+            if fn and self._can_fail(fn["body"], functions, guards, depth - 1, seen | {fn["name"]}):
+                return True
+        return False
+
+    @staticmethod
+    def _bound(signers: list, accounts: dict) -> bool:
+        """A constraint names a signer (has_one, seeds, constraint, address), or pins the signer
+        itself (a PDA signer, an address or constraint on it)."""
+        for account in accounts.values():
+            for c in account.constraints:
+                if account.name in signers and c.kind in ("address", "constraint", "seeds"):
+                    return True
+                if any(re.search(rf"\b{re.escape(s)}\b", c.value) for s in signers):
+                    return True
+        return False
+
+    def _checks_inline(self, body: str, functions: list, guards: str, depth: int, seen: set) -> bool:
+        """`is_signer`, require_keys_*!, or a failing key / authority comparison, here or in a helper."""
+        if re.search(r"\.\s*is_signer\b|\brequire_keys_(?:eq|neq)!", body):
+            return True
+        fails = ERROR_EXIT_RE.search(body) or re.search(rf"\b{guards}!\s*\(", body)
+        if fails and any(re.search(r"==|!=", stmt) and KEY_TERM_RE.search(stmt)
+                         for stmt in re.findall(r"[^;{}]+", body)):
+            return True
+        if depth <= 1:
+            return False
+        for m in CALL_RE.finditer(body):
+            fn = next((f for f in functions if f["name"] == m.group(1) and f["name"] not in seen), None)
+            if fn and self._checks_inline(fn["body"], functions, guards, depth - 1, seen | {fn["name"]}):
+                return True
+        return False
+
+    def _finding(self, file_path, content, line, severity, message) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=severity,
+            file=file_path,
+            line=line,
+            description=f"{message}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "config.fee_bps set by the protocol admin",
+                "after": "config.fee_bps set by whoever signs the transaction",
+                "damage": "Attacker takes over admin control: fees, pause state or the authority itself.",
+            },
+            impact={
+                "attack_cost": "Transaction fees only",
+                "exploitability": "High — the guard or signer check passes for any wallet",
+                "breach_cost_context": "Guards that check the wrong thing pass review because the attribute is there.",
+            },
+            anchor_versions_affected="All versions (developer-side pattern)",
+            ecosystem_recommendations=[
+                "Bind the signer to the stored authority with has_one or an address constraint",
+                "Keep #[access_control] for checks on accounts; validate instruction data in the handler",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Make the guard compare the signer with the stored authority, or bind it in the accounts struct:\n"
+            "  fn only_admin(ctx: &Context<SetFee>) -> Result<()> {\n"
+            "      require_keys_eq!(ctx.accounts.admin.key(), ctx.accounts.config.admin, ErrorCode::Unauthorized);\n"
+            "      Ok(())\n"
+            "  }\n"
+            "  #[account(mut, has_one = admin)]\n"
+            "  pub config: Account<'info, Config>,\n"
+            "  pub admin: Signer<'info>,"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "#[access_control] only aborts the instruction when a guard returns "
+            "an error, and Signer<'info> only proves that some wallet signed. "
+            "Unless a guard, a constraint or the handler compares that wallet "
+            "with the stored authority, the instruction is permissionless."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. Attacker builds the admin instruction with their own wallet as the signer\n"
+            "2. The access_control guard returns Ok(()) / checks only the instruction data\n"
+            "3. No constraint ties the signer to config.admin\n"
+            "4. Attacker sets the fee, pauses the program, or installs themselves as authority"
+        )
@@ -0,0 +1,219 @@
+"""
+ANCHOR-019: Mutable Account Aliasing
+
+Two fields of an accounts struct that hold the same account type and are
+both written can be passed the same account:
+
+    pub fn update(ctx: Context<Update>, a: u64, b: u64) -> Result<()> {
+        ctx.accounts.user_a.data = a;
+        ctx.accounts.user_b.data = b;      // same account: the first write is lost
+        Ok(())
+    }
+
+    #[derive(Accounts)]
+    pub struct Update<'info> {
+        #[account(mut)]
+        pub user_a: Account<'info, User>,
+        #[account(mut)]
+        pub user_b: Account<'info, User>,
+    }
+
+ANCHOR-002 covers the init_if_needed case. This rule generalizes it to any
+pair of Account/InterfaceAccount fields of one program-owned type that are
+`mut` or written in a handler, and to accounts taken from
+`ctx.remaining_accounts`, deserialized as a type a mutable field also holds,
+and written back.
+
+Each Account<T> handle deserializes its own copy and writes it back on exit,
+so aliased writes are silently lost. SPL token accounts and mints are left
+out — the token program works on the live account for every CPI — as are
+AccountLoader fields, whose aliased `load_mut()` calls fail at runtime on
+the shared RefCell. A pair is distinguished, and not reported, when:
+
+  - a constraint or the handler compares the two keys
+    (`constraint = a.key() != b.key()`, `require_keys_neq!`, `if a.key() == b.key()`)
+  - both are PDAs with different seeds, or ATAs with different
+    authority/mint
+  - either is created in the instruction (`init`, `zero`)
+"""
+
+import re
+from scanner.patterns.account_types import account_type
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+
+# Loaders are left out: an aliased load_mut() fails at runtime
+ALIASED_WRAPPERS = ("Account", "InterfaceAccount")
+REMAINING_LOAD_RE = re.compile(
+    r"\b(?:Account|InterfaceAccount)\s*::\s*<\s*(?:'[^,]+,\s*)?(\w+)\s*>\s*::\s*try_from\w*\s*\("
+)
+WRITE_BACK_RE = re.compile(r"\.\s*exit\s*\(|\btry_borrow_mut_data\b|\.\s*serialize\s*\(|\btry_serialize\s*\(")
+# Accounts owned by other programs, which apply every write to the live account
+EXTERNAL_TYPES = {"TokenAccount", "Mint", "Metadata", "MasterEditionAccount", "TokenRecord"}
+
+
+class AccountAliasingPattern(VulnerabilityPattern):
+    id = "ANCHOR-019"
+    name = "Mutable Account Aliasing"
+    severity = "Medium"
+    category = "accounts"
+    remediation_effort = "mechanical"
+    description = (
+        "Two mutable accounts of the same type (or a remaining account and a "
+        "mutable field) are never checked to be different, so one account "
+        "can be passed for both."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        facts = Facts(content)
+        handlers = {}
+        for ix in facts.instructions():
+            handlers.setdefault(ix.accounts_struct, []).append(ix)
+
+        for struct in facts.accounts_structs():
+            ixs = handlers.get(struct.name, [])
+            bodies = " ".join(ix.body for ix in ixs)
+            mutable = [a for a in struct.accounts if self._data_type(a) and self._is_written(a, bodies)]
+            for i, a in enumerate(mutable):
+                for b in mutable[i + 1:]:
+                    if self._data_type(a) != self._data_type(b) or self._distinguished(a, b, bodies):
+                        continue
+                    findings.append(self._finding(
+                        file_path, content, b.line,
+                        f"In struct {struct.name}: mutable fields '{a.name}' and '{b.name}' both hold "
+                        f"{self._data_type(a)} and nothing checks that they are different accounts",
+                    ))
+            for ix in ixs:
+                findings.extend(self._remaining_aliases(file_path, content, ix, mutable))
+
+        return findings
+
+    @staticmethod
+    def _data_type(account) -> str:
+        t = account_type(account.type)
+        return t.inner if t.wrapper in ALIASED_WRAPPERS and t.inner not in EXTERNAL_TYPES else ""
+
+    @staticmethod
+    def _is_written(account, bodies: str) -> bool:
+        if account.has("init") or account.has("zero"):
+            return False
+        # init_if_needed pairs are ANCHOR-002's
+        if account.has("init_if_needed"):
+            return False
+        if account.is_mut:
+            return True
+        name = re.escape(account.name)
+        return bool(re.search(
+            rf"&\s*mut\s+ctx\s*\.\s*accounts\s*\.\s*{name}\b|"
+            rf"ctx\s*\.\s*accounts\s*\.\s*{name}\s*\.\s*(?:\w+\s*\.\s*)*\w+\s*[+\-*/]?=(?!=)",
+            bodies,
+        ))
+
+    def _distinguished(self, a, b, bodies: str) -> bool:
+        for x, y in ((a, b), (b, a)):
+            for c in x.constraints:
+                if c.kind == "constraint" and re.search(rf"\b{re.escape(y.name)}\b", c.value) \
+                        and re.search(r"!=|==", c.value):
+                    return True
+        if self._keys_compared(a.name, b.name, bodies):
+            return True
+        if a.pda and b.pda and (self._norm(a.pda.seeds), self._norm(a.pda.program)) != \
+                (self._norm(b.pda.seeds), self._norm(b.pda.program)):
+            return True
+        ata_a = {c.kind: self._norm(c.value) for c in a.constraints if c.kind.startswith("associated_token::")}
+        ata_b = {c.kind: self._norm(c.value) for c in b.constraints if c.kind.startswith("associated_token::")}
+        return len(ata_a) >= 2 and len(ata_b) >= 2 and ata_a != ata_b
+
+    def _keys_compared(self, a: str, b: str, body: str) -> bool:
+        for stmt in re.split(r"[;{}]", body):
+            if not (re.search(rf"\b{re.escape(a)}\b", stmt) and re.search(rf"\b{re.escape(b)}\b", stmt)):
+                continue
+            if re.search(r"\bkey\b", stmt) and re.search(rf"!=|==|\b{self._guards()}!", stmt):
+                return True
+        return False
+
+    def _key_checked(self, name: str, body: str) -> bool:
+        """Some statement compares another account's key with `name`'s."""
+        for stmt in re.split(r"[;{}]", body):
+            if re.search(rf"\b{re.escape(name)}\b", stmt) and len(re.findall(r"\bkey\b", stmt)) >= 2 \
+                    and re.search(rf"!=|==|\b{self._guards()}!", stmt):
+                return True
+        return False
+
+    def _remaining_aliases(self, file_path: str, content: str, ix, mutable: list) -> list[Finding]:
+        """Remaining accounts loaded as a type a mutable field holds, written, never compared."""
+        if not re.search(r"\bremaining_accounts\b", ix.body) or not WRITE_BACK_RE.search(ix.body):
+            return []
+        findings = []
+        for m in REMAINING_LOAD_RE.finditer(ix.body):
+            fields = [a for a in mutable if self._data_type(a) == m.group(1)]
+            unchecked = [a.name for a in fields if not self._key_checked(a.name, ix.body)]
+            if not unchecked:
+                continue
+            line = self._get_line_number(content, ix.body_start + m.start())
+            findings.append(self._finding(
+                file_path, content, line,
+                f"Instruction '{ix.name}' loads a remaining account as {m.group(1)} and writes it back, "
+                f"but never compares its key with mutable field(s) {', '.join(unchecked)}",
+            ))
+        return findings
+
+    @staticmethod
+    def _norm(text: str) -> str:
+        return re.sub(r"\s+", "", text)
+
+    def _finding(self, file_path, content, line, message) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=self.severity,
+            file=file_path,
+            line=line,
+            description=f"{message}. The same account can be passed for both.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "user_a.balance = 100, user_b.balance = 0",
+                "after": "Same account passed twice: transfer credits 100 without debiting",
+                "damage": "Writes through one alias overwrite or double-count the other.",
+            },
+            impact={
+                "attack_cost": "< 0.01 SOL (single transaction)",
+                "exploitability": "Medium — needs two same-typed writable inputs",
+                "breach_cost_context": "Duplicate mutable accounts are a Sealevel classic behind double-credit bugs.",
+            },
+            anchor_versions_affected="All versions (remaining_accounts and handler writes are never de-duplicated)",
+            ecosystem_recommendations=[
+                "Add constraint = a.key() != b.key() for every pair of same-typed writable accounts",
+                "Derive distinct PDAs (different seeds) where the accounts play different roles",
+                "Check remaining accounts against every writable field before writing",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Require the accounts to differ:\n"
+            "  #[account(mut, constraint = user_a.key() != user_b.key() @ ErrorCode::DuplicateAccount)]\n"
+            "  pub user_a: Account<'info, User>,\n"
+            "and for remaining accounts:\n"
+            "  require_keys_neq!(acc.key(), ctx.accounts.user_a.key(), ErrorCode::DuplicateAccount);"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Account fields are matched by position, not identity. Unless a "
+            "constraint or the handler compares keys, the runtime happily passes "
+            "one account for two writable slots, and each handle holds its own "
+            "deserialized copy that is written back last-wins."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. Attacker passes the same User account as user_a and user_b\n"
+            "2. Handler debits user_a and credits user_b on separate copies\n"
+            "3. Both copies are written back; the credit overwrites the debit\n"
+            "4. Attacker's balance grows with every call"
+        )
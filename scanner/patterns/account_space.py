@@ -0,0 +1,221 @@
+"""
+ANCHOR-029: Account Space Mismatch
+
+Account sizes are usually maintained by hand, next to the struct they
+describe. Raydium keeps `PoolState::LEN` as a 22-term sum and a 400-line
+test that serializes a pool to check it. Without such a test, a field added
+without updating LEN, or a space expression that forgets the discriminator,
+goes unnoticed until an instruction fails on chain:
+
+    #[account]
+    pub struct Config {
+        pub admin: Pubkey,
+        pub fee_bps: u16,
+        pub bump: u8,               // added later
+    }
+
+    impl Config {
+        pub const LEN: usize = 32 + 2;                       // 34, fields take 35
+    }
+
+    #[account(init, payer = admin, space = Config::LEN)]      // no 8-byte discriminator
+
+The rule computes the size of every `#[account]` type in the unit from its
+fields (layout.py: borsh for regular accounts, the repr(C)/packed layout for
+zero-copy ones) and reports:
+
+  - `LEN` / `SIZE` / `SPACE` constants in `impl T` that are neither the size
+    of the fields nor that plus the 8-byte discriminator
+  - `space = ...` of an `init` / `init_if_needed` account that is smaller
+    than 8 + the size of the fields; when it equals the fields' size, the
+    discriminator was left out
+  - for a type that derives `InitSpace`, a hand-written `space` (`8 + 32 +
+    8`, no `T::INIT_SPACE`) that differs from `8 + T::INIT_SPACE` in either
+    direction, `#[max_len]` vectors and strings counted at their maximum
+  - a hand-written `impl Space for T` whose `INIT_SPACE` is not the size of
+    the fields, which every `8 + T::INIT_SPACE` then inherits
+
+Over-allocation (headroom for future fields) is not reported for types
+that do not derive `InitSpace`. Types with
+an unbounded `Vec` or `String` (no `#[max_len]`), or with fields of types
+defined elsewhere, have no computable size and are skipped.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+from scanner.patterns.layout import DISCRIMINATOR_LEN, Layout
+from scanner.patterns.lifecycle import TYPE_RE
+
+SIZE_CONST_RE = re.compile(r"^(?:LEN|SIZE|SPACE|ACCOUNT_(?:LEN|SIZE|SPACE)|MAX_(?:SIZE|SPACE))$")
+# Space expressions sized by the compiler rather than by hand
+DERIVED_SPACE_RE = re.compile(r"\bINIT_SPACE\b|\bsize_of\b|\b[A-Z]\w*\s*::\s*(?:LEN|SIZE|SPACE)\b")
+
+
+class AccountSpacePattern(VulnerabilityPattern):
+    id = "ANCHOR-029"
+    name = "Account Space Mismatch"
+    severity = "Medium"
+    category = "init"
+    remediation_effort = "mechanical"
+    description = (
+        "A hand-maintained LEN constant or init space expression does not "
+        "match the serialized size of the account's fields, or leaves out "
+        "the 8-byte discriminator."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        layout = Layout(content)
+        accounts = {name for name, s in layout.structs.items() if s.account}
+        if not accounts:
+            return findings
+
+        for key, const in layout.consts.items():
+            owner, _, name = key.rpartition("::")
+            if owner not in accounts or not (SIZE_CONST_RE.match(name) or name == "INIT_SPACE"):
+                continue
+            declared, size = layout.value(key), layout.account_size(owner)
+            if declared is None or size is None or declared == size:
+                continue
+            if name == "INIT_SPACE":
+                # Space::INIT_SPACE excludes the discriminator by definition
+                problem = (f"{size - declared} byte(s) short of the fields" if declared < size else
+                           "the discriminator counted twice, once here and once in `8 + INIT_SPACE`"
+                           if declared == size + DISCRIMINATOR_LEN else f"{declared - size} byte(s) too many")
+                findings.append(self._finding(
+                    file_path, content, const.line, "Medium" if declared < size else "Low",
+                    f"The hand-written {key} is {declared}, but the fields of {owner} serialize to "
+                    f"{size} bytes: {problem}",
+                    owner, size,
+                ))
+                continue
+            if declared == size + DISCRIMINATOR_LEN:
+                continue
+            if declared < size:
+                problem = f"{size - declared} byte(s) short of the fields alone"
+            elif declared < size + DISCRIMINATOR_LEN:
+                problem = "more than the fields but less than the fields plus the 8-byte discriminator"
+            else:
+                problem = (f"{declared - size - DISCRIMINATOR_LEN} byte(s) more than the fields plus the "
+                           f"discriminator; a field was removed or shrunk without updating it")
+            findings.append(self._finding(
+                file_path, content, const.line, "Medium" if declared < size + DISCRIMINATOR_LEN else "Low",
+                f"{key} is {declared}, but the fields of {owner} serialize to {size} bytes "
+                f"({size + DISCRIMINATOR_LEN} with the discriminator): {problem}",
+                owner, size,
+            ))
+
+        for struct in Facts(content).accounts_structs():
+            for account in struct.accounts:
+                m = TYPE_RE.search(account.type)
+                if not m or m.group(1) not in accounts:
+                    continue
+                space = next((c for c in account.constraints if c.kind == "space"), None)
+                if space is None or not (account.has("init") or account.has("init_if_needed")):
+                    continue
+                type_name = m.group(1)
+                value, size = layout.eval(space.value), layout.account_size(type_name)
+                if value is None or size is None or value == size + DISCRIMINATOR_LEN:
+                    continue
+                derived = layout.structs[type_name].derives("InitSpace") and not DERIVED_SPACE_RE.search(space.value)
+                if value > size + DISCRIMINATOR_LEN:
+                    if derived:
+                        findings.append(self._finding(
+                            file_path, content, space.line, "Low",
+                            f"In struct {struct.name}: the hand-written `space = {space.value}` for "
+                            f"'{account.name}' is {value} bytes, but {type_name} derives InitSpace and "
+                            f"8 + {type_name}::INIT_SPACE is {size + DISCRIMINATOR_LEN}{self._max_len_note(layout, type_name)}: "
+                            f"{value - size - DISCRIMINATOR_LEN} byte(s) of rent paid for nothing, and the two drift apart "
+                            f"as fields change",
+                            type_name, size,
+                        ))
+                    continue
+                if value == size:
+                    problem = (f"exactly the size of {type_name}'s fields: the 8-byte discriminator is not "
+                               f"counted, so the data does not fit")
+                else:
+                    problem = f"{size + DISCRIMINATOR_LEN - value} byte(s) less than 8 + {size} needed"
+                if derived:
+                    problem += (f"; {type_name} derives InitSpace, so `8 + {type_name}::INIT_SPACE` "
+                                f"gives the right size{self._max_len_note(layout, type_name)}")
+                findings.append(self._finding(
+                    file_path, content, space.line, self.severity,
+                    f"In struct {struct.name}: `space = {space.value}` for '{account.name}' ({type_name}) "
+                    f"is {value} bytes, {problem}",
+                    type_name, size,
+                ))
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    @staticmethod
+    def _max_len_note(layout, type_name: str) -> str:
+        """` (counting label, members at #[max_len])` for the bounded Vec/String fields of the type."""
+        bounded = [f.name for f in layout.structs[type_name].fields
+                   if re.search(r"#\[max_len\s*\(", f.attrs) and re.match(r"(?:Vec|String)\b", f.type)]
+        return f" (counting {', '.join(bounded)} at #[max_len])" if bounded else ""
+
+    def _finding(self, file_path, content, line, severity, message, type_name, size) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=severity,
+            file=file_path,
+            line=line,
+            description=f"{message}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=(
+                f"{type_name} needs {DISCRIMINATOR_LEN} + {size} = {size + DISCRIMINATOR_LEN} bytes. Let Anchor "
+                f"compute it instead of summing fields by hand:\n"
+                f"  #[account]\n"
+                f"  #[derive(InitSpace)]\n"
+                f"  pub struct {type_name} {{ .. }}\n"
+                f"  #[account(init, payer = payer, space = {type_name}::DISCRIMINATOR.len() + "
+                f"{type_name}::INIT_SPACE)]\n"
+                f"For zero-copy accounts: `space = 8 + std::mem::size_of::<{type_name}>()`."
+            ),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "Config::LEN = 34 after a 1-byte field was added (35 bytes of fields)",
+                "after": "init allocates 8 + 34 = 42 bytes; serializing 43 fails",
+                "damage": "The instruction creating the account always fails, or a later field "
+                          "addition breaks every new account.",
+            },
+            impact={
+                "attack_cost": "None — the mismatch fails on its own",
+                "exploitability": "Low — availability and upgrade risk rather than theft",
+                "breach_cost_context": "Hand-counted LEN constants are a recurring audit note in Anchor programs.",
+            },
+            anchor_versions_affected="All versions (developer-side pattern); InitSpace since Anchor 0.28",
+            ecosystem_recommendations=[
+                "Derive InitSpace and use T::INIT_SPACE, with #[max_len] on Vec and String fields",
+                "Keep the discriminator out of LEN and add it at the space expression",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Derive the size instead of counting it:\n"
+            "  #[account]\n"
+            "  #[derive(InitSpace)]\n"
+            "  pub struct Config { .. }\n"
+            "  #[account(init, payer = admin, space = 8 + Config::INIT_SPACE)]"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Anchor allocates exactly the `space` it is given and writes an "
+            "8-byte discriminator before the data. Hand-written sizes drift "
+            "from the struct as fields change, and the discriminator is easy "
+            "to forget."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. A field is added to the account struct; LEN is not updated\n"
+            "2. init allocates the old size\n"
+            "3. Serializing the account at the end of the instruction fails: no new account can be created\n"
+            "4. For zero-copy accounts, load_init rejects the undersized account the same way"
+        )
@@ -0,0 +1,112 @@
+"""
+Account wrapper normalization.
+
+Anchor accepts the same account under several spellings:
+
+    Account<'info, Vault>                 // typed, owner and discriminator checked
+    Box<Account<'info, Vault>>            // same, moved to the heap for stack size
+    InterfaceAccount<'info, Mint>         // typed over Token and Token-2022
+    AccountLoader<'info, Pool>            // zero-copy, checked on load()
+    Option<Box<UncheckedAccount<'info>>>  // optional account (Anchor >= 0.26)
+
+Boxing and `Option` change nothing about what the runtime validates, so
+rules must not treat `Box<AccountInfo<'info>>` differently from
+`AccountInfo<'info>`. Rules ask through `account_type(...)` or
+`field_type(line)` instead of matching the spelling:
+
+    from scanner.patterns.account_types import account_type
+
+    t = account_type(account.type)
+    t.wrapper   # "Account", "AccountLoader", "UncheckedAccount", ...
+    t.inner     # "Vault" for the typed wrappers, else ""
+    t.is_raw    # AccountInfo / UncheckedAccount: nothing checked
+"""
+
+import re
+from dataclasses import dataclass
+from typing import Optional, Tuple
+
+# Wrappers that check owner and discriminator of T
+TYPED_WRAPPERS = ("Account", "InterfaceAccount", "AccountLoader")
+# Wrappers that check nothing about the account
+RAW_WRAPPERS = ("AccountInfo", "UncheckedAccount")
+
+# `Box<..>` / `Option<..>` around the wrapper, any nesting
+OUTER_RE = re.compile(r"\s*(Box|Option)\s*<(.*)>\s*$", re.S)
+WRAPPER_RE = re.compile(r"\s*(?:[\w:]+::)?(\w+)\s*(?:<(.*)>)?\s*$", re.S)
+# `name: Type` of a struct field on one line, type up to the trailing comma
+FIELD_RE = re.compile(r"^\s*(?:pub(?:\([^)]*\))?\s+)?(\w+)\s*:\s*(.+?)\s*,?\s*(?://.*)?$")
+
+
+@dataclass(frozen=True)
+class AccountType:
+    wrapper: str       # "Account", "Signer", "Program", ... ("" when unparsable)
+    inner: str = ""    # T of the typed wrappers, the program / sysvar type of Program / Sysvar / Interface
+    boxed: bool = False
+    optional: bool = False
+
+    @property
+    def is_typed(self) -> bool:
+        return self.wrapper in TYPED_WRAPPERS
+
+    @property
+    def is_raw(self) -> bool:
+        return self.wrapper in RAW_WRAPPERS
+
+    @property
+    def is_signer(self) -> bool:
+        return self.wrapper == "Signer"
+
+    @property
+    def is_program(self) -> bool:
+        return self.wrapper in ("Program", "Interface")
+
+
+def unwrap_type(type_str: str) -> Tuple[str, bool, bool]:
+    """(type without Box / Option, boxed, optional)."""
+    boxed = optional = False
+    text = type_str.strip()
+    while True:
+        m = OUTER_RE.match(text)
+        if not m:
+            return text, boxed, optional
+        if m.group(1) == "Box":
+            boxed = True
+        else:
+            optional = True
+        text = m.group(2).strip()
+
+
+def account_type(type_str: str) -> AccountType:
+    """The wrapper and inner type of an Accounts field type."""
+    text, boxed, optional = unwrap_type(type_str)
+    m = WRAPPER_RE.match(text)
+    if not m:
+        return AccountType("", "", boxed, optional)
+    args = [a.strip() for a in _split_args(m.group(2) or "") if a.strip() and not a.strip().startswith("'")]
+    inner = re.sub(r"^(?:[\w]+::)*", "", args[0]) if args else ""
+    inner = re.match(r"\w*", inner).group(0)
+    return AccountType(m.group(1), inner, boxed, optional)
+
+
+def field_type(line: str) -> Optional[Tuple[str, AccountType]]:
+    """(field name, type) of a one-line struct field such as `pub vault: Box<Account<'info, V>>,`."""
+    m = FIELD_RE.match(line)
+    if not m or m.group(2).startswith(("=", ":")):
+        return None
+    t = account_type(m.group(2))
+    return (m.group(1), t) if t.wrapper else None
+
+
+def _split_args(text: str) -> list:
+    parts, depth, start = [], 0, 0
+    for i, ch in enumerate(text):
+        if ch == "<":
+            depth += 1
+        elif ch == ">":
+            depth -= 1
+        elif ch == "," and depth == 0:
+            parts.append(text[start:i])
+            start = i + 1
+    parts.append(text[start:])
+    return parts
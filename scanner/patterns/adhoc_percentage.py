@@ -0,0 +1,151 @@
+"""
+ANCHOR-011: Ad-Hoc Percentage Math With Bare Denominators
+
+Detects fee/share math written with literal denominators (`* 75 / 100`,
+`.checked_mul(fee).checked_div(10_000)`) repeated across handlers instead of
+going through a typed fee or basis-points abstraction (like Marinade's
+`Fee`). When one program mixes denominators — percent in one handler, basis
+points in another — a value configured for one scale is silently applied on
+the other, a 100x error that compiles and passes casual tests.
+
+Sites inside `impl` blocks of fee-like types (Fee, Bps, BasisPoints,
+Percent...) are the abstraction itself and are not reported. A single site in
+a single function is not reported either; the smell is the repetition.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+
+
+class AdHocPercentagePattern(VulnerabilityPattern):
+    id = "ANCHOR-011"
+    name = "Ad-Hoc Percentage Math With Bare Denominators"
+    severity = "Low"
+    category = "arithmetic"
+    remediation_effort = "localized"
+    description = (
+        "Percentage/fee math uses bare literal denominators across handlers "
+        "instead of a shared fee type; mixed denominators risk scale errors."
+    )
+
+    DENOMINATOR_RE = re.compile(
+        r"(?:/\s*|checked_div\s*\(\s*)(100|1_?000|10_?000|100_?000|1_?000_?000)(?:u64|u128|_u64|_u128)?\b(?!\.)"
+    )
+
+    MULTIPLY_RE = re.compile(r"\*|checked_mul\s*\(|saturating_mul\s*\(")
+
+    FEE_TYPE_RE = re.compile(r"(?i)fee|bps|basis|percent|ratio")
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        functions = self._find_functions(content)
+        fee_impls = [
+            (start, end) for start, end, name in self._find_impl_blocks(content)
+            if self.FEE_TYPE_RE.search(name)
+        ]
+
+        sites = []  # (offset, denominator, fn_name)
+        for m in self.DENOMINATOR_RE.finditer(content):
+            pos = m.start()
+            if any(start <= pos < end for start, end in fee_impls):
+                continue
+            if self._in_comment(content, pos):
+                continue
+            stmt_start = max(content.rfind(c, 0, pos) for c in ";{}") + 1
+            if not self.MULTIPLY_RE.search(content[stmt_start:pos]):
+                continue
+            fn = self._enclosing_function(functions, pos)
+            if fn is None:
+                continue
+            sites.append((pos, int(m.group(1).replace("_", "")), fn["name"]))
+
+        fn_names = {name for _, _, name in sites}
+        denominators = sorted({d for _, d, _ in sites})
+        if len(fn_names) < 2 and len(denominators) < 2:
+            return []
+
+        findings = []
+        reported = set()
+        for pos, denom, fn_name in sites:
+            if fn_name in reported:
+                continue
+            reported.add(fn_name)
+            line = self._get_line_number(content, pos)
+            if len(denominators) > 1:
+                detail = (
+                    f"This file mixes denominators {', '.join(map(str, denominators))}; "
+                    f"a rate configured for one scale can be applied on another."
+                )
+            else:
+                detail = (
+                    f"The same literal denominator is repeated in {len(fn_names)} "
+                    f"functions instead of a shared fee type."
+                )
+            findings.append(Finding(
+                id=self.id,
+                name=self.name,
+                severity=self.severity,
+                file=file_path,
+                line=line,
+                description=f"In fn {fn_name}: percentage math divides by bare literal {denom}. {detail}",
+                root_cause=self.get_root_cause(),
+                exploit_scenario=self.get_exploit_scenario(),
+                fix_recommendation=self.get_fix_recommendation(),
+                code_snippet=self._extract_snippet(content, line),
+                before_after_state={
+                    "before": "Admin sets fee = 50 intending 0.5% (basis points)",
+                    "after": "Handler computes amount * 50 / 100 = 50%",
+                    "damage": "Fee charged at 100x the intended rate (or 1/100th of it).",
+                },
+                impact={
+                    "attack_cost": "None — a configuration/scale mismatch",
+                    "exploitability": "Low — correctness smell, exploitable only via misconfiguration",
+                    "breach_cost_context": "Fee scale mix-ups have drained protocol revenue or overcharged users at launch.",
+                },
+                anchor_versions_affected="All versions (developer-side pattern)",
+                ecosystem_recommendations=[
+                    "Wrap rates in a newtype (Fee/Bps) that owns its denominator",
+                    "Validate configured rates against the type's maximum on write",
+                ],
+            ))
+        return findings
+
+    @staticmethod
+    def _enclosing_function(functions: list, pos: int):
+        best = None
+        for fn in functions:
+            if fn["body_start"] <= pos < fn["end"]:
+                if best is None or fn["body_start"] > best["body_start"]:
+                    best = fn
+        return best
+
+    @staticmethod
+    def _in_comment(content: str, pos: int) -> bool:
+        line_start = content.rfind("\n", 0, pos) + 1
+        return "//" in content[line_start:pos]
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Introduce one rate type that owns its denominator and use it everywhere:\n"
+            "  pub struct Bps { pub bps: u16 }\n"
+            "  impl Bps {\n"
+            "      pub fn apply(&self, amount: u64) -> Option<u64> {\n"
+            "          (amount as u128).checked_mul(self.bps as u128)?\n"
+            "              .checked_div(10_000)?.try_into().ok()\n"
+            "      }\n"
+            "  }"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Rates stored as bare integers carry no scale. Each handler that "
+            "re-implements `x * rate / DENOM` picks its own denominator, so the "
+            "meaning of a stored rate depends on which handler reads it."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. Protocol stores a fee as basis points (out of 10_000)\n"
+            "2. A later handler applies it as a percentage (out of 100)\n"
+            "3. Users of that handler are charged 100x the configured fee,\n"
+            "   or the protocol collects 1/100th of what it should"
+        )
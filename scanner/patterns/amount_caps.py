@@ -0,0 +1,264 @@
+"""
+ANCHOR-048: Uncapped Privileged Amount
+
+Instructions that mint, or pay out of a program-owned vault, take the
+amount as an argument. When only an admin can call them, or the program
+signs the CPI with its own PDA seeds, nothing but the caller's honesty
+bounds the amount:
+
+    pub fn admin_mint(ctx: Context<AdminMint>, amount: u64) -> Result<()> {
+        token::mint_to(ctx.accounts.mint_ctx().with_signer(&[&seeds]), amount)
+    }
+
+A leaked admin key, a compromised multisig member or a bug upstream then
+mints or drains everything in one transaction. Marinade bounds even its
+user-facing paths with state fields checked on every call:
+
+    pub staking_sol_cap: u64,
+    require_lte!(result_amount, self.staking_sol_cap, MarinadeError::StakingIsCapped);
+
+The rule reports (Medium) each integer argument of a handler that reaches
+the amount of a `mint_to`/`transfer`(`_checked`) CPI, or of a PDA-signing
+payout helper, when the CPI is PDA-signed or the instruction is
+admin-only (an admin/authority/operator signer bound to state by
+`has_one`, `address` or a constraint), and nothing bounds the argument: no
+`require_lte!`/`<`/`>` against anything but zero, `.min(..)` or call to a
+`*cap*`/`*limit*` check before the CPI, and no `-=`/`checked_sub` debiting
+a recorded balance by it. Cap fields the program already keeps in state
+(`*_cap`, `max_*`, `*_limit`) are suggested by name.
+
+A program that takes user deposits and pays them back out with PDA
+signatures, but has no cap field or constant anywhere in the file, is
+reported once at its `#[program]` module (Low): there is no mechanism a
+per-instruction cap could use.
+"""
+
+import re
+from scanner.cpi import find_cpi_calls
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+from scanner.patterns.math_summary import _blank
+from scanner.patterns.signing import pda_signers, signing_calls
+
+MOVE_IXS = {"mint_to", "mint_to_checked", "transfer", "transfer_checked"}
+INT_PARAM_RE = re.compile(r"(?:^|,)\s*(?:mut\s+)?(\w+)\s*:\s*(?:u|i)(?:64|128|32)\b")
+ADMIN_RE = re.compile(r"(?:^|_)(?:admin|authority|operator|manager|governance|governor|multisig|owner_authority)(?:_|$)")
+CAP_FIELD_RE = re.compile(r"^\s*pub\s+(\w*(?:_cap|cap_|max_|_max|_limit|limit_|ceiling)\w*)\s*:\s*[ui](?:64|128|32)\b", re.M)
+CAP_CONST_RE = re.compile(r"\bconst\s+(\w*(?:_CAP\b|MAX_\w*(?:AMOUNT|DEPOSIT|WITHDRAW|MINT|SUPPLY|LAMPORTS)|_LIMIT\b)\w*)\s*:")
+CAP_CALL_RE = re.compile(r"\b\w*(?:cap|limit|max_amount|bound)\w*\s*\(", re.I)
+STATE_STRUCT_RE = re.compile(r"#\[account(?:\([^)]*\))?\]\s*(?:#\[[^\]]*\]\s*)*pub\s+struct\s+(\w+)\s*\{")
+PROGRAM_MOD_RE = re.compile(r"#\[program\]\s*(?:#\[[^\]]*\]\s*)*pub\s+mod\s+(\w+)")
+
+
+class AmountCapsPattern(VulnerabilityPattern):
+    id = "ANCHOR-048"
+    name = "Uncapped Privileged Amount"
+    severity = "Medium"
+    category = "economics"
+    remediation_effort = "localized"
+    description = (
+        "An admin-only or PDA-signed mint/transfer takes its amount from an "
+        "argument that nothing caps, so one compromised call moves any amount."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        facts = Facts(content)
+        code = _blank(content)
+        signers = pda_signers(code, depth=self.max_depth)
+        caps = self._cap_fields(content)
+        impls = self._find_impl_blocks(code)
+        deposits, payouts = [], []
+        for ix in facts.instructions():
+            struct = next((s for s in facts.accounts_structs() if s.name == ix.accounts_struct), None)
+            admin = self._admin(struct)
+            bodies = [(ix.body_start, code[ix.body_start:ix.body_start + len(ix.body)])]
+            bodies += [(s, code[s:e]) for s, e, t in impls if t == ix.accounts_struct]
+            params = {m.group(1) for m in INT_PARAM_RE.finditer(ix.params)}
+            reported = set()
+            for start, body in bodies:
+                params |= {m.group(1) for fn in re.finditer(r"\bfn\s+\w+\s*(?:<[^>]*>)?\s*\(([^)]*)\)", body)
+                           for m in INT_PARAM_RE.finditer(fn.group(1))}
+                for pos, target, signed, amount in self._moves(body, signers):
+                    (payouts if signed else deposits).append(ix.name)
+                    if not (signed or admin):
+                        continue
+                    for arg in sorted(params - reported):
+                        flows = self._flows(arg, body[:pos])
+                        if not any(re.search(rf"\b{re.escape(n)}\b", amount) for n in flows):
+                            continue
+                        if self._bounded(flows, body[:pos]) or self._booked(flows, body):
+                            continue
+                        why = f"only '{admin}' can call it" if admin else "the program signs it with PDA seeds"
+                        reported.add(arg)
+                        line = self._get_line_number(content, start + pos)
+                        findings.append(self._finding(
+                            file_path, content, line, self.severity,
+                            f"In {ix.name}: argument '{arg}' is the amount of {target}, {why}, and nothing "
+                            f"compares it against a cap; {self._suggestion(caps)}",
+                        ))
+
+        program = PROGRAM_MOD_RE.search(content)
+        if program and deposits and payouts and not caps and not CAP_CONST_RE.search(code):
+            findings.append(self._finding(
+                file_path, content, self._get_line_number(content, program.start(1)), "Low",
+                f"Program {program.group(1)} holds user funds (deposits in {', '.join(dict.fromkeys(deposits))}; "
+                f"PDA-signed payouts in {', '.join(dict.fromkeys(payouts))}) but keeps no cap field or constant "
+                f"anywhere, so no instruction can bound how much moves at once",
+            ))
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    @staticmethod
+    def _admin(struct) -> str:
+        """Name of the admin signer bound to state, or empty."""
+        if struct is None:
+            return ""
+        for signer in (a for a in struct.accounts if a.is_signer and ADMIN_RE.search(a.name)):
+            name = re.escape(signer.name)
+            if signer.has("address") or any(
+                (c.kind == "has_one" and re.match(rf"\s*{name}\b", c.value))
+                or (c.kind == "constraint" and re.search(rf"\b{name}\s*\.\s*key\s*\(", c.value))
+                for a in struct.accounts for c in a.constraints
+            ):
+                return signer.name
+        return ""
+
+    def _moves(self, body: str, signers: dict) -> list:
+        """(offset, description, signed, amount text) of mint/transfer CPIs and PDA-signing payout helpers."""
+        moves = []
+        for call in find_cpi_calls(body):
+            if call.instruction in MOVE_IXS:
+                signed = call.signed or bool(re.search(r"\binvoke_signed\s*\(", call.text))
+                args = self._args(body, call.start)
+                if call.via != "invoke":
+                    # transfer(ctx, amount) / transfer_checked(ctx, amount, decimals)
+                    amount = args[1] if len(args) > 1 else ""
+                else:
+                    # The instruction builder, inline or bound by `let ix = ...` earlier
+                    ix = re.match(r"&?\s*(\w+)$", args[0]) if args else None
+                    let = ix and re.findall(rf"\blet\s+{ix.group(1)}\b[^=]*=([^;]*);", body[:call.start])
+                    amount = let[-1] if let else call.text
+                moves.append((call.start, f"{call.instruction} ({call.program})", signed, amount))
+        for pos, name in signing_calls(body, signers):
+            if re.search(r"transfer|mint|pay|withdraw|send", name):
+                moves.append((pos, f"{name}()", True, ", ".join(self._args(body, pos))))
+        moves.sort(key=lambda m: m[0])
+        return moves
+
+    @staticmethod
+    def _args(body: str, pos: int) -> list:
+        """Top-level arguments of the call starting at pos."""
+        args, depth, start = [], 0, body.find("(", pos) + 1
+        for i in range(start, len(body)):
+            ch = body[i]
+            if ch in "([{":
+                depth += 1
+            elif ch in ")]}" and depth:
+                depth -= 1
+            elif ch == ")" or (ch == "," and not depth):
+                args.append(body[start:i].strip())
+                start = i + 1
+                if ch == ")":
+                    break
+        return [a for a in args if a]
+
+    @staticmethod
+    def _flows(arg: str, before: str) -> set:
+        """The argument and the locals computed from it before the call."""
+        names = {arg}
+        for m in re.finditer(r"\blet\s+(?:mut\s+)?(\w+)\s*(?::[^=]+)?=([^;]+);", before):
+            if any(re.search(rf"\b{re.escape(n)}\b", m.group(2)) for n in names):
+                names.add(m.group(1))
+        return names
+
+    @staticmethod
+    def _bounded(names: set, before: str) -> bool:
+        """A comparison, min() or cap check on any of the names before the call."""
+        for name in names:
+            n = re.escape(name)
+            if re.search(rf"\b{n}\s*(?:<=?|>=?)\s*(?!0\b)[\w(]|[\w)]\s*(?<!0)(?:<=?|>=?)\s*{n}\b", before):
+                return True
+            if re.search(rf"\brequire_(?:lte|lt|gte|gt)!\s*\((?:[^;]*?\b{n}\b)(?![^;]*?,\s*0\s*,)", before):
+                return True
+            if re.search(rf"\b{n}\s*\.\s*min\s*\(|\bmin\s*\([^;]*\b{n}\b", before):
+                return True
+            if any(re.search(rf"\b{n}\b", before[m.end():before.find(")", m.end()) + 1])
+                   for m in CAP_CALL_RE.finditer(before)):
+                return True
+        return False
+
+    @staticmethod
+    def _booked(names: set, body: str) -> bool:
+        """The handler debits a recorded balance by the amount, which bounds it to that balance."""
+        return any(re.search(rf"-=\s*{re.escape(n)}\b|\.\s*(?:checked|saturating)_sub\s*\(\s*{re.escape(n)}\b", body)
+                   for n in names)
+
+    @staticmethod
+    def _cap_fields(content: str) -> list:
+        """'State.field' for every cap-like integer field of an #[account] struct."""
+        fields = []
+        for m in STATE_STRUCT_RE.finditer(content):
+            end = VulnerabilityPattern._find_block_end(content, m.end() - 1)
+            for f in CAP_FIELD_RE.finditer(content[m.end():end]):
+                fields.append(f"{m.group(1)}.{f.group(1)}")
+        return fields
+
+    @staticmethod
+    def _suggestion(caps: list) -> str:
+        if caps:
+            return f"require it to be at most a cap field such as {', '.join(caps[:3])}"
+        return ("add a cap field to state (max_mint_per_ix, withdraw_cap, in the style of Marinade's "
+                "staking_sol_cap) and require the amount to be at most it")
+
+    def _finding(self, file_path, content, line, severity, message) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=severity,
+            file=file_path,
+            line=line,
+            description=f"{message}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "Admin key held by one operator; mint supply 10M",
+                "after": "admin_mint(amount = u64::MAX) signed with the leaked key",
+                "damage": "Supply inflated without limit in one transaction, or the vault drained in one payout.",
+            },
+            impact={
+                "attack_cost": "Compromising the admin key or finding a path to the PDA-signed call",
+                "exploitability": "Medium — needs a privileged caller, but the loss is unbounded",
+                "breach_cost_context": "Admin-key compromises are among the largest Solana losses; caps limit them.",
+            },
+            anchor_versions_affected="All versions (program-side logic)",
+            ecosystem_recommendations=[
+                "Keep per-instruction and per-epoch caps in state and check them on every privileged mint/payout",
+                "Put admin keys behind a multisig or timelock in addition to caps",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Bound the amount with a cap kept in state:\n"
+            "  require_lte!(amount, ctx.accounts.config.max_mint_per_ix, ErrorCode::CapExceeded);\n"
+            "and let only a separate governance path raise the cap."
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Privileged mint and payout instructions trust their caller for the "
+            "amount. Signer checks decide who may call them, not how much one "
+            "call may move, so a compromised key or an upstream bug has no limit."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. The program mints rewards through admin_mint(amount), checked only for the admin signer\n"
+            "2. The admin key leaks from a CI secret\n"
+            "3. Attacker calls admin_mint with u64::MAX to their own token account\n"
+            "4. They sell into every pool before anyone can react; a cap would have bounded the loss"
+        )
@@ -0,0 +1,318 @@
+"""
+ANCHOR-010: Anchor.toml Configuration Inconsistency
+
+Checks the workspace's Anchor.toml for settings that leak into deployments:
+  - `[features] seeds = false` while programs rely on `seeds = [...]`
+    constraints (the IDL omits the seeds, so clients derive PDAs by hand)
+  - a `[provider] wallet` keypair stored inside the repository
+  - `[provider] cluster` pointing at mainnet in a config that runs tests
+  - `[programs.*]` IDs that differ from the program's `declare_id!`
+
+and each program under programs/ for an ID that cannot be the deployed one:
+  - Anchor's template ID `Fg6PaF...` as the unconditional `declare_id!` of
+    a program outside tests/ and examples/: its keypair is public, so the
+    program is either undeployable there or deployable by anyone
+  - a `target/deploy/<program>-keypair.json` whose public key differs from
+    `declare_id!`: `anchor deploy` publishes to the keypair's address and
+    every instruction then fails the program ID check
+  - program ID guards that check a different ID: `other::check_id(program_id)`
+    or `program_id == other::ID` where module `other` declares its own ID,
+    or `program_id` compared with a hard-coded `pubkey!("...")` that is not
+    the declared one
+"""
+
+import json
+import os
+import re
+
+try:
+    import tomllib
+except ImportError:  # Python < 3.11
+    import tomli as tomllib
+
+from scanner.patterns.base import VulnerabilityPattern, Finding
+
+BASE58_ALPHABET = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz"
+
+
+def _base58(data: bytes) -> str:
+    n = int.from_bytes(data, "big")
+    digits = ""
+    while n:
+        n, r = divmod(n, 58)
+        digits = BASE58_ALPHABET[r] + digits
+    return "1" * (len(data) - len(data.lstrip(b"\0"))) + digits
+
+
+class AnchorTomlPattern(VulnerabilityPattern):
+    id = "ANCHOR-010"
+    name = "Anchor.toml Configuration Inconsistency"
+    severity = "Medium"
+    category = "config"
+    remediation_effort = "mechanical"
+    description = (
+        "Anchor.toml setting diverges from the program sources or exposes "
+        "deployment secrets."
+    )
+
+    SKIP_DIRS = {"target", "node_modules", ".git"}
+
+    DECLARE_ID_RE = re.compile(r'declare_id!\s*\(\s*"([1-9A-HJ-NP-Za-km-z]+)"\s*\)')
+    PLACEHOLDER_ID = "Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS"
+    TEST_DIRS = {"tests", "test", "examples"}
+    MOD_RE = re.compile(r"\bmod\s+(\w+)\s*\{")
+    CHECK_ID_RE = re.compile(r"\b(\w+)\s*::\s*check_id\s*\(")
+    PUBKEY_LITERAL_RE = re.compile(r'(?:pubkey!|Pubkey::from_str)\s*\(\s*"([1-9A-HJ-NP-Za-km-z]+)"\s*\)')
+    COMPARISON_RE = re.compile(r"==|!=|\b(?:require_keys_(?:eq|neq)|assert_eq|assert_ne)!")
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        # Rust sources are handled by the other patterns; see scan_workspace.
+        return []
+
+    def scan_workspace(self, root: str) -> list[Finding]:
+        findings = []
+        for dirpath, dirnames, files in os.walk(root):
+            dirnames[:] = [d for d in dirnames if d not in self.SKIP_DIRS]
+            if "Anchor.toml" in files:
+                findings.extend(self._check_workspace(root, dirpath))
+        return findings
+
+    def _check_workspace(self, root: str, workspace: str) -> list[Finding]:
+        toml_path = os.path.join(workspace, "Anchor.toml")
+        rel_toml = os.path.relpath(toml_path, root)
+        with open(toml_path, "r", encoding="utf-8", errors="ignore") as fh:
+            raw = fh.read()
+        try:
+            config = tomllib.loads(raw)
+        except tomllib.TOMLDecodeError:
+            return []
+
+        findings = []
+        provider = config.get("provider", {})
+
+        # seeds = false with seed constraints in the programs
+        if config.get("features", {}).get("seeds") is False:
+            if self._sources_use_seeds(workspace):
+                findings.append(self._make_finding(
+                    rel_toml, raw, r"^\s*seeds\s*=", "Low",
+                    "[features] seeds = false, but programs use `seeds = [...]` "
+                    "constraints. The IDL will not carry the seeds, so every "
+                    "client must re-derive PDAs by hand.",
+                ))
+
+        # Wallet keypair committed to the repo
+        wallet = provider.get("wallet", "")
+        if wallet and not wallet.startswith(("~", "/", "$")):
+            wallet_path = os.path.normpath(os.path.join(workspace, wallet))
+            if os.path.isfile(wallet_path):
+                findings.append(self._make_finding(
+                    rel_toml, raw, r"^\s*wallet\s*=", "High",
+                    f"[provider] wallet = \"{wallet}\" points to a keypair file "
+                    f"inside the repository. Anyone with repo access holds the "
+                    f"deployer key.",
+                ))
+
+        # Mainnet cluster in a config that runs tests
+        cluster = str(provider.get("cluster", "")).lower()
+        if "mainnet" in cluster and "test" in config.get("scripts", {}):
+            findings.append(self._make_finding(
+                rel_toml, raw, r"^\s*cluster\s*=", "Medium",
+                f"[provider] cluster = \"{provider.get('cluster')}\" while "
+                f"[scripts] test is defined. `anchor test` will deploy and run "
+                f"against mainnet with the configured wallet.",
+            ))
+
+        # Program IDs vs declare_id!
+        declared = self._declared_ids(workspace)
+        for section, programs in config.get("programs", {}).items():
+            if not isinstance(programs, dict):
+                continue
+            for program, program_id in programs.items():
+                if isinstance(program_id, dict):
+                    program_id = program_id.get("address", "")
+                source_id = declared.get(program.replace("-", "_"))
+                if source_id and program_id and source_id != program_id:
+                    findings.append(self._make_finding(
+                        rel_toml, raw, rf"^\s*{re.escape(program)}\s*=", "High",
+                        f"[programs.{section}] {program} = \"{program_id}\" but "
+                        f"declare_id! in the program is \"{source_id}\". Clients "
+                        f"and deploys will target a different program than the "
+                        f"one the code checks against.",
+                    ))
+
+        findings.extend(self._check_programs(root, workspace, declared))
+        return findings
+
+    def _check_programs(self, root: str, workspace: str, declared: dict) -> list[Finding]:
+        """Placeholder IDs, stale deploy keypairs and foreign ID guards in programs/*."""
+        findings = []
+        shipped = not self.TEST_DIRS & set(os.path.relpath(workspace, root).split(os.sep))
+        for program, program_id in declared.items():
+            crate = self._program_dir(workspace, program)
+            lib = os.path.join(crate, "src", "lib.rs")
+            rel_lib = os.path.relpath(lib, root)
+            with open(lib, "r", encoding="utf-8", errors="ignore") as fh:
+                source = fh.read()
+            m = self.DECLARE_ID_RE.search(source)
+            line = self._get_line_number(source, m.start())
+            gated = re.search(r"#\[cfg\([^\]]*\]\s*$", source[:m.start()])
+
+            if shipped and program_id == self.PLACEHOLDER_ID and not gated:
+                findings.append(self._make_finding(
+                    rel_lib, source, None, "Medium",
+                    f"Program '{program}' ships with Anchor's template ID "
+                    f"\"{program_id}\" in declare_id!. The template keypair is "
+                    f"public: the program cannot be deployed to an address it "
+                    f"controls, and anyone can deploy to this one.",
+                    line=line,
+                ))
+
+            keypair = os.path.join(workspace, "target", "deploy", f"{program}-keypair.json")
+            deploy_id = self._keypair_pubkey(keypair)
+            if deploy_id and deploy_id != program_id and not gated:
+                findings.append(self._make_finding(
+                    rel_lib, source, None, "Medium",
+                    f"target/deploy/{program}-keypair.json holds \"{deploy_id}\" but "
+                    f"declare_id! in '{program}' is \"{program_id}\". `anchor deploy` "
+                    f"publishes to the keypair's address, where every instruction "
+                    f"fails the declared program ID check.",
+                    line=line,
+                ))
+
+            for path in self._rust_files(crate):
+                with open(path, "r", encoding="utf-8", errors="ignore") as fh:
+                    text = fh.read()
+                for offset, guarded in self._id_guards(text):
+                    if guarded == program_id:
+                        continue
+                    findings.append(self._make_finding(
+                        os.path.relpath(path, root), text, None, "High",
+                        f"Program ID guard in '{program}' checks \"{guarded}\" while "
+                        f"declare_id! is \"{program_id}\". The guard rejects the "
+                        f"deployed program, or accepts a different one.",
+                        line=self._get_line_number(text, offset),
+                    ))
+        return findings
+
+    def _id_guards(self, source: str):
+        """(offset, ID) of each check of the program ID against a specific ID."""
+        modules = {}
+        for m in self.MOD_RE.finditer(source):
+            end = self._find_block_end(source, m.end() - 1)
+            declared = self.DECLARE_ID_RE.search(source, m.end(), end)
+            if declared:
+                modules[m.group(1)] = declared.group(1)
+        for m in self.CHECK_ID_RE.finditer(source):
+            if m.group(1) in modules:
+                yield m.start(), modules[m.group(1)]
+        start = 0
+        for stmt in re.split(r"([;{}])", source):
+            if re.search(r"\bprogram_id\b", stmt) and self.COMPARISON_RE.search(stmt):
+                for m in self.PUBKEY_LITERAL_RE.finditer(stmt):
+                    yield start + m.start(), m.group(1)
+                for m in re.finditer(r"\b(\w+)\s*::\s*ID\b", stmt):
+                    if m.group(1) in modules:
+                        yield start + m.start(), modules[m.group(1)]
+            start += len(stmt)
+
+    def _keypair_pubkey(self, path: str):
+        """Base58 public key of a Solana CLI keypair file, None if unreadable."""
+        if not os.path.isfile(path):
+            return None
+        try:
+            with open(path, "r", encoding="utf-8") as fh:
+                secret = json.load(fh)
+            key = bytes(secret)
+        except (ValueError, TypeError):
+            return None
+        return _base58(key[32:]) if len(key) == 64 else None
+
+    def _program_dir(self, workspace: str, program: str) -> str:
+        programs_dir = os.path.join(workspace, "programs")
+        for name in sorted(os.listdir(programs_dir)):
+            if name.replace("-", "_") == program:
+                return os.path.join(programs_dir, name)
+        return os.path.join(programs_dir, program)
+
+    def _sources_use_seeds(self, workspace: str) -> bool:
+        for path in self._rust_files(workspace):
+            with open(path, "r", encoding="utf-8", errors="ignore") as fh:
+                if re.search(r"\bseeds\s*=\s*\[", fh.read()):
+                    return True
+        return False
+
+    def _declared_ids(self, workspace: str) -> dict:
+        """Map program crate directory name (snake_case) -> declare_id! value."""
+        ids = {}
+        programs_dir = os.path.join(workspace, "programs")
+        if not os.path.isdir(programs_dir):
+            return ids
+        for program in sorted(os.listdir(programs_dir)):
+            lib = os.path.join(programs_dir, program, "src", "lib.rs")
+            if not os.path.isfile(lib):
+                continue
+            with open(lib, "r", encoding="utf-8", errors="ignore") as fh:
+                m = self.DECLARE_ID_RE.search(fh.read())
+            if m:
+                ids[program.replace("-", "_")] = m.group(1)
+        return ids
+
+    def _rust_files(self, workspace: str):
+        for dirpath, dirnames, files in os.walk(workspace):
+            dirnames[:] = [d for d in dirnames if d not in self.SKIP_DIRS]
+            for f in files:
+                if f.endswith(".rs"):
+                    yield os.path.join(dirpath, f)
+
+    def _make_finding(self, rel_path, raw, key_re, severity, what, line=None) -> Finding:
+        if line is None:
+            m = re.search(key_re, raw, re.MULTILINE)
+            line = self._get_line_number(raw, m.start()) if m else 1
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=severity,
+            file=rel_path,
+            line=line,
+            description=what,
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(raw, line, context=2),
+            impact={
+                "attack_cost": "None — configuration is read at build/deploy time",
+                "exploitability": "Depends on setting — a committed deployer key is directly exploitable",
+                "breach_cost_context": "Leaked upgrade-authority keys allow replacing the deployed program outright.",
+            },
+            anchor_versions_affected="All versions",
+            ecosystem_recommendations=[
+                "Keep deployer keypairs outside the repo (~/.config/solana/id.json)",
+                "Use separate Anchor.toml provider settings for tests and mainnet deploys",
+                "Run `anchor keys sync` before every deploy",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Align Anchor.toml with the program and deployment environment:\n"
+            "  [provider]\n"
+            "  cluster = \"localnet\"\n"
+            "  wallet = \"~/.config/solana/id.json\"\n"
+            "and run `anchor keys sync` so [programs.*] matches declare_id!."
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Anchor.toml drives builds, IDL generation, tests and deploys, but is "
+            "not type-checked against the program. Settings copied from templates "
+            "or left over from a mainnet deploy silently change what gets built "
+            "and where it is sent."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. Deployer keypair referenced by [provider] wallet is committed\n"
+            "2. Repository is forked, mirrored or leaked\n"
+            "3. Attacker uses the key (often the upgrade authority) to deploy a\n"
+            "   malicious program build to the same program ID"
+        )
@@ -0,0 +1,195 @@
+"""
+ANCHOR-034: Unprotected Authority Initialization
+
+The instruction that creates a program's state usually also decides who
+controls it. When anyone can call it, the authority it stores is whoever
+got there first:
+
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.authority = ctx.accounts.authority.key();   // any signer
+        Ok(())
+    }
+
+    #[derive(Accounts)]
+    pub struct Initialize<'info> {
+        #[account(init, payer = authority, space = 8 + Pool::INIT_SPACE)]
+        pub pool: Account<'info, Pool>,
+        #[account(mut)]
+        pub authority: Signer<'info>,
+    }
+
+For every account an instruction creates (`init`, `zero`) whose authority-
+like field (`authority`, `admin`, `owner`, `operator`, `manager`) the body
+assigns, the rule looks for a gate on the caller: an `address`, `has_one`
+or key-comparing `constraint` on one of the instruction's accounts, the
+program's upgrade authority (`program_data.upgrade_authority_address`), or
+a key comparison in the body. Without one it reports:
+
+  - an authority taken from an account that does not sign, an instruction
+    argument or remaining_accounts (High): the caller installs any key,
+    including one it front-runs the deployer with
+  - an authority taken from the signer that is never emitted or logged
+    (Medium): the first caller becomes the authority and nothing on-chain
+    shows who initialized, so a front-run goes unnoticed. `owner` fields
+    set to the signer are the per-user pattern and are not reported
+
+Authority writes on existing accounts are ANCHOR-016's.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+from scanner.patterns.privileged_signer import AUTHORITY_FIELD_RE
+from scanner.patterns.validation import _balanced
+
+ALIAS_RE = re.compile(
+    r"let\s+(?:mut\s+)?(\w+)\s*(?::[^=]+)?=\s*&?\s*(?:mut\s+)?ctx\s*\.\s*accounts\s*\.\s*(\w+)"
+)
+WRITE_RE = re.compile(r"(?:\bctx\s*\.\s*accounts\s*\.\s*)?\b(\w+)\s*\.\s*(\w+)\s*=(?!=)\s*([^;]+);")
+ACCOUNT_KEY_RE = re.compile(r"\bctx\s*\.\s*accounts\s*\.\s*(\w+)\s*\.\s*(?:key\s*\(\s*\)|key\b)")
+KEY_COMPARISON_RE = re.compile(r"\bkey\b[^;{]*(?:==|!=)|(?:==|!=)[^;{]*\bkey\b|\brequire_keys_(?:eq|neq)!")
+UPGRADE_AUTHORITY_RE = re.compile(r"\bupgrade_authority_address\b|\bprogram_data\b")
+RECORD_RE = re.compile(r"\b(?:emit|emit_cpi|msg)!\s*\(")
+
+
+class AuthorityInitPattern(VulnerabilityPattern):
+    id = "ANCHOR-034"
+    name = "Unprotected Authority Initialization"
+    severity = "High"
+    category = "init"
+    remediation_effort = "localized"
+    description = (
+        "An initialize-style instruction anyone can call stores an authority "
+        "taken from an unsigned account or argument, or from the caller "
+        "without recording it, so initialization can be front-run."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        facts = Facts(content)
+        for ix in facts.instructions():
+            accounts = {a.name: a for a in facts.accounts_of(ix)}
+            created = {n for n, a in accounts.items() if a.has("init") or a.has("zero")}
+            if not created or self._gated(ix, accounts):
+                continue
+            aliases = {m.group(1): m.group(2) for m in ALIAS_RE.finditer(ix.body) if m.group(2) in accounts}
+            for m in WRITE_RE.finditer(ix.body):
+                target = aliases.get(m.group(1), m.group(1))
+                if target not in created or not AUTHORITY_FIELD_RE.match(m.group(2)):
+                    continue
+                problem = self._problem(m.group(3), ix, accounts, aliases, m.group(2))
+                if not problem:
+                    continue
+                severity, text = problem
+                line = self._get_line_number(content, ix.body_start + m.start())
+                findings.append(self._finding(
+                    file_path, content, line, severity,
+                    f"'{ix.name}' creates '{target}' and sets {target}.{m.group(2)} {text}; nothing restricts "
+                    f"who may call it",
+                ))
+                break
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    def _gated(self, ix, accounts: dict) -> bool:
+        """Whether the instruction restricts its caller to a known key."""
+        for account in accounts.values():
+            for c in account.constraints:
+                if c.kind in ("address", "has_one"):
+                    return True
+                if c.kind == "constraint" and KEY_COMPARISON_RE.search(c.value):
+                    return True
+                if UPGRADE_AUTHORITY_RE.search(c.value):
+                    return True
+        if UPGRADE_AUTHORITY_RE.search(ix.body):
+            return True
+        return any(KEY_COMPARISON_RE.search(stmt) and re.search(rf"==|!=|\b{self._guards()}!", stmt)
+                   for stmt in re.split(r"[;{}]", ix.body))
+
+    def _problem(self, value: str, ix, accounts: dict, aliases: dict, field: str):
+        """(severity, how the value was chosen) of an unsafe authority value, None when it is safe."""
+        value = " ".join(value.split())
+        m = ACCOUNT_KEY_RE.search(value)
+        source = m.group(1) if m else None
+        if not source:
+            bare = re.match(r"(\w+)\s*\.\s*key\b", value)
+            source = aliases.get(bare.group(1)) if bare else None
+        if source in accounts:
+            if not accounts[source].is_signer:
+                return "High", f"from '{source}', an account that never signs"
+            # A signer owning the account it creates is the per-user pattern, not a global authority
+            if re.match(r"\w*owner", field) or self._recorded(ix.body, source, field):
+                return None
+            return "Medium", (f"from the signer '{source}' without emitting or logging it, so the first caller "
+                              f"becomes the authority unnoticed")
+        if re.search(r"\bremaining_accounts\b", value):
+            return "High", "from remaining_accounts"
+        arg = next((a for a in ix.args if re.fullmatch(rf"\*?{re.escape(a)}", value)
+                    and re.search(rf"\b{re.escape(a)}\s*:\s*Pubkey\b", ix.params)), None)
+        if arg:
+            return "High", f"from instruction argument '{arg}'"
+        return None
+
+    @staticmethod
+    def _recorded(body: str, source: str, field: str) -> bool:
+        return any(re.search(rf"\b(?:{re.escape(source)}|{re.escape(field)})\b", _balanced(body, m.end() - 1))
+                   for m in RECORD_RE.finditer(body))
+
+    def _finding(self, file_path, content, line, severity, message) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=severity,
+            file=file_path,
+            line=line,
+            description=f"{message}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "Program deployed; deployer's initialize transaction is in the mempool",
+                "after": "Attacker's initialize lands first with the attacker as authority",
+                "damage": "Attacker controls the program's configuration; the deployer must redeploy.",
+            },
+            impact={
+                "attack_cost": "Transaction fees and rent for the created account",
+                "exploitability": "Medium — needs a race with the deployer's initialize",
+                "breach_cost_context": "Initialization front-running is a standard audit finding for singleton configs.",
+            },
+            anchor_versions_affected="All versions (developer-side pattern)",
+            ecosystem_recommendations=[
+                "Restrict initialize to the program's upgrade authority via program_data",
+                "Take the authority from a signer, never from an argument or unchecked account",
+                "Emit an event with the authority when state is initialized",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Only let the upgrade authority initialize, and record who did:\n"
+            "  #[account(constraint = program_data.upgrade_authority_address == Some(authority.key()))]\n"
+            "  pub program_data: Account<'info, ProgramData>,\n"
+            "  pub authority: Signer<'info>,\n"
+            "  ..\n"
+            "  pool.authority = ctx.accounts.authority.key();\n"
+            "  emit!(PoolInitialized { pool: pool.key(), authority: pool.authority });"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Creating an account proves only that the caller paid for it. Unless "
+            "the instruction checks who the caller is, the authority it stores is "
+            "chosen by whoever initializes first, and without an event nothing "
+            "shows that it was not the deployer."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. The program is deployed; its state still has to be initialized\n"
+            "2. Attacker watches for the deployment and calls initialize first\n"
+            "3. The stored authority is the attacker's key\n"
+            "4. Attacker changes fees, pauses the program or drains admin-controlled vaults"
+        )
@@ -1,9 +1,17 @@
 """Base class for vulnerability detection patterns."""
 
+import fnmatch
+import hashlib
 import re
 from dataclasses import dataclass, field
 from typing import Optional
 
+from scanner.budget import DEFAULT_DEPTH
+from scanner.patterns.guards import guard_alternation
+
+# Remediation effort classes, cheapest first
+EFFORT_LEVELS = ("mechanical", "localized", "architectural")
+
 
 @dataclass
 class Finding:
@@ -24,6 +32,39 @@ class Finding:
     reference: str = "https://github.com/solana-foundation/anchor/pull/4229"
     anchor_versions_affected: str = "0.25.0 - 0.30.x"
     ecosystem_recommendations: list = field(default_factory=list)
+    remediation_effort: str = ""
+    # Set when an inline comment or baseline entry covers this finding;
+    # see scanner/suppression.py
+    suppression: Optional[dict] = None
+    # Runtime features/program versions the finding depends on, and those of
+    # them the target cluster lacks; see scanner/features.py
+    feature_gates: list = field(default_factory=list)
+    inapplicable: list = field(default_factory=list)
+    # Findings of generic rules at the same location folded into this one;
+    # see scanner/dedup.py
+    supporting: list = field(default_factory=list)
+    # Why the file is generated code, when it is; see scanner/generated.py
+    generated: str = ""
+    # Commit and author that last touched the flagged line (scan --blame);
+    # see scanner/blame.py
+    blame: Optional[dict] = None
+    # Rule-specific structured payload (PdaCollision, Invariant, CpiTarget, SeedCollision);
+    # see scanner/patterns/details.py
+    details: Optional[object] = None
+    # Attack steps for critical findings with a resolved taint path;
+    # see scanner/narrative.py
+    narrative: list = field(default_factory=list)
+    # Token/lamport legs of the handler (source, destination, authority);
+    # see scanner/flows.py
+    flow: list = field(default_factory=list)
+    # Balances of the vaults the finding guards (scan --rpc), None when not
+    # looked up; see scanner/value_at_risk.py
+    value_at_risk: Optional[dict] = None
+    # Innermost struct / enum / fn (`Type::method` inside an impl) around the
+    # flagged line, and the finding's index among same-text findings of the
+    # rule in that item; both are part of the fingerprint (see scope_findings)
+    scope: str = ""
+    occurrence: int = 0
 
     def to_dict(self) -> dict:
         """Convert finding to dictionary for JSON serialization."""
@@ -43,8 +84,90 @@ class Finding:
             "reference": self.reference,
             "anchor_versions_affected": self.anchor_versions_affected,
             "ecosystem_recommendations": self.ecosystem_recommendations,
+            "remediation_effort": self.remediation_effort,
+            "suppression": self.suppression,
+            "feature_gates": self.feature_gates,
+            "inapplicable": self.inapplicable,
+            "supporting": self.supporting,
+            "generated": self.generated,
+            "blame": self.blame,
+            "details": self.details.to_dict() if self.details else None,
+            "narrative": self.narrative,
+            "flow": self.flow,
+            "value_at_risk": self.value_at_risk,
+            "scope": self.scope,
+            "occurrence": self.occurrence,
         }
 
+    def fingerprint(self) -> str:
+        """Stable identity for triage and baselines: rule + file + enclosing item + flagged line text.
+
+        Uses the line's content rather than its number so the fingerprint
+        survives unrelated edits above the finding. The enclosing item and
+        the occurrence index keep identical lines apart: the same field
+        declaration in two accounts structs is two findings.
+        """
+        key = f"{self.id}|{self.file}|{self.flagged_text()}"
+        if self.scope:
+            key += f"|{self.scope}"
+        if self.occurrence:
+            key += f"#{self.occurrence}"
+        return hashlib.sha256(key.encode()).hexdigest()[:16]
+
+    def flagged_text(self) -> str:
+        """The flagged source line, stripped; the line number when the snippet has none."""
+        for snip_line in self.code_snippet.split("\n"):
+            if snip_line.startswith(">>>"):
+                flagged = snip_line.split("|", 1)[-1].strip()
+                if flagged:
+                    return flagged
+        return str(self.line)
+
+
+ITEM_RE = re.compile(r"\b(struct|enum|trait|mod|fn)\s+(\w+)[^;{]*\{")
+
+
+def enclosing_items(content: str) -> list[tuple[int, int, str]]:
+    """(start, end, label) of the named items of a unit: `struct Name`, `fn Type::name`, ...
+
+    Functions inside an `impl` block are labelled with the implementing type,
+    so same-named methods of different types are different items.
+    """
+    impls = VulnerabilityPattern._find_impl_blocks(content)
+    items = []
+    for m in ITEM_RE.finditer(content):
+        line_start = content.rfind("\n", 0, m.start()) + 1
+        if "//" in content[line_start:m.start()]:
+            continue
+        end = VulnerabilityPattern._find_block_end(content, m.end() - 1)
+        if end == -1:
+            continue
+        kind, name = m.group(1), m.group(2)
+        if kind == "fn":
+            owner = [t for s, e, t in impls if s <= m.start() < e]
+            if owner:
+                name = f"{owner[-1]}::{name}"
+        items.append((m.start(), end, f"{kind} {name}"))
+    return items + [(s, e, f"impl {t}") for s, e, t in impls]
+
+
+def scope_findings(findings: list, content: str, items: Optional[list] = None) -> None:
+    """Set each finding's scope (innermost enclosing item) and occurrence among identical ones."""
+    items = enclosing_items(content) if items is None else items
+    starts = [0]
+    for text_line in content.split("\n"):
+        starts.append(starts[-1] + len(text_line) + 1)
+    seen = {}
+    for finding in sorted(findings, key=lambda f: f.line):
+        # The line's last character: inside an item whose header is on the line
+        line = min(max(finding.line, 1), len(starts) - 1)
+        at = max(starts[line - 1], starts[line] - 2)
+        inner = [i for i in items if i[0] <= at < i[1]]
+        finding.scope = min(inner, key=lambda i: i[1] - i[0])[2] if inner else ""
+        key = (finding.id, finding.file, finding.scope, finding.flagged_text())
+        finding.occurrence = seen.get(key, 0)
+        seen[key] = finding.occurrence + 1
+
 
 class VulnerabilityPattern:
     """Base class for vulnerability detection patterns."""
@@ -54,11 +177,69 @@ class VulnerabilityPattern:
     severity: str = ""
     description: str = ""
     reference: str = "https://github.com/solana-foundation/anchor/pull/4229"
+    # Rule namespace segment, e.g. "cpi" -> shield::cpi::ANCHOR-007
+    category: str = "general"
+    # Typical fix size: "mechanical" (one-line constraint/type change),
+    # "localized" (handler or struct edit), "architectural" (state/flow redesign)
+    remediation_effort: str = "localized"
+    # "expensive" marks flow/dataflow passes that a scan over its resource
+    # budget skips (see scanner/budget.py)
+    cost: str = "standard"
+    # Feature gates (scanner.features.KNOWN_FEATURES) every finding of this
+    # rule depends on; gates seen in the flagged code are added per finding
+    feature_gates: tuple = ()
+    # Project assertion macros treated like require!/assert!; set by the
+    # engine for each scan (see patterns/guards.py)
+    guard_macros: tuple = ()
+    # Calls followed by interprocedural summaries; set by the engine for
+    # each scan (--interprocedural-depth)
+    max_depth: int = DEFAULT_DEPTH
+    # Project options of this rule from anchor-shield.toml; set by the
+    # engine for each scan (see scanner/config.py)
+    options: dict = {}
+    # Workspace `[profile.release] overflow-checks`, None when no manifest
+    # was found; set by the engine for each scan (see scanner/config.py)
+    overflow_checks: Optional[bool] = None
+    # Sources of the target's dependencies (scan --scan-deps), None when not
+    # pulled in; set by the engine for each scan (see scanner/dependencies.py)
+    dependencies = None
+
+    @property
+    def qualified_id(self) -> str:
+        return f"shield::{self.category}::{self.id}"
+
+    def matches(self, selector: str) -> bool:
+        """True if a rule selector (ID or namespace glob) selects this pattern."""
+        selector = selector.strip()
+        return any(
+            fnmatch.fnmatchcase(candidate.lower(), selector.lower())
+            for candidate in (self.id, self.qualified_id)
+        )
+
+    def _guards(self) -> str:
+        """Regex alternation of guard macro names, without the trailing `!`."""
+        return guard_alternation(self.guard_macros)
 
     def scan(self, file_path: str, content: str) -> list[Finding]:
         """Scan a file for this vulnerability pattern."""
         raise NotImplementedError
 
+    def scan_workspace(self, root: str) -> list[Finding]:
+        """Scan workspace-level files (e.g. Anchor.toml) under a scan root.
+
+        Called once per directory scan. Most patterns only look at Rust
+        sources and keep this default.
+        """
+        return []
+
+    def estimate_effort(self, finding: Finding) -> str:
+        """Classify the remediation effort for one finding.
+
+        Defaults to the pattern-wide estimate; patterns whose fix size depends
+        on the finding's shape override this.
+        """
+        return self.remediation_effort
+
     def get_fix_recommendation(self) -> str:
         """Return actionable fix recommendation."""
         raise NotImplementedError
@@ -88,6 +269,90 @@ class VulnerabilityPattern:
             snippet_lines.append(f"{prefix}{i + 1:4d} | {lines[i]}")
         return "\n".join(snippet_lines)
 
+    @staticmethod
+    def _find_block_end(content: str, brace_start: int) -> int:
+        """Return the index just past the brace matching content[brace_start].
+
+        Returns -1 if the block is unbalanced.
+        """
+        depth = 0
+        i = brace_start
+        while i < len(content):
+            if content[i] == "{":
+                depth += 1
+            elif content[i] == "}":
+                depth -= 1
+                if depth == 0:
+                    return i + 1
+            i += 1
+        return -1
+
+    @staticmethod
+    def _find_functions(content: str) -> list[dict]:
+        """Find all fn items with a body, using brace-counting (not regex).
+
+        Returns list of dicts with: name, params, ret, body, line, body_start,
+        end. `body` excludes the outer braces; `body_start` is the offset of
+        the first character inside them.
+        """
+        results = []
+        for m in re.finditer(r"\bfn\s+(\w+)\s*(?:<[^(){};]*>)?\s*\(", content):
+            # Skip `fn` mentioned in line comments
+            line_start = content.rfind("\n", 0, m.start()) + 1
+            if "//" in content[line_start:m.start()]:
+                continue
+            # Match the parameter list parentheses
+            depth = 1
+            i = m.end()
+            while i < len(content) and depth > 0:
+                if content[i] == "(":
+                    depth += 1
+                elif content[i] == ")":
+                    depth -= 1
+                i += 1
+            if depth != 0:
+                continue
+            params = content[m.end():i - 1]
+            # Signature ends at the body brace or a ';' (trait method declaration);
+            # a ';' inside an array type (`-> Result<[u64; 3]>`) does not end it
+            sig_end, brackets = i, 0
+            while sig_end < len(content) and (content[sig_end] not in "{;" or brackets):
+                brackets += {"[": 1, "]": -1}.get(content[sig_end], 0)
+                sig_end += 1
+            if sig_end >= len(content) or content[sig_end] == ";":
+                continue
+            ret = content[i:sig_end]
+            ret = re.sub(r"\bwhere\b.*", "", ret, flags=re.DOTALL)
+            ret = ret.strip()
+            if ret.startswith("->"):
+                ret = ret[2:].strip()
+            end = VulnerabilityPattern._find_block_end(content, sig_end)
+            if end == -1:
+                continue
+            results.append({
+                "name": m.group(1),
+                "params": " ".join(params.split()),
+                "ret": ret,
+                "body": content[sig_end + 1:end - 1],
+                "line": content[:m.start()].count("\n") + 1,
+                "body_start": sig_end + 1,
+                "end": end,
+            })
+        return results
+
+    @staticmethod
+    def _find_impl_blocks(content: str) -> list[tuple[int, int, str]]:
+        """Return (start, end, type_name) for each `impl [Trait for] Type { ... }`."""
+        blocks = []
+        for m in re.finditer(
+            r"\bimpl\s*(?:<[^>{]*>)?\s*(?:[\w:]+(?:<[^>{]*>)?\s+for\s+)?([\w:]+)\s*(?:<[^>{]*>)?\s*(?:where[^{]*)?\{",
+            content,
+        ):
+            end = VulnerabilityPattern._find_block_end(content, m.end() - 1)
+            if end != -1:
+                blocks.append((m.start(), end, m.group(1).split("::")[-1]))
+        return blocks
+
     @staticmethod
     def _find_derive_accounts_structs(content: str) -> list[tuple[str, str, int]]:
         """Find all #[derive(Accounts)] structs using brace-counting (not regex).
@@ -122,6 +387,32 @@ class VulnerabilityPattern:
                 results.append((struct_name, struct_body, start_line))
         return results
 
+    ACCOUNT_ATTR_RE = re.compile(
+        r"#\[account\(((?:[^()]*|\((?:[^()]*|\([^()]*\))*\))*)\)\]",
+        re.DOTALL,
+    )
+
+    @staticmethod
+    def _account_clauses(attrs: str) -> list[str]:
+        """Split the contents of every #[account(...)] in attrs on top-level commas."""
+        clauses = []
+        for m in VulnerabilityPattern.ACCOUNT_ATTR_RE.finditer(attrs):
+            depth = 0
+            current = ""
+            for ch in m.group(1):
+                if ch in "([{":
+                    depth += 1
+                elif ch in ")]}":
+                    depth -= 1
+                if ch == "," and depth == 0:
+                    clauses.append(current)
+                    current = ""
+                else:
+                    current += ch
+            if current.strip():
+                clauses.append(current)
+        return clauses
+
     @staticmethod
     def _parse_struct_fields(struct_body: str, struct_start: int) -> list[dict]:
         """Parse fields from a derive(Accounts) struct body.
@@ -0,0 +1,213 @@
+"""
+ANCHOR-044: Weak CHECK Justification
+
+Anchor refuses to build an `UncheckedAccount` or `AccountInfo` field
+without a `/// CHECK:` doc comment, meant to say why the missing type
+check is safe. The build only looks for the word, so the comments drift
+into formalities:
+
+    /// CHECK:
+    pub vault_authority: UncheckedAccount<'info>,
+    /// CHECK: PDA
+    pub escrow: AccountInfo<'info>,
+    /// CHECK: safe
+    pub oracle: AccountInfo<'info>,
+
+A useful justification names what compensates for the missing check —
+the `seeds`, `address`, `owner` or `constraint` on the field, the handler
+or helper that validates it, the CPI that checks it — or says the account
+is never read:
+
+    /// CHECK: PDA signer of the vault; address checked by seeds below.
+    /// CHECK: validated in check_oracle() against config.oracle.
+    /// CHECK: only receives lamports on close, never read.
+
+The rule reports (Low) every raw account field whose CHECK comment is
+empty, generic (a lone "PDA", "safe", "unchecked", "ok" or one of the
+project's own phrases) or mentions no validation, no code and no non-use.
+Fields with no CHECK comment at all are left to Anchor's build, which
+rejects them from 0.21 on; code older than that predates the convention.
+
+Projects that want the justification machine-readable set, in
+anchor-shield.toml:
+
+    [rules."ANCHOR-044"]
+    require-tag = true          # every CHECK must carry validated-by=
+    generic = ["trusted"]       # more phrases counted as generic
+
+and write `/// CHECK(validated-by=seeds): ...`, which Anchor's build
+accepts like any CHECK. Each name in `validated-by=a, b` must be a
+constraint present on the field (`seeds`, `address`, `owner`,
+`constraint`, `has_one`), a function defined in the file, or `cpi` /
+`unused`.
+"""
+
+import re
+from scanner.patterns.account_types import account_type
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+
+CHECK_RE = re.compile(r"^\s*CHECK\b\s*(?:\(([^)]*)\))?\s*:?\s*(.*)$", re.DOTALL)
+TAG_RE = re.compile(r"\bvalidated-by\s*=\s*([^)]*)")
+GENERIC = {
+    "pda", "safe", "this is safe", "unchecked", "unsafe", "ok", "fine", "todo", "none", "n/a", "na",
+    "account", "checked", "validated", "not checked", "no check", "no checks", "checked later",
+    "not needed", "no check needed", "no checks needed", "see below", "see above", "trust me",
+}
+MECHANISM_RE = re.compile(
+    r"\b(?:seeds?|pda derivation|derived|address|owner|owned|has_one|constraint|signer|signed|cpi|program|"
+    r"key|pubkey|discriminator|deserializ\w*|compared|matches|equal\w*|verif\w*|validat\w*\s+(?:in|by|against)|"
+    r"check\w*\s+(?:in|by|against)|sysvar|mint|token account)\b",
+    re.I,
+)
+UNUSED_RE = re.compile(
+    r"\b(?:not|never|no(?:thing)?)\s+(?:\w+\s+)?(?:read|written|deserialized|used|accessed|modified|data)\b|"
+    r"\bonly\s+(?:used|receives?|passed|for|as|to)\b|\bread[- ]only\b|\bdestination\b",
+    re.I,
+)
+CONSTRAINT_NAMES = {"seeds", "address", "owner", "constraint", "has_one"}
+TAG_KEYWORDS = {"cpi", "unused"}
+
+
+class CheckCommentPattern(VulnerabilityPattern):
+    id = "ANCHOR-044"
+    name = "Weak CHECK Justification"
+    severity = "Low"
+    category = "accounts"
+    remediation_effort = "mechanical"
+    description = (
+        "A raw UncheckedAccount/AccountInfo field's `/// CHECK:` comment is "
+        "empty or generic, or does not say what validates the account."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        facts = Facts(content)
+        require_tag = bool(self.options.get("require-tag", False))
+        generic = GENERIC | {self._normalized(str(p)) for p in self.options.get("generic", [])}
+        functions = {fn["name"] for fn in self._find_functions(content)}
+        for struct in facts.accounts_structs():
+            for account in struct.accounts:
+                if not account_type(account.type).is_raw:
+                    continue
+                problem = self._problem(account, generic, functions, require_tag)
+                if problem:
+                    findings.append(self._finding(
+                        file_path, content, account.line,
+                        f"In struct {struct.name}: '{account.name}' is {account.wrapper} "
+                        f"and its CHECK comment {problem}",
+                    ))
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    @staticmethod
+    def _justification(account):
+        """(tag, text) of the field's CHECK doc comment, None when it has none."""
+        for i, doc in enumerate(account.docs):
+            m = CHECK_RE.match(doc)
+            if m:
+                text = " ".join([m.group(2)] + list(account.docs[i + 1:]))
+                return (m.group(1) or "").strip(), " ".join(text.split())
+        return None
+
+    def _problem(self, account, generic: set, functions: set, require_tag: bool) -> str:
+        found = self._justification(account)
+        if found is None:
+            return ""
+        tag, text = found
+        if require_tag or tag:
+            problem = self._tag_problem(account, tag, functions)
+            if problem:
+                return problem
+            if tag:
+                return ""
+        if not self._normalized(text):
+            return "is empty"
+        if self._normalized(text) in generic:
+            return f"is generic (\"{text}\") and names nothing that validates it"
+        if MECHANISM_RE.search(text) or UNUSED_RE.search(text) or self._names_code(text, functions):
+            return ""
+        return f"(\"{text}\") does not say which constraint, check or CPI compensates for the missing type check"
+
+    @staticmethod
+    def _normalized(text: str) -> str:
+        """Lowercase words without punctuation, as generic phrases are compared."""
+        return " ".join(re.sub(r"[^\w/\s-]", " ", text.lower()).split())
+
+    @staticmethod
+    def _names_code(text: str, functions: set) -> bool:
+        """The comment points at code: a backticked or snake_case/path identifier, or a function."""
+        if re.search(r"`[^`]+`|\b\w+::\w+|\b[a-z]+_[a-z0-9_]+\b|\b[A-Z][A-Z0-9_]{2,}\b", text):
+            return True
+        return any(re.search(rf"\b{re.escape(fn)}\b", text) for fn in functions)
+
+    @staticmethod
+    def _tag_problem(account, tag: str, functions: set) -> str:
+        m = TAG_RE.search(tag)
+        if not m:
+            return "has no `CHECK(validated-by=...)` tag, which anchor-shield.toml requires"
+        names = [n.strip() for n in re.split(r"[,\s]+", m.group(1)) if n.strip()]
+        if not names:
+            return "has an empty validated-by= tag"
+        kinds = {c.kind for c in account.constraints}
+        for name in names:
+            if name in TAG_KEYWORDS or name in functions or (name in CONSTRAINT_NAMES and name in kinds):
+                continue
+            if name in CONSTRAINT_NAMES:
+                return f"says validated-by={name}, but the field has no `{name}` constraint"
+            return f"says validated-by={name}, which is neither a constraint on the field nor a function in this file"
+        return ""
+
+    def _finding(self, file_path, content, line, message) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=self.severity,
+            file=file_path,
+            line=line,
+            description=f"{message}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "`/// CHECK: PDA` on the escrow authority; reviewers assume seeds pin it",
+                "after": "The seeds were dropped in a refactor and the comment still reads the same",
+                "damage": "An unvalidated raw account passes review because its justification says nothing checkable.",
+            },
+            impact={
+                "attack_cost": "None — review-quality issue",
+                "exploitability": "Low — hides a missing check rather than being one",
+                "breach_cost_context": "Raw accounts are where owner and address checks go missing.",
+            },
+            anchor_versions_affected="0.21+ (CHECK comments required for raw accounts)",
+            ecosystem_recommendations=[
+                "Name the constraint, handler check or CPI that validates each raw account",
+                "Require CHECK(validated-by=...) tags so tooling can verify the claim",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Say what validates the account, pointing at the code:\n"
+            "  /// CHECK(validated-by=seeds): vault signer PDA, address fixed by seeds.\n"
+            "  #[account(seeds = [b\"vault\", pool.key().as_ref()], bump)]\n"
+            "  pub vault_authority: UncheckedAccount<'info>,"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Anchor enforces that a CHECK comment exists, not what it says. A "
+            "justification that names no constraint, function or CPI cannot be "
+            "checked against the code, so it stays the same when the check it "
+            "once described is removed."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. `/// CHECK: PDA` documents an AccountInfo the seeds once pinned\n"
+            "2. A refactor moves the derivation into the handler and then drops it\n"
+            "3. Review sees the CHECK comment and moves on\n"
+            "4. Attacker passes an account of their own where the PDA was expected"
+        )
@@ -0,0 +1,228 @@
+"""
+ANCHOR-057: Checked Arithmetic Unwrapped
+
+`checked_add` and friends return None on overflow so the program can fail
+with an error of its own. Unwrapping the result throws that away:
+
+    let total = pool.total_staked.checked_add(amount).unwrap();   // overflow: panic
+
+The overflow still stops the instruction, but as a panic — `Program failed
+to complete`, no error code, nothing the client or an integrator can match
+on — which is exactly what the checked call was written to avoid. When an
+operand comes from the caller, anyone can trigger that abort on purpose.
+
+The rule reports every `.checked_{add,sub,mul,div,rem,pow,neg,shl,shr}(..)`
+followed by `.unwrap()` or `.expect(..)`, in the #[program] handlers and in
+the local functions they reach within the interprocedural depth. For each
+site it counts the instructions that reach it with an operand derived from
+their arguments (followed through `let` bindings and helper parameters, as
+in ANCHOR-056):
+
+  - Medium: at least one instruction passes a caller-chosen operand; the
+    finding lists them, so a helper shared by ten instructions shows as
+    ten abortable entry points;
+  - Low: only program state reaches it.
+
+The fix maps the None to the program's own error. When the crate already
+declares an #[error_code] variant for overflows (`MathOverflow`,
+`CalculationFailure`...) the suggestion uses it.
+
+Supersedes ANCHOR-056 at the same line: the unwrap is the same panic,
+reported here with the arithmetic-specific fix.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+from scanner.patterns.handler_panic import HandlerPanicPattern
+from scanner.patterns.math_summary import _blank
+from scanner.patterns.saturating_sub import SaturatingSubPattern
+from scanner.patterns.signatures import result_note, return_types
+from scanner.patterns.validation import CALL_RE, _param_names
+
+CHECKED_RE = re.compile(r"\.\s*checked_(add|sub|mul|div|rem|pow|neg|shl|shr)\s*\(")
+UNWRAPPED_RE = re.compile(r"\s*\.\s*(unwrap\s*\(\s*\)|expect\s*\()")
+ERROR_ENUM_RE = re.compile(r"#\[\s*error(?:_code)?\s*(?:\([^)]*\))?\s*\]\s*(?:#\[[^\]]*\]\s*)*pub\s+enum\s+(\w+)\s*\{([^}]*)\}")
+OVERFLOW_VARIANT_RE = re.compile(r"overflow|underflow|math|arithmetic|calculation", re.I)
+
+
+class CheckedUnwrapPattern(VulnerabilityPattern):
+    id = "ANCHOR-057"
+    name = "Checked Arithmetic Unwrapped"
+    severity = "Medium"
+    category = "arithmetic"
+    remediation_effort = "mechanical"
+    description = (
+        "checked_add(..).unwrap() and friends turn an overflow into a panic "
+        "instead of a program error, abortable by caller-chosen operands."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        code = _blank(content)
+        if not CHECKED_RE.search(code):
+            return []
+        functions = self._find_functions(code)
+        by_name = {}
+        for fn in functions:
+            by_name.setdefault(fn["name"], []).append(fn)
+        instructions = Facts(content, file_path).instructions()
+        entrypoints = [ix for ix in instructions if ix.is_entrypoint]
+        roots = [(ix.name, fn, ix.args) for ix in entrypoints or instructions
+                 for fn in by_name.get(ix.name, []) if fn["body_start"] == ix.body_start]
+        if not entrypoints:
+            # Same roots as ANCHOR-056 in a unit split off its program; helpers count no instruction
+            called = {m.group(1) for fn in functions for m in CALL_RE.finditer(fn["body"])}
+            roots += [(None, fn, []) for fn in functions
+                      if fn["name"] not in called and not any(fn is root for _, root, _ in roots)
+                      and re.search(r"\bpub\s+fn\s+" + fn["name"] + r"\b", code)]
+
+        # line -> site, with the instructions reaching it and those reaching it with caller input
+        sites, found = {}, {}
+        for ix_name, fn, args in roots:
+            reached = []
+            self._walk(fn, set(args), [fn["name"]], by_name, code, reached,
+                       HandlerPanicPattern._guards_of(code, fn))
+            for visited, tainted, path in reached:
+                if visited["body_start"] not in found:
+                    found[visited["body_start"]] = self._sites(visited, code)
+                for line, checked, method, operands in found[visited["body_start"]]:
+                    site = sites.setdefault(line, {
+                        "fn": visited["name"], "path": path, "checked": checked, "method": method,
+                        "instructions": [], "abortable": [],
+                    })
+                    if ix_name and ix_name not in site["instructions"]:
+                        site["instructions"].append(ix_name)
+                    if ix_name and HandlerPanicPattern._mentions(operands, tainted) \
+                            and ix_name not in site["abortable"]:
+                        site["abortable"].append(ix_name)
+
+        error = self._overflow_error(code)
+        returns = return_types(code)
+        findings = [
+            self._finding(file_path, content, line, site, error, returns.get(site["fn"], ""))
+            for line, site in sites.items()
+        ]
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    def _walk(self, fn: dict, tainted: set, path: list, by_name: dict, code: str, reached: list,
+              guards: str = "", seen: set = None):
+        """(function, tainted names, call path) for the root and every local function it reaches."""
+        seen = set() if seen is None else seen
+        key = (fn["body_start"], frozenset(tainted))
+        if key in seen:
+            return
+        seen.add(key)
+        tainted = HandlerPanicPattern._derived(fn["body"], tainted)
+        reached.append((fn, tainted, path))
+        if len(path) >= self.max_depth:
+            return
+        calls = fn["body"] + "\n" + guards
+        for m in CALL_RE.finditer(calls):
+            for callee in by_name.get(m.group(1), []):
+                if callee["name"] in path:
+                    continue
+                args = HandlerPanicPattern._call_args(calls, m.end() - 1)
+                params = [name for name, _ in _param_names(callee["params"])]
+                passed = {p for p, a in zip(params, args) if HandlerPanicPattern._mentions(a, tainted)}
+                self._walk(callee, passed, path + [callee["name"]], by_name, code, reached, seen=seen)
+
+    def _sites(self, fn: dict, code: str) -> list:
+        """(line, checked call, unwrap call, operand text) of each unwrapped checked operation."""
+        result = []
+        body = fn["body"]
+        for stmt_m in re.finditer(r"[^;{}]+", body):
+            stmt = stmt_m.group(0)
+            for call in CHECKED_RE.finditer(stmt):
+                argument = SaturatingSubPattern._argument(stmt, call.end() - 1)
+                close = call.end() + len(argument)
+                unwrap = UNWRAPPED_RE.match(stmt, close + 1)
+                if close >= len(stmt) or not unwrap:
+                    continue
+                receiver = HandlerPanicPattern._receiver(stmt, call.start())
+                argument = SaturatingSubPattern._text(argument)
+                method = "unwrap()" if unwrap.group(1).startswith("unwrap") else "expect(..)"
+                checked = f"{HandlerPanicPattern._last_call(receiver)}.checked_{call.group(1)}({argument})"
+                line = self._get_line_number(code, fn["body_start"] + stmt_m.start() + unwrap.start(1))
+                result.append((line, checked, method, f"{receiver} {argument}"))
+        return result
+
+    @staticmethod
+    def _overflow_error(code: str) -> str:
+        """`Enum::Variant` of the crate's overflow error, else ""."""
+        for m in ERROR_ENUM_RE.finditer(code):
+            for variant in re.findall(r"^\s*(\w+)\s*[,=]?", m.group(2), re.M):
+                if OVERFLOW_VARIANT_RE.search(variant):
+                    return f"{m.group(1)}::{variant}"
+        return ""
+
+    def _finding(self, file_path, content, line, site, error, returns) -> Finding:
+        path = site["path"]
+        where = f"In {site['fn']}" + (f", reached from {' → '.join(path[:-1])}" if len(path) > 1 else "")
+        abortable, reaching = site["abortable"], site["instructions"]
+        if abortable:
+            severity = self.severity
+            reach = (f"{len(abortable)} of the {len(reaching)} instruction(s) reaching it pass a "
+                     f"caller-chosen operand and can be aborted at will: {', '.join(abortable)}")
+        else:
+            severity = "Low"
+            reach = (f"reached by {len(reaching)} instruction(s) with operands from program state only"
+                     if reaching else "no instruction in this unit reaches it with caller input")
+        code_name = error or "ErrorCode::MathOverflow"
+        fix = f"Map the overflow to a program error:\n  {site['checked']}.ok_or({code_name})?"
+        if not error:
+            fix += "\nThe crate declares no overflow error yet: add `MathOverflow` to its #[error_code] enum."
+        fix += result_note(site["fn"], returns)
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=severity,
+            file=file_path,
+            line=line,
+            description=(
+                f"{where}: `{site['checked']}.{site['method']}` panics on overflow instead of returning an error; {reach}."
+            ),
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=fix,
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "pool.total_staked = u64::MAX - 10, stake(amount = 11)",
+                "after": "instruction aborted: Program failed to complete",
+                "damage": "The overflow is caught but surfaces as a panic with no error code.",
+            },
+            impact={
+                "attack_cost": "One transaction with an operand large enough to overflow",
+                "exploitability": "Medium — availability; any caller controlling an operand can abort the instruction",
+                "breach_cost_context": "Panics in shared paths (crank, settle, liquidate) block every user "
+                                       "behind them and give integrators nothing to handle.",
+            },
+            anchor_versions_affected="All versions (program-side arithmetic)",
+            ecosystem_recommendations=[
+                "Map checked arithmetic to a named error: `.ok_or(ErrorCode::MathOverflow)?`",
+                "Deny clippy::unwrap_used in program crates so new unwraps fail CI",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Map the overflow to a program error:\n"
+            "  let total = pool.total_staked\n"
+            "      .checked_add(amount)\n"
+            "      .ok_or(ErrorCode::MathOverflow)?;"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "A checked operation detects the overflow and returns None; unwrap "
+            "turns that None into a panic. The instruction still fails, but "
+            "without the error code the checked call was there to produce."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. stake adds the caller's amount with checked_add(..).unwrap()\n"
+            "2. Attacker sends an amount that overflows the running total\n"
+            "3. The instruction panics: no error code, nothing logged about the input\n"
+            "4. In a shared helper, every instruction reaching it can be aborted the same way"
+        )
@@ -0,0 +1,305 @@
+"""
+ANCHOR-042: Enforcement Left To Client
+
+A check that moved off-chain is usually still described where it used to
+be. The comment or IDL doc says the client takes care of it:
+
+    /// Withdraw from the vault. The frontend validates amount against the user's share.
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+
+or the constraint itself is still there, commented out:
+
+    #[account(
+        mut,
+        // constraint = vault.owner == pool.key(),
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+Anyone can build a transaction without the client, so an invariant only
+the client keeps is not kept. The rule reports:
+
+  - comments and doc comments (the ones Anchor copies into the IDL
+    included) saying the client, frontend, UI, SDK or backend validates,
+    checks or ensures something, or that it is checked off-chain, when no
+    constraint or require/assert/if in the instruction mentions the field
+    or argument the comment is about
+  - constraint clauses (`constraint`, `has_one`, `address`, `seeds`,
+    `owner`, `token::*`) commented out of a live account's attribute, when
+    no live constraint or handler check relates the account to what the
+    clause named
+  - whole `#[account(...)]` fields commented out of a struct, unless a
+    handler reads `remaining_accounts` and validates the account there —
+    Raydium's commented tick-array bitmap and support-mint accounts are
+    checked by key in the handlers that take them as remaining accounts
+
+A comment is about the arguments and accounts its sentence on the client
+names (`min amount out` names `min_amount_out`), else about the field it
+documents; a handler comment naming neither is not reported.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+
+CLIENT = r"(?:client|front[- ]?end|ui|sdk|off[- ]?chain|backend|dapp|web\s*app)"
+CLIENT_CLAIM_RE = re.compile(
+    rf"\b{CLIENT}s?\b[^.;]*?\b(?:validat|check|enforc|verif|ensur|guarant|sanitiz|clamp|is\s+responsible|"
+    rf"takes?\s+care|makes?\s+sure|rejects?|handles?)\w*"
+    rf"|\b(?:validated|checked|enforced|verified|ensured|guaranteed|sanitized|clamped)\s+"
+    rf"(?:by|on|in)\s+(?:the\s+)?{CLIENT}s?\b"
+    rf"|\b(?:checked|validated|enforced|verified)\s+off[- ]?chain\b"
+    rf"|\btrust(?:s|ed|ing)?\s+(?:the\s+)?{CLIENT}s?\b",
+    re.I,
+)
+COMMENTED_CLAUSE_RE = re.compile(
+    r"^\s*(?:#\[account\(\s*)?(constraint|has_one|address|seeds|owner|token::\w+|associated_token::\w+)\s*=\s*(.*?)"
+    r"\s*(?:\)\]|,)?\s*$"
+)
+COMMENTED_FIELD_RE = re.compile(r"^\s*(?:pub\s+)?(\w+)\s*:\s*([\w:]+\s*<.*>|[\w:]+)\s*,?\s*$")
+CHECK_RE = r"\b(?:require\w*|assert\w*)!\s*\(([^;]*)\)\s*;|\bif\s+([^{;]*)\{"
+KEYWORDS = {
+    "key", "as_ref", "to_account_info", "load", "unwrap", "pubkey", "self", "ctx", "accounts",
+    "as_bytes", "bump", "mut", "true", "false", "info",
+}
+
+
+class ClientEnforcementPattern(VulnerabilityPattern):
+    id = "ANCHOR-042"
+    name = "Enforcement Left To Client"
+    severity = "Medium"
+    category = "constraints"
+    remediation_effort = "localized"
+    description = (
+        "A comment leaves an invariant to the client, or an account "
+        "constraint is commented out, and no on-chain check takes its place."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        facts = Facts(content)
+        lines = content.splitlines()
+        structs = self._struct_spans(content, facts)
+        handlers = [(ix, ix.line, ix.line + content.count("\n", ix.body_start, ix.body_start + len(ix.body)))
+                    for ix in facts.instructions()]
+        for first, last, block in self._comment_blocks(lines):
+            struct = next((s for s, start, end in structs if start <= first <= end), None)
+            if struct is not None:
+                findings += self._commented_out(file_path, content, facts, struct, first, last, block)
+            text = " ".join(block)
+            claim = CLIENT_CLAIM_RE.search(text)
+            if not claim:
+                continue
+            field, ixs = None, []
+            if struct is not None:
+                field = next((a for a in struct.accounts if a.line > last), None)
+                ixs = [ix for ix in facts.instructions() if ix.accounts_struct == struct.name]
+            else:
+                ix = next((ix for ix, start, end in handlers if start <= first <= end), None) \
+                    or self._documented_handler(lines, last, handlers)
+                if ix is None:
+                    continue
+                ixs = [ix]
+                struct = next((s for s, _, _ in structs if s.name == ix.accounts_struct), None)
+            accounts = struct.accounts if struct else []
+            names = [arg for ix in ixs for arg in ix.args] + [a.name for a in accounts]
+            sentence = self._sentence(text, claim)
+            subjects = [n for n in dict.fromkeys(names) if self._mentions(sentence, n)]
+            if not subjects and field is not None:
+                subjects = [field.name]
+            checks = self._checks(content, facts, struct, ixs)
+            for subject in subjects:
+                if self._enforced(subject, accounts, checks):
+                    continue
+                where = f"struct {struct.name}" if field is not None else f"instruction {ixs[0].name}"
+                findings.append(self._finding(
+                    file_path, content, first,
+                    f"In {where}: the comment says \"{sentence}\", but no constraint or "
+                    f"check on-chain mentions '{subject}'; a transaction built without the client skips it",
+                ))
+                break
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    # ─── Comment blocks ─────────────────────────────────────────────
+
+    @staticmethod
+    def _comment_blocks(lines: list) -> list[tuple[int, int, list]]:
+        """(first line, last line, text of each line) of runs of `//` comments."""
+        blocks, current = [], []
+        for number, line in enumerate(lines, 1):
+            m = re.match(r"\s*//[/!]?\s?(.*)$", line)
+            if m:
+                current.append((number, m.group(1).rstrip()))
+                continue
+            if current:
+                blocks.append((current[0][0], current[-1][0], [t for _, t in current]))
+                current = []
+        if current:
+            blocks.append((current[0][0], current[-1][0], [t for _, t in current]))
+        return blocks
+
+    @staticmethod
+    def _sentence(text: str, claim) -> str:
+        start = max(text.rfind(".", 0, claim.start()), text.rfind(";", 0, claim.start())) + 1
+        end = re.search(r"[.;]", text[claim.end():])
+        end = claim.end() + end.start() if end else len(text)
+        return " ".join(text[start:end].split())
+
+    @staticmethod
+    def _documented_handler(lines: list, last: int, handlers: list):
+        """The handler a doc block sits on, across attributes and blank lines."""
+        number = last + 1
+        while number <= len(lines) and re.match(r"\s*(?:#\[.*\]\s*)?$", lines[number - 1]):
+            number += 1
+        return next((ix for ix, start, _ in handlers if start == number), None)
+
+    @staticmethod
+    def _mentions(text: str, name: str) -> bool:
+        spelled = r"[_\s]+".join(re.escape(part) for part in name.split("_") if part)
+        return bool(spelled) and bool(re.search(rf"(?<![\w.]){spelled}\b", text, re.I))
+
+    # ─── On-chain counterparts ──────────────────────────────────────
+
+    @staticmethod
+    def _struct_spans(content: str, facts: Facts) -> list:
+        by_name = {s.name: s for s in facts.accounts_structs()}
+        spans = []
+        for name, body, start in VulnerabilityPattern._find_derive_accounts_structs(content):
+            if name in by_name:
+                spans.append((by_name[name], start, start + body.count("\n")))
+        return spans
+
+    @staticmethod
+    def _live(account) -> list:
+        """Constraints of an account, without clauses Facts picked up from commented lines."""
+        return [c for c in account.constraints if not c.kind.startswith("//") and "//" not in c.value]
+
+    def _checks(self, content: str, facts: Facts, struct, ixs: list) -> list:
+        """Conditions and constraint values that enforce something for these instructions."""
+        checks = []
+        for account in struct.accounts if struct else []:
+            for c in self._live(account):
+                if c.kind in ("mut", "payer", "space", "bump", "realloc", "close"):
+                    continue
+                checks.append(f"{account.name} {c.kind} {c.value}")
+        bodies = [ix.body for ix in ixs]
+        if struct:
+            bodies += [content[s:e] for s, e, t in self._find_impl_blocks(content) if t == struct.name]
+        for body in bodies:
+            for m in re.finditer(CHECK_RE.replace("require\\w*|assert\\w*", self._guards()), body):
+                checks.append(m.group(1) or m.group(2))
+        return checks
+
+    @staticmethod
+    def _enforced(subject: str, accounts: list, checks: list) -> bool:
+        account = next((a for a in accounts if a.name == subject), None)
+        if account is not None and account.is_signer:
+            return True
+        return any(re.search(rf"\b{re.escape(subject)}\b", check) for check in checks)
+
+    def _commented_out(self, file_path, content, facts, struct, first, last, block) -> list:
+        """Findings for constraint clauses or whole fields commented out of an accounts struct."""
+        clauses = [(first + i, m.group(1), m.group(2)) for i, line in enumerate(block)
+                   if (m := COMMENTED_CLAUSE_RE.match(line))]
+        if not clauses and not any("#[account(" in line for line in block):
+            return []
+        ixs = [ix for ix in facts.instructions() if ix.accounts_struct == struct.name]
+        field = next((COMMENTED_FIELD_RE.match(line) for line in reversed(block) if COMMENTED_FIELD_RE.match(line)),
+                     None)
+        if field is not None:
+            name, type_ = field.group(1), field.group(2)
+            if self._validated_from_remaining(content, struct, name, type_, block, ixs):
+                return []
+            return [self._finding(
+                file_path, content, first,
+                f"In struct {struct.name}: the account '{name}' and its #[account(...)] constraints are commented "
+                f"out, and no handler validates it from remaining_accounts instead",
+            )]
+        account = next((a for a in struct.accounts if a.line > last), None)
+        if account is None:
+            return []
+        checks = self._checks(content, facts, struct, ixs)
+        findings = []
+        for line, kind, value in clauses:
+            related = {w for w in re.findall(r"[A-Za-z_]\w*", value)
+                       if w not in KEYWORDS and w != account.name and not w[0].isupper()}
+            if any(re.search(rf"\b{re.escape(account.name)}\b", check)
+                   and (not related or any(re.search(rf"\b{re.escape(w)}\b", check) for w in related))
+                   for check in checks):
+                continue
+            clause = " ".join(f"{kind} = {value}".split())
+            findings.append(self._finding(
+                file_path, content, line,
+                f"In struct {struct.name}: the constraint `{clause}` on '{account.name}' is commented out, and "
+                f"no live constraint or handler check replaces it",
+            ))
+        return findings
+
+    def _validated_from_remaining(self, content, struct, name, type_, block, ixs) -> bool:
+        """A handler reads remaining_accounts and live code checks the commented account there."""
+        if not any("remaining_accounts" in ix.body for ix in ixs):
+            return False
+        inner = re.search(r"<\s*(?:'[^,>]+,\s*)?(\w+)\s*>", type_)
+        terms = [re.sub(r"\d", "", name)]
+        terms += [inner.group(1)] if inner else []
+        terms += re.findall(r"\b[A-Z][A-Z0-9_]{2,}\b", " ".join(block))
+        live = "\n".join(line.split("//")[0] for line in content.splitlines())
+        for s, body, start in self._find_derive_accounts_structs(content):
+            if s == struct.name:
+                live = live.replace(body, "")
+        # A use of the type, seed or name, not the type's own definition
+        return any(re.search(rf"(?<!struct )(?<!enum )\b{re.escape(term)}\w*", live) for term in terms if term)
+
+    def _finding(self, file_path, content, line, message) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=self.severity,
+            file=file_path,
+            line=line,
+            description=f"{message}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "The dApp only lets a user withdraw up to their 1,000 USDC share",
+                "after": "A hand-built transaction withdraws 50,000 USDC from the shared vault",
+                "damage": "The invariant held only for users of the official frontend.",
+            },
+            impact={
+                "attack_cost": "Transaction fees only",
+                "exploitability": "High once noticed — the client is the attacker's to replace",
+                "breach_cost_context": "Checks moved to the frontend are the ones nobody audits on-chain.",
+            },
+            anchor_versions_affected="All versions (developer-side pattern)",
+            ecosystem_recommendations=[
+                "Enforce every invariant the program relies on in constraints or handler requires",
+                "Delete commented-out constraints, or restore them, instead of leaving both readings",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Enforce the invariant on-chain, whatever the client also checks:\n"
+            "  require!(amount <= ctx.accounts.position.shares, VaultError::ExceedsShare);\n"
+            "and restore commented-out constraints (or validate the account where it is now read):\n"
+            "  #[account(mut, constraint = vault.owner == pool.key() @ VaultError::WrongVault)]"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "The program is an open endpoint: any signer can send it any "
+            "instruction data and accounts without going through the official "
+            "client. Validation the client performs, or a constraint left in a "
+            "comment, protects only users who did not want to break it."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. The withdraw doc says the frontend caps amount at the user's share\n"
+            "2. The handler subtracts amount from the vault without comparing it to the share\n"
+            "3. Attacker builds the instruction directly with amount = vault balance\n"
+            "4. The vault pays out every depositor's funds to the attacker"
+        )
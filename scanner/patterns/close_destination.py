@@ -0,0 +1,144 @@
+"""
+ANCHOR-025: Close Destination Not Constrained
+
+`close = <destination>` sends the closed account's lamports — its rent, and
+for SOL vaults the whole balance — to another account of the instruction.
+When nothing ties that account to anyone, the caller picks it:
+
+    #[derive(Accounts)]
+    pub struct ClosePosition<'info> {
+        pub owner: Signer<'info>,
+        #[account(mut, has_one = owner, close = receiver)]
+        pub position: Account<'info, Position>,
+        #[account(mut)]
+        pub receiver: SystemAccount<'info>,     // any account
+    }
+
+The close steps come from the per-type lifecycle (lifecycle.py). The
+destination counts as constrained when it is a signer, is pinned
+(`address`, `seeds`, an associated-token derivation), is named by a
+`has_one` or compared by key in a constraint of the struct, or is compared
+by key in a handler of the struct — a crank closing expired orders back to
+their stored payer is fine as long as that comparison exists.
+
+Structs without any signer are left out: there the close itself is
+unauthenticated, and where the lamports go is the lesser problem.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+from scanner.patterns.lifecycle import CLOSE, account_lifecycles
+
+
+class CloseDestinationPattern(VulnerabilityPattern):
+    id = "ANCHOR-025"
+    name = "Close Destination Not Constrained"
+    severity = "Medium"
+    category = "lifecycle"
+    remediation_effort = "mechanical"
+    description = (
+        "An account is closed to a destination account that no signer, "
+        "has_one, address or key check constrains, so the caller chooses "
+        "who receives its lamports."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        facts = Facts(content)
+        handlers = {}
+        for ix in facts.instructions():
+            handlers.setdefault(ix.accounts_struct, []).append(ix.body)
+
+        for acct_type, lifecycle in account_lifecycles(facts).items():
+            for step in lifecycle.of(CLOSE):
+                if not any(a.is_signer for a in step.struct.accounts):
+                    continue
+                destination = next((a for a in step.struct.accounts if a.name == step.destination), None)
+                if destination is None or self._constrained(destination, step.struct, handlers):
+                    continue
+                findings.append(self._finding(file_path, content, acct_type, step, destination))
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    def _constrained(self, destination, struct, handlers: dict) -> bool:
+        if destination.is_signer:
+            return True
+        if any(c.kind in ("address", "seeds") or c.kind.startswith("associated_token::")
+               for c in destination.constraints):
+            return True
+        name = re.escape(destination.name)
+        for other in struct.accounts:
+            for c in other.constraints:
+                if c.kind == "has_one" and c.value.split("@")[0].strip() == destination.name:
+                    return True
+                expression = c.value if c.kind == "constraint" else c.text if "==" in c.kind else ""
+                if re.search(rf"\b{name}\b", expression) and "==" in expression:
+                    return True
+        for body in handlers.get(struct.name, []):
+            for stmt in re.split(r"[;{}]", body):
+                if re.search(rf"\b{name}\b", stmt) and re.search(r"\bkey\b", stmt) \
+                        and re.search(rf"==|!=|\b{self._guards()}!", stmt):
+                    return True
+        return False
+
+    def _finding(self, file_path, content, acct_type, step, destination) -> Finding:
+        line = step.account.line
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=self.severity,
+            file=file_path,
+            line=line,
+            description=(
+                f"In struct {step.struct.name}: '{step.account.name}' ({acct_type}) is closed to "
+                f"'{destination.name}', which nothing constrains — the caller chooses who receives "
+                f"the lamports."
+            ),
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=(
+                f"Close to the account's owner or recorded payer:\n"
+                f"  #[account(mut, has_one = {destination.name}, close = {destination.name})]\n"
+                f"or require the destination to sign."
+            ),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": f"{step.account.name} holds rent (and any deposited SOL) owed to its owner",
+                "after": f"{step.account.name} closed; lamports sent to the caller's account",
+                "damage": "Rent refunds, or whole SOL balances of vault-like accounts, are redirected.",
+            },
+            impact={
+                "attack_cost": "< 0.01 SOL (single transaction)",
+                "exploitability": "Medium — needs whatever authority the close instruction requires",
+                "breach_cost_context": "Unconstrained close destinations are a common audit finding in escrow and order-book programs.",
+            },
+            anchor_versions_affected="All versions (developer-side pattern)",
+            ecosystem_recommendations=[
+                "Close to a signer or a has_one field of the closed account",
+                "Record the rent payer at creation and close back to it",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Constrain the close destination:\n"
+            "  #[account(mut, has_one = owner, close = owner)]\n"
+            "  pub position: Account<'info, Position>,\n"
+            "  pub owner: Signer<'info>,"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Anchor's close constraint transfers all lamports to the named field "
+            "but does not check which account that is. Without a constraint on "
+            "the destination, any writable account can be passed."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. Attacker calls the close instruction (or front-runs a permissionless close)\n"
+            "2. Attacker passes their own account as the destination\n"
+            "3. The closed account's lamports are credited to the attacker"
+        )
@@ -5,16 +5,25 @@ Detects when the same account type is used with both close and init_if_needed
 constraints within a program. After close zeroes an account, init_if_needed
 can "revive" it in the same or subsequent transaction, potentially with
 attacker-prepared state.
+
+Built on the per-type lifecycle (lifecycle.py). A closed PDA is only revived
+where init_if_needed derives the same address: when both sides are seeded
+and their leading seeds differ, the addresses cannot meet and nothing is
+reported; identical seeds are called out as a certain resurrection.
 """
 
-import re
 from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.details import PdaCollision, SeedRecipe
+from scanner.patterns.facts import Facts
+from scanner.patterns.lifecycle import CLOSE, REINIT, account_lifecycles, seed_prefix
 
 
 class CloseReinitPattern(VulnerabilityPattern):
     id = "ANCHOR-005"
     name = "Close + Reinit Lifecycle Attack"
     severity = "Medium"
+    category = "lifecycle"
+    remediation_effort = "architectural"
     description = (
         "Same account type is used with both close and init_if_needed constraints, "
         "enabling potential account revival after close with attacker-controlled state."
@@ -23,28 +32,21 @@ class CloseReinitPattern(VulnerabilityPattern):
     def scan(self, file_path: str, content: str) -> list[Finding]:
         findings = []
 
-        close_types = {}
-        init_if_needed_types = {}
-
-        for struct_name, struct_body, struct_start in self._find_derive_accounts_structs(content):
-            for field in self._parse_struct_fields(struct_body, struct_start):
-                base_type = self._extract_account_type(field["type"])
-                if not base_type:
-                    continue
-
-                if re.search(r"\bclose\s*=", field["attrs"]):
-                    close_types[base_type] = (struct_name, field["name"], field["line"])
-
-                if re.search(r"init_if_needed", field["attrs"]):
-                    init_if_needed_types[base_type] = (struct_name, field["name"], field["line"])
-
-        overlapping = set(close_types.keys()) & set(init_if_needed_types.keys())
-
-        for acct_type in overlapping:
-            close_struct, close_field, close_line = close_types[acct_type]
-            init_struct, init_field, init_line = init_if_needed_types[acct_type]
-
-            snippet = self._extract_snippet(content, init_line)
+        for acct_type, lifecycle in account_lifecycles(Facts(content)).items():
+            pairs = [
+                (close, reinit) for reinit in lifecycle.of(REINIT) for close in lifecycle.of(CLOSE)
+                if self._same_address(close.seeds, reinit.seeds) is not False
+            ]
+            if not pairs:
+                continue
+            # Prefer a pair proven to share the address
+            close, reinit = max(pairs, key=lambda p: self._same_address(p[0].seeds, p[1].seeds) is True)
+            init_line = reinit.account.line
+            same_address = self._same_address(close.seeds, reinit.seeds)
+            address = (
+                f" with the same seeds [{reinit.seeds}], so the closed address is re-created"
+                if same_address else ""
+            )
 
             findings.append(
                 Finding(
@@ -55,14 +57,15 @@ class CloseReinitPattern(VulnerabilityPattern):
                     line=init_line,
                     description=(
                         f"Account type '{acct_type}' is used with close "
-                        f"(in {close_struct}.{close_field}, line {close_line}) and "
-                        f"init_if_needed (in {init_struct}.{init_field}, line "
-                        f"{init_line}). Attacker can close and revive the account."
+                        f"(in {close.struct.name}.{close.account.name}, line {close.account.line}) and "
+                        f"init_if_needed (in {reinit.struct.name}.{reinit.account.name}, line "
+                        f"{init_line}){address}. Attacker can close and revive the account. "
+                        f"Lifecycle: {lifecycle.summary()}."
                     ),
                     root_cause=self.get_root_cause(),
                     exploit_scenario=self.get_exploit_scenario(),
                     fix_recommendation=self.get_fix_recommendation(),
-                    code_snippet=snippet,
+                    code_snippet=self._extract_snippet(content, init_line),
                     before_after_state={
                         "before": "Account: initialized, authority=victim",
                         "after": "Account: re-initialized via init_if_needed, authority=attacker",
@@ -78,21 +81,30 @@ class CloseReinitPattern(VulnerabilityPattern):
                         "Use plain init instead of init_if_needed",
                         "Add lifecycle state tracking to prevent re-initialization",
                     ],
+                    details=PdaCollision(acct_type, self._recipe(close), self._recipe(reinit), same_address),
                 )
             )
 
+        findings.sort(key=lambda f: f.line)
         return findings
 
     @staticmethod
-    def _extract_account_type(type_str: str) -> str:
-        """Extract the inner account type from Account<'info, T>."""
-        m = re.search(r"Account\s*<\s*'[^,]+,\s*(\w+)", type_str)
-        if m:
-            return m.group(1)
-        m = re.search(r"InterfaceAccount\s*<\s*'[^,]+,\s*(\w+)", type_str)
-        if m:
-            return m.group(1)
-        return ""
+    def _recipe(step) -> SeedRecipe:
+        seeds = next((c.value for c in step.account.constraints if c.kind == "seeds"), "")
+        return SeedRecipe(
+            step.struct.name, step.account.name, step.account.line, " ".join(seeds.split()),
+            step.seeds.split(",") if step.seeds else [],
+        )
+
+    @staticmethod
+    def _same_address(close_seeds: str, reinit_seeds: str):
+        """True if both derive the same PDA, False if they cannot meet, None if unknown."""
+        if close_seeds and close_seeds == reinit_seeds:
+            return True
+        close_prefix, reinit_prefix = seed_prefix(close_seeds), seed_prefix(reinit_seeds)
+        if close_prefix and reinit_prefix and close_prefix != reinit_prefix:
+            return False
+        return None
 
     def get_fix_recommendation(self) -> str:
         return (
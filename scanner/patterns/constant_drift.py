@@ -0,0 +1,320 @@
+"""
+ANCHOR-026: Diverging Constant Definitions
+
+Limits and seeds get copied: into a second module, a client crate, a fork of
+the program kept next to the original. The copies then drift:
+
+    // state/fee.rs
+    pub const MAX_FEE_BPS: u16 = 1_000;
+
+    // instructions/update_config.rs
+    const MAX_FEE_BPS: u16 = 10_000;              // the check uses this copy
+
+    seeds = [b"user_stats", owner.key().as_ref()]
+    seeds = [b"user-stats", owner.key().as_ref()] // never the same PDA
+
+The rule reports
+
+  - a constant defined more than once, in different modules of a unit or
+    different files of a crate, with different values. Constants are keyed
+    by name and the type of the `impl` they sit in, so `Vault::SEED` and
+    `Pool::SEED` are distinct. Single-word names (`MAX`, `SEED`) are left
+    out: modules legitimately namespace those. So are sizes and ids (`LEN`,
+    `SPACE`, `DISCRIMINATOR`), `#[cfg(...)]`-gated alternatives, and
+    function-local constants. The value most copies share is taken as the
+    intended one; the others are reported.
+  - seed literals that differ from another seed of the unit or crate by a
+    typo: case or `-`/`_` only, two swapped letters, or a dropped doubled
+    letter (`b"acount"`). Unrelated seeds of one letter's difference
+    (`b"vault_a"`, `b"vault_b"`) are not typos and are not reported.
+
+scan() compares within one source unit; scan_workspace() compares across the
+files of each crate (the files under the nearest Cargo.toml). Separate crates
+are not compared with each other: a v2 program is expected to change its
+limits.
+"""
+
+import os
+import re
+from collections import Counter
+from scanner.generated import generated_reason
+from scanner.patterns.base import VulnerabilityPattern, Finding
+
+CONST_RE = re.compile(
+    r"^[ \t]*(?:pub(?:\([^)]*\))?\s+)?const\s+([A-Z][A-Z0-9_]*)\s*:\s*[^=;]+=\s*([^;]+);", re.MULTILINE
+)
+IGNORED_NAME_RE = re.compile(r"(?:^|_)(?:LEN|LENGTH|SIZE|SPACE|DISCRIMINATOR|ID|VERSION|NAME)$")
+SEED_LITERAL_RE = re.compile(r'\bb"([\w\-]{3,})"')
+SEED_CONTEXT_RE = re.compile(r"(?i)seed|program_address|invoke_signed|with_signer")
+MOD_RE = re.compile(r"\bmod\s+(\w+)\s*\{")
+
+
+class ConstantDriftPattern(VulnerabilityPattern):
+    id = "ANCHOR-026"
+    name = "Diverging Constant Definitions"
+    severity = "Medium"
+    category = "consistency"
+    remediation_effort = "mechanical"
+    description = (
+        "The same constant is defined in several modules with different values, "
+        "or a seed string is duplicated with a typo; one copy of a limit or "
+        "PDA derivation has drifted from the others."
+    )
+
+    SKIP_DIRS = {"target", "node_modules", ".git", "tests", "benches", "examples"}
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        units = [(file_path, content)]
+        for definition, reference in self._divergent(self._constants(file_path, content)):
+            findings.append(self._constant_finding(definition, reference, units))
+        for typo, canonical in self._typos(self._seeds(file_path, content)):
+            findings.append(self._seed_finding(typo, canonical, units))
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    def scan_workspace(self, root: str) -> list[Finding]:
+        findings = []
+        for files in self._crates(root).values():
+            units = []
+            for path in sorted(files):
+                with open(path, "r", encoding="utf-8", errors="ignore") as fh:
+                    content = fh.read()
+                rel_path = os.path.relpath(path, root)
+                if not generated_reason(rel_path, content):
+                    units.append((rel_path, content))
+            if len(units) < 2:
+                continue
+            constants, seeds = [], []
+            for rel_path, content in units:
+                constants += self._constants(rel_path, content)
+                seeds += self._seeds(rel_path, content)
+            # Divergences within one file are scan()'s
+            for definition, reference in self._divergent(constants, across_files=True):
+                findings.append(self._constant_finding(definition, reference, units))
+            for typo, canonical in self._typos(seeds):
+                if not {s["path"] for s in seeds if s["value"] == typo["value"]} \
+                        & {s["path"] for s in seeds if s["value"] == canonical["value"]}:
+                    findings.append(self._seed_finding(typo, canonical, units))
+        return findings
+
+    def _crates(self, root: str) -> dict:
+        """{crate directory: [.rs files]}, a crate being the nearest Cargo.toml above a file (or the root)."""
+        crates = {}
+        for dirpath, dirnames, files in os.walk(root):
+            dirnames[:] = [d for d in dirnames if d not in self.SKIP_DIRS]
+            crate = dirpath
+            while crate != root and not os.path.isfile(os.path.join(crate, "Cargo.toml")):
+                crate = os.path.dirname(crate)
+            crates.setdefault(crate, []).extend(os.path.join(dirpath, f) for f in files if f.endswith(".rs"))
+        return crates
+
+    def _constants(self, path: str, content: str) -> list:
+        """Item-level constants of a unit as dicts: key, value (normalized), text, module, path, offset."""
+        impls = self._find_impl_blocks(content)
+        functions = [(fn["body_start"], fn["end"]) for fn in self._find_functions(content)]
+        mods, gated = [], []
+        for m in MOD_RE.finditer(content):
+            end = self._find_block_end(content, m.end() - 1)
+            if end == -1:
+                continue
+            mods.append((m.start(), end, m.group(1)))
+            if self._cfg_gated(content, m.start()):
+                gated.append((m.start(), end))
+
+        constants = []
+        for m in CONST_RE.finditer(content):
+            name, pos = m.group(1), m.start(1)
+            if "_" not in name or IGNORED_NAME_RE.search(name) or self._cfg_gated(content, m.start()):
+                continue
+            if any(start <= pos < end for start, end in functions + gated):
+                continue
+            owner = min((b for b in impls if b[0] <= pos < b[1]), key=lambda b: b[1] - b[0], default=None)
+            module = "::".join(n for start, end, n in mods if start <= pos < end)
+            constants.append({
+                "key": f"{owner[2]}::{name}" if owner else name,
+                "value": self._normalize(m.group(2)),
+                "text": m.group(2).strip(),
+                "module": module,
+                "path": path,
+                "offset": pos,
+            })
+        return constants
+
+    @staticmethod
+    def _cfg_gated(content: str, pos: int) -> bool:
+        """The item at pos carries a #[cfg(...)] attribute on the lines above it."""
+        before = content[:content.rfind("\n", 0, pos) + 1].rstrip("\n").split("\n")
+        for line in reversed(before):
+            stripped = line.strip()
+            if not stripped.startswith(("#[", "///")):
+                return False
+            if stripped.startswith("#[cfg"):
+                return True
+        return False
+
+    @staticmethod
+    def _normalize(value: str) -> str:
+        text = re.sub(r"\s+", "", value)
+        text = re.sub(r"(?<=\d)_(?=\d)", "", text)
+        text = re.sub(r"(?<=\d)_?(?:u|i)(?:8|16|32|64|128|size)$", "", text)
+        try:
+            return str(int(text, 0))
+        except ValueError:
+            return text
+
+    @staticmethod
+    def _divergent(constants: list, across_files: bool = False) -> list:
+        """(definition, reference) for every copy whose value differs from the most common one."""
+        groups = {}
+        for c in constants:
+            groups.setdefault(c["key"], []).append(c)
+        pairs = []
+        for copies in groups.values():
+            counts = Counter(c["value"] for c in copies)
+            if len(counts) < 2:
+                continue
+            # Most common value wins; on a tie, the first definition
+            intended = max(counts, key=lambda v: (counts[v], -[c["value"] for c in copies].index(v)))
+            references = [c for c in copies if c["value"] == intended]
+            for c in copies:
+                if c["value"] == intended:
+                    continue
+                if across_files:
+                    reference = next((r for r in references if r["path"] != c["path"]), None)
+                    if reference is None or any(r["path"] == c["path"] for r in references):
+                        continue
+                else:
+                    reference = references[0]
+                pairs.append((c, reference))
+        return pairs
+
+    @staticmethod
+    def _seeds(path: str, content: str) -> list:
+        """Byte-string literals used as seeds, as dicts: value, path, offset."""
+        seeds = []
+        for m in SEED_LITERAL_RE.finditer(content):
+            stmt_start = max(content.rfind(c, 0, m.start()) for c in ";{}") + 1
+            stmt_end = min((i for i in (content.find(c, m.end()) for c in ";{}") if i != -1), default=len(content))
+            line_start = content.rfind("\n", 0, m.start()) + 1
+            if "//" in content[line_start:m.start()]:
+                continue
+            if SEED_CONTEXT_RE.search(content[stmt_start:stmt_end]):
+                seeds.append({"value": m.group(1), "path": path, "offset": m.start()})
+        return seeds
+
+    @staticmethod
+    def _typo_kind(a: str, b: str) -> str:
+        """How b is a typo of a, or "" if they are different words."""
+        if a.lower().replace("-", "").replace("_", "") == b.lower().replace("-", "").replace("_", ""):
+            return "differs only in case or separators"
+        if len(a) == len(b) and len(a) >= 4:
+            diff = [i for i in range(len(a)) if a[i] != b[i]]
+            if len(diff) == 2 and diff[1] == diff[0] + 1 and a[diff[0]] == b[diff[1]] and a[diff[1]] == b[diff[0]] \
+                    and a[diff[0]].isalpha() and a[diff[1]].isalpha():
+                return "has two letters swapped"
+        if len(a) == len(b) + 1 and len(b) >= 4:
+            for i in range(len(a)):
+                if a[:i] + a[i + 1:] == b and i > 0 and a[i] == a[i - 1] and a[i].isalpha():
+                    return "drops a doubled letter"
+        return ""
+
+    def _typos(self, seeds: list) -> list:
+        """(typo, canonical) seed occurrences; the less used spelling is the typo."""
+        counts = Counter(s["value"] for s in seeds)
+        first = {}
+        for s in seeds:
+            first.setdefault(s["value"], s)
+        pairs = []
+        values = list(first)
+        for i, a in enumerate(values):
+            for b in values[i + 1:]:
+                kind = self._typo_kind(a, b) or self._typo_kind(b, a)
+                if not kind:
+                    continue
+                # Tie: the later spelling is the typo
+                typo, canonical = (b, a) if counts[b] <= counts[a] else (a, b)
+                pairs.append((dict(first[typo], kind=kind, uses=counts[canonical]), first[canonical]))
+        return pairs
+
+    def _unit(self, units: list, path: str) -> str:
+        return next(content for p, content in units if p == path)
+
+    def _where(self, units: list, item: dict) -> str:
+        line = self._get_line_number(self._unit(units, item["path"]), item["offset"])
+        module = f" (mod {item['module']})" if item.get("module") else ""
+        return f"{item['path']}:{line}{module}"
+
+    def _constant_finding(self, definition, reference, units) -> Finding:
+        content = self._unit(units, definition["path"])
+        line = self._get_line_number(content, definition["offset"])
+        return self._finding(
+            definition["path"], content, line,
+            f"Constant {definition['key']} is {definition['text']} here, but {reference['text']} in "
+            f"{self._where(units, reference)}. One copy of the limit has drifted; code using this copy "
+            f"enforces a different value than the rest of the program",
+        )
+
+    def _seed_finding(self, typo, canonical, units) -> Finding:
+        content = self._unit(units, typo["path"])
+        line = self._get_line_number(content, typo["offset"])
+        return self._finding(
+            typo["path"], content, line,
+            f'Seed b"{typo["value"]}" {typo["kind"]} from b"{canonical["value"]}" (used {typo["uses"]}x, '
+            f'first at {self._where(units, canonical)}). PDAs derived from the two spellings never match',
+        )
+
+    def _finding(self, file_path, content, line, message) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=self.severity,
+            file=file_path,
+            line=line,
+            description=f"{message}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "MAX_FEE_BPS = 1_000 in state, enforced by the fee curve",
+                "after": "MAX_FEE_BPS = 10_000 in the config handler's copy",
+                "damage": "The check accepts values the rest of the program assumes impossible, "
+                          "or a PDA is derived at an address nothing else uses.",
+            },
+            impact={
+                "attack_cost": "Depends on the drifted limit",
+                "exploitability": "Medium — the weaker copy is whichever the attacker's path reaches",
+                "breach_cost_context": "Drifted copies of limits are common in forked and vendored Solana programs.",
+            },
+            anchor_versions_affected="All versions (developer-side pattern)",
+            ecosystem_recommendations=[
+                "Define each limit and seed once, in a constants module every other module imports",
+                "Re-export constants for client crates instead of copying them",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Keep one definition and import it everywhere:\n"
+            "  // constants.rs\n"
+            "  pub const MAX_FEE_BPS: u16 = 1_000;\n"
+            "  pub const USER_STATS_SEED: &[u8] = b\"user_stats\";\n"
+            "  // elsewhere\n"
+            "  use crate::constants::{MAX_FEE_BPS, USER_STATS_SEED};"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Copies of a constant are independent definitions. The compiler "
+            "cannot tell that they are meant to agree, so a change to one copy, "
+            "or a typo in a seed string, goes unnoticed."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. A limit is raised or lowered in one copy only (often in a fork)\n"
+            "2. The handler using the weaker copy accepts a value the rest of the program rejects\n"
+            "3. Attacker submits that value through the lenient path (or a mistyped seed strands funds at an "
+            "unreachable PDA)"
+        )
@@ -0,0 +1,198 @@
+"""
+ANCHOR-020: Inconsistent Account Validation
+
+Learns, per source unit, how each account is validated across the accounts
+structs that take it, and reports the struct that breaks the habit:
+
+    #[derive(Accounts)]
+    pub struct Deposit<'info> {             // ... and Withdraw, Swap, Harvest
+        #[account(has_one = vault)]
+        pub pool: Account<'info, Pool>,
+        #[account(mut)]
+        pub vault: Account<'info, TokenAccount>,
+    }
+
+    #[derive(Accounts)]
+    pub struct EmergencyExit<'info> {
+        pub pool: Account<'info, Pool>,
+        #[account(mut)]
+        pub vault: Account<'info, TokenAccount>,   // the one struct without it
+    }
+
+An account is identified by field name and type. Each occurrence is
+classified by the checks applied to it:
+
+  - pinned:  `address =`, `seeds`, an associated-token derivation, another
+             field's `has_one = <it>`, or a constraint comparing its key
+  - signer:  Signer<'info> or the `signer` constraint
+  - owner:   `owner =`
+  - token:   `token::mint` / `token::authority`
+
+When an account appears in at least MIN_OCCURRENCES structs and a check is
+present in at least CONSENSUS of them, every occurrence without it is
+reported. No hard rule needs to match: the program's own habits are the
+specification. Occurrences that create the account (`init`, `zero`,
+`init_if_needed`) are left out, as are structs that create program state:
+initialization sets up the relations the other instructions check.
+"""
+
+import re
+from scanner.patterns.account_types import account_type
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+
+MIN_OCCURRENCES = 4
+CONSENSUS = 0.75
+
+TOKEN_TYPES = {"TokenAccount", "Mint"}
+
+CHECK_LABELS = {
+    "pinned": "a stored-key/address/seeds check",
+    "signer": "a signer requirement",
+    "owner": "an owner constraint",
+    "token": "a token mint/authority constraint",
+}
+
+
+class ConstraintAnomalyPattern(VulnerabilityPattern):
+    id = "ANCHOR-020"
+    name = "Inconsistent Account Validation"
+    severity = "Low"
+    category = "accounts"
+    remediation_effort = "mechanical"
+    description = (
+        "An account is validated the same way in most instructions that take "
+        "it, but one accounts struct omits the check."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        facts = Facts(content)
+        occurrences = {}
+        for struct in facts.accounts_structs():
+            # Creating the state establishes the relations other structs check
+            if any(self._creates(a) and a.inner_type not in TOKEN_TYPES for a in struct.accounts):
+                continue
+            for account in struct.accounts:
+                if self._creates(account) or account.has("init_if_needed"):
+                    continue
+                key = (account.name, self._norm_type(account.type))
+                occurrences.setdefault(key, []).append((struct, account, self._checks(account, struct)))
+
+        for (name, type_), seen in occurrences.items():
+            structs = {struct.name for struct, _, _ in seen}
+            if len(structs) < MIN_OCCURRENCES:
+                continue
+            for check, label in CHECK_LABELS.items():
+                having = [struct.name for struct, _, checks in seen if check in checks]
+                if len(having) < CONSENSUS * len(seen) or len(having) == len(seen):
+                    continue
+                pinners = {name for _, _, checks in seen for name in checks.get("pinned", ())}
+                for struct, account, checks in seen:
+                    if check in checks:
+                        continue
+                    # A root account is pinned through others; without them in the struct there is nothing to pin to
+                    if check == "pinned" and "" not in pinners and not pinners & {a.name for a in struct.accounts}:
+                        continue
+                    findings.append(self._finding(
+                        file_path, content, account.line,
+                        f"Account '{name}' ({type_}) has {label} in {len(having)} of {len(seen)} "
+                        f"accounts structs ({self._names(having)}) but not in {struct.name}",
+                    ))
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    @staticmethod
+    def _creates(account) -> bool:
+        return account.has("init") or account.has("zero")
+
+    @staticmethod
+    def _norm_type(type_: str) -> str:
+        """`Account<Vault>` for any boxed or optional spelling of it."""
+        t = account_type(type_)
+        if not t.wrapper:
+            return re.sub(r"\s+", "", type_)
+        return f"{t.wrapper}<{t.inner}>" if t.inner else t.wrapper
+
+    @staticmethod
+    def _checks(account, struct) -> dict:
+        """{check: names of the fields applying it, "" for the account itself}."""
+        checks = {}
+        name = re.escape(account.name)
+        if account.is_signer:
+            checks.setdefault("signer", set()).add("")
+        for c in account.constraints:
+            if c.kind in ("address", "seeds") or c.kind.startswith("associated_token::"):
+                checks.setdefault("pinned", set()).add("")
+            elif c.kind == "owner":
+                checks.setdefault("owner", set()).add("")
+            elif c.kind in ("token::mint", "token::authority"):
+                checks.setdefault("token", set()).add("")
+        for other in struct.accounts:
+            for c in other.constraints:
+                if c.kind == "has_one" and c.value.split("@")[0].strip() == account.name:
+                    checks.setdefault("pinned", set()).add(other.name)
+                # `constraint = a.key() == b.key()`, or the pre-0.20 `#[account("a.key == b")]`
+                expression = c.value if c.kind == "constraint" else c.text if "==" in c.kind else ""
+                if re.search(rf"\b{name}\s*\.\s*(?:key\b|to_account_info\s*\(\s*\)\s*\.\s*key\b)", expression) \
+                        and "==" in expression:
+                    checks.setdefault("pinned", set()).add("" if other is account else other.name)
+        return checks
+
+    @staticmethod
+    def _names(structs: list) -> str:
+        shown = ", ".join(structs[:4])
+        return shown + (f", +{len(structs) - 4} more" if len(structs) > 4 else "")
+
+    def _finding(self, file_path, content, line, message) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=self.severity,
+            file=file_path,
+            line=line,
+            description=f"{message}. The odd one out may accept an account the others reject.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "vault pinned to pool.vault in every instruction",
+                "after": "One instruction accepts any token account as the vault",
+                "damage": "The unchecked instruction operates on an attacker-chosen account.",
+            },
+            impact={
+                "attack_cost": "< 0.01 SOL (single transaction)",
+                "exploitability": "Varies — depends on what the instruction does with the account",
+                "breach_cost_context": "Checks dropped from a single copy-pasted struct are a recurring audit finding.",
+            },
+            anchor_versions_affected="All versions (developer-side pattern)",
+            ecosystem_recommendations=[
+                "Factor shared account validation into one place (a nested accounts struct or helper)",
+                "Review why the odd instruction differs; document it if the omission is intended",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Apply the check the other instructions use, e.g.:\n"
+            "  #[account(has_one = vault)]\n"
+            "  pub pool: Account<'info, Pool>,\n"
+            "or, if the omission is deliberate, suppress the finding with a reason."
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Account validation is repeated per accounts struct. When the same "
+            "account is checked in nearly every instruction, the one struct "
+            "that skips the check is usually a copy-paste slip rather than a "
+            "design decision."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. Attacker finds the one instruction that does not pin the account\n"
+            "2. Attacker passes an account they control in its place\n"
+            "3. The instruction reads or writes it as if it were the real one"
+        )
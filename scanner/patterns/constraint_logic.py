@@ -0,0 +1,155 @@
+"""
+ANCHOR-040: Business Logic In Constraint
+
+`constraint = <expr>` is meant for relations between accounts: this vault
+belongs to this pool, this signer is the pool's authority. Business rules
+written there instead are hard to audit — they sit in an attribute far
+from the handler that depends on them — and fail with an opaque
+`ConstraintRaw` error unless every clause carries its own code:
+
+    #[account(
+        mut,
+        constraint = pool.total_deposits + amount <= pool.cap
+            && Clock::get()?.unix_timestamp >= pool.start_ts
+            && !pool.paused
+    )]
+    pub pool: Account<'info, Pool>,
+
+The same rules as handler requires name each failure:
+
+    require!(!pool.paused, PoolError::Paused);
+    require!(clock.unix_timestamp >= pool.start_ts, PoolError::NotStarted);
+    let total = pool.total_deposits.checked_add(amount).ok_or(PoolError::Overflow)?;
+    require!(total <= pool.cap, PoolError::CapExceeded);
+
+The rule reports (Low) a constraint expression that:
+
+  - does arithmetic (`+ - * / %`, `checked_*`, `saturating_*`), which can
+    also overflow inside the validation code
+  - reads the clock, raw account data (`try_borrow_data`, `deserialize`,
+    `try_from_slice`) or CPI return data
+  - or combines three or more conditions with `&&` / `||`
+
+Key and field comparisons, however many accounts they relate — including
+zero-copy `load()?.pool_id == pool.key()` — are what constraints are for and
+are not reported.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+
+MIN_CLAUSES = 3
+
+ARITHMETIC_RE = re.compile(
+    r"[\w)\]]\s*[+*/%]\s*[\w(&]|[\w)\]]\s+-\s*[\w(]|\b(?:checked|saturating|wrapping|overflowing)_\w+\s*\("
+)
+READ_RE = re.compile(
+    r"\bClock\s*::\s*get\b|\bunix_timestamp\b|"
+    r"\b(?:try_borrow_data|try_borrow_mut_data|try_from_slice|try_deserialize\w*|deserialize)\s*\(|"
+    r"\.\s*data\s*\.\s*borrow\s*\(|\bget_return_data\s*\("
+)
+
+
+class ConstraintLogicPattern(VulnerabilityPattern):
+    id = "ANCHOR-040"
+    name = "Business Logic In Constraint"
+    severity = "Low"
+    category = "constraints"
+    remediation_effort = "localized"
+    description = (
+        "A `constraint = ...` expression carries business rules — arithmetic, "
+        "clock or raw data reads, several combined conditions — that belong "
+        "in the handler as requires with named errors."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        facts = Facts(content)
+        for struct in facts.accounts_structs():
+            for account in struct.accounts:
+                for c in account.constraints:
+                    if c.kind != "constraint":
+                        continue
+                    expr, _, error = c.value.partition("@")
+                    reasons = self._reasons(" ".join(expr.split()))
+                    if not reasons:
+                        continue
+                    if len(reasons) > 1:
+                        reasons = [", ".join(reasons[:-1]) + " and " + reasons[-1]]
+                    opaque = "" if error.strip() else ", and fails with the generic ConstraintRaw error"
+                    findings.append(self._finding(
+                        file_path, content, c.line,
+                        f"In struct {struct.name}: the constraint on '{account.name}' "
+                        f"{reasons[0]}{opaque}; move it into the handler as requires "
+                        f"with named error codes",
+                    ))
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    @staticmethod
+    def _reasons(expr: str) -> list:
+        reasons = []
+        if ARITHMETIC_RE.search(expr):
+            reasons.append("does arithmetic")
+        if READ_RE.search(expr):
+            reasons.append("reads the clock, raw account data or return data")
+        clauses = len(re.findall(r"&&|\|\|", expr)) + 1
+        if clauses >= MIN_CLAUSES:
+            reasons.append(f"combines {clauses} conditions")
+        return reasons
+
+    def _finding(self, file_path, content, line, message) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=self.severity,
+            file=file_path,
+            line=line,
+            description=f"{message}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "Deposit cap, start time and pause flag checked in one constraint",
+                "after": "A deposit fails with ConstraintRaw (2003)",
+                "damage": "Users and auditors cannot tell which rule failed or that the sum can overflow.",
+            },
+            impact={
+                "attack_cost": "None — maintainability and auditability issue",
+                "exploitability": "Low — hides bugs rather than being one",
+                "breach_cost_context": "Rules buried in attributes are the ones reviews skip.",
+            },
+            anchor_versions_affected="All versions (developer-side pattern)",
+            ecosystem_recommendations=[
+                "Keep constraints to relations between accounts",
+                "Check business rules in the handler with require! and a named error per rule",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Move the rules into the handler, one require per rule:\n"
+            "  require!(!pool.paused, PoolError::Paused);\n"
+            "  require!(Clock::get()?.unix_timestamp >= pool.start_ts, PoolError::NotStarted);\n"
+            "  let total = pool.total_deposits.checked_add(amount).ok_or(PoolError::Overflow)?;\n"
+            "  require!(total <= pool.cap, PoolError::CapExceeded);"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Account constraints run before the handler and report a failure "
+            "by constraint kind, not by rule. Business logic placed there is "
+            "separated from the code it guards and, without an error per "
+            "clause, indistinguishable when it fails."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. A deposit cap is checked as total + amount <= cap in a constraint\n"
+            "2. Review of the handler never sees the check\n"
+            "3. A later change moves the cap update, the constraint goes stale\n"
+            "4. Failures surface as ConstraintRaw with no hint of which rule broke"
+        )
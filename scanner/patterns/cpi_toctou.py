@@ -0,0 +1,252 @@
+"""
+ANCHOR-007: Stale Account Data Across CPI (TOCTOU)
+
+Detects values read from shared accounts (token balances, mint supply,
+lamports) before a CPI and relied on after it. A CPI — even to a trusted
+program such as SPL Token — changes balances and supplies under the
+handler's feet, but Anchor's deserialized copies and any captured locals
+keep the pre-CPI value until the account is explicitly reloaded.
+
+Two shapes are reported:
+  - a local captured from account state before the CPI and used after it
+  - an account field read after a CPI that touched that account, without
+    an intervening `.reload()`
+"""
+
+import re
+from scanner.cpi import SPL_HELPER_PROGRAMS, declared_programs, find_cpi_calls, interface_modules
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.details import CpiTarget
+
+
+class CpiToctouPattern(VulnerabilityPattern):
+    id = "ANCHOR-007"
+    name = "Stale Account Data Across CPI"
+    severity = "Medium"
+    category = "cpi"
+    remediation_effort = "localized"
+    cost = "expensive"
+    description = (
+        "Account balance or supply read before a CPI is used after it. The CPI "
+        "mutates the underlying account, so the handler computes with stale data."
+    )
+
+    # Shared-state fields whose value changes when a token/system CPI executes
+    VOLATILE_FIELD = r"(?:amount|supply|lamports\(\))"
+
+    CAPTURE_RE = re.compile(
+        r"let\s+(?:mut\s+)?(\w+)\s*(?::[^=;]+)?=\s*([^;]*?\b(\w+)\s*\.\s*"
+        + VOLATILE_FIELD + r"\b[^;]*);"
+    )
+
+    CPI_RE = re.compile(
+        r"\b(?:token|token_interface|token_2022|anchor_spl::token|system_program)\s*::\s*"
+        r"(?:transfer|transfer_checked|mint_to|burn|close_account)\s*\("
+        r"|\binvoke(?:_signed)?\s*\("
+        r"|::\s*cpi\s*::\s*\w+\s*\("
+        r"|\btransfer_\w+\s*\("
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        seen = set()
+
+        declared = declared_programs(content)
+        interfaces = interface_modules(content)
+        functions = self._find_functions(content)
+        helpers = {fn["name"]: fn for fn in functions}
+
+        for fn in functions:
+            body = fn["body"]
+            cpis = self._find_cpi_calls(body, declared, interfaces, fn["name"], helpers)
+            if not cpis:
+                continue
+
+            # Shape 1: captured local used after the CPI
+            for cap in self.CAPTURE_RE.finditer(body):
+                var, account = cap.group(1), cap.group(3)
+                for cpi_start, cpi_end, cpi_text, target in cpis:
+                    if cpi_start < cap.end():
+                        continue
+                    # Accounts not involved in the CPI keep their value
+                    if not re.search(rf"\b{re.escape(account)}\b", cpi_text):
+                        continue
+                    use = self._first_use_after(body, var, cpi_end)
+                    if use is None:
+                        continue
+                    line = self._get_line_number(content, fn["body_start"] + use)
+                    key = (line, var)
+                    if key in seen:
+                        break
+                    seen.add(key)
+                    findings.append(self._make_finding(
+                        file_path, content, line, fn["name"],
+                        f"local '{var}' captured from '{account}' at line "
+                        f"{self._get_line_number(content, fn['body_start'] + cap.start())}",
+                        self._get_line_number(content, fn["body_start"] + cpi_start), target,
+                    ))
+                    break
+
+            # Shape 2: account field read after a CPI on it, without reload()
+            for cpi_start, cpi_end, cpi_text, target in cpis:
+                for m in re.finditer(
+                    r"\b(\w+)\s*\.\s*(amount|supply)\b(?!\s*\()", body[cpi_end:]
+                ):
+                    account = m.group(1)
+                    if account in ("self", "ctx", "accounts"):
+                        continue
+                    if not re.search(rf"\b{re.escape(account)}\b", cpi_text):
+                        continue
+                    between = body[cpi_end:cpi_end + m.start()]
+                    if re.search(rf"\b{re.escape(account)}\s*\.\s*reload\s*\(", between):
+                        continue
+                    # Assignment to the field is a write, not a stale read
+                    rest = body[cpi_end + m.end():cpi_end + m.end() + 4]
+                    if re.match(r"\s*=[^=]", rest):
+                        continue
+                    line = self._get_line_number(content, fn["body_start"] + cpi_end + m.start())
+                    key = (line, account)
+                    if key in seen:
+                        continue
+                    seen.add(key)
+                    findings.append(self._make_finding(
+                        file_path, content, line, fn["name"],
+                        f"'{account}.{m.group(2)}' read without reload()",
+                        self._get_line_number(content, fn["body_start"] + cpi_start), target,
+                    ))
+                    break
+
+        return findings
+
+    def estimate_effort(self, finding: Finding) -> str:
+        # A missing reload() is a one-line fix; a captured local means the
+        # post-CPI math has to be reworked.
+        if "without reload()" in finding.description:
+            return "mechanical"
+        return self.remediation_effort
+
+    def _find_cpi_calls(self, body: str, declared=None, interfaces=None,
+                        fn_name: str = "", helpers=None, depth: int = 0) -> list[tuple[int, int, str, CpiTarget]]:
+        """Return (start, end, call_text, target) for each CPI-looking call in a body.
+
+        Combines the CPI front-end (declare_program! clients, #[interface]
+        clients, SPL helpers, invoke) with local transfer_* helper wrappers,
+        whose target is resolved from the wrapper's own CPI.
+        """
+        calls = [
+            (c.start, c.end, c.text,
+             CpiTarget(c.program, c.instruction, c.via, c.resolved, c.signed, [fn_name], " ".join(c.text.split())))
+            for c in find_cpi_calls(body, declared, interfaces)
+        ]
+        known = {start for start, _, _, _ in calls}
+        for m in self.CPI_RE.finditer(body):
+            depth = 1
+            i = m.end()
+            while i < len(body) and depth > 0:
+                if body[i] == "(":
+                    depth += 1
+                elif body[i] == ")":
+                    depth -= 1
+                i += 1
+            if not any(start <= m.start() < end for start, end, _, _ in calls) and m.start() not in known:
+                text = body[m.start():i]
+                calls.append((m.start(), i, text, self._target(text, fn_name, declared, interfaces, helpers, depth)))
+        calls.sort(key=lambda c: c[0])
+        return calls
+
+    def _target(self, text: str, fn_name: str, declared, interfaces, helpers, depth: int) -> CpiTarget:
+        """Target of a call the front-end does not model: an SPL helper it leaves out, or a local wrapper."""
+        call = " ".join(text.split())
+        path = re.match(r"([\w\s:]+?)\s*\(", text).group(1)
+        segments = [seg.strip() for seg in path.split("::")]
+        if len(segments) > 1:
+            program = segments[-3] if segments[-2] == "cpi" and len(segments) > 2 else segments[-2]
+            return CpiTarget(SPL_HELPER_PROGRAMS.get(program, program), segments[-1], "spl-helper",
+                             True, False, [fn_name], call)
+        helper = (helpers or {}).get(segments[-1])
+        if helper and helper["name"] != fn_name and depth < self.max_depth:
+            inner = self._find_cpi_calls(helper["body"], declared, interfaces, helper["name"], helpers, depth + 1)
+            if inner:
+                target = inner[0][3]
+                return CpiTarget(target.program, target.instruction, target.via, target.resolved,
+                                 target.signed, [fn_name] + target.chain, call)
+        return CpiTarget("<unresolved>", segments[-1], "local-helper", False, False, [fn_name], call)
+
+    @staticmethod
+    def _first_use_after(body: str, var: str, pos: int):
+        """Offset of the first computational read of `var` after pos.
+
+        Returns None if the variable is reassigned or shadowed first. Reads
+        inside logging macros (msg!, emit!) are not relied on and are skipped.
+        """
+        for m in re.finditer(rf"\b{re.escape(var)}\b", body[pos:]):
+            start = pos + m.start()
+            after = body[pos + m.end():pos + m.end() + 3]
+            before = body[max(0, start - 8):start]
+            if re.search(r"let\s+(?:mut\s+)?$", before):
+                return None
+            if re.match(r"\s*=[^=]", after):
+                return None
+            macros = list(re.finditer(r"\b(?:msg|emit|emit_cpi)!\s*\(", body[:start]))
+            if macros:
+                inside = body[macros[-1].end():start]
+                if inside.count("(") >= inside.count(")"):
+                    continue
+            return start
+        return None
+
+    def _make_finding(self, file_path, content, line, fn_name, what, cpi_line, target) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=self.severity,
+            file=file_path,
+            line=line,
+            description=(
+                f"In fn {fn_name}: {what} is used after the CPI at line "
+                f"{cpi_line}. The CPI changes the account, so this value is stale."
+            ),
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "vault.amount = 1000 captured before transfer CPI",
+                "after": "CPI moves 400 tokens; handler still computes with 1000",
+                "damage": "Share/price math uses a balance that no longer exists.",
+            },
+            impact={
+                "attack_cost": "Normal instruction fees",
+                "exploitability": "Medium — depends on how the stale value feeds accounting",
+                "breach_cost_context": "Stale-balance accounting bugs drive share-inflation and over-withdrawal exploits.",
+            },
+            anchor_versions_affected="All versions (developer-side pattern)",
+            ecosystem_recommendations=[
+                "Call account.reload()? after the CPI before reading balances",
+                "Compute post-CPI values from the pre-CPI value and the transferred amount explicitly",
+            ],
+            details=target,
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Reload the account after the CPI and re-read the value:\n"
+            "  token::transfer(cpi_ctx, amount)?;\n"
+            "  ctx.accounts.vault.reload()?;\n"
+            "  let balance = ctx.accounts.vault.amount;"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Anchor deserializes accounts once at instruction entry. A CPI writes the "
+            "underlying account data, but neither the Account<T> copy nor locals "
+            "captured from it are refreshed until reload() is called."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. Handler captures vault.amount before a transfer CPI\n"
+            "2. CPI moves tokens in or out of the vault\n"
+            "3. Handler computes shares/fees from the captured (stale) balance\n"
+            "4. Attacker sizes the transfer so the stale math over-credits them"
+        )
@@ -0,0 +1,155 @@
+"""
+ANCHOR-009: Non-Idempotent Crank Instruction
+
+Detects permissionless "crank" instructions (update/refresh/harvest/accrue...)
+that add to stored accumulators without guarding against being executed more
+than once in the same epoch or slot. Anyone can call a crank, so if repeated
+calls keep adding rewards, fees or stake deltas, an attacker double-counts
+rewards or griefs the protocol by spamming the instruction.
+
+A crank is considered guarded if it compares a `last_*` / epoch / slot field
+in a `require!`/`if`/`assert!`, or derives the increment from the time
+elapsed since a stored checkpoint (so a repeat call adds zero).
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+
+
+class CrankIdempotencyPattern(VulnerabilityPattern):
+    id = "ANCHOR-009"
+    name = "Non-Idempotent Crank Instruction"
+    severity = "Medium"
+    category = "state"
+    remediation_effort = "localized"
+    description = (
+        "Permissionless crank mutates accumulators without a last-update "
+        "epoch/slot guard. Repeated calls double-count rewards or fees."
+    )
+
+    CRANK_NAME_RE = re.compile(
+        r"^(?:update|crank|refresh|harvest|sync|accrue|distribute|poke|settle|"
+        r"compound|rebalance)(?:_|$)"
+    )
+
+    CRANK_STRUCT_RE = re.compile(
+        r"^(?:Update|Crank|Refresh|Harvest|Sync|Accrue|Distribute|Poke|Settle|"
+        r"Compound|Rebalance)[A-Z]?"
+    )
+
+    # `acc.field += x`, `acc.field = acc.field.checked_add(..)`, `acc.field = acc.field + x`
+    ACCUMULATE_RE = re.compile(
+        r"\b((?:\w+\.)+(\w+))\s*\+=|"
+        r"\b((?:\w+\.)+(\w+))\s*=\s*(?:\w+\.)*\4\s*(?:\.\s*(?:checked|saturating|wrapping)_add\b|\+)"
+    )
+
+    TIME_IDENT = r"(?:last_\w+|\w*epoch\w*|\w*slot\w*|\w*checkpoint\w*)"
+
+    # Increment derived from elapsed time since a checkpoint: `now - x.last_update`
+    ELAPSED_RE = re.compile(
+        r"(?:-\s*|checked_sub\s*\(\s*|saturating_sub\s*\(\s*)(?:\w+\.)*" + TIME_IDENT + r"\b"
+    )
+
+    def _guard_re(self):
+        """A require!/assert!/if condition on a time checkpoint."""
+        return re.compile(rf"(?:\b{self._guards()}!|\bif\b)[^;{{]*\b{self.TIME_IDENT}\b")
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        structs = {name: body for name, body, _ in self._find_derive_accounts_structs(content)}
+        impls = self._find_impl_blocks(content)
+        facts = Facts(content)
+
+        for fn in self._find_functions(content):
+            accounts_struct = self._accounts_struct_for(fn, facts.instruction_of(fn), impls)
+            if not self._is_crank(fn["name"], accounts_struct):
+                continue
+            # A Signer in the accounts struct means the crank is permissioned
+            if accounts_struct in structs and re.search(r":\s*Signer\s*<", structs[accounts_struct]):
+                continue
+
+            body = fn["body"]
+            acc = self.ACCUMULATE_RE.search(body)
+            if not acc:
+                continue
+            if self._guard_re().search(body) or self.ELAPSED_RE.search(body):
+                continue
+
+            target = acc.group(1) or acc.group(3)
+            line = self._get_line_number(content, fn["body_start"] + acc.start())
+            findings.append(Finding(
+                id=self.id,
+                name=self.name,
+                severity=self.severity,
+                file=file_path,
+                line=line,
+                description=(
+                    f"Crank '{fn['name']}'"
+                    + (f" ({accounts_struct})" if accounts_struct else "")
+                    + f" accumulates into '{target}' with no last-update "
+                    f"epoch/slot guard. Calling it twice counts the increment twice."
+                ),
+                root_cause=self.get_root_cause(),
+                exploit_scenario=self.get_exploit_scenario(),
+                fix_recommendation=self.get_fix_recommendation(),
+                code_snippet=self._extract_snippet(content, line),
+                before_after_state={
+                    "before": "pool.total_rewards = 1000 after the epoch's crank",
+                    "after": "Crank called again in the same epoch: total_rewards = 2000",
+                    "damage": "Rewards/fees accrued twice; stakers withdraw more than was earned.",
+                },
+                impact={
+                    "attack_cost": "Transaction fees only (crank is permissionless)",
+                    "exploitability": "High — any wallet can call the instruction repeatedly",
+                    "breach_cost_context": "Double-accrual in reward cranks inflates liabilities until the vault is insolvent.",
+                },
+                anchor_versions_affected="All versions (developer-side pattern)",
+                ecosystem_recommendations=[
+                    "Store the epoch/slot of the last crank and require it to advance",
+                    "Accrue from elapsed time since a stored checkpoint so repeat calls add zero",
+                ],
+            ))
+
+        return findings
+
+    def _is_crank(self, fn_name: str, accounts_struct) -> bool:
+        if self.CRANK_NAME_RE.match(fn_name):
+            return True
+        return bool(accounts_struct and fn_name in ("process", "handler")
+                    and self.CRANK_STRUCT_RE.match(accounts_struct))
+
+    @staticmethod
+    def _accounts_struct_for(fn: dict, ix, impls: list) -> str:
+        """Accounts struct from `Context<X>`, or the enclosing `impl X` block."""
+        if ix:
+            return ix.accounts_struct
+        for start, end, name in impls:
+            if start <= fn["body_start"] < end:
+                return name
+        return ""
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Record when the crank last ran and refuse to run twice in one period:\n"
+            "  let clock = Clock::get()?;\n"
+            "  require!(state.last_update_epoch < clock.epoch, ErrorCode::AlreadyUpdated);\n"
+            "  state.last_update_epoch = clock.epoch;\n"
+            "  state.total_rewards += epoch_rewards;"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Cranks are intentionally permissionless so keepers can drive state "
+            "forward. Accumulating a fixed increment per call is only correct if "
+            "the instruction can run at most once per period; without a stored "
+            "last-update marker, nothing enforces that."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. Attacker stakes into the pool\n"
+            "2. Attacker calls the reward crank N times in one epoch\n"
+            "3. Each call adds the epoch's rewards to the accumulator again\n"
+            "4. Attacker claims N times their fair share of rewards"
+        )
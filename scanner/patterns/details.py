@@ -0,0 +1,156 @@
+"""
+Rule-specific structured payloads attached to findings.
+
+Descriptions are written for people; tooling that acts on one kind of
+finding (opening a fix PR for a missing ordering constraint, replaying a
+PDA collision on a local validator, allow-listing a CPI target) needs the
+facts behind the sentence. Rules that have them set `Finding.details` to
+one of the types below, and the JSON report carries it as `details`, with
+a `type` field naming the schema:
+
+  - pda-collision (ANCHOR-005): the two seed recipes that derive the
+    closed and the re-created account
+  - invariant (ANCHOR-015): the documented requirement and the expression
+    that would enforce it
+  - cpi-target (ANCHOR-007): the CPI's target program and how it was
+    resolved, from the handler through local wrappers to the call
+  - seed-collision (ANCHOR-031): the two seed recipes of different
+    account types that can derive the same address
+  - zero-division (ANCHOR-047): the denominator, when it is zero, and the
+    branch conditions on the path to the division
+  - health-formula (ANCHOR-062): a health / LTV formula reconstructed
+    with its locals substituted, which way it points, its scale and the
+    checks comparing it
+
+Fields are only added to a type, never renamed or removed, so consumers
+can rely on them across releases.
+"""
+
+from dataclasses import asdict, dataclass, field
+from typing import Optional
+
+
+@dataclass
+class SeedRecipe:
+    """How one accounts-struct field derives its PDA."""
+
+    struct: str
+    field: str
+    line: int
+    # `seeds = [...]` as written, collapsed to one line ("" for a non-PDA account)
+    seeds: str
+    # Seeds with references and conversions stripped, one entry per seed
+    components: list = field(default_factory=list)
+
+
+@dataclass
+class PdaCollision:
+    type = "pda-collision"
+
+    account_type: str
+    closed: SeedRecipe
+    recreated: SeedRecipe
+    # True when both recipes derive the same address, None when it cannot be decided statically
+    same_address: Optional[bool] = None
+
+    def to_dict(self) -> dict:
+        return {"type": self.type, **asdict(self)}
+
+
+@dataclass
+class Invariant:
+    type = "invariant"
+
+    struct: str
+    field: str
+    # ordering | relation | signer | writable | pda | uninitialized
+    kind: str
+    # The doc comment sentence stating the requirement
+    requirement: str
+    # Rust condition that holds when the requirement is enforced; for a
+    # relation, the check `has_one = <related>` performs
+    expression: str
+    # The other field the requirement names, for ordering and relation
+    related: str = ""
+
+    def to_dict(self) -> dict:
+        return {"type": self.type, **asdict(self)}
+
+
+@dataclass
+class CpiTarget:
+    type = "cpi-target"
+
+    # Program module or name, `<unresolved>` or `<Trait implementor>` (see scanner/cpi.py)
+    program: str
+    instruction: str
+    # declare_program | anchor-client | interface | spl-helper | invoke | local-helper
+    via: str
+    resolved: bool
+    signed: bool
+    # Functions from the flagged one down to the one making the CPI
+    chain: list = field(default_factory=list)
+    # The call in the flagged function, collapsed to one line
+    call: str = ""
+
+    def to_dict(self) -> dict:
+        return {"type": self.type, **asdict(self)}
+
+
+@dataclass
+class SeedCollision:
+    type = "seed-collision"
+
+    first_type: str
+    second_type: str
+    # Components hold the resolved shape of each seed (`b"pos"`, `<pubkey>`, `<64-bit int>`, `<bytes>`)
+    first: SeedRecipe
+    second: SeedRecipe
+    # True when both recipes have the same shape seed for seed
+    identical: bool = False
+
+    def to_dict(self) -> dict:
+        return {"type": self.type, **asdict(self)}
+
+
+@dataclass
+class ZeroDivision:
+    type = "zero-division"
+
+    function: str
+    line: int
+    # The dividing statement, collapsed to one line
+    expression: str
+    denominator: str
+    # Atoms that together make the denominator zero (`user.borrowed == 0`)
+    zero_condition: list = field(default_factory=list)
+    # Conditions of the enclosing branches, outermost first (`!(x > 0)` for an else branch)
+    path_condition: list = field(default_factory=list)
+
+    def to_dict(self) -> dict:
+        return {"type": self.type, **asdict(self)}
+
+
+@dataclass
+class HealthFormula:
+    type = "health-formula"
+
+    function: str
+    variable: str
+    line: int
+    # Right-hand side with let-bound locals substituted and casts stripped
+    formula: str
+    # "collateral/debt" (higher is safer) or "debt/collateral" (lower is safer)
+    orientation: str
+    # Value of the formula when collateral equals debt (100 for a percent), "" when unknown
+    scale: str = ""
+    # Conditions comparing the variable, as written
+    comparisons: list = field(default_factory=list)
+    issues: list = field(default_factory=list)
+
+    def to_dict(self) -> dict:
+        return {"type": self.type, **asdict(self)}
+
+
+DETAIL_TYPES = {cls.type: cls for cls in (PdaCollision, Invariant, CpiTarget, SeedCollision, ZeroDivision,
+                                          HealthFormula)}
@@ -0,0 +1,218 @@
+"""
+ANCHOR-015: Documented Requirement Not Enforced
+
+Parses doc comments on accounts-struct fields for stated requirements and
+checks that a matching constraint exists:
+
+    /// Token_0 mint, the key must be smaller then token_1 mint.
+    pub token_mint_0: Box<InterfaceAccount<'info, Mint>>,
+
+needs `constraint = token_mint_0.key() < token_mint_1.key()` (or an
+equivalent `require!`). Forked protocols keep the original documentation
+while the constraint that backed it is lost in the fork, so the comment
+tells reviewers a check exists that the program no longer performs.
+
+Recognized requirements: key ordering against another field, signer,
+writable, relationship to another field ("must match", "must be the
+authority of", "must belong to"), PDA derivation, and uninitialized state.
+A requirement that names no resolvable field is not reported.
+"""
+
+import re
+from scanner.patterns.account_types import account_type
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.details import Invariant
+
+MODAL = r"(?:must|should|needs?\s+to|has\s+to|is\s+required\s+to)"
+STOP_WORDS = {
+    "the", "a", "an", "of", "account", "accounts", "key", "pubkey", "address",
+    "one", "in", "this", "that", "its", "given", "same", "as", "provided",
+}
+
+
+class DocConstraintPattern(VulnerabilityPattern):
+    id = "ANCHOR-015"
+    name = "Documented Requirement Not Enforced"
+    severity = "Medium"
+    category = "constraints"
+    remediation_effort = "mechanical"
+    description = (
+        "An accounts field's doc comment states a requirement (ordering, "
+        "relationship, signer, PDA...) that no constraint or check enforces."
+    )
+
+    ORDER_RE = re.compile(
+        MODAL + r"\s+be\s+(smaller|less|lower|greater|larger|bigger|higher)\s+th[ae]n\s+([^.;,()]+)",
+        re.I,
+    )
+    RELATION_RE = re.compile(
+        MODAL + r"\s+(?:match|equal|be\s+(?:equal\s+to|the\s+same\s+as|the\s+one\s+in|"
+        r"the\s+\w+\s+of|owned\s+by|associated\s+with)|belong\s+to|correspond\s+to)\s+([^.;,()]+)",
+        re.I,
+    )
+    SIGNER_RE = re.compile(MODAL + r"\s+(?:be\s+(?:a\s+|the\s+)?signer|sign\b|have\s+signed)", re.I)
+    WRITABLE_RE = re.compile(MODAL + r"\s+be\s+(?:mutable|writable)", re.I)
+    PDA_RE = re.compile(MODAL + r"\s+be\s+(?:a\s+|the\s+)?(?:pda|program[- ]derived)", re.I)
+    UNINIT_RE = re.compile(MODAL + r"\s+be\s+(?:empty|uninitiali[sz]ed|zeroed|zero)\b", re.I)
+
+    DOC_RE = re.compile(r"///\s?(.*?)(?=\s*///|\s*#\[|$)")
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        checks = self._body_checks(content)
+        for struct_name, body, start_line in self._find_derive_accounts_structs(content):
+            fields = self._parse_struct_fields(body, start_line)
+            names = [f["name"] for f in fields]
+            all_attrs = " ".join(self._constraint_text(f["attrs"]) for f in fields)
+            for field in fields:
+                doc = " ".join(self.DOC_RE.findall(field["attrs"]))
+                if not doc:
+                    continue
+                problem = self._unenforced(field, doc, names, all_attrs, checks)
+                if not problem:
+                    continue
+                requirement, missing, kind, expression, related = problem
+                findings.append(Finding(
+                    id=self.id,
+                    name=self.name,
+                    severity=self.severity,
+                    file=file_path,
+                    line=field["line"],
+                    description=(
+                        f"In struct {struct_name}: the doc comment on '{field['name']}' says it "
+                        f"\"{requirement}\", but {missing}."
+                    ),
+                    root_cause=self.get_root_cause(),
+                    exploit_scenario=self.get_exploit_scenario(),
+                    fix_recommendation=self.get_fix_recommendation(),
+                    code_snippet=self._extract_snippet(content, field["line"]),
+                    before_after_state={
+                        "before": "Reviewers read the doc comment and assume the check exists",
+                        "after": "Attacker passes an account that violates the documented requirement",
+                        "damage": "Invariant the rest of the program relies on is not actually held.",
+                    },
+                    impact={
+                        "attack_cost": "Transaction fees only",
+                        "exploitability": "Depends on the requirement — ordering/relationship checks often guard pool identity",
+                        "breach_cost_context": "Forks that dropped a documented mint-ordering check allowed duplicate pools for one pair.",
+                    },
+                    anchor_versions_affected="All versions (developer-side pattern)",
+                    ecosystem_recommendations=[
+                        "Encode documented account requirements as #[account(...)] constraints",
+                        "Re-audit doc comments after forking: they describe the upstream checks",
+                    ],
+                    details=Invariant(struct_name, field["name"], kind, requirement, expression, related),
+                ))
+        return findings
+
+    def _unenforced(self, field, doc, names, all_attrs, checks):
+        """Return (requirement text, what is missing, kind, enforcing expression, other field)
+        for the first unenforced requirement."""
+        name = field["name"]
+        own = self._constraint_text(field["attrs"])
+
+        m = self.ORDER_RE.search(doc)
+        if m:
+            other = self._resolve(m.group(2), names, name)
+            if other and not self._ordered(name, other, all_attrs + " " + checks):
+                op = "<" if m.group(1).lower() in ("smaller", "less", "lower") else ">"
+                return (m.group(0).strip(), f"no constraint compares {name} with {other}",
+                        "ordering", f"{name}.key() {op} {other}.key()", other)
+
+        m = self.RELATION_RE.search(doc)
+        if m:
+            other = self._resolve(m.group(1), names, name)
+            if other and not self._related(name, other, own, all_attrs, checks):
+                return (m.group(0).strip(), f"no has_one/constraint ties {name} to {other}",
+                        "relation", f"{name}.{other} == {other}.key()", other)
+
+        m = self.SIGNER_RE.search(doc)
+        if m and not (account_type(field["type"]).is_signer or re.search(r"\bsigner\b", own)):
+            return (m.group(0).strip(), "the field is neither Signer<'info> nor marked `signer`",
+                    "signer", f"{name}.is_signer", "")
+
+        m = self.WRITABLE_RE.search(doc)
+        if m and not re.search(r"\bmut\b", own):
+            return m.group(0).strip(), "the field is not marked `mut`", "writable", f"{name}.is_writable", ""
+
+        m = self.PDA_RE.search(doc)
+        if m and not re.search(r"\b(?:seeds|address)\s*=", own):
+            return (m.group(0).strip(), "no `seeds`/`address` constraint derives or pins it",
+                    "pda", f"{name}.key() == Pubkey::find_program_address(seeds, program_id).0", "")
+
+        m = self.UNINIT_RE.search(doc)
+        if m and not re.search(r"\b(?:init|zero)\b", own):
+            return (m.group(0).strip(), "the field is neither `init` nor `zero`",
+                    "uninitialized", f"{name}.data_is_empty()", "")
+        return None
+
+    @staticmethod
+    def _constraint_text(attrs: str) -> str:
+        """Attribute text with doc comments removed."""
+        return re.sub(r"///.*?(?=///|#\[|$)", " ", attrs)
+
+    def _body_checks(self, content: str) -> str:
+        """Conditions in require!/assert!/if across the file (handlers and helpers)."""
+        parts = re.findall(rf"\b{self._guards()}!\s*\(([^;]*)\)\s*;", content)
+        parts += re.findall(r"\bif\s+([^{;]*)\{", content)
+        return " ".join(parts)
+
+    @staticmethod
+    def _words(text: str) -> set:
+        text = re.sub(r"'s\b", "", text.lower())
+        return {w for w in re.split(r"[^a-z0-9]+", text) if w and w not in STOP_WORDS}
+
+    def _resolve(self, phrase: str, names: list, own: str):
+        """Field whose name words all appear in the phrase (longest name wins)."""
+        words = self._words(phrase)
+        best = None
+        for candidate in names:
+            if candidate == own:
+                continue
+            parts = self._words(candidate.replace("_", " "))
+            if parts and parts <= words and (best is None or len(candidate) > len(best)):
+                best = candidate
+        return best
+
+    @staticmethod
+    def _ordered(a: str, b: str, text: str) -> bool:
+        pair = rf"\b{a}\b[^,;]*?[<>]=?[^,;]*?\b{b}\b|\b{b}\b[^,;]*?[<>]=?[^,;]*?\b{a}\b"
+        return bool(re.search(pair, text))
+
+    @staticmethod
+    def _related(a: str, b: str, own: str, all_attrs: str, checks: str) -> bool:
+        if re.search(rf"\b{b}\b", own):
+            return True
+        # Reverse direction: `has_one = a` on b, or a constraint naming both
+        if re.search(rf"\bhas_one\s*=\s*{a}\b", all_attrs):
+            return True
+        for text in (all_attrs, checks):
+            for clause in re.split(r",(?![^()]*\))", text):
+                if re.search(rf"\b{a}\b", clause) and re.search(rf"\b{b}\b", clause):
+                    return True
+        return False
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Turn the documented requirement into a constraint:\n"
+            "  /// Token_0 mint, the key must be smaller than token_1 mint.\n"
+            "  #[account(constraint = token_mint_0.key() < token_mint_1.key())]\n"
+            "  pub token_mint_0: Box<InterfaceAccount<'info, Mint>>,"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Doc comments on accounts fields are not checked by Anchor. When a "
+            "constraint is removed, renamed or never written, the comment keeps "
+            "promising it, and reviewers reading the struct take the promise at "
+            "face value."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. Doc comment says token_0 mint must sort before token_1 mint\n"
+            "2. The fork dropped the constraint that enforced it\n"
+            "3. Attacker creates the pool for (B, A) in addition to (A, B)\n"
+            "4. Liquidity and price feeds split across duplicate pools; routers\n"
+            "   relying on canonical ordering can be steered to the attacker's pool"
+        )
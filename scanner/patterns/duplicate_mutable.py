@@ -12,6 +12,7 @@ and a regular mutable field.
 """
 
 import re
+from scanner.patterns.account_types import account_type
 from scanner.patterns.base import VulnerabilityPattern, Finding
 
 
@@ -19,6 +20,8 @@ class DuplicateMutablePattern(VulnerabilityPattern):
     id = "ANCHOR-002"
     name = "Duplicate Mutable Account Bypass"
     severity = "Medium"
+    category = "accounts"
+    remediation_effort = "mechanical"
     description = (
         "init_if_needed accounts are excluded from Anchor's duplicate mutable "
         "account check. If the account already exists, an attacker could pass "
@@ -104,14 +107,9 @@ class DuplicateMutablePattern(VulnerabilityPattern):
 
     @staticmethod
     def _extract_base_type(type_str: str) -> str:
-        """Extract base account type from Account<'info, TokenAccount>."""
-        m = re.search(r"Account\s*<\s*'[^,]+,\s*(\w+)", type_str)
-        if m:
-            return m.group(1)
-        m = re.search(r"InterfaceAccount\s*<\s*'[^,]+,\s*(\w+)", type_str)
-        if m:
-            return m.group(1)
-        return ""
+        """Extract base account type from Account<'info, TokenAccount>, boxed, interface or zero-copy."""
+        t = account_type(type_str)
+        return t.inner if t.is_typed else ""
 
     def get_fix_recommendation(self) -> str:
         return (
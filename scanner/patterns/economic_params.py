@@ -0,0 +1,291 @@
+"""
+ANCHOR-024: Unchecked Economic Parameter
+
+Liquid-staking and AMM programs take their economics as instruction
+arguments: fee rates, the treasury's cut, min/max fee curves, liquidity
+targets. Marinade validates every such update in one place:
+
+    pub fn validate(&self) -> Result<()> {
+        require_lte!(self.lp_max_fee, Self::MAX_FEE, MarinadeError::LpMaxFeeIsTooHigh);
+        require_gte!(self.lp_max_fee, self.lp_min_fee, MarinadeError::LpFeesAreWrongWayRound);
+        require_gte!(self.lp_liquidity_target, Self::MIN_LIQUIDITY_TARGET, ...);
+        require_lte!(self.treasury_cut, Self::MAX_TREASURY_CUT, ...);
+        Ok(())
+    }
+
+A program that stores the arguments without an equivalent lets one admin
+transaction (or a compromised admin key) set a 100% fee, a max fee below the
+min fee — which underflows the fee curve — or a zero liquidity target the
+curve divides by.
+
+The rule reports handlers (functions taking `Context<T>`, or methods of T)
+that store an argument-derived value into a parameter field without the
+check its kind needs, in the handler or in a function it calls:
+
+  - fee, cut, commission, royalty fields: compared against a bound, or
+    `.check()`ed
+  - `min`/`max` pairs set together: compared with each other
+  - liquidity targets and floors: compared against a bound
+  - denominators and divisors: checked non-zero
+
+Basis-point and percentage rates (`interest_rate: u16`, `*_bps`,
+`basis_points`, `bp_cents`) must be compared against an upper bound such as
+MAX_BASIS_POINTS; a `*_rate` field counts as one when it is stored in a
+u8/u16/u32, so u64 exchange and stake rates are not taken for one.
+
+Denominator constants are checked too: `*_DENOMINATOR`/`*_DIVISOR` must not
+be zero, and a basis-point or percent denominator (`BPS_DENOMINATOR`,
+`MAX_BASIS_POINTS`, `PERCENT`; not bounds such as `MAX_FEE_BPS`) must be
+10_000 or 100. A fee or rate constant above 100% of its scale is reported:
+over 10_000 for `*_BPS`, over 100 for `*_PERCENT`, and over 10_000 for an
+unnamed scale that is divided by 10_000 or a BPS denominator where used.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+
+# Parameter kind -> field name pattern
+KINDS = [
+    ("fee", re.compile(r"(?:^|_)(?:fee|fees|cut|commission|royalty)(?:_|$)")),
+    ("floor", re.compile(r"(?:^|_)(?:target|floor)(?:_|$)")),
+    ("denominator", re.compile(r"denominator|divisor")),
+    ("rate", re.compile(r"(?:^|_)(?:rate|bps|basis_points|bp|bp_cents|pct|percent|percentage)(?:_|$)")),
+]
+# A rate named only `*_rate` is taken for basis points or a percentage when it is stored in a u8/u16/u32
+SCALED_RE = re.compile(r"bps|basis_points|bp_cents|(?:^|_)bp(?:_|$)|pct|percent")
+SMALL_INT_RE = re.compile(r"\b(\w+)\s*:\s*u(?:8|16|32)\b")
+FEE_CONST_RE = re.compile(r"\bconst\s+(\w*(?:FEE|RATE|CUT|COMMISSION|ROYALTY)\w*)\s*:\s*\w+\s*=\s*([\d_]+)\s*;")
+BPS_DENOMINATOR_RE = r"(?:10_?000\b|\w*(?:BPS|BASIS_POINTS)\w*)"
+BOUND_RE = re.compile(r"<|>|\brequire_(?:lte|lt|gte|gt)!|\bmin\s*\(|\bmax\s*\(|\bclamp\s*\(")
+NONZERO_RE = re.compile(r"!=\s*0\b|>\s*0\b|>=\s*1\b|\brequire_(?:gt|neq)!|\bNonZero|\bis_zero\s*\(")
+CONST_RE = re.compile(
+    r"\bconst\s+(\w*(?:DENOMINATOR|DIVISOR)\w*|\w*(?:BPS|BASIS_POINTS)\w*|\w*PERCENT\w*)\s*:\s*\w+\s*=\s*([\w_]+)\s*;"
+)
+CHECK_LABELS = {
+    "bound": "is never compared against a bound",
+    "order": "is never compared with {partner}",
+    "nonzero": "is never checked to be non-zero",
+    "scaled": "is never compared against an upper bound such as MAX_BASIS_POINTS (10_000) or 100%",
+}
+
+
+class EconomicParamsPattern(VulnerabilityPattern):
+    id = "ANCHOR-024"
+    name = "Unchecked Economic Parameter"
+    severity = "Medium"
+    category = "economics"
+    remediation_effort = "localized"
+    description = (
+        "A fee, min/max pair, liquidity target or denominator is set from "
+        "instruction input without the bounds check that keeps the program's "
+        "economics sane."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        facts = Facts(content)
+        functions = {}
+        for fn in self._find_functions(content):
+            functions.setdefault(fn["name"], []).append(fn["body"])
+        self._memo = {}
+
+        small = set(SMALL_INT_RE.findall(content))
+        handlers = [(ix.name, ix.args, ix.body, ix.body_start) for ix in facts.instructions()]
+        for struct in facts.accounts_structs():
+            for fn in self._methods(content, struct.name):
+                args = [a for a in re.findall(r"(?:^|,)\s*(?:mut\s+)?(\w+)\s*:", fn["params"]) if a != "self"]
+                handlers.append((fn["name"], args, fn["body"], fn["body_start"]))
+
+        seen = set()
+        for fn_name, args, body, body_start in handlers:
+            stored = self._stored_params(body, args, small)
+            for field_name, (kind, offset, source) in stored.items():
+                missing = []
+                needed = {"denominator": ["nonzero"], "rate": ["scaled"]}.get(kind, ["bound"])
+                partner = self._partner(field_name)
+                # The ordering is reported once, on the max side
+                if partner in stored and "min" in partner:
+                    needed.append("order")
+                for check in needed:
+                    if not self._enforced(body, field_name, partner, check, functions, 0, source):
+                        missing.append(CHECK_LABELS[check].format(partner=partner))
+                line = self._get_line_number(content, body_start + offset)
+                if missing and (line, field_name) not in seen:
+                    seen.add((line, field_name))
+                    findings.append(self._finding(
+                        file_path, content, line,
+                        f"{fn_name}() sets {kind} parameter '{field_name}' from instruction input, "
+                        f"but it {' and '.join(missing)}. Nothing keeps the program's economics within "
+                        f"sane limits",
+                    ))
+
+        for m in CONST_RE.finditer(content):
+            problem = self._const_problem(m.group(1), m.group(2))
+            if problem:
+                line = self._get_line_number(content, m.start())
+                findings.append(self._finding(file_path, content, line, f"Constant {m.group(1)} {problem}"))
+
+        for m in FEE_CONST_RE.finditer(content):
+            problem = self._fee_const_problem(content, m.group(1), m.group(2))
+            if problem:
+                line = self._get_line_number(content, m.start())
+                findings.append(self._finding(file_path, content, line, f"Constant {m.group(1)} {problem}"))
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    def _methods(self, content: str, struct_name: str) -> list:
+        """Functions in `impl ... StructName<'info>` blocks."""
+        methods = []
+        for m in re.finditer(rf"\bimpl\s*(?:<[^>]*>)?\s*{struct_name}\s*(?:<[^>]*>)?\s*\{{", content):
+            end = self._find_block_end(content, m.end() - 1)
+            if end != -1:
+                methods += [fn for fn in self._find_functions(content) if m.end() <= fn["body_start"] < end]
+        return methods
+
+    @staticmethod
+    def _stored_params(body: str, args: list, small: set = frozenset()) -> dict:
+        """{field: (kind, offset, argument path)} of parameter fields assigned from an argument."""
+        if not args:
+            return {}
+        arg_re = re.compile(r"\b(?:" + "|".join(map(re.escape, args)) + r")\b(?:\s*\.\s*\w+)*")
+        stored = {}
+        # `state.lp_max_fee = params.lp_max_fee;` or `lp_max_fee: params.lp_max_fee,` in a struct literal
+        for m in re.finditer(r"\.\s*(\w+)\s*=(?!=)\s*([^;]+)|(?<![\w.])(?<!let )(\w+)\s*:\s*([^,;{}\n]+)", body):
+            name, value = (m.group(1), m.group(2)) if m.group(1) else (m.group(3), m.group(4))
+            kind = next((k for k, kind_re in KINDS if kind_re.search(name.lower())), None)
+            if kind == "rate" and not SCALED_RE.search(name.lower()) and name not in small:
+                kind = None
+            source = arg_re.search(value)
+            if kind and source and name not in stored:
+                stored[name] = (kind, m.start(), re.sub(r"\s+", "", source.group(0)))
+        return stored
+
+    @staticmethod
+    def _partner(name: str) -> str:
+        swapped = re.sub(r"(?<![a-z])(min|max)(?![a-z])", lambda m: "max" if m.group(1) == "min" else "min", name)
+        return swapped if swapped != name else ""
+
+    def _enforced(self, body: str, name: str, partner: str, check: str, functions: dict, depth: int,
+                  source: str = "") -> bool:
+        """The check on `name` (or, in the handler, on the argument it comes from) appears in
+        `body` or in a local function it calls."""
+        guard_re = re.compile(rf"\b(?:if|{self._guards()}!|assert\w*!)")
+        refs = [re.escape(name)]
+        if source:
+            refs.append(r"\s*\.\s*".join(map(re.escape, source.split("."))))
+        field_re = re.compile(rf"\b(?:{'|'.join(refs)})\b")
+        for stmt in re.split(r"[;{}]", body):
+            if not field_re.search(stmt):
+                continue
+            if check in ("bound", "scaled") and re.search(r"\.\s*check\s*\(", stmt):
+                return True
+            if not guard_re.search(stmt):
+                continue
+            if check in ("bound", "scaled") and BOUND_RE.search(stmt):
+                return True
+            if check == "nonzero" and NONZERO_RE.search(stmt):
+                return True
+            if check == "order" and re.search(rf"\b{re.escape(partner)}\b", stmt):
+                return True
+        if depth >= self.max_depth:
+            return False
+        for callee in set(re.findall(r"\b(\w+)\s*\(", body)) & functions.keys():
+            key = (callee, name, check)
+            if key not in self._memo:
+                self._memo[key] = False  # recursion guard
+                self._memo[key] = any(
+                    self._enforced(b, name, partner, check, functions, depth + 1) for b in functions[callee]
+                )
+            if self._memo[key]:
+                return True
+        return False
+
+    @staticmethod
+    def _const_problem(name: str, value: str) -> str:
+        try:
+            number = int(value.replace("_", ""), 0)
+        except ValueError:
+            return ""
+        upper = name.upper()
+        if ("DENOMINATOR" in upper or "DIVISOR" in upper) and number == 0:
+            return "is zero; every division by it fails"
+        # The unit's denominator itself, not a bound in that unit: MAX_BPS, BPS_DENOMINATOR, not MAX_FEE_BPS
+        rest = re.sub(r"_+", "_", re.sub(r"BPS|BASIS_POINTS|PERCENT(?:AGE)?", "", upper)).strip("_")
+        if rest not in ("", "DENOMINATOR", "DIVISOR", "MAX", "ONE", "FULL", "TOTAL", "ONE_HUNDRED", "HUNDRED"):
+            return ""
+        if ("BPS" in upper or "BASIS_POINTS" in upper) and number != 10_000:
+            return f"is {number}, but a basis-point denominator is 10_000"
+        if "PERCENT" in upper and "BPS" not in upper and number != 100:
+            return f"is {number}, but a percent denominator is 100"
+        return ""
+
+    @staticmethod
+    def _fee_const_problem(content: str, name: str, value: str) -> str:
+        """A fee or rate constant above 100% of the scale it is written in."""
+        number = int(value.replace("_", ""))
+        upper = name.upper()
+        if re.search(r"BPS|BASIS_POINTS", upper):
+            return f"is {number} basis points, a fee above 100%" if number > 10_000 else ""
+        if re.search(r"PERCENT|PCT", upper):
+            return f"is {number} percent, a fee above 100%" if number > 100 else ""
+        if number <= 10_000:
+            return ""
+        # Not named for a scale: applied as basis points when divided by 10_000 / a BPS denominator
+        for stmt in re.split(r"[;{}]", content):
+            if re.search(rf"\b{name}\b", stmt) and not re.search(rf"\bconst\s+{name}\b", stmt) and \
+                    re.search(rf"(?:/\s*|checked_div\s*\(\s*){BPS_DENOMINATOR_RE}", stmt):
+                return f"is {number} and is applied in basis points (divided by 10_000): a fee above 100%"
+        return ""
+
+    def _finding(self, file_path, content, line, message) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=self.severity,
+            file=file_path,
+            line=line,
+            description=f"{message}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "lp_min_fee = 0.3%, lp_max_fee = 3%, treasury_cut = 25%",
+                "after": "lp_max_fee = 0.1% < lp_min_fee, treasury_cut = 100%",
+                "damage": "Fee curve underflows or every unstake is confiscated as fees.",
+            },
+            impact={
+                "attack_cost": "One admin transaction (compromised or careless key)",
+                "exploitability": "Low — needs the parameter authority, but nothing limits the damage",
+                "breach_cost_context": "Parameter bounds are a standard audit item for staking pools and AMMs.",
+            },
+            anchor_versions_affected="All versions (developer-side pattern)",
+            ecosystem_recommendations=[
+                "Validate all parameters in one validate() called after every update (Marinade's LiqPool::validate)",
+                "Keep hard limits as named constants next to the state",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Validate parameters after every update:\n"
+            "  require_lte!(pool.max_fee, MAX_FEE, ErrorCode::FeeTooHigh);\n"
+            "  require_gte!(pool.max_fee, pool.min_fee, ErrorCode::FeesWrongWayRound);\n"
+            "  require_gte!(pool.liquidity_target, MIN_LIQUIDITY_TARGET, ErrorCode::TargetTooLow);"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Economic parameters feed fee curves and share prices. Stored "
+            "unchecked, a single bad value makes the curve underflow, divide "
+            "by zero, or hand all value to the fee receiver."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. The parameter authority (or an attacker holding its key) calls the config instruction\n"
+            "2. It sets max_fee below min_fee, or a 100% treasury cut\n"
+            "3. Unstakes revert on fee-curve underflow, or users lose their whole withdrawal to fees"
+        )
@@ -0,0 +1,304 @@
+"""
+Fact queries for rule authors.
+
+Detectors written directly against the source re-implement struct parsing,
+attribute splitting and line bookkeeping each time. Facts builds that model
+once per source unit and answers the questions rules actually ask:
+
+    from scanner.patterns.facts import Facts
+
+    facts = Facts(content)
+    for ix in facts.instructions():                 # handlers taking Context<T>
+        for account in facts.accounts_of(ix):       # fields of T
+            for c in facts.constraints(account, "realloc::payer"):
+                payer = facts.account(ix.accounts_struct, c.value)
+                if payer and not payer.is_signer: ...
+    facts.taint_paths("amount", r"invoke_signed\\(")  # arg -> sink, per handler
+
+`Account.pda` reads the seeds, bump and `seeds::program` of an account as
+one derivation, so a PDA of another program (Metaplex metadata) counts as
+a validated address without each rule parsing the three clauses.
+
+Everything is derived from the same helpers VulnerabilityPattern uses, so
+line numbers agree with findings produced by hand-written rules. Taint is
+intra-procedural: it follows `let` bindings and assignments inside one
+handler body, in source order.
+
+Reference ports: ANCHOR-003 (realloc_payer.py) and ANCHOR-005
+(close_reinit.py).
+"""
+
+import re
+from dataclasses import dataclass, field
+from typing import Optional, Union
+
+from scanner.patterns.account_types import account_type
+from scanner.patterns.base import VulnerabilityPattern
+
+# The accounts struct T of a handler's `Context<'_, '_, '_, 'info, T>` parameter
+CONTEXT_RE = re.compile(r"Context\s*<\s*(?:'[^,>]+,\s*)*(\w+)")
+
+
+@dataclass
+class Constraint:
+    """One clause of an #[account(...)] attribute, e.g. `has_one = authority`."""
+
+    kind: str      # normalized name: "mut", "has_one", "realloc::payer", "constraint"...
+    value: str     # right-hand side, "" for flags such as `mut`
+    text: str
+    line: int
+
+
+@dataclass
+class Account:
+    """One field of a #[derive(Accounts)] struct."""
+
+    name: str
+    type: str
+    line: int
+    struct: str
+    constraints: list = field(default_factory=list)
+    docs: list = field(default_factory=list)
+
+    @property
+    def wrapper(self) -> str:
+        """Account, AccountLoader, Signer, UncheckedAccount... with Box / Option removed."""
+        return account_type(self.type).wrapper
+
+    @property
+    def inner_type(self) -> str:
+        """T in Account / InterfaceAccount / AccountLoader<'info, T>, boxed or not, else ""."""
+        t = account_type(self.type)
+        return t.inner if t.is_typed else ""
+
+    @property
+    def is_signer(self) -> bool:
+        return bool(re.search(r"\bSigner\s*<", self.type)) or self.has("signer")
+
+    @property
+    def is_mut(self) -> bool:
+        return self.has("mut")
+
+    def has(self, kind: str) -> bool:
+        return any(c.kind == kind for c in self.constraints)
+
+    @property
+    def pda(self) -> Optional["Pda"]:
+        """The address derivation the constraints check, None for an account that is not a PDA."""
+        seeds = next((c.value for c in self.constraints if c.kind == "seeds"), None)
+        if seeds is None:
+            return None
+        program = next((c.value for c in self.constraints if c.kind == "seeds::program"), "")
+        bump = next((c.value for c in self.constraints if c.kind == "bump"), None)
+        return Pda(" ".join(seeds.split()), " ".join(program.split()), bump)
+
+
+@dataclass
+class Pda:
+    """`seeds = [...]` with its `bump`, and `seeds::program` for another program's PDA."""
+
+    seeds: str
+    # Program the address is derived under, "" for this program
+    program: str = ""
+    # Bump expression, "" for a bare `bump`, None when the clause is missing
+    bump: Optional[str] = None
+
+    @property
+    def is_foreign(self) -> bool:
+        return bool(self.program)
+
+
+@dataclass
+class AccountsStruct:
+    name: str
+    line: int
+    accounts: list = field(default_factory=list)
+
+
+@dataclass
+class Instruction:
+    """A handler: a function taking Context<T>."""
+
+    name: str
+    accounts_struct: str
+    params: str
+    body: str
+    body_start: int
+    line: int
+    is_entrypoint: bool = False
+
+    @property
+    def args(self) -> list:
+        """Names of the arguments after ctx."""
+        return [
+            m.group(1) for m in re.finditer(r"(?:^|,)\s*(?:mut\s+)?(\w+)\s*:", self.params)
+            if not re.match(r"\s*Context\b", self.params[m.end():])
+        ]
+
+
+@dataclass
+class TaintPath:
+    """How a source value reaches a sink inside one instruction."""
+
+    instruction: str
+    source: str
+    steps: list      # [(line, statement)] from the first use to the sink
+    sink_line: int
+    sink_text: str
+
+
+class Facts:
+    """Queryable model of one source unit."""
+
+    def __init__(self, content: str, path: str = ""):
+        self.content = content
+        self.path = path
+        self._structs = {}
+        for name, body, start in VulnerabilityPattern._find_derive_accounts_structs(content):
+            self._structs[name] = AccountsStruct(name, start, self._accounts(name, body, start))
+        self._instructions = None
+        self._by_body = None
+
+    # ─── Queries ────────────────────────────────────────────────────
+
+    def accounts_structs(self) -> list[AccountsStruct]:
+        return list(self._structs.values())
+
+    def instructions(self) -> list[Instruction]:
+        if self._instructions is None:
+            self._instructions = self._find_instructions()
+        return self._instructions
+
+    def instruction_of(self, fn: dict) -> Optional[Instruction]:
+        """The instruction a function of _find_functions() is, None unless it takes Context<T>."""
+        if self._by_body is None:
+            self._by_body = {ix.body_start: ix for ix in self.instructions()}
+        return self._by_body.get(fn["body_start"])
+
+    def accounts_of(self, ix: Union[Instruction, str]) -> list[Account]:
+        """Accounts of an instruction, or of an accounts struct given by name."""
+        name = ix.accounts_struct if isinstance(ix, Instruction) else ix
+        struct = self._structs.get(name)
+        return list(struct.accounts) if struct else []
+
+    def account(self, ix: Union[Instruction, str], name: str) -> Optional[Account]:
+        return next((a for a in self.accounts_of(ix) if a.name == name), None)
+
+    @staticmethod
+    def constraints(account: Account, kind: Optional[str] = None) -> list[Constraint]:
+        return [c for c in account.constraints if kind is None or c.kind == kind]
+
+    def taint_paths(self, source: str, sink: str,
+                    ix: Optional[Instruction] = None) -> list[TaintPath]:
+        """Statements through which `source` (identifier or regex) reaches `sink` (regex)."""
+        source_re = re.compile(source if re.search(r"[\\()\[\]|*+?]", source) else rf"\b{re.escape(source)}\b")
+        sink_re = re.compile(sink)
+        paths = []
+        for instruction in [ix] if ix else self.instructions():
+            tainted = [source_re]
+            steps = []
+            for offset, stmt in self._statements(instruction.body):
+                if not any(t.search(stmt) for t in tainted):
+                    continue
+                line = VulnerabilityPattern._get_line_number(self.content, instruction.body_start + offset)
+                text = " ".join(stmt.split())
+                steps.append((line, text))
+                if sink_re.search(stmt):
+                    paths.append(TaintPath(instruction.name, source, list(steps), line, text))
+                    continue
+                m = re.match(r"\s*(?:let\s+(?:mut\s+)?)?(\w+)\s*(?::[^=]+)?(?:[+\-*/]?=)(?!=)", stmt)
+                if m:
+                    tainted.append(re.compile(rf"\b{re.escape(m.group(1))}\b"))
+        return paths
+
+    # ─── Construction ───────────────────────────────────────────────
+
+    def _accounts(self, struct_name: str, body: str, start: int) -> list[Account]:
+        fields = VulnerabilityPattern._parse_struct_fields(body, start)
+        constraints = self._constraints_by_line(body, start)
+        accounts = []
+        previous = start
+        for f in fields:
+            owned = [c for line, c in constraints if previous < line <= f["line"]]
+            docs = re.findall(r"///\s?(.*?)(?=\s*///|\s*#\[|$)", f["attrs"])
+            accounts.append(Account(f["name"], f["type"], f["line"], struct_name, owned, docs))
+            previous = f["line"]
+        return accounts
+
+    @staticmethod
+    def _constraints_by_line(body: str, start: int) -> list[tuple[int, Constraint]]:
+        result = []
+        for m in VulnerabilityPattern.ACCOUNT_ATTR_RE.finditer(body):
+            inner_start = m.start(1)
+            depth, begin = 0, 0
+            inner = m.group(1) + ","
+            for i, ch in enumerate(inner):
+                if ch in "([{":
+                    depth += 1
+                elif ch in ")]}":
+                    depth -= 1
+                if ch == "," and depth == 0:
+                    clause = inner[begin:i]
+                    if clause.strip():
+                        lead = len(clause) - len(clause.lstrip())
+                        line = start + body[:inner_start + begin + lead].count("\n") + 1
+                        result.append((line, _constraint(clause, line)))
+                    begin = i + 1
+        return result
+
+    def _find_instructions(self) -> list[Instruction]:
+        program_ranges = []
+        for m in re.finditer(r"#\[program\]\s*(?:pub\s+)?mod\s+\w+\s*\{", self.content):
+            end = VulnerabilityPattern._find_block_end(self.content, m.end() - 1)
+            if end != -1:
+                program_ranges.append((m.end(), end))
+        result = []
+        for fn in VulnerabilityPattern._find_functions(self.content):
+            m = CONTEXT_RE.search(fn["params"])
+            if not m:
+                continue
+            result.append(Instruction(
+                name=fn["name"], accounts_struct=m.group(1), params=fn["params"],
+                body=fn["body"], body_start=fn["body_start"], line=fn["line"],
+                is_entrypoint=any(s <= fn["body_start"] < e for s, e in program_ranges),
+            ))
+        return result
+
+    @staticmethod
+    def _statements(body: str) -> list[tuple[int, str]]:
+        """(offset, text) of each statement, split on `;` and block braces.
+
+        Braces inside parentheses or brackets are struct literals and closure
+        bodies, so a call like `transfer(CpiContext::new(p, Transfer { .. }), amount)`
+        stays one statement. Comments are blanked out of the text.
+        """
+        body = re.sub(r"//[^\n]*|/\*.*?\*/", lambda c: re.sub(r"[^\n]", " ", c.group()), body, flags=re.DOTALL)
+        result = []
+        begin = 0
+        depth = 0
+        for m in re.finditer(r"[;{}()\[\]]", body):
+            if m.group() in "([":
+                depth += 1
+                continue
+            if m.group() in ")]":
+                depth = max(depth - 1, 0)
+                continue
+            if depth:
+                continue
+            text = body[begin:m.start()]
+            if text.strip():
+                result.append((begin + len(text) - len(text.lstrip()), text))
+            begin = m.end()
+        if body[begin:].strip():
+            tail = body[begin:]
+            result.append((begin + len(tail) - len(tail.lstrip()), tail))
+        return result
+
+
+def _constraint(clause: str, line: int) -> Constraint:
+    text = " ".join(clause.split())
+    m = re.match(r"([\w]+(?:\s*::\s*\w+)*)\s*(?:=\s*(.*))?$", text, re.DOTALL)
+    if not m:
+        return Constraint(kind=text, value="", text=text, line=line)
+    kind = re.sub(r"\s*::\s*", "::", m.group(1))
+    return Constraint(kind=kind, value=(m.group(2) or "").strip(), text=text, line=line)
@@ -0,0 +1,234 @@
+"""
+ANCHOR-045: Q64.64 Scale Mismatch
+
+CLMM and reward programs keep prices, emission rates and growth
+accumulators as Q64.64 fixed point: a u128 holding the value times 2^64.
+Raydium names them `sqrt_price_x64`, `emissions_per_second_x64`,
+`fee_growth_global_0_x64` and converts at every boundary with
+`fixed_point_64::Q64`:
+
+    U128::from(fee_growth_delta_x64)
+        .mul_div_floor(U128::from(liquidity), U128::from(fixed_point_64::Q64))
+
+Arithmetic that meets a plain integer without that conversion compiles
+and is wrong by a factor of 2^64:
+
+    let fees_owed = fee_growth_delta_x64 * liquidity;          // still Q64.64
+    reward_info.reward_growth_global_x64 += reward_amount;       // plain + Q64.64
+
+Q64.64 values are names ending in `x64` / `_x64`, fields whose doc comment
+says Q64.64 (or Q64 / X64), and locals bound to a Q64.64 value in the
+same function. The rule reports (High):
+
+  - `+`, `-` (also `+=`, `-=`, `checked_add`/`wrapping_sub`/...) and
+    comparisons between a Q64.64 value and a plain integer
+  - `*` or `/` of a Q64.64 value by a plain integer whose result goes to a
+    plain binding or field — a Q64.64 result named as one is a scalar
+    multiple and fine
+  - a plain integer divided by a Q64.64 value
+
+A statement that shifts by 64, goes through `Q64` (or `RESOLUTION`), or
+uses a `mul_div`/`mul_shr` helper is taken to convert correctly. Literals
+and SCREAMING_CASE constants are not plain quantities.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.math_summary import _blank
+
+Q64_NAME_RE = re.compile(r"(?:^|_)x64$", re.I)
+Q64_DOC_RE = re.compile(r"\bQ64(?:\.64)?\b|\bX64\b")
+CONVERTED_RE = re.compile(r"\bQ64\b|<<\s*64\b|>>\s*64\b|\bRESOLUTION\b")
+SCALED_RE = re.compile(CONVERTED_RE.pattern + r"|\bmul_div\w*|\bmul_shr\b|\bshl_div\b")
+# Wrappers that keep a value's scale
+WRAPPER_RE = re.compile(r"\b(?:U\d+|u128|i128|u64)\s*::\s*from\s*\(\s*([\w.]+(?:\(\))?)\s*\)|\bidentity\s*\(\s*([\w.]+)\s*\)")
+CAST_RE = re.compile(r"\s+as\s+[iu](?:8|16|32|64|128|size)\b|\s*\.\s*(?:as_[iu]\d+|into|try_into|unwrap|clone)\s*\(\s*\)")
+OPERAND = r"[A-Za-z_][\w]*(?:\s*\.\s*[A-Za-z_]\w*)*(?:\(\))?|\d[\d_]*\w*"
+BINARY_RE = re.compile(rf"(?<![\w.])({OPERAND})\s*(\+|-|\*|/|<=?|>=?|==|!=)(?!=)\s*({OPERAND})(?![\w.(])")
+METHOD_RE = re.compile(
+    rf"(?<![\w.])({OPERAND})\s*\.\s*(?:checked|wrapping|saturating|overflowing)_(add|sub|mul|div)\s*\(\s*({OPERAND})\s*\)"
+)
+METHOD_OP_RE = re.compile(r"\s*\.\s*(?:checked|wrapping|saturating|overflowing)_(add|sub|mul|div)\s*\(")
+TARGET_RE = re.compile(r"^\s*(?:let\s+(?:mut\s+)?(\w+)\s*(?::[^=]+)?=(?!=)|([\w.\[\]]+)\s*([-+*/]?)=(?!=))")
+OPS = {"add": "+", "sub": "-", "mul": "*", "div": "/"}
+
+
+class FixedPointPattern(VulnerabilityPattern):
+    id = "ANCHOR-045"
+    name = "Q64.64 Scale Mismatch"
+    severity = "High"
+    category = "arithmetic"
+    remediation_effort = "localized"
+    description = (
+        "Arithmetic mixes a Q64.64 fixed-point value with a plain integer "
+        "without a shift or mul_div through Q64, giving results off by 2^64."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        code = _blank(content)
+        documented = self._documented_fields(content)
+        for fn in self._find_functions(content):
+            body = code[fn["body_start"]:fn["body_start"] + len(fn["body"])]
+            q64 = set(documented) | {p for p in self._params(fn["params"]) if Q64_NAME_RE.search(p)}
+            for m in re.finditer(r"[^;{}]+", body):
+                stmt = m.group(0)
+                problem = self._mismatch(stmt, q64)
+                line = self._get_line_number(content, fn["body_start"] + m.start() + len(stmt) - len(stmt.lstrip()))
+                if problem:
+                    text = re.sub(r"\s*\.\s*", ".", " ".join(stmt.split()))
+                    findings.append(self._finding(
+                        file_path, content, line,
+                        f"In {fn['name']}: `{text}` {problem} without a shift or mul_div "
+                        f"by fixed_point_64::Q64, so the result is off by a factor of 2^64",
+                    ))
+                    continue
+                target = TARGET_RE.match(stmt)
+                if target and target.group(1) and self._q64_valued(stmt[target.end():], q64):
+                    q64.add(target.group(1))
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    @staticmethod
+    def _documented_fields(content: str) -> set:
+        """Struct fields whose doc comment says they are Q64.64."""
+        names = set()
+        for m in re.finditer(r"((?:^[ \t]*///[^\n]*\n)+)(?:[ \t]*#\[[^\n]*\n)*[ \t]*(?:pub\s+)?(\w+)\s*:", content, re.M):
+            if Q64_DOC_RE.search(m.group(1)):
+                names.add(m.group(2))
+        return names
+
+    @staticmethod
+    def _params(params: str) -> list:
+        return re.findall(r"(?:^|,)\s*(?:mut\s+)?(\w+)\s*:", params)
+
+    @staticmethod
+    def _is_q64(operand: str, q64: set) -> bool:
+        last = re.split(r"\s*\.\s*", operand.replace("()", ""))[-1]
+        return bool(Q64_NAME_RE.search(last)) or last in q64
+
+    @staticmethod
+    def _is_plain(operand: str) -> bool:
+        last = re.split(r"\s*\.\s*", operand.replace("()", ""))[-1]
+        return not re.fullmatch(r"\d[\d_]*\w*|[A-Z][A-Z0-9_]*|self|true|false", last)
+
+    @staticmethod
+    def _unwrap(stmt: str) -> str:
+        stmt = CAST_RE.sub("", stmt)
+        while True:
+            unwrapped = WRAPPER_RE.sub(lambda m: m.group(1) or m.group(2), stmt)
+            if unwrapped == stmt:
+                return stmt
+            stmt = unwrapped
+
+    def _pairs(self, stmt: str) -> list[tuple[str, str, str]]:
+        """(left, operator, right) of every binary operation, method forms included."""
+        text = self._unwrap(stmt)
+        pairs = [(m.group(1), m.group(2), m.group(3)) for m in BINARY_RE.finditer(text)]
+        # Overlapping chains: `a + b * c` also pairs b with c
+        for m in re.finditer(rf"(?=(?<![\w.])({OPERAND})\s*([-+*/])(?!=)\s*({OPERAND})(?![\w.(]))", text):
+            pairs.append((m.group(1), m.group(2), m.group(3)))
+        pairs += [(m.group(1), OPS[m.group(2)], m.group(3)) for m in METHOD_RE.finditer(text)]
+        return list(dict.fromkeys(pairs))
+
+    def _mismatch(self, stmt: str, q64: set) -> str:
+        if SCALED_RE.search(stmt):
+            return ""
+        target = TARGET_RE.match(stmt)
+        compound = target.group(3) if target and target.group(2) else None
+        if target and target.group(2) and compound:
+            lhs, rhs = target.group(2), stmt[target.end():].strip()
+            if re.fullmatch(OPERAND, rhs):
+                pairs = [(lhs, compound, rhs)]
+            else:
+                pairs = []
+        else:
+            pairs = []
+        pairs += self._pairs(stmt[target.end():] if target else stmt)
+        bound = (target.group(1) or target.group(2)) if target else None
+        for left, op, right in pairs:
+            lq, rq = self._is_q64(left, q64), self._is_q64(right, q64)
+            if lq == rq:
+                continue
+            q, plain = (left, right) if lq else (right, left)
+            if not self._is_plain(plain):
+                continue
+            q, plain = (re.sub(r"\s*\.\s*", ".", " ".join(o.split())) for o in (q, plain))
+            if op in ("+", "-") or op in ("<", "<=", ">", ">=", "==", "!="):
+                verb = {"+": "adds", "-": "subtracts"}.get(op, "compares")
+                return f"{verb} plain integer '{plain}' and Q64.64 value '{q}'"
+            if op == "/" and rq:
+                return f"divides plain integer '{plain}' by Q64.64 value '{q}'"
+            if bound and not self._is_q64(bound, q64):
+                return (f"scales Q64.64 value '{q}' by '{plain}' into plain '{bound}', which stays "
+                        f"multiplied by 2^64")
+        return ""
+
+    def _q64_valued(self, expr: str, q64: set) -> bool:
+        """The expression is a Q64.64 value: it reads one, and does not convert or mix it."""
+        if CONVERTED_RE.search(expr):
+            return False
+        text = METHOD_OP_RE.sub(lambda m: f" {OPS[m.group(1)]} (", self._unwrap(expr))
+        # Values only: function and macro names are not operands
+        operands = [m.group(0) for m in re.finditer(OPERAND, text)
+                    if not re.match(r"\s*[(!]", text[m.end():])]
+        # time * emissions_x64 / liquidity through mul_div stays Q64.64
+        if SCALED_RE.search(expr):
+            return any(self._is_q64(o, q64) for o in operands)
+        return any(self._is_q64(o, q64) for o in operands) and \
+            not any(self._is_plain(o) and not self._is_q64(o, q64) for o in operands)
+
+    def _finding(self, file_path, content, line, message) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=self.severity,
+            file=file_path,
+            line=line,
+            description=f"{message}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "Position owed 120 USDC of fees: growth delta 2^64 * 0.0012, liquidity 100,000",
+                "after": "fees_owed computed as growth_delta_x64 * liquidity = 2.2e21",
+                "damage": "Collect transfers the whole fee vault, or the multiplication overflows and blocks collection.",
+            },
+            impact={
+                "attack_cost": "Any position large enough to trigger the path",
+                "exploitability": "High — the error is deterministic and 2^64 large",
+                "breach_cost_context": "Fixed-point unit errors in CLMM forks have drained fee and reward vaults.",
+            },
+            anchor_versions_affected="All versions (program-side arithmetic)",
+            ecosystem_recommendations=[
+                "Keep Q64.64 values in _x64-named variables and fields",
+                "Convert at every boundary with mul_div against fixed_point_64::Q64 or a >> 64 shift",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Convert between Q64.64 and plain integers explicitly:\n"
+            "  let fees_owed = U128::from(fee_growth_delta_x64)\n"
+            "      .mul_div_floor(U128::from(liquidity), U128::from(fixed_point_64::Q64))\n"
+            "      .unwrap()\n"
+            "      .as_u64();"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "A Q64.64 value and a plain integer are both u128 to the compiler. "
+            "Adding them, or multiplying a Q64.64 value by an amount without "
+            "dividing by 2^64 afterwards, type-checks and yields a number 2^64 "
+            "times too large or too small."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. Fees owed are computed as fee_growth_delta_x64 * liquidity, never shifted\n"
+            "2. A position opens and waits for a single swap to accrue fees\n"
+            "3. collect_fees transfers min(owed, vault balance), i.e. the whole vault\n"
+            "4. Every other LP's fees are gone"
+        )
@@ -0,0 +1,78 @@
+"""
+Assertion macros recognized as guards.
+
+Rules that ask "is this value checked before use?" treat Anchor's
+`require*!` and Rust's `assert*!` families as guards. Projects often add
+their own — Marinade's `require_lte!`/`require_lt!` live in its lib.rs,
+others use `ensure!` or `check_bounds!` — and a check written with an
+unknown macro reads as no check at all.
+
+Project macros are registered in three ways:
+
+  - `--guard-macro NAME` on scan/report (repeatable or comma-separated),
+  - a guard-macros file (`--guard-macros FILE`): TOML/JSON `macros = [...]`
+    or plain text, one name per line,
+  - automatically: a `macro_rules!` anywhere in the target whose expansion
+    branches on a condition and returns an error (local_guard_macros).
+
+Registered names are matched exactly; the built-in families keep matching
+any suffix.
+"""
+
+import json
+import re
+
+try:
+    import tomllib
+except ImportError:  # Python < 3.11
+    import tomli as tomllib
+
+BUILTIN_GUARDS = r"require\w*|assert\w*"
+
+MACRO_DEF_RE = re.compile(r"\bmacro_rules!\s*(\w+)\s*\{")
+ERROR_EXIT_RE = re.compile(r"\breturn\s+Err\b|\berr!\s*\(|\berror!\s*\(|\bpanic!\s*\(|\bErr\s*\(")
+CONDITION_RE = re.compile(r"\bif\b|\bmatch\b|\brequire\w*!|\bassert\w*!")
+NAME_RE = re.compile(r"^[A-Za-z_]\w*$")
+
+
+def guard_alternation(extra=()) -> str:
+    """Regex alternation (no `!`) of built-in plus registered guard macros."""
+    names = sorted({n.rstrip("!") for n in extra if n})
+    return "(?:" + "|".join([BUILTIN_GUARDS] + [re.escape(n) for n in names]) + ")"
+
+
+def local_guard_macros(content: str) -> set:
+    """macro_rules! names whose body tests a condition and exits with an error."""
+    found = set()
+    for m in MACRO_DEF_RE.finditer(content):
+        depth, end = 0, len(content)
+        for i in range(m.end() - 1, len(content)):
+            if content[i] == "{":
+                depth += 1
+            elif content[i] == "}":
+                depth -= 1
+                if depth == 0:
+                    end = i
+                    break
+        body = content[m.end():end]
+        if ERROR_EXIT_RE.search(body) and CONDITION_RE.search(body):
+            found.add(m.group(1))
+    return found
+
+
+def load_guard_macros(path: str) -> list:
+    """Read macro names from a TOML/JSON/text file; invalid names raise ValueError."""
+    with open(path, "rb") as f:
+        text = f.read().decode("utf-8")
+    if path.endswith(".json"):
+        data = json.loads(text)
+        names = data if isinstance(data, list) else data.get("macros", [])
+    elif path.endswith(".toml"):
+        names = tomllib.loads(text).get("macros", [])
+    else:
+        names = [line.split("#", 1)[0].strip() for line in text.splitlines()]
+    names = [n.strip().rstrip("!") for n in names if n and n.strip()]
+    invalid = [n for n in names if not NAME_RE.match(n)]
+    if invalid:
+        raise ValueError(f"invalid macro name(s): {', '.join(invalid)}")
+    return names
@@ -0,0 +1,326 @@
+"""
+ANCHOR-056: Panic In Instruction Handler
+
+A panic inside an instruction aborts it with a bare
+`Program failed to complete` — no error code, no message the client can
+act on, and no way for the program to recover or log which input caused
+it. When the panic depends on data an attacker controls, it is also a
+denial of service on every instruction that reaches it:
+
+    let clock = Clock::get().unwrap();
+    let price = u64::from_le_bytes(data[40..48].try_into().unwrap());   // short account: abort
+    let tier = config.tiers[tier_index as usize];                        // tier_index from the caller
+
+The rule reports (Medium), in the #[program] handlers and in every local
+function they reach within the interprocedural depth:
+
+  - `.unwrap()` and `.expect(..)`;
+  - slice indexing with a range (`data[40..48]`, `buf[8..]`) not preceded
+    by a length check on the same value;
+  - array and Vec indexing whose index is derived from an instruction
+    argument — followed through `let` bindings and into helpers whose
+    parameters receive it — unless the index is bounds-checked against
+    `.len()` or reduced with `% len`.
+
+In a unit without the #[program] module (a file split off a concatenated
+target, a `state/` or `utils/` module) the roots are its Context handlers
+and the public functions nothing in the unit calls.
+
+A site in a helper is reported once, with the call path from the first
+handler that reaches it (`withdraw_reward → withdraw_reward_handler →
+calc_reward`). `ctx.bumps.get(..).unwrap()` is left out: the key is a
+literal account name and cannot miss. So is the `.try_into().unwrap()`
+of a range slice into an array of its length; the slice is the panic.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+from scanner.patterns.math_summary import _blank
+from scanner.patterns.validation import CALL_RE, _param_names, _split_top
+
+UNWRAP_RE = re.compile(r"\.\s*(?:unwrap\s*\(\s*\)|expect\s*\()")
+RANGE_INDEX_RE = re.compile(r"([\w.]*\w)\s*\[([^\[\];]*\.\.[^\[\];]*)\]")
+INDEX_RE = re.compile(r"([\w.]*\w|\))\s*\[([^\[\];]+)\]")
+BUMPS_RE = re.compile(r"\bbumps\.get\(")
+SLICE_INTO_RE = re.compile(r"\[[^\[\]]*\.\.[^\[\]]*\]\.try_into\(\)$")
+LET_RE = re.compile(r"\s*let\s+(?:mut\s+)?(\w+|\([^)]*\))\s*(?::[^=]+)?=(?!=)(.*)", re.S)
+FOR_RE = re.compile(r"\s*for\s+(\w+)\s+in\s+(.*)", re.S)
+LEN_CHECK_RE = r"\.\s*len\s*\(\s*\)"
+
+FIX = {
+    "unwrap": ("Return an error instead of panicking: `{expr}?` for a Result, "
+               "`{expr}.ok_or(ErrorCode::...)?` for an Option."),
+    "slice": ("Check the length before slicing, or slice with get:\n"
+              "  let bytes = {base}.get({range}).ok_or(ErrorCode::InvalidAccountData)?;"),
+    "index": ("Bounds-check the caller's index, or index with get:\n"
+              "  let item = {base}.get({index}).ok_or(ErrorCode::InvalidIndex)?;"),
+}
+
+
+class HandlerPanicPattern(VulnerabilityPattern):
+    id = "ANCHOR-056"
+    name = "Panic In Instruction Handler"
+    severity = "Medium"
+    category = "availability"
+    remediation_effort = "mechanical"
+    cost = "expensive"
+    description = (
+        "unwrap/expect, unchecked slice ranges and caller-indexed arrays in an "
+        "instruction abort it with a panic instead of a program error."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        code = _blank(content)
+        functions = self._find_functions(code)
+        by_name = {}
+        for fn in functions:
+            by_name.setdefault(fn["name"], []).append(fn)
+        facts = Facts(content, file_path)
+        instructions = facts.instructions()
+        entrypoints = [ix for ix in instructions if ix.is_entrypoint]
+        roots = [(fn, ix.args) for ix in entrypoints or instructions
+                 for fn in by_name.get(ix.name, []) if fn["body_start"] == ix.body_start]
+        if not entrypoints:
+            # A unit split off its program (utils/, state/): its public helpers are reached from
+            # handlers in other units, with arguments not known here
+            called = {m.group(1) for fn in functions for m in CALL_RE.finditer(fn["body"])}
+            roots += [(fn, []) for fn in functions
+                      if fn["name"] not in called and not any(fn is root for root, _ in roots)
+                      and re.search(r"\bpub\s+fn\s+" + fn["name"] + r"\b", code)]
+
+        sites, seen = {}, set()
+        for fn, args in roots:
+            self._walk(fn, set(args), [fn["name"]], by_name, sites, seen, code, self._guards_of(code, fn))
+
+        findings = [
+            self._finding(file_path, content, line, kind, fn_name, path, expr, parts)
+            for (line, kind), (fn_name, path, expr, parts) in sites.items()
+        ]
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    def _walk(self, fn: dict, tainted: set, path: list, by_name: dict, sites: dict, seen: set, code: str,
+              guards: str = ""):
+        # A function already walked with the same tainted parameters adds nothing
+        key = (fn["body_start"], frozenset(tainted))
+        if key in seen:
+            return
+        seen.add(key)
+        body = fn["body"]
+        tainted = self._derived(body, tainted)
+        self._sites(fn, tainted, path, sites, code)
+        if len(path) >= self.max_depth:
+            return
+        # #[access_control] guards run before the handler, with its arguments
+        calls = body + "\n" + guards
+        for m in CALL_RE.finditer(calls):
+            for callee in by_name.get(m.group(1), []):
+                if callee["name"] in path:
+                    continue
+                args = self._call_args(calls, m.end() - 1)
+                params = [name for name, _ in _param_names(callee["params"])]
+                passed = {p for p, a in zip(params, args) if self._mentions(a, tainted)}
+                self._walk(callee, passed, path + [callee["name"]], by_name, sites, seen, code)
+
+    @staticmethod
+    def _guards_of(code: str, fn: dict) -> str:
+        """Expressions of the #[access_control(..)] attributes on a handler."""
+        start = code.rfind("fn", 0, fn["body_start"])
+        head = code[:start]
+        attrs = re.search(r"((?:\s*#\[[^\[\]]*(?:\[[^\[\]]*\][^\[\]]*)*\])+)\s*(?:pub(?:\([^)]*\))?\s+)?$", head)
+        if not attrs:
+            return ""
+        return "\n".join(m.group(1) for m in re.finditer(r"#\[\s*access_control\s*\((.*)\)\s*\]", attrs.group(1)))
+
+    @staticmethod
+    def _derived(body: str, tainted: set) -> set:
+        """Tainted names plus locals bound from them (let and for)."""
+        tainted = set(tainted)
+        for stmt in re.split(r"[;{}]", body):
+            m = LET_RE.match(stmt) or FOR_RE.match(stmt)
+            if m and HandlerPanicPattern._mentions(m.group(2), tainted):
+                tainted.update(re.findall(r"\w+", m.group(1)))
+        tainted.discard("mut")
+        return tainted
+
+    @staticmethod
+    def _mentions(expr: str, names: set) -> bool:
+        return any(re.search(rf"\b{re.escape(n)}\b", expr) for n in names)
+
+    @staticmethod
+    def _call_args(body: str, open_pos: int) -> list:
+        depth = 0
+        for i in range(open_pos, len(body)):
+            depth += {"(": 1, ")": -1}.get(body[i], 0)
+            if depth == 0:
+                return _split_top(body[open_pos + 1:i])
+        return []
+
+    def _sites(self, fn: dict, tainted: set, path: list, sites: dict, code: str):
+        body = fn["body"]
+        for stmt_m in re.finditer(r"[^;{}]+", body):
+            stmt = stmt_m.group(0)
+            base = fn["body_start"] + stmt_m.start()
+            for m in UNWRAP_RE.finditer(stmt):
+                receiver = self._receiver(stmt, m.start())
+                # A slice converted to its own length fails only in the slice, reported below
+                if BUMPS_RE.search(receiver) or SLICE_INTO_RE.search(receiver):
+                    continue
+                call = "unwrap()" if "unwrap" in m.group(0) else "expect(..)"
+                receiver = self._last_call(receiver)
+                self._add(sites, code, base + m.start(), "unwrap", fn, path, f"{receiver}.{call}",
+                          {"expr": receiver})
+            for m in RANGE_INDEX_RE.finditer(stmt):
+                target, index = m.group(1), m.group(2).strip()
+                if re.fullmatch(r"0?\.\.=?0?", index) or self._within_array(code, target, index):
+                    continue
+                if self._length_checked(body, target, stmt_m.start()):
+                    continue
+                self._add(sites, code, base + m.start(), "slice", fn, path, f"{target}[{index}]",
+                          {"base": target, "range": index})
+            for m in INDEX_RE.finditer(stmt):
+                target, index = m.group(1), m.group(2).strip()
+                if ".." in index or not self._mentions(index, tainted):
+                    continue
+                if re.search(LEN_CHECK_RE, index) or self._index_checked(body, index, stmt_m.start()):
+                    continue
+                self._add(sites, code, base + m.start(), "index", fn, path, f"{target}[{index}]",
+                          {"base": target, "index": index})
+
+    @staticmethod
+    def _receiver(stmt: str, end: int) -> str:
+        """The method chain an `.unwrap()` applies to: paths, calls, indexing and turbofish."""
+        i = end
+        while i > 0:
+            ch = stmt[i - 1]
+            if ch in ")]>":
+                close, open_ = ch, {")": "(", "]": "[", ">": "<"}[ch]
+                depth, j = 0, i - 1
+                while j >= 0:
+                    depth += {close: 1, open_: -1}.get(stmt[j], 0)
+                    if depth == 0:
+                        break
+                    j -= 1
+                if j < 0 or (ch == ">" and not stmt[:j].rstrip().endswith("::")):
+                    break
+                i = j
+            elif ch.isalnum() or ch in "_.:?":
+                i -= 1
+            elif ch.isspace() and (stmt[:i].rstrip().endswith((".", "::")) or stmt[i:].lstrip().startswith(".")):
+                i -= 1
+            else:
+                break
+        return " ".join(stmt[i:end].split()).replace(" .", ".")
+
+    @staticmethod
+    def _last_call(receiver: str) -> str:
+        """A long chain shortened to the call the unwrap applies to: `...checked_div(100)`."""
+        if len(receiver) <= 60:
+            return receiver
+        depth = 0
+        for i in range(len(receiver) - 1, 0, -1):
+            depth += {")": 1, "]": 1, "(": -1, "[": -1}.get(receiver[i], 0)
+            if depth == 0 and receiver[i] == ".":
+                return "..." + receiver[i + 1:]
+        return receiver
+
+    @staticmethod
+    def _within_array(code: str, target: str, index: str) -> bool:
+        """A literal range inside a fixed-size array (`board: [u8; 9]`, `[b' '; 10]`)."""
+        bounds = re.fullmatch(r"(\d*)\s*\.\.(=?)\s*(\d*)", index)
+        if not bounds:
+            return False
+        name = re.escape(target.split(".")[-1])
+        m = re.search(rf"\b{name}\s*(?::\s*|=\s*)\[[^\[\];]+;\s*(\d+)\s*\]", code)
+        if not m:
+            return False
+        start, inclusive, end = bounds.groups()
+        last = int(end) + bool(inclusive) if end else int(m.group(1))
+        return int(start or 0) <= last <= int(m.group(1))
+
+    @staticmethod
+    def _length_checked(body: str, target: str, before: int) -> bool:
+        """A comparison on `target.len()` ahead of the slice."""
+        name = re.escape(target.split(".")[-1])
+        return bool(re.search(rf"\b{name}\s*{LEN_CHECK_RE}\s*(?:[<>]=?|==)|(?:[<>]=?|==)\s*[\w.]*\b{name}\s*{LEN_CHECK_RE}",
+                              body[:before]))
+
+    @staticmethod
+    def _index_checked(body: str, index: str, before: int) -> bool:
+        """`index < x.len()` (or `>=` in an early return) ahead of the access, or `% len`."""
+        if "%" in index:
+            return True
+        for name in re.findall(r"[A-Za-z_]\w*", index):
+            if name in ("as", "usize", "u8", "u16", "u32", "u64"):
+                continue
+            if re.search(rf"\b{re.escape(name)}\b[^;{{}}]*?(?:[<>]=?)[^;{{}}]*{LEN_CHECK_RE}|"
+                         rf"{LEN_CHECK_RE}[^;{{}}]*?(?:[<>]=?)[^;{{}}]*\b{re.escape(name)}\b", body[:before]):
+                return True
+        return False
+
+    def _add(self, sites, code, pos, kind, fn, path, expr, parts):
+        line = self._get_line_number(code, pos)
+        sites.setdefault((line, kind), (fn["name"], list(path), expr, parts))
+
+    def _finding(self, file_path, content, line, kind, fn_name, path, expr, parts) -> Finding:
+        where = f"In {fn_name}" + (f", reached from {' → '.join(path[:-1])}" if len(path) > 1 else "")
+        what = {
+            "unwrap": "panics when the value is an error or None",
+            "slice": "panics when the data is shorter than the range",
+            "index": "panics when the caller passes an index past the end",
+        }[kind]
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=self.severity,
+            file=file_path,
+            line=line,
+            description=(
+                f"{where}: `{expr}` {what}; the instruction aborts with 'Program failed to complete' "
+                f"instead of a program error."
+            ),
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=FIX[kind].format(**parts),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "claim(tier_index = 7) against a config with 4 tiers",
+                "after": "instruction aborted: Program failed to complete",
+                "damage": "No error code reaches the client; every call with that input fails the same way.",
+            },
+            impact={
+                "attack_cost": "One transaction with an out-of-range argument or a short account",
+                "exploitability": "Medium — availability; depends on who controls the panicking input",
+                "breach_cost_context": "Panics in shared instructions (crank, liquidate, settle) block every "
+                                       "user behind the bad input until the program is upgraded.",
+            },
+            anchor_versions_affected="All versions (program-side code)",
+            ecosystem_recommendations=[
+                "Deny clippy::unwrap_used, clippy::expect_used and clippy::indexing_slicing in program crates",
+                "Return named errors from every fallible step of an instruction",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Return errors instead of panicking:\n"
+            "  let clock = Clock::get()?;\n"
+            "  let tier = config.tiers.get(tier_index as usize).ok_or(ErrorCode::InvalidTier)?;"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "unwrap, expect and out-of-bounds indexing panic. On Solana a panic "
+            "aborts the instruction with no error code, so a failure the program "
+            "could have reported — a missing sysvar, a short account, a bad index "
+            "— looks like a crash to the client."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. claim indexes config.tiers with the caller's tier_index\n"
+            "2. Attacker sends tier_index past the end of the list\n"
+            "3. The instruction panics; no error code, nothing logged about the input\n"
+            "4. In a crank or settlement path, the same input blocks every later call"
+        )
@@ -0,0 +1,333 @@
+"""
+ANCHOR-053: has_one Field Never Set Or Freely Overwritten
+
+`has_one = authority` compares the account passed as `authority` with the
+key stored in `pool.authority`. The check is only as good as the write
+that stored the key, and that write lives in another instruction:
+
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        ctx.accounts.pool.fee_bps = 30;               // authority never set
+        Ok(())
+    }
+
+    pub fn set_authority(ctx: Context<SetAuthority>, new: Pubkey) -> Result<()> {
+        ctx.accounts.pool.authority = new;            // any caller
+        Ok(())
+    }
+
+    #[account(mut, has_one = authority)]
+    pub pool: Account<'info, Pool>,
+
+The rule reads every instruction of the unit and, for each field of the
+unit's #[account] structs that some accounts struct relies on —
+`has_one = <field>`, or a constraint comparing `<state>.<field>` with an
+account's key — reports (High) in one finding:
+
+  - never set: the unit has instructions creating the state (`init`,
+    `zero`, `init_if_needed`) and none of them writes the field, directly,
+    through a struct literal, or in a function it calls. The field stays
+    Pubkey::default(): the check then accepts the System Program's address
+    for an account that need not sign, and can never pass for a Signer
+  - overwritable: an instruction that takes the state without creating it
+    writes the field with no gate on its caller — no `has_one` or `address`
+    binding a signer, no key-comparing constraint or body check, no
+    per-user seeds — so anyone stores their own key and then passes the check
+
+Writes through a method of the state type (`pool.init(..)`) or of the
+accounts struct (`ctx.accounts.process()`) count as the instruction's.
+Creation-time writes by an ungated initialize are ANCHOR-034's.
+"""
+
+import re
+from scanner.patterns.authority_init import KEY_COMPARISON_RE, UPGRADE_AUTHORITY_RE, WRITE_RE
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+from scanner.patterns.lifecycle import TYPE_RE
+from scanner.patterns.state_binding import STATE_STRUCT_RE
+from scanner.patterns.validation import _balanced
+
+ALIAS_RE = re.compile(
+    r"let\s+(?:mut\s+)?(\w+)\s*(?::[^=]+)?=\s*&?\s*(?:mut\s+)?(?:ctx\s*\.\s*accounts|self)\s*\.\s*(\w+)"
+)
+IMPL_RE = re.compile(r"\bimpl\s*(?:<[^{>]*>)?\s*(?:[\w:]+\s*(?:<[^{>]*>)?\s+for\s+)?(\w+)\s*(?:<[^{>]*>)?\s*\{")
+CALL_RE = re.compile(r"(?:\b(\w+)\s*\.\s*|::\s*|(?<![\w.:]))(\w+)\s*\(")
+CREATE_KINDS = ("init", "zero", "init_if_needed")
+
+
+class HasOneBindingPattern(VulnerabilityPattern):
+    id = "ANCHOR-053"
+    name = "has_one Field Never Set Or Freely Overwritten"
+    severity = "High"
+    category = "init"
+    remediation_effort = "localized"
+    description = (
+        "A state field checked with has_one is never written when the account is "
+        "created, or can be rewritten by anyone, so the check binds nothing."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        facts = Facts(content)
+        methods = self._methods(content)
+        functions = {fn["name"]: fn["body"] for fn in self._find_functions(content)}
+        states = set(STATE_STRUCT_RE.findall(content))
+        reliance = self._reliance(facts, states)
+        if not reliance:
+            return findings
+
+        creators, writers = {}, {}
+        for ix in facts.instructions():
+            accounts = {a.name: a for a in facts.accounts_of(ix)}
+            types = {n: self._type(a) for n, a in accounts.items()}
+            for name, account in accounts.items():
+                if types[name] and any(account.has(k) for k in CREATE_KINDS):
+                    creators.setdefault(types[name], []).append((ix, self._called(ix.body, functions, methods)))
+            gated = None
+            for (target, field), offset in self._writes(ix, types, methods).items():
+                state = types.get(target)
+                if (state, field) not in reliance or any(accounts[target].has(k) for k in CREATE_KINDS):
+                    continue
+                if gated is None:
+                    gated = self._gated(ix, accounts)
+                if not gated and not self._per_user(accounts[target], accounts):
+                    line = self._get_line_number(content, ix.body_start + offset) if offset >= 0 else ix.line
+                    writers.setdefault((state, field), []).append((ix.name, line))
+
+        for (state, field), sites in reliance.items():
+            problems, fixes = [], []
+            created = creators.get(state, [])
+            if created and not any(self._sets(body, state, field) for _, body in created):
+                names = ", ".join(dict.fromkeys(ix.name for ix, _ in created))
+                unsigned = [s for s, signer in sites if not signer]
+                signed = [s for s, signer in sites if signer]
+                effect = []
+                if unsigned:
+                    effect.append(f"{self._list(unsigned)} accept the System Program's address as '{field}'")
+                if signed:
+                    effect.append(f"{self._list(signed)} can never succeed")
+                problems.append(
+                    f"no instruction creating {state} accounts ({names}) sets {state}.{field}, so it stays "
+                    f"Pubkey::default(): {' and '.join(effect)}"
+                )
+                fixes.append(f"Set the field where the account is created, in {names}:\n"
+                             f"  {state.lower()}.{field} = ctx.accounts.{field}.key();")
+            if (state, field) in writers:
+                where = ", ".join(f"{name} (line {line})" for name, line in writers[(state, field)])
+                problems.append(
+                    f"{where} rewrite{'s' if len(writers[(state, field)]) == 1 else ''} {state}.{field} "
+                    f"without checking the caller, so anyone can store their own key and then pass the check"
+                )
+                first = writers[(state, field)][0][0]
+                fixes.append(f"Gate {first} on the current {field}:\n"
+                             f"  #[account(mut, has_one = {field})]\n"
+                             f"  pub {state.lower()}: Account<'info, {state}>,\n"
+                             f"  pub {field}: Signer<'info>,")
+            if not problems:
+                continue
+            struct, line, check, _ = sites[0][0]
+            findings.append(self._finding(
+                file_path, content, line,
+                f"{state}.{field} is checked with `{check}` in {self._list([s for s, _ in sites])}, but "
+                + "; and ".join(problems),
+                "\n".join(fixes),
+            ))
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    @staticmethod
+    def _type(account) -> str:
+        m = TYPE_RE.search(account.type)
+        return m.group(1) if m else ""
+
+    @staticmethod
+    def _list(sites: list) -> str:
+        names = list(dict.fromkeys(s[0] for s in sites))
+        return ", ".join(names)
+
+    def _reliance(self, facts, states: set) -> dict:
+        """{(state type, field): [((struct, line, check text, field account), field account signs)]}."""
+        reliance = {}
+        for struct in facts.accounts_structs():
+            accounts = {a.name: a for a in struct.accounts}
+            for account in struct.accounts:
+                state = self._type(account)
+                if state not in states:
+                    continue
+                for c in account.constraints:
+                    checked = []
+                    if c.kind == "has_one":
+                        field = re.match(r"\s*(\w+)", c.value)
+                        if field:
+                            checked.append((field.group(1), field.group(1)))
+                    elif c.kind == "constraint":
+                        name = re.escape(account.name)
+                        for m in re.finditer(
+                            rf"\b{name}\s*\.\s*(\w+)\s*==\s*(\w+)\s*\.\s*key\s*\(\s*\)|"
+                            rf"(\w+)\s*\.\s*key\s*\(\s*\)\s*==\s*{name}\s*\.\s*(\w+)\b", c.value,
+                        ):
+                            field, other = (m.group(1), m.group(2)) if m.group(1) else (m.group(4), m.group(3))
+                            checked.append((field, other))
+                    for field, other in checked:
+                        signer = other in accounts and accounts[other].is_signer
+                        entry = reliance.setdefault((state, field), [])
+                        if not any(s[0] == struct.name for s, _ in entry):
+                            entry.append(((struct.name, c.line, c.text, other), signer))
+        return reliance
+
+    def _methods(self, content: str) -> dict:
+        """{type name: {method name: body}} of the unit's impl blocks."""
+        methods = {}
+        for m in IMPL_RE.finditer(content):
+            end = self._find_block_end(content, m.end() - 1)
+            if end == -1:
+                continue
+            block = content[m.end():end]
+            for fn in self._find_functions(block):
+                methods.setdefault(m.group(1), {})[fn["name"]] = fn["body"]
+        return methods
+
+    def _writes(self, ix, types: dict, methods: dict) -> dict:
+        """{(account, field): offset in the body} written by the instruction; offset -1 for a called method."""
+        writes = {}
+        bodies = [(ix.body, 0)]
+        struct_methods = methods.get(ix.accounts_struct, {})
+        for m in re.finditer(r"\bctx\s*\.\s*accounts\s*\.\s*(\w+)\s*\(", ix.body):
+            if m.group(1) in struct_methods:
+                bodies.append((struct_methods[m.group(1)], -1))
+        for body, base in bodies:
+            aliases = {m.group(1): m.group(2) for m in ALIAS_RE.finditer(body) if m.group(2) in types}
+            for m in WRITE_RE.finditer(body):
+                target = aliases.get(m.group(1), m.group(1))
+                if target in types:
+                    writes.setdefault((target, m.group(2)), m.start() if base == 0 else -1)
+            for m in CALL_RE.finditer(body):
+                target = aliases.get(m.group(1), m.group(1)) if m.group(1) else None
+                state = types.get(target) if target else None
+                method = methods.get(state, {}).get(m.group(2)) if state else None
+                if method:
+                    for field in re.findall(r"\bself\s*\.\s*(\w+)\s*=(?!=)", method):
+                        writes.setdefault((target, field), m.start() if base == 0 else -1)
+            for name, state in types.items():
+                for literal in re.finditer(rf"\b{state}\s*\{{", body):
+                    for field in self._literal_fields(body, literal.end() - 1):
+                        writes.setdefault((name, field), literal.start() if base == 0 else -1)
+        return writes
+
+    @staticmethod
+    def _literal_fields(text: str, open_pos: int) -> list:
+        inner = _balanced(text, open_pos)
+        return re.findall(r"(?:^|,)\s*(\w+)\s*(?=[:,]|$)", inner.strip())
+
+    def _called(self, body: str, functions: dict, methods: dict, depth: int = 2) -> str:
+        """The body followed by the bodies of the unit's functions it calls, to the given depth."""
+        # Methods of different types share names (`init`): a call reaches all of them
+        every = {name: [body] for name, body in functions.items()}
+        for table in methods.values():
+            for name, method in table.items():
+                every.setdefault(name, []).append(method)
+        seen, frontier, text = set(), [body], body
+        for _ in range(depth):
+            called = []
+            for part in frontier:
+                for m in CALL_RE.finditer(part):
+                    name = m.group(2)
+                    if name in every and name not in seen:
+                        seen.add(name)
+                        called.extend(every[name])
+            text += "\n" + "\n".join(called)
+            frontier = called
+        return text
+
+    def _sets(self, text: str, state: str, field: str) -> bool:
+        """Whether the text writes `.field`, or builds the state with the field set."""
+        if re.search(rf"\.\s*{re.escape(field)}\s*=(?!=)", text):
+            return True
+        for literal in re.finditer(rf"\b(?:{state}|Self)\s*\{{", text):
+            if field in self._literal_fields(text, literal.end() - 1):
+                return True
+        return False
+
+    def _gated(self, ix, accounts: dict) -> bool:
+        """Whether the instruction binds its caller to a known key."""
+        signers = {n for n, a in accounts.items() if a.is_signer}
+        for account in accounts.values():
+            for c in account.constraints:
+                if c.kind == "has_one" and c.value.split("@")[0].strip() in signers:
+                    return True
+                if c.kind == "address" and account.is_signer:
+                    return True
+                if c.kind == "constraint" and KEY_COMPARISON_RE.search(c.value) and \
+                        any(re.search(rf"\b{re.escape(s)}\b", c.value) for s in signers):
+                    return True
+                if UPGRADE_AUTHORITY_RE.search(c.value):
+                    return True
+        if UPGRADE_AUTHORITY_RE.search(ix.body):
+            return True
+        return any(KEY_COMPARISON_RE.search(stmt) and re.search(rf"==|!=|\b{self._guards()}!", stmt)
+                   for stmt in re.split(r"[;{}]", ix.body))
+
+    @staticmethod
+    def _per_user(account, accounts: dict) -> bool:
+        """A PDA seeded by a signer's key: each caller writes only their own account."""
+        pda = account.pda
+        if pda is None:
+            return False
+        return any(a.is_signer and re.search(rf"\b{re.escape(n)}\s*\.\s*key\b", pda.seeds)
+                   for n, a in accounts.items())
+
+    def _finding(self, file_path, content, line, message, fix) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=self.severity,
+            file=file_path,
+            line=line,
+            description=f"{message}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=fix,
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "pool.authority = Pubkey::default() (initialize never set it)",
+                "after": "withdraw_fees accepts authority = 11111111111111111111111111111111",
+                "damage": "has_one compares against a key nobody chose; the gated instruction is open or dead.",
+            },
+            impact={
+                "attack_cost": "One transaction",
+                "exploitability": "High — the stored key is public and the check is deterministic",
+                "breach_cost_context": "Admin checks against an unset or attacker-written key give away the gated "
+                                       "instructions.",
+            },
+            anchor_versions_affected="All versions (developer-side pattern)",
+            ecosystem_recommendations=[
+                "Set every field a has_one relies on in the instruction that creates the account",
+                "Gate writes of authority fields on the current authority signing",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Set the field when the account is created, and gate later changes on it:\n"
+            "  pool.authority = ctx.accounts.authority.key();   // in initialize\n"
+            "  #[account(mut, has_one = authority)]              // in set_authority\n"
+            "  pub pool: Account<'info, Pool>,\n"
+            "  pub authority: Signer<'info>,"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "has_one checks an account against a key stored in state; it says "
+            "nothing about how that key got there. If no creating instruction "
+            "stores it, the field holds Pubkey::default(), and if any caller can "
+            "rewrite it, the check accepts whoever wrote last."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. initialize creates the pool without setting pool.authority\n"
+            "2. Attacker calls set_authority (no gate) with their own key, or passes the System\n"
+            "   Program's address as an unsigned authority\n"
+            "3. has_one = authority now matches the attacker's account\n"
+            "4. Attacker calls withdraw_fees and drains the fee vault"
+        )
@@ -20,6 +20,8 @@ class InitIfNeededPattern(VulnerabilityPattern):
     id = "ANCHOR-001"
     name = "init_if_needed Incomplete Field Validation"
     severity = "High"
+    category = "init"
+    remediation_effort = "localized"
     description = (
         "Token or associated token account accepted via init_if_needed without "
         "validation of delegate, close_authority, or state fields. An attacker "
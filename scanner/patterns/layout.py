@@ -0,0 +1,418 @@
+"""
+Layout and serialized size of account types.
+
+Zero-copy accounts are read by casting the account bytes to the struct, so
+their layout is the on-chain format. `#[account(zero_copy)]` is `repr(C)`:
+fields are aligned and bytemuck rejects implicit padding. The legacy
+`zero_copy(unsafe)` form, and any `#[repr(C, packed)]` struct, has no
+padding and alignment 1, so a field can sit at any offset:
+
+    #[account(zero_copy(unsafe))]
+    #[repr(C, packed)]
+    pub struct PoolState {
+        pub bump: [u8; 1],          // offset 0
+        pub amm_config: Pubkey,     // offset 1
+        pub tick_spacing: u16,      // offset 33: misaligned
+    }
+
+Other `#[account]` types are borsh-serialized: no padding, `Option<T>` is
+a tag byte plus T, enums a tag byte plus the largest variant, and `Vec` /
+`String` a 4-byte length plus at most `#[max_len(n)]` elements.
+
+Layout(content) parses the unit's structs, enums and integer constants
+(`Type::NAME` for constants in `impl Type`) and computes size, alignment
+and field offsets as the SBF target lays them out (u128 aligned to 8,
+Pubkey a byte array), the borsh size of a type, and the value of space
+expressions such as `8 + PoolState::LEN`, `8 + size_of::<Pool>()` or
+`8 + Escrow::INIT_SPACE`. Types it cannot resolve (other crates' types,
+generic parameters, unbounded vectors) have no size, and neither do the
+offsets after them.
+"""
+
+import re
+from dataclasses import dataclass, field
+from typing import Optional
+
+from scanner.patterns.base import VulnerabilityPattern
+
+STRUCT_RE = re.compile(r"\bpub(?:\([^)]*\))?\s+struct\s+(\w+)\s*(?:<[^>{]*>)?\s*\{")
+ENUM_RE = re.compile(r"\bpub(?:\([^)]*\))?\s+enum\s+(\w+)\s*\{")
+CONST_RE = re.compile(r"\bconst\s+(\w+)\s*:\s*(?:u8|u16|u32|u64|usize|i32|i64)\s*=\s*")
+FIELD_RE = re.compile(r"^(?:pub(?:\([^)]*\))?\s+)?(\w+)\s*:\s*(.+)$", re.S)
+SIZE_OF_RE = re.compile(r"\b(?:(?:std|core)\s*::\s*)?(?:mem\s*::\s*)?size_of\s*::\s*<\s*([\w:]+)\s*>\s*\(\s*\)")
+# (size, alignment) on SBF; borsh sizes are the same
+PRIMITIVES = {
+    "u8": (1, 1), "i8": (1, 1), "bool": (1, 1),
+    "u16": (2, 2), "i16": (2, 2),
+    "u32": (4, 4), "i32": (4, 4), "f32": (4, 4),
+    "u64": (8, 8), "i64": (8, 8), "f64": (8, 8), "usize": (8, 8), "isize": (8, 8),
+    "u128": (16, 8), "i128": (16, 8),
+    "Pubkey": (32, 1),
+}
+# bytemuck-style byte-array wrappers: PodU64, PodI128, PodBool
+POD_RE = re.compile(r"^Pod(?:([UI])(16|32|64|128)|Bool)$")
+DISCRIMINATOR_LEN = 8
+
+
+@dataclass
+class Field:
+    name: str
+    type: str
+    line: int
+    attrs: str = ""              # field attributes, e.g. `#[max_len(32)]`
+
+
+@dataclass
+class StructDef:
+    name: str
+    line: int
+    attrs: str                   # attribute lines above the struct, joined
+    fields: list = field(default_factory=list)
+
+    @property
+    def zero_copy(self) -> bool:
+        return bool(re.search(r"#\[(?:account\s*\(\s*)?zero_copy\b", self.attrs))
+
+    @property
+    def account(self) -> bool:
+        """A program account type: `#[account]`, `#[account(zero_copy)]`, ..."""
+        return bool(re.search(r"#\[account\b", self.attrs))
+
+    @property
+    def packed(self) -> bool:
+        """`repr(packed)`, explicitly or through `zero_copy(unsafe)`."""
+        return bool(re.search(r"#\[repr\s*\([^)]*\bpacked\b", self.attrs)
+                    or re.search(r"\bzero_copy\s*\(\s*unsafe\s*\)", self.attrs))
+
+    @property
+    def repr_c(self) -> bool:
+        return self.zero_copy or bool(re.search(r"#\[repr\s*\([^)]*\bC\b", self.attrs))
+
+    def derives(self, trait: str) -> bool:
+        return any(re.search(rf"\b{trait}\b", d) for d in re.findall(r"#\[derive\s*\(([^)]*)\)", self.attrs))
+
+
+@dataclass
+class Const:
+    key: str                     # "NAME", or "Type::NAME" inside `impl Type`
+    expr: str
+    line: int
+
+
+class Layout:
+    """Sizes, alignments and offsets of the types of a source unit."""
+
+    def __init__(self, content: str):
+        code = _blank_comments(content)
+        self.structs = struct_defs(content, code)
+        self.enums = enum_defs(code)
+        self.consts = const_defs(content, code)
+        self._values = {}
+        self._cache = {}
+        self._borsh = {}
+
+    # ─── In-memory layout (zero-copy) ───────────────────────────────
+
+    def size_align(self, type_: str) -> Optional[tuple]:
+        """(size, alignment) of a type, or None when it cannot be resolved."""
+        type_ = re.sub(r"\s+", " ", type_).strip()
+        if type_ not in self._cache:
+            self._cache[type_] = None       # recursion guard
+            self._cache[type_] = self._size_align(type_)
+        return self._cache[type_]
+
+    def _size_align(self, type_: str) -> Optional[tuple]:
+        if type_ in PRIMITIVES:
+            return PRIMITIVES[type_]
+        pod = POD_RE.match(type_)
+        if pod:
+            return (int(pod.group(2)) // 8 if pod.group(2) else 1, 1)
+        array = re.match(r"^\[\s*(.+?)\s*;\s*(.+)\]$", type_)
+        if array:
+            elem, length = self.size_align(array.group(1)), self.eval(array.group(2))
+            if elem is None or length is None:
+                return None
+            return (elem[0] * length, elem[1])
+        struct = self.structs.get(type_.split("::")[-1])
+        if struct is None or not (struct.packed or struct.repr_c):
+            return None
+        offsets = self.offsets(struct)
+        if not offsets or offsets[-1][1] is None:
+            return None
+        last, offset, _ = offsets[-1]
+        end = offset + self.size_align(last.type)[0]
+        if struct.packed:
+            return (end, 1)
+        align = max(self.size_align(f.type)[1] for f, _, _ in offsets)
+        return (-(-end // align) * align, align)
+
+    def offsets(self, struct: StructDef) -> list:
+        """[(Field, offset, natural alignment)]; offsets are None after a type of unknown size."""
+        result, offset = [], 0
+        for f in struct.fields:
+            sa = self.size_align(f.type)
+            if sa is None:
+                result.append((f, None, None))
+                offset = None
+                continue
+            size, align = sa
+            if offset is not None and not struct.packed:
+                offset = -(-offset // align) * align
+            result.append((f, offset, align))
+            offset = offset + size if offset is not None else None
+        return result
+
+    # ─── Serialized size ────────────────────────────────────────────
+
+    def account_size(self, name: str) -> Optional[int]:
+        """Bytes an account type occupies after the discriminator."""
+        struct = self.structs.get(name)
+        if struct is None:
+            return None
+        if struct.zero_copy:
+            sa = self.size_align(name)
+            return sa[0] if sa else None
+        return self.borsh_size(name)
+
+    def borsh_size(self, type_: str, max_len: tuple = ()) -> Optional[int]:
+        """Largest borsh encoding of a type; `max_len` bounds Vec/String lengths, outermost first."""
+        type_ = re.sub(r"\s+", " ", type_).strip()
+        key = (type_, max_len)
+        if key not in self._borsh:
+            self._borsh[key] = None         # recursion guard
+            self._borsh[key] = self._borsh_size(type_, max_len)
+        return self._borsh[key]
+
+    def _borsh_size(self, type_: str, max_len: tuple) -> Optional[int]:
+        if type_ in PRIMITIVES:
+            return PRIMITIVES[type_][0]
+        pod = POD_RE.match(type_)
+        if pod:
+            return int(pod.group(2)) // 8 if pod.group(2) else 1
+        array = re.match(r"^\[\s*(.+?)\s*;\s*(.+)\]$", type_)
+        if array:
+            elem, length = self.borsh_size(array.group(1), max_len), self.eval(array.group(2))
+            return elem * length if elem is not None and length is not None else None
+        generic = re.match(r"^(?:[\w:]+::)?(Option|Box|Vec)\s*<\s*(.+)\s*>$", type_)
+        if generic:
+            inner = self.borsh_size(generic.group(2), max_len[1:] if generic.group(1) == "Vec" else max_len)
+            if inner is None:
+                return None
+            if generic.group(1) == "Option":
+                return 1 + inner
+            if generic.group(1) == "Box":
+                return inner
+            return 4 + max_len[0] * inner if max_len else None
+        if type_ == "String":
+            return 4 + max_len[0] if max_len else None
+        tuple_ = re.match(r"^\((.*)\)$", type_)
+        if tuple_:
+            sizes = [self.borsh_size(t) for t in _split(tuple_.group(1))]
+            return sum(sizes) if None not in sizes else None
+        name = type_.split("::")[-1]
+        if name in self.enums:
+            variants = []
+            for payload in self.enums[name]:
+                sizes = [self.borsh_size(t) for t in payload]
+                if None in sizes:
+                    return None
+                variants.append(sum(sizes))
+            return 1 + max(variants, default=0)
+        struct = self.structs.get(name)
+        if struct is None:
+            return None
+        if struct.zero_copy:
+            sa = self.size_align(name)
+            return sa[0] if sa else None
+        sizes = [self.borsh_size(f.type, self._max_len(f)) for f in struct.fields]
+        return sum(sizes) if None not in sizes else None
+
+    def _max_len(self, f: Field) -> tuple:
+        m = re.search(r"#\[max_len\s*\(([^)]*)\)\]", f.attrs)
+        if not m:
+            return ()
+        values = tuple(self.eval(v) for v in _split(m.group(1)))
+        return values if None not in values else ()
+
+    # ─── Constants and space expressions ────────────────────────────
+
+    def value(self, key: str) -> Optional[int]:
+        """Value of the constant `NAME` or `Type::NAME`."""
+        if key not in self._values:
+            self._values[key] = None        # recursion guard
+            const = self.consts.get(key)
+            if const is not None:
+                owner = key.rsplit("::", 1)[0] if "::" in key else ""
+                self._values[key] = self.eval(const.expr, owner)
+        return self._values[key]
+
+    def eval(self, expr: str, owner: str = "") -> Optional[int]:
+        """Value of an integer expression over literals, constants, size_of and INIT_SPACE."""
+        expr = expr.strip()
+        if expr.startswith("{") and expr.endswith("}"):
+            return self._eval_block(expr[1:-1], owner)
+        expr = re.sub(r"\bas\s+\w+", "", expr)
+        expr = re.sub(r"(?<=\d)_(?=\d)|(?<=\d)(?:u8|u16|u32|u64|usize|i32|i64)\b", "", expr)
+        expr = re.sub(r"\b(?:\w+\s*::\s*)*DISCRIMINATOR\s*\.\s*len\s*\(\s*\)", str(DISCRIMINATOR_LEN), expr)
+
+        def size_of(m):
+            sa = self.size_align(m.group(1).split("::")[-1])
+            return str(sa[0]) if sa else "?"
+        expr = SIZE_OF_RE.sub(size_of, expr)
+        expr = re.sub(r"\b[a-z_]\w*\s*::\s*(?=\w)", "", expr)     # module paths
+
+        def path(m):
+            type_name = owner if m.group(1) == "Self" else m.group(1)
+            if m.group(2) == "INIT_SPACE" and f"{type_name}::INIT_SPACE" not in self.consts:
+                value = self.borsh_size(type_name)
+            else:
+                value = self.value(f"{type_name}::{m.group(2)}")
+            return "?" if value is None else str(value)
+        expr = re.sub(r"\b(?:\w+\s*::\s*)*(Self|[A-Z]\w*)\s*::\s*([A-Z_][A-Z0-9_]*)\b", path, expr)
+
+        def name(m):
+            value = self.value(m.group(0))
+            return "?" if value is None else str(value)
+        expr = re.sub(r"\b[A-Za-z_]\w*\b", name, expr)
+        return _arith(expr)
+
+    def _eval_block(self, block: str, owner: str) -> Optional[int]:
+        """`{ let a = 8; let b = 2 * 32; a + b }`"""
+        statements = [s.strip() for s in block.split(";")]
+        local = {}
+        for stmt in statements[:-1]:
+            m = re.match(r"let\s+(\w+)\s*(?::\s*\w+\s*)?=\s*(.+)$", stmt, re.S)
+            if not m:
+                return None
+            value = self.eval(_substitute(m.group(2), local), owner)
+            if value is None:
+                return None
+            local[m.group(1)] = value
+        return self.eval(_substitute(statements[-1], local), owner)
+
+
+def _substitute(expr: str, local: dict) -> str:
+    return re.sub(r"\b[a-z_]\w*\b", lambda m: str(local.get(m.group(0), m.group(0))), expr)
+
+
+def struct_defs(content: str, code: Optional[str] = None) -> dict:
+    """{name: StructDef} for the named-field structs of a unit."""
+    code = _blank_comments(content) if code is None else code
+    structs = {}
+    for m in STRUCT_RE.finditer(code):
+        end = VulnerabilityPattern._find_block_end(code, m.end() - 1)
+        if end == -1:
+            continue
+        start_line = VulnerabilityPattern._get_line_number(content, m.start())
+        lines = content[:m.start()].split("\n")
+        attrs = []
+        for line in reversed(lines[:-1]):
+            stripped = line.strip()
+            if not stripped.startswith(("#[", "//")) and not (attrs and stripped.endswith(")]")):
+                break
+            attrs.insert(0, stripped)
+        struct = StructDef(m.group(1), start_line, "\n".join(attrs))
+        for offset, entry, field_attrs in _fields(code[m.end():end - 1]):
+            fm = FIELD_RE.match(entry.strip())
+            if fm:
+                indent = len(entry) - len(entry.lstrip())
+                line = VulnerabilityPattern._get_line_number(content, m.end() + offset + indent)
+                struct.fields.append(Field(fm.group(1), re.sub(r"\s+", " ", fm.group(2)).strip(), line, field_attrs))
+        structs.setdefault(struct.name, struct)
+    return structs
+
+
+def enum_defs(code: str) -> dict:
+    """{name: [[payload types] per variant]}; explicit discriminants are fine, borsh still writes one byte."""
+    enums = {}
+    for m in ENUM_RE.finditer(code):
+        end = VulnerabilityPattern._find_block_end(code, m.end() - 1)
+        if end == -1:
+            continue
+        variants = []
+        for _, entry, _ in _fields(code[m.end():end - 1]):
+            vm = re.match(r"\s*(\w+)\s*(.*)$", entry, re.S)
+            if not vm:
+                continue
+            payload = vm.group(2).strip()
+            if payload.startswith("("):
+                variants.append(_split(payload[1:-1]))
+            elif payload.startswith("{"):
+                variants.append([
+                    re.sub(r"\s+", " ", fm.group(2)).strip()
+                    for _, e, _ in _fields(payload[1:-1]) for fm in [FIELD_RE.match(e.strip())] if fm
+                ])
+            else:
+                variants.append([])
+        enums.setdefault(m.group(1), variants)
+    return enums
+
+
+def const_defs(content: str, code: Optional[str] = None) -> dict:
+    """{key: Const} for integer constants; constants in `impl Type` are keyed `Type::NAME`."""
+    code = _blank_comments(content) if code is None else code
+    impls = VulnerabilityPattern._find_impl_blocks(code)
+    consts = {}
+    for m in CONST_RE.finditer(code):
+        if code[m.end():m.end() + 1] == "{":
+            end = VulnerabilityPattern._find_block_end(code, m.end())
+            expr = code[m.end():end] if end != -1 else ""
+        else:
+            semi = code.find(";", m.end())
+            expr = code[m.end():semi] if semi != -1 else ""
+        owner = next((name for s, e, name in impls if s <= m.start() < e), "")
+        key = f"{owner}::{m.group(1)}" if owner else m.group(1)
+        consts.setdefault(key, Const(key, expr, VulnerabilityPattern._get_line_number(content, m.start())))
+    return consts
+
+
+def _fields(body: str) -> list:
+    """[(offset, entry, attributes)] for top-level comma-separated entries, attributes blanked."""
+    attrs = {}
+
+    def blank(m):
+        attrs[m.start()] = m.group(0)
+        return " " * len(m.group(0))
+    body = re.sub(r"#\[(?:[^\[\]]|\[[^\]]*\])*\]", blank, body)
+    parts, depth, start = [], 0, 0
+    for i, ch in enumerate(body + ","):
+        if ch in "([{<":
+            depth += 1
+        elif ch in ")]}>":
+            depth -= 1
+        elif ch == "," and depth == 0:
+            if body[start:i].strip():
+                owned = " ".join(a for pos, a in attrs.items() if start <= pos < i)
+                parts.append((start, body[start:i], owned))
+            start = i + 1
+    return parts
+
+
+def _split(text: str) -> list:
+    parts, depth, current = [], 0, ""
+    for ch in text:
+        if ch in "([{<":
+            depth += 1
+        elif ch in ")]}>":
+            depth -= 1
+        if ch == "," and depth == 0:
+            parts.append(current.strip())
+            current = ""
+        else:
+            current += ch
+    if current.strip():
+        parts.append(current.strip())
+    return parts
+
+
+def _blank_comments(text: str) -> str:
+    return re.sub(r"//[^\n]*|/\*.*?\*/", lambda m: " " * len(m.group(0)), text, flags=re.S)
+
+
+def _arith(expr: str) -> Optional[int]:
+    if not re.fullmatch(r"[\d\s+\-*/%()]+", expr) or not re.search(r"\d", expr):
+        return None
+    try:
+        return int(eval(" ".join(expr.split()).replace("/", "//"), {"__builtins__": {}}))  # noqa: S307 - digits and operators only
+    except (SyntaxError, ZeroDivisionError, TypeError):
+        return None
@@ -0,0 +1,217 @@
+"""
+Lending shapes of a program, shared by the lending rules.
+
+Lending programs keep a position per user — collateral deposited and debt
+borrowed side by side — and move it with the same instructions: deposit,
+borrow, withdraw, repay, liquidate. ANCHOR-060 and ANCHOR-061
+(`shield::lending::*`) check the solvency checks of borrow and withdraw,
+ANCHOR-062 the health formula they compare, ANCHOR-063 liquidation; this
+module answers which parts of a program play which role:
+
+  - positions(): #[account] structs holding both a collateral field
+    (`deposited`, `collateral`, `supplied`...) and a debt field
+    (`borrowed`, `debt`, `loan`...). Pool-wide totals (`total_borrows`)
+    are not a position
+  - lending_instructions(): the instructions that borrow (add to a
+    position's debt) or withdraw (take from its collateral without paying
+    debt down, as repay and liquidate do), each with the text of the local
+    functions it reaches and of its accounts struct's constraints
+  - liquidations(): the instructions named liquidate / liquidation_*,
+    with the same text
+  - conditions(): the guard conditions of that text — `require!(..)`,
+    `assert!(..)` and `if .. { return Err(..) }` — with the let-bound
+    values they compare spelled out
+"""
+
+import re
+from dataclasses import dataclass, field
+
+from scanner.patterns.base import VulnerabilityPattern
+from scanner.patterns.facts import Facts
+from scanner.patterns.guards import guard_alternation
+from scanner.patterns.handler_panic import HandlerPanicPattern
+from scanner.patterns.layout import Layout
+from scanner.patterns.math_summary import _blank
+from scanner.patterns.validation import CALL_RE
+
+COLLATERAL_RE = re.compile(r"^(?:deposit(?:ed|s)?|deposited_\w+|\w+_deposited|collateral\w*|\w+_collateral|supplied\w*)$")
+DEBT_RE = re.compile(r"^(?:borrow(?:ed|s)?|borrowed_\w+|\w+_borrowed|debt\w*|\w+_debt|loans?|loan_\w+|liabilit\w*)$")
+# Pool-wide aggregates and non-amount fields are not a position's balance
+AGGREGATE_RE = re.compile(r"^(?:total|cumulative|global|max|min)_|_(?:rate|index|bps|ts|time|at|bump|mint|vault)$")
+NON_AMOUNT_TYPE_RE = re.compile(r"\b(?:Pubkey|Vec|bool|String|Option)\b|\[")
+# Calls that stand for a solvency check as a whole
+HEALTH_RE = re.compile(
+    r"health|ltv|loan_to_value|collateral(?:ization)?_ratio|solven|borrow_limit|borrowing_power|"
+    r"max_borrow|undercollateral|margin",
+    re.I,
+)
+LIQUIDATE_RE = re.compile(r"liquidat", re.I)
+ERROR_EXIT_RE = re.compile(r"\breturn\s+Err\b|\berr!\s*\(|\berror!\s*\(|\bErr\s*\(")
+
+
+@dataclass
+class Position:
+    struct: str
+    collateral: list
+    debt: list
+
+
+@dataclass
+class LendingInstruction:
+    name: str
+    kind: str           # "borrow" | "withdraw" | "liquidate"
+    position: Position
+    field: str          # the debt field borrowed into / the collateral field withdrawn from, "" for liquidate
+    line: int           # line of that update, of the handler for liquidate
+    text: str           # handler, reached local functions and struct constraints, comments blanked
+    body_start: int     # offset of the handler body, which text starts with
+    body_len: int
+    lets: dict = field(default_factory=dict)
+    accounts: list = field(default_factory=list)    # facts.Account of the accounts struct
+
+    def line_of(self, content: str, at: int) -> int:
+        """Line of an offset of text inside the handler body, else the line of the update."""
+        if at < self.body_len:
+            return VulnerabilityPattern._get_line_number(content, self.body_start + at)
+        return self.line
+
+
+def positions(content: str) -> list:
+    """#[account] structs with a collateral field and a debt field."""
+    result = []
+    for struct in Layout(content).structs.values():
+        if not struct.account:
+            continue
+        amounts = [f.name for f in struct.fields
+                   if not AGGREGATE_RE.search(f.name) and not NON_AMOUNT_TYPE_RE.search(f.type)]
+        collateral = [n for n in amounts if COLLATERAL_RE.match(n)]
+        debt = [n for n in amounts if DEBT_RE.match(n)]
+        if collateral and debt:
+            result.append(Position(struct.name, collateral, debt))
+    return result
+
+
+def updates(text: str, name: str, op: str) -> list:
+    """Offsets of `x.name += ..` (op "+") / `-=`, of `x.name = x.name + ..` / `.checked_add(..)`, and of
+    `x.name = new` where `let new = x.name + ..`."""
+    word = "add" if op == "+" else "sub"
+    change = rf"\b{name}\b\s*(?:\{op}(?!=)|\.\s*(?:checked|saturating|wrapping)_{word}\b)"
+    pattern = rf"\.\s*{name}\s*\{op}=|\.\s*{name}\s*=(?!=)[^;]*?{change}"
+    found = [m.start() for m in re.finditer(pattern, text)]
+    lets = _lets(text)
+    for m in re.finditer(rf"\.\s*{name}\s*=(?!=)\s*(\w+)\s*;", text):
+        if m.group(1) in lets and re.search(change, lets[m.group(1)]):
+            found.append(m.start())
+    return sorted(found)
+
+
+def lending_instructions(content: str, max_depth: int) -> list:
+    """Borrow and withdraw instructions of the positions of the unit."""
+    found = positions(content)
+    result = []
+    for ix, fn, text, accounts in _handlers(content, max_depth) if found else []:
+        for position in found:
+            repays = any(updates(text, d, "-") for d in position.debt)
+            borrowed = [(d, at) for d in position.debt for at in updates(fn["body"], d, "+")]
+            withdrawn = [(c, at) for c in position.collateral for at in updates(fn["body"], c, "-")]
+            if borrowed:
+                kind, (name, at) = "borrow", borrowed[0]
+            elif withdrawn and not repays:
+                kind, (name, at) = "withdraw", withdrawn[0]
+            else:
+                continue
+            line = VulnerabilityPattern._get_line_number(content, fn["body_start"] + at)
+            result.append(LendingInstruction(ix.name, kind, position, name, line, text,
+                                             fn["body_start"], len(fn["body"]), _lets(text), accounts))
+            break
+    return result
+
+
+def liquidations(content: str, max_depth: int) -> list:
+    """Liquidation instructions of a unit with positions, by name."""
+    found = positions(content)
+    result = []
+    for ix, fn, text, accounts in _handlers(content, max_depth) if found else []:
+        if not LIQUIDATE_RE.search(ix.name):
+            continue
+        # The position the handler touches, else the first of the unit
+        position = next((p for p in found if mentions(text, p.collateral + p.debt)), found[0])
+        result.append(LendingInstruction(ix.name, "liquidate", position, "", ix.line, text,
+                                         fn["body_start"], len(fn["body"]), _lets(text), accounts))
+    return result
+
+
+def _handlers(content: str, max_depth: int):
+    """(instruction, function, text, accounts) per instruction handler of the unit."""
+    code = _blank(content)
+    facts = Facts(content)
+    by_name = {}
+    for fn in VulnerabilityPattern._find_functions(code):
+        by_name.setdefault(fn["name"], []).append(fn)
+    structs = {s.name: s for s in facts.accounts_structs()}
+    for ix in facts.instructions():
+        fn = next((f for f in by_name.get(ix.name, []) if f["body_start"] == ix.body_start), None)
+        if fn is None:
+            continue
+        text = _reached(fn, by_name, max_depth) + "\n" + HandlerPanicPattern._guards_of(code, fn)
+        struct = structs.get(ix.accounts_struct)
+        if struct:
+            text += "\n" + "\n".join(f"require!({c.value.split('@')[0].strip()})"
+                                     for a in struct.accounts for c in a.constraints if c.kind == "constraint")
+        yield ix, fn, text, struct.accounts if struct else []
+
+
+def borrowed_anywhere(content: str, position: Position) -> bool:
+    """Some code of the unit adds to the position's debt."""
+    code = _blank(content)
+    return any(updates(code, d, "+") for d in position.debt)
+
+
+def conditions(ix: LendingInstruction, guards=()) -> list:
+    """[(offset in ix.text, condition)] of the guard macros and error-returning ifs, lets expanded."""
+    result = []
+    macro_re = re.compile(rf"\b(?:{guard_alternation(guards)})!\s*\(")
+    for m in macro_re.finditer(ix.text):
+        args = HandlerPanicPattern._call_args(ix.text, m.end() - 1)
+        # The error argument names the failure, not what is compared
+        result.append((m.start(), ", ".join(a for a in args if not re.match(r"\s*[A-Z]\w*::\w+\s*$", a))))
+    for m in re.finditer(r"\bif\s+([^{};]+?)\s*\{", ix.text):
+        end = VulnerabilityPattern._find_block_end(ix.text, m.end() - 1)
+        if end != -1 and ERROR_EXIT_RE.search(ix.text[m.end():end]):
+            result.append((m.start(), m.group(1)))
+    return [(at, expand(cond, ix.lets)) for at, cond in sorted(result)]
+
+
+def expand(expr: str, lets: dict, rounds: int = 3) -> str:
+    """The expression followed by the let-bound values it mentions, a few bindings deep."""
+    text, seen = expr, set()
+    for _ in range(rounds):
+        names = {n for n in re.findall(r"\b[a-z_]\w*\b", text) if n in lets and n not in seen}
+        if not names:
+            break
+        seen |= names
+        text += "\n" + "\n".join(lets[n] for n in sorted(names))
+    return text
+
+
+def mentions(text: str, names) -> bool:
+    return any(re.search(rf"\b{re.escape(n)}\b", text) for n in names)
+
+
+def _reached(fn: dict, by_name: dict, max_depth: int, path: tuple = ()) -> str:
+    """The function's body followed by the bodies of the local functions it calls."""
+    path = path + (fn["name"],)
+    parts = [fn["body"]]
+    if len(path) < max_depth:
+        for name in dict.fromkeys(m.group(1) for m in CALL_RE.finditer(fn["body"])):
+            for callee in by_name.get(name, []):
+                if callee["name"] not in path:
+                    parts.append(_reached(callee, by_name, max_depth, path))
+    return "\n".join(parts)
+
+
+def _lets(text: str) -> dict:
+    lets = {}
+    for m in re.finditer(r"\blet\s+(?:mut\s+)?(\w+)\s*(?::[^=;]+)?=(?!=)([^;]+);", text):
+        lets.setdefault(m.group(1), m.group(2).strip())
+    return lets
@@ -0,0 +1,150 @@
+"""
+ANCHOR-060: Borrow Check Ignores Debt Or LTV
+
+A borrow is safe when the position's debt after it stays below a fraction
+of its collateral:
+
+    require!(
+        user.deposited * LTV_BPS / 10_000 >= user.borrowed + amount,
+        LendingError::InsufficientCollateral
+    );
+
+Two halves of that check go missing in practice. Without the existing
+debt (`require!(user.deposited >= amount)`) every borrow is measured
+against the full collateral again: deposit 100, borrow 100 ten times.
+Without a loan-to-value factor the position is allowed to owe exactly
+what it holds, with no margin for interest, price moves or the
+liquidator's bonus.
+
+For the borrow instructions of a lending program (see lending.py) the rule
+collects the guard conditions of the handler, the local functions it
+reaches and its accounts struct, and keeps those comparing the position's
+collateral (directly, through a let, or via a health / LTV helper). It
+reports:
+
+  - Critical: no such condition, or none including the position's debt
+  - High: the debt is included but no LTV factor is applied — no
+    `* 75 / 100`, no `LTV` / `collateral_factor` / `threshold` / bps
+    constant, no health helper
+
+ANCHOR-061 checks the other side of the position: withdrawals.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.lending import HEALTH_RE, conditions, lending_instructions, mentions
+
+# A fraction applied to the collateral: `* 75 / 100`, `* LTV_BPS / 10_000`, `ltv`, `collateral_factor`...
+FACTOR_RE = re.compile(
+    r"\*\s*\(?\s*\d[\d_]*\s*\)?\s*/\s*\d|/\s*(?:100|1_?000|10_?000)\b|"
+    r"\b\w*(?:ltv|LTV|factor|FACTOR|ratio|RATIO|threshold|THRESHOLD|haircut|HAIRCUT|bps|BPS|basis_points)\w*\b|"
+    r"\b\d*\.\d+\b"
+)
+
+
+class BorrowCollateralCheckPattern(VulnerabilityPattern):
+    id = "ANCHOR-060"
+    name = "Borrow Check Ignores Debt Or LTV"
+    severity = "High"
+    category = "lending"
+    remediation_effort = "localized"
+    description = (
+        "A borrow is checked against the position's collateral without its existing "
+        "debt or without a loan-to-value factor."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        for ix in lending_instructions(content, self.max_depth):
+            if ix.kind != "borrow":
+                continue
+            position = ix.position
+            relevant = [(at, cond) for at, cond in conditions(ix, self.guard_macros)
+                        if mentions(cond, position.collateral) or HEALTH_RE.search(cond)]
+            collateral = " / ".join(f"{position.struct}.{c}" for c in position.collateral)
+            debt = f"{position.struct}.{ix.field}"
+            if not relevant:
+                findings.append(self._finding(
+                    file_path, content, ix.line, "Critical",
+                    f"In {ix.name}: the borrow adds to {debt} without any condition comparing it with "
+                    f"{collateral}, so any position borrows up to the whole vault",
+                ))
+                continue
+            line = ix.line_of(content, relevant[0][0])
+            text = "\n".join(cond for _, cond in relevant)
+            with_debt = mentions(text, position.debt)
+            with_factor = bool(FACTOR_RE.search(text) or any(HEALTH_RE.search(c) for _, c in relevant))
+            if not with_debt:
+                missing = "neither the existing debt nor an LTV factor" if not with_factor else "not the existing debt"
+                findings.append(self._finding(
+                    file_path, content, line, "Critical",
+                    f"In {ix.name}: the collateral check compares {collateral} with the new amount and includes "
+                    f"{missing}; {debt} is never part of it, so every further borrow passes against the same "
+                    f"collateral (deposit 100, borrow 100 as often as the vault allows)",
+                ))
+            elif not with_factor:
+                findings.append(self._finding(
+                    file_path, content, line, "High",
+                    f"In {ix.name}: the collateral check includes {debt} but applies no LTV factor, so a "
+                    f"position may owe all of {collateral} with no margin for interest, price moves or the "
+                    f"liquidation bonus",
+                ))
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    def _finding(self, file_path, content, line, severity, message) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=severity,
+            file=file_path,
+            line=line,
+            description=f"{message}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "deposited = 100, borrowed = 0",
+                "after": "deposited = 100, borrowed = 500 after five borrows of 100",
+                "damage": "The position owes several times its collateral; the vault is drained by one user.",
+            },
+            impact={
+                "attack_cost": "One deposit, then repeated borrows",
+                "exploitability": "High — any user with a position, no special accounts or timing",
+                "breach_cost_context": "Lending vaults hold every depositor's funds; an unbounded borrow empties them.",
+            },
+            anchor_versions_affected="All versions (developer-side pattern)",
+            ecosystem_recommendations=[
+                "Check debt after the borrow, not the borrowed amount: collateral * LTV >= debt + amount",
+                "Keep the LTV in one named constant (or per-reserve config) shared by borrow, withdraw and liquidate",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Check the debt after the borrow against a fraction of the collateral:\n"
+            "  require!(\n"
+            "      user.deposited\n"
+            "          .checked_mul(LTV_BPS).ok_or(LendingError::MathOverflow)?\n"
+            "          / 10_000\n"
+            "          >= user.borrowed.checked_add(amount).ok_or(LendingError::MathOverflow)?,\n"
+            "      LendingError::InsufficientCollateral\n"
+            "  );"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Solvency is a property of the whole position: total debt against "
+            "discounted collateral. A check on the borrowed amount alone, or on "
+            "undiscounted collateral, holds for each borrow while the position "
+            "as a whole becomes insolvent."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. Attacker deposits 100 SOL as collateral\n"
+            "2. borrow(100) passes: deposited >= amount\n"
+            "3. borrow(100) passes again, and again — the check never sees the existing debt\n"
+            "4. The vault is drained; the position owes many times its collateral"
+        )
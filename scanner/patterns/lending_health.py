@@ -0,0 +1,429 @@
+"""
+ANCHOR-062: Health Factor Formula Inconsistent
+
+A lending program's health factor is one line of arithmetic, and each
+part of it can be wrong on its own:
+
+    let interest = user.borrowed * pool.interest_rate as u64 * pool.total_borrows;
+    let health = user.deposited * 100 / (user.borrowed + interest);
+    require!(health < 75, LendingError::HealthyPosition);
+
+`health` is collateral over debt in percent, so 100 means the position
+holds exactly what it owes; liquidating below 75 waits until it is a
+quarter underwater — 75 is an LTV, a debt-over-collateral number, applied
+to the inverse ratio. The interest term multiplies two amounts (amount²)
+and adds them to an amount, and `interest_rate` is in basis points but
+never divided by 10_000.
+
+In lending programs (see lending.py) the rule rebuilds every let-bound
+health / LTV / collateral-ratio value, and the value returned by a
+function of that name, with the locals it is computed from substituted and
+casts stripped. It decides which way the ratio points — collateral over
+debt (higher is safer) or debt over collateral — and its scale (100 for a
+percent, 10_000 for bps), then reports:
+
+  - High: a comparison pointing the wrong way — a borrow or withdraw
+    requiring the position to be unhealthy, a liquidation requiring it to
+    be healthy — or a threshold on the wrong side of parity (a
+    collateral/debt percent compared with 75, an LTV percent with 120)
+  - High: collateral and debt in different units — terms of different
+    dimension added together, a price on one side only, a collateral and
+    a debt mint with no price at all, or a bps / percent rate never
+    divided by its scale
+  - Medium: a debt side without the interest the program tracks
+    (`interest_rate`, `borrow_index`, `accrued_interest`...)
+
+Each finding carries the reconstructed formula, in the description and
+as `details` (type `health-formula`), so an auditor can check it against
+the intended one.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.details import HealthFormula
+from scanner.patterns.guards import guard_alternation
+from scanner.patterns.handler_panic import HandlerPanicPattern
+from scanner.patterns.layout import Layout
+from scanner.patterns.lending import COLLATERAL_RE, DEBT_RE, positions
+from scanner.patterns.math_summary import _blank
+from scanner.patterns.zero_division import (
+    EXIT_RE, LET_RE, NEGATION, RELATION_RE, ZeroDivisionPattern, _parse, _show,
+)
+
+HEALTH_NAME_RE = re.compile(r"health|ltv|loan_to_value|collateral(?:ization)?_ratio|debt_ratio|margin_ratio", re.I)
+INTEREST_RE = re.compile(r"interest|borrow_rate|borrow_index|cumulative_borrow|accrued", re.I)
+SCALAR_RE = re.compile(r"rate|bps|basis|ratio|factor|threshold|ltv|percent|index|decimals|bump|time|slot|precision|scale|wad", re.I)
+AMOUNT_RE = re.compile(r"amount|deposit|borrow|debt|collateral|interest|balance|supply|reserve|liquidity|lamports|shares|_value$", re.I)
+PRICE_RE = re.compile(r"price|oracle|_usd$|rate_per_token", re.I)
+SCALE_RE = re.compile(r"precision|scale|wad|bps|basis|percent", re.I)
+THRESHOLD_RE = re.compile(r"ltv|threshold|factor|haircut", re.I)
+RATE_COMMENT_RE = re.compile(r"\.\s*(\w+)\s*=\s*[\d_]+\s*;\s*//([^\n]*)")
+COLLATERAL_MINT_RE = re.compile(r"\b(?:collateral|deposit|supply)_mint\b")
+DEBT_MINT_RE = re.compile(r"\b(?:borrow|debt|loan|liability)_mint\b")
+CHECKED_OP_RE = re.compile(r"\.\s*checked_(mul|div|add|sub)\s*\(")
+OPS = {"mul": "*", "div": "/", "add": "+", "sub": "-"}
+
+
+class HealthFormulaPattern(VulnerabilityPattern):
+    id = "ANCHOR-062"
+    name = "Health Factor Formula Inconsistent"
+    severity = "High"
+    category = "lending"
+    remediation_effort = "localized"
+    cost = "expensive"
+    description = (
+        "A health / LTV formula compares the wrong way, mixes units of collateral "
+        "and debt, or leaves out the interest the program accrues."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        found = positions(content)
+        if not found:
+            return []
+        code = _blank(content)
+        layout = Layout(content)
+        context = {
+            "collateral": {c for p in found for c in p.collateral},
+            "debt": {d for p in found for d in p.debt},
+            "interest": [f"{s.name}.{f.name}" for s in layout.structs.values() if s.account
+                         for f in s.fields if INTEREST_RE.search(f.name)],
+            "rates": self._rate_scales(content, layout),
+            "two_assets": bool(COLLATERAL_MINT_RE.search(code) and DEBT_MINT_RE.search(code)),
+            "layout": layout,
+        }
+        findings = []
+        for fn in self._find_functions(code):
+            env = {}
+            for m in re.finditer(r"[^;{}]+", fn["body"]):
+                let = LET_RE.match(m.group(0))
+                if not let:
+                    continue
+                tree = self._tree(let.group(2), env)
+                if tree is None:
+                    continue
+                env[let.group(1)] = tree
+                if HEALTH_NAME_RE.search(let.group(1)):
+                    after = fn["body"][m.end():]
+                    line = self._get_line_number(content, fn["body_start"] + m.start() + len(m.group(0)) -
+                                                 len(m.group(0).lstrip()))
+                    finding = self._check(file_path, content, fn["name"], let.group(1), rf"\b{let.group(1)}\b",
+                                          tree, after, line, context)
+                    if finding:
+                        findings.append(finding)
+            # `fn health_factor(..) -> u64 { ... expr }`, compared wherever it is called
+            if HEALTH_NAME_RE.search(fn["name"]):
+                tail = re.sub(r"^\s*Ok\s*\((.*)\)\s*$", r"\1", fn["body"].rsplit(";", 1)[-1].strip(), flags=re.S)
+                tree = self._tree(tail, env) if tail else None
+                if tree is not None:
+                    line = self._get_line_number(content, fn["body_start"] + fn["body"].rfind(tail))
+                    finding = self._check(file_path, content, fn["name"], f"{fn['name']}(..)",
+                                          rf"\b{fn['name']}\s*\([^;{{}}]*?\)\s*\??", tree, code, line, context)
+                    if finding:
+                        findings.append(finding)
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    # ─── Reconstruction ─────────────────────────────────────────────
+
+    @staticmethod
+    def _tree(expr: str, env: dict):
+        """Expression tree of the value, checked arithmetic as operators and known locals substituted."""
+        expr = re.sub(r"\s*\.\s*ok_or(?:_else)?\s*\((?:[^()]|\([^()]*\))*\)\s*\??|\s*\.\s*expect\s*\([^)]*\)|\?", "",
+                      expr)
+        expr = ZeroDivisionPattern._normalize(expr)
+        for _ in range(8):
+            m = CHECKED_OP_RE.search(expr)
+            if not m:
+                break
+            receiver = HandlerPanicPattern._receiver(expr, m.start())
+            close = HandlerPanicPattern._call_args(expr, m.end() - 1)
+            if not receiver or len(close) != 1:
+                return None
+            end = expr.index("(", m.end() - 1)
+            depth = 0
+            for i in range(end, len(expr)):
+                depth += {"(": 1, ")": -1}.get(expr[i], 0)
+                if depth == 0:
+                    break
+            expr = f"{expr[:m.start() - len(receiver)]}({receiver} {OPS[m.group(1)]} {close[0].strip()}){expr[i + 1:]}"
+        tree = _parse(expr)
+        return HealthFormulaPattern._substitute(tree, env) if tree is not None else None
+
+    @staticmethod
+    def _substitute(node, env: dict):
+        if node[0] == "name":
+            return env.get(node[1], node)
+        if node[0] == "bin":
+            return ("bin", node[1], HealthFormulaPattern._substitute(node[2], env),
+                    HealthFormulaPattern._substitute(node[3], env))
+        return node
+
+    @staticmethod
+    def _fraction(node):
+        """(numerator factors, denominator factors) of the top-level product."""
+        if node[0] == "bin" and node[1] == "*":
+            (n1, d1), (n2, d2) = HealthFormulaPattern._fraction(node[2]), HealthFormulaPattern._fraction(node[3])
+            return n1 + n2, d1 + d2
+        if node[0] == "bin" and node[1] == "/":
+            (n1, d1), (n2, d2) = HealthFormulaPattern._fraction(node[2]), HealthFormulaPattern._fraction(node[3])
+            return n1 + d2, d1 + n2
+        return [node], []
+
+    @staticmethod
+    def _leaves(node) -> list:
+        if node[0] == "bin":
+            return HealthFormulaPattern._leaves(node[2]) + HealthFormulaPattern._leaves(node[3])
+        return [node]
+
+    @staticmethod
+    def _field(leaf) -> str:
+        return re.split(r"[.:]", re.sub(r"\(.*$", "", leaf[1]))[-1] if leaf[0] == "name" else ""
+
+    def _side(self, factors: list, context: dict) -> set:
+        kinds = set()
+        for leaf in (l for f in factors for l in self._leaves(f)):
+            name = self._field(leaf)
+            if name in context["collateral"] or (COLLATERAL_RE.match(name) and name not in context["debt"]):
+                kinds.add("collateral")
+            elif name in context["debt"] or DEBT_RE.match(name):
+                kinds.add("debt")
+        return kinds
+
+    def _scale(self, factors: list, layout):
+        """Product of the numeric and SCALE-like constant factors, or None."""
+        scale, seen = 1, False
+        for factor in factors:
+            if factor[0] == "num":
+                scale, seen = scale * factor[1], True
+            elif factor[0] == "name" and SCALE_RE.search(factor[1]) and re.match(r"^[\w:]+$", factor[1]):
+                value = layout.eval(factor[1].split("::")[-1])
+                if value is None:
+                    return None
+                scale, seen = scale * value, True
+        return scale if seen else 1
+
+    # ─── Checks ─────────────────────────────────────────────────────
+
+    def _check(self, file_path, content, fn_name, variable, subject, tree, scope, line, context):
+        numerator, denominator = self._fraction(tree)
+        top, bottom = self._side(numerator, context), self._side(denominator, context)
+        if top == {"collateral"} and "debt" in bottom:
+            orientation, debt_side, collateral_side = "collateral/debt", denominator, numerator
+        elif top == {"debt"} and "collateral" in bottom:
+            orientation, debt_side, collateral_side = "debt/collateral", numerator, denominator
+        else:
+            return None
+        ratio_factors = [f for f in numerator + denominator if f[0] == "name" and THRESHOLD_RE.search(f[1])]
+        scale = None if ratio_factors else self._scale(numerator, context["layout"])
+        formula = _show(tree)
+        issues, severity = [], "Medium"
+
+        comparisons = []
+        liquidation = "liquidat" in fn_name.lower()
+        for cond, asserted_op, threshold in self._comparisons(scope, subject):
+            comparisons.append(cond)
+            problem = self._direction(orientation, asserted_op, threshold, scale, liquidation, cond, context)
+            if problem:
+                issues.append(problem)
+                severity = "High"
+
+        for problem in self._units(tree, collateral_side, debt_side, formula, context):
+            issues.append(problem)
+            severity = "High"
+
+        debt_leaves = [l for f in debt_side for l in self._leaves(f)]
+        if context["interest"] and not any(INTEREST_RE.search(l[1]) for l in debt_leaves if l[0] == "name"):
+            shown = " * ".join(_show(f) for f in debt_side if f[0] != "num")
+            issues.append(f"the debt side ({shown}) has no interest term although the program tracks "
+                          f"{', '.join(context['interest'])}, so the ratio ignores the interest a position owes")
+        if not issues:
+            return None
+        scale_text = str(scale) if scale not in (None, 1) else ""
+        parity = f", {scale_text} at collateral == debt" if scale_text else ""
+        return self._finding(
+            file_path, content, line, severity,
+            f"In {fn_name}: {variable} = {formula} ({orientation}{parity}); " + "; ".join(issues),
+            HealthFormula(fn_name, variable, line, formula, orientation, scale_text, comparisons, issues),
+        )
+
+    def _comparisons(self, scope: str, subject: str) -> list:
+        """(condition, operator the check asserts with the subject on the left, threshold) per comparison."""
+        result = []
+        macro_re = re.compile(rf"\b(?:{guard_alternation(self.guard_macros)})!\s*\(")
+        conditions = []
+        for m in macro_re.finditer(scope):
+            args = HandlerPanicPattern._call_args(scope, m.end() - 1)
+            if args:
+                conditions.append((args[0], False))
+        for m in re.finditer(r"\bif\s+([^{};]+?)\s*\{", scope):
+            end = self._find_block_end(scope, m.end() - 1)
+            if end != -1 and EXIT_RE.search(scope[m.end():end]):
+                conditions.append((m.group(1), True))
+        for cond, exits in conditions:
+            for part in re.split(r"&&|\|\|", cond):
+                rel = RELATION_RE.match(part.strip())
+                if not rel:
+                    continue
+                left, op, right = rel.group(1).strip(), rel.group(2), rel.group(3).strip()
+                if re.fullmatch(subject, left):
+                    threshold = right
+                elif re.fullmatch(subject, right):
+                    op, threshold = {"<": ">", "<=": ">=", ">": "<", ">=": "<="}.get(op, op), left
+                else:
+                    continue
+                if exits:
+                    op = NEGATION[op]
+                result.append((" ".join(part.split()), op, threshold))
+        return result
+
+    def _direction(self, orientation, op, threshold, scale, liquidation, cond, context):
+        if op not in ("<", "<=", ">", ">="):
+            return ""
+        above = op in (">", ">=")
+        safe_above = orientation == "collateral/debt"
+        healthy = above == safe_above
+        if healthy == liquidation:
+            side = "healthy" if healthy else "unhealthy"
+            return (f"`{cond}` lets {'liquidation' if liquidation else 'the instruction'} proceed only for "
+                    f"{side} positions — the comparison points the wrong way for a {orientation} ratio")
+        value = context["layout"].eval(re.sub(r"\s+", "", threshold).split("::")[-1]) \
+            if not re.fullmatch(r"\d[\d_]*", threshold.strip()) else int(threshold.replace("_", ""))
+        if value is None or not scale or scale == 1:
+            return ""
+        if orientation == "collateral/debt" and value < scale:
+            return (f"`{cond}` puts the threshold below {scale}, where collateral equals debt: the position "
+                    f"is already insolvent when the check trips, and {value} reads like an LTV "
+                    f"(debt/collateral) applied to the inverse ratio")
+        if orientation == "debt/collateral" and value > scale:
+            return (f"`{cond}` puts the threshold above {scale}, where debt equals collateral: the position "
+                    f"may owe more than it holds before the check trips")
+        return ""
+
+    def _units(self, tree, collateral_side, debt_side, formula, context) -> list:
+        problems = []
+        for left, ldim, right, rdim in self._mismatches(tree):
+            problems.append(f"`{left}` ({self._dim_name(ldim)}) is added to `{right}` ({self._dim_name(rdim)})")
+        priced = [any(PRICE_RE.search(l[1]) or l[1].endswith("_value")
+                      for f in side for l in self._leaves(f) if l[0] == "name")
+                  for side in (collateral_side, debt_side)]
+        if priced[0] != priced[1]:
+            problems.append(f"only the {'collateral' if priced[0] else 'debt'} side is converted with a price, so "
+                            f"the ratio divides a value by a token amount")
+        elif context["two_assets"] and not any(priced):
+            problems.append("collateral and debt are different mints but neither side is converted with a price, "
+                            "so the ratio compares raw amounts of two tokens")
+        for field, scale in context["rates"].items():
+            if re.search(rf"\.{field}\b|^{field}\b", formula) and not re.search(rf"/\s*\(?\s*{scale}\b", formula):
+                problems.append(f"{field} is in {'basis points' if scale == 10000 else 'percent'} but the formula "
+                                f"never divides by {scale}, so its term is {scale}× too large")
+        return problems
+
+    def _mismatches(self, node) -> list:
+        if node[0] != "bin":
+            return []
+        found = self._mismatches(node[2]) + self._mismatches(node[3])
+        if node[1] in "+-":
+            ldim, rdim = self._dim(node[2]), self._dim(node[3])
+            # A plain number next to an amount is a fee or an offset, not a second unit
+            if ldim != rdim and (0, 0) not in (ldim, rdim):
+                found.append((_show(node[2]), ldim, _show(node[3]), rdim))
+        return found
+
+    def _dim(self, node) -> tuple:
+        """(amount exponent, price exponent) of a subtree."""
+        if node[0] == "num":
+            return (0, 0)
+        if node[0] == "name":
+            name = self._field(node)
+            if PRICE_RE.search(name):
+                return (0, 1)
+            if name.endswith("_value"):
+                return (1, 1)
+            if AMOUNT_RE.search(name) and not SCALAR_RE.search(name):
+                return (1, 0)
+            return (0, 0)
+        left, right = self._dim(node[2]), self._dim(node[3])
+        if node[1] == "*":
+            return (left[0] + right[0], left[1] + right[1])
+        if node[1] == "/":
+            return (left[0] - right[0], left[1] - right[1])
+        return left if left != (0, 0) else right
+
+    @staticmethod
+    def _dim_name(dim: tuple) -> str:
+        names = {(1, 0): "an amount", (2, 0): "amount²", (1, 1): "a value (amount × price)",
+                 (0, 0): "a plain number", (0, 1): "a price"}
+        return names.get(dim, f"amount^{dim[0]} × price^{dim[1]}")
+
+    @staticmethod
+    def _rate_scales(content: str, layout) -> dict:
+        """{rate field: 10000 | 100} for rates named or commented as basis points / percent."""
+        rates = {}
+        for struct in layout.structs.values():
+            for f in struct.fields:
+                if re.search(r"bps|basis_points", f.name):
+                    rates[f.name] = 10000
+                elif re.search(r"percent|_pct$", f.name):
+                    rates[f.name] = 100
+        for m in RATE_COMMENT_RE.finditer(content):
+            if re.search(r"(?i)basis points|\bbps\b", m.group(2)):
+                rates.setdefault(m.group(1), 10000)
+            elif re.search(r"(?i)percent|%", m.group(2)):
+                rates.setdefault(m.group(1), 100)
+        return rates
+
+    def _finding(self, file_path, content, line, severity, message, details) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=severity,
+            file=file_path,
+            line=line,
+            description=f"{message}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "deposited = 100, borrowed = 120: health = 83 (collateral/debt, percent)",
+                "after": "require!(health < 75) fails: the insolvent position cannot be liquidated",
+                "damage": "Bad debt accumulates until positions are far underwater; depositors absorb the loss.",
+            },
+            impact={
+                "attack_cost": "None — positions drift into the gap on their own, or a borrower steers into it",
+                "exploitability": "Medium — depends on prices and the distance between the intended and the coded threshold",
+                "breach_cost_context": "Liquidation thresholds set the protocol's solvency margin; an inverted or "
+                                       "mis-scaled one removes it.",
+            },
+            anchor_versions_affected="All versions (developer-side pattern)",
+            ecosystem_recommendations=[
+                "Write health as collateral value × liquidation threshold / debt value, compared with one constant",
+                "Convert both sides to a common unit with the oracle price and scale rates once, in one helper",
+            ],
+            details=details,
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Compute health in one unit and compare it with parity:\n"
+            "  let debt = user.borrowed + accrued_interest(user, pool)?;   // interest scaled by 10_000\n"
+            "  let health = user.deposited * LIQUIDATION_THRESHOLD_BPS / 10_000 * 100 / debt;\n"
+            "  require!(health < 100, LendingError::HealthyPosition);      // liquidate below parity"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Health factors mix three conventions — which side is the numerator, "
+            "what scale the result has, and which units each term is in — and "
+            "the code compiles whichever way they are combined. A threshold "
+            "written for one convention and compared in another silently moves "
+            "the liquidation point."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. health = collateral * 100 / debt; liquidation requires health < 75\n"
+            "2. A position's collateral falls to 80% of its debt: insolvent, but health = 80\n"
+            "3. Liquidators are refused until collateral is below 75% of the debt\n"
+            "4. The shortfall between the two is bad debt borne by depositors"
+        )
@@ -0,0 +1,240 @@
+"""
+ANCHOR-063: Liquidation Incomplete Or Unincentivized
+
+Liquidation is what keeps a lending pool solvent: a third party repays an
+unhealthy position's debt and takes its collateral, at a discount, as the
+reward for doing so. Two halves of that go missing:
+
+    pub fn liquidate(ctx: Context<Liquidate>) -> Result<()> {
+        let health = user.deposited * 100 / user.borrowed;
+        require!(health < 75, LendingError::HealthyPosition);
+        Ok(())                                  // nothing repaid, nothing seized
+    }
+
+A handler that checks health and stops is a liquidation in name only:
+unhealthy positions stay open and their bad debt stays in the pool. A
+handler that does change the position but pays the caller nothing, and
+lets anyone call it, has no keeper with a reason to run it — and when it
+also writes the collateral down without moving it anywhere, any caller
+can erase another user's collateral from the books.
+
+For the liquidation instructions of a lending program (see lending.py)
+the rule looks at the handler, the local functions it reaches and its
+accounts struct, and reports:
+
+  - High: no state change at all — no field written, no transfer, mint,
+    burn or lamport movement, no `close` — whatever the handler checks
+    first (its accounts being read-only is quoted as well)
+  - High: no transfer to the liquidator and no restriction on the signer
+    (`has_one`, `address`, a key comparison), while the position's
+    collateral is decremented and nothing is transferred at all
+  - Medium: the same without the collateral write-down — a permissionless
+    liquidation with no reward
+
+Whether the health check compares the right way is ANCHOR-062's question.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.authority_init import KEY_COMPARISON_RE
+from scanner.patterns.lending import conditions, liquidations, mentions, updates
+
+STATE_WRITE_RE = re.compile(r"\.\s*\w+\s*[+\-*/]?=(?!=)")
+MOVE_RE = re.compile(
+    r"\b(?:transfer|transfer_checked|mint_to|burn|close_account)\s*\(|\binvoke(?:_signed)?\s*\(|"
+    r"\b(?:add|sub)_lamports\s*\(|\blamports\s*\(\s*\)\s*\.\s*borrow_mut\b|\btry_borrow_mut_lamports\b"
+)
+# Where a movement goes: `to: ctx.accounts.x`, `x.add_lamports(..)`, `**x.lamports.borrow_mut() +=`
+RECIPIENT_RE = re.compile(
+    r"\bto\s*:\s*(?:ctx\s*\.\s*accounts\s*\.\s*|&\s*)?(\w+)|"
+    r"\b(\w+)\s*(?:\.\s*to_account_info\s*\(\s*\))?\s*\.\s*(?:add_lamports|try_borrow_mut_lamports)\b|"
+    r"\b(\w+)\s*(?:\.\s*to_account_info\s*\(\s*\))?\s*\.\s*lamports\s*\.\s*borrow_mut\s*\(\s*\)\s*\)?\s*\+="
+)
+# A ledger credit: `x.field += ..`, `x.field = x.field.checked_add(..)`
+CREDIT_RE = re.compile(r"(\w+)\s*\.\s*\w+\s*(?:\+=|=(?!=)[^;]*\.\s*(?:checked|saturating)_add\s*\()")
+BINDING_RE = re.compile(r"\blet\s+(?:mut\s+)?(\w+)\s*=\s*&?\s*(?:mut\s+)?ctx\s*\.\s*accounts\s*\.\s*(\w+)\b")
+AUTHORIZING_KINDS = ("has_one", "address", "constraint")
+OWNED_BY_KINDS = ("token::authority", "associated_token::authority", "has_one")
+
+
+class LiquidationIncompletePattern(VulnerabilityPattern):
+    id = "ANCHOR-063"
+    name = "Liquidation Incomplete Or Unincentivized"
+    severity = "High"
+    category = "lending"
+    remediation_effort = "localized"
+    cost = "expensive"
+    description = (
+        "A liquidation handler changes no state, or pays the liquidator nothing "
+        "while letting anyone call it."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        for ix in liquidations(content, self.max_depth):
+            found = conditions(ix, self.guard_macros)
+            moves = list(MOVE_RE.finditer(ix.text))
+            closes = [a for a in ix.accounts for c in a.constraints if c.kind == "close"]
+            if not moves and not closes and not STATE_WRITE_RE.search(ix.text):
+                findings.append(self._no_op(file_path, content, ix, found))
+                continue
+            signers = {a.name for a in ix.accounts if a.is_signer}
+            if self._pays(ix, moves, closes, signers) or self._restricted(ix, found, signers):
+                continue
+            position = ix.position
+            seized = [(c, at) for c in position.collateral for at in updates(ix.text, c, "-")]
+            who = f"any signer ({', '.join(sorted(signers))})" if signers else "anyone"
+            if seized and not moves:
+                field, at = seized[0]
+                findings.append(self._finding(
+                    file_path, content, ix.line, "High",
+                    f"In {ix.name}: the liquidation writes down {position.struct}.{field} without "
+                    f"transferring the collateral anywhere, and {who} may call it, so any caller erases "
+                    f"another user's collateral from the books",
+                    {
+                        "before": f"{position.struct}.{field} holds an unhealthy borrower's collateral",
+                        "after": f"{ix.name}() by {who}: `{_statement(ix.text, at)}`; no account receives "
+                                 f"the collateral",
+                        "damage": "The borrower's collateral disappears from the books; the pool's "
+                                  "tokens stay in the vault, owned by no position.",
+                    },
+                ))
+            else:
+                write = STATE_WRITE_RE.search(ix.text)
+                at = write.start() if write else moves[0].start()
+                to = sorted({next(g for g in r.groups() if g) for r in RECIPIENT_RE.finditer(ix.text)})
+                moved = f" and moves funds to {', '.join(to)}" if to else ""
+                findings.append(self._finding(
+                    file_path, content, ix.line, "Medium",
+                    f"In {ix.name}: the liquidation transfers nothing to the liquidator and {who} may call "
+                    f"it, so no keeper has a reason to run it and unhealthy positions stay open",
+                    {
+                        "before": f"A {position.struct} is unhealthy; liquidating it costs the caller a "
+                                  f"transaction fee",
+                        "after": f"{ix.name}() runs `{_statement(ix.text, at)}`{moved}; the liquidator "
+                                 f"receives nothing",
+                        "damage": "No keeper runs the liquidation; bad debt stays in the pool and "
+                                  "depositors absorb it.",
+                    },
+                ))
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    def _no_op(self, file_path, content, ix, found) -> Finding:
+        checked = next((cond.split("\n")[0] for _, cond in found), "")
+        what = f"checks `{' '.join(checked.split())}` and then changes nothing" if checked else "changes nothing"
+        read_only = ""
+        if ix.accounts and not any(a.is_mut for a in ix.accounts):
+            read_only = "; none of its accounts is mut, so it could not write if it tried"
+        unhealthy = f"`{' '.join(checked.split())}` holds" if checked else "The position is unhealthy"
+        return self._finding(
+            file_path, content, ix.line, "High",
+            f"In {ix.name}: the liquidation {what} — no debt repaid, no collateral seized or transferred, "
+            f"so unhealthy positions are never closed{read_only}",
+            {
+                "before": f"{unhealthy}: the {ix.position.struct} is insolvent",
+                "after": f"{ix.name}() returns Ok; the {ix.position.struct} is unchanged",
+                "damage": "Bad debt stays in the pool and grows with every price move; depositors absorb it.",
+            },
+        )
+
+    @staticmethod
+    def _pays(ix, moves, closes, signers) -> bool:
+        """Some movement or `close` goes to the signer, an account it owns, or an account named liquidator.
+
+        A recipient that is not an account of the struct (a helper's parameter) is given the benefit of
+        the doubt. A ledger credit to such an account (`liquidator_account.deposited += seized`) pays too,
+        unless it is the account whose debt is written down.
+        """
+        names = {a.name for a in ix.accounts}
+        paid = set(signers) | {a.name for a in ix.accounts if re.search(r"(?i)liquidator", a.name)}
+        paid |= {a.name for a in ix.accounts for c in a.constraints
+                 if c.kind in OWNED_BY_KINDS and c.value.split("@")[0].strip() in signers}
+        if any(c.value.split("@")[0].strip() in paid for a in closes for c in a.constraints if c.kind == "close"):
+            return True
+        for m in moves:
+            end = ix.text.find(";", m.end())
+            window = ix.text[m.start():end if end != -1 else len(ix.text)]
+            if m.start() > 0:
+                window = ix.text[max(0, ix.text.rfind(";", 0, m.start()) + 1):m.start()] + window
+            for r in RECIPIENT_RE.finditer(window):
+                recipient = next(g for g in r.groups() if g)
+                if recipient in paid or recipient not in names:
+                    return True
+        bound = {m.group(1): m.group(2) for m in BINDING_RE.finditer(ix.text)}
+        liquidated = {bound.get(r.group(1), r.group(1)) for d in ix.position.debt for at in updates(ix.text, d, "-")
+                      for r in [re.search(r"(\w+)\s*$", ix.text[:at])] if r}
+        for m in CREDIT_RE.finditer(ix.text):
+            account = bound.get(m.group(1), m.group(1))
+            if account in paid and account in names and account not in liquidated:
+                return True
+        return False
+
+    @staticmethod
+    def _restricted(ix, found, signers) -> bool:
+        """The signer is pinned by a constraint of the struct or compared by key in a condition."""
+        for account in ix.accounts:
+            for c in account.constraints:
+                if c.kind in AUTHORIZING_KINDS and (account.name in signers or mentions(c.value, signers)):
+                    return True
+        return any(mentions(cond, signers) and KEY_COMPARISON_RE.search(cond) for _, cond in found)
+
+    def _finding(self, file_path, content, line, severity, message, state) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=severity,
+            file=file_path,
+            line=line,
+            description=f"{message}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state=state,
+            impact={
+                "attack_cost": "None — the loss accrues on its own while liquidation does nothing",
+                "exploitability": "Medium — depends on positions becoming unhealthy, which markets guarantee",
+                "breach_cost_context": "Lending protocols without working liquidations become insolvent in "
+                                       "the first sharp price move.",
+            },
+            anchor_versions_affected="All versions (developer-side pattern)",
+            ecosystem_recommendations=[
+                "Liquidate in one handler: repay debt from the liquidator, seize collateral plus a bonus to them",
+                "Either pay the liquidator or restrict the instruction to a keeper the protocol runs",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Repay the debt and pay the liquidator out of the collateral:\n"
+            "  require!(health < 100, LendingError::HealthyPosition);\n"
+            "  let seized = repay.checked_mul(10_000 + LIQUIDATION_BONUS_BPS)\n"
+            "      .ok_or(LendingError::MathOverflow)? / 10_000;\n"
+            "  position.borrowed = position.borrowed.checked_sub(repay).ok_or(LendingError::MathOverflow)?;\n"
+            "  position.deposited = position.deposited.checked_sub(seized).ok_or(LendingError::MathOverflow)?;\n"
+            "  transfer_from_vault(&ctx, &ctx.accounts.liquidator, seized)?;"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Liquidation is left as a check without the action it guards, or as "
+            "an action without the reward that gets it called. Both compile and "
+            "pass tests that only assert an unhealthy position is accepted; "
+            "neither removes bad debt from the pool."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. A borrower's collateral falls below its debt\n"
+            "2. A keeper calls liquidate(): the health check passes, nothing else happens\n"
+            "3. The position stays open; its collateral keeps falling\n"
+            "4. Depositors' withdrawals eventually fail against the unrecoverable debt"
+        )
+
+
+def _statement(text: str, at: int) -> str:
+    """The statement around an offset, collapsed to one line."""
+    start = max(text.rfind(";", 0, at), text.rfind("{", 0, at), text.rfind("}", 0, at)) + 1
+    end = text.find(";", at)
+    return " ".join(text[start:end if end != -1 else len(text)].split())
@@ -0,0 +1,127 @@
+"""
+ANCHOR-061: Withdraw Ignores Outstanding Debt
+
+Collateral backs a loan only while it stays in the position. A withdraw
+that checks the deposit alone lets the borrower take it back with the loan
+still open:
+
+    require!(user.deposited >= amount, LendingError::InsufficientBalance);
+    user.deposited -= amount;          // user.borrowed is never looked at
+
+Deposit 100, borrow 90, withdraw 100: the vault is 90 short and the
+position owes 90 against nothing.
+
+For the withdraw instructions of a lending program (see lending.py) —
+instructions taking from a position's collateral without paying its debt
+down — the rule reports (Critical) those whose guard conditions, across
+the handler, the local functions it reaches and its accounts struct's
+constraints, never read the position's debt: no `borrowed == 0`, no
+`deposited - amount >= borrowed * ..`, no health helper. Only positions
+some instruction of the unit borrows into are considered; a debt field
+nothing increases cannot be outstanding.
+
+Whether the remaining collateral clears the debt with an LTV margin is
+ANCHOR-060's question on the borrow side; here any re-check of the debt
+counts.
+"""
+
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.lending import (
+    HEALTH_RE, borrowed_anywhere, conditions, lending_instructions, mentions,
+)
+
+
+class WithdrawDebtCheckPattern(VulnerabilityPattern):
+    id = "ANCHOR-061"
+    name = "Withdraw Ignores Outstanding Debt"
+    severity = "Critical"
+    category = "lending"
+    remediation_effort = "localized"
+    description = (
+        "Collateral is withdrawn from a lending position without re-checking the "
+        "debt it backs."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        borrowed = {}
+        for ix in lending_instructions(content, self.max_depth):
+            if ix.kind != "withdraw":
+                continue
+            position = ix.position
+            if position.struct not in borrowed:
+                borrowed[position.struct] = borrowed_anywhere(content, position)
+            if not borrowed[position.struct]:
+                continue
+            found = conditions(ix, self.guard_macros)
+            if any(mentions(cond, position.debt) or HEALTH_RE.search(cond) for _, cond in found):
+                continue
+            debt = " / ".join(f"{position.struct}.{d}" for d in position.debt)
+            checked = [(at, cond) for at, cond in found if mentions(cond, position.collateral)]
+            if checked:
+                line = ix.line_of(content, checked[0][0])
+                what = (f"checks only that {position.struct}.{ix.field} covers the amount; {debt} is never "
+                        f"read, so a borrower withdraws the collateral backing an open loan")
+            else:
+                line = ix.line
+                what = (f"takes from {position.struct}.{ix.field} without any condition on {debt}, so a "
+                        f"borrower withdraws the collateral backing an open loan")
+            findings.append(self._finding(file_path, content, line, f"In {ix.name}: the withdrawal {what}"))
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    def _finding(self, file_path, content, line, message) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=self.severity,
+            file=file_path,
+            line=line,
+            description=f"{message}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "deposited = 100, borrowed = 90",
+                "after": "deposited = 0, borrowed = 90",
+                "damage": "The loan is left unbacked; the vault is short by the borrowed amount.",
+            },
+            impact={
+                "attack_cost": "Deposit, borrow, withdraw — three ordinary transactions",
+                "exploitability": "High — any user with a position, repeatable until the vault is empty",
+                "breach_cost_context": "Every unbacked loan is a direct loss to the pool's depositors.",
+            },
+            anchor_versions_affected="All versions (developer-side pattern)",
+            ecosystem_recommendations=[
+                "Run the same solvency check after every change to a position: borrow, withdraw, liquidate",
+                "Keep that check in one function so withdraw cannot drift from borrow",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Re-check the debt against the collateral that remains:\n"
+            "  let remaining = user.deposited\n"
+            "      .checked_sub(amount)\n"
+            "      .ok_or(LendingError::InsufficientBalance)?;\n"
+            "  require!(\n"
+            "      remaining * LTV_BPS / 10_000 >= user.borrowed,\n"
+            "      LendingError::InsufficientCollateral\n"
+            "  );"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "The withdraw path treats the deposit as the user's free balance. In "
+            "a lending position part of it is pledged against the debt, and only "
+            "the excess over the debt's collateral requirement may leave."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. Attacker deposits 100 SOL\n"
+            "2. borrow(90) passes the collateral check\n"
+            "3. withdraw(100) passes: deposited >= amount, the debt is never read\n"
+            "4. Attacker holds 190 SOL of the pool's 100 + 90; the loan is never repaid"
+        )
@@ -0,0 +1,109 @@
+"""
+Cross-instruction lifecycle of program account types.
+
+Each accounts struct moves the accounts it takes through one step of their
+life: created (`init`, `zero`, `init_if_needed`), used, or closed
+(`close = <destination>`). Read across every instruction of a program, the
+steps form a state machine per account type:
+
+    Position:  init  (OpenPosition)      seeds = [b"position", owner.key()]
+               use   (Deposit, Withdraw)
+               close (ClosePosition)     close = owner
+               init_if_needed (Deposit)  seeds = [b"position", owner.key()]
+
+account_lifecycles() builds that machine for a source unit. Rules ask it
+the lifecycle questions no single struct answers: can a closed account be
+re-created at the same address by an instruction that does not expect a
+fresh account (close + init_if_needed on the same seeds), and where do the
+lamports of a close go.
+
+Types are the `T` of `Account<'info, T>`, `AccountLoader<'info, T>` and
+`InterfaceAccount<'info, T>`; raw accounts have no type to track.
+"""
+
+import re
+from dataclasses import dataclass, field
+
+from scanner.patterns.facts import Facts
+
+TYPE_RE = re.compile(r"\b(?:Account|AccountLoader|InterfaceAccount)\s*<\s*'[^,]+,\s*(\w+)")
+CREATE, REINIT, USE, CLOSE = "init", "init_if_needed", "use", "close"
+
+
+@dataclass
+class Step:
+    """One accounts-struct field moving an account of the type through its life."""
+
+    kind: str          # "init" | "init_if_needed" | "use" | "close"
+    struct: object     # facts.AccountsStruct
+    account: object    # facts.Account
+    # Normalized seeds expression (ATAs as `associated_token,<authority>,<mint>`), "" for a non-PDA account
+    seeds: str = ""
+    # Close destination field name
+    destination: str = ""
+
+
+@dataclass
+class Lifecycle:
+    """Every step an account type goes through across a unit's instructions."""
+
+    type: str
+    steps: list = field(default_factory=list)
+
+    def of(self, kind: str) -> list:
+        return [s for s in self.steps if s.kind == kind]
+
+    def summary(self) -> str:
+        """`init (A) -> use (B, C) -> close (D)`, for finding descriptions."""
+        parts = []
+        for kind in (CREATE, REINIT, USE, CLOSE):
+            structs = list(dict.fromkeys(s.struct.name for s in self.of(kind)))
+            if structs:
+                shown = ", ".join(structs[:3]) + (f", +{len(structs) - 3}" if len(structs) > 3 else "")
+                parts.append(f"{kind} ({shown})")
+        return " -> ".join(parts)
+
+
+def account_lifecycles(facts: Facts) -> dict:
+    """{account type: Lifecycle} for every program account type in the unit."""
+    lifecycles = {}
+    for struct in facts.accounts_structs():
+        for account in struct.accounts:
+            m = TYPE_RE.search(account.type)
+            if not m:
+                continue
+            lifecycle = lifecycles.setdefault(m.group(1), Lifecycle(m.group(1)))
+            pda = account.pda
+            seeds = normalize_seeds(pda.seeds) if pda else ""
+            if pda and pda.is_foreign:
+                # Same seeds under another program are another address
+                seeds += f"@{normalize_seeds(pda.program)}"
+            if not seeds and account.has("associated_token::mint"):
+                # An ATA is a PDA of (authority, mint); name the derivation the same way
+                ata = {c.kind: c.value for c in account.constraints}
+                seeds = normalize_seeds(
+                    f"associated_token,{ata.get('associated_token::authority', '')},{ata['associated_token::mint']}"
+                )
+            if account.has("init_if_needed"):
+                lifecycle.steps.append(Step(REINIT, struct, account, seeds))
+            elif account.has("init") or account.has("zero"):
+                lifecycle.steps.append(Step(CREATE, struct, account, seeds))
+            close = next((c for c in account.constraints if c.kind == "close"), None)
+            if close:
+                lifecycle.steps.append(Step(CLOSE, struct, account, seeds, close.value.split("@")[0].strip()))
+            elif not any(account.has(k) for k in ("init", "zero", "init_if_needed")):
+                lifecycle.steps.append(Step(USE, struct, account, seeds))
+    return lifecycles
+
+
+def normalize_seeds(seeds: str) -> str:
+    """Seeds with formatting and reference/conversion noise removed, so equal derivations compare equal."""
+    text = re.sub(r"\s+", "", seeds)
+    text = re.sub(r"\.as_ref\(\)|\.as_bytes\(\)|\.to_bytes\(\)|\.key\(\)|&", "", text)
+    return text.strip("[],")
+
+
+def seed_prefix(seeds: str) -> str:
+    """The leading literal seed (`b"position"`, a SEED constant), "" if there is none."""
+    first = seeds.split(",")[0] if seeds else ""
+    return first if re.match(r'^(?:b"|"|[A-Z][A-Z0-9_]*$|\w+::[A-Z][A-Z0-9_]*$)', first) else ""
@@ -0,0 +1,328 @@
+"""
+ANCHOR-058: Loop Accumulator Overflow
+
+Reward and vesting code sums one product per schedule entry:
+
+    let total_changes = reward_change_time.len() - 1;
+    for ix in index..=total_changes {
+        let reward = rewardable_time.checked_mul(reward_emission[ix]).ok_or(..)?;
+        reward_tokens = reward_tokens.checked_add(reward).ok_or(..)?;
+    }
+
+Whether the sum can overflow depends on how many times the loop runs, and
+that is decided by account data: the schedule vector grows by one entry
+every time the admin changes the rate. Once enough entries pile up the
+sum passes u64::MAX, and from then on the claim fails for every staker —
+with checked_add the instruction errors forever, with a plain `+` it
+panics (or wraps, without overflow-checks).
+
+The rule reports an accumulation inside a `for` loop — `acc += term`,
+`acc = acc + term`, `acc = acc.checked_add(term)` into a variable or field
+declared outside the loop — when the term is a per-iteration product
+(`*`, `checked_mul`, directly or through a `let` in the loop), and bounds
+the iteration count from where the iterated collection comes from:
+
+  - the collection is followed through `.len()` range bounds, `let`
+    bindings and helper parameters back to an account field;
+  - a `#[max_len(N)]` field, a fixed array, or a Vec whose every `push`
+    is guarded by a length check is bounded: reported Low, with the bound
+    to check against the largest term;
+  - an account Vec pushed to with no length check is bounded by nothing:
+    Medium for checked_add (the instruction fails permanently once the
+    sum overflows), High for an unchecked `+`;
+  - `remaining_accounts` or a vector instruction argument is chosen by
+    the caller: Low for checked_add, which only fails the caller's own
+    transaction, High for an unchecked `+`, which can wrap to a small
+    total.
+
+A public helper nothing in the unit calls (a file split off a concatenated
+target) is reported Low, with the parameter the collection comes from.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+from scanner.patterns.layout import Layout
+from scanner.patterns.math_summary import _blank
+from scanner.patterns.reward_groups import LOOP_RE
+from scanner.patterns.saturating_sub import SaturatingSubPattern
+from scanner.patterns.validation import CALL_RE, _param_names, _split_top
+
+ADD_ASSIGN_RE = re.compile(r"^\s*([\w.\[\]]+)\s*\+=\s*(.+)$", re.S)
+ADD_RE = re.compile(r"^\s*([\w.\[\]]+)\s*=\s*([\w.\[\]]+)\s*(\+|\.\s*checked_add\s*\()(.+)$", re.S)
+PRODUCT_RE = re.compile(r"[\w)\]]\s*\*\s*[\w(]|\.\s*(?:checked|saturating|wrapping)_mul\s*\(|\bmul_div\w*\s*\(")
+ITER_RE = re.compile(r"^&?\s*(?:mut\s+)?([\w.]+?)(?:\s*\.\s*(?:iter|iter_mut|into_iter)\s*\(\s*\))?"
+                     r"(?:\s*\.\s*enumerate\s*\(\s*\))?$")
+RANGE_RE = re.compile(r"^\(?\s*[\w.()]*\s*\.\.=?\s*(.+?)\s*$")
+
+
+class LoopAccumulatorPattern(VulnerabilityPattern):
+    id = "ANCHOR-058"
+    name = "Loop Accumulator Overflow"
+    severity = "Medium"
+    category = "arithmetic"
+    remediation_effort = "localized"
+    cost = "expensive"
+    description = (
+        "A loop sums per-iteration products over a collection whose length "
+        "account data or the caller controls, so the total can be pushed past "
+        "u64::MAX."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        code = _blank(content)
+        if not LOOP_RE.search(code):
+            return []
+        functions = self._find_functions(code)
+        layout = Layout(content)
+        handlers = {ix.name: ix for ix in Facts(content, file_path).instructions()}
+        findings = []
+        for fn in functions:
+            body = fn["body"]
+            for m in LOOP_RE.finditer(body):
+                end = self._find_block_end(body, m.end() - 1)
+                if end == -1:
+                    continue
+                loop = body[m.end():end - 1]
+                collection = self._collection(" ".join(m.group(4).split()), body[:m.start()])
+                if not collection:
+                    continue
+                for acc, term, checked, offset in self._accumulations(loop, body[:m.start()]):
+                    bound = self._bound(collection, fn, functions, layout, handlers, code, 0)
+                    if bound is None:
+                        continue
+                    line = self._get_line_number(code, fn["body_start"] + m.end() + offset)
+                    findings.append(self._finding(
+                        file_path, content, line, fn["name"], collection, acc, term, checked, bound,
+                    ))
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    @staticmethod
+    def _collection(header: str, before: str) -> str:
+        """What the loop iterates: `remaining_accounts`, or a collection path such as `x.rewards`."""
+        if "remaining_accounts" in header:
+            return "remaining_accounts"
+        m = RANGE_RE.match(header)
+        if m and ".." in header:
+            upper = m.group(1)
+            # `total_changes` bound by `let total_changes = v.len() - 1`
+            for name in re.findall(r"(?<![\w.])([A-Za-z_]\w*)\b(?!\s*[.(])", upper):
+                let = re.findall(rf"\blet\s+(?:mut\s+)?{re.escape(name)}\s*(?::[^=]+)?=\s*([^;]+);", before)
+                if let:
+                    upper = re.sub(rf"\b{re.escape(name)}\b", lambda _: let[-1], upper)
+            if "remaining_accounts" in upper:
+                return "remaining_accounts"
+            length = re.search(r"([\w.]+?)\s*\.\s*len\s*\(\s*\)", upper)
+            return length.group(1) if length else ""
+        m = ITER_RE.match(header)
+        return m.group(1) if m and not re.fullmatch(r"\d+", m.group(1)) else ""
+
+    @staticmethod
+    def _accumulations(loop: str, before: str) -> list:
+        """[(accumulator, product term, checked, offset in loop)] of sums of per-iteration products."""
+        result = []
+        lets = dict(re.findall(r"\blet\s+(?:mut\s+)?(\w+)\s*(?::[^=]+)?=\s*([^;]+);", loop))
+        for stmt_m in re.finditer(r"[^;{}]+", loop):
+            stmt = stmt_m.group(0)
+            m = ADD_ASSIGN_RE.match(stmt)
+            if m:
+                acc, term, checked = m.group(1), m.group(2), False
+            else:
+                m = ADD_RE.match(stmt)
+                if not m or m.group(1) != m.group(2):
+                    continue
+                acc, checked = m.group(1), m.group(3) != "+"
+                term = SaturatingSubPattern._argument(stmt, m.start(4) - 1) if checked else m.group(4)
+            acc, term = " ".join(acc.split()), " ".join(term.split())
+            # The accumulator lives across iterations: a field, or a local declared before the loop
+            if "." not in acc and not re.search(rf"\blet\s+mut\s+{re.escape(acc)}\b", before):
+                continue
+            if re.search(rf"\blet\s+mut\s+{re.escape(acc)}\s*(?::\s*f(?:32|64)\b|=\s*[\d_]*\.\d)", before):
+                continue
+            product = term if PRODUCT_RE.search(term) else lets.get(term.strip("&* ()"), "")
+            if not PRODUCT_RE.search(product):
+                continue
+            product = re.sub(r"\s*\.\s*(?:ok_or(?:_else)?|expect)\s*\(.*\)\s*\??$|\s*\.\s*unwrap\s*\(\s*\)$|\s*\?$", "",
+                             " ".join(product.split()))
+            result.append((acc, product, checked, stmt_m.start() + len(stmt) - len(stmt.lstrip())))
+        return result
+
+    def _bound(self, collection: str, fn: dict, functions: list, layout, handlers: dict, code: str,
+               depth: int):
+        """(kind, explanation) for the length of the collection: "caller" when the caller picks it,
+        "stored" for an account Vec that grows without a cap, "bounded", "unknown" for the parameter
+        of a public helper nothing in the unit calls, or None when its source is not found."""
+        if collection == "remaining_accounts":
+            return "caller", "remaining_accounts, as many as the caller fits in the transaction"
+        root = collection.split(".")[0]
+        params = [name for name, _ in _param_names(fn["params"])]
+        if "." not in collection and root in params:
+            if fn["name"] in handlers:
+                return "caller", f"the instruction argument {root}, as long as the caller makes it"
+            if depth >= self.max_depth:
+                return None
+            # Follow the parameter back to what the callers pass
+            position = params.index(root)
+            called = False
+            for caller in functions:
+                for m in CALL_RE.finditer(caller["body"]):
+                    if m.group(1) != fn["name"]:
+                        continue
+                    called = True
+                    args = self._args(caller["body"], m.end() - 1)
+                    # `Type::f(&mut x, ..)` passes self first; `x.f(..)` does not
+                    if self._takes_self(fn) and not re.search(r"\.\s*$", caller["body"][:m.start(1)]):
+                        args = args[1:]
+                    if position >= len(args):
+                        continue
+                    source = self._resolve(args[position], caller["body"][:m.start()])
+                    bound = self._bound(source, caller, functions, layout, handlers, code, depth + 1)
+                    if bound is not None:
+                        return bound
+            # A public helper of a unit split off its program: the collection is built elsewhere
+            if not called and re.search(rf"\bpub\s+fn\s+{fn['name']}\b", code):
+                return "unknown", f"the {root} argument of {fn['name']}, passed in from outside this unit"
+            return None
+        if "." not in collection:
+            let = re.findall(rf"\blet\s+(?:mut\s+)?{re.escape(collection)}\s*(?::[^=]+)?=\s*([^;]+);", fn["body"])
+            if not let or depth >= self.max_depth:
+                return None
+            return self._bound(self._resolve(let[-1], ""), fn, functions, layout, handlers, code, depth + 1)
+        return self._field_bound(collection.split(".")[-1], functions, layout, code)
+
+    def _field_bound(self, name: str, functions: list, layout, code: str):
+        for struct in layout.structs.values():
+            for f in struct.fields:
+                if f.name != name:
+                    continue
+                fixed = re.fullmatch(r"\[.+;\s*([^\]]+)\]", f.type.strip())
+                if fixed:
+                    return "bounded", f"{struct.name}.{name}, a fixed array of {fixed.group(1).strip()}"
+                if not f.type.strip().startswith("Vec"):
+                    continue
+                max_len = re.search(r"#\[max_len\s*\(([^)]*)\)\]", f.attrs)
+                if max_len:
+                    return "bounded", f"{struct.name}.{name}, #[max_len({max_len.group(1).strip()})]"
+                pushes = [fn for fn in functions if re.search(rf"\.\s*{re.escape(name)}\s*\.\s*push\s*\(", fn["body"])]
+                unchecked = [fn["name"] for fn in pushes if not self._length_checked(fn["body"], name)]
+                if unchecked:
+                    return "stored", (f"{struct.name}.{name}, which grows by one entry on every call to "
+                                  f"{', '.join(unchecked)} with no length cap")
+                if pushes:
+                    return "bounded", f"{struct.name}.{name}, length-checked before every push"
+                return "bounded", f"{struct.name}.{name}, never pushed to after creation"
+        return None
+
+    @staticmethod
+    def _length_checked(body: str, name: str) -> bool:
+        return bool(re.search(rf"\b{re.escape(name)}\s*\.\s*len\s*\(\s*\)\s*(?:[<>]=?|==)|"
+                              rf"(?:[<>]=?|==)\s*[\w.]*\b{re.escape(name)}\s*\.\s*len\s*\(\s*\)", body))
+
+    @staticmethod
+    def _takes_self(fn: dict) -> bool:
+        return bool(re.match(r"\s*&?\s*(?:mut\s+)?self\b", fn["params"]))
+
+    @staticmethod
+    def _args(body: str, open_pos: int) -> list:
+        depth = 0
+        for i in range(open_pos, len(body)):
+            depth += {"(": 1, ")": -1}.get(body[i], 0)
+            if depth == 0:
+                return _split_top(body[open_pos + 1:i])
+        return []
+
+    @staticmethod
+    def _resolve(expr: str, before: str) -> str:
+        """A passed argument as a path: `&stake_details.reward_change_time` for `&record` bound to it."""
+        expr = re.sub(r"\s*\.\s*(?:clone|to_vec|as_slice|iter)\s*\(\s*\)\s*$", "", expr.strip()).lstrip("&").strip()
+        expr = re.sub(r"^mut\s+", "", expr)
+        if re.fullmatch(r"\w+", expr):
+            let = re.findall(rf"\blet\s+(?:mut\s+)?{re.escape(expr)}\s*(?::[^=]+)?=\s*([^;]+);", before)
+            if let:
+                return LoopAccumulatorPattern._resolve(let[-1], "")
+        return " ".join(expr.split()).replace(" .", ".").replace(". ", ".")
+
+    def _finding(self, file_path, content, line, fn_name, collection, acc, term, checked, bound) -> Finding:
+        kind, source = bound
+        op = f"{acc}.checked_add(..)" if checked else f"{acc} + .."
+        if kind == "bounded":
+            severity = "Low"
+            verdict = (f"the iteration count is bounded by {source}; check that this many times the "
+                       f"largest `{term}` fits in the accumulator")
+        elif kind == "unknown":
+            severity = "Low"
+            verdict = (f"the iteration count comes from {source}; check that whatever builds it caps "
+                       f"its length")
+        elif kind == "stored":
+            severity = "Medium" if checked else "High"
+            outcome = ("the checked_add fails and the instruction can never succeed again"
+                       if checked else "the unchecked addition panics or wraps on every later call")
+            verdict = (f"the iteration count is not bounded by the program: {source}. Once enough entries "
+                       f"accumulate {outcome}")
+        else:
+            # Only the caller's own transaction fails on a checked sum; an unchecked one can wrap to a small total
+            severity = "Low" if checked else "High"
+            outcome = ("the checked_add fails that caller's transaction"
+                       if checked else "an overflowing sum panics or wraps to a small total")
+            verdict = f"the iteration count is chosen by the caller: {source}; {outcome}"
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=severity,
+            file=file_path,
+            line=line,
+            description=(
+                f"In {fn_name}: the loop over {collection} sums `{term}` into {acc} ({op}); {verdict}."
+            ),
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=(
+                f"Bound the iterations where {collection.split('.')[-1]} grows (a length check before "
+                f"every push, or #[max_len(N)] with a matching check), accumulate in u128 and convert "
+                f"once with try_into, or keep a running total updated when an entry is added instead "
+                f"of summing the whole history on every call."
+            ),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "reward_change_time has 3 entries; claim sums 3 products",
+                "after": "after repeated rate changes the sum exceeds u64::MAX",
+                "damage": "Every claim of a position staked before the changes fails; the rewards are stuck.",
+            },
+            impact={
+                "attack_cost": "Repeated calls to whatever grows the collection, or a long remaining_accounts list",
+                "exploitability": "Medium — depends on who can grow the collection and how large each term gets",
+                "breach_cost_context": "Accumulated-history loops are where reward programs lock user funds: "
+                                       "the instruction that pays out is the one that can no longer run.",
+            },
+            anchor_versions_affected="All versions (program-side arithmetic)",
+            ecosystem_recommendations=[
+                "Cap every Vec an instruction iterates, with #[max_len] and a length check on push",
+                "Accumulate reward sums in u128 and convert once at the end",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Bound the schedule and widen the accumulator:\n"
+            "  require!(details.reward_change_time.len() < MAX_RATE_CHANGES, StakeError::TooManyChanges);\n"
+            "  let mut total: u128 = 0;\n"
+            "  for .. { total = total.checked_add(u128::from(time) * u128::from(rate)).ok_or(..)?; }\n"
+            "  let total: u64 = total.try_into().map_err(|_| StakeError::Overflow)?;"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "The sum runs over a collection whose length the program never "
+            "caps. Each iteration can add up to u64::MAX, so the number of "
+            "entries — grown by an admin, a crank or the caller — decides "
+            "when the accumulator overflows."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. change_reward appends to reward_change_time with no cap\n"
+            "2. The rate is changed often, or set high, over the life of the pool\n"
+            "3. calc_reward's sum over the schedule passes u64::MAX\n"
+            "4. Every claim (and any unstake that claims first) fails from then on"
+        )
@@ -0,0 +1,258 @@
+"""
+Arithmetic summaries of math helpers, propagated to their callers.
+
+AMM programs keep their arithmetic in libraries several calls below the
+handler. In raydium-clmm a swap reaches the fixed-point leaves through
+swap_internal -> compute_swap_step -> get_delta_amount_0_unsigned ->
+mul_div_ceil, and every tick, fee-growth and reward helper calls into the
+same few leaves:
+
+    let reward_growth_delta = U256::from(time_delta)
+        .mul_div_floor(U256::from(emissions_per_second_x64), U256::from(self.liquidity))
+        .unwrap();
+
+Re-reading each helper for every caller is what makes such chains
+expensive, so math_summaries() reads every function once, records the
+arithmetic facts of its own body, and lets callers inherit the facts of the
+functions they call, at most `depth` calls away (the scan's
+--interprocedural-depth). Facts:
+
+  - overflow:     `+`, `*`, `<<` (or `+=`, `*=`, `<<=`) on integer operands,
+                  outside checked_/saturating_/wrapping_/overflowing_
+                  arithmetic and without widening to u128 / U128 / U256
+  - rounds-down:  integer `/`, `checked_div`, and floor helpers
+  - rounds-up:    `div_ceil`, ceil helpers, and `(a + b - 1) / b`
+
+Helpers that are called but not defined in the unit — the math crate's
+mul_div_floor, mul_div_ceil, proportional, shares_from_value, ... — take
+their summary from the dependency sources pulled into the scan (scan
+--scan-deps, see scanner/dependencies.py), else from LIBRARY_HELPERS.
+Methods are summarized as `Type::name`, so the `process` of every
+accounts struct stays apart. A call resolves through its receiver:
+`Type::name(..)` and `Self::name(..)` by path, `self.name(..)` to the
+enclosing impl, and `ctx.accounts.name(..)` to the impl of the handler's
+Accounts struct. Any other call resolves by name when only one function in
+the unit carries it, and not at all when several do.
+"""
+
+import re
+from dataclasses import dataclass, field
+from functools import lru_cache
+
+from scanner.budget import DEFAULT_DEPTH
+from scanner.patterns.base import VulnerabilityPattern
+from scanner.patterns.facts import Facts
+from scanner.patterns.validation import CALL_RE
+
+OVERFLOW, ROUNDS_DOWN, ROUNDS_UP = "overflow", "rounds-down", "rounds-up"
+
+# Leaf helpers of common math crates (spl-math, raydium/orca libraries, stake pools)
+LIBRARY_HELPERS = {
+    "mul_div": {ROUNDS_DOWN},
+    "mul_div_floor": {ROUNDS_DOWN},
+    "mul_div_ceil": {ROUNDS_UP},
+    "mul_div_round_up": {ROUNDS_UP},
+    "mul_shr": {ROUNDS_DOWN},
+    "div_rounding_up": {ROUNDS_UP},
+    "checked_ceil_div": {ROUNDS_UP},
+    "proportional": {ROUNDS_DOWN},
+    "shares_from_value": {ROUNDS_DOWN},
+    "value_from_shares": {ROUNDS_DOWN},
+}
+
+# Statements whose arithmetic is checked or widened
+GUARDED_RE = re.compile(r"\b(?:checked|saturating|wrapping|overflowing)_\w+|\b(?:u128|i128|U\d+)\b")
+FLOAT_RE = re.compile(r"\bf(?:32|64)\b|\d\.\d")
+KEYWORDS = {"mut", "return", "let", "in", "if", "else", "match", "as", "dyn", "impl", "move", "ref", "break"}
+# Left operand, operator, right operand of a binary `+` / `*` / `<<`
+ARITH_RE = re.compile(
+    r"((?<![\w.])\w+(?:\s*\.\s*\w+(?:\(\))?)*|[)\]])\s*(\+|\*|<<)=?\s*(?=[\w(])(\w*)"
+)
+DIV_RE = re.compile(r"(?<![/*])/(?![/*])|\.\s*(?:checked|saturating|wrapping)_div\s*\(|\bfloor\b")
+CEIL_RE = re.compile(r"\bdiv_ceil\b|\bceil_div\b|\bceil\b|-\s*1\s*\)\s*/")
+
+
+@dataclass
+class MathFact:
+    """One arithmetic fact of a function, and where it comes from."""
+
+    kind: str
+    # Line of the operation, or of the call it is inherited through
+    line: int
+    # Function keys from the summarized one down to the one doing the arithmetic
+    chain: list = field(default_factory=list)
+    # The operation, collapsed to one line
+    expr: str = ""
+    # Dependency file the operation is in; empty for the unit itself
+    file: str = ""
+
+    def describe(self) -> str:
+        via = f" via {' -> '.join(self.chain)}" if len(self.chain) > 1 else ""
+        return f"{self.kind}{via}: `{self.expr}` ({self.location})"
+
+    @property
+    def location(self) -> str:
+        return f"{self.file}:{self.line}" if self.file else f"line {self.line}"
+
+
+@dataclass
+class MathSummary:
+    """Arithmetic facts of one function, its own and its callees'."""
+
+    function: str
+    facts: dict = field(default_factory=dict)
+
+    def has(self, kind: str) -> bool:
+        return kind in self.facts
+
+
+def math_summaries(content: str, depth: int = DEFAULT_DEPTH, dependencies=None) -> dict:
+    """{function key: MathSummary} for every function in the unit with an arithmetic fact.
+
+    The key is the function's name, or `Type::name` for a method; look
+    calls up with resolve_call().
+
+    With a DependencyScope, dependency functions the unit calls are included,
+    their facts carrying the dependency file. Cached per source unit, depth
+    and scope: every rule asking about the same unit shares one pass.
+    Callers must not modify the result.
+    """
+    return _summarize(content, depth, dependencies)
+
+
+@lru_cache(maxsize=16)
+def _summarize(content: str, depth: int, dependencies=None) -> dict:
+    code = _blank(content)
+    functions = unit_functions(content)
+    defined = {key for key, _, _, _ in functions}
+    external = dependencies.summaries(depth) if dependencies else {}
+    known = defined | set(external) | {name for name in LIBRARY_HELPERS if not _defines(defined, name)}
+
+    # Leaves first: each body is read once
+    summaries = {}
+    calls = {}
+    for key, fn, owner, accounts in functions:
+        summary = summaries.setdefault(key, MathSummary(key))
+        body = code[fn["body_start"]:fn["body_start"] + len(fn["body"])]
+        for kind, pos, expr in _local_facts(body):
+            summary.facts.setdefault(kind, MathFact(kind, _line(content, fn["body_start"] + pos), [key], expr))
+        for m in CALL_RE.finditer(body):
+            callee = resolve_call(body[:m.start(1)], m.group(1), owner, accounts, known)
+            if callee and callee != key:
+                calls.setdefault(key, []).append((callee, _line(content, fn["body_start"] + m.start(1))))
+    for name, kinds in LIBRARY_HELPERS.items():
+        if not _defines(defined, name):
+            summaries[name] = MathSummary(name, {k: MathFact(k, 0, [name], f"{name}(..)") for k in kinds})
+    called = {callee for sites in calls.values() for callee, _ in sites}
+    for name in called - defined:
+        if name in external:
+            summaries[name] = external[name]
+
+    # One call level per round, from the previous round's facts, so each
+    # inherited fact carries its shortest chain
+    for _ in range(depth):
+        inherited = []
+        for caller, sites in calls.items():
+            for callee, line in sites:
+                for kind, fact in summaries[callee].facts.items():
+                    if kind not in summaries[caller].facts:
+                        inherited.append((caller, MathFact(kind, line, [caller] + fact.chain, fact.expr)))
+        if not inherited:
+            break
+        for caller, fact in inherited:
+            summaries[caller].facts.setdefault(fact.kind, fact)
+    return {name: s for name, s in summaries.items()
+            if s.facts and (name in defined or name in external and name in called)}
+
+
+@lru_cache(maxsize=16)
+def unit_functions(content: str) -> tuple:
+    """((key, fn, impl type, Accounts struct)) for every function of the unit.
+
+    The impl type is empty outside an impl; the Accounts struct is the
+    handler's Context<T> type, empty for functions that are not handlers.
+    """
+    impls = VulnerabilityPattern._find_impl_blocks(content)
+    facts = Facts(content)
+    functions = []
+    for fn in VulnerabilityPattern._find_functions(content):
+        owner = next((t for start, end, t in reversed(impls) if start < fn["body_start"] < end), "")
+        key = f"{owner}::{fn['name']}" if owner else fn["name"]
+        ix = facts.instruction_of(fn)
+        functions.append((key, fn, owner, ix.accounts_struct if ix else ""))
+    return tuple(functions)
+
+
+def resolve_call(before: str, name: str, owner: str, accounts: str, keys) -> str:
+    """The key among keys that a call of name, preceded by before, reaches; "" when unknown or ambiguous.
+
+    owner is the impl type of the calling function, accounts its handler's
+    Accounts struct.
+    """
+    path = re.search(r"(\w+)\s*::\s*$", before)
+    target = ""
+    if path:
+        target = f"{owner if path.group(1) == 'Self' else path.group(1)}::{name}"
+    elif re.search(r"\.\s*$", before):
+        receiver = re.search(r"(?<![\w.)\]])(\w+(?:\s*\.\s*\w+)*)\s*\.\s*$", before)
+        chain = re.sub(r"\s+", "", receiver.group(1)) if receiver else ""
+        if chain == "self" and owner:
+            target = f"{owner}::{name}"
+        elif chain == "ctx.accounts" and accounts:
+            target = f"{accounts}::{name}"
+    else:
+        target = name
+    if target in keys:
+        return target
+    if target and not path:
+        # A free function, or a method of a known type, that the unit does not define
+        return ""
+    matches = [k for k in keys if k == name or k.endswith(f"::{name}")]
+    return matches[0] if len(matches) == 1 else ""
+
+
+def _defines(keys, name: str) -> bool:
+    return any(k == name or k.endswith(f"::{name}") for k in keys)
+
+
+def _local_facts(body: str) -> list:
+    """[(kind, offset, expr)] of the arithmetic in one function body."""
+    facts = []
+    for m in re.finditer(r"[^;{}]+", body):
+        stmt = m.group(0)
+        if FLOAT_RE.search(stmt) or re.match(r"\s*(?:const|static|type|use)\b", stmt):
+            continue
+        expr = " ".join(stmt.split())
+        ceil = CEIL_RE.search(stmt)
+        if ceil:
+            facts.append((ROUNDS_UP, m.start() + ceil.start(), expr))
+        else:
+            div = DIV_RE.search(stmt)
+            if div:
+                facts.append((ROUNDS_DOWN, m.start() + div.start(), expr))
+        if GUARDED_RE.search(stmt):
+            continue
+        for op in ARITH_RE.finditer(stmt):
+            left, right = op.group(1), op.group(3)
+            if left.split(".")[0].strip() in KEYWORDS:
+                continue
+            # Sums of literals and constants (`8 + 32`, `MAX * 2`) are compile-time
+            if right and all(re.fullmatch(r"\d[\d_]*\w*|[A-Z][A-Z0-9_]*", x) for x in (left, right)):
+                continue
+            facts.append((OVERFLOW, m.start() + op.start(2), expr))
+            break
+    return facts
+
+
+def _blank(content: str) -> str:
+    """Comments and string literals replaced by spaces, offsets unchanged."""
+    return re.sub(
+        r'//[^\n]*|/\*.*?\*/|"(?:\\.|[^"\\])*"',
+        lambda m: re.sub(r"[^\n]", " ", m.group(0)),
+        content,
+        flags=re.S,
+    )
+
+
+def _line(content: str, pos: int) -> int:
+    return content.count("\n", 0, pos) + 1
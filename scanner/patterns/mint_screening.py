@@ -0,0 +1,233 @@
+"""
+ANCHOR-039: Unscreened Mint Accepted
+
+A mint's freeze authority can freeze any token account of that mint,
+including the program's vaults. A program that opens a vault for whatever
+mint the caller passes — a lending reserve, a reward farm, a pool — lets
+that caller pick a mint whose freeze authority it keeps:
+
+    #[derive(Accounts)]
+    pub struct CreateReserve<'info> {
+        pub collateral_mint: Account<'info, Mint>,     // any mint
+        #[account(init, payer = payer, token::mint = collateral_mint, token::authority = reserve,
+                  seeds = [b"vault", collateral_mint.key().as_ref()], bump)]
+        pub vault: Account<'info, TokenAccount>,
+        ..
+    }
+
+Marinade screens the mints it accepts in a helper:
+
+    pub fn check_freeze_authority(mint: &Mint, field_name: &str) -> Result<()> {
+        if mint.freeze_authority.is_none() { Ok(()) } else { .. }
+    }
+
+For every mint of an accounts struct that the instruction opens a
+program-held token account of (created with `init` or `init_if_needed`,
+its authority not a signer of the struct, or the account a PDA), the rule
+looks for a `freeze_authority` check of the mint in the struct's
+constraints, its handlers or impl blocks, directly or through a function
+whose body reads `freeze_authority`. It reports the mint (Medium) when none
+is found, naming an unchecked `mint_authority` as well.
+
+Mints the instruction creates, PDAs, and mints pinned by `address` (also
+under `cfg_attr`), `mint::authority`, a `has_one` or a key comparison
+against stored state or an allowlist are the program's choice, not the
+caller's, and are not reported. Neither are NFT mints (see nft.py), whose
+freeze authority is the master edition by design, nor vaults derived from
+the signer's key, which only ever hold the signer's own tokens.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+from scanner.patterns.nft import nft_mints
+
+MINT_RE = re.compile(r"\b(?:Account|InterfaceAccount)\s*<\s*'[^,]+,\s*(?:\w+::)*Mint\s*>")
+FN_RE = re.compile(r"\bfn\s+(\w+)\s*(?:<[^>{]*>)?\s*\(")
+
+
+class MintScreeningPattern(VulnerabilityPattern):
+    id = "ANCHOR-039"
+    name = "Unscreened Mint Accepted"
+    severity = "Medium"
+    category = "tokens"
+    remediation_effort = "mechanical"
+    description = (
+        "An instruction opens a program-held vault for any mint the caller "
+        "passes without checking the mint's freeze authority, so the vault "
+        "can be frozen by whoever created the mint."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        facts = Facts(content)
+        screens = {
+            field: self._screening_functions(content, field)
+            for field in ("freeze_authority", "mint_authority")
+        }
+        for struct in facts.accounts_structs():
+            held = self._held_mints(struct)
+            if not held:
+                continue
+            text = self._checks(struct, facts, content)
+            handlers = [ix.name for ix in facts.instructions() if ix.accounts_struct == struct.name]
+            for mint in held:
+                if self._pinned(mint, struct, text, content):
+                    continue
+                if self._screened(mint.name, text, "freeze_authority", screens["freeze_authority"]):
+                    continue
+                also = "" if self._screened(mint.name, text, "mint_authority", screens["mint_authority"]) \
+                    else " (nor its mint_authority)"
+                where = f" ({', '.join(handlers)})" if handlers else ""
+                findings.append(self._finding(
+                    file_path, content, mint.line,
+                    f"In struct {struct.name}{where}: mint '{mint.name}' is any mint the caller passes and backs "
+                    f"a program-held token account, but its freeze_authority is never checked{also}, so the "
+                    f"mint's creator can freeze the program's vault",
+                ))
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    def _held_mints(self, struct) -> list:
+        """Mint accounts the struct opens a program-held, shared token account of."""
+        accounts = {a.name: a for a in struct.accounts}
+        nfts = nft_mints(struct)
+        held = []
+        for account in struct.accounts:
+            if not (account.has("init") or account.has("init_if_needed")):
+                continue
+            mint = next((c.value.split("@")[0].strip() for c in account.constraints
+                         if c.kind in ("token::mint", "associated_token::mint")), "")
+            owner = next((c.value.split("@")[0].strip() for c in account.constraints
+                          if c.kind in ("token::authority", "associated_token::authority")), "")
+            authority = accounts.get(owner)
+            program_held = (authority is not None and not authority.is_signer) or (not owner and account.pda)
+            candidate = accounts.get(mint)
+            if program_held and candidate and MINT_RE.search(candidate.type) and candidate not in held \
+                    and candidate not in nfts and not self._per_signer(account, accounts, [authority]):
+                held.append(candidate)
+        return held
+
+    @staticmethod
+    def _per_signer(account, accounts: dict, also: list) -> bool:
+        """The account, or an account its derivation goes through, is derived from a signer's key."""
+        pending, seen = [account] + [a for a in also if a], set()
+        while pending:
+            current = pending.pop()
+            if current.name in seen or not current.pda:
+                continue
+            seen.add(current.name)
+            for name in re.findall(r"\b(\w+)\s*\.\s*key\b", current.pda.seeds):
+                other = accounts.get(name)
+                if other and other.is_signer:
+                    return True
+                if other:
+                    pending.append(other)
+        return False
+
+    @staticmethod
+    def _checks(struct, facts: Facts, content: str) -> str:
+        parts = [c.value for a in struct.accounts for c in a.constraints if c.kind == "constraint"]
+        parts += [ix.body for ix in facts.instructions() if ix.accounts_struct == struct.name]
+        parts += [content[s:e] for s, e, t in VulnerabilityPattern._find_impl_blocks(content) if t == struct.name]
+        return "\n".join(parts)
+
+    @staticmethod
+    def _pinned(mint, struct, text: str, content: str) -> bool:
+        """The program, not the caller, decides which mint this is."""
+        if mint.pda or any(mint.has(kind) for kind in ("init", "init_if_needed", "address", "mint::authority")):
+            return True
+        # Attributes of the field, including cfg_attr(.., account(address = ..)) Facts does not parse
+        lines = content.splitlines()[:mint.line - 1]
+        start = len(lines)
+        while start > 0 and re.match(r"\s*(?:#|\)|\]|//|account\s*\(|not\s*\(|[\w:]+\s*=|\w+\s*\()", lines[start - 1]) \
+                and not re.match(r"\s*(?:pub\s+)?\w+\s*:", lines[start - 1]):
+            start -= 1
+        if re.search(r"\baddress\s*=", "\n".join(lines[start:])):
+            return True
+        name = re.escape(mint.name)
+        for other in struct.accounts:
+            for c in other.constraints:
+                if c.kind == "has_one" and c.value.split("@")[0].strip() == mint.name:
+                    return True
+                if c.kind == "constraint" and re.search(rf"\b{name}\s*\.\s*key\b", c.value) \
+                        and re.search(r"==|!=|\bcontains\s*\(", c.value):
+                    return True
+        for stmt in re.split(r"[;{}]", text):
+            if re.search(rf"\b{name}\s*\.\s*key\b", stmt) and \
+                    re.search(r"==|!=|\brequire_keys_(?:eq|neq)!|\bcontains\s*\(", stmt):
+                return True
+        return False
+
+    @staticmethod
+    def _screening_functions(content: str, field: str) -> list:
+        """Names of functions whose body reads the mint's `field`."""
+        names = []
+        for m in FN_RE.finditer(content):
+            open_brace = content.find("{", m.end())
+            if open_brace == -1:
+                continue
+            end = VulnerabilityPattern._find_block_end(content, open_brace)
+            if re.search(rf"\.\s*{field}\b", content[open_brace:end if end != -1 else len(content)]):
+                names.append(m.group(1))
+        return names
+
+    @staticmethod
+    def _screened(name: str, text: str, field: str, functions: list) -> bool:
+        name = re.escape(name)
+        if re.search(rf"\b{name}\s*\.\s*{field}\b", text):
+            return True
+        return any(re.search(rf"\b{re.escape(fn)}\s*\([^;]*\b{name}\b", text) for fn in functions)
+
+    def _finding(self, file_path, content, line, message) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=self.severity,
+            file=file_path,
+            line=line,
+            description=f"{message}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "Reserve vault of attacker's mint holds 400,000 USDC worth of deposits",
+                "after": "Attacker freezes the vault with the mint's freeze authority",
+                "damage": "Withdrawals, liquidations and repayments of the reserve fail until it is thawed.",
+            },
+            impact={
+                "attack_cost": "Creating a mint (a fraction of a SOL)",
+                "exploitability": "Medium — the frozen mint has to attract deposits first",
+                "breach_cost_context": "A frozen vault locks every user's position in the market.",
+            },
+            anchor_versions_affected="All versions (developer-side pattern)",
+            ecosystem_recommendations=[
+                "Require freeze_authority to be None for mints the caller chooses",
+                "Or accept only allowlisted mints stored in the program's config",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Reject mints with a freeze authority, as Marinade's check_freeze_authority does:\n"
+            "  #[account(constraint = collateral_mint.freeze_authority.is_none() @ LendingError::FreezableMint)]\n"
+            "  pub collateral_mint: Account<'info, Mint>,"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Account<'info, Mint> proves only that the account is an SPL mint. "
+            "Its freeze authority can freeze every token account of the mint, "
+            "the program's vaults included, so a mint the caller chooses brings "
+            "whatever control the caller kept over it."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. Attacker creates a mint and keeps its freeze authority\n"
+            "2. Attacker opens a reserve or pool for that mint\n"
+            "3. Users deposit into the market the vault backs\n"
+            "4. Attacker freezes the vault and ransoms the thaw"
+        )
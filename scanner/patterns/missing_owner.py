@@ -8,13 +8,18 @@ program allows attackers to pass fake accounts from programs they control.
 """
 
 import re
+from scanner.patterns.account_types import field_type
 from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+from scanner.patterns.validation import OWNER, validated_accounts
 
 
 class MissingOwnerPattern(VulnerabilityPattern):
     id = "ANCHOR-006"
     name = "Missing Owner Validation"
     severity = "High"
+    category = "accounts"
+    remediation_effort = "mechanical"
     description = (
         "Account used without verifying program ownership. An attacker can "
         "substitute a fake account from an arbitrary program."
@@ -32,6 +37,8 @@ class MissingOwnerPattern(VulnerabilityPattern):
 
     def scan(self, file_path: str, content: str) -> list[Finding]:
         findings = []
+        validated = validated_accounts(content, self.max_depth)
+        facts = Facts(content)
 
         for struct_name, struct_body, struct_start in self._find_derive_accounts_structs(content):
             lines = struct_body.split("\n")
@@ -42,16 +49,13 @@ class MissingOwnerPattern(VulnerabilityPattern):
                     current_attrs.append(stripped)
                     continue
 
-                # Check for AccountInfo or UncheckedAccount
-                ai_match = re.search(r"(\w+)\s*:\s*AccountInfo\s*<", stripped)
-                uc_match = re.search(r"(\w+)\s*:\s*UncheckedAccount\s*<", stripped)
-                match = ai_match or uc_match
-                if not match:
+                # Check for AccountInfo or UncheckedAccount, boxed or optional
+                match = field_type(stripped)
+                if not match or not match[1].is_raw:
                     current_attrs = []
                     continue
 
-                field_name = match.group(1)
-                type_name = "AccountInfo" if ai_match else "UncheckedAccount"
+                field_name, type_name = match[0], match[1].wrapper
                 actual_line = struct_start + i + 1
                 attrs_str = " ".join(current_attrs)
                 current_attrs = []
@@ -74,8 +78,9 @@ class MissingOwnerPattern(VulnerabilityPattern):
                 if re.search(r"///\s*CHECK\s*:", attrs_str):
                     continue
 
-                # Skip if PDA (seeds constraint) — PDA address itself is validation
-                if re.search(r"\bseeds\s*=", attrs_str):
+                # Skip if PDA, of this program or under seeds::program — the derived address is validation
+                account = facts.account(struct_name, field_name)
+                if re.search(r"\bseeds\s*=", attrs_str) or (account and account.pda):
                     continue
 
                 # Skip if address constraint — explicit pubkey validation
@@ -94,6 +99,10 @@ class MissingOwnerPattern(VulnerabilityPattern):
                 if re.search(r"\bconstraint\s*=", attrs_str):
                     continue
 
+                # Skip if a handler checks the owner, itself or through a helper
+                if OWNER in validated.get((struct_name, field_name), ()):
+                    continue
+
                 # UncheckedAccount is a deliberate Anchor choice — lower severity
                 # In modern Anchor, UncheckedAccount requires /// CHECK: documentation.
                 # Its absence is a linting issue, not a security vulnerability.
@@ -0,0 +1,212 @@
+"""
+ANCHOR-049: u64 Multiplication Chain Overflow
+
+Interest, fee and reward formulas multiply three or more quantities in
+u64 before dividing them back down:
+
+    let interest = user.borrowed * pool.interest_rate as u64 * pool.total_borrows;
+
+Each factor fits in u64, the product does not: two full-range u64 factors
+already need 128 bits, so the chain wraps (or panics with overflow-checks)
+long before the balances involved look large.
+
+The rule reports (High) each chain of three or more factors multiplied in
+u64, with at least two factors that are not constants, when the bit widths
+of the factors add up to more than 64. A factor's width comes from its
+declared type — struct fields, parameters and typed `let`s, narrowed by an
+`as` cast (`interest_rate: u16` cast `as u64` counts 16 bits) — or from the
+value of a literal or `const`; anything unknown counts as u64. Chains that
+are widened to u128 / U256 or built with checked_/saturating_/wrapping_mul
+are left out. The fix spells out both the widened and the checked form of
+the flagged chain.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.math_summary import FLOAT_RE, KEYWORDS, _blank
+
+WIDTHS = {"u8": 8, "u16": 16, "u32": 32, "u64": 64, "usize": 64,
+          "i8": 7, "i16": 15, "i32": 31, "i64": 63, "isize": 63}
+WIDE_RE = re.compile(r"\b(?:u128|i128|U128|U192|U256|I256)\b|\b(?:checked|saturating|wrapping|overflowing)_mul\b")
+TYPED_RE = re.compile(r"(?:^|[,({]|\bpub(?:\([^)]*\))?)\s*(?:mut\s+)?(\w+)\s*:\s*([iu](?:8|16|32|64|128|size))\b", re.M)
+LET_TYPED_RE = re.compile(r"\blet\s+(?:mut\s+)?(\w+)\s*:\s*([iu](?:8|16|32|64|128|size))\b")
+CONST_RE = re.compile(r"\bconst\s+(\w+)\s*:\s*([iu](?:8|16|32|64|128|size))\s*=\s*([\d_]+)(?:[iu]\d+)?\s*;")
+VALUE = r"(?:[A-Za-z_]\w*(?:\s*::\s*\w+)*(?:\s*\.\s*\w+(?:\(\))?)*|\d[\d_]*(?:[iu]\d+|usize)?)"
+FACTOR = rf"(?:\(\s*{VALUE}(?:\s+as\s+\w+)?\s*\)|{VALUE}(?:\s+as\s+\w+)?)"
+CHAIN_RE = re.compile(rf"(?<![\w.*)\]])\s*({FACTOR}(?:\s*\*(?!=)\s*{FACTOR}){{2,}})(?![\w.(])")
+CAST_STRIP_RE = re.compile(r"^\(\s*|\s+as\s+\w+|\s*\)$")
+
+
+def integer_widths(code: str) -> dict:
+    """Bit width of every integer struct field, the widest when a name repeats."""
+    widths = {}
+    for m in re.finditer(r"\bstruct\s+\w+\s*(?:<[^>]*>)?\s*\{([^{}]*)\}", code):
+        for name, ty in TYPED_RE.findall(m.group(1)):
+            widths[name] = max(widths.get(name, 0), WIDTHS.get(ty, 128))
+    return widths
+
+
+def const_values(code: str) -> dict:
+    """Value of every integer `const` with a literal initializer."""
+    return {m.group(1): int(m.group(3).replace("_", "")) for m in CONST_RE.finditer(code)}
+
+
+def local_widths(params: str, body: str, fields: dict) -> dict:
+    """Field widths overlaid with a function's typed parameters and `let`s."""
+    types = dict(fields)
+    types.update({n: WIDTHS.get(t, 128) for n, t in TYPED_RE.findall(params)})
+    types.update({n: WIDTHS.get(t, 128) for n, t in LET_TYPED_RE.findall(body)})
+    return types
+
+
+def last_segment(value: str) -> str:
+    return re.split(r"\s*(?:\.|::)\s*", value.replace("()", ""))[-1]
+
+
+def is_constant(factor: str) -> bool:
+    """A literal or SCREAMING_CASE constant, cast or not."""
+    value = CAST_STRIP_RE.sub("", factor)
+    return bool(re.fullmatch(r"\d[\d_]*\w*", value) or re.fullmatch(r"[A-Z][A-Z0-9_]*", last_segment(value)))
+
+
+def factor_bits(factor: str, types: dict, consts: dict) -> int:
+    """Bits a factor can occupy: the narrower of its own type and any cast; unknown counts as u64."""
+    cast = re.search(r"\s+as\s+(\w+)", factor)
+    value = CAST_STRIP_RE.sub("", factor)
+    literal = re.fullmatch(r"(\d[\d_]*)(?:[iu]\d+|usize)?", value)
+    if literal:
+        bits = max(int(literal.group(1).replace("_", "")).bit_length(), 1)
+    elif last_segment(value) in consts:
+        bits = max(consts[last_segment(value)].bit_length(), 1)
+    else:
+        bits = types.get(last_segment(value)) or 64
+    if cast and cast.group(1) in WIDTHS:
+        bits = min(bits, WIDTHS[cast.group(1)])
+    return min(bits, 64)
+
+
+class MulChainPattern(VulnerabilityPattern):
+    id = "ANCHOR-049"
+    name = "u64 Multiplication Chain Overflow"
+    severity = "High"
+    category = "arithmetic"
+    remediation_effort = "mechanical"
+    description = (
+        "Three or more quantities are multiplied in u64 whose bit widths add up "
+        "past 64, so the product overflows before any division brings it back."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        code = _blank(content)
+        fields, consts = integer_widths(code), const_values(code)
+        for fn in self._find_functions(content):
+            body = code[fn["body_start"]:fn["body_start"] + len(fn["body"])]
+            types = local_widths(fn["params"], body, fields)
+            for m in re.finditer(r"[^;{}]+", body):
+                stmt = m.group(0)
+                if WIDE_RE.search(stmt) or FLOAT_RE.search(stmt):
+                    continue
+                for chain in CHAIN_RE.finditer(stmt):
+                    factors = [f.strip() for f in re.split(r"\s*\*\s*", chain.group(1))]
+                    if factors[0].split(".")[0].strip() in KEYWORDS:
+                        continue
+                    bits = [factor_bits(f, types, consts) for f in factors]
+                    variable = [f for f in factors if not is_constant(f)]
+                    if len(variable) < 2 or sum(bits) <= 64:
+                        continue
+                    line = self._get_line_number(content, fn["body_start"] + m.start() + chain.start(1))
+                    text = " * ".join(self._text(f) for f in factors)
+                    widths = ", ".join(self._describe(f, b, consts) for f, b in zip(factors, bits))
+                    findings.append(self._finding(
+                        file_path, content, line,
+                        f"In {fn['name']}: `{text}` multiplies {len(factors)} factors in u64 ({widths}) "
+                        f"whose product can reach 2^{sum(bits)}, past u64::MAX (2^64); widen to u128 "
+                        f"and narrow the result with u64::try_from, or use checked_mul",
+                        self._fix(stmt, factors),
+                    ))
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    @staticmethod
+    def _text(factor: str) -> str:
+        return re.sub(r"\s*\.\s*", ".", " ".join(factor.split()))
+
+    def _describe(self, factor: str, bits: int, consts: dict) -> str:
+        value = CAST_STRIP_RE.sub("", factor)
+        if last_segment(value) in consts:
+            return f"{self._text(value)} = {consts[last_segment(value)]}: {bits} bits"
+        if re.fullmatch(r"\d[\d_]*\w*", value):
+            return f"{value}: {bits} bits"
+        return f"{self._text(value)}: {bits} bits"
+
+    def _fix(self, stmt: str, factors: list) -> str:
+        plain = [self._text(CAST_STRIP_RE.sub("", f)) for f in factors]
+        target = re.match(r"\s*let\s+(?:mut\s+)?(\w+)", stmt)
+        bind = f"let {target.group(1)} = " if target else ""
+        widened = " * ".join(f"({p} as u128)" for p in plain)
+        checked = self._text(factors[0]) + "".join(
+            f"\n    .checked_mul({self._text(f)})" if i == 0 else f"\n    .and_then(|v| v.checked_mul({self._text(f)}))"
+            for i, f in enumerate(factors[1:])
+        )
+        return (
+            "Widen the product to u128 and narrow once at the end:\n"
+            f"  {bind}u64::try_from({widened})\n"
+            "      .map_err(|_| ErrorCode::MathOverflow)?;\n"
+            "or fail on overflow with checked_mul:\n"
+            f"  {bind}{checked}\n"
+            "    .ok_or(ErrorCode::MathOverflow)?;"
+        ).replace("\n    .", "\n      .")
+
+    def _finding(self, file_path, content, line, message, fix) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=self.severity,
+            file=file_path,
+            line=line,
+            description=f"{message}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=fix,
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "borrowed = 5,000 SOL, interest_rate = 500 bps, total_borrows = 200,000 SOL",
+                "after": "borrowed * interest_rate * total_borrows = 5e29, wrapped modulo 2^64 to ~1.6e19",
+                "damage": "The wrapped interest is arbitrary: a position reads as healthy, or liquidation panics.",
+            },
+            impact={
+                "attack_cost": "Balances large enough to push the product past 2^64",
+                "exploitability": "High — the overflow point follows from public pool state",
+                "breach_cost_context": "Wrapped interest and reward products have mispriced Solana lending positions.",
+            },
+            anchor_versions_affected="All versions (program-side arithmetic)",
+            ecosystem_recommendations=[
+                "Compute products of quantities in u128 and convert back with u64::try_from",
+                "Keep overflow-checks = true in [profile.release] as a backstop",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Widen the product to u128 and narrow once at the end:\n"
+            "  let interest = u64::try_from((borrowed as u128) * (rate as u128) * (total as u128))\n"
+            "      .map_err(|_| ErrorCode::MathOverflow)?;"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "The product of n u64 values needs up to 64·n bits. Multiplying "
+            "three quantities in u64 overflows as soon as their bit widths add "
+            "up past 64, whatever a later division would bring the result "
+            "back to."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. liquidate computes interest = borrowed * interest_rate * total_borrows in u64\n"
+            "2. Pool borrows grow until the product passes 2^64\n"
+            "3. The product wraps to a small number and the position reads as healthy\n"
+            "4. Underwater positions can no longer be liquidated and the pool absorbs the bad debt"
+        )
@@ -0,0 +1,76 @@
+"""
+NFT accounts of an accounts struct, shared by the NFT rules.
+
+NFT programs (staking, escrow, marketplaces) take the same group of
+accounts everywhere: the NFT's mint, its Metaplex metadata and edition
+PDAs, the owner's token account and a custody account the program holds it
+in. ANCHOR-036 to ANCHOR-038 (`shield::nft::*`) check that group; this
+module answers which accounts of a struct play which part:
+
+  - nft_mints(): Mint accounts holding an NFT — named `nft`, constrained
+    to `mint::decimals = 0`, or next to metadata or edition accounts — that
+    one of the struct's token accounts or metadata PDAs is for. Mints the
+    instruction creates are the program's own
+  - metaplex_accounts(): metadata and edition accounts, by type
+    (`MetadataAccount`, `MasterEditionAccount`) or, for unchecked
+    accounts, by name
+  - checks(): the struct's constraint expressions and the bodies of its
+    handlers and impl blocks, for rules that accept a check in either
+"""
+
+import re
+
+from scanner.patterns.base import VulnerabilityPattern
+
+MINT_TYPE_RE = re.compile(r"\b(?:Account|InterfaceAccount)\s*<\s*'[^,]+,\s*(?:\w+::)*Mint\s*>")
+TOKEN_ACCOUNT_TYPE_RE = re.compile(r"\b(?:Account|InterfaceAccount)\s*<\s*'[^,]+,\s*(?:\w+::)*TokenAccount\s*>")
+METADATA_TYPES = {"MetadataAccount": "metadata", "Metadata": "metadata",
+                  "MasterEditionAccount": "edition", "MasterEdition": "edition", "EditionAccount": "edition"}
+UNTYPED_RE = re.compile(r"\b(?:UncheckedAccount|AccountInfo)\b")
+# The Token Metadata program's id as programs write it
+METADATA_PROGRAM_RE = re.compile(
+    r"\b(?:Metadata|MetadataProgram|TokenMetadata|mpl_token_metadata)\s*::\s*(?:id\s*\(\s*\)|ID\b)|"
+    r"\bmpl_token_metadata\s*::\s*programs?\s*::\s*\w+|\b\w*METADATA_PROGRAM_ID\b"
+)
+
+
+def nft_mints(struct) -> list:
+    """Mint accounts of the struct that hold an NFT, other than mints the instruction creates.
+
+    The mint has to be the mint of one of the struct's token accounts or of
+    its metadata: a collection mint only stored as an identifier is not held.
+    """
+    mints = [a for a in struct.accounts if MINT_TYPE_RE.search(a.type) and not a.has("init")]
+    derived = " ".join(a.pda.seeds for a, _ in metaplex_accounts(struct) if a.pda)
+    held = {c.value.split("@")[0].strip() for a in struct.accounts for c in a.constraints
+            if c.kind in ("token::mint", "associated_token::mint")}
+    return [m for m in mints
+            if (m.name in held or re.search(rf"\b{re.escape(m.name)}\b", derived))
+            and (re.search(r"(?i)nft", m.name) or decimals_zero_constraint(m) or metaplex_accounts(struct))]
+
+
+def metaplex_accounts(struct) -> list:
+    """[(account, "metadata" | "edition")] of the struct."""
+    result = []
+    for account in struct.accounts:
+        if account.wrapper == "Program" or re.search(r"(?i)program", account.name):
+            continue
+        kind = METADATA_TYPES.get(account.inner_type)
+        if not kind and UNTYPED_RE.search(account.type):
+            kind = "edition" if re.search(r"(?i)edition", account.name) else \
+                "metadata" if re.search(r"(?i)metadata", account.name) else None
+        if kind:
+            result.append((account, kind))
+    return result
+
+
+def decimals_zero_constraint(mint) -> bool:
+    return any(c.kind == "mint::decimals" and c.value.split("@")[0].strip() == "0" for c in mint.constraints)
+
+
+def checks(struct, facts, content: str) -> str:
+    """Constraint expressions of the struct and its handler and impl bodies, as one text."""
+    parts = [c.value for a in struct.accounts for c in a.constraints if c.kind == "constraint"]
+    parts += [ix.body for ix in facts.instructions() if ix.accounts_struct == struct.name]
+    parts += [content[s:e] for s, e, t in VulnerabilityPattern._find_impl_blocks(content) if t == struct.name]
+    return "\n".join(parts)
@@ -0,0 +1,168 @@
+"""
+ANCHOR-038: NFT Custody Not Program-Owned
+
+Staking and escrow programs take the NFT out of the user's wallet into a
+custody token account only the program can move it out of: a token account
+whose owner is a PDA of the program.
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = nft_mint,
+        associated_token::authority = nft_authority,    // PDA [b"nft-authority", stake_details]
+    )]
+    pub nft_custody: Account<'info, TokenAccount>,
+
+For every token account of an NFT mint (see nft.py) that receives a
+transfer signed by one of the struct's signers — the NFT going into
+custody — the rule checks the account's `token::authority` /
+`associated_token::authority`. It reports:
+
+  - an authority that is a signer (High): the NFT stays in an account the
+    user controls and can be moved out while staked or listed
+  - no authority constraint and no `seeds` or `address` (High): the caller
+    passes a token account it owns as custody
+  - an authority that is not a PDA of this program (Medium): the program
+    cannot sign for it, so the NFT either belongs to someone else or is
+    stuck
+
+Unpinned fee and treasury token accounts in general are ANCHOR-035's.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+from scanner.patterns.nft import TOKEN_ACCOUNT_TYPE_RE, checks, nft_mints
+
+TRANSFER_RE = re.compile(r"\bTransfer(?:Checked)?\s*\{")
+FIELD_RE = r"\b{}\s*:\s*(?:ctx\s*\.\s*accounts\s*\.\s*|self\s*\.\s*|&\s*)?(\w+)"
+
+
+class NftCustodyPattern(VulnerabilityPattern):
+    id = "ANCHOR-038"
+    name = "NFT Custody Not Program-Owned"
+    severity = "High"
+    category = "nft"
+    remediation_effort = "localized"
+    description = (
+        "A token account NFTs are transferred into for custody is owned by "
+        "the user, by no one in particular, or by an account that is not a "
+        "PDA of the program."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        facts = Facts(content)
+        for struct in facts.accounts_structs():
+            mints = {m.name for m in nft_mints(struct)}
+            if not mints:
+                continue
+            accounts = {a.name: a for a in struct.accounts}
+            for custody in self._custodies(checks(struct, facts, content), accounts, mints):
+                problem = self._problem(custody, accounts)
+                if not problem:
+                    continue
+                severity, text = problem
+                findings.append(self._finding(
+                    file_path, content, custody.line, severity,
+                    f"In struct {struct.name}: NFT custody '{custody.name}' {text}",
+                ))
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    @staticmethod
+    def _custodies(text: str, accounts: dict, mints: set) -> list:
+        """NFT token accounts receiving a transfer one of the struct's signers authorizes."""
+        result = []
+        for m in TRANSFER_RE.finditer(text):
+            end = VulnerabilityPattern._find_block_end(text, m.end() - 1)
+            block = text[m.end():end if end != -1 else len(text)]
+            to = re.search(FIELD_RE.format("to"), block)
+            authority = re.search(FIELD_RE.format("authority"), block)
+            if not (to and authority) or to.group(1) not in accounts:
+                continue
+            signer = accounts.get(authority.group(1))
+            custody = accounts[to.group(1)]
+            mint = next((c.value for c in custody.constraints if c.kind in ("token::mint", "associated_token::mint")), "")
+            if signer and signer.is_signer and TOKEN_ACCOUNT_TYPE_RE.search(custody.type) \
+                    and mint in mints and custody not in result:
+                result.append(custody)
+        return result
+
+    @staticmethod
+    def _problem(custody, accounts: dict):
+        """(severity, text) when the custody is not owned by a PDA of the program, else None."""
+        owner = next((c.value for c in custody.constraints
+                      if c.kind in ("token::authority", "associated_token::authority")), "")
+        if not owner:
+            if custody.pda or custody.has("address"):
+                return None
+            return "High", ("has no token::authority or associated_token::authority, seeds or address, so the "
+                            "caller passes a token account it owns and the NFT never leaves its control")
+        authority = accounts.get(owner.split("@")[0].strip())
+        if authority is None:
+            return None
+        if authority.is_signer:
+            return "High", (f"is owned by the signer '{authority.name}', not by the program, so the NFT can be "
+                            f"moved out of custody while the program counts it as held")
+        if authority.pda and not authority.pda.is_foreign:
+            return None
+        return "Medium", (f"is owned by '{authority.name}', which is not a PDA of this program, so the program "
+                          f"cannot sign to release the NFT")
+
+    def _finding(self, file_path, content, line, severity, message) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=severity,
+            file=file_path,
+            line=line,
+            description=f"{message}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "User stakes an NFT; the program records it as held",
+                "after": "The NFT sits in an account the user still controls",
+                "damage": "The NFT is sold or re-staked elsewhere while rewards keep accruing.",
+            },
+            impact={
+                "attack_cost": "Transaction fees only",
+                "exploitability": "High — the user already signs for the custody account",
+                "breach_cost_context": "Custody owned by the depositor defeats staking and escrow entirely.",
+            },
+            anchor_versions_affected="All versions (developer-side pattern)",
+            ecosystem_recommendations=[
+                "Make a PDA of the program the custody account's authority",
+                "Derive the custody address (ATA of the PDA, or seeds) so callers cannot substitute it",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Hold the NFT in an account owned by a program PDA:\n"
+            "  #[account(seeds = [b\"nft-authority\", stake_details.key().as_ref()], bump)]\n"
+            "  pub nft_authority: UncheckedAccount<'info>,\n"
+            "  #[account(init_if_needed, payer = signer,\n"
+            "            associated_token::mint = nft_mint,\n"
+            "            associated_token::authority = nft_authority)]\n"
+            "  pub nft_custody: Account<'info, TokenAccount>,"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Moving a token into an account only changes who holds it if the "
+            "account's owner changes. Custody means the program, through a PDA "
+            "it signs with, is the owner; any other owner keeps the NFT movable "
+            "outside the program's rules."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. The stake instruction transfers the NFT to nft_custody\n"
+            "2. nft_custody's authority is the staker\n"
+            "3. Staker transfers the NFT out and sells it\n"
+            "4. The program keeps paying staking rewards for an NFT it no longer holds"
+        )
@@ -0,0 +1,189 @@
+"""
+ANCHOR-037: Unverified NFT Metadata
+
+Token Metadata accounts are PDAs of the Metaplex program:
+`[b"metadata", Metadata::id(), mint]`, and `[.., b"edition"]` for the
+master edition. A program reading one has to derive it from the NFT's mint
+under that program, and a collection read from it only means something
+once Metaplex has verified it:
+
+    #[account(
+        seeds = [b"metadata", Metadata::id().as_ref(), nft_mint.key().as_ref()],
+        seeds::program = Metadata::id(),
+        bump,
+        constraint = nft_metadata.collection.as_ref().unwrap().verified @ StakeError::CollectionNotVerified,
+        constraint = nft_metadata.collection.as_ref().unwrap().key == stake_details.collection @ StakeError::InvalidCollection
+    )]
+    nft_metadata: Box<Account<'info, MetadataAccount>>,
+
+For every metadata and edition account of an accounts struct (see nft.py)
+that the program reads — a deserialized type, or an unchecked account
+deserialized in a handler — the rule reports:
+
+  - no derivation (High): no `seeds`, `address`, or check of its `mint`
+    against the NFT's mint, so the metadata of any other NFT is accepted
+  - seeds that do not include a mint (High): one metadata account for
+    every NFT
+  - `seeds::program` taken from an account nothing pins to the Token
+    Metadata program (High): the caller derives under its own program
+  - seeds without `seeds::program` (Medium): the address is derived under
+    this program and never matches the real metadata account
+  - a collection compared by key without its `verified` flag (High):
+    anyone can mint an NFT whose metadata names the collection
+
+Unchecked metadata accounts only handed to Metaplex CPIs are validated by
+Metaplex and left alone.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+from scanner.patterns.nft import METADATA_PROGRAM_RE, MINT_TYPE_RE, checks, metaplex_accounts
+
+DESERIALIZE_RE = r"\b(?:from_account_info|safe_deserialize|try_deserialize\w*|deserialize|from_bytes)\s*\("
+
+
+class NftMetadataPattern(VulnerabilityPattern):
+    id = "ANCHOR-037"
+    name = "Unverified NFT Metadata"
+    severity = "High"
+    category = "nft"
+    remediation_effort = "mechanical"
+    description = (
+        "An NFT's metadata or edition account is not derived from its mint "
+        "under the Token Metadata program, or its collection is trusted "
+        "without the verified flag."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        facts = Facts(content)
+        for struct in facts.accounts_structs():
+            accounts = metaplex_accounts(struct)
+            if not accounts:
+                continue
+            text = checks(struct, facts, content)
+            mints = [a.name for a in struct.accounts if MINT_TYPE_RE.search(a.type) or re.search(r"mint", a.name)]
+            for account, kind in accounts:
+                names = self._names(account.name, text)
+                if not account.inner_type and not re.search(
+                        rf"\b(?:{'|'.join(names)})\b[^;]*{DESERIALIZE_RE}|{DESERIALIZE_RE}[^;]*\b(?:{'|'.join(names)})\b",
+                        text):
+                    continue
+                issues = self._derivation(account, struct, mints, text, names)
+                if kind == "metadata":
+                    issues += self._collection(names, text)
+                if not issues:
+                    continue
+                severity = "High" if any(high for high, _ in issues) else "Medium"
+                findings.append(self._finding(
+                    file_path, content, account.line, severity,
+                    f"In struct {struct.name}: {kind} account '{account.name}' "
+                    + "; ".join(text for _, text in issues),
+                ))
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    @staticmethod
+    def _names(name: str, text: str) -> list:
+        """The account's name and the bindings of it in handler bodies."""
+        names = [re.escape(name)]
+        names += [re.escape(m.group(1)) for m in re.finditer(
+            rf"\blet\s+(?:mut\s+)?(\w+)\s*(?::[^=]+)?=\s*&?\s*(?:mut\s+)?(?:ctx\s*\.\s*accounts|self)\s*\.\s*{re.escape(name)}\b",
+            text)]
+        return names
+
+    def _derivation(self, account, struct, mints: list, text: str, names: list) -> list:
+        """[(high, text)] about how the account's address is checked."""
+        pda = account.pda
+        if pda is None:
+            if account.has("address"):
+                return []
+            bound = rf"\b(?:{'|'.join(names)})\s*\.\s*(?:mint\b|key\b)[^;]*(?:==|!=)|\brequire_keys_(?:eq|neq)![^;]*\b(?:{'|'.join(names)})\s*\.\s*(?:mint|key)\b"
+            if re.search(bound, text):
+                return []
+            return [(True, "is not derived from the NFT's mint (no seeds, address or mint check), so the "
+                           "metadata of any other NFT is accepted")]
+        issues = []
+        if not any(re.search(rf"\b{re.escape(m)}\b", pda.seeds) for m in mints):
+            issues.append((True, "is derived from seeds that do not include a mint, so it is not tied to the NFT"))
+        if not pda.is_foreign:
+            issues.append((False, "has seeds but no seeds::program = Metadata::id(), so the address is derived "
+                                  "under this program and never matches the Token Metadata account"))
+        elif not METADATA_PROGRAM_RE.search(pda.program):
+            source = re.match(r"\s*(\w+)", pda.program).group(1)
+            program = next((a for a in struct.accounts if a.name == source), None)
+            pinned = program and (program.wrapper == "Program" or program.has("address"))
+            if not pinned:
+                issues.append((True, f"is derived under seeds::program = {pda.program}, which nothing pins to "
+                                     f"the Token Metadata program, so the caller chooses the derivation"))
+        return issues
+
+    @staticmethod
+    def _collection(names: list, text: str) -> list:
+        alternation = "|".join(names)
+        if not re.search(rf"\b(?:{alternation})\s*\.\s*collection\b", text):
+            return []
+        if re.search(rf"\b(?:{alternation})\b[^;]*\.\s*verified\b", text):
+            return []
+        return [(True, "has its collection compared without checking collection.verified, so an NFT whose "
+                       "metadata merely names the collection passes")]
+
+    def _finding(self, file_path, content, line, severity, message) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=severity,
+            file=file_path,
+            line=line,
+            description=f"{message}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "Staking accepts only NFTs of the verified collection",
+                "after": "Attacker stakes its own mint with metadata naming that collection",
+                "damage": "Rewards and privileges of the collection go to worthless NFTs.",
+            },
+            impact={
+                "attack_cost": "Minting an NFT (a fraction of a SOL)",
+                "exploitability": "High — creating metadata that names any collection is permissionless",
+                "breach_cost_context": "Unverified collections are the classic NFT staking and marketplace bug.",
+            },
+            anchor_versions_affected="All versions (developer-side pattern)",
+            ecosystem_recommendations=[
+                "Derive metadata with seeds = [b\"metadata\", Metadata::id(), mint] and seeds::program = Metadata::id()",
+                "Check collection.verified before trusting collection.key",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Derive the account from the mint under Token Metadata and check verification:\n"
+            "  #[account(\n"
+            "      seeds = [b\"metadata\", Metadata::id().as_ref(), nft_mint.key().as_ref()],\n"
+            "      seeds::program = Metadata::id(),\n"
+            "      bump,\n"
+            "      constraint = nft_metadata.collection.as_ref().unwrap().verified,\n"
+            "      constraint = nft_metadata.collection.as_ref().unwrap().key == config.collection,\n"
+            "  )]\n"
+            "  pub nft_metadata: Box<Account<'info, MetadataAccount>>,"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Owner checks only prove an account was written by Token Metadata. "
+            "Which NFT it describes comes from its address, and anyone can "
+            "create metadata naming any collection; only the verified flag, set "
+            "by the collection authority, makes the claim trustworthy."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. The staking program gates on the metadata's collection key\n"
+            "2. Attacker mints an NFT whose metadata names the collection, unverified\n"
+            "3. The program reads collection.key without checking verified\n"
+            "4. The attacker's NFT earns the collection's rewards"
+        )
@@ -0,0 +1,143 @@
+"""
+ANCHOR-036: NFT Mint Not Checked As NFT
+
+An NFT is a mint with zero decimals and a supply of one. A program that
+stakes, escrows or trades NFTs and takes the mint as a plain
+`Account<'info, Mint>` accepts any token:
+
+    #[derive(Accounts)]
+    pub struct Stake<'info> {
+        pub nft_mint: Account<'info, Mint>,          // no decimals / supply check
+        #[account(mut, associated_token::mint = nft_mint, associated_token::authority = staker)]
+        pub nft_token: Account<'info, TokenAccount>,
+        ..
+    }
+
+The expected form (nft-staking's `Stake`):
+
+    #[account(
+        mint::decimals = 0,
+        constraint = nft_mint.supply == 1 @ StakeError::TokenNotNFT
+    )]
+    nft_mint: Account<'info, Mint>,
+
+For every NFT mint of an accounts struct (see nft.py) the rule looks for
+`mint::decimals = 0` or a `decimals == 0` check, and a `supply == 1` check,
+in the struct's constraints or the bodies of its handlers. A master
+edition account derived from the mint under the Token Metadata program
+proves both and is accepted instead. It reports:
+
+  - neither check (High): a fungible token, or a semi-fungible edition
+    with many copies, is staked as if it were the unique NFT, and one
+    collection's rewards are claimed per copy
+  - only one of them (Medium)
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+from scanner.patterns.nft import METADATA_PROGRAM_RE, checks, decimals_zero_constraint, metaplex_accounts, nft_mints
+
+
+class NftMintPattern(VulnerabilityPattern):
+    id = "ANCHOR-036"
+    name = "NFT Mint Not Checked As NFT"
+    severity = "High"
+    category = "nft"
+    remediation_effort = "mechanical"
+    description = (
+        "An NFT program accepts a mint without checking zero decimals and a "
+        "supply of one, so fungible tokens and multi-copy editions pass as NFTs."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        facts = Facts(content)
+        for struct in facts.accounts_structs():
+            mints = nft_mints(struct)
+            if not mints:
+                continue
+            text = checks(struct, facts, content)
+            for mint in mints:
+                if self._has_edition(struct, mint):
+                    continue
+                name = re.escape(mint.name)
+                decimals = decimals_zero_constraint(mint) or \
+                    re.search(rf"\b{name}\s*\.\s*decimals\s*==\s*0\b", text)
+                supply = re.search(rf"\b{name}\s*\.\s*supply\s*==\s*1\b", text)
+                missing = [check for check, present in (("decimals = 0", decimals), ("supply == 1", supply))
+                           if not present]
+                if not missing:
+                    continue
+                findings.append(self._finding(
+                    file_path, content, mint.line, "High" if len(missing) == 2 else "Medium",
+                    f"In struct {struct.name}: NFT mint '{mint.name}' is never checked for "
+                    f"{' or '.join(missing)}, so a token that is not a unique NFT is accepted",
+                ))
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    @staticmethod
+    def _has_edition(struct, mint) -> bool:
+        """A deserialized master edition derived from the mint under Token Metadata."""
+        for account, kind in metaplex_accounts(struct):
+            pda = account.pda
+            if kind == "edition" and account.inner_type and pda and METADATA_PROGRAM_RE.search(pda.program) \
+                    and re.search(rf"\b{re.escape(mint.name)}\b", pda.seeds):
+                return True
+        return False
+
+    def _finding(self, file_path, content, line, severity, message) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=severity,
+            file=file_path,
+            line=line,
+            description=f"{message}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "Staking pool pays 10 reward tokens per staked NFT per day",
+                "after": "Attacker stakes 1,000 copies of a print edition one by one",
+                "damage": "Rewards, voting power or loans sized for unique NFTs paid per copy.",
+            },
+            impact={
+                "attack_cost": "Cost of minting the copies",
+                "exploitability": "Medium — needs a mint that also passes the metadata checks",
+                "breach_cost_context": "NFT staking and lending programs price one reward or loan per NFT.",
+            },
+            anchor_versions_affected="All versions (developer-side pattern)",
+            ecosystem_recommendations=[
+                "Constrain NFT mints with mint::decimals = 0 and supply == 1",
+                "Or require the master edition account derived from the mint",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Check both properties on the mint:\n"
+            "  #[account(\n"
+            "      mint::decimals = 0,\n"
+            "      constraint = nft_mint.supply == 1 @ StakeError::TokenNotNFT\n"
+            "  )]\n"
+            "  pub nft_mint: Account<'info, Mint>,"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Account<'info, Mint> proves only that the account is an SPL mint. "
+            "Whether it is an NFT — zero decimals, one token in existence — is a "
+            "property of its data the program has to check."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. The staking program checks the NFT's collection but not its supply\n"
+            "2. The collection also holds a semi-fungible asset with a supply of 1,000\n"
+            "3. Each unit is staked as a separate NFT\n"
+            "4. Rewards meant for one NFT are paid a thousand times"
+        )
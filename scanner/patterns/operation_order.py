@@ -0,0 +1,252 @@
+"""
+ANCHOR-050: Operation Order Precision Loss
+
+Integer formulas lose value in one of two directions depending on the
+order of `*` and `/`:
+
+    let per_share = rewards / total_shares;        // truncated here...
+    let owed = per_share * user.shares;            // ...and scaled up after
+
+    let fee = amount * fee_bps / BPS_DENOMINATOR;  // amount * fee_bps can pass u64::MAX
+
+Dividing first throws away up to `multiplier - 1` units per call, which
+adds up over every claim; multiplying first in u64 keeps the precision but
+the intermediate product overflows for large inputs. Raydium's full_math
+does both in one step, widened:
+
+    U128::from(amount).mul_div_floor(U128::from(fee_bps), U128::from(BPS_DENOMINATOR))
+
+The rule reports (Medium):
+
+  - divide-before-multiply: `a / b * c`, `.checked_div(b)…checked_mul(c)`,
+    and a local bound to a quotient that is multiplied later in the same
+    function; `x / LOT * LOT` (rounding to a multiple) is left out
+  - multiply-before-divide without widening: `a * b / c` whose factors'
+    bit widths (as in ANCHOR-049) add up past 64, in a statement with no
+    u128/U256 and no checked_/saturating_ multiplication
+
+The fix is a mul_div rewrite of the flagged expression: mul_div_ceil when
+the result is charged to the user (fee, debt, amount in, ...), otherwise
+mul_div_floor.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.math_summary import FLOAT_RE, KEYWORDS, _blank
+from scanner.patterns.mul_chain import (
+    CAST_STRIP_RE, FACTOR, WIDE_RE, const_values, factor_bits, integer_widths, is_constant, last_segment, local_widths,
+)
+
+DIV_MUL_RE = re.compile(rf"(?<![\w.)\]])(\(\s*)?({FACTOR})\s*/(?!=)\s*({FACTOR})\s*(\))?\s*\*(?!=)\s*({FACTOR})(?![\w.(])")
+MUL_DIV_RE = re.compile(rf"(?<![\w.)\]])(\(\s*)?({FACTOR})\s*\*(?!=)\s*({FACTOR})\s*(\))?\s*/(?!=)\s*({FACTOR})(?![\w.(])")
+# Unwrapping between a checked division and the multiplication that follows it
+UNWRAP = r"(?:\s*\?|\s*\.\s*unwrap\s*\(\s*\)|\s*\.\s*expect\s*\([^)]*\)|\s*\.\s*ok_or\w*\s*\([^()]*(?:\([^()]*\))?[^()]*\)\s*\?)*"
+METHOD_DIV_MUL_RE = re.compile(
+    rf"\.\s*(?:checked_|saturating_)?div\s*\(\s*([^()]*(?:\([^()]*\))?[^()]*)\){UNWRAP}"
+    r"\s*\.\s*(?:checked_|saturating_|wrapping_)?mul\s*\(\s*([^()]*(?:\([^()]*\))?[^()]*)\)"
+)
+LET_RE = re.compile(r"^\s*let\s+(?:mut\s+)?(\w+)\s*(?::\s*[\w<>]+)?\s*=(?!=)\s*(.+)$", re.S)
+TRAILER_RE = re.compile(rf"(?:{UNWRAP}|\s+as\s+\w+)*\s*$")
+CHARGED_RE = re.compile(r"fee|debt|cost|price|required|repay|amount_in|premium|penalty", re.I)
+MULDIV_RE = re.compile(r"\bMulDiv\b|\bfull_math\b|\bfn\s+mul_div_(?:floor|ceil)\b")
+
+
+class OperationOrderPattern(VulnerabilityPattern):
+    id = "ANCHOR-050"
+    name = "Operation Order Precision Loss"
+    severity = "Medium"
+    category = "arithmetic"
+    remediation_effort = "mechanical"
+    description = (
+        "Integer math divides before multiplying (truncating the quotient before "
+        "scaling it) or multiplies before dividing in u64 without widening."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        code = _blank(content)
+        fields, consts = integer_widths(code), const_values(code)
+        local = MULDIV_RE.search(code) or self.dependencies and \
+            any(MULDIV_RE.search(source) for _, source in self.dependencies.sources)
+        helper = "this program's MulDiv (full_math)" if local else \
+            "a full_math-style MulDiv helper (raydium-clmm libraries::full_math)"
+        for fn in self._find_functions(content):
+            body = code[fn["body_start"]:fn["body_start"] + len(fn["body"])]
+            types = local_widths(fn["params"], body, fields)
+            quotients = {}
+            for m in re.finditer(r"[^;{}]+", body):
+                stmt = m.group(0)
+                if FLOAT_RE.search(stmt):
+                    continue
+                at = fn["body_start"] + m.start()
+                target = self._target(stmt)
+                hit = self._divide_first(stmt, quotients)
+                if hit:
+                    offset, dividend, divisor, multiplier, where = hit
+                    findings.append(self._finding(
+                        file_path, content, self._get_line_number(content, at + offset),
+                        f"In {fn['name']}: {where} divides by `{divisor}` before multiplying by "
+                        f"`{multiplier}`, so up to `{multiplier}` - 1 units of the result are truncated away; "
+                        f"multiply first",
+                        self._fix(dividend, multiplier, divisor, target, helper),
+                    ))
+                hit = self._multiply_first(stmt, types, consts)
+                if hit:
+                    offset, left, right, divisor, bits = hit
+                    findings.append(self._finding(
+                        file_path, content, self._get_line_number(content, at + offset),
+                        f"In {fn['name']}: `{left} * {right}` is computed in u64 before dividing by "
+                        f"`{divisor}`; the product can reach 2^{bits} and overflow while the quotient "
+                        f"would fit",
+                        self._fix(left, right, divisor, target, helper),
+                    ))
+                let = LET_RE.match(stmt)
+                if let:
+                    quotient = self._quotient(let.group(2))
+                    if quotient:
+                        quotients[let.group(1)] = (*quotient, self._get_line_number(content, at + stmt.find("let")))
+                    else:
+                        quotients.pop(let.group(1), None)
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    @staticmethod
+    def _text(value: str) -> str:
+        return re.sub(r"\s*\.\s*", ".", " ".join(value.split()))
+
+    @staticmethod
+    def _target(stmt: str) -> str:
+        m = re.match(r"\s*(?:let\s+(?:mut\s+)?(\w+)|([\w.]+)\s*[-+*/]?=(?!=))", stmt)
+        return (m.group(1) or m.group(2)) if m else ""
+
+    def _quotient(self, expr: str):
+        """(dividend, divisor) when the expression's last operation is a division."""
+        expr = TRAILER_RE.sub("", expr.strip())
+        m = re.fullmatch(rf"\(?\s*({FACTOR}(?:\s*[*+-]\s*{FACTOR})*)\s*/\s*({FACTOR})\s*\)?", expr)
+        if m:
+            return self._text(m.group(1)), self._text(m.group(2))
+        m = re.fullmatch(rf"({FACTOR})\s*\.\s*(?:checked_|saturating_)?div\s*\(\s*({FACTOR})\s*\)", expr)
+        if m:
+            return self._text(m.group(1)), self._text(m.group(2))
+        return None
+
+    def _divide_first(self, stmt: str, quotients: dict):
+        """(offset, dividend, divisor, multiplier, description) of a divide-before-multiply."""
+        for m in DIV_MUL_RE.finditer(stmt):
+            # `(a / b) * c` needs both parentheses or neither
+            if bool(m.group(1)) != bool(m.group(4)) or stmt[:m.start()].rstrip().endswith(("*", "/")):
+                continue
+            dividend, divisor, multiplier = (self._text(m.group(i)) for i in (2, 3, 5))
+            if divisor == multiplier or re.fullmatch(r"\d[\d_]*\w*", dividend):
+                continue
+            return m.start(2), dividend, divisor, multiplier, f"`{dividend} / {divisor} * {multiplier}`"
+        for m in METHOD_DIV_MUL_RE.finditer(stmt):
+            divisor, multiplier = self._text(m.group(1)), self._text(m.group(2))
+            if divisor == multiplier:
+                continue
+            head = re.search(rf"({FACTOR})\s*$", stmt[:m.start()])
+            dividend = self._text(head.group(1)) if head else "the value"
+            return m.start(), dividend, divisor, multiplier, \
+                f"`{dividend}.checked_div({divisor})…checked_mul({multiplier})`"
+        for name, (dividend, divisor, line) in quotients.items():
+            use = re.search(
+                rf"(?<![\w.])(?:{name}\s*\*(?!=)\s*({FACTOR})|({FACTOR})\s*\*(?!=)\s*{name}(?![\w(])|"
+                rf"{name}\s*\.\s*(?:checked_|saturating_|wrapping_)?mul\s*\(\s*({FACTOR})\s*\))",
+                stmt,
+            )
+            if not use:
+                continue
+            multiplier = self._text(next(g for g in use.groups() if g))
+            if multiplier == divisor or multiplier.split(".")[0] in KEYWORDS:
+                continue
+            return use.start(), dividend, divisor, multiplier, \
+                f"`{name}` is `{dividend} / {divisor}` (line {line}), which"
+        return None
+
+    def _multiply_first(self, stmt: str, types: dict, consts: dict):
+        """(offset, left, right, divisor, bits) of an unwidened multiply-before-divide that can overflow."""
+        if WIDE_RE.search(stmt):
+            return None
+        for m in MUL_DIV_RE.finditer(stmt):
+            if bool(m.group(1)) != bool(m.group(4)) or stmt[:m.start()].rstrip().endswith(("*", "/")):
+                continue
+            left, right, divisor = m.group(2), m.group(3), m.group(5)
+            if left.split(".")[0].strip() in KEYWORDS or all(is_constant(f) for f in (left, right)):
+                continue
+            # Declared u128 operands are already widened
+            if any(types.get(last_segment(CAST_STRIP_RE.sub("", f)), 0) > 64
+                   and not re.search(r"\bas\s+[iu](?:8|16|32|64|size)\b", f) for f in (left, right)):
+                continue
+            bits = factor_bits(left, types, consts) + factor_bits(right, types, consts)
+            if bits > 64:
+                return m.start(2), self._text(left), self._text(right), self._text(divisor), bits
+        return None
+
+    @staticmethod
+    def _fix(a: str, b: str, c: str, target: str, helper: str) -> str:
+        rounding = "mul_div_ceil" if CHARGED_RE.search(target) else "mul_div_floor"
+        why = "rounds up, against the user who pays it" if rounding == "mul_div_ceil" else \
+            "rounds down, in the program's favor"
+        bind = f"let {target} = " if target and "." not in target else f"{target} = " if target else ""
+        return (
+            f"Multiply and divide in one widened step with {helper}; {rounding} {why}:\n"
+            f"  {bind}U128::from({a})\n"
+            f"      .{rounding}(U128::from({b}), U128::from({c}))\n"
+            "      .ok_or(ErrorCode::MathOverflow)?\n"
+            "      .as_u64();"
+        )
+
+    def _finding(self, file_path, content, line, message, fix) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=self.severity,
+            file=file_path,
+            line=line,
+            description=f"{message}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=fix,
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "rewards = 999, total_shares = 1,000, user.shares = 500",
+                "after": "rewards / total_shares * user.shares = 0 * 500 = 0 (exact: 499)",
+                "damage": "Each claim rounds the user's share to zero, or a large amount overflows the product.",
+            },
+            impact={
+                "attack_cost": "Repeated small calls, or one large amount",
+                "exploitability": "Medium — the loss per call is bounded, but it repeats on every call",
+                "breach_cost_context": "Truncation in reward and fee formulas accumulates into real value leaks.",
+            },
+            anchor_versions_affected="All versions (program-side arithmetic)",
+            ecosystem_recommendations=[
+                "Keep a mul_div_floor / mul_div_ceil helper on U128/U256 and use it for every a * b / c",
+                "Pick the rounding direction per formula so it never favors the caller",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Multiply before dividing, widened, in one step:\n"
+            "  let owed = U128::from(rewards)\n"
+            "      .mul_div_floor(U128::from(user.shares), U128::from(total_shares))\n"
+            "      .ok_or(ErrorCode::MathOverflow)?\n"
+            "      .as_u64();"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Integer division truncates. Dividing before multiplying scales the "
+            "truncation error up by the multiplier; multiplying first in u64 "
+            "keeps precision but needs the full product to fit in 64 bits. Only "
+            "a widened multiply-then-divide avoids both."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. claim computes per_share = rewards / total_shares, then per_share * user.shares\n"
+            "2. With rewards < total_shares, per_share is 0 and every claim pays nothing\n"
+            "3. The undistributed remainder accumulates in the vault across claims\n"
+            "4. Whoever triggers the next large distribution, or the admin sweep, takes it"
+        )
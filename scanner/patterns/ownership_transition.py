@@ -0,0 +1,331 @@
+"""
+ANCHOR-013: Account Used Under Unexpected Owner
+
+Models which program owns each account at each point in a handler and flags
+data access performed while the account is owned by a program other than the
+one the access assumes:
+
+  - System Program (pre-init): a raw `AccountInfo` / `UncheckedAccount` that
+    the handler creates itself via `system_program::create_account`, or
+    `invoke(&system_instruction::create_account(..))`, is system-owned and empty
+    until that call. Reading or deserializing it earlier reads whatever an
+    attacker pre-funded at that address.
+  - Program-owned: after `create_account(.., owner = program_id)`, `#[account(init)]`
+    or for typed `Account<T>` / `AccountLoader<T>`.
+  - Token Program: vaults created through CPI helpers (Raydium's
+    `create_token_vault_account`, `token::initialize_account3`) or typed
+    `TokenAccount` / `Mint`. The program cannot write their data, and
+    deserializing them as program state reads a token layout.
+
+Only transitions the handler itself performs, or owners implied by the
+accounts struct, are tracked; accounts with unknown owners are not reported.
+"""
+
+import re
+from scanner.cpi import find_cpi_calls
+from scanner.patterns.account_types import account_type
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+
+SYSTEM, PROGRAM, TOKEN = "system", "program", "token"
+
+OWNER_NAMES = {
+    SYSTEM: "the System Program (not yet created)",
+    PROGRAM: "this program",
+    TOKEN: "the Token Program",
+}
+
+
+class OwnershipTransitionPattern(VulnerabilityPattern):
+    id = "ANCHOR-013"
+    name = "Account Used Under Unexpected Owner"
+    severity = "Medium"
+    category = "lifecycle"
+    remediation_effort = "localized"
+    cost = "expensive"
+    description = (
+        "Account data is read or written at a point in the handler where the "
+        "account is still owned by a different program than the access assumes."
+    )
+
+    TOKEN_TYPE_RE = re.compile(r"\b(?:TokenAccount|Mint|TokenAccountInterface)\b")
+    TOKEN_INIT_ATTR_RE = re.compile(r"\b(?:token|mint|associated_token)\s*::")
+
+    # (method regex, expected owners, verb)
+    ACCESSES = [
+        (r"try_borrow_mut_data\s*\(|data\s*\.\s*borrow_mut\s*\(|realloc\s*\(", {PROGRAM}, "written"),
+        (r"load_init\s*\(|load_mut\s*\(|load\s*\(", {PROGRAM}, "loaded as program state"),
+        (r"try_borrow_data\s*\(|data\s*\.\s*borrow\s*\(", {PROGRAM, TOKEN}, "read"),
+    ]
+
+    TYPED_RE = re.compile(
+        r"\b((?:\w+\s*::\s*)*\w+)(?:\s*<[^>]*>)?\s*::\s*"
+        r"(?:try_deserialize(?:_unchecked)?|try_from(?:_unchecked)?|unpack(?:_unchecked|_from_slice)?)\s*\("
+    )
+
+    HELPER_CREATE_RE = re.compile(r"\b(create_\w*(?:account|vault)\w*)\s*\(")
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        structs = {name: (body, line) for name, body, line in self._find_derive_accounts_structs(content)}
+        findings = []
+        facts = Facts(content)
+
+        for fn in self._find_functions(content):
+            ix = facts.instruction_of(fn)
+            if not ix or ix.accounts_struct not in structs:
+                continue
+            body_text, start_line = structs[ix.accounts_struct]
+            fields = {f["name"]: f for f in self._parse_struct_fields(body_text, start_line)}
+            body = re.sub(r"//[^\n]*", lambda c: " " * len(c.group(0)), fn["body"])
+
+            aliases = self._aliases(body, fields)
+            events = self._creations(body, fields, aliases) + self._accesses(body, fields, aliases)
+            if not any(kind == "create" for kind, *_ in events):
+                continue
+            events.sort(key=lambda e: e[1])
+
+            created = {e[2] for e in events if e[0] == "create"}
+            owners = {}
+            for name, field in fields.items():
+                owners[name] = self._initial_owner(field)
+                if name in created and account_type(field["type"]).is_raw:
+                    owners[name] = SYSTEM
+
+            reported = set()
+            history = {}
+            for kind, pos, name, owner_or_expected, verb in events:
+                if kind == "create":
+                    owners[name] = owner_or_expected
+                    history[name] = (owner_or_expected, pos)
+                    continue
+                owner = owners.get(name)
+                if owner is None or owner in owner_or_expected or name in reported:
+                    continue
+                reported.add(name)
+                line = self._get_line_number(content, fn["body_start"] + pos)
+                findings.append(self._finding(
+                    file_path, content, line, fn["name"], name, verb, owner,
+                    owner_or_expected, history.get(name), fn["body_start"],
+                ))
+        return findings
+
+    # ── event extraction ────────────────────────────────────────────────
+
+    def _ref_re(self, name: str, aliases: dict) -> str:
+        names = [re.escape(a) for a, target in aliases.items() if target == name]
+        alts = [rf"ctx\s*\.\s*accounts\s*\.\s*{re.escape(name)}\b"] + [rf"\b{a}\b" for a in names]
+        return "(?:" + "|".join(alts) + ")"
+
+    @staticmethod
+    def _aliases(body: str, fields: dict) -> dict:
+        aliases = {}
+        for m in re.finditer(
+            r"\blet\s+(?:mut\s+)?(\w+)\s*(?::[^=]+)?=\s*&?\s*(?:mut\s+)?ctx\s*\.\s*accounts\s*\.\s*(\w+)"
+            r"(?:\s*\.\s*(?:to_account_info|as_ref|clone)\s*\(\s*\))*\s*;",
+            body,
+        ):
+            if m.group(2) in fields:
+                aliases[m.group(1)] = m.group(2)
+        return aliases
+
+    def _accesses(self, body: str, fields: dict, aliases: dict) -> list:
+        events = []
+        for name in fields:
+            ref = self._ref_re(name, aliases)
+            for method_re, expected, verb in self.ACCESSES:
+                pattern = ref + r"(?:\s*\.\s*to_account_info\s*\(\s*\))?\s*\.\s*(?:" + method_re + ")"
+                for m in re.finditer(pattern, body):
+                    events.append(("access", m.start(), name, expected, verb))
+            for m in self.TYPED_RE.finditer(body):
+                end = self._call_end(body, m.end() - 1)
+                if not re.search(ref, body[m.end():end]):
+                    continue
+                type_name = m.group(1)
+                token = bool(self.TOKEN_TYPE_RE.search(type_name) or "spl_token" in type_name)
+                events.append((
+                    "access", m.start(), name, {TOKEN} if token else {PROGRAM},
+                    f"deserialized as {type_name.split('::')[-1]}",
+                ))
+        return events
+
+    def _creations(self, body: str, fields: dict, aliases: dict) -> list:
+        events = []
+        covered = []
+        for call in find_cpi_calls(body):
+            ix = call.instruction
+            if ix in ("create_account", "create_account_with_seed", "assign") and \
+                    call.program in ("system_program", "system"):
+                target = self._field_after(call.text, r"\b(?:to|account_to_assign)\s*:", fields, aliases)
+                args = self._builder_args(call.text, ix)
+                if target is None and args:
+                    target = self._field_in(args[0 if ix == "assign" else 1], fields, aliases)
+                owner = self._owner_of(args[-1] if args else self._last_arg(call.text))
+                if target:
+                    events.append(("create", call.start, target, owner, ""))
+                covered.append((call.start, call.end))
+            elif ix.startswith(("initialize_account", "initialize_mint")):
+                target = self._field_after(call.text, r"\b(?:account|mint)\s*:", fields, aliases)
+                if target:
+                    events.append(("create", call.start, target, TOKEN, ""))
+                covered.append((call.start, call.end))
+
+        for m in self.HELPER_CREATE_RE.finditer(body):
+            if any(s <= m.start() < e for s, e in covered) or m.group(1) in ("create_account",):
+                continue
+            end = self._call_end(body, m.end() - 1)
+            args = body[m.end():end]
+            owner = TOKEN if "token" in m.group(1) or "vault" in m.group(1) else None
+            for name, field in fields.items():
+                if name.endswith("_program") or not account_type(field["type"]).is_raw:
+                    continue
+                if re.search(self._ref_re(name, aliases), args):
+                    events.append(("create", m.start(), name, owner, ""))
+        return events
+
+    def _initial_owner(self, field: dict):
+        ty, attrs = field["type"], field["attrs"]
+        wrapper = account_type(ty)
+        if wrapper.is_raw:
+            return None
+        if re.search(r"\binit(?:_if_needed)?\b", attrs) and self.TOKEN_INIT_ATTR_RE.search(attrs):
+            return TOKEN
+        if self.TOKEN_TYPE_RE.search(ty):
+            return TOKEN
+        if wrapper.wrapper in ("Account", "AccountLoader"):
+            return PROGRAM
+        if wrapper.wrapper == "SystemAccount":
+            return SYSTEM
+        return None
+
+    def _field_after(self, text: str, key_re: str, fields: dict, aliases: dict):
+        m = re.search(key_re + r"([^,}]*)", text)
+        return self._field_in(m.group(1), fields, aliases) if m else None
+
+    def _field_in(self, text: str, fields: dict, aliases: dict):
+        for name in fields:
+            if re.search(self._ref_re(name, aliases), text):
+                return name
+        return None
+
+    def _builder_args(self, text: str, ix: str) -> list:
+        """Top-level arguments of `system_instruction::<ix>(..)` inside an invoke."""
+        m = re.search(rf"\b{ix}\s*\(", text)
+        if not m or not re.search(r"instruction\s*::\s*" + ix, text):
+            return []
+        return self._split_args(text[m.end():self._call_end(text, m.end() - 1) - 1])
+
+    def _last_arg(self, text: str) -> str:
+        open_pos = text.find("(")
+        if open_pos < 0:
+            return ""
+        args = self._split_args(text[open_pos + 1:self._call_end(text, open_pos) - 1])
+        return args[-1] if args else ""
+
+    @staticmethod
+    def _owner_of(text: str):
+        if re.search(r"(?i)token", text):
+            return TOKEN
+        if re.search(r"\bprogram_id\b|\bcrate\s*::\s*(?:ID|id\s*\(\s*\))|^\s*&?\s*(?:ID|id\s*\(\s*\))\s*$", text):
+            return PROGRAM
+        return None
+
+    @staticmethod
+    def _split_args(text: str) -> list:
+        args, depth, start = [], 0, 0
+        for i, ch in enumerate(text):
+            if ch in "([{":
+                depth += 1
+            elif ch in ")]}":
+                depth -= 1
+            elif ch == "," and depth == 0:
+                args.append(text[start:i])
+                start = i + 1
+        if text[start:].strip():
+            args.append(text[start:])
+        return [a.strip() for a in args]
+
+    @staticmethod
+    def _call_end(text: str, open_pos: int) -> int:
+        depth = 0
+        for i in range(open_pos, len(text)):
+            if text[i] == "(":
+                depth += 1
+            elif text[i] == ")":
+                depth -= 1
+                if depth == 0:
+                    return i + 1
+        return len(text)
+
+    # ── reporting ───────────────────────────────────────────────────────
+
+    def _finding(self, file_path, content, line, fn_name, account, verb, owner,
+                 expected, history, body_start) -> Finding:
+        if expected == {PROGRAM, TOKEN}:
+            expected_names = "an already-created account"
+        else:
+            expected_names = OWNER_NAMES[next(iter(expected))] + " as owner"
+        if owner == SYSTEM:
+            context = "It is only created later in the handler."
+        elif history:
+            created_line = self._get_line_number(content, body_start + history[1])
+            context = f"Line {created_line} hands it to {OWNER_NAMES[history[0]]}."
+        else:
+            context = "Its type in the accounts struct fixes that owner."
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=self.severity,
+            file=file_path,
+            line=line,
+            description=(
+                f"In fn {fn_name}: '{account}' is {verb} while owned by "
+                f"{OWNER_NAMES[owner]}; the access assumes {expected_names}. {context}"
+            ),
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "Account address is system-owned; attacker pre-funds it with crafted data/lamports",
+                "after": "Handler reads or writes it as if it were already program state",
+                "damage": "Logic runs on attacker-controlled or wrong-layout data, or the transaction always fails.",
+            },
+            impact={
+                "attack_cost": "Rent for a pre-created account at the target address",
+                "exploitability": "Medium — depends on what the early read feeds into",
+                "breach_cost_context": "Init-ordering bugs have let attackers adopt accounts before the program created them.",
+            },
+            anchor_versions_affected="All versions (manual account creation)",
+            ecosystem_recommendations=[
+                "Prefer #[account(init, ...)] so Anchor creates and assigns before the handler runs",
+                "Create the account first, then touch its data",
+                "Access token-owned vaults only through the Token Program",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Order account creation before any data access, or let Anchor do it:\n"
+            "  #[account(init, payer = payer, space = 8 + State::INIT_SPACE)]\n"
+            "  pub state: Account<'info, State>,\n"
+            "For token vaults, read balances via Account<'info, TokenAccount>\n"
+            "and move funds with token::transfer instead of writing account data."
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "An account's owner changes during a handler that creates it: it is "
+            "system-owned until create_account/assign runs, and a vault created "
+            "through the Token Program belongs to that program afterwards. Code "
+            "that touches account data without tracking those transitions reads "
+            "data nobody validated or writes data it does not own."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. Handler reads a PDA's data before creating it with create_account\n"
+            "2. Attacker transfers lamports and crafted bytes to that address first\n"
+            "   (or the read simply sees zeroes and skips a check)\n"
+            "3. The handler's decision is based on attacker-chosen state\n"
+            "4. The account is then created/adopted with that state baked in"
+        )
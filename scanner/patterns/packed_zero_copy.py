@@ -0,0 +1,269 @@
+"""
+ANCHOR-028: Unsafe Packed Zero-Copy Account
+
+`#[account(zero_copy(unsafe))]` and `#[repr(C, packed)]` structs have no
+padding and alignment 1. Raydium's PoolState puts a u16 and u128s right
+after 227 bytes of keys and flags:
+
+    #[account(zero_copy(unsafe))]
+    #[repr(C, packed)]
+    pub struct PoolState {
+        pub bump: [u8; 1],
+        pub amm_config: Pubkey,
+        ...
+        pub tick_spacing: u16,      // offset 227
+        pub liquidity: u128,        // offset 229
+    }
+
+Reading a field by value copies it and is fine. Taking a reference to it is
+undefined behavior whenever the field's type needs alignment:
+
+    let pool = ctx.accounts.pool.load()?;
+    let fees = &pool.protocol_fees;             // unaligned &u64
+    pool.tick_array_bitmap.iter()               // unaligned &[u64; 16]
+    msg!("{}", pool.liquidity);                 // format! borrows its arguments
+
+Older platform-tools compilers only warn (`unaligned_references`) and an
+`#[allow]` silences it. The rule reports, for packed zero-copy types:
+
+  - references to fields with alignment > 1 (`&`/`&mut`, methods taking
+    `&self` such as `iter()`, `len()` or `eq()`, and formatting/assert
+    macros) through a loaded account, a `&T` parameter or `self` in
+    `impl T`
+  - layouts that only hold because of `packed`: fields at offsets that are
+    not a multiple of their alignment. Reordering the fields, dropping
+    `packed` or moving to the checked form shifts every later field of the
+    accounts already on chain
+  - padding and reserved fields with no `Default` to zero them, so values
+    built in code and future fields carved out of the reserved bytes start
+    from whatever the bytes held
+
+When no field is misaligned, the checked `#[account(zero_copy)]` form lays
+out the same bytes, and the fix says so.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+from scanner.patterns.layout import Layout
+from scanner.patterns.validation import _balanced, _param_names, _split_top
+
+PADDING_FIELD_RE = re.compile(r"^_?(?:padding|pad|reserved|unused)\w*$")
+LOADER_RE = re.compile(r"\bAccountLoader\s*<\s*(?:'\w+\s*,\s*)?(\w+)\s*>")
+REF_PARAM_RE = re.compile(r"^(?:&\s*(?:'\w+\s+)?(?:mut\s+)?|Ref(?:Mut)?\s*<\s*(?:'\w+\s*,\s*)?)(\w+)\s*>?$")
+# Methods taking &self / &mut self that arrays and integers are commonly called with
+REF_METHODS = (
+    r"iter|iter_mut|as_ref|as_mut|as_slice|as_mut_slice|len|is_empty|contains|get|get_mut|first|last"
+    r"|swap|fill|copy_from_slice|clone_from_slice|to_vec|eq|ne|cmp|partial_cmp|clone|to_string"
+)
+FORMAT_MACROS = r"msg|println|print|eprintln|format|write|writeln|panic|assert_eq|assert_ne|debug_assert_eq|debug_assert_ne"
+
+
+class PackedZeroCopyPattern(VulnerabilityPattern):
+    id = "ANCHOR-028"
+    name = "Unsafe Packed Zero-Copy Account"
+    severity = "Medium"
+    category = "state"
+    remediation_effort = "localized"
+    description = (
+        "A zero_copy(unsafe) / repr(packed) account type has its unaligned "
+        "fields borrowed, a layout that only holds while packed, or padding "
+        "that nothing zeroes."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        layout = Layout(content)
+        packed = {name: s for name, s in layout.structs.items() if s.zero_copy and s.packed}
+        if not packed:
+            return findings
+
+        for struct in packed.values():
+            offsets = layout.offsets(struct)
+            misaligned = [(f, o, a) for f, o, a in offsets if o is not None and a and o % a]
+            if misaligned:
+                shown = ", ".join(f"{f.name}: {f.type} at offset {o}" for f, o, _ in misaligned[:3])
+                more = f" and {len(misaligned) - 3} more" if len(misaligned) > 3 else ""
+                findings.append(self._finding(
+                    file_path, content, struct.line, "Low",
+                    f"{len(misaligned)} field(s) of packed {struct.name} sit at offsets that are not a multiple "
+                    f"of their alignment ({shown}{more}). The layout only holds because of `packed`: reordering "
+                    f"fields, dropping `packed` or moving to `zero_copy` shifts every later field of the "
+                    f"{struct.name} accounts already on chain",
+                    self._fix(struct, layout),
+                ))
+            padding = [f for f in struct.fields if PADDING_FIELD_RE.match(f.name)]
+            if padding and not self._has_default(struct, content):
+                findings.append(self._finding(
+                    file_path, content, padding[0].line, "Low",
+                    f"Packed {struct.name} has padding/reserved field(s) "
+                    f"({', '.join(f.name for f in padding[:4])}) but no `Default` that zeroes them. Values "
+                    f"built in code must spell out every padding array, and fields later carved out of the "
+                    f"reserved bytes read whatever they held",
+                    f"Zero the padding in one place:\n"
+                    f"  impl Default for {struct.name} {{\n"
+                    f"      fn default() -> Self {{ bytemuck::Zeroable::zeroed() }}\n"
+                    f"  }}\n"
+                    f"and build values with `..Default::default()`.",
+                ))
+
+        findings += self._references(file_path, content, layout, packed)
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    def _references(self, file_path, content, layout, packed) -> list:
+        findings, seen = [], set()
+        loaders = {}
+        for struct in Facts(content).accounts_structs():
+            for account in struct.accounts:
+                m = LOADER_RE.search(account.type)
+                if m and m.group(1) in packed:
+                    loaders[account.name] = m.group(1)
+        impls = self._find_impl_blocks(content)
+
+        for fn in self._find_functions(content):
+            body, start = fn["body"], fn["body_start"]
+            fn_loaders = dict(loaders)
+            subjects = {}
+            for name, type_ in _param_names(fn["params"]):
+                m = LOADER_RE.search(type_)
+                if m and m.group(1) in packed:
+                    fn_loaders[name] = m.group(1)
+                m = REF_PARAM_RE.match(type_.strip())
+                if m and m.group(1) in packed:
+                    subjects[rf"\b{re.escape(name)}"] = m.group(1)
+            owner = next((name for s, e, name in impls if s <= start < e), "")
+            if owner in packed and re.search(r"\bself\b", fn["params"]):
+                subjects[r"\bself"] = owner
+            for name, type_name in fn_loaders.items():
+                load = rf"\b{re.escape(name)}\s*\.\s*load(?:_mut|_init)?\s*\(\s*\)\s*\?"
+                subjects[load] = type_name
+                for m in re.finditer(rf"let\s+(?:mut\s+)?(\w+)\s*(?::[^=;]+)?=\s*&?\s*(?:mut\s+)?[\w.\s]*?{load}", body):
+                    subjects[rf"\b{re.escape(m.group(1))}"] = type_name
+
+            for subject, type_name in subjects.items():
+                struct = packed[type_name]
+                fields = {f.name: (f, o, a) for f, o, a in layout.offsets(struct) if a and a > 1}
+                if not fields:
+                    continue
+                for pos, field_name, how in self._borrows(body, subject):
+                    if field_name not in fields or start + pos in seen:
+                        continue
+                    seen.add(start + pos)
+                    f, offset, align = fields[field_name]
+                    at = f" at offset {offset}" if offset is not None else ""
+                    findings.append(self._finding(
+                        file_path, content, self._get_line_number(content, start + pos), self.severity,
+                        f"In {fn['name']}: {how} `{field_name}: {f.type}`{at} of packed {type_name} "
+                        f"(alignment {align}). A reference to an unaligned field is undefined behavior; "
+                        f"copy the value out instead",
+                        self._fix(struct, layout, field_name),
+                    ))
+        return findings
+
+    @staticmethod
+    def _borrows(body: str, subject: str) -> list:
+        """[(offset, field, how)] for references taken to `subject.field`."""
+        access = rf"{subject}\s*\.\s*(\w+)(\s*\[[^\[\]]*\])?"
+        result = []
+        for m in re.finditer(rf"(?<![&\w])&\s*(?:mut\s+)?{access}(?!\s*[.(\[\w])", body):
+            result.append((m.start(), m.group(1), "takes a reference to"))
+        for m in re.finditer(rf"{access}\s*\.\s*({REF_METHODS})\s*\(", body):
+            result.append((m.start(), m.group(1), f"calls `{m.group(3)}()` (by reference) on"))
+        for m in re.finditer(rf"\b({FORMAT_MACROS})!\s*\(", body):
+            for arg in _split_top(_balanced(body, m.end() - 1)):
+                fm = re.fullmatch(access, arg)
+                if fm:
+                    result.append((m.start(), fm.group(1), f"passes to {m.group(1)}! (which borrows it)"))
+        return result
+
+    @staticmethod
+    def _has_default(struct, content: str) -> bool:
+        return struct.derives("Default") or bool(
+            re.search(rf"\bimpl\s*(?:<[^>]*>\s*)?(?:\w+::)*Default\s+for\s+{re.escape(struct.name)}\b", content)
+        )
+
+    @staticmethod
+    def _fix(struct, layout, field_name: str = "") -> str:
+        lines = []
+        if field_name:
+            lines += [
+                f"Read the field by value instead of borrowing it:",
+                f"  let {field_name} = state.{field_name};",
+                f"  // or, through a raw pointer:",
+                f"  let {field_name} = unsafe {{ std::ptr::addr_of!(state.{field_name}).read_unaligned() }};",
+            ]
+        offsets = layout.offsets(struct)
+        size = layout.size_align(struct.name)
+        aligned = all(o is not None and o % a == 0 for _, o, a in offsets)
+        widest = max((a for _, _, a in offsets if a), default=1)
+        if aligned and size and size[0] % widest == 0:
+            lines.append(
+                f"No field of {struct.name} is misaligned, so the checked form lays out the same bytes: "
+                f"replace `zero_copy(unsafe)`/`repr(packed)` with `#[account(zero_copy)]` and let bytemuck "
+                f"check the layout."
+            )
+        else:
+            lines.append(
+                f"Keep the field order of {struct.name} frozen. A layout that can move to the checked "
+                f"`#[account(zero_copy)]` form needs every field aligned, which changes the on-chain "
+                f"format and needs an account migration."
+            )
+        return "\n".join(lines)
+
+    def _finding(self, file_path, content, line, severity, message, fix) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=severity,
+            file=file_path,
+            line=line,
+            description=f"{message}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=fix,
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "PoolState.liquidity (u128) at offset 229, read through &pool.liquidity",
+                "after": "the compiler assumes 8-byte alignment and may emit aligned loads",
+                "damage": "Undefined behavior: miscompiled reads and writes of account state, "
+                          "or account layouts that break on the next refactor.",
+            },
+            impact={
+                "attack_cost": "None — depends on how the compiler lowers the access",
+                "exploitability": "Low — a latent miscompilation or migration hazard, not a direct exploit",
+                "breach_cost_context": "Anchor replaced zero_copy's packed layout with a checked repr(C) "
+                                       "layout because of unaligned references.",
+            },
+            anchor_versions_affected="Anchor 0.27+ (zero_copy(unsafe)); all versions with repr(packed)",
+            ecosystem_recommendations=[
+                "Prefer #[account(zero_copy)]; it rejects layouts with implicit padding at compile time",
+                "Read packed fields by value or through addr_of!(..).read_unaligned()",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Read packed fields by value and never borrow them:\n"
+            "  let liquidity = pool.liquidity;\n"
+            "Where no field is misaligned, switch to the checked form:\n"
+            "  #[account(zero_copy)]\n"
+            "  pub struct PoolState { .. }"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "A packed struct has alignment 1, so its fields can be at any "
+            "address. Rust references must be aligned; borrowing a packed "
+            "field (explicitly, through a &self method or a formatting macro) "
+            "creates a misaligned reference, which is undefined behavior."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. A handler borrows an unaligned u64/u128 field of a packed zero-copy account\n"
+            "2. The compiler emits code that assumes the reference is aligned\n"
+            "3. A toolchain upgrade changes the generated loads and stores\n"
+            "4. Account state is read or written wrongly, or a refactor of the packed layout "
+            "silently moves fields of existing accounts"
+        )
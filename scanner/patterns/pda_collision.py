@@ -0,0 +1,135 @@
+"""
+ANCHOR-031: PDA Seed Collision Across Account Types
+
+Seeds are hashed as plain concatenated bytes, so two account types whose
+recipes share a literal prefix and line up in their dynamic parts live in
+one address space:
+
+    #[account(init, payer = owner, space = 8 + Position::LEN,
+              seeds = [b"pos", owner.key().as_ref()], bump)]
+    pub position: Account<'info, Position>,
+
+    #[account(init, payer = admin, space = 8 + PoolConfig::LEN,
+              seeds = [b"pos", pool.key().as_ref()], bump)]
+    pub pool_config: Account<'info, PoolConfig>,
+
+Passing the pool's key as `owner` derives the pool config's address. Only
+one of the two accounts can ever exist there: whoever creates it first
+blocks the other's `init`, and code that reads the address without a
+discriminator check (UncheckedAccount, AccountInfo, manual deserialization)
+takes one type for the other.
+
+The rule builds the seed registry of the unit (seeds.py) and reports each
+pair of distinct program account types whose recipes start with the same
+literal seed (or both start with a dynamic one) and can produce the same
+bytes: fixed-length parts must line up, literals must agree where they
+overlap, and a string or byte-vector seed can absorb any bytes. Recipes
+with different leading literals, or the same literal followed by keys and
+integers of different total length, cannot meet and are not reported.
+"""
+
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.details import SeedCollision, SeedRecipe
+from scanner.patterns.facts import Facts
+from scanner.patterns.seeds import can_collide, seed_registry
+
+
+class PdaSeedCollisionPattern(VulnerabilityPattern):
+    id = "ANCHOR-031"
+    name = "PDA Seed Collision Across Account Types"
+    severity = "Medium"
+    category = "lifecycle"
+    remediation_effort = "architectural"
+    description = (
+        "Two account types derive their PDAs from seeds with the same literal "
+        "prefix and compatible dynamic parts, so one type's address can be "
+        "the other's."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        shapes = seed_registry(content, Facts(content))
+        reported = set()
+        for i, later in enumerate(shapes):
+            for earlier in shapes[:i]:
+                pair = frozenset((earlier.type, later.type))
+                if earlier.type == later.type or pair in reported:
+                    continue
+                if earlier.leading() != later.leading() or not can_collide(earlier, later):
+                    continue
+                reported.add(pair)
+                findings.append(self._finding(file_path, content, earlier, later))
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    def _finding(self, file_path, content, first, second) -> Finding:
+        identical = first.describe() == second.describe()
+        how = ("the same seed shape" if identical else
+               "seed shapes whose bytes can line up")
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=self.severity,
+            file=file_path,
+            line=second.line,
+            description=(
+                f"Account types '{first.type}' ({first.struct}.{first.field}, line {first.line}) and "
+                f"'{second.type}' ({second.struct}.{second.field}) derive their PDAs from {how}: "
+                f"{first.describe()} and {second.describe()}. Seeds that derive one type's address "
+                f"derive the other's, so creating either account squats the other, and unchecked "
+                f"reads of the address can confuse the two types."
+            ),
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=(
+                f"Give each type its own leading seed, e.g. b\"{first.type.lower()}\" for {first.type} "
+                f"and b\"{second.type.lower()}\" for {second.type}."
+            ),
+            code_snippet=self._extract_snippet(content, second.line),
+            before_after_state={
+                "before": "Position PDA [b\"pos\", owner] and PoolConfig PDA [b\"pos\", pool] share a prefix",
+                "after": "Attacker passes the pool's key as owner: the position lands on the pool config's address",
+                "damage": "Pool config can no longer be created, or one type is read as the other.",
+            },
+            impact={
+                "attack_cost": "Rent for one account",
+                "exploitability": "Medium — needs the colliding seed values to be caller-chosen",
+                "breach_cost_context": "Shared seed prefixes are a recurring audit note in multi-account programs.",
+            },
+            anchor_versions_affected="All versions (developer-side pattern)",
+            ecosystem_recommendations=[
+                "Start every PDA recipe with a literal seed unique to the account type",
+                "Use Account<'info, T> rather than unchecked accounts so the discriminator is verified",
+            ],
+            details=SeedCollision(first.type, second.type, self._recipe(first), self._recipe(second), identical),
+        )
+
+    @staticmethod
+    def _recipe(shape) -> SeedRecipe:
+        return SeedRecipe(shape.struct, shape.field, shape.line, shape.seeds,
+                          [c.describe() for c in shape.components])
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Prefix each account type's seeds with a literal of its own:\n"
+            "  seeds = [b\"position\", owner.key().as_ref()]\n"
+            "  seeds = [b\"pool_config\", pool.key().as_ref()]"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "find_program_address hashes the seeds as one byte string, without "
+            "separators or the account type. Two recipes that can produce the "
+            "same bytes derive the same address, whatever type each struct "
+            "expects there."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. Position and PoolConfig both derive from [b\"pos\", <pubkey>]\n"
+            "2. Attacker opens a position with the pool's key as the owner seed\n"
+            "3. The position is created at the pool config's address\n"
+            "4. Initializing the pool config now fails, or a reader that skips the "
+            "discriminator check treats the position as the config"
+        )
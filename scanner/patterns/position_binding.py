@@ -0,0 +1,286 @@
+"""
+ANCHOR-027: Per-User Position Not Bound To User
+
+Lending, staking and vault programs keep one account per user and pool
+holding that user's deposits, debt or shares. Every instruction has to get
+the caller's own position, and a user must not be able to open a second
+one next to it:
+
+    #[derive(Accounts)]
+    pub struct Borrow<'info> {
+        #[account(mut)]
+        pub pool: Account<'info, Pool>,
+        #[account(mut)]
+        pub user_account: Account<'info, UserAccount>,   // anyone's position
+        #[account(mut)]
+        pub borrower: Signer<'info>,
+    }
+
+With nothing binding user_account to borrower, the borrower passes a
+position with someone else's collateral; with positions created at
+keypair addresses, a user opens several and the pool counts the same
+collateral more than once.
+
+Position types are `#[account]` structs with an owner-like Pubkey field
+(owner, user, borrower, depositor, ...) and a balance-like field
+(deposited, borrowed, shares, ...); pool totals (`total_deposits`) do not
+count. Their steps come from the per-type lifecycle (lifecycle.py):
+
+  - a mutable use must be a PDA (`[b"position", pool.key(), user.key()]`),
+    or be tied to a signer by `has_one`, by a constraint comparing the
+    owner field with the signer's key, or by the same comparison in a
+    handler of the struct
+  - positions holding collateral or debt must not be created at keypair
+    addresses
+  - a PDA holding a user's balance must have the user in its seeds:
+    `[b"user_stake", pool.key()]` written with a signer's stake is one
+    account every staker shares. This applies to balance accounts without
+    an owner field too (`UserStake { staked_amount }`)
+
+Seeds with another per-key component (an NFT mint, an instruction
+argument, a field of the account itself) are a deliberate one-per-key
+design and are left alone, as are PDAs whose has_one or constraint names
+the signer.
+
+Closes are ANCHOR-025's concern. Structs without a user signer are left
+out: liquidations, cranks, and admin instructions whose signer is itself
+checked against the pool (`has_one = admin`) act on other users' positions
+by design. Whether the position is bound to the right pool is ANCHOR-022's
+question (`position.pool` next to a `pool` account).
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+from scanner.patterns.lifecycle import CLOSE, CREATE, REINIT, USE, account_lifecycles
+
+STATE_STRUCT_RE = re.compile(
+    r"#\[account(?:\([^)]*\))?\]\s*(?:#\[[^\]]*\]\s*)*pub\s+struct\s+(\w+)\s*\{"
+)
+OWNER_FIELD_RE = re.compile(r"^(?:owner|user|borrower|depositor|staker|holder|beneficiary|wallet)$")
+BALANCE_FIELD_RE = re.compile(r"deposit|borrow|collateral|debt|shares|stake|balance|amount|liquidity|credit|reward")
+# Fields whose value is counted against the pool: a second position double-counts them
+COLLATERAL_FIELD_RE = re.compile(r"collateral|deposit|borrow|debt|loan")
+PRIVILEGED_STRUCT_RE = re.compile(r"(?i)liquidat|crank|keeper|admin")
+
+
+class PositionBindingPattern(VulnerabilityPattern):
+    id = "ANCHOR-027"
+    name = "Per-User Position Not Bound To User"
+    severity = "High"
+    category = "accounts"
+    remediation_effort = "localized"
+    description = (
+        "A per-user position account is neither a PDA derived from the user "
+        "nor tied to the signing user, so callers can pass another user's "
+        "position or open several that double-count collateral."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        balance_types = self._balance_types(content)
+        if not balance_types:
+            return findings
+        positions = {
+            name: (owner, any(COLLATERAL_FIELD_RE.search(n) for n in balances))
+            for name, (owner, balances, _) in balance_types.items() if owner
+        }
+        facts = Facts(content)
+        handlers = {}
+        for ix in facts.instructions():
+            handlers.setdefault(ix.accounts_struct, []).append(ix.body)
+
+        for acct_type, lifecycle in account_lifecycles(facts).items():
+            if acct_type not in balance_types:
+                continue
+            for step in lifecycle.steps:
+                if PRIVILEGED_STRUCT_RE.search(step.struct.name):
+                    continue
+                users = self._user_signers(step.struct, step.account)
+                if not users:
+                    continue
+                if step.seeds and step.kind != CLOSE and (step.kind != USE or step.account.is_mut) \
+                        and self._shared_seeds(step, users):
+                    written = self._balance_writes(step.account, balance_types[acct_type][2],
+                                                   handlers.get(step.struct.name, []))
+                    if written:
+                        findings.append(self._finding(
+                            file_path, content, acct_type, step, users,
+                            f"is a PDA of [{step.seeds}], with no user key in its seeds, yet the instruction "
+                            f"writes {', '.join(written)} for {self._names(users).replace('.key()', '')}: "
+                            f"every user shares one account, so one user's deposit can be withdrawn by any other "
+                            f"and no user can open their own",
+                        ))
+                        continue
+                if acct_type not in positions:
+                    continue
+                owner_field, collateral = positions[acct_type]
+                if step.kind in (CREATE, REINIT) and not step.seeds and collateral:
+                    findings.append(self._finding(
+                        file_path, content, acct_type, step, users,
+                        "is created at a caller-chosen keypair address, so one user can open any number "
+                        "of positions and have the same collateral counted more than once",
+                    ))
+                elif step.kind == USE and step.account.is_mut \
+                        and not self._bound(step.account, owner_field, users, handlers.get(step.struct.name, [])):
+                    findings.append(self._finding(
+                        file_path, content, acct_type, step, users,
+                        f"is not derived from the user and nothing checks {step.account.name}.{owner_field} "
+                        f"against {self._names(users)}, so a caller can pass another user's position",
+                    ))
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    def _balance_types(self, content: str) -> dict:
+        """#[account] struct name -> (owner field or None, balance fields, integer balance fields),
+        for structs holding a balance.
+
+        Pool totals (`total_deposits`) are aggregates, not a user's balance.
+        Structs with an owner field as well are positions.
+        """
+        result = {}
+        for m in STATE_STRUCT_RE.finditer(content):
+            end = self._find_block_end(content, m.end() - 1)
+            body = content[m.end():end] if end != -1 else ""
+            fields = re.findall(r"pub\s+(\w+)\s*:\s*([\w:<>\[\]; ]+)", body)
+            owner = next((n for n, t in fields if OWNER_FIELD_RE.match(n) and t.strip() == "Pubkey"), None)
+            balances = [n for n, _ in fields if BALANCE_FIELD_RE.search(n) and not n.startswith("total_")]
+            if balances:
+                amounts = [n for n, t in fields if n in balances and re.fullmatch(r"\s*[ui](?:64|128)\s*", t)]
+                result[m.group(1)] = (owner, balances, amounts)
+        return result
+
+    @staticmethod
+    def _shared_seeds(step, users: list) -> bool:
+        """Every seed is a literal, a constant, or a shared account (pool, market, config).
+
+        A signer's key, an owner-like field, a field of the account itself, a
+        mint (one account per NFT or token by design) or an instruction
+        argument make the PDA per-key.
+        """
+        accounts = {a.name: a for a in step.struct.accounts}
+        # has_one / a constraint naming the signer: one account owned by that signer by design
+        bound = " ".join(c.value for c in step.account.constraints if c.kind in ("has_one", "constraint"))
+        if any(re.search(rf"\b{re.escape(u.name)}\b", bound) for u in users):
+            return False
+        for seed in step.seeds.split(","):
+            if re.match(r'^(?:b?"|[A-Z][A-Z0-9_]*$|(?:\w+::)+[A-Z][A-Z0-9_]*$)', seed):
+                continue
+            m = re.match(r"(\w+)((?:\.\w+)*)", seed)
+            account = accounts.get(m.group(1)) if m else None
+            if account is None or account.is_signer or account is step.account:
+                return False
+            if any(OWNER_FIELD_RE.match(f) for f in m.group(2).split(".")[1:]):
+                return False
+            if re.search(r"(?i)mint|nft", account.name) or re.search(r"\bMint\b", account.type):
+                return False
+        return True
+
+    @staticmethod
+    def _balance_writes(account, balances: list, bodies: list) -> list:
+        """Balance fields of the account assigned in the struct's handlers."""
+        names = [rf"ctx\s*\.\s*accounts\s*\.\s*{re.escape(account.name)}"]
+        for body in bodies:
+            names += [re.escape(m.group(1)) for m in re.finditer(
+                rf"let\s+(?:mut\s+)?(\w+)\s*(?::[^=]+)?=\s*&?\s*(?:mut\s+)?{names[0]}\b", body)]
+        written = []
+        for body in bodies:
+            for m in re.finditer(rf"(?:{'|'.join(names)})\s*\.\s*(\w+)\s*[+\-]?=(?!=)", body):
+                if m.group(1) in balances and m.group(1) not in written:
+                    written.append(m.group(1))
+        return written
+
+    @staticmethod
+    def _user_signers(struct, position) -> list:
+        """Signers not themselves checked against another account (admins, pool authorities)."""
+        checked = " ".join(
+            c.value for a in struct.accounts if a is not position
+            for c in a.constraints if c.kind in ("has_one", "constraint", "address")
+        )
+        return [a for a in struct.accounts
+                if a.is_signer and not re.search(rf"\b{re.escape(a.name)}\b", checked)]
+
+    def _bound(self, position, owner_field: str, users: list, bodies: list) -> bool:
+        names = "|".join(re.escape(u.name) for u in users)
+        for c in position.constraints:
+            if c.kind == "has_one" and c.value.split("@")[0].strip() in {u.name for u in users}:
+                return True
+            if c.kind in ("seeds", "address"):
+                # A PDA of the user, or pinned to some other key by design
+                return True
+        owner_ref = rf"\.\s*{re.escape(owner_field)}\b"
+        for account in (position,) + tuple(u for u in users):
+            for c in account.constraints:
+                expression = c.value if c.kind == "constraint" else ""
+                if re.search(owner_ref, expression) and re.search(rf"\b(?:{names})\b", expression):
+                    return True
+        for body in bodies:
+            for stmt in re.split(r"[;{}]", body):
+                if re.search(owner_ref, stmt) and re.search(rf"\b(?:{names})\b", stmt) \
+                        and re.search(rf"==|!=|\b{self._guards()}!", stmt):
+                    return True
+        return False
+
+    @staticmethod
+    def _names(users: list) -> str:
+        return " or ".join(f"{u.name}.key()" for u in users)
+
+    def _finding(self, file_path, content, acct_type, step, users, problem) -> Finding:
+        user = users[0].name
+        line = step.account.line
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=self.severity,
+            file=file_path,
+            line=line,
+            description=f"In struct {step.struct.name}: position '{step.account.name}' ({acct_type}) {problem}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=(
+                f"Derive the position from the pool and the user, in every struct that takes it:\n"
+                f"  #[account(mut, seeds = [b\"position\", pool.key().as_ref(), {user}.key().as_ref()], bump)]\n"
+                f"  pub {step.account.name}: Account<'info, {acct_type}>,\n"
+                f"or bind it to the signer with `has_one` on its owner field."
+            ),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "victim position: deposited=100 SOL, borrowed=0; attacker position: empty",
+                "after": "attacker borrows 75 SOL against the victim's position (or a second copy of their own)",
+                "damage": "Borrowing or withdrawing against collateral the caller does not own.",
+            },
+            impact={
+                "attack_cost": "< 0.01 SOL (single transaction)",
+                "exploitability": "High — position addresses are public",
+                "breach_cost_context": "Unbound user positions are a recurring critical finding in lending audits.",
+            },
+            anchor_versions_affected="All versions (developer-side pattern)",
+            ecosystem_recommendations=[
+                "Make positions PDAs of (pool, user) so there is exactly one per user and pool",
+                "Re-derive the PDA in every instruction, not only at creation",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Derive positions from (pool, user) and re-derive them everywhere:\n"
+            "  #[account(mut, seeds = [b\"position\", pool.key().as_ref(), user.key().as_ref()], bump)]\n"
+            "  pub position: Account<'info, Position>,\n"
+            "  pub user: Signer<'info>,"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Anchor checks that a position account has the right type and owner "
+            "program, not whose position it is. Unless seeds or constraints tie "
+            "it to the signer, any position of the program is accepted."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. Victim deposits collateral into their position\n"
+            "2. Attacker calls borrow (or withdraw) passing the victim's position account\n"
+            "3. The collateral check reads the victim's deposits and the attacker receives the funds\n"
+            "4. Or: attacker opens several keypair positions and the pool sums the same collateral twice"
+        )
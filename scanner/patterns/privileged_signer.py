@@ -0,0 +1,225 @@
+"""
+ANCHOR-016: Privileged Instruction Without Authority Signer
+
+Flags instruction handlers that write admin-controlled state — authority
+fields, fee parameters, pause flags — on a program account without the
+stored authority being required to sign:
+
+    pub fn set_fee(ctx: Context<SetFee>, fee_rate: u32) -> Result<()> {
+        ctx.accounts.pool.fee_rate = fee_rate;       // admin-controlled
+        Ok(())
+    }
+
+    #[derive(Accounts)]
+    pub struct SetFee<'info> {
+        #[account(mut, has_one = authority)]
+        pub pool: Account<'info, Pool>,
+        pub authority: UncheckedAccount<'info>,     // matched, never signs
+    }
+
+The written account's type is correlated with its #[account] struct: its
+authority-like Pubkey fields (`Pool.authority`, `Config.admin`...) name the
+account expected to sign. A handler is reported when that account is passed
+as AccountInfo/UncheckedAccount, or when the instruction takes no signer at
+all. Accounts created in the same instruction (`init`, `zero`) are skipped,
+as is a body that checks `is_signer` itself.
+
+Paying out of a PDA-owned vault is privileged too. Helpers that sign with
+PDA seeds — `transfer_from_pool_vault_to_user`, or anything calling it — are
+summarized once per file (patterns/signing.py), and an instruction that
+reaches one, at any depth, without taking a signer is reported at its call.
+"""
+
+import re
+from scanner.patterns.account_types import account_type
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+from scanner.patterns.signing import pda_signers, signing_calls
+
+AUTHORITY_FIELD = r"\w*(?:authority|admin|owner|operator|manager)\w*"
+ADMIN_FIELD_RE = re.compile(
+    rf"^(?:{AUTHORITY_FIELD}|\w*fee_(?:rate|bps|numerator|denominator|pct|percent|tier)\w*|"
+    r"\w*(?:pause|paused|frozen|halted)\w*|emergency\w*|\w*_enabled|\w*_disabled)$"
+)
+AUTHORITY_FIELD_RE = re.compile(rf"^{AUTHORITY_FIELD}$")
+
+
+class PrivilegedSignerPattern(VulnerabilityPattern):
+    id = "ANCHOR-016"
+    name = "Privileged Instruction Without Authority Signer"
+    severity = "High"
+    category = "accounts"
+    remediation_effort = "mechanical"
+    description = (
+        "An instruction writes admin-controlled state (authority, fee "
+        "parameters, pause flags) without requiring the stored authority "
+        "to sign."
+    )
+
+    STATE_STRUCT_RE = re.compile(
+        r"#\[account(?:\([^)]*\))?\]\s*(?:#\[[^\]]*\]\s*)*pub\s+struct\s+(\w+)\s*\{"
+    )
+    ALIAS_RE = re.compile(
+        r"let\s+(?:mut\s+)?(\w+)\s*(?::[^=]+)?=\s*&?\s*(?:mut\s+)?ctx\s*\.\s*accounts\s*\.\s*(\w+)"
+    )
+    WRITE_RE = re.compile(r"\b(\w+)\s*\.\s*(\w+)\s*=(?!=)")
+    DIRECT_WRITE_RE = re.compile(r"\bctx\s*\.\s*accounts\s*\.\s*(\w+)\s*\.\s*(\w+)\s*=(?!=)")
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        facts = Facts(content)
+        authorities = self._state_authorities(content)
+        signers = pda_signers(content, depth=self.max_depth)
+        structs = {struct.name: struct for struct in facts.accounts_structs()}
+        payout_structs = set()
+
+        # Handlers before the entrypoints that forward to them, so a payout is reported where it happens
+        for ix in sorted(facts.instructions(), key=lambda ix: ix.is_entrypoint):
+            accounts = {a.name: a for a in facts.accounts_of(ix)}
+            if not accounts or re.search(r"\.\s*is_signer\b", ix.body):
+                continue
+            for target, fields, offset in self._admin_writes(ix, accounts):
+                account = accounts[target]
+                if account.has("init") or account.has("zero"):
+                    continue
+                problem = self._missing_signer(account, accounts, authorities)
+                if not problem:
+                    continue
+                line = self._get_line_number(content, ix.body_start + offset)
+                findings.append(self._finding(
+                    file_path, content, ix, f"writes {', '.join(fields)} on '{account.name}'", problem, line,
+                    {
+                        "before": f"{account.name}.{fields[0]} set by the protocol admin",
+                        "after": f"{account.name}.{fields[0]} set by an arbitrary caller",
+                        "damage": "Attacker takes over admin control: fees, pause state or the authority itself.",
+                    },
+                ))
+                break
+
+            if ix.accounts_struct in payout_structs or self._takes_signer(ix.accounts_struct, structs):
+                continue
+            for offset, name in signing_calls(ix.body, signers):
+                if name == ix.name:
+                    continue
+                summary = signers[name]
+                line = self._get_line_number(content, ix.body_start + offset)
+                findings.append(self._finding(
+                    file_path, content, ix,
+                    f"reaches a PDA-signed CPI through {' → '.join(summary.chain)} "
+                    f"(signs with {summary.seeds})",
+                    "the instruction takes no signer", line,
+                    {
+                        "before": "PDA-owned vault pays out on behalf of its owner",
+                        "after": f"Any caller drives {summary.chain[-1]}",
+                        "damage": "Attacker moves whatever the program's PDA signs for.",
+                    },
+                ))
+                payout_structs.add(ix.accounts_struct)
+                break
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    def _state_authorities(self, content: str) -> dict:
+        """#[account] struct name -> its authority-like fields."""
+        result = {}
+        for m in self.STATE_STRUCT_RE.finditer(content):
+            end = self._find_block_end(content, m.end() - 1)
+            body = content[m.end():end] if end != -1 else ""
+            result[m.group(1)] = [
+                name for name in re.findall(r"pub\s+(\w+)\s*:\s*Pubkey\b", body)
+                if AUTHORITY_FIELD_RE.match(name)
+            ]
+        return result
+
+    def _admin_writes(self, ix, accounts: dict) -> list:
+        """[(account name, [admin fields written], first offset)] in body order."""
+        aliases = {m.group(1): m.group(2) for m in self.ALIAS_RE.finditer(ix.body)
+                   if m.group(2) in accounts}
+        writes = {}
+        for m in self.DIRECT_WRITE_RE.finditer(ix.body):
+            if m.group(1) in accounts and ADMIN_FIELD_RE.match(m.group(2)):
+                writes.setdefault(m.group(1), ([], m.start()))[0].append(m.group(2))
+        for m in self.WRITE_RE.finditer(ix.body):
+            target = aliases.get(m.group(1))
+            if target and ADMIN_FIELD_RE.match(m.group(2)):
+                writes.setdefault(target, ([], m.start()))[0].append(m.group(2))
+        return sorted(
+            ((name, sorted(set(fields)), offset) for name, (fields, offset) in writes.items()),
+            key=lambda w: w[2],
+        )
+
+    def _missing_signer(self, account, accounts: dict, authorities: dict):
+        """Why the write is unauthorized, or None when a signer guards it."""
+        inner = account.inner_type
+        stored = authorities.get(inner, []) if inner else []
+        expected = [c.value for c in account.constraints if c.kind == "has_one"]
+        expected += [name for name in stored if name in accounts]
+        for name in expected:
+            candidate = accounts.get(name)
+            if candidate is not None and not candidate.is_signer and account_type(candidate.type).is_raw:
+                owner = f"{inner}.{name}" if inner and name in stored else name
+                return f"the stored authority {owner} is passed as {candidate.wrapper} and never signs"
+        if not any(a.is_signer for a in accounts.values()):
+            owners = ", ".join(f"{inner}.{n}" for n in stored) if inner and stored else ""
+            return "the instruction takes no signer" + (f" (expected {owners})" if owners else "")
+        return None
+
+    def _takes_signer(self, name: str, structs: dict, seen: frozenset = frozenset()) -> bool:
+        """Some account of the struct, or of a nested accounts struct, signs."""
+        for account in structs[name].accounts if name in structs else []:
+            nested = account.wrapper
+            if account.is_signer or (
+                nested in structs and nested not in seen
+                and self._takes_signer(nested, structs, seen | {name})
+            ):
+                return True
+        return False
+
+    def _finding(self, file_path, content, ix, action, problem, line, state) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=self.severity,
+            file=file_path,
+            line=line,
+            description=f"Instruction '{ix.name}' {action}, but {problem}. Anyone can call it.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state=state,
+            impact={
+                "attack_cost": "Transaction fees only",
+                "exploitability": "High — a single unsigned call",
+                "breach_cost_context": "Missing signer checks on admin paths are among the most common Solana audit criticals.",
+            },
+            anchor_versions_affected="All versions (developer-side pattern)",
+            ecosystem_recommendations=[
+                "Type the authority account as Signer<'info>",
+                "Tie it to the stored authority with has_one or an address constraint",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Require the stored authority to sign and tie it to the state account:\n"
+            "  #[account(mut, has_one = authority)]\n"
+            "  pub pool: Account<'info, Pool>,\n"
+            "  pub authority: Signer<'info>,"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "has_one only checks that the passed account's key equals the stored "
+            "field; it says nothing about who signed. Without a Signer<'info> tied "
+            "to the stored authority, the admin instruction is permissionless."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. Attacker reads the stored authority pubkey from the pool account\n"
+            "2. Attacker passes that pubkey as the (unsigned) authority account\n"
+            "3. has_one passes; no signature is checked\n"
+            "4. Attacker sets the fee to 100%, pauses the pool, or installs themselves as authority"
+        )
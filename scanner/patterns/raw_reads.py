@@ -0,0 +1,190 @@
+"""
+Interprocedural summary of raw byte reads of account data.
+
+Not every read of account data goes through Account<T>. Raydium reads vault
+balances in a helper (check_unclaimed_fees_and_vault):
+
+    let token_vault_0_amount = spl_token_2022::extension::StateWithExtensions::<
+        spl_token_2022::state::Account,
+    >::unpack(token_vault_0.try_borrow_data()?.deref())?.base.amount;
+
+and auction-house slices token accounts by offset:
+
+    let data = token_account_info.try_borrow_data()?;
+    let key_data = array_ref![data, 76, 32];
+
+raw_reads() finds such reads of a function's parameters and of
+`ctx.accounts.<field>` / `self.<field>`:
+
+  - `T::unpack(..)`, `StateWithExtensions::<T>::unpack(..)` (also
+    `unpack_from_slice`, `unpack_unchecked`)
+  - `bytemuck::from_bytes(..)` and friends
+  - borsh `T::try_from_slice(..)`
+  - `data[a..b]` and `array_ref![data, off, len]` on a `try_borrow_data()`
+    or `data.borrow()` of the account
+
+Parameters are followed through local calls to a fixed point, as in
+validation.py, and handler and method arguments are resolved to
+accounts-struct fields. Reads of a value whose owner the function checks
+first are dropped. Each read keeps the byte range it covers, so a finding
+can say which bytes were trusted.
+"""
+
+import re
+from dataclasses import dataclass, field
+
+from scanner.budget import DEFAULT_DEPTH
+from scanner.patterns.base import VulnerabilityPattern
+from scanner.patterns.facts import Facts
+from scanner.patterns.validation import (
+    CALL_RE, DISCRIMINATOR_RE, OWNER, _balanced, _checks_on, _param_names, _split_top,
+)
+from scanner.patterns.validation import _summaries as validation_summaries
+
+RAW_PARAM_RE = re.compile(r"\b(?:AccountInfo|UncheckedAccount)\b")
+# `.try_borrow_data()?`, `.data.borrow()` (also _mut), optionally after `.to_account_info()`
+BORROW_RE = (
+    r"(?:\s*\.\s*to_account_info\s*\(\s*\))?\s*\.\s*"
+    r"(?:try_borrow(?:_mut)?_data\s*\(\s*\)\s*\??|data\s*\.\s*borrow(?:_mut)?\s*\(\s*\))"
+)
+# (type or crate, decoding function)
+DECODERS = [
+    re.compile(r"((?:\w+\s*::\s*)*\w+(?:\s*::\s*<[^;]*?>)?)\s*::\s*(unpack(?:_from_slice|_unchecked)?)\s*\("),
+    re.compile(r"\b(bytemuck)\s*::\s*((?:try_)?(?:from_bytes|pod_from_bytes)(?:_mut)?)\s*\("),
+    re.compile(r"(\w+)\s*::\s*(try_from_slice)\s*\("),
+]
+RANGE_RE = re.compile(r"\[\s*([^\[\]]*\.\.[^\[\]]*)\]")
+
+
+@dataclass
+class RawRead:
+    """One read of account bytes that skips Anchor's owner and discriminator checks."""
+
+    function: str   # function the read is in
+    how: str        # "StateWithExtensions::<Account>::unpack", "array_ref!", "slice", ...
+    bytes: str      # "0..32", "8..size_of::<PoolState>() + 8", or "all"
+    offset: int     # offset of the read in the unit
+    # Callers from the resolved function down to `function`
+    chain: list = field(default_factory=list)
+
+    def describe(self) -> str:
+        where = " -> ".join(self.chain + [self.function]) + "()"
+        span = "all bytes" if self.bytes == "all" else f"bytes [{self.bytes}]"
+        return f"{span} via {self.how} in {where}"
+
+
+def raw_reads(content: str, depth: int = DEFAULT_DEPTH) -> dict:
+    """{(accounts struct, field): [RawRead]} for fields whose data is read as raw bytes,
+    followed into helpers at most `depth` calls away."""
+    functions = VulnerabilityPattern._find_functions(content)
+    checks = validation_summaries(functions, depth)
+    summaries = _summaries(functions, checks, depth)
+    impls = VulnerabilityPattern._find_impl_blocks(content)
+    structs = {name for name, _, _ in VulnerabilityPattern._find_derive_accounts_structs(content)}
+
+    facts = Facts(content)
+
+    result = {}
+    for fn in functions:
+        ix = facts.instruction_of(fn)
+        if ix:
+            struct, base = ix.accounts_struct, r"ctx\s*\.\s*accounts"
+        else:
+            struct = next((name for s, e, name in impls if s <= fn["body_start"] < e), "")
+            base = r"self"
+        if struct not in structs:
+            continue
+        for name in set(re.findall(base + r"\s*\.\s*(\w+)", fn["body"])):
+            subject = base + r"\s*\.\s*" + re.escape(name) + r"\b"
+            if OWNER in _checks_on(subject, fn["body"], checks):
+                continue
+            reads = _reads_of(subject, fn, summaries)
+            if reads:
+                result.setdefault((struct, name), []).extend(reads)
+    return result
+
+
+def _summaries(functions: list, checks: dict, depth: int = DEFAULT_DEPTH) -> dict:
+    """{function name: {param index: [RawRead]}}, iterated over calls to a fixed point
+    or `depth` rounds (see validation._summaries)."""
+    summaries = {fn["name"]: {} for fn in functions}
+    for _ in range(min(len(functions) + 1, depth)):
+        changed = False
+        previous = {name: dict(summary) for name, summary in summaries.items()}
+        for fn in functions:
+            for i, (name, type_) in enumerate(_param_names(fn["params"])):
+                if not RAW_PARAM_RE.search(type_) or i in summaries[fn["name"]]:
+                    continue
+                subject = rf"\b{re.escape(name)}\b"
+                if OWNER in _checks_on(subject, fn["body"], checks):
+                    continue
+                reads = _reads_of(subject, fn, previous)
+                if reads:
+                    summaries[fn["name"]][i] = reads
+                    changed = True
+        if not changed:
+            break
+    return summaries
+
+
+def _reads_of(subject: str, fn: dict, summaries: dict) -> list:
+    """Reads of the value matched by `subject` in fn, directly or through its callees."""
+    body, start = fn["body"], fn["body_start"]
+    reads = []
+    data_refs = [subject + BORROW_RE]
+    for m in re.finditer(rf"let\s+(?:mut\s+)?(\w+)\s*(?::[^=;]+)?=\s*&?\s*(?:mut\s+)?{subject}{BORROW_RE}", body):
+        data_refs.append(rf"(?<![\w.]){re.escape(m.group(1))}\b")
+    data = "(?:" + "|".join(data_refs) + ")"
+
+    decoded = []
+    for decoder_re in DECODERS:
+        for m in decoder_re.finditer(body):
+            arg = _balanced(body, m.end() - 1)
+            if not re.search(data, arg):
+                continue
+            rng = RANGE_RE.search(arg)
+            how = f"{_short(m.group(1))}::{m.group(2)}"
+            reads.append(RawRead(fn["name"], how, _range(rng.group(1)) if rng else "all", start + m.start()))
+            decoded.append((m.start(), m.end() + len(arg) + 1))
+
+    for m in re.finditer(rf"\barray_ref!\s*\[\s*{data}\s*,\s*([^,\]]+),\s*([^\]]+)\]", body):
+        off, length = m.group(1).strip(), m.group(2).strip()
+        try:
+            span = f"{int(off.replace('_', ''))}..{int(off.replace('_', '')) + int(length.replace('_', ''))}"
+        except ValueError:
+            span = f"{off}..{off} + {length}"
+        reads.append(RawRead(fn["name"], "array_ref!", span, start + m.start()))
+
+    for m in re.finditer(rf"{data}\s*\[\s*([^\[\]]*\.\.[^\[\]]*)\](?!\s*\.\s*copy_from_slice|\s*=[^=])", body):
+        if any(s <= m.start() < e for s, e in decoded):
+            continue
+        # `T::try_deserialize(&mut &data[..])` checks the discriminator: not a raw read
+        stmt_start = max(body.rfind(c, 0, m.start()) for c in ";{}") + 1
+        if DISCRIMINATOR_RE.search(body[stmt_start:m.start()]):
+            continue
+        reads.append(RawRead(fn["name"], "slice", _range(m.group(1)), start + m.start()))
+
+    for stmt_match in re.finditer(r"[^;{}]+", body):
+        stmt = stmt_match.group(0)
+        for call in CALL_RE.finditer(stmt):
+            summary = summaries.get(call.group(1))
+            if not summary or call.group(1) == fn["name"]:
+                continue
+            args = _split_top(_balanced(stmt, call.end() - 1))
+            for i, arg in enumerate(args):
+                if i in summary and re.search(subject + r"(?!\s*\.\s*key\s*\()", arg):
+                    reads += [RawRead(r.function, r.how, r.bytes, r.offset, [fn["name"]] + r.chain)
+                              for r in summary[i]]
+    reads.sort(key=lambda r: r.offset)
+    return reads
+
+
+def _range(text: str) -> str:
+    return re.sub(r"\s+", " ", text).strip()
+
+
+def _short(path: str) -> str:
+    """`spl_token_2022::extension::StateWithExtensions::<spl_token_2022::state::Account,>`
+    -> `StateWithExtensions::<Account>`."""
+    text = re.sub(r",?>", ">", re.sub(r"\s+", "", path))
+    return re.sub(r"\b(?:\w+::)+(?=\w+\b(?!::\w))", "", text)
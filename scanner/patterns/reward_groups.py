@@ -0,0 +1,223 @@
+"""
+ANCHOR-033: Misaligned Reward Account Groups
+
+Programs with several reward tokens take one group of accounts per reward
+from `remaining_accounts` and pay reward `i` from group `i` (Raydium's
+`collect_rewards`):
+
+    let count = remaining_accounts.len() / 2;
+    let mut accounts = remaining_accounts.iter();
+    for i in 0..count {
+        let reward_vault = InterfaceAccount::<TokenAccount>::try_from(accounts.next().unwrap())?;
+        let recipient = InterfaceAccount::<TokenAccount>::try_from(accounts.next().unwrap())?;
+        // reward_vault is never compared with pool.reward_infos[i].token_vault
+        transfer_from_pool_vault_to_user(pool, &reward_vault, &recipient, owed[i])?;
+    }
+
+Nothing in the accounts struct describes these accounts, so the loop is the
+only place their position can be checked. For every loop over account
+groups — `for i in 0..len / N`, `chunks(N)`, `chunks_exact(N)` — whose body
+indexes reward state with the loop index, the rule reports:
+
+  - a vault taken from the group (a `*vault*` binding, or the `from` of a
+    transfer) that is never compared by key with an entry indexed by the
+    loop variable (`require_keys_eq!(vault.key(), reward_infos[i].token_vault)`):
+    the caller can pay reward 0 out of reward 1's vault
+  - group math nothing validates: no check that the number of remaining
+    accounts equals, or is a multiple of, the group size, in the function
+    or the helpers it calls (Raydium's `check_required_accounts_length`);
+    a partial group is silently dropped and missing groups go unnoticed
+  - a `continue` that skips the rest of a group: in a loop taking accounts
+    with `.next()`, a `continue` before the group's last `.next()` leaves
+    its remaining accounts to be read as the next reward's group
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.validation import CALL_RE
+
+LOOP_RE = re.compile(r"\bfor\s+(?:\(\s*(\w+)\s*,\s*(\w+)\s*\)|(\w+))\s+in\s+([^{;]+?)\s*\{")
+RANGE_RE = re.compile(r"^0\s*\.\.\s*\(?\s*([\w.()]+)\s*\)?\s*/\s*([\w.]+)$")
+CHUNKS_RE = re.compile(r"([\w.]+)\s*\.\s*(chunks|chunks_exact)\s*\(\s*([\w.]+)\s*\)\s*\.\s*enumerate\s*\(\s*\)$")
+VAULT_RE = re.compile(r"(?i)vault")
+LENGTH = r"\b(?:\w+_)?len(?:gth)?\b"
+# A comparison or remainder test on a length, or chunks_exact's remainder()
+LENGTH_CHECK_RE = re.compile(rf"{LENGTH}[^;{{]*?(?:==|!=|%)|(?:==|!=)[^;{{]*?{LENGTH}|\bremainder\s*\(\s*\)")
+
+
+class RewardGroupPattern(VulnerabilityPattern):
+    id = "ANCHOR-033"
+    name = "Misaligned Reward Account Groups"
+    severity = "High"
+    category = "accounts"
+    remediation_effort = "localized"
+    description = (
+        "A loop paying several rewards from groups of remaining accounts does "
+        "not bind each group's vault to its reward entry by key, does not "
+        "validate the group count, or lets a partial group shift the index."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        functions = self._find_functions(content)
+        for fn in functions:
+            body = fn["body"]
+            remaining = self._remaining_names(body, fn["params"])
+            if not remaining:
+                continue
+            for m in LOOP_RE.finditer(body):
+                index = m.group(1) or m.group(3)
+                header = " ".join(m.group(4).split())
+                group = self._group(header, body, remaining, m.group(2))
+                if not group:
+                    continue
+                end = self._find_block_end(body, m.end() - 1)
+                loop = body[m.end():end]
+                if not re.search(rf"\b\w*reward\w*\s*\[\s*{index}\s*\]", loop):
+                    continue
+                issues = self._issues(loop, index, group, remaining, body, functions, fn["name"])
+                if not issues:
+                    continue
+                line = self._get_line_number(content, fn["body_start"] + m.start())
+                severity = self.severity if any(high for high, _ in issues) else "Medium"
+                findings.append(self._finding(
+                    file_path, content, line, severity,
+                    f"Reward loop in '{fn['name']}' over groups of {group[1]} remaining accounts: "
+                    + "; ".join(text for _, text in issues),
+                ))
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    def _remaining_names(self, body: str, params: str) -> set:
+        """`remaining_accounts` and the bindings and iterators built from it."""
+        names = set(re.findall(r"\b(\w*remaining_accounts\w*)\b", params + " " + body))
+        for _ in range(2):
+            for name, value in re.findall(r"\blet\s+(?:mut\s+)?(\w+)\s*(?::[^=]+)?=\s*([^;]+);", body):
+                if any(re.search(rf"\b{re.escape(n)}\b", value) for n in names):
+                    names.add(name)
+        return names
+
+    def _group(self, header: str, body: str, remaining: set, chunk: str):
+        """(kind, group size, chunk binding) of a loop over account groups, None for any other loop."""
+        bound = re.fullmatch(r"0\s*\.\.\s*(\w+)", header)
+        value = bound and re.search(rf"\blet\s+(?:mut\s+)?{bound.group(1)}\s*(?::[^=]+)?=\s*([^;]+);", body)
+        if value:
+            header = "0.." + " ".join(value.group(1).split())
+        m = RANGE_RE.match(header)
+        if m:
+            count = m.group(1)
+            bound = re.search(rf"\blet\s+(?:mut\s+)?{re.escape(count)}\s*(?::[^=]+)?=\s*([^;]+);", body)
+            source = bound.group(1) if bound else count
+            if re.search(r"\blen\s*\(", source) and any(re.search(rf"\b{re.escape(n)}\b", source) for n in remaining):
+                return "range", m.group(2), None
+            return None
+        m = CHUNKS_RE.search(header)
+        if m and m.group(1).split(".")[-1] in remaining and chunk:
+            return m.group(2), m.group(3), chunk
+        return None
+
+    def _issues(self, loop, index, group, remaining, body, functions, name) -> list:
+        """[(high, text)] for one reward loop."""
+        issues = []
+        kind, size, chunk = group
+        sources = remaining | ({chunk} if chunk else set())
+        taken = [n for n, value in re.findall(r"\blet\s+(?:mut\s+)?(\w+)\s*(?::[^=]+)?=\s*([^;]+);", loop)
+                 if re.search(rf"\b(?:{'|'.join(map(re.escape, sources))})\b|\bnext_account_info\s*\(", value)]
+        payers = set(re.findall(r"\bfrom\s*:\s*&?\s*(\w+)", loop))
+        vaults = [n for n in taken if VAULT_RE.search(n) or n in payers]
+        unbound = [v for v in vaults if not any(
+            re.search(rf"\b{v}\b", s) and re.search(rf"\[\s*{index}\s*\]", s)
+            and re.search(r"==|!=|require_keys_(?:eq|neq)!", s) for s in self._statements(loop))]
+        if unbound:
+            issues.append((True, f"'{unbound[0]}' taken for reward {index} is never compared by key with the "
+                                 f"reward entry [{index}], so the caller chooses which vault pays which reward"))
+
+        if not self._validates_length(body, functions, self.max_depth, {name}):
+            how = (f"len / {size} drops a partial group" if kind == "range"
+                   else f"chunks_exact({size}) drops a partial group" if kind == "chunks_exact"
+                   else f"chunks({size}) yields a short last group")
+            issues.append((False, f"nothing checks that the number of remaining accounts is a multiple of "
+                                  f"{size} or matches the reward count: {how}, and missing groups go unnoticed"))
+
+        nexts = [m.start() for m in re.finditer(r"\.\s*next\s*\(\s*\)|\bnext_account_info\s*\(", loop)]
+        if nexts and any(m.start() < nexts[-1] for m in re.finditer(r"\bcontinue\b", loop)):
+            issues.append((True, "`continue` before the group's last account is taken leaves the rest of the "
+                                 "group to be read as the next reward's accounts"))
+        return issues
+
+    @staticmethod
+    def _statements(loop: str) -> list:
+        """Statements of the loop, a macro call with its arguments counting as one."""
+        return [s for s in re.split(r";|\{|\}", loop) if s.strip()]
+
+    def _validates_length(self, body: str, functions: list, depth: int, seen: set) -> bool:
+        """A length comparison that can fail, here or in a local helper up to depth."""
+        for stmt in re.findall(r"(?:if|require\w*!|assert\w*!)[^{;]*", body):
+            if LENGTH_CHECK_RE.search(stmt):
+                return True
+        if depth <= 1:
+            return False
+        for m in CALL_RE.finditer(body):
+            fn = next((f for f in functions if f["name"] == m.group(1) and f["name"] not in seen), None)
+            if fn and self._validates_length(fn["body"], functions, depth - 1, seen | {fn["name"]}):
+                return True
+        return False
+
+    def _finding(self, file_path, content, line, severity, message) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=severity,
+            file=file_path,
+            line=line,
+            description=f"{message}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "Reward 0 owed 100 of token A, reward 1 owed 100 of token B",
+                "after": "Caller passes reward 1's vault in group 0: 100 units of B paid against A's accounting",
+                "damage": "Rewards paid from the wrong vault; the expensive reward drains at the cheap one's rate.",
+            },
+            impact={
+                "attack_cost": "Transaction fees only",
+                "exploitability": "High — remaining accounts are entirely caller-supplied",
+                "breach_cost_context": "Remaining-accounts loops sit outside every Anchor constraint.",
+            },
+            anchor_versions_affected="All versions (remaining_accounts are never validated by Anchor)",
+            ecosystem_recommendations=[
+                "Compare each group's vault with reward_infos[i].token_vault before paying",
+                "Require remaining_accounts.len() == reward_count * group_size before the loop",
+                "Take all of a group's accounts before any early continue",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Validate the group count and bind each vault to its reward entry:\n"
+            "  require!(remaining_accounts.len() == valid_rewards * GROUP, ErrorCode::InvalidAccountNumber);\n"
+            "  for i in 0..valid_rewards {\n"
+            "      let vault = InterfaceAccount::<TokenAccount>::try_from(accounts.next().unwrap())?;\n"
+            "      let recipient = InterfaceAccount::<TokenAccount>::try_from(accounts.next().unwrap())?;\n"
+            "      require_keys_eq!(vault.key(), pool.reward_infos[i].token_vault);\n"
+            "      if owed[i] == 0 { continue; }\n"
+            "      ..\n"
+            "  }"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Accounts in remaining_accounts carry no constraints: their order is "
+            "whatever the caller sent. A loop that pays reward i from group i "
+            "must itself check that the group belongs to reward i and that the "
+            "groups line up with the rewards."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. Pool has two rewards; reward 1's token is worth far more than reward 0's\n"
+            "2. Attacker calls collect with reward 1's vault in group 0\n"
+            "3. The loop pays reward 0's owed amount out of reward 1's vault\n"
+            "4. Repeating drains the valuable reward vault at the cheap reward's rate"
+        )
@@ -0,0 +1,391 @@
+"""
+ANCHOR-052: Rounding Direction Favors User
+
+Every integer division rounds, and the remainder goes to someone. It
+should stay with the program: amounts the user pays (fees, debt, shares
+burned for a withdrawal) round up, amounts the user receives (withdrawals,
+rewards, shares minted for a deposit) round down:
+
+    let fee = amount * fee_bps / 10_000;            // floored: a 9-unit trade pays no fee
+    let amount_out = amount - amount * fee_bps / 10_000;  // same fee, kept by the user
+    let assets = shares.div_ceil(total_shares) ...  // ceiled payout: each redeem takes a unit extra
+
+Each rounding site in a value calculation is classified by beneficiary
+from the value it computes — the `let` or assignment target, else the
+function name:
+
+  - paid by the user (should round up): fee, debt, cost, required,
+    repay, premium, penalty, tax, amount_in, ...
+  - received by the user (should round down): withdraw, redeem, payout,
+    reward, claim, refund, proceeds, amount_out, and the fees a position
+    earns (fee_growth, fees_owed, ...)
+  - shares and plain amounts by the instruction's direction: shares
+    computed in a deposit are minted to the user (down), shares computed
+    in a withdrawal are burned from them (up), and the reverse for assets
+
+Running totals (`total_*`) are left out: they record amounts rounded
+where they were computed.
+A division subtracted from the value (`amount - fee`, `checked_sub`, `-=`)
+moves it the other way. Rounding is read from the operator: `/`,
+checked_div and mul_div_floor round down; div_ceil, checked_ceil_div,
+mul_div_ceil and the `(a + b - 1) / b` idiom round up; try_round_u64
+rounds to nearest. The rule reports (Medium) each site whose rounding
+favors the user, with the direction it should take; `rounding TARGET`
+lists every classified site, reported or not.
+"""
+
+import re
+from dataclasses import dataclass
+
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.math_summary import FLOAT_RE, _blank
+from scanner.patterns.mul_chain import CAST_STRIP_RE, FACTOR
+
+# Fees a position earns (an LP's fee growth) are received, not paid
+EARNED_FEE_RE = re.compile(r"fee_growth|fees?_owed|fees?_earned|earned_fees?|token_fees|fees?_(?:token_)?[01ab]$", re.I)
+PAID_RE = re.compile(r"fee|debt|cost|required|repay|premium|penalty|tax|commission|amount_in|_in$|^in_", re.I)
+RECEIVED_RE = re.compile(
+    r"withdraw|redeem|payout|pay_out|reward|claim|refund|rebate|proceeds|amount_out|_out$|^out_|"
+    r"to_mint|minted|receive|earned|dividend|emission|yield",
+    re.I,
+)
+SHARES_RE = re.compile(r"shares?|^lp(?:_|$)|_lp(?:_|$)|pool_tokens?|receipt|ctokens?", re.I)
+ASSETS_RE = re.compile(r"^(?:amount|assets?|tokens?|lamports|underlying\w*|quantity|\w+_amount)$", re.I)
+# Running totals record amounts already rounded where they were computed
+TOTAL_RE = re.compile(r"total", re.I)
+# Instruction direction, checked in this order: `unstake` is an outflow
+OUT_FN_RE = re.compile(r"withdraw|redeem|unstake|remove_liquidity|burn|sell|exit|leave|claim|harvest|collect", re.I)
+IN_FN_RE = re.compile(r"deposit|stake|mint|add_liquidity|provide|supply|lend|buy|contribute|subscribe", re.I)
+# Method (or free function) name -> rounding direction
+METHODS = {
+    "div": "floor", "checked_div": "floor", "saturating_div": "floor", "mul_div_floor": "floor",
+    "try_floor_div": "floor", "div_floor": "floor", "div_down": "floor", "mul_div_down": "floor",
+    "div_ceil": "ceil", "checked_ceil_div": "ceil", "ceil_div": "ceil", "try_ceil_div": "ceil",
+    "mul_div_ceil": "ceil", "div_up": "ceil", "mul_div_up": "ceil",
+    "try_round_u64": "nearest", "try_round_u128": "nearest", "round_div": "nearest",
+}
+# The same operation rounding up / down
+TO_CEIL = {
+    "div": "div_ceil", "checked_div": "div_ceil", "saturating_div": "div_ceil", "mul_div_floor": "mul_div_ceil",
+    "try_floor_div": "try_ceil_div", "div_floor": "div_ceil", "div_down": "div_up", "mul_div_down": "mul_div_up",
+    "try_round_u64": "try_ceil_u64", "try_round_u128": "try_ceil_u128", "round_div": "div_ceil",
+}
+TO_FLOOR = {
+    "div_ceil": "checked_div", "checked_ceil_div": "checked_div", "ceil_div": "checked_div",
+    "try_ceil_div": "try_floor_div", "mul_div_ceil": "mul_div_floor", "div_up": "div_down",
+    "mul_div_up": "mul_div_down", "try_round_u64": "try_floor_u64", "try_round_u128": "try_floor_u128",
+    "round_div": "checked_div",
+}
+METHOD_RE = re.compile(rf"(?:\.\s*|(?<![\w.]))({'|'.join(sorted(METHODS, key=len, reverse=True))})\s*(?:::<[^>]*>)?\s*\(")
+DIV_OP_RE = re.compile(r"(?<![/*])/(?![/*=])")
+COMPARISON_RE = re.compile(r"\s(?:[<>]=?|==|!=)\s")
+SKIP_STMT_RE = re.compile(r"^\s*(?:if|while|for|match|require\w*!|assert\w*!|msg!|emit!|panic!)\b")
+
+
+@dataclass
+class RoundingSite:
+    """One division in a value calculation, and which way it should round."""
+
+    file: str
+    line: int
+    function: str
+    expression: str
+    # Value the division computes: the `let` / assignment target, else the function
+    value: str
+    # "protocol" (the user pays the value) or "user" (the user receives it)
+    beneficiary: str
+    # floor | ceil | nearest
+    rounds: str
+    # Direction the division itself should take
+    should: str
+    # The division is subtracted from the value
+    subtracted: bool = False
+    # `/` or the rounding method called
+    operation: str = "/"
+
+    @property
+    def favors_user(self) -> bool:
+        return self.rounds != self.should
+
+    def to_dict(self) -> dict:
+        return {
+            "file": self.file, "line": self.line, "function": self.function, "expression": self.expression,
+            "value": self.value, "beneficiary": self.beneficiary, "rounds": self.rounds,
+            "should": self.should, "subtracted": self.subtracted, "operation": self.operation,
+            "favors_user": self.favors_user,
+        }
+
+
+def beneficiary(value: str, fn_name: str, from_target: bool = True) -> str:
+    """"protocol" when the user pays the value, "user" when they receive it, else ""."""
+    name = value.split(".")[-1]
+    if TOTAL_RE.search(name):
+        return ""
+    if EARNED_FEE_RE.search(name):
+        return "user"
+    if PAID_RE.search(name):
+        return "protocol"
+    if RECEIVED_RE.search(name):
+        return "user"
+    if not from_target:
+        return ""
+    outflow = bool(OUT_FN_RE.search(fn_name) or RECEIVED_RE.search(fn_name))
+    if not outflow and not IN_FN_RE.search(fn_name):
+        return ""
+    if SHARES_RE.search(name):
+        return "protocol" if outflow else "user"
+    if ASSETS_RE.search(name):
+        return "user" if outflow else "protocol"
+    return ""
+
+
+class RoundingDirectionPattern(VulnerabilityPattern):
+    id = "ANCHOR-052"
+    name = "Rounding Direction Favors User"
+    severity = "Medium"
+    category = "arithmetic"
+    remediation_effort = "mechanical"
+    description = (
+        "A division rounds in the user's favor: an amount the user pays is rounded "
+        "down, or an amount the user receives is rounded up."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = [self._finding(file_path, content, site) for site in self.sites(file_path, content)
+                    if site.favors_user]
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    def sites(self, file_path: str, content: str) -> list[RoundingSite]:
+        """Every division in the file whose beneficiary is known, in source order."""
+        sites = []
+        code = _blank(content)
+        for fn in self._find_functions(content):
+            body = code[fn["body_start"]:fn["body_start"] + len(fn["body"])]
+            for m in re.finditer(r"[^;{}]+", body):
+                stmt = m.group(0)
+                if FLOAT_RE.search(stmt) or SKIP_STMT_RE.match(stmt) or COMPARISON_RE.search(stmt):
+                    continue
+                target, compound = self._target(stmt)
+                if target:
+                    owner = beneficiary(target, fn["name"])
+                else:
+                    owner = beneficiary(fn["name"], fn["name"], from_target=False)
+                if not owner:
+                    continue
+                for offset, start, end, rounds, method in self._divisions(stmt):
+                    subtracted = compound == "-" or self._subtracted(stmt, start)
+                    should = "ceil" if owner == "protocol" else "floor"
+                    if subtracted:
+                        should = "floor" if should == "ceil" else "ceil"
+                    sites.append(RoundingSite(
+                        file=file_path,
+                        line=self._get_line_number(content, fn["body_start"] + m.start() + offset),
+                        function=fn["name"],
+                        expression=self._text(stmt[start:end]),
+                        value=target or fn["name"],
+                        beneficiary=owner,
+                        rounds=rounds,
+                        should=should,
+                        subtracted=subtracted,
+                        operation=method,
+                    ))
+        sites.sort(key=lambda s: s.line)
+        return sites
+
+    @staticmethod
+    def _text(value: str) -> str:
+        return re.sub(r"\s*\.\s*", ".", " ".join(value.split()))
+
+    @staticmethod
+    def _target(stmt: str) -> tuple[str, str]:
+        """(assigned name, compound operator) of a `let` or assignment; ("", "") for an expression."""
+        m = re.match(r"\s*(?:let\s+(?:mut\s+)?(\w+)\s*(?::[^=]+)?=(?!=)|([\w.\[\]]+)\s*([-+*/]?)=(?!=))", stmt)
+        if not m:
+            return "", ""
+        return (m.group(1) or re.sub(r"\[.*?\]", "", m.group(2))), (m.group(3) or "")
+
+    def _divisions(self, stmt: str):
+        """(offset, start, end, rounds, method) per division: start/end bound the rounded expression."""
+        for m in DIV_OP_RE.finditer(stmt):
+            start = self._term_start(stmt, m.start())
+            end = self._operand_end(stmt, m.end())
+            if end is None or not stmt[start:m.start()].strip():
+                continue
+            dividend = stmt[start:m.start()]
+            ceil = re.search(r"-\s*1\s*\)\s*$", dividend) and "+" in dividend or \
+                re.search(r"\badd\s*\([^()]*-\s*1\s*\)", dividend)
+            yield m.start(), start, end, "ceil" if ceil else "floor", "/"
+        for m in METHOD_RE.finditer(stmt):
+            if re.search(r"\bfn\s*$", stmt[:m.start()]):
+                continue
+            close = self._matching(stmt, m.end() - 1)
+            if close is None:
+                continue
+            method = m.group(1)
+            start = self._term_start(stmt, m.start()) if stmt[m.start()] == "." else m.start()
+            yield m.start(), start, close + 1, METHODS[method], method
+
+    @staticmethod
+    def _matching(stmt: str, open_at: int):
+        depth = 0
+        for i in range(open_at, len(stmt)):
+            depth += {"(": 1, ")": -1}.get(stmt[i], 0)
+            if depth == 0:
+                return i
+        return None
+
+    def _operand_end(self, stmt: str, at: int):
+        """End of the divisor after `/`: a parenthesized group or a factor."""
+        rest = stmt[at:]
+        skipped = len(rest) - len(rest.lstrip())
+        if rest.lstrip().startswith("("):
+            close = self._matching(stmt, at + skipped)
+            return close + 1 if close is not None else None
+        m = re.match(rf"\s*{FACTOR}", rest)
+        return at + m.end() if m else None
+
+    @staticmethod
+    def _term_start(stmt: str, pos: int) -> int:
+        """Start of the product/quotient term ending at pos: back to `+ - = ,` or an open paren."""
+        depth, i = 0, pos
+        while i > 0:
+            c = stmt[i - 1]
+            if c in ")]":
+                depth += 1
+            elif c in "([":
+                if depth == 0:
+                    break
+                depth -= 1
+            elif depth == 0 and c in "+-=,{}|&<>":
+                break
+            i -= 1
+        while i < pos and stmt[i].isspace():
+            i += 1
+        return i
+
+    @staticmethod
+    def _subtracted(stmt: str, start: int) -> bool:
+        """The term at start is subtracted: `x - term`, `x.checked_sub(term)`."""
+        before = stmt[:start].rstrip()
+        if before.endswith("-"):
+            return True
+        return bool(before.endswith("(") and re.search(r"\b(?:checked_|saturating_|wrapping_)?sub\s*\($", before))
+
+    def _rewrite(self, site: RoundingSite) -> str:
+        """The site's expression rounding the way it should."""
+        expr = site.expression
+        if site.operation != "/":
+            other = (TO_CEIL if site.should == "ceil" else TO_FLOOR).get(site.operation, site.operation)
+            return re.sub(rf"\b{site.operation}\b", other, expr, count=1)
+        dividend, divisor = self._split_division(expr)
+        if site.should == "floor":
+            # The `(a + b - 1) / b` idiom: drop the bias
+            plain = re.sub(r"\s*\+\s*[^+()]+?\s*-\s*1\s*(\)\s*)$", r"\1", dividend)
+            return f"{plain} / {divisor}"
+        factors = self._factors(dividend)
+        if len(factors) == 2:
+            a, b = (self._text(CAST_STRIP_RE.sub("", f)) for f in factors)
+            c = self._text(CAST_STRIP_RE.sub("", divisor))
+            return (f"U128::from({a})\n      .mul_div_ceil(U128::from({b}), U128::from({c}))\n"
+                    "      .ok_or(ErrorCode::MathOverflow)?\n      .as_u64()")
+        return f"({dividend}).div_ceil({divisor})" if re.search(r"[-+*/\s]", dividend) else \
+            f"{dividend}.div_ceil({divisor})"
+
+    @staticmethod
+    def _split_division(expr: str) -> tuple[str, str]:
+        depth = 0
+        for i in range(len(expr) - 1, -1, -1):
+            depth += {")": 1, "(": -1}.get(expr[i], 0)
+            if expr[i] == "/" and depth == 0:
+                return expr[:i].strip(), expr[i + 1:].strip()
+        return expr, ""
+
+    @staticmethod
+    def _factors(expr: str) -> list:
+        """Top-level `*` factors, unwrapping one pair of outer parentheses."""
+        if expr.startswith("(") and expr.endswith(")") and \
+                RoundingDirectionPattern._matching(expr, 0) == len(expr) - 1:
+            expr = expr[1:-1]
+        factors, depth, current = [], 0, ""
+        for c in expr:
+            depth += {"(": 1, ")": -1}.get(c, 0)
+            if c == "*" and depth == 0:
+                factors.append(current.strip())
+                current = ""
+            else:
+                current += c
+        factors.append(current.strip())
+        if any(re.search(r"[-+/]", f.replace("->", "")) for f in factors):
+            return []
+        return factors
+
+    def _finding(self, file_path, content, site: RoundingSite) -> Finding:
+        value = f"`{site.value}`"
+        pays = site.beneficiary == "protocol"
+        role = f"{value} is paid by the user" if pays else f"{value} is paid out to the user"
+        rounds = {"floor": "rounds down", "ceil": "rounds up", "nearest": "rounds to nearest"}[site.rounds]
+        how = f"is subtracted from {value} and {rounds}" if site.subtracted else rounds
+        should = "up" if site.should == "ceil" else "down"
+        fix = (
+            f"Round {'the subtracted term ' if site.subtracted else ''}{should}, so the remainder stays "
+            f"with the program ({role}):\n"
+            f"  {self._rewrite(site)}"
+        )
+        if ".div_ceil(" in fix:
+            fix += "\nCheck the divisor is nonzero first: div_ceil panics on zero like `/`."
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=self.severity,
+            file=file_path,
+            line=site.line,
+            description=(
+                f"In {site.function}: `{site.expression}` {how}; {role}, so the rounding "
+                f"favors the user — it should round {should}."
+            ),
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=fix,
+            code_snippet=self._extract_snippet(content, site.line),
+            before_after_state={
+                "before": "fee_bps = 30, amount = 333 (fee = 333 * 30 / 10,000 = 0.999)",
+                "after": "fee = 0: the trade pays nothing",
+                "damage": "Splitting a trade into pieces under the rounding threshold skips the fee entirely.",
+            },
+            impact={
+                "attack_cost": "Transaction fees for many small calls",
+                "exploitability": "Medium — up to one unit per call, repeated as often as the attacker likes",
+                "breach_cost_context": "Rounding in the user's favor has been compounded into vault drains "
+                                       "on low-decimal and low-fee markets.",
+            },
+            anchor_versions_affected="All versions (program-side arithmetic)",
+            ecosystem_recommendations=[
+                "Round amounts the user pays up and amounts the user receives down",
+                "Use explicit mul_div_floor / mul_div_ceil helpers so the direction is visible at each site",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Round fees and other amounts the user pays up:\n"
+            "  let fee = U128::from(amount).mul_div_ceil(U128::from(fee_bps), U128::from(10_000))\n"
+            "      .ok_or(ErrorCode::MathOverflow)?\n"
+            "      .as_u64();"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Integer division discards the remainder, and which party keeps it "
+            "depends on the direction. Rounding a charge down or a payout up "
+            "gives the remainder to the caller on every call; rounding the "
+            "other way keeps the program solvent."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. swap computes fee = amount * fee_bps / 10_000, rounded down\n"
+            "2. The attacker swaps in pieces small enough that each fee is 0\n"
+            "3. Each piece trades fee-free; with a ceiled payout, each call also takes a unit extra\n"
+            "4. Repeated in one transaction, the rounding adds up to a drain of the fee vault or the pool"
+        )
@@ -0,0 +1,210 @@
+"""
+ANCHOR-054: Saturating Subtraction On Accounting Field
+
+`saturating_sub` clamps at zero instead of failing. On a balance or a
+supply that is an invariant violation turned into a silent reset:
+
+    user.deposited = user.deposited.saturating_sub(amount);   // amount > deposited?
+    token::transfer(cpi_ctx, amount)?;                        // transferred anyway
+
+A withdrawal larger than the deposit, a double-counted fee or a supply
+that drifted below the sum of its parts no longer stops the instruction:
+the record reads 0, the tokens still move, and the error that would have
+pointed at the bug is gone.
+
+The rule reports (Medium) `.saturating_sub(..)` whose receiver, argument
+or assignment target names an accounting quantity — supply, balance,
+deposit, stake, debt, shares, reserve, collected or owed fees, totals.
+Left out are clamps that are the intended semantics: time and slot
+differences, rent top-ups (`minimum_balance(..).saturating_sub(lamports)`),
+fee-rate and basis-point ranges, counters and sizes, and `required` /
+`missing` / `excess` amounts. A clamp explained in a comment on the line
+or just above it (slashing, rounding, "may be negative") is taken as
+intended, as are the functions listed under ALLOWLIST — Marinade's
+`total_virtual_staked_lamports`, which clamps on slashing — and under
+`allow` in anchor-shield.toml:
+
+    [rules."ANCHOR-054"]
+    allow = ["available_liquidity"]
+
+The fix is `checked_sub` with an explicit error.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.math_summary import _blank
+from scanner.patterns.signatures import result_note, return_types
+from scanner.patterns.truncating_cast import TruncatingCastPattern
+
+SATURATING_RE = re.compile(r"\.\s*saturating_sub\s*\(")
+ACCOUNTING_RE = re.compile(
+    r"supply|balance|deposit|withdrawn|staked|stake_amount|owed|debt|borrow|principal|collateral|"
+    r"shares?$|shares_|reserve|liquidity|accrued|collected|unclaimed|claimed|outstanding|minted|burned|"
+    r"^fees?$|_fees?$|fees_|^total_|_total$|tvl|lamports$|amount$",
+    re.I,
+)
+# Names whose subtraction clamps by design
+INTENDED_RE = re.compile(
+    r"time|_ts$|^ts$|slot|epoch|now|elapsed|duration|rent|minimum_balance|bps|basis_points|_rate|rate_|"
+    r"pct|percent|len$|size|space|index|idx|count|decimals|bump|required|needed|missing|shortfall|"
+    r"deficit|excess|surplus|remaining_accounts",
+    re.I,
+)
+JUSTIFIED_RE = re.compile(
+    r"saturat|clamp|slash|negative|intentional|on purpose|rounding|dust|below zero|floor(?:ed)? at",
+    re.I,
+)
+# Functions whose clamp is the documented behavior
+ALLOWLIST = {
+    # Marinade: total lamports under control minus circulating tickets may go negative after slashing
+    "total_virtual_staked_lamports",
+}
+
+
+class SaturatingSubPattern(VulnerabilityPattern):
+    id = "ANCHOR-054"
+    name = "Saturating Subtraction On Accounting Field"
+    severity = "Medium"
+    category = "arithmetic"
+    remediation_effort = "mechanical"
+    description = (
+        "saturating_sub on a balance, supply or fee total clamps an underflow to "
+        "zero, hiding the invariant violation instead of failing."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        code = _blank(content)
+        lines = content.split("\n")
+        returns = return_types(code)
+        allowed = ALLOWLIST | {str(name) for name in self.options.get("allow", [])}
+        for fn in self._find_functions(content):
+            if fn["name"] in allowed:
+                continue
+            body = code[fn["body_start"]:fn["body_start"] + len(fn["body"])]
+            for m in re.finditer(r"[^;{}]+", body):
+                stmt = m.group(0)
+                for call in SATURATING_RE.finditer(stmt):
+                    start = TruncatingCastPattern._operand_start(stmt, call.start())
+                    if start is None:
+                        continue
+                    receiver = self._text(stmt[start:call.start()])
+                    argument = self._text(self._argument(stmt, call.end() - 1))
+                    binding = self._binding(stmt)
+                    target = re.split(r"[\s.]", binding[:-3])[-1] if binding else ""
+                    names = self._names(receiver) + self._names(argument)
+                    if any(n in allowed for n in names + [target]):
+                        continue
+                    if any(INTENDED_RE.search(n) for n in names + [target]):
+                        continue
+                    if not any(ACCOUNTING_RE.search(n) for n in names + [target]):
+                        continue
+                    line = self._get_line_number(content, fn["body_start"] + m.start() + call.start())
+                    if self._justified(lines, line):
+                        continue
+                    findings.append(self._finding(
+                        file_path, content, line, fn["name"], receiver, argument, target, binding,
+                        returns.get(fn["name"], ""),
+                    ))
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    @staticmethod
+    def _text(value: str) -> str:
+        return re.sub(r"\s*\.\s*", ".", " ".join(value.split()))
+
+    @staticmethod
+    def _names(expr: str) -> list:
+        return [n for n in re.findall(r"[A-Za-z_]\w*", expr) if n not in ("self", "as", "ctx", "accounts")]
+
+    @staticmethod
+    def _argument(stmt: str, open_pos: int) -> str:
+        depth = 0
+        for i in range(open_pos, len(stmt)):
+            depth += {"(": 1, ")": -1}.get(stmt[i], 0)
+            if depth == 0:
+                return stmt[open_pos + 1:i]
+        return stmt[open_pos + 1:]
+
+    @staticmethod
+    def _binding(stmt: str) -> str:
+        """`let x = ` or `a.b = ` the statement starts with, else ""."""
+        m = re.match(r"\s*(let\s+(?:mut\s+)?\w+|[\w.]+)\s*=(?!=)", stmt)
+        return f"{' '.join(m.group(1).split())} = " if m else ""
+
+    @staticmethod
+    def _justified(lines: list, line: int) -> bool:
+        """A comment on the line or on the two lines above it explains the clamp."""
+        for text in lines[max(line - 3, 0):line]:
+            comment = text.find("//")
+            if comment != -1 and JUSTIFIED_RE.search(text[comment:]):
+                return True
+        return False
+
+    def _finding(self, file_path, content, line, fn_name, receiver, argument, target, binding,
+                 returns) -> Finding:
+        value = target or "the result"
+        fix = (
+            "Fail on the underflow instead of clamping it:\n"
+            f"  {binding}{receiver}\n      .checked_sub({argument})\n      .ok_or(ErrorCode::MathOverflow)?;"
+        )
+        fix += result_note(fn_name, returns)
+        fix += ("\nIf clamping is intended here, say why in a comment next to the call, or list "
+                f"{fn_name} under `allow` in [rules.\"{self.id}\"] of anchor-shield.toml.")
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=self.severity,
+            file=file_path,
+            line=line,
+            description=(
+                f"In {fn_name}: `{receiver}.saturating_sub({argument})` clamps {value} at zero when "
+                f"{argument} exceeds it; an accounting error is recorded as 0 instead of failing the "
+                f"instruction."
+            ),
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=fix,
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "user.deposited = 100, withdraw(amount = 150)",
+                "after": "user.deposited = 0, 150 transferred out",
+                "damage": "50 tokens left the vault with no record against them; the books no longer add up.",
+            },
+            impact={
+                "attack_cost": "One call with an amount past the recorded balance",
+                "exploitability": "Medium — depends on whether the clamped amount still moves tokens",
+                "breach_cost_context": "Clamped underflows turn over-withdrawals and double-counted fees into "
+                                       "silent accounting drift.",
+            },
+            anchor_versions_affected="All versions (program-side arithmetic)",
+            ecosystem_recommendations=[
+                "Use checked_sub with a named error on balances, supplies and fee totals",
+                "Keep saturating_sub for values where zero is the intended floor, and say so in a comment",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Fail on the underflow instead of clamping it:\n"
+            "  user.deposited = user.deposited\n"
+            "      .checked_sub(amount)\n"
+            "      .ok_or(ErrorCode::InsufficientFunds)?;"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "saturating_sub returns 0 where the exact result would be negative. "
+            "For an accounting quantity a negative result means the books are "
+            "already wrong — more withdrawn than deposited, more burned than "
+            "minted — and clamping records a plausible value over the error."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. withdraw subtracts amount from user.deposited with saturating_sub\n"
+            "2. Attacker withdraws more than they deposited\n"
+            "3. deposited clamps to 0 and the transfer of the full amount proceeds\n"
+            "4. Repeated, the vault pays out other users' deposits"
+        )
@@ -0,0 +1,192 @@
+"""
+Registry of the PDA seed recipes in a source unit, resolved to byte shapes.
+
+A PDA is the hash of its seeds concatenated with no separator or length
+prefix, so two recipes derive the same address whenever their seed bytes
+line up — whatever the account type each struct expects there:
+
+    #[account(init, seeds = [b"pos", owner.key().as_ref()], bump, ...)]
+    pub position: Account<'info, Position>,
+
+    #[account(init, seeds = [b"pos", pool.key().as_ref()], bump, ...)]
+    pub pool_config: Account<'info, PoolConfig>,
+
+seed_registry() lists every `seeds = [...]` on a program account type and
+resolves each seed to a component of known or unknown length:
+
+  - literal:   `b"pos"`, `"pos".as_bytes()`, and `const` seeds defined in
+               the unit (`POOL_SEED`, `Pool::SEED`), with their bytes
+  - key:       `x.key()`, `x.to_account_info().key`, Pubkey fields — 32 bytes
+  - int:       `x.to_le_bytes()` with `x` typed by `#[instruction(..)]` or a
+               struct field — 1 to 16 bytes
+  - bump:      `&[bump]`, `[state.bump]` — 1 byte
+  - variable:  strings, byte vectors and anything unresolved
+
+parse_seeds() resolves seed expressions written in code the same way
+(signer seeds, `find_program_address` arguments).
+
+can_collide() decides whether two shapes can produce the same bytes: known
+lengths must line up, literals must agree where they overlap, and a
+variable-length component can absorb any bytes.
+"""
+
+import re
+from dataclasses import dataclass, field
+from typing import Optional
+
+from scanner.patterns.facts import Facts
+from scanner.patterns.lifecycle import TYPE_RE
+from scanner.patterns.validation import _split_top
+
+# Types owned by other programs: their PDAs live in a different address space or hold no program data
+EXTERNAL_TYPES = {
+    "TokenAccount", "Mint", "Metadata", "MasterEdition", "MasterEditionAccount", "MetadataAccount",
+    "EditionMarker", "TokenRecord", "StakeAccount", "Stake",
+}
+INT_SIZES = {"u8": 1, "i8": 1, "u16": 2, "i16": 2, "u32": 4, "i32": 4, "u64": 8, "i64": 8,
+             "u128": 16, "i128": 16, "usize": 8, "isize": 8}
+CONST_RE = re.compile(
+    r"\bconst\s+(\w+)\s*:\s*&\s*(?:'static\s+)?(?:\[\s*u8\s*\]|str|\[\s*u8\s*;\s*\d+\s*\])\s*=\s*b?\"([^\"]*)\""
+)
+INSTRUCTION_ARGS_RE = re.compile(r"#\[instruction\(([^)]*)\)\]\s*(?:#\[[^\]]*\]\s*)*pub\s+struct\s+(\w+)")
+CONVERSION_RE = re.compile(r"\.\s*(?:as_ref|as_bytes|to_bytes|as_slice|to_vec)\s*\(\s*\)|\[\s*\.\.\s*\]|^&+|\s+")
+
+LITERAL, KEY, INT, BUMP, VARIABLE = "literal", "key", "int", "bump", "variable"
+ANY, STAR = object(), object()
+
+
+@dataclass
+class Seed:
+    text: str
+    kind: str
+    # Bytes of a literal seed
+    value: bytes = b""
+    # Byte length, None when it varies
+    length: Optional[int] = None
+
+    def describe(self) -> str:
+        if self.kind == LITERAL:
+            return f'b"{self.value.decode(errors="replace")}"'
+        if self.kind == INT:
+            return f"<{self.length * 8}-bit int>"
+        return {KEY: "<pubkey>", BUMP: "<bump>"}.get(self.kind, "<bytes>")
+
+
+@dataclass
+class SeedShape:
+    """One `seeds = [...]` recipe on a program account type."""
+
+    type: str
+    struct: str
+    field: str
+    line: int
+    seeds: str
+    components: list = field(default_factory=list)
+
+    def describe(self) -> str:
+        return "[" + ", ".join(c.describe() for c in self.components) + "]"
+
+    def leading(self) -> str:
+        """The leading literal seed's bytes, "" if the recipe does not start with one."""
+        first = self.components[0] if self.components else None
+        return first.value.decode(errors="replace") if first and first.kind == LITERAL else ""
+
+    def tokens(self) -> list:
+        """One token per byte: the literal byte, ANY for a fixed-length part, STAR for a variable one."""
+        result = []
+        for c in self.components:
+            if c.kind == LITERAL:
+                result.extend(c.value)
+            elif c.length is None:
+                result.append(STAR)
+            else:
+                result.extend([ANY] * c.length)
+        return result
+
+
+def seed_registry(content: str, facts: Optional[Facts] = None) -> list:
+    """[SeedShape] for every PDA of a program account type in the unit, in source order."""
+    facts = facts or Facts(content)
+    consts, pubkeys, ints, args = _tables(content)
+    shapes = []
+    for struct in facts.accounts_structs():
+        names = {a.name for a in struct.accounts}
+        for account in struct.accounts:
+            m = TYPE_RE.search(account.type)
+            pda = account.pda
+            if not m or m.group(1) in EXTERNAL_TYPES or pda is None or pda.is_foreign:
+                continue
+            inner = pda.seeds
+            if not (inner.startswith("[") and inner.endswith("]")) or "..." in inner:
+                continue
+            sized = {**ints, **args.get(struct.name, {})}
+            components = [_component(s, consts, pubkeys, sized, names) for s in _split_top(inner[1:-1]) if s]
+            shapes.append(SeedShape(m.group(1), struct.name, account.name, account.line,
+                                    " ".join(inner.split()), components))
+    return shapes
+
+
+def parse_seeds(items: list, content: str, accounts=(), struct: str = "") -> list:
+    """[Seed] for seed expressions written outside a `seeds = [...]` constraint
+    (signer seeds, find_program_address), resolved against the unit's consts and types."""
+    consts, pubkeys, ints, args = _tables(content)
+    sized = {**ints, **args.get(struct, {})}
+    return [_component(s, consts, pubkeys, sized, set(accounts)) for s in items if s.strip()]
+
+
+def _tables(content: str) -> tuple:
+    """(consts, Pubkey fields, integer sizes, #[instruction] integer args per struct) of a unit."""
+    consts = {m.group(1): m.group(2).encode() for m in CONST_RE.finditer(content)}
+    pubkeys = set(re.findall(r"pub\s+(\w+)\s*:\s*Pubkey\b", content))
+    ints = {name: INT_SIZES[t] for name, t in re.findall(r"\b(\w+)\s*:\s*([ui](?:8|16|32|64|128|size))\b", content)}
+    args = {}
+    for m in INSTRUCTION_ARGS_RE.finditer(content):
+        args[m.group(2)] = {n: INT_SIZES[t] for n, t in re.findall(r"(\w+)\s*:\s*([ui](?:8|16|32|64|128|size))\b",
+                                                                    m.group(1))}
+    return consts, pubkeys, ints, args
+
+
+def can_collide(a: SeedShape, b: SeedShape) -> bool:
+    """Whether some values of the dynamic seeds make both recipes hash the same bytes."""
+    x, y = a.tokens(), b.tokens()
+    seen, stack = set(), [(0, 0)]
+    while stack:
+        i, j = stack.pop()
+        if (i, j) in seen:
+            continue
+        seen.add((i, j))
+        if i == len(x) and j == len(y):
+            return True
+        if i < len(x) and x[i] is STAR:
+            stack += [(i + 1, j)] + ([(i, j + 1)] if j < len(y) else [])
+        if j < len(y) and y[j] is STAR:
+            stack += [(i, j + 1)] + ([(i + 1, j)] if i < len(x) else [])
+        if i < len(x) and j < len(y) and x[i] is not STAR and y[j] is not STAR \
+                and (x[i] is ANY or y[j] is ANY or x[i] == y[j]):
+            stack.append((i + 1, j + 1))
+    return False
+
+
+def _component(text: str, consts: dict, pubkeys: set, ints: dict, accounts: set) -> Seed:
+    raw = " ".join(text.split())
+    expr = CONVERSION_RE.sub("", text.strip())
+    m = re.fullmatch(r'b?"([^"]*)"', expr)
+    if m:
+        return Seed(raw, LITERAL, m.group(1).encode(), len(m.group(1).encode()))
+    m = re.fullmatch(r"(?:\w+::)*([A-Z][A-Z0-9_]*)", expr)
+    if m and m.group(1) in consts:
+        return Seed(raw, LITERAL, consts[m.group(1)], len(consts[m.group(1)]))
+    m = re.fullmatch(r"\[\s*(?:[\w.]*\.)?(\w*bump\w*)(?:\.\w+)*\s*\]", expr)
+    if m:
+        return Seed(raw, BUMP, length=1)
+    if re.search(r"\.key\b|\bkey\s*\(\s*\)", expr):
+        return Seed(raw, KEY, length=32)
+    m = re.fullmatch(r"(?:[\w.]*\.)?(\w+)\.to_[lb]e_bytes\(\)", expr)
+    if m:
+        size = ints.get(m.group(1))
+        return Seed(raw, INT, length=size) if size else Seed(raw, VARIABLE)
+    last = re.fullmatch(r"(?:[\w]+\.)*(\w+)", expr)
+    if last and (last.group(1) in pubkeys or expr in accounts):
+        return Seed(raw, KEY, length=32)
+    return Seed(raw, VARIABLE)
+
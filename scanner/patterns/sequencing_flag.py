@@ -0,0 +1,208 @@
+"""
+ANCHOR-014: Missing Instruction-Order (Sequencing Flag) Check
+
+Program state often carries fields that record how far an account's
+lifecycle has progressed — `is_active`, `initialized`, `funded`, `open_time`,
+`status` — and instructions are only correct after a previous instruction
+has set them (stake only after init finished, borrow only after the pool was
+funded). Nothing in Anchor enforces that order; each handler has to check.
+
+This rule learns which fields act as sequencing flags from the program
+itself: a flag must be set by some instruction and checked by most of the
+other instructions that mutate the same account type. Mutating handlers that
+neither check nor set it are reported, since they run regardless of the
+state the rest of the program waits for.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+
+
+class SequencingFlagPattern(VulnerabilityPattern):
+    id = "ANCHOR-014"
+    name = "Missing Instruction-Order Check"
+    severity = "Medium"
+    category = "state"
+    remediation_effort = "mechanical"
+    description = (
+        "Instruction mutates state that other instructions only touch after "
+        "checking a sequencing flag (is_active, initialized, open_time...), "
+        "but does not check it."
+    )
+
+    STATE_STRUCT_RE = re.compile(
+        r"#\[account(?:\([^)]*\))?\]\s*(?:#\[[^\]]*\]\s*)*pub\s+struct\s+(\w+)\s*(?:<[^>{]*>)?\s*\{"
+    )
+    FIELD_RE = re.compile(r"\bpub\s+(\w+)\s*:\s*([^,\n]+)")
+
+    FLAG_BOOL_RE = re.compile(
+        r"^(?:is_)?(?:active|initiali[sz]ed|open(?:ed)?|funded|started|enabled|live|ready|"
+        r"paused|frozen|halted|closed|finali[sz]ed|settled)$"
+    )
+    FLAG_TIME_RE = re.compile(
+        r"^(?:open|start|activation|launch|begin|unlock|listing)_(?:time|ts|timestamp|slot|at)$"
+    )
+    FLAG_STATUS_RE = re.compile(r"^(?:status|state|phase|stage)$")
+
+    # Handlers that establish or administer state rather than depend on it
+    SETUP_NAME_RE = re.compile(
+        r"^(?:init\w*|create\w*|new\w*|open_\w*|set_\w*|update_\w*config\w*|admin_\w*|"
+        r"migrate\w*|close\w*|pause\w*|unpause\w*|activate\w*|deactivate\w*)$"
+    )
+
+    # A handler is flagged only if at least this share of its peers check the flag
+    MIN_CHECK_RATIO = 0.5
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        state_flags = self._state_flags(content)
+        if not state_flags:
+            return []
+        structs = {
+            name: self._parse_struct_fields(body, line)
+            for name, body, line in self._find_derive_accounts_structs(content)
+        }
+
+        handlers = []
+        facts = Facts(content)
+        for fn in self._find_functions(content):
+            ix = facts.instruction_of(fn)
+            if ix and ix.accounts_struct in structs:
+                handlers.append((fn, structs[ix.accounts_struct]))
+
+        findings = []
+        for state, (fields, flags) in state_flags.items():
+            for flag in flags:
+                findings.extend(self._check_flag(file_path, content, handlers, state, fields, flag))
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    def _check_flag(self, file_path, content, handlers, state, fields, flag) -> list[Finding]:
+        setters, checkers, unchecked = [], [], []
+        data_fields = [f for f in fields if f != flag]
+        for fn, accounts in handlers:
+            mutable = [
+                a["name"] for a in accounts
+                if re.search(rf"\b{state}\b", a["type"]) and re.search(r"\bmut\b", a["attrs"])
+                and not re.search(r"\binit(?:_if_needed)?\b", a["attrs"])
+            ]
+            if not mutable:
+                continue
+            body = fn["body"]
+            attrs = " ".join(a["attrs"] for a in accounts)
+            if re.search(rf"\.\s*{flag}\s*=(?!=)", body):
+                setters.append(fn)
+            elif self._checks(flag, body, attrs):
+                checkers.append(fn)
+            elif self._mutates(body, data_fields) and not self.SETUP_NAME_RE.match(fn["name"]):
+                unchecked.append((fn, mutable[0]))
+
+        peers = len(checkers) + len(unchecked)
+        if not setters or not checkers or not unchecked:
+            return []
+        if len(checkers) / peers < self.MIN_CHECK_RATIO:
+            return []
+
+        findings = []
+        checked_by = ", ".join(fn["name"] for fn in checkers[:3])
+        set_by = ", ".join(fn["name"] for fn in setters[:3])
+        for fn, account in unchecked:
+            line = self._get_line_number(content, fn["body_start"])
+            findings.append(Finding(
+                id=self.id,
+                name=self.name,
+                severity=self.severity,
+                file=file_path,
+                line=line,
+                description=(
+                    f"Handler '{fn['name']}' mutates {state} ('{account}') without checking "
+                    f"'{flag}'. {len(checkers)} of {peers} handlers that mutate {state} check it "
+                    f"(e.g. {checked_by}); it is set by {set_by}. This instruction runs "
+                    f"before that step has happened."
+                ),
+                root_cause=self.get_root_cause(),
+                exploit_scenario=self.get_exploit_scenario(),
+                fix_recommendation=self.get_fix_recommendation(),
+                code_snippet=self._extract_snippet(content, line),
+                before_after_state={
+                    "before": f"{state}.{flag} not yet set (setup instruction pending)",
+                    "after": f"'{fn['name']}' executes anyway and mutates {state}",
+                    "damage": "State is modified outside the lifecycle the rest of the program assumes.",
+                },
+                impact={
+                    "attack_cost": "Transaction fees only",
+                    "exploitability": "Medium — requires acting in the window before the flag is set (or after it is cleared)",
+                    "breach_cost_context": "Front-running pool/vault setup has let attackers stake or borrow against unfunded state.",
+                },
+                anchor_versions_affected="All versions (developer-side pattern)",
+                ecosystem_recommendations=[
+                    "Check lifecycle flags in an accounts-struct constraint so every handler using the struct inherits it",
+                    "Prefer a single status enum over several independent booleans",
+                ],
+            ))
+        return findings
+
+    def _state_flags(self, content: str) -> dict:
+        """Map #[account] struct name -> (field names, sequencing-flag fields)."""
+        result = {}
+        for m in self.STATE_STRUCT_RE.finditer(content):
+            end = self._find_block_end(content, m.end() - 1)
+            if end == -1:
+                continue
+            fields = self.FIELD_RE.findall(content[m.end():end])
+            flags = []
+            for name, ty in fields:
+                ty = ty.strip()
+                if ty == "bool" and self.FLAG_BOOL_RE.match(name):
+                    flags.append(name)
+                elif re.match(r"^[ui](?:32|64)$", ty) and self.FLAG_TIME_RE.match(name):
+                    flags.append(name)
+                elif self.FLAG_STATUS_RE.match(name) and re.search(r"(?:Status|State|Phase|Stage)$", ty):
+                    flags.append(name)
+            if flags:
+                result[m.group(1)] = ([name for name, _ in fields], flags)
+        return result
+
+    def _checks(self, flag: str, body: str, attrs: str) -> bool:
+        if re.search(rf"\b{flag}\b", attrs):
+            return True
+        # Direct read in a condition or assertion
+        if re.search(rf"(?:\b{self._guards()}!|\bif\b|\bmatch\b)[^;{{]*\.\s*{flag}\b", body):
+            return True
+        # Helper such as pool.assert_active() / pool.is_open()
+        stem = re.sub(r"^is_|_(?:time|ts|timestamp|slot|at)$", "", flag)
+        return bool(re.search(rf"\.\s*(?:is_|assert_|check_|require_|ensure_)\w*{stem}\w*\s*\(", body))
+
+    @staticmethod
+    def _mutates(body: str, data_fields: list) -> bool:
+        if not data_fields:
+            return False
+        names = "|".join(re.escape(f) for f in data_fields)
+        return bool(re.search(
+            rf"\.\s*(?:{names})\s*(?:[+\-*/]?=(?!=)|=\s*\w)", body,
+        ))
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Check the lifecycle flag wherever the state is mutated, ideally once\n"
+            "in the accounts struct:\n"
+            "  #[account(mut, constraint = pool.is_active @ ErrorCode::PoolNotActive)]\n"
+            "  pub pool: Account<'info, Pool>,"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Solana transactions can call any instruction in any order. A flag "
+            "that records a completed setup step only protects the handlers that "
+            "check it; a handler added later, or one that was assumed to run only "
+            "after setup, mutates state the rest of the program treats as not yet live."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. Admin creates a pool; funding/activation is a separate instruction\n"
+            "2. Deposit and borrow check pool.is_active, but stake does not\n"
+            "3. Attacker stakes in the window before activation, when rates and\n"
+            "   reward indexes are still at their initial values\n"
+            "4. Attacker's position is recorded against uninitialized parameters"
+        )
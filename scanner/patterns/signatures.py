@@ -0,0 +1,37 @@
+"""
+Function signatures, for fixes that replace a panic with an error.
+
+Rules that suggest `.ok_or(ErrorCode::MathOverflow)?` or `.map_err(..)?`
+also have to say when the `?` would not compile:
+
+    fn fee(amount: u64) -> u64 {
+        amount.checked_mul(FEE_BPS).unwrap() / 10_000
+    }
+
+Here the helper has to return Result<u64> first. Rules read the return
+types of a unit once and append the note through `result_note(...)`:
+
+    from scanner.patterns.signatures import result_note, return_types
+
+    returns = return_types(code)
+    fix += result_note(fn_name, returns.get(fn_name, ""))
+"""
+
+import re
+
+# `fn name<..>(..) -> Type {`, the return type up to the body or a where clause
+FN_SIG_RE = re.compile(r"\bfn\s+(\w+)\s*(?:<[^>]*>)?\s*\([^)]*\)\s*->\s*([^{;]+?)\s*(?:where\b[^{]*)?\{")
+
+
+def return_types(code: str) -> dict:
+    """{function name: declared return type} for the functions of a unit that declare one."""
+    return {m.group(1): m.group(2).strip() for m in FN_SIG_RE.finditer(code)}
+
+
+def result_note(fn_name: str, returns: str, alternative: str = "") -> str:
+    """The fix-text line asking fn_name to return Result, "" when it already does."""
+    if "Result" in returns:
+        return ""
+    returned = returns or "()"
+    return (f"\n{fn_name} returns {returned}: make it return Result<{returned}> "
+            f"so the error reaches the instruction{alternative}.")
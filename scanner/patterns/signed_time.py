@@ -0,0 +1,272 @@
+"""
+ANCHOR-059: Signed Timestamp Cast To Unsigned
+
+Unix timestamps are i64, and a difference of two of them is negative
+whenever the later one isn't:
+
+    let base = self.staking_ends_at
+        .checked_sub(last_reward_time)
+        .ok_or(StakeError::ProgramSubError)? as u128; // can't be negative
+
+checked_sub only catches i64 overflow — `100 - 200` is a valid i64 — and
+`as u128` reinterprets -100 as ~3.4e38. Here `staking_ends_at` can be moved
+before the last reward change by an admin, and the weight divides by a
+number near u128::MAX; a comment is not a check.
+
+The rule reports:
+
+  - Medium: an i64 timestamp difference (`a - b`, `a.checked_sub(b)`,
+    saturating / wrapping / overflowing sub) cast with `as u64` / `as u128`
+    / `as u32` / `as usize`, directly or through the `let` it was bound to,
+    when the function never compares the operands (`require!(end >= start)`,
+    `if now < start { return ... }`) nor the difference against zero. The
+    finding quotes a comment on the statement that claims the value can't
+    be negative.
+  - Low: a subtraction of two stored or passed-in timestamps, neither from
+    the clock, whose ordering the function never establishes and whose
+    result scales other arithmetic (a multiplier or divisor) — a negative
+    duration that becomes a negative rate or reward.
+
+`u64::try_from(..)` with the error surfaced is the fix and is left out, as
+is `.max(0)`. Supersedes ANCHOR-051 at the same line: the narrowing is the
+same cast, reported here with the ordering the cast relies on.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.handler_panic import HandlerPanicPattern
+from scanner.patterns.math_summary import _blank
+from scanner.patterns.mul_chain import LET_TYPED_RE, TYPED_RE
+from scanner.patterns.saturating_sub import SaturatingSubPattern
+from scanner.patterns.signatures import result_note, return_types
+from scanner.patterns.truncating_cast import NEVER_NEGATIVE, TruncatingCastPattern
+
+UNSIGNED_CAST_RE = re.compile(r"\bas\s+(u128|u64|u32|usize)\b")
+TIME_RE = re.compile(
+    r"time|timestamp|_ts$|^ts$|_at$|deadline|expir|^now$|start|end|period|duration|elapsed|since|until|last_update",
+    re.I,
+)
+METHOD_SUB_RE = re.compile(r"\.\s*(?:checked|saturating|wrapping|overflowing)_sub\s*\(")
+PATH = r"[A-Za-z_]\w*(?:\s*\.\s*\w+(?:\(\))?)*"
+BINARY_SUB_RE = re.compile(rf"(?<![\w.])({PATH})\s*-(?![=>])\s*({PATH})")
+SCALES_RE = r"(?:[*/]\s*\(?\s*{0}\b|\b{0}\s*\)?\s*[*/]|\.\s*checked_(?:mul|div)\s*\(\s*{0}\s*\)|\b{0}\s*\.\s*checked_(?:mul|div)\b)"
+WRAP = {"u128": "~3.4e38", "u64": "~1.8e19", "usize": "~1.8e19", "u32": "~4.3e9"}
+_CASTS = TruncatingCastPattern()
+COMMENT_RE = re.compile(r"//.*(?:negative|can'?t|cannot|never|always|positive)", re.I)
+
+
+class SignedTimeCastPattern(VulnerabilityPattern):
+    id = "ANCHOR-059"
+    name = "Signed Timestamp Cast To Unsigned"
+    severity = "Medium"
+    category = "arithmetic"
+    remediation_effort = "mechanical"
+    description = (
+        "An i64 timestamp difference is cast to u64/u128 with `as` (or used as a "
+        "duration) without establishing that the later timestamp is later."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        code = _blank(content)
+        if not re.search(r"\bi64\b|unix_timestamp", code):
+            return []
+        lines = content.split("\n")
+        fields = TruncatingCastPattern._field_types(code)
+        returns = return_types(code)
+        findings = {}
+        for fn in self._find_functions(content):
+            body = code[fn["body_start"]:fn["body_start"] + len(fn["body"])]
+            types = dict(fields)
+            types.update(dict(TYPED_RE.findall(fn["params"])))
+            types.update(dict(LET_TYPED_RE.findall(body)))
+            clock = set(NEVER_NEGATIVE)
+            diffs = {}   # let-bound difference -> (line, subtraction)
+            for m in re.finditer(r"[^;{}]+", body):
+                stmt, before = m.group(0), body[:m.start()]
+                line = self._get_line_number(content, fn["body_start"] + m.start() + len(stmt) - len(stmt.lstrip()))
+                subs = [s for s in self._subtractions(stmt) if self._is_time(s, types)]
+                cast_subs = []
+                for cast in UNSIGNED_CAST_RE.finditer(stmt):
+                    # `expr? as u128`: the operand is the expression the `?` unwraps
+                    end = len(stmt[:cast.start()].rstrip().rstrip("?"))
+                    start = TruncatingCastPattern._operand_start(stmt, end)
+                    if start is None:
+                        continue
+                    operand = stmt[start:cast.start()]
+                    if re.search(r"\.\s*max\s*\(\s*0", operand):
+                        continue
+                    inner = [s for s in subs if s[2] >= start and s[3] <= cast.start()]
+                    bound = re.fullmatch(r"\s*\(?\s*(\w+)\s*\)?\s*", operand)
+                    if inner:
+                        sub, via = inner[0], None
+                    elif bound and bound.group(1) in diffs:
+                        via = bound.group(1)
+                        sub = diffs[via][1]
+                        if TruncatingCastPattern._compared([via], before):
+                            continue
+                    else:
+                        continue
+                    cast_subs.extend(inner)
+                    if self._ordered(sub, before):
+                        continue
+                    at = self._get_line_number(content, fn["body_start"] + m.start() + start)
+                    comment = self._comment(lines, at, self._get_line_number(
+                        content, fn["body_start"] + m.start() + cast.end()))
+                    findings.setdefault(at, self._finding(
+                        file_path, content, at, fn["name"], sub, cast.group(1), self.severity,
+                        via=(via, diffs[via][0]) if via else None, comment=comment,
+                        returns=returns.get(fn["name"], ""),
+                    ))
+
+                let = re.match(r"\s*let\s+(?:mut\s+)?(\w+)\s*(?::\s*\w+\s*)?=(?!=)\s*(.+)$", stmt, re.S)
+                if let:
+                    value = let.group(2)
+                    if re.search(r"\bunix_timestamp\b|Clock\s*::\s*get\b", value):
+                        clock.add(let.group(1))
+                    source = _CASTS._source_type(value, types, returns)
+                    if source and let.group(1) not in types:
+                        types[let.group(1)] = source
+                    whole = [s for s in subs if s not in cast_subs and not UNSIGNED_CAST_RE.search(stmt[s[3]:])]
+                    if whole:
+                        diffs[let.group(1)] = (line, whole[0])
+
+                # Durations between two stored timestamps that scale other arithmetic
+                for sub in subs:
+                    if sub in cast_subs or set(sub[4]) & clock or self._ordered(sub, before):
+                        continue
+                    if not all(self._time_name(n, types) for n in (sub[4][0], sub[4][1])):
+                        continue
+                    scaled = let and re.search(SCALES_RE.format(re.escape(let.group(1))), body[m.end():])
+                    if not scaled and not re.search(r"[*/]\s*\(?\s*$", stmt[:sub[2]]) \
+                            and not re.match(r"\s*\)?\s*[*/]", stmt[sub[3]:]):
+                        continue
+                    at = self._get_line_number(content, fn["body_start"] + m.start() + sub[2])
+                    findings.setdefault(at, self._finding(
+                        file_path, content, at, fn["name"], sub, "", "Low",
+                        returns=returns.get(fn["name"], ""),
+                    ))
+        return sorted(findings.values(), key=lambda f: f.line)
+
+    @staticmethod
+    def _subtractions(stmt: str) -> list:
+        """(minuend, subtrahend, start, end, (last name of each side)) of each subtraction in the statement."""
+        result = []
+        for m in METHOD_SUB_RE.finditer(stmt):
+            receiver = HandlerPanicPattern._receiver(stmt, m.start())
+            argument = SaturatingSubPattern._argument(stmt, m.end() - 1)
+            start = m.start() - len(receiver)
+            result.append((receiver, argument, start, m.end() + len(argument) + 1))
+        for m in BINARY_SUB_RE.finditer(stmt):
+            result.append((m.group(1), m.group(2), m.start(), m.end()))
+        subs = []
+        for left, right, start, end in result:
+            names = [SaturatingSubPattern._names(side) for side in (left, right)]
+            if all(names):
+                subs.append((SaturatingSubPattern._text(left), SaturatingSubPattern._text(right), start, end,
+                             (names[0][-1], names[1][-1])))
+        return subs
+
+    @staticmethod
+    def _time_name(name: str, types: dict) -> bool:
+        return types.get(name) == "i64" and bool(TIME_RE.search(name))
+
+    def _is_time(self, sub: tuple, types: dict) -> bool:
+        """An i64 timestamp on either side (the other may be a `let` of unknown type)."""
+        return any(self._time_name(n, types) for n in sub[4])
+
+    @staticmethod
+    def _ordered(sub: tuple, before: str) -> bool:
+        """The function compared the two sides, or derived one from the other with max / min."""
+        names = list(sub[4])
+        if TruncatingCastPattern._compared(names, before):
+            return True
+        left, right = (re.escape(n) for n in names)
+        return bool(re.search(rf"\b{left}\b[^;]*\.\s*(?:max|min)\s*\(\s*[^;)]*\b{right}\b|"
+                              rf"\b{right}\b[^;]*\.\s*(?:max|min)\s*\(\s*[^;)]*\b{left}\b", before))
+
+    @staticmethod
+    def _comment(lines: list, first: int, last: int) -> str:
+        for text in lines[first - 1:last]:
+            m = COMMENT_RE.search(text)
+            if m:
+                return text[text.find("//") + 2:].strip()
+        return ""
+
+    def _finding(self, file_path, content, line, fn_name, sub, target, severity,
+                 via=None, comment="", returns="") -> Finding:
+        left, right = sub[0], sub[1]
+        if target:
+            what = (f"`{left} - {right}` is bound to `{via[0]}` (line {via[1]}) and cast `{via[0]} as {target}`"
+                    if via else f"`{left} - {right}` is cast `as {target}`")
+            description = (
+                f"In {fn_name}: {what}; nothing in {fn_name} establishes {left} >= {right}, "
+                f"and a negative i64 becomes {WRAP[target]} as {target}."
+            )
+            if comment:
+                description += f" The comment (\"{comment}\") assumes it; no check enforces it."
+            fix = (f"Check the ordering, or convert with the error surfaced:\n"
+                   f"  require!({left} >= {right}, ErrorCode::InvalidTimestamps);\n"
+                   f"  let value = {target}::try_from({via[0] if via else f'{left} - {right}'})\n"
+                   f"      .map_err(|_| ErrorCode::InvalidTimestamps)?;")
+        else:
+            description = (
+                f"In {fn_name}: `{left} - {right}` subtracts two stored timestamps whose ordering "
+                f"{fn_name} never checks, and the difference scales other arithmetic; "
+                f"a negative duration flips the sign of the result."
+            )
+            fix = f"Establish the ordering first:\n  require!({left} >= {right}, ErrorCode::InvalidTimestamps);"
+        if target:
+            fix += result_note(fn_name, returns)
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=severity,
+            file=file_path,
+            line=line,
+            description=description,
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=fix,
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "staking_ends_at = 1_700_000_000 moved before last_reward_time = 1_700_000_100",
+                "after": "(staking_ends_at - last_reward_time) as u128 = 2^128 - 100",
+                "damage": "Weights, rewards or vesting computed from the duration are off by ~2^64 or ~2^128.",
+            },
+            impact={
+                "attack_cost": "Timestamps out of order: an admin update, a caller-chosen time, or stale state",
+                "exploitability": "Medium — needs the later timestamp to be earlier, which admin setters and inputs allow",
+                "breach_cost_context": "A wrapped duration divides or multiplies into rewards and weights, "
+                                       "draining pools or zeroing every staker's share.",
+            },
+            anchor_versions_affected="All versions (program-side arithmetic)",
+            ecosystem_recommendations=[
+                "require! the ordering of two timestamps before subtracting them",
+                "Convert i64 durations with u64::try_from and return the error",
+                "Enable clippy::cast_sign_loss in CI",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Check the ordering, or convert with the error surfaced:\n"
+            "  require!(end_ts >= start_ts, ErrorCode::InvalidTimestamps);\n"
+            "  let duration = u64::try_from(end_ts - start_ts)\n"
+            "      .map_err(|_| ErrorCode::InvalidTimestamps)?;"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Timestamps are i64 and their difference is negative whenever they "
+            "are out of order. checked_sub doesn't catch that — it is not an "
+            "overflow — and `as` to an unsigned type reinterprets the sign bit "
+            "instead of failing."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. The admin extends (or shortens) the staking period to end before the last reward change\n"
+            "2. update_staked_weight computes (staking_ends_at - last_reward_time) as u128\n"
+            "3. The negative difference becomes ~3.4e38 and the weight math divides by it\n"
+            "4. Every staker's weight rounds to zero, or the mirrored cast inflates it"
+        )
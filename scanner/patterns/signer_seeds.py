@@ -0,0 +1,250 @@
+"""
+ANCHOR-032: Unverified Signer Seeds
+
+`invoke_signed` and `CpiContext::new_with_signer` make the program sign for
+whatever PDA its seeds derive. When the seeds come from the caller, the
+caller chooses the PDA:
+
+    pub fn withdraw(ctx: Context<Withdraw>, owner: Pubkey, amount: u64) -> Result<()> {
+        let seeds = &[b"vault", owner.as_ref(), &[ctx.accounts.vault.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(program, accounts, &[&seeds[..]]),  // any owner's vault
+            amount,
+        )
+    }
+
+The rule resolves the signer seeds of every signing call (through `let`
+bindings such as `&[&seeds[..]]`), drops the trailing bump, and compares
+each recipe with the PDAs the program declares: `seeds = [...]` in an
+accounts struct and `find_program_address` calls. It reports:
+
+  - a seed built from an instruction argument, unless the PDA the recipe
+    matches in the handler's accounts struct takes the same argument at
+    that position (`#[instruction(id)]` seeds), so Anchor already derived
+    the account from it
+  - a seed read from the data of an account nothing validates
+    (`AccountInfo` / `UncheckedAccount` without seeds, address or owner
+    constraints, deserialized by hand)
+  - a recipe that matches no declared PDA — a different seed count, or a
+    literal that differs — in a unit that declares PDAs: the program signs
+    for an address no constraint derives
+
+Recipes built by methods (`pool.seeds()`) or passed in by a caller are not
+resolved and not reported.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+from scanner.patterns.seeds import LITERAL, parse_seeds
+from scanner.patterns.signing import SIGNED_CPI_RE, _seeds_arg
+from scanner.patterns.validation import _split_top
+
+DERIVE_RE = re.compile(r"\b(?:find_program_address|create_program_address)\s*\(\s*&?\s*(\[[^;]*?\])\s*,")
+# A one-byte array: the bump at the end of a signer recipe
+BUMP_ITEM_RE = re.compile(r"&?\s*\[\s*[^\[\],;]+\s*\]")
+UNCHECKED_RE = re.compile(r"\b(?:AccountInfo|UncheckedAccount)\s*<")
+RAW_DATA_RE = (
+    r"\.\s*(?:data|try_borrow_data|try_borrow_mut_data)\b|"
+    r"\b(?:try_from_slice|try_deserialize\w*|deserialize|from_bytes|unpack\w*)\s*\("
+)
+
+ARGUMENT, RAW_DATA, UNDECLARED = "argument", "raw-data", "undeclared"
+
+
+class SignerSeedsPattern(VulnerabilityPattern):
+    id = "ANCHOR-032"
+    name = "Unverified Signer Seeds"
+    severity = "High"
+    category = "accounts"
+    remediation_effort = "localized"
+    description = (
+        "Signer seeds passed to invoke_signed / new_with_signer come from "
+        "instruction arguments or unvalidated account data, or match no PDA "
+        "the program declares, so the caller can choose what the program signs for."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        facts = Facts(content)
+        structs = {s.name: s for s in facts.accounts_structs()}
+        handlers = {ix.body_start: ix for ix in facts.instructions()}
+        impls = self._find_impl_blocks(content)
+        declared = [(s.name, a, self._seed_items(a.pda.seeds)) for s in structs.values() for a in s.accounts
+                    if a.pda and not a.pda.is_foreign]
+        derived = [self._seed_items(m.group(1)) for m in DERIVE_RE.finditer(content)]
+
+        for fn in self._find_functions(content):
+            sites = list(SIGNED_CPI_RE.finditer(fn["body"]))
+            if not sites:
+                continue
+            ix = handlers.get(fn["body_start"])
+            owner = next((t for s, e, t in impls if s <= fn["body_start"] < e and t in structs), None)
+            struct = ix.accounts_struct if ix else owner
+            accounts = {a.name: a for a in structs[struct].accounts} if struct in structs else {}
+            args = ix.args if ix else []
+            for m in sites:
+                recipes = self._recipes(fn["body"], _seeds_arg(fn["body"], m))
+                issues = []
+                for items in recipes:
+                    issues += self._check(items, fn["body"], content, struct, accounts, args, declared, derived)
+                if not issues:
+                    continue
+                line = self._get_line_number(content, fn["body_start"] + m.start())
+                severity = "Medium" if all(kind == UNDECLARED for kind, _ in issues) else self.severity
+                findings.append(self._finding(file_path, content, line, severity, fn["name"],
+                                              "; ".join(text for _, text in issues)))
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    def _recipes(self, body: str, arg: str) -> list:
+        """[[seed expression]] per signer recipe, bump dropped; unresolvable recipes left out."""
+        outer = self._unwrap(arg)
+        if not outer.startswith("["):
+            outer = self._unwrap(self._binding(body, outer) or "")
+        if not (outer.startswith("[") and outer.endswith("]")):
+            return []
+        recipes = []
+        for element in _split_top(outer[1:-1]):
+            inner = self._unwrap(element)
+            if re.fullmatch(r"\w+", inner):
+                inner = self._unwrap(self._binding(body, inner) or "")
+            if inner.startswith("[") and inner.endswith("]"):
+                items = self._seed_items(inner)
+                if items:
+                    recipes.append(items)
+        return recipes
+
+    @staticmethod
+    def _unwrap(expr: str) -> str:
+        """`&seeds[..]` -> `seeds`, `&[..]` stays a list."""
+        expr = " ".join(expr.split())
+        expr = re.sub(r"^&\s*", "", expr)
+        expr = re.sub(r"\s*\[\s*\.\.\s*\]$|\.\s*as_ref\s*\(\s*\)$|\.\s*to_vec\s*\(\s*\)$", "", expr)
+        return expr.strip()
+
+    @staticmethod
+    def _binding(body: str, name: str):
+        m = re.search(rf"\blet\s+(?:mut\s+)?{re.escape(name)}\s*(?::[^=]+)?=\s*([^;]+);", body)
+        return m.group(1) if m else None
+
+    @staticmethod
+    def _seed_items(seeds: str) -> list:
+        """The seeds of a `[...]` recipe, a trailing one-byte bump array removed."""
+        seeds = seeds.strip()
+        if not (seeds.startswith("[") and seeds.endswith("]")):
+            return []
+        items = [s for s in _split_top(seeds[1:-1]) if s.strip()]
+        if items and BUMP_ITEM_RE.fullmatch(items[-1].strip()):
+            items = items[:-1]
+        return items
+
+    def _check(self, items, body, content, struct, accounts, args, declared, derived) -> list:
+        """[(kind, text)] issues of one signer recipe."""
+        shape = parse_seeds(items, content, accounts, struct or "")
+        own = [(a, d) for s, a, d in declared if s == struct and self._same_shape(shape, d, content)]
+        issues = []
+        for i, item in enumerate(items):
+            if shape[i].kind == LITERAL:
+                continue
+            names = self._sources(item, body)
+            tainted = [a for a in args if a in names
+                       and not any(re.search(rf"\b{re.escape(a)}\b", d[i]) for _, d in own)]
+            if tainted:
+                issues.append((ARGUMENT, f"seed `{' '.join(item.split())}` comes from instruction argument "
+                                         f"{', '.join(tainted)}, so the caller picks the PDA signed for"))
+                continue
+            text = " ".join([item] + [self._binding(body, n) or "" for n in names])
+            raw = [n for n in names if n in accounts and UNCHECKED_RE.search(accounts[n].type)
+                   and not any(c.kind in ("seeds", "address", "owner") for c in accounts[n].constraints)
+                   and re.search(RAW_DATA_RE, text)]
+            if raw:
+                issues.append((RAW_DATA, f"seed `{' '.join(item.split())}` is read from the data of "
+                                         f"'{raw[0]}', which nothing validates"))
+        if declared and not own and not any(self._same_shape(shape, d, content) for _, _, d in declared) \
+                and not any(self._same_shape(shape, d, content) for d in derived):
+            issues.append((UNDECLARED, f"recipe [{', '.join(' '.join(s.split()) for s in items)}] matches no "
+                                       f"PDA declared with seeds or derived with find_program_address"))
+        return issues
+
+    @staticmethod
+    def _same_shape(shape: list, other: list, content: str) -> bool:
+        """Same seed count, equal literals where either has one, and equal known lengths."""
+        theirs = parse_seeds(other, content)
+        if len(theirs) != len(shape):
+            return False
+        for a, b in zip(shape, theirs):
+            if (a.kind == LITERAL) != (b.kind == LITERAL) or (a.kind == LITERAL and a.value != b.value):
+                return False
+            if a.length is not None and b.length is not None and a.length != b.length:
+                return False
+        return True
+
+    def _sources(self, expr: str, body: str, depth: int = 4) -> set:
+        """Identifiers in expr and, through `let` bindings, in what they were built from."""
+        names, frontier = set(), set(re.findall(r"\b[a-z_]\w*\b", expr))
+        for _ in range(depth):
+            names |= frontier
+            nxt = set()
+            for name in frontier:
+                bound = self._binding(body, name)
+                if bound:
+                    nxt |= set(re.findall(r"\b[a-z_]\w*\b", bound)) - names
+            frontier = nxt
+        names |= frontier
+        return names - {"ctx", "accounts", "self"}
+
+    def _finding(self, file_path, content, line, severity, function, message) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=severity,
+            file=file_path,
+            line=line,
+            description=f"Signing call in '{function}': {message}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "Vault PDA of user A holds A's deposit",
+                "after": "Attacker's withdraw signs for A's vault by passing A's key as the owner argument",
+                "damage": "Any PDA the program controls can be made to sign for the attacker.",
+            },
+            impact={
+                "attack_cost": "Transaction fees only",
+                "exploitability": "High — the signer seeds are instruction data",
+                "breach_cost_context": "PDA signer confusion is a recurring cause of vault drains.",
+            },
+            anchor_versions_affected="All versions (developer-side pattern)",
+            ecosystem_recommendations=[
+                "Derive signer seeds from accounts Anchor validated, never from instruction data",
+                "Declare every signing PDA in the accounts struct with seeds and bump",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Declare the signing PDA in the accounts struct and sign with the same seeds:\n"
+            "  #[account(seeds = [b\"vault\", owner.key().as_ref()], bump = vault.bump)]\n"
+            "  pub vault: Account<'info, Vault>,\n"
+            "  pub owner: Signer<'info>,\n"
+            "  let seeds = &[b\"vault\", ctx.accounts.owner.key.as_ref(), &[ctx.accounts.vault.bump]];"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "invoke_signed signs for the PDA its seeds derive, whatever account "
+            "the instruction was given. Seeds taken from instruction data or "
+            "unvalidated accounts let the caller choose that PDA; seeds that "
+            "match no declared recipe sign for an address no constraint checks."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. The program signs a transfer with seeds [b\"vault\", owner] where owner is an argument\n"
+            "2. Attacker passes the victim's key as owner and the victim's vault as the source\n"
+            "3. The program's signature authorizes the transfer out of the victim's vault\n"
+            "4. Attacker receives the victim's funds"
+        )
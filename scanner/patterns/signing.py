@@ -0,0 +1,122 @@
+"""
+Interprocedural summary of functions that sign CPIs with PDA seeds.
+
+Vault payouts usually sit in a helper shared by several instructions
+(Raydium's util/token.rs):
+
+    pub fn transfer_from_pool_vault_to_user<'info>(
+        pool_state_loader: &AccountLoader<'info, PoolState>, ...
+    ) -> Result<()> {
+        token_2022::transfer_checked(
+            CpiContext::new_with_signer(token_program.to_account_info(), accounts,
+                                        &[&pool_state_loader.load()?.seeds()]),
+            amount, decimals,
+        )
+    }
+
+    decrease_liquidity(..) -> transfer_from_pool_vault_to_user(..)
+
+pda_signers() summarizes each function in a source unit once: whether it
+signs with PDA seeds, directly (`invoke_signed`, `new_with_signer`,
+`.with_signer`) or by calling a function that does, iterated to a fixed
+point so signing two or more calls deep is found. Each summary keeps the
+call chain down to the signing function and the seeds it passes. Rules then
+check every caller against the summary instead of re-analyzing the helper at
+each call site.
+
+Resolution is by function or method name within the unit; calls qualified
+with an SPL/system module path (`token::transfer`) are not local and are
+ignored, and helpers in other files are not seen.
+"""
+
+import re
+from dataclasses import dataclass, field
+from typing import Optional
+
+from scanner.budget import DEFAULT_DEPTH
+from scanner.patterns.base import VulnerabilityPattern
+
+SIGNED_CPI_RE = re.compile(r"\binvoke_signed\s*\(|\bnew_with_signer\s*\(|\.\s*with_signer\s*\(")
+CALL_RE = re.compile(r"((?:\w+\s*::\s*)*)\b(\w+)\s*(?:::\s*<[^>]*>\s*)?\(")
+# Module paths of CPI clients whose functions share names with local helpers
+EXTERNAL_MODULES = {
+    "token", "token_2022", "token_interface", "system_program", "associated_token",
+    "anchor_spl", "anchor_lang", "solana_program", "program", "spl_token",
+}
+
+
+@dataclass
+class SigningSummary:
+    """How a function comes to sign with PDA seeds."""
+
+    function: str
+    # Function names from this one down to the one that signs
+    chain: list = field(default_factory=list)
+    # Signer seeds argument at the signing call, collapsed to one line
+    seeds: str = ""
+
+
+def pda_signers(content: str, functions: Optional[list] = None, depth: int = DEFAULT_DEPTH) -> dict:
+    """{function name: SigningSummary} for every function that signs, directly or
+    through callees; a handler calling one reaches the signing call within `depth` calls."""
+    functions = functions if functions is not None else VulnerabilityPattern._find_functions(content)
+    summaries = {}
+    for fn in functions:
+        m = SIGNED_CPI_RE.search(fn["body"])
+        if m and fn["name"] not in summaries:
+            summaries[fn["name"]] = SigningSummary(fn["name"], [fn["name"]], _seeds_arg(fn["body"], m))
+    # A caller is one call above its callee's chain; one level per round
+    for _ in range(min(len(functions), depth - 1)):
+        changed = False
+        previous = dict(summaries)
+        for fn in functions:
+            if fn["name"] in summaries:
+                continue
+            call = next(iter(signing_calls(fn["body"], previous)), None)
+            if call:
+                callee = previous[call[1]]
+                summaries[fn["name"]] = SigningSummary(fn["name"], [fn["name"]] + callee.chain, callee.seeds)
+                changed = True
+        if not changed:
+            break
+    return summaries
+
+
+def signing_calls(body: str, summaries: dict) -> list:
+    """[(offset, function name)] of calls in `body` to summarized signing functions."""
+    calls = []
+    for m in CALL_RE.finditer(body):
+        qualifier, name = m.group(1), m.group(2)
+        if name not in summaries:
+            continue
+        segments = [s.strip() for s in qualifier.split("::") if s.strip()]
+        if segments and segments[-1] in EXTERNAL_MODULES:
+            continue
+        calls.append((m.start(2), name))
+    return calls
+
+
+def _seeds_arg(body: str, m) -> str:
+    """Last argument of the signing call: the signer seeds."""
+    depth, last, arg = 0, m.end(), ""
+    for i in range(m.end() - 1, len(body)):
+        ch = body[i]
+        if ch in "([{":
+            depth += 1
+        elif ch in ")]}":
+            depth -= 1
+            if depth == 0:
+                # A trailing comma leaves an empty last argument
+                return _resolve(body, " ".join(body[last:i].split()) or arg)
+        elif ch == "," and depth == 1:
+            arg, last = " ".join(body[last:i].split()), i + 1
+    return ""
+
+
+def _resolve(body: str, arg: str) -> str:
+    """The `let` binding behind a seeds variable, e.g. `signer_seeds` -> `&[&[POOL_SEED, ..]]`."""
+    m = re.fullmatch(r"&?\s*(\w+)", arg)
+    if not m:
+        return arg
+    binding = re.search(rf"\blet\s+{m.group(1)}\s*(?::[^=]+)?=\s*([^;]+);", body)
+    return " ".join(binding.group(1).split()) if binding else arg
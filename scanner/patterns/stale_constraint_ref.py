@@ -0,0 +1,153 @@
+"""
+ANCHOR-008: Copy-Pasted Constraint With Stale Identifier
+
+Detects account constraints that reference the wrong sibling account — the
+typical residue of copy-pasting a constraint block for `vault_0` into
+`vault_1` and forgetting to update one identifier:
+
+    #[account(mut, token::mint = token_vault_0.mint)]
+    pub token_account_1: Box<Account<'info, TokenAccount>>,
+
+Indexed fields (`*_0` / `*_1`, `*_a` / `*_b`) are checked for constraint
+clauses that only mention a different index. A `bump = x.bump` whose source
+account does not appear in the field's own seeds is reported as well.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+
+
+class StaleConstraintRefPattern(VulnerabilityPattern):
+    id = "ANCHOR-008"
+    name = "Copy-Pasted Constraint With Stale Identifier"
+    severity = "High"
+    category = "constraints"
+    remediation_effort = "mechanical"
+    description = (
+        "Constraint on an indexed account references a sibling with a different "
+        "index (or a bump from an unrelated account). The constraint validates "
+        "the wrong relationship, leaving the intended one unchecked."
+    )
+
+    INDEXED_IDENT_RE = re.compile(r"\b([A-Za-z]\w*?(?:_\d+\w*|_[ab]))\b")
+
+    # Clause kinds where cross-index references are expected (ordering/distinctness)
+    COMPARISON_RE = re.compile(r"!=|<|>")
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+
+        for struct_name, struct_body, struct_start in self._find_derive_accounts_structs(content):
+            fields = self._parse_struct_fields(struct_body, struct_start)
+            for field in fields:
+                clauses = self._account_clauses(field["attrs"])
+                if not clauses:
+                    continue
+
+                own_index = self._index_of(field["name"])
+                if own_index is not None:
+                    for clause in clauses:
+                        if self.COMPARISON_RE.search(clause):
+                            continue
+                        indices = {}
+                        for ident in self.INDEXED_IDENT_RE.findall(clause):
+                            if ident == field["name"]:
+                                continue
+                            idx = self._index_of(ident)
+                            if idx is not None:
+                                indices.setdefault(idx, ident)
+                        if not indices or own_index in indices:
+                            continue
+                        stale = next(iter(indices.values()))
+                        findings.append(self._make_finding(
+                            file_path, content, field, struct_name,
+                            f"constraint `{clause.strip()}` on '{field['name']}' "
+                            f"(index {own_index}) references '{stale}' "
+                            f"(index {self._index_of(stale)})",
+                        ))
+
+                bump_source = self._bump_source(clauses)
+                seeds = next((c for c in clauses if re.match(r"\s*seeds\s*=", c)), None)
+                if bump_source and seeds and bump_source != field["name"]:
+                    if not re.search(rf"\b{re.escape(bump_source)}\b", seeds):
+                        findings.append(self._make_finding(
+                            file_path, content, field, struct_name,
+                            f"'{field['name']}' takes its bump from "
+                            f"'{bump_source}', which is not part of its seeds "
+                            f"`{seeds.strip()}`",
+                        ))
+
+        return findings
+
+    @staticmethod
+    def _index_of(name: str):
+        """Return the positional index encoded in an identifier (0/1/a/b), or None."""
+        m = re.search(r"(?:^|_)(\d+)(?:_|$)", name)
+        if m:
+            return m.group(1)
+        m = re.search(r"_([ab])$", name)
+        if m:
+            return m.group(1)
+        return None
+
+    @staticmethod
+    def _bump_source(clauses: list[str]):
+        """Account name used in `bump = account.field`, if any."""
+        for clause in clauses:
+            m = re.match(r"\s*bump\s*=\s*(\w+)\s*\.", clause)
+            if m:
+                return m.group(1)
+        return None
+
+    def _make_finding(self, file_path, content, field, struct_name, what) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=self.severity,
+            file=file_path,
+            line=field["line"],
+            description=f"In struct {struct_name}: {what}. Likely a copy-paste error.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, field["line"]),
+            before_after_state={
+                "before": "Intended: token_account_1.mint == token_vault_1.mint",
+                "after": "Enforced: token_account_1.mint == token_vault_0.mint",
+                "damage": "The account for one side of the pool is validated against the other side.",
+            },
+            impact={
+                "attack_cost": "< 0.01 SOL",
+                "exploitability": "High — the intended binding is simply absent",
+                "breach_cost_context": "Copy-paste constraint errors are a recurring root cause in forked AMM audits.",
+            },
+            anchor_versions_affected="All versions (developer-side pattern)",
+            ecosystem_recommendations=[
+                "Review every indexed constraint block side by side",
+                "Derive per-side constraints from a single macro or helper to avoid drift",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Reference the sibling with the same index as the constrained field:\n"
+            "  #[account(mut, token::mint = token_vault_1.mint)]\n"
+            "  pub token_account_1: Box<Account<'info, TokenAccount>>,\n"
+            "and take the bump from the account whose key is part of the seeds."
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Accounts structs for two-sided pools repeat near-identical constraint "
+            "blocks per side. When a block is copied and one identifier is not "
+            "updated, the constraint still compiles and passes for honest inputs, "
+            "but binds the account to the wrong sibling."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. token_account_1 is constrained against token_vault_0's mint\n"
+            "2. Attacker passes a token_account_1 of mint 0 instead of mint 1\n"
+            "3. The constraint passes; the handler moves the wrong token\n"
+            "4. Pool accounting for side 1 is credited with side-0 tokens"
+        )
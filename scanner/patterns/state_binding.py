@@ -0,0 +1,175 @@
+"""
+ANCHOR-022: Account Trusted By Name Only
+
+Program state records the accounts it works with — `pool.token_vault_0`,
+`state.msol_mint`, `config.fee_receiver`. Every instruction that takes such
+an account next to the state must check it is the recorded one; naming the
+field after the stored key checks nothing:
+
+    #[derive(Accounts)]
+    pub struct Swap<'info> {
+        pub pool_state: AccountLoader<'info, PoolState>,   // stores token_vault_0
+        #[account(mut)]
+        pub token_vault_0: Box<Account<'info, TokenAccount>>,   // any vault will do
+    }
+
+The rule builds the graph of `#[account]` state structs and their Pubkey
+fields, then, for every accounts struct holding a state account, looks for
+sibling fields named like one of the state's Pubkey fields. The edge counts
+as enforced by:
+
+  - `has_one = <field>` on the state account
+  - `address = ...` or `seeds` on the field (an independently pinned account)
+  - a constraint naming both the field and `<state>.<field>`, or
+    `associated_token::` derivation
+  - a key comparison in a handler of the struct
+    (`require_keys_eq!(ctx.accounts.token_vault_0.key(), pool.token_vault_0)`)
+
+Structs that create the state or the account (`init`, `zero`), and handlers
+that assign `<state>.<field> = <field>.key()`, are skipped: that is where the
+stored key is written. Program, Interface and Sysvar fields are checked by
+Anchor itself.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+
+STATE_STRUCT_RE = re.compile(
+    r"#\[account(?:\([^)]*\))?\]\s*(?:#\[[^\]]*\]\s*)*pub\s+struct\s+(\w+)\s*\{"
+)
+# Anchor checks the address of these itself
+SELF_CHECKED_WRAPPERS = ("Program", "Interface", "Sysvar", "Signer")
+
+
+class StateBindingPattern(VulnerabilityPattern):
+    id = "ANCHOR-022"
+    name = "Account Trusted By Name Only"
+    severity = "High"
+    category = "constraints"
+    remediation_effort = "mechanical"
+    description = (
+        "An account whose key is stored in program state is passed next to that "
+        "state, but no constraint ties it to the stored key."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        facts = Facts(content)
+        stored = self._stored_keys(content)
+        handlers = {}
+        for ix in facts.instructions():
+            handlers.setdefault(ix.accounts_struct, []).append(ix.body)
+
+        for struct in facts.accounts_structs():
+            if any(a.has("init") or a.has("zero") for a in struct.accounts):
+                continue
+            fields = {a.name: a for a in struct.accounts}
+            bodies = " ".join(handlers.get(struct.name, []))
+            for state in struct.accounts:
+                if state.inner_type not in stored:
+                    continue
+                for key in stored[state.inner_type]:
+                    account = fields.get(key)
+                    if account is None or account is state or account.is_signer \
+                            or account.wrapper in SELF_CHECKED_WRAPPERS:
+                        continue
+                    if self._enforced(state, account, struct, bodies):
+                        continue
+                    findings.append(self._finding(file_path, content, struct.name, state, state.inner_type, account))
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    def _stored_keys(self, content: str) -> dict:
+        """#[account] struct name -> its Pubkey field names."""
+        result = {}
+        for m in STATE_STRUCT_RE.finditer(content):
+            end = self._find_block_end(content, m.end() - 1)
+            body = content[m.end():end] if end != -1 else ""
+            result[m.group(1)] = re.findall(r"pub\s+(\w+)\s*:\s*Pubkey\b", body)
+        return result
+
+    def _enforced(self, state, account, struct, bodies: str) -> bool:
+        name = re.escape(account.name)
+        if any(c.kind == "has_one" and c.value.split("@")[0].strip() == account.name for c in state.constraints):
+            return True
+        if any(c.kind in ("address", "seeds") or c.kind.startswith("associated_token::")
+               for c in account.constraints):
+            return True
+        # `<state>.<field>`, also through `.load()?` on zero-copy state
+        stored_ref = rf"\b{re.escape(state.name)}\s*(?:\.\s*load\w*\s*\(\s*\)\s*\??\s*)?\.\s*{name}\b"
+        for other in struct.accounts:
+            for c in other.constraints:
+                text = c.value if c.kind == "constraint" else c.text if "==" in c.kind else ""
+                if re.search(stored_ref, text) and len(re.findall(rf"\b{name}\b", text)) >= 2:
+                    return True
+        for stmt in re.split(r"[;{}]", bodies):
+            # A setter writes the stored key from the account instead of checking it
+            if re.search(rf"\.\s*{name}\s*=[^=]", stmt) and re.search(rf"\b{name}\b[^;]*\bkey\b", stmt):
+                return True
+            if len(re.findall(rf"\b{name}\b", stmt)) >= 2 and re.search(r"\bkey\b", stmt) \
+                    and re.search(rf"==|!=|\b{self._guards()}!", stmt):
+                return True
+        return False
+
+    def _finding(self, file_path, content, struct_name, state, state_type, account) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=self.severity,
+            file=file_path,
+            line=account.line,
+            description=(
+                f"In struct {struct_name}: '{account.name}' is passed next to '{state.name}' "
+                f"({state_type}), which stores {state_type}.{account.name}, but nothing checks "
+                f"{account.name}.key() == {state.name}.{account.name}. The account is trusted by name only."
+            ),
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=(
+                f"Tie the account to the stored key:\n"
+                f"  #[account(has_one = {account.name})]\n"
+                f"  pub {state.name}: ...,\n"
+                f"or on the account:\n"
+                f"  #[account(constraint = {account.name}.key() == {state.name}.{account.name})]"
+            ),
+            code_snippet=self._extract_snippet(content, account.line),
+            before_after_state={
+                "before": f"{account.name} is the account recorded in {state.name}",
+                "after": f"{account.name} is any account of the right type the caller chose",
+                "damage": "The instruction moves funds into, out of, or according to an attacker's account.",
+            },
+            impact={
+                "attack_cost": "< 0.01 SOL (single transaction)",
+                "exploitability": "High — the substitute account only needs the right type",
+                "breach_cost_context": "Unbound vault and mint accounts are a recurring root cause of pool drains.",
+            },
+            anchor_versions_affected="All versions (developer-side pattern)",
+            ecosystem_recommendations=[
+                "Use has_one for every Pubkey the state stores and the instruction takes",
+                "Derive accounts as PDAs of the state where possible, so their address is implied",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Tie the account to the key the state stores:\n"
+            "  #[account(has_one = token_vault_0)]\n"
+            "  pub pool_state: Account<'info, PoolState>,"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Anchor matches accounts by position; the field name is documentation. "
+            "When state records which account to use, only a has_one, address or "
+            "key constraint makes the instruction use that account."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. Attacker creates a token account (or mint) of the expected type\n"
+            "2. Attacker passes it where the pool's recorded vault belongs\n"
+            "3. The instruction pays into or values against the attacker's account\n"
+            "4. Pool accounting and real balances diverge in the attacker's favour"
+        )
@@ -0,0 +1,193 @@
+"""
+ANCHOR-023: init_if_needed State Reinitialization
+
+init_if_needed on program state runs the handler whether the account was
+just created or already existed. A handler written for the first call then
+resets live state on every later call:
+
+    #[account(init_if_needed, payer = user, space = 8 + Vault::LEN,
+              seeds = [b"vault", pool.key().as_ref()], bump)]
+    pub vault: Account<'info, Vault>,
+
+    let vault = &mut ctx.accounts.vault;
+    vault.authority = ctx.accounts.user.key();    // anyone takes over the vault
+    vault.total_deposits = 0;                     // or wipes its accounting
+
+The rule reports init_if_needed accounts of program-owned types (token
+accounts and mints are ANCHOR-001's concern) whose handler — a function
+taking the accounts struct as `Context<T>`, or a method of T — assigns an
+authority or balance field, or replaces the whole account (`set_inner`,
+`**vault = Vault { .. }`), at the top level of the body, before any check
+on the account. An assignment inside an `if`/`match` block, or after a
+`require!`/`if` that reads the account (`require!(!vault.initialized)`,
+`if vault.authority == Pubkey::default()`), is taken as guarded. Setting an
+authority to the signer the account's seeds are derived from re-asserts the
+same value and is not reported.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+
+TOKEN_TYPES = {"TokenAccount", "Mint"}
+SENSITIVE_FIELD_RE = re.compile(
+    r"authority|owner|admin|manager|operator|delegate|creator|beneficiary"
+    r"|balance|amount|deposit|shares|debt|reward|stake|lamports|supply|total|collateral|credit"
+)
+
+
+class StateReinitPattern(VulnerabilityPattern):
+    id = "ANCHOR-023"
+    name = "init_if_needed State Reinitialization"
+    severity = "High"
+    category = "init"
+    remediation_effort = "localized"
+    description = (
+        "Program state is accepted via init_if_needed and the handler overwrites "
+        "authority or balance fields without checking whether the account was "
+        "already initialized."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        facts = Facts(content)
+        handlers = {}
+        for ix in facts.instructions():
+            handlers.setdefault(ix.accounts_struct, []).append((ix.name, ix.body, ix.body_start))
+        for struct in facts.accounts_structs():
+            for fn in self._methods(content, struct.name):
+                handlers.setdefault(struct.name, []).append((fn["name"], fn["body"], fn["body_start"]))
+
+        for struct in facts.accounts_structs():
+            for account in struct.accounts:
+                if not account.has("init_if_needed") or self._token_account(account):
+                    continue
+                for fn_name, body, body_start in handlers.get(struct.name, []):
+                    hit = self._reset(body, account, struct)
+                    if hit:
+                        offset, fields = hit
+                        line = self._get_line_number(content, body_start + offset)
+                        findings.append(self._finding(file_path, content, struct.name, account, fn_name, fields, line))
+                        break
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    @staticmethod
+    def _token_account(account) -> bool:
+        return account.inner_type in TOKEN_TYPES or any(
+            c.kind.startswith(("token::", "associated_token::", "mint::")) for c in account.constraints
+        )
+
+    def _methods(self, content: str, struct_name: str) -> list:
+        """Functions in `impl ... StructName<'info>` blocks."""
+        methods = []
+        for m in re.finditer(rf"\bimpl\s*(?:<[^>]*>)?\s*{struct_name}\s*(?:<[^>]*>)?\s*\{{", content):
+            end = self._find_block_end(content, m.end() - 1)
+            if end == -1:
+                continue
+            methods += [fn for fn in self._find_functions(content) if m.end() <= fn["body_start"] < end]
+        return methods
+
+    def _reset(self, body: str, account, struct):
+        """(offset, fields) of the first unguarded top-level overwrite of the account."""
+        name = re.escape(account.name)
+        refs = [rf"(?:ctx\s*\.\s*)?accounts\s*\.\s*{name}\b", rf"\bself\s*\.\s*{name}\b"]
+        for m in re.finditer(
+            rf"\blet\s+(?:mut\s+)?(\w+)\s*(?::[^=;]+)?=\s*&?\s*(?:mut\s+)?(?:ctx\s*\.\s*accounts|self)\s*\.\s*{name}\b",
+            body,
+        ):
+            refs.append(rf"(?<![.\w]){m.group(1)}\b")
+        target = "(?:" + "|".join(refs) + r")(?:\s*\.\s*load\w*\s*\(\s*\)\s*\??)?"
+        seeded_signers = [
+            a.name for a in struct.accounts
+            if a.is_signer and any(c.kind == "seeds" and re.search(rf"\b{re.escape(a.name)}\b", c.value)
+                                   for c in account.constraints)
+        ]
+        guard_re = re.compile(rf"\b(?:if|match|{self._guards()}!|assert\w*!)")
+
+        depth, begin = 0, 0
+        for m in re.finditer(r"[;{}]", body + ";"):
+            stmt = body[begin:m.start()]
+            offset = begin + len(stmt) - len(stmt.lstrip())
+            if depth == 0:
+                if guard_re.search(stmt) and re.search(target, stmt):
+                    return None
+                if re.search(rf"{target}\s*\.\s*set_inner\s*\(|\*\*?\s*{target}\s*=\s*\w+\s*\{{", stmt):
+                    return offset, ["all fields"]
+                assigned = re.search(rf"{target}\s*\.\s*(\w+)\s*=(?!=)([^;]*)", stmt)
+                if assigned and SENSITIVE_FIELD_RE.search(assigned.group(1).lower()):
+                    value = assigned.group(2)
+                    if not any(re.search(rf"\b{s}\b", value) for s in seeded_signers):
+                        fields = [assigned.group(1)]
+                        for later in re.finditer(rf"{target}\s*\.\s*(\w+)\s*=(?!=)", body[m.end():]):
+                            if SENSITIVE_FIELD_RE.search(later.group(1).lower()) and later.group(1) not in fields:
+                                fields.append(later.group(1))
+                        return offset, fields
+            if m.group(0) == "{":
+                depth += 1
+            elif m.group(0) == "}":
+                depth -= 1
+            begin = m.end()
+        return None
+
+    def _finding(self, file_path, content, struct_name, account, fn_name, fields, line) -> Finding:
+        shown = ", ".join(fields[:4]) + (f" (+{len(fields) - 4} more)" if len(fields) > 4 else "")
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=self.severity,
+            file=file_path,
+            line=line,
+            description=(
+                f"'{account.name}' ({account.inner_type or account.type}) is init_if_needed in struct "
+                f"{struct_name}, and {fn_name}() overwrites {shown} without checking whether the account "
+                f"already held state. Calling it again resets a live account."
+            ),
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=(
+                f"Use `init` and move reconfiguration to a separate, authorized instruction, or guard the "
+                f"writes:\n"
+                f"  require!(!{account.name}.is_initialized, ErrorCode::AlreadyInitialized);\n"
+                f"  {account.name}.is_initialized = true;"
+            ),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": f"{account.name}: authority=OWNER, balances reflect past deposits",
+                "after": f"{account.name}: {shown} overwritten by the caller's second init",
+                "damage": "Authority takeover, or wiped debt/deposit accounting.",
+            },
+            impact={
+                "attack_cost": "< 0.01 SOL (single transaction)",
+                "exploitability": "High — the init instruction is usually permissionless",
+                "breach_cost_context": "Reinitialization of existing state is a recurring critical audit finding.",
+            },
+            anchor_versions_affected="0.25.0+ (init_if_needed)",
+            ecosystem_recommendations=[
+                "Prefer init for program state; init_if_needed is meant for idempotent token/ATA creation",
+                "Track initialization explicitly when an instruction must accept both cases",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Use `init` for program state, or guard the writes:\n"
+            "  require!(!state.is_initialized, ErrorCode::AlreadyInitialized);\n"
+            "  state.is_initialized = true;"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "init_if_needed skips creation when the account exists and runs the "
+            "handler anyway. Writes meant to set up a fresh account then overwrite "
+            "the state of an account that is already in use."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. Victim initializes the account and uses it (deposits, becomes authority)\n"
+            "2. Attacker calls the init_if_needed instruction on the same account\n"
+            "3. The handler overwrites authority with the attacker or zeroes balances\n"
+            "4. Attacker withdraws as the new authority, or debts are erased"
+        )
@@ -0,0 +1,183 @@
+"""
+ANCHOR-021: Sysvar Account Spoofing
+
+A sysvar passed as a raw account is just another account the caller picks:
+
+    #[derive(Accounts)]
+    pub struct VerifySignatures<'info> {
+        /// CHECK: instructions sysvar
+        pub instructions: UncheckedAccount<'info>,      // any account with fake data
+    }
+
+    let ix = load_instruction_at(0, &ctx.accounts.instructions)?;   // unchecked
+
+Wormhole lost 120k ETH to a spoofed Instructions sysvar. The rule reports
+AccountInfo/UncheckedAccount fields that stand for Clock, Rent,
+Instructions or EpochSchedule — by name (`clock`, `rent`, `instructions`,
+`ix_sysvar`, `epoch_schedule`...) or because the program reads them with a
+sysvar API — unless the account is pinned:
+
+  - `Sysvar<'info, T>` (not reported at all), or `address = sysvar::<x>::ID`
+  - a key comparison with the sysvar ID in a constraint or the handler
+    (`require_keys_eq!(ix.key(), sysvar::instructions::ID)`, `check_id(..)`)
+  - a read through an API that checks the ID itself: `T::from_account_info`,
+    `load_instruction_at_checked`, `load_current_index_checked`,
+    `get_instruction_relative`
+
+Fields that are only forwarded into a CPI (`rent: self.rent.clone()`) are
+left to the callee, which validates them.
+"""
+
+import re
+from scanner.patterns.account_types import account_type
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+
+SYSVAR_NAMES = [
+    ("Clock", re.compile(r"^(?:sysvar_)?clock(?:_sysvar|_account|_info)?$")),
+    ("Rent", re.compile(r"^(?:sysvar_)?rent(?:_sysvar|_account|_info)?$")),
+    ("Instructions", re.compile(
+        r"^(?:sysvar_|tx_)?instructions?(?:_sysvar)?(?:_account|_info)?$|^(?:sysvar_)?ixs?_sysvar(?:_account|_info)?$"
+        r"|^sysvar_ixs?$"
+    )),
+    ("EpochSchedule", re.compile(r"^(?:sysvar_)?epoch_schedule(?:_sysvar|_account|_info)?$")),
+]
+# API -> sysvar it reads; the unchecked variants trust whatever account is passed
+SYSVAR_APIS = [
+    ("Instructions", r"\bload_instruction_at\s*\("),
+    ("Instructions", r"\bload_current_index\s*\("),
+    ("Clock", r"\bClock\s*::\s*from_account_info\s*\("),
+    ("Rent", r"\bRent\s*::\s*from_account_info\s*\("),
+    ("EpochSchedule", r"\bEpochSchedule\s*::\s*from_account_info\s*\("),
+]
+CHECKED_API_RE = re.compile(
+    r"\bfrom_account_info\s*\(|\bload_instruction_at_checked\s*\(|\bload_current_index_checked\s*\("
+    r"|\bget_instruction_relative\s*\("
+)
+
+
+class SysvarSpoofingPattern(VulnerabilityPattern):
+    id = "ANCHOR-021"
+    name = "Sysvar Account Spoofing"
+    severity = "High"
+    category = "accounts"
+    remediation_effort = "mechanical"
+    description = (
+        "A Clock, Rent, Instructions or EpochSchedule sysvar is taken as a raw "
+        "account without checking its address, so callers can pass fake sysvar data."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        facts = Facts(content)
+        for struct in facts.accounts_structs():
+            for account in struct.accounts:
+                if not account_type(account.type).is_raw:
+                    continue
+                uses = self._uses(account.name, content)
+                sysvar = self._sysvar(account.name, uses)
+                if not sysvar or self._pinned(account, struct, uses) or self._forwarded(account.name, uses):
+                    continue
+                findings.append(self._finding(file_path, content, struct.name, account, sysvar))
+        return findings
+
+    @staticmethod
+    def _uses(name: str, content: str) -> list:
+        """Statements referring to the field through `ctx.accounts`, `accounts` or `self`."""
+        ref = re.compile(rf"\b(?:accounts|self)\s*\.\s*{re.escape(name)}\b")
+        return [stmt for stmt in re.split(r";|\{|\}", content) if ref.search(stmt)]
+
+    @staticmethod
+    def _sysvar(name: str, uses: list) -> str:
+        for sysvar, name_re in SYSVAR_NAMES:
+            if name_re.match(name):
+                return sysvar
+        for sysvar, api in SYSVAR_APIS:
+            if any(re.search(api, stmt) for stmt in uses):
+                return sysvar
+        return ""
+
+    def _pinned(self, account, struct, uses: list) -> bool:
+        if account.has("address"):
+            return True
+        name = re.escape(account.name)
+        for other in struct.accounts:
+            for c in other.constraints:
+                if c.kind == "constraint" and re.search(rf"\b{name}\b", c.value) and "sysvar" in c.value:
+                    return True
+        for stmt in uses:
+            if CHECKED_API_RE.search(stmt):
+                return True
+            if re.search(r"\bsysvar\b|\bcheck_id\s*\(", stmt) and (
+                re.search(rf"==|!=|\bcheck_id\s*\(|\b{self._guards()}!", stmt)
+            ):
+                return True
+        return False
+
+    @staticmethod
+    def _forwarded(name: str, uses: list) -> bool:
+        """Every use hands the account to a CPI accounts struct."""
+        ref = rf"(?:ctx\s*\.\s*)?(?:accounts|self)\s*\.\s*{re.escape(name)}"
+        forward_re = re.compile(rf"\w+\s*:\s*{ref}\s*\.\s*(?:clone|to_account_info)\s*\(\s*\)\s*(?:,|$)")
+        read_re = re.compile(rf"{ref}\s*\.\s*(?:data|lamports|key)\b")
+        return bool(uses) and all(forward_re.search(stmt) and not read_re.search(stmt) for stmt in uses)
+
+    def _finding(self, file_path, content, struct_name, account, sysvar) -> Finding:
+        kind = account.wrapper or "AccountInfo"
+        module = re.sub(r"(?<!^)([A-Z])", r"_\1", sysvar).lower()
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=self.severity,
+            file=file_path,
+            line=account.line,
+            description=(
+                f"In struct {struct_name}: '{account.name}' stands for the {sysvar} sysvar but is "
+                f"typed as {kind} and its address is never checked. A caller can pass an account "
+                f"with fabricated {sysvar} data."
+            ),
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=(
+                f"Type it as Sysvar<'info, {sysvar}>, or pin the address:\n"
+                f"  #[account(address = sysvar::{module}::ID)]\n"
+                f"  pub {account.name}: UncheckedAccount<'info>,"
+            ),
+            code_snippet=self._extract_snippet(content, account.line),
+            before_after_state={
+                "before": f"{account.name} expected to be the {sysvar} sysvar",
+                "after": f"{account.name} is an attacker-created account with forged {sysvar} data",
+                "damage": "Time locks, rent checks or instruction introspection (signature verification) are bypassed.",
+            },
+            impact={
+                "attack_cost": "One account creation (< 0.01 SOL)",
+                "exploitability": "High — the account is chosen by the caller",
+                "breach_cost_context": "A spoofed Instructions sysvar was the root cause of the $326M Wormhole exploit.",
+            },
+            anchor_versions_affected="All versions (developer-side pattern)",
+            ecosystem_recommendations=[
+                "Prefer Sysvar<'info, T> or Clock::get()/Rent::get() over passing sysvar accounts",
+                "Use the *_checked instruction introspection APIs",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Use Sysvar<'info, T> (or T::get()), or pin the address with\n"
+            "  #[account(address = sysvar::instructions::ID)]"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Sysvars are ordinary accounts at well-known addresses. Taken as "
+            "AccountInfo/UncheckedAccount, nothing checks that the account passed "
+            "is the real sysvar, and its data is whatever the caller wrote."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. Attacker creates an account laid out like the sysvar\n"
+            "2. Attacker fills it with forged data (a past timestamp, a fake secp256k1 instruction)\n"
+            "3. Attacker passes it in place of the sysvar\n"
+            "4. The program acts on the forged data"
+        )
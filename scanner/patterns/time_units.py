@@ -0,0 +1,348 @@
+"""
+ANCHOR-018: Epoch / Slot / Timestamp Unit Confusion
+
+Staking, vesting and cooldown code reads three clocks from the Clock sysvar:
+`epoch` (u64, ~2 days), `slot` (u64, ~400ms) and `unix_timestamp` (i64,
+seconds). They are all integers, so mixing them compiles:
+
+    let unlock_epoch = stake.deactivation_epoch + ctx.accounts.config.cooldown_seconds;
+    require!(clock.slot >= stake.unlock_epoch, ErrorCode::StillLocked);
+    let elapsed = (clock.unix_timestamp as u64) - stake.last_claim_ts;
+
+Two kinds of issue are reported:
+
+  - unit mix: `+ - += -= < > ==` (or checked/saturating add/sub) between an
+    epoch, a slot and a timestamp value without a conversion
+    (`slots_to_epochs(..)`, a `*_PER_*` factor, or scaling by `*` / `/`)
+  - unchecked cast: in reward/cooldown/vesting functions, `unix_timestamp`
+    cast to an unsigned type (a negative or skewed clock wraps to ~2^64),
+    a clock epoch/slot cast to a signed type, or any time value narrowed
+    to 32 bits or less with `as`
+
+Units are inferred from names (`*_epoch`, `*_slot`, `*_ts`, `*_timestamp`,
+`*_time`, `*_at`, `*_secs`), Clock fields, and `let` bindings that carry
+the unit forward. `TryFrom` conversions and casts preceded by a `>= 0`
+guard on the same value are not reported.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+
+EPOCHS, SLOTS, SECONDS = "epochs", "slots", "seconds"
+
+
+class TimeUnitPattern(VulnerabilityPattern):
+    id = "ANCHOR-018"
+    name = "Epoch / Slot / Timestamp Unit Confusion"
+    severity = "Medium"
+    category = "arithmetic"
+    remediation_effort = "localized"
+    description = (
+        "Expression mixes epochs, slots and unix timestamps, or converts a "
+        "clock value with an unchecked `as` cast, in time-based reward or "
+        "cooldown logic."
+    )
+
+    _CALL = r"(?:\s*\((?:[^()]|\([^()]*\))*\))"
+    OPERAND_RE = re.compile(
+        r"\**&?(?:\w+\s*::\s*)*\w+" + _CALL + r"?(?:\s*\.\s*\w+" + _CALL + r"?)*"
+    )
+    LEFT_OPERAND_RE = re.compile(
+        r"(\**&?(?:\w+\s*::\s*)*\w+" + _CALL + r"?(?:\s*\.\s*\w+" + _CALL + r"?)*)\s*$"
+    )
+    CAST_RE = re.compile(r"\bas\s+(u8|u16|u32|u64|u128|i8|i16|i32|i64|i128)\b")
+
+    # `-` excludes `->`; `>` excludes `=>` / `->`
+    OPERATOR_RE = re.compile(r"\+=|-=|>=|<=|==|!=|\+|-(?!>)|(?<![=\-])>|<")
+    METHOD_OP_RE = re.compile(r"\.\s*(?:checked|saturating|wrapping)_(?:add|sub)\s*\(")
+    LET_RE = re.compile(r"^\s*let\s+(?:mut\s+)?(\w+)\s*(?::[^=]+)?=(?!=)(.*)$", re.S)
+
+    CONVERSION_RE = re.compile(r"\b(\w+?)_(?:to|into|as)_(\w+)\s*\(")
+    SCALED_RE = re.compile(r"_PER_|_per_|\bpow\s*\(|\bms_per_slot\b")
+    TIME_LOGIC_RE = re.compile(
+        r"reward|cooldown|unlock|unstake|vest|(?<![a-z])lock|elapsed|accru|interest|emission|"
+        r"claim|stake|duration|expir|deadline|period|warmup"
+    )
+    UNSIGNED, NARROW = ("u8", "u16", "u32", "u64", "u128"), ("u8", "u16", "u32", "i8", "i16", "i32")
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        code = re.sub(r"//[^\n]*", lambda m: " " * len(m.group(0)), content)
+        findings = []
+        reported = set()
+
+        for fn in self._find_functions(code):
+            env = {}
+            body = fn["body"]
+            time_logic = bool(self.TIME_LOGIC_RE.search(fn["name"].lower()))
+            for stmt_start, stmt in self._statements(body):
+                issue = self._check_mix(stmt, env)
+                if issue is None and (time_logic or self.TIME_LOGIC_RE.search(stmt.lower())):
+                    issue = self._check_cast(stmt, env, body[:stmt_start])
+                let = self.LET_RE.match(stmt)
+                if let:
+                    env[let.group(1)] = self._expr_unit(let.group(2), env)
+                if issue is None:
+                    continue
+                offset, message = issue
+                line = self._get_line_number(content, fn["body_start"] + stmt_start + offset)
+                if line in reported:
+                    continue
+                reported.add(line)
+                findings.append(self._finding(file_path, content, fn, message, line))
+        return findings
+
+    # ── statement analysis ──────────────────────────────────────────────
+
+    def _check_mix(self, stmt: str, env: dict):
+        """(offset, message) for the first epoch/slot/timestamp mix."""
+        if self.SCALED_RE.search(stmt):
+            return None
+        let = self.LET_RE.match(stmt)
+        expr_start = let.start(2) if let else 0
+        expr = stmt[expr_start:]
+
+        for m in self.METHOD_OP_RE.finditer(expr):
+            left = self.LEFT_OPERAND_RE.search(expr[:m.start()])
+            close = self._paren_end(expr, m.end() - 1)
+            if not left or close is None:
+                continue
+            right = expr[m.end():close - 1]
+            lu, ru = self._operand_unit(left.group(1), env)[0], self._expr_unit(right, env)[0]
+            if lu and ru and lu != ru:
+                return expr_start + m.start(), self._mix_message(left.group(1), lu, right, ru)
+
+        for m in self.OPERATOR_RE.finditer(expr):
+            if self._inside_parens(expr, m.start()):
+                continue
+            left = self.LEFT_OPERAND_RE.search(expr[:m.start()])
+            rest = expr[m.end():]
+            right = self.OPERAND_RE.match(rest.lstrip())
+            if not left or not right:
+                continue
+            before = expr[:left.start(1)].rstrip()
+            after = rest.lstrip()[right.end():].lstrip()
+            # Operands of a product/quotient are scaled, not raw clock values
+            if before.endswith(("*", "/")) or after.startswith(("*", "/")):
+                continue
+            lu, ru = self._operand_unit(left.group(1), env)[0], self._operand_unit(right.group(0), env)[0]
+            if lu and ru and lu != ru:
+                return expr_start + m.start(), self._mix_message(left.group(1), lu, right.group(0), ru)
+        return None
+
+    def _check_cast(self, stmt: str, env: dict, preceding: str):
+        """(offset, message) for the first unchecked `as` cast of a clock value."""
+        for m in self.CAST_RE.finditer(stmt):
+            operand, target = self._cast_operand(stmt, m.start()), m.group(1)
+            if not operand:
+                continue
+            if operand.startswith("("):
+                unit, signed_clock = self._expr_unit(operand[1:-1], env)
+            else:
+                unit, signed_clock = self._operand_unit(operand, env)
+            if not unit:
+                continue
+            shown = self._compact(operand)
+            if target in self.NARROW:
+                problem = f"narrows {unit} value '{shown}' to {target}; it truncates silently"
+            elif unit == SECONDS and signed_clock and target in self.UNSIGNED:
+                if self._guarded_non_negative(operand, preceding):
+                    continue
+                problem = (
+                    f"casts i64 timestamp '{shown}' to {target}; a negative or skewed "
+                    f"clock value wraps to ~2^64"
+                )
+            elif unit in (EPOCHS, SLOTS) and signed_clock and target.startswith("i"):
+                problem = f"casts u64 {unit[:-1]} '{shown}' to {target}; values above i64::MAX turn negative"
+            else:
+                continue
+            return m.start(), problem
+        return None
+
+    @staticmethod
+    def _cast_operand(stmt: str, end: int) -> str:
+        """The operand of the `as` at `end`: a path/method chain or a parenthesized group."""
+        i, depth = end, 0
+        while i > 0:
+            ch = stmt[i - 1]
+            if ch == ")":
+                depth += 1
+            elif ch == "(":
+                if depth == 0:
+                    break
+                depth -= 1
+            elif depth == 0 and not (ch.isalnum() or ch in "_.:?&*" or ch.isspace()):
+                break
+            i -= 1
+        operand = stmt[i:end].strip()
+        # `let x = a.b as u64`: the operand is the last whitespace-free chain
+        return operand if operand.startswith("(") else re.split(r"\s+", operand)[-1]
+
+    def _guarded_non_negative(self, operand: str, preceding: str) -> bool:
+        name = re.sub(r"\s+", "", operand).strip("()").split(".")[-1]
+        return bool(re.search(
+            rf"\b{re.escape(name)}\s*>=?\s*0\b|(?:{self._guards()})!\s*\([^;]*\b{re.escape(name)}\b[^;]*>=?\s*0\b",
+            preceding,
+        ))
+
+    def _mix_message(self, left: str, lu: str, right: str, ru: str) -> str:
+        return (
+            f"'{self._compact(left)}' ({lu}) is combined with '{self._compact(right)}' "
+            f"({ru}) without a conversion"
+        )
+
+    def _expr_unit(self, expr: str, env: dict):
+        """(unit, clock-signed) of an expression; unit is None when scaled or mixed."""
+        expr = expr.strip()
+        if re.match(r"(?:if|match|loop|unsafe)\b", expr):
+            return None, False
+        conv = list(self.CONVERSION_RE.finditer(expr))
+        if conv:
+            return self._name_unit(conv[-1].group(2)), False
+        if self.SCALED_RE.search(expr) or re.search(r"[*/%]|checked_(?:mul|div)", expr):
+            return None, False
+        cast = re.search(r"\bas\s+(\w+)", expr)
+        units, signed = set(), False
+        for m in self.OPERAND_RE.finditer(expr):
+            if m.group(0) in ("as",) + self.UNSIGNED + ("i8", "i16", "i32", "i64", "i128"):
+                continue
+            unit, clock = self._operand_unit(m.group(0), env)
+            if unit:
+                units.add(unit)
+                signed = signed or clock
+        if len(units) != 1:
+            return None, False
+        return units.pop(), signed and not cast
+
+    def _operand_unit(self, operand: str, env: dict):
+        """(unit, clock-signed): clock-signed marks values read from Clock with its own type."""
+        operand = re.sub(r"\s+", "", operand).lstrip("*&")
+        conv = list(self.CONVERSION_RE.finditer(operand))
+        if conv:
+            return self._name_unit(conv[-1].group(2)), False
+        chain = re.sub(r"\((?:[^()]|\([^()]*\))*\)", "", operand).replace("?", "")
+        parts = [p for p in re.split(r"\.|::", chain) if p]
+        while len(parts) > 1 and (re.match(r"^(?:checked|saturating|wrapping)_(?:add|sub)$", parts[-1]) or
+                                  parts[-1] in ("unwrap", "into", "clone")):
+            parts.pop()
+        if not parts:
+            return None, False
+        last = parts[-1]
+        if len(parts) == 1 and last in env:
+            return env[last]
+        clock = any(re.match(r"(?i)^clock$|^get$", p) for p in parts[:-1])
+        unit = self._name_unit(last)
+        if last == "unix_timestamp":
+            return SECONDS, True
+        return unit, bool(unit and clock and last in ("epoch", "slot"))
+
+    @staticmethod
+    def _name_unit(name: str):
+        name = name.lower()
+        if re.search(r"_per_|(?:^|_)(?:index|idx|count|len)$", name):
+            return None
+        if re.search(r"timestamp|(?:^|_)(?:ts|time|secs?|seconds)(?:_|$)|_at$", name):
+            return SECONDS
+        if re.search(r"(?:^|_)epochs?(?:_|$)", name):
+            return EPOCHS
+        if re.search(r"(?:^|_)slots?(?:_|$)", name):
+            return SLOTS
+        return None
+
+    # ── helpers ─────────────────────────────────────────────────────────
+
+    @staticmethod
+    def _statements(body: str):
+        start = 0
+        for i, ch in enumerate(body):
+            if ch in ";{}":
+                if body[start:i].strip():
+                    yield start, body[start:i]
+                start = i + 1
+        if body[start:].strip():
+            yield start, body[start:]
+
+    @staticmethod
+    def _paren_end(text: str, open_pos: int):
+        depth = 0
+        for i in range(open_pos, len(text)):
+            if text[i] == "(":
+                depth += 1
+            elif text[i] == ")":
+                depth -= 1
+                if depth == 0:
+                    return i + 1
+        return None
+
+    def _inside_parens(self, text: str, pos: int) -> bool:
+        """True for operators inside a call's argument list (handled per call)."""
+        depth = 0
+        for ch in text[:pos]:
+            if ch == "(":
+                depth += 1
+            elif ch == ")":
+                depth -= 1
+        head = text[:pos].lstrip()
+        if depth == 1 and re.match(rf"(?:{self._guards()}!|if)\s*\(", head):
+            return False
+        return depth > 0
+
+    @staticmethod
+    def _compact(text: str) -> str:
+        text = re.sub(r"\s+", "", text)
+        return text if len(text) <= 60 else text[:57] + "..."
+
+    def _finding(self, file_path, content, fn, message, line) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=self.severity,
+            file=file_path,
+            line=line,
+            description=f"In fn {fn['name']}: {message}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "Cooldown of 3 epochs (~6 days) after deactivation",
+                "after": "Cooldown compared against slots or seconds: over in ~1 second",
+                "damage": "Locks, cooldowns or reward accrual run on the wrong clock.",
+            },
+            impact={
+                "attack_cost": "None — any staker calls the handler at the right time",
+                "exploitability": "Medium — depends on how far apart the mixed clocks are",
+                "breach_cost_context": "Unit mix-ups between epochs, slots and seconds bypass lockups or over-accrue rewards.",
+            },
+            anchor_versions_affected="All versions (developer-side pattern)",
+            ecosystem_recommendations=[
+                "Name every time value with its unit (`_epoch`, `_slot`, `_ts`)",
+                "Use u64::try_from / i64::try_from instead of `as` on clock values",
+                "Keep one clock per feature: cooldowns in epochs, vesting in seconds",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Compare values in one unit and convert clock values with checked casts:\n"
+            "  let now = u64::try_from(Clock::get()?.unix_timestamp)\n"
+            "      .map_err(|_| ErrorCode::InvalidClock)?;\n"
+            "  let elapsed = now.checked_sub(stake.last_claim_ts).ok_or(ErrorCode::Overflow)?;\n"
+            "  require!(clock.epoch >= stake.deactivation_epoch + COOLDOWN_EPOCHS, ErrorCode::StillLocked);"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Epochs, slots and unix timestamps are all plain integers, so the "
+            "compiler cannot tell them apart, and `as` between i64 and u64 "
+            "reinterprets the sign instead of failing. A cooldown measured in "
+            "epochs but compared against slots or seconds is off by orders of "
+            "magnitude."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. Staker deactivates; the program stores the current epoch\n"
+            "2. Unstake compares that epoch against clock.slot (or adds a seconds constant)\n"
+            "3. The check passes immediately instead of after the cooldown\n"
+            "4. Staker exits early or claims rewards for time that never elapsed"
+        )
@@ -0,0 +1,199 @@
+"""
+ANCHOR-035: Incomplete Token Account Constraints
+
+An associated token account is the PDA of (authority, mint). Anchor can only
+derive and check that address when both halves are given:
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = reward_mint,     // no associated_token::authority
+    )]
+    pub reward_ata: Account<'info, TokenAccount>,
+
+and a token account the program pays into is only the protocol's if
+something says which mint and owner it has:
+
+    #[account(mut)]
+    pub fee_vault: Account<'info, TokenAccount>,   // any token account
+    ..
+    token::transfer(CpiContext::new(program, Transfer { from, to: ctx.accounts.fee_vault.to_account_info(), .. }), fee)?;
+
+The rule reports:
+
+  - an account created with `init` or `init_if_needed` that gives
+    `associated_token::mint` without `associated_token::authority`, or the
+    authority without the mint (High): an existing account with the missing
+    half chosen by the caller is accepted, or the account is created for
+    the wrong owner
+  - a token account that receives a transfer, whose name marks it as the
+    protocol's (fee, treasury, vault, reserve, pool, escrow, insurance,
+    collector), and that has neither `token::mint`/`token::authority` nor
+    `associated_token::` constraints, nor `address`, `seeds`, a `has_one`
+    on state naming it, or a constraint or handler check of its mint, owner
+    or key (Medium): the caller routes the protocol's share to an account
+    it owns
+
+Destinations named after the user (`user_token`, `recipient`) are the
+caller's to choose and are not reported.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+
+TOKEN_ACCOUNT_RE = re.compile(r"\b(?:Account|InterfaceAccount)\s*<\s*'[^,]+,\s*(?:\w+::)*TokenAccount\s*>")
+PROTOCOL_NAME_RE = re.compile(r"(?i)fee|treasury|vault|reserve|pool|escrow|insurance|collector")
+DESTINATION_RE = re.compile(
+    r"\bto\s*:\s*(?:ctx\s*\.\s*accounts\s*\.\s*|self\s*\.\s*|&\s*)?(\w+)\s*(?:\.\s*to_account_info\s*\(\s*\))?"
+)
+
+
+class TokenConstraintsPattern(VulnerabilityPattern):
+    id = "ANCHOR-035"
+    name = "Incomplete Token Account Constraints"
+    severity = "High"
+    category = "constraints"
+    remediation_effort = "mechanical"
+    description = (
+        "An associated token account is created with only one of mint and "
+        "authority, or a protocol token account receiving transfers has no "
+        "mint, owner or address constraint."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        facts = Facts(content)
+        structs = {s.name: s for s in facts.accounts_structs()}
+        for struct in structs.values():
+            for account in struct.accounts:
+                half = self._missing_half(account)
+                if half:
+                    given = "authority" if half == "mint" else "mint"
+                    findings.append(self._finding(
+                        file_path, content, account.line, "High",
+                        f"In struct {struct.name}: '{account.name}' is created with associated_token::{given} "
+                        f"but no associated_token::{half}, so the ATA derivation is incomplete and the "
+                        f"{half} of the account it accepts is not checked",
+                    ))
+
+        destinations = self._destinations(content, facts, structs)
+        for struct_name, name in sorted(destinations, key=lambda d: structs[d[0]].line):
+            struct = structs[struct_name]
+            account = next(a for a in struct.accounts if a.name == name)
+            if not self._unpinned(account, struct, destinations[(struct_name, name)], content):
+                continue
+            findings.append(self._finding(
+                file_path, content, account.line, "Medium",
+                f"In struct {struct.name}: token account '{name}' receives transfers but has neither "
+                f"token::mint nor associated_token constraints, and nothing checks its mint, owner or "
+                f"address, so the caller chooses where the protocol's tokens go",
+            ))
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    @staticmethod
+    def _missing_half(account):
+        """'mint' or 'authority' when an ATA created here gives only the other one."""
+        if not (account.has("init") or account.has("init_if_needed")):
+            return None
+        has_mint = account.has("associated_token::mint")
+        has_authority = account.has("associated_token::authority")
+        if has_mint == has_authority:
+            return None
+        return "authority" if has_mint else "mint"
+
+    def _destinations(self, content: str, facts: Facts, structs: dict) -> dict:
+        """{(accounts struct, field): [bodies transferring into it]} for protocol-named token accounts."""
+        bodies = [(ix.accounts_struct, ix.body) for ix in facts.instructions()]
+        bodies += [(t, content[s:e]) for s, e, t in self._find_impl_blocks(content) if t in structs]
+        result = {}
+        for struct_name, body in bodies:
+            struct = structs.get(struct_name)
+            if not struct:
+                continue
+            accounts = {a.name: a for a in struct.accounts}
+            for m in DESTINATION_RE.finditer(body):
+                account = accounts.get(m.group(1))
+                if account and TOKEN_ACCOUNT_RE.search(account.type) and PROTOCOL_NAME_RE.search(account.name):
+                    result.setdefault((struct_name, account.name), []).append(body)
+        return result
+
+    @staticmethod
+    def _unpinned(account, struct, bodies: list, content: str) -> bool:
+        """No constraint or handler check fixes the account's mint, owner or address."""
+        for c in account.constraints:
+            if c.kind in ("address", "seeds", "init", "init_if_needed", "zero") \
+                    or c.kind.startswith(("token::", "associated_token::")):
+                return False
+            if c.kind == "constraint" and re.search(r"\.\s*(?:mint|owner|key)\b", c.value):
+                return False
+        name = re.escape(account.name)
+        for other in struct.accounts:
+            for c in other.constraints:
+                if c.kind == "has_one" and c.value.split("@")[0].strip() == account.name:
+                    return False
+                if c.kind == "constraint" and re.search(rf"\b{name}\b", c.value) and other is not account:
+                    return False
+        for body in bodies:
+            for stmt in re.split(r"[;{}]", body):
+                if re.search(rf"\b{name}\s*\.\s*(?:mint|owner|key)\b", stmt) and \
+                        re.search(r"==|!=|\brequire_keys_(?:eq|neq)!", stmt):
+                    return False
+        return True
+
+    def _finding(self, file_path, content, line, severity, message) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=severity,
+            file=file_path,
+            line=line,
+            description=f"{message}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "Swap fee of 30 USDC due to the protocol's fee vault",
+                "after": "Caller passes its own USDC account as fee_vault",
+                "damage": "Protocol fees, or tokens meant for a specific owner, go to the caller.",
+            },
+            impact={
+                "attack_cost": "Transaction fees only",
+                "exploitability": "High — the account is entirely caller-supplied",
+                "breach_cost_context": "Unconstrained fee and treasury accounts are a common audit finding.",
+            },
+            anchor_versions_affected="All versions (developer-side pattern)",
+            ecosystem_recommendations=[
+                "Give every ATA both associated_token::mint and associated_token::authority",
+                "Pin protocol token accounts with token::mint and token::authority, address or has_one",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Constrain both halves of the ATA and pin protocol accounts:\n"
+            "  #[account(init_if_needed, payer = user,\n"
+            "            associated_token::mint = reward_mint,\n"
+            "            associated_token::authority = user)]\n"
+            "  #[account(mut, token::mint = usdc_mint, token::authority = config)]\n"
+            "  pub fee_vault: Account<'info, TokenAccount>,"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Account<'info, TokenAccount> only proves the account is a token "
+            "account. Which mint it holds and who owns it are constraints the "
+            "struct has to state; an ATA constraint with one half missing "
+            "leaves the address underived."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. The swap instruction pays the protocol fee into fee_vault\n"
+            "2. fee_vault has no mint, authority or address constraint\n"
+            "3. Attacker passes a token account it owns as fee_vault\n"
+            "4. Every swap the attacker routes pays the protocol fee to the attacker"
+        )
@@ -0,0 +1,217 @@
+"""
+ANCHOR-041: Unscreened Token-2022 Extensions
+
+`InterfaceAccount<'info, Mint>` accepts Token-2022 mints, and a Token-2022
+mint can carry extensions that change what holding its tokens means:
+
+  - permanent delegate:     a key that can transfer or burn tokens out of
+                            any account of the mint, the program's vault too
+  - transfer hook:          a program of the mint creator's choice runs on
+                            every transfer, and can make withdrawals fail
+  - confidential transfers: balances move outside the `amount` the program
+                            reads
+  - default account state:  new token accounts start frozen
+
+A program holding tokens for whatever interface mint the caller passes
+inherits all four:
+
+    #[derive(Accounts)]
+    pub struct Deposit<'info> {
+        pub mint: InterfaceAccount<'info, Mint>,       // any mint, any extension
+        #[account(mut, seeds = [b"vault", mint.key().as_ref()], bump)]
+        pub vault: InterfaceAccount<'info, TokenAccount>,
+        ..
+    }
+
+Programs that accept Token-2022 read the mint's extensions and reject the
+ones they cannot handle:
+
+    let data = mint.to_account_info().try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    for ext in state.get_extension_types()? {
+        require!(ALLOWED_EXTENSIONS.contains(&ext), PoolError::UnsupportedMint);
+    }
+
+For every interface mint of an accounts struct that also holds tokens for
+the program (a token account created with `init`, derived as a PDA, owned
+by a non-signer authority, or named as a pool, escrow, reserve or treasury
+account), the rule looks through the struct's constraints, its handlers
+and impl blocks, and functions called with the mint, for each extension by
+name. An allowlist over `get_extension_types()`, or a call with the mint to
+an unresolved `is_supported_mint`-style check, screens all four. It reports
+the mint with the extensions left unscreened: High when the permanent
+delegate is among them, Medium otherwise.
+
+Mints pinned the way ANCHOR-039 recognizes (PDA, created by the
+instruction, `address`, `mint::authority`, `has_one` or a key comparison)
+are not reported.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+from scanner.patterns.mint_screening import FN_RE, MintScreeningPattern
+
+INTERFACE_MINT_RE = re.compile(r"\bInterfaceAccount\s*<\s*'[^,]+,\s*(?:\w+::)*Mint\s*>")
+TOKEN_ACCOUNT_RE = re.compile(r"\b(?:Account|InterfaceAccount)\s*<\s*'[^,]+,\s*(?:\w+::)*TokenAccount\s*>")
+CUSTODY_NAME_RE = re.compile(r"vault|pool|escrow|reserve|treasury|pda", re.IGNORECASE)
+# Outside a derivation "vault" alone is too common for the caller's own accounts (fee_vault, user_vault)
+HELD_NAME_RE = re.compile(r"pool|escrow|reserve|treasury|pda", re.IGNORECASE)
+
+# (name in the message, spellings that show the program looked at it)
+EXTENSIONS = [
+    ("permanent delegate", r"PermanentDelegate|permanent_delegate"),
+    ("transfer hook", r"TransferHook|transfer_hook"),
+    ("confidential transfers", r"ConfidentialTransfer\w*|confidential_transfer\w*"),
+    ("default account state", r"DefaultAccountState|default_account_state"),
+]
+ALLOWLIST_RE = re.compile(r"\bget_extension_types\s*\([^;]*?(?:\bcontains\s*\(|\bmatches!|\bmatch\b)|"
+                          r"\bmatch\b[^;{]*\bget_extension_types\b", re.DOTALL)
+# Helpers by these names are taken as a mint allowlist when their body is not in the sources
+SCREEN_FN_RE = re.compile(r"supported|allowed|whitelist|allowlist|extension", re.IGNORECASE)
+
+
+class TokenExtensionsPattern(VulnerabilityPattern):
+    id = "ANCHOR-041"
+    name = "Unscreened Token-2022 Extensions"
+    severity = "High"
+    category = "tokens"
+    remediation_effort = "mechanical"
+    description = (
+        "An instruction holds tokens of any Token-2022 mint the caller passes "
+        "without screening its extensions, so a permanent delegate, transfer "
+        "hook, confidential transfers or frozen default state come with it."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        facts = Facts(content)
+        functions = self._functions(content)
+        for struct in facts.accounts_structs():
+            mints = [a for a in struct.accounts if INTERFACE_MINT_RE.search(a.type)]
+            if not mints or not self._holds_tokens(struct):
+                continue
+            text = MintScreeningPattern._checks(struct, facts, content)
+            text += "\n" + "\n".join(c.kind for a in struct.accounts for c in a.constraints)
+            handlers = [ix.name for ix in facts.instructions() if ix.accounts_struct == struct.name]
+            for mint in mints:
+                if MintScreeningPattern._pinned(mint, struct, text, content):
+                    continue
+                missing = self._unscreened(mint.name, text, functions)
+                if not missing:
+                    continue
+                severity = "High" if "permanent delegate" in missing else "Medium"
+                listed = ", ".join(missing[:-1]) + " or " + missing[-1] if len(missing) > 1 else missing[0]
+                where = f" ({', '.join(handlers)})" if handlers else ""
+                findings.append(self._finding(
+                    file_path, content, mint.line, severity,
+                    f"In struct {struct.name}{where}: interface mint '{mint.name}' is any Token-2022 mint the "
+                    f"caller passes and backs tokens the program holds, but its extensions are never screened "
+                    f"for {listed}",
+                ))
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    @staticmethod
+    def _holds_tokens(struct) -> bool:
+        """The struct has a token account the program, not the caller, controls."""
+        accounts = {a.name: a for a in struct.accounts}
+        for account in struct.accounts:
+            owner = next((c.value.split("@")[0].strip() for c in account.constraints
+                          if c.kind in ("token::authority", "associated_token::authority")), "")
+            authority = accounts.get(owner)
+            if account.has("init") or account.has("init_if_needed"):
+                if (authority is not None and not authority.is_signer) or (not owner and account.pda):
+                    return True
+            elif account.pda and (TOKEN_ACCOUNT_RE.search(account.type) or CUSTODY_NAME_RE.search(account.name)):
+                return True
+            elif authority is not None and not authority.is_signer:
+                return True
+            elif HELD_NAME_RE.search(account.name) and (
+                    TOKEN_ACCOUNT_RE.search(account.type) or "token_account" in account.name):
+                return True
+        return False
+
+    @staticmethod
+    def _functions(content: str) -> dict:
+        """Body of every function in the file, by name."""
+        bodies = {}
+        for m in FN_RE.finditer(content):
+            open_brace = content.find("{", m.end())
+            if open_brace == -1:
+                continue
+            end = VulnerabilityPattern._find_block_end(content, open_brace)
+            bodies[m.group(1)] = content[open_brace:end if end != -1 else len(content)]
+        return bodies
+
+    @staticmethod
+    def _unscreened(name: str, text: str, functions: dict) -> list:
+        """Extensions of the mint nothing in `text`, or a function called with the mint, looks at."""
+        seen = [text]
+        for call in re.finditer(rf"\b(\w+)\s*\([^;]*\b{re.escape(name)}\b", text):
+            callee = call.group(1)
+            if callee in functions:
+                seen.append(functions[callee])
+            elif SCREEN_FN_RE.search(callee):
+                return []
+        seen = "\n".join(seen)
+        if ALLOWLIST_RE.search(seen):
+            return []
+        return [label for label, spelling in EXTENSIONS if not re.search(spelling, seen)]
+
+    def _finding(self, file_path, content, line, severity, message) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=severity,
+            file=file_path,
+            line=line,
+            description=f"{message}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "Pool vault holds 250,000 tokens of an attacker-created Token-2022 mint",
+                "after": "Attacker's permanent delegate transfers the vault balance out",
+                "damage": "Deposits priced against the vault are backed by nothing; its hook can also block withdrawals.",
+            },
+            impact={
+                "attack_cost": "Creating a Token-2022 mint with extensions (a fraction of a SOL)",
+                "exploitability": "Medium — the mint has to attract deposits or liquidity first",
+                "breach_cost_context": "Pools and escrows holding Token-2022 mints lose the vault or lock it.",
+            },
+            anchor_versions_affected="All versions with token_interface (0.28+)",
+            ecosystem_recommendations=[
+                "Read the mint's extension types and accept an explicit allowlist",
+                "Reject permanent delegate and transfer hook mints unless the program is built for them",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Screen the mint's extensions against an allowlist before holding its tokens:\n"
+            "  let data = ctx.accounts.mint.to_account_info().try_borrow_data()?;\n"
+            "  let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;\n"
+            "  for ext in mint.get_extension_types()? {\n"
+            "      require!(ALLOWED_EXTENSIONS.contains(&ext), PoolError::UnsupportedMint);\n"
+            "  }"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "InterfaceAccount<'info, Mint> checks that the account is a mint of "
+            "the SPL Token or Token-2022 program, not which extensions it has. "
+            "A permanent delegate, transfer hook, confidential transfers or a "
+            "frozen default account state each break assumptions a program "
+            "holding the tokens makes about its own vault."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. Attacker creates a Token-2022 mint with itself as permanent delegate\n"
+            "2. Attacker opens a pool for the mint and seeds it with liquidity\n"
+            "3. Users swap real assets into the pool against the attacker's token\n"
+            "4. Attacker uses the permanent delegate to move the vault's tokens out and drains the other side"
+        )
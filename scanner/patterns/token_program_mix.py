@@ -0,0 +1,223 @@
+"""
+ANCHOR-043: Mixed Token Programs
+
+SPL Token and Token-2022 are two programs. `anchor_spl::token_interface`
+types accept accounts of either; `anchor_spl::token` types, and the
+`spl_token` crate, only the legacy one. A struct or CPI that mixes them
+accepts Token-2022 accounts it then cannot operate on, or the reverse:
+
+    #[derive(Accounts)]
+    pub struct Deposit<'info> {
+        pub mint: InterfaceAccount<'info, Mint>,       // Token-2022 mints accepted
+        #[account(mut)]
+        pub vault: Account<'info, TokenAccount>,       // legacy token accounts only
+        pub token_program: Program<'info, Token>,      // legacy program only
+    }
+
+    invoke(&spl_token::instruction::transfer(&spl_token::ID, ..)?, ..)?;
+
+The transfer to a Token-2022 vault fails — the pool is stuck for those
+mints — or, where the program branches on the account's owner elsewhere,
+moves tokens through a path the other half of the program never checked.
+
+The rule reports, for accounts structs that take interface accounts, a
+`Program<'info, Token2022>` or `Interface<'info, TokenInterface>`:
+
+  - a legacy `Program<'info, Token>` next to interface mints or token
+    accounts, with no Token-2022 program alongside it
+  - a legacy `Account<'info, TokenAccount>` or `Account<'info, Mint>`
+    (imported from `anchor_spl::token`, under any alias) next to a
+    Token-2022 program or the token interface, with no legacy program
+    alongside it
+  - a CPI in the struct's handlers or impl blocks, or a token-program
+    constraint, hard-coding the legacy program: `token::ID`,
+    `spl_token::ID`/`id()`, `Token::id()`, or an `spl_token::instruction`
+    builder, which rejects any other program id
+
+Owner comparisons against `spl_token::ID` in a condition are how programs
+tell the two apart and are not reported. Structs that take both programs
+explicitly (Raydium's `token_program` and `token_program_2022`) do so on
+purpose and are reported only for hard-coded CPIs.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+
+LEGACY_PROGRAM_RE = re.compile(r"\bProgram\s*<\s*'[^,]+,\s*(?:\w+::)*Token\s*>")
+TOKEN_2022_RE = re.compile(
+    r"\bProgram\s*<\s*'[^,]+,\s*(?:\w+::)*Token2022\s*>|\bInterface\s*<\s*'[^,]+,\s*(?:\w+::)*TokenInterface\s*>"
+)
+INTERFACE_RE = re.compile(r"\bInterfaceAccount\s*<\s*'[^,]+,\s*(?:\w+::)*(?:Mint|TokenAccount)\s*>")
+ACCOUNT_RE = re.compile(r"(?<!Interface)\bAccount\s*<\s*'[^,]+,\s*((?:\w+::)*)(\w+)\s*>")
+USE_TOKEN_RE = re.compile(r"\buse\s+(?:anchor_spl|spl_token)\s*::\s*(?:token\s*::\s*)?(\{[^}]*\}|\w+(?:\s+as\s+\w+)?)\s*;")
+LEGACY_ID_RE = re.compile(
+    r"\b(?:anchor_spl\s*::\s*)?(?:token|spl_token)\s*::\s*(?:ID\b|id\s*\(\s*\))|\bToken\s*::\s*id\s*\(\s*\)|"
+    r"\bspl_token\s*::\s*instruction\s*::\s*\w+"
+)
+CPI_RE = re.compile(r"\binvoke(?:_signed)?\s*\(|\bCpiContext\s*::|\bprogram_id\b|\binstruction\s*::")
+
+
+class TokenProgramMixPattern(VulnerabilityPattern):
+    id = "ANCHOR-043"
+    name = "Mixed Token Programs"
+    severity = "Medium"
+    category = "tokens"
+    remediation_effort = "localized"
+    description = (
+        "An instruction accepts Token-2022 accounts but pairs them with the "
+        "legacy token program, legacy account types or a CPI hard-coding "
+        "the legacy program id, so Token-2022 mints fail or take an "
+        "unchecked path."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        facts = Facts(content)
+        legacy = self._legacy_imports(content)
+        for struct in facts.accounts_structs():
+            interface = [a for a in struct.accounts if INTERFACE_RE.search(a.type)]
+            programs_2022 = [a for a in struct.accounts if TOKEN_2022_RE.search(a.type)]
+            if not interface and not programs_2022:
+                continue
+            legacy_programs = [a for a in struct.accounts if LEGACY_PROGRAM_RE.search(a.type)]
+            handlers = [ix.name for ix in facts.instructions() if ix.accounts_struct == struct.name]
+            where = f"In struct {struct.name}{' (' + ', '.join(handlers) + ')' if handlers else ''}"
+
+            if interface and not programs_2022:
+                for program in legacy_programs:
+                    findings.append(self._finding(
+                        file_path, content, program.line,
+                        f"{where}: '{program.name}' is the legacy Program<'info, Token> while "
+                        f"{self._names(interface)} accept Token-2022 accounts; token CPIs for a Token-2022 "
+                        f"mint fail, use Interface<'info, TokenInterface>",
+                    ))
+            if programs_2022 and not legacy_programs:
+                for account in struct.accounts:
+                    kind = self._legacy_account(account.type, legacy)
+                    if not kind:
+                        continue
+                    findings.append(self._finding(
+                        file_path, content, account.line,
+                        f"{where}: '{account.name}' is a legacy Account<'info, {kind}>, which only loads "
+                        f"SPL Token accounts, next to Token-2022 program '{programs_2022[0].name}'; Token-2022 "
+                        f"accounts fail its owner check, use InterfaceAccount",
+                    ))
+
+            for line, text in self._hard_coded(struct, facts, content):
+                findings.append(self._finding(
+                    file_path, content, line,
+                    f"{where}: `{text}` hard-codes the legacy token program, but the struct takes "
+                    f"Token-2022 accounts; pass the token program account (or token_interface CPI) instead",
+                ))
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    @staticmethod
+    def _legacy_imports(content: str) -> dict:
+        """Local names of anchor_spl::token's TokenAccount and Mint: {name: type}."""
+        names = {}
+        for m in USE_TOKEN_RE.finditer(content):
+            if not re.search(r"::\s*token\s*::|\bspl_token\b", m.group(0)):
+                continue
+            for item in m.group(1).strip("{}").split(","):
+                parts = item.split()
+                if parts and parts[0] in ("TokenAccount", "Mint"):
+                    names[parts[-1]] = parts[0]
+        return names
+
+    @staticmethod
+    def _legacy_account(type_: str, legacy: dict) -> str:
+        """TokenAccount or Mint when the type is anchor_spl::token's, under any alias, else empty."""
+        m = ACCOUNT_RE.search(type_)
+        if not m:
+            return ""
+        path, name = m.group(1), m.group(2)
+        if path:
+            legacy_path = re.search(r"(?:^|::)(?:token|spl_token)::$", path)
+            return name if legacy_path and name in ("TokenAccount", "Mint") else ""
+        return legacy.get(name, "")
+
+    @staticmethod
+    def _names(accounts: list) -> str:
+        names = [f"'{a.name}'" for a in accounts]
+        return names[0] if len(names) == 1 else ", ".join(names[:-1]) + " and " + names[-1]
+
+    @staticmethod
+    def _hard_coded(struct, facts: Facts, content: str) -> list[tuple[int, str]]:
+        """(line, text) of CPIs and program constraints naming the legacy program id."""
+        found = []
+        for account in struct.accounts:
+            for c in account.constraints:
+                if c.kind in ("address", "constraint") and LEGACY_ID_RE.search(c.value) \
+                        and re.search(r"program", account.name + " " + c.value):
+                    found.append((c.line, " ".join(c.text.split())))
+        bodies = [(ix.body_start, ix.body) for ix in facts.instructions() if ix.accounts_struct == struct.name]
+        bodies += [(s, content[s:e]) for s, e, t in VulnerabilityPattern._find_impl_blocks(content)
+                   if t == struct.name]
+        for start, body in bodies:
+            for m in re.finditer(r"[^;{}]+", body):
+                stmt = m.group(0)
+                # Owner checks telling the programs apart
+                if re.match(r"\s*(?:if|else|while|match)\b", stmt) or not CPI_RE.search(stmt):
+                    continue
+                hit = LEGACY_ID_RE.search(stmt)
+                if hit:
+                    line = VulnerabilityPattern._get_line_number(content, start + m.start() + stmt.find(hit.group(0)))
+                    found.append((line, " ".join(hit.group(0).split())))
+        return sorted(set(found))
+
+    def _finding(self, file_path, content, line, message) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=self.severity,
+            file=file_path,
+            line=line,
+            description=f"{message}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "Pool created for a Token-2022 mint; deposits land in its vault",
+                "after": "Withdrawals CPI into the legacy token program and fail",
+                "damage": "Funds in Token-2022 vaults cannot leave until the program is upgraded.",
+            },
+            impact={
+                "attack_cost": "Creating a pool for a Token-2022 mint",
+                "exploitability": "Medium — locks funds rather than moving them, unless a branch skips checks",
+                "breach_cost_context": "Half-migrated token programs are a common source of stuck pools.",
+            },
+            anchor_versions_affected="All versions with token_interface (0.28+)",
+            ecosystem_recommendations=[
+                "Use token_interface types and Interface<'info, TokenInterface> together",
+                "Take the token program as an account in CPIs instead of hard-coding its id",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Use the token interface end to end:\n"
+            "  pub mint: InterfaceAccount<'info, Mint>,\n"
+            "  pub vault: InterfaceAccount<'info, TokenAccount>,\n"
+            "  pub token_program: Interface<'info, TokenInterface>,\n"
+            "and CPI with token_interface::transfer_checked(CpiContext::new(token_program, ..), amount, decimals)"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "anchor_spl::token types and the spl_token crate check that accounts "
+            "and program ids belong to the legacy SPL Token program. Accepting "
+            "Token-2022 accounts through token_interface in the same instruction "
+            "admits mints the legacy half cannot process."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. The pool accepts InterfaceAccount<Mint>, so a Token-2022 mint is allowed\n"
+            "2. Users deposit into the pool's Token-2022 vault\n"
+            "3. The withdraw CPI is built with spl_token::ID and rejected by Token-2022\n"
+            "4. Deposits are stuck until an upgrade rewrites the CPI"
+        )
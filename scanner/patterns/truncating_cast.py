@@ -0,0 +1,247 @@
+"""
+ANCHOR-051: Truncating Value Cast
+
+Amounts computed in u128 are narrowed back with `as`:
+
+    let fee = (lamports as u128 * bp as u128 / MAX_BASIS_POINTS as u128) as u64;
+    token::transfer(cpi_ctx, watermelon_amount as u64)?;
+
+`as` never fails: a u128 past u64::MAX keeps its low 64 bits, so the
+amount transferred or recorded is an unrelated small number, and an i64
+below zero cast to u64 becomes ~1.8e19. The widening that made the
+arithmetic safe is undone silently at the last step.
+
+The rule reports (Medium) `as u64` / `as u32` / `as u16` / `as u8` casts of
+a value-bearing expression — an amount, price, fee, balance or timestamp
+by name — whose source type is wider: u128 / i128 (declared, cast inside
+the expression, u128::from, or a helper returning it), or i64 cast to an
+unsigned type or a narrower one. Left out: the clock's unix_timestamp
+(never negative), i64 operands the function compared before the cast
+(`if end_ts <= start_ts { return ... }`), and casts checked against
+`<target>::MAX` first. The fix is `<target>::try_from(..)` with the error
+surfaced, or a Result-returning signature when the function has none.
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.math_summary import FLOAT_RE, _blank
+from scanner.patterns.mul_chain import LET_TYPED_RE, TYPED_RE
+from scanner.patterns.signatures import result_note, return_types
+
+NARROW = {"u64": 64, "u32": 32, "u16": 16, "u8": 8}
+CAST_RE = re.compile(r"\bas\s+(u64|u32|u16|u8)\b")
+WIDE_TYPE_RE = re.compile(r"\bas\s+([iu]128)\b|\b([iu]128)::from\b|\d(?:_?)([iu]128)\b")
+VALUE_RE = re.compile(
+    r"amount|lamports|balance|deposit|borrow|debt|shares?$|supply|reserve|liquidity|collateral|"
+    r"tokens?$|fees?$|fee_|reward|stake|owed|principal|payout|pending|pnl|profit|price|value|cost|proceeds|"
+    r"timestamp|_ts$|^ts$|time$|deadline|expir|bp$|basis_points|bp_cents",
+    re.I,
+)
+# Sysvar fields whose type is known without a declaration
+KNOWN_TYPES = {"unix_timestamp": "i64", "epoch_start_timestamp": "i64"}
+NEVER_NEGATIVE = {"unix_timestamp", "epoch_start_timestamp"}
+
+
+class TruncatingCastPattern(VulnerabilityPattern):
+    id = "ANCHOR-051"
+    name = "Truncating Value Cast"
+    severity = "Medium"
+    category = "arithmetic"
+    remediation_effort = "mechanical"
+    description = (
+        "An amount, price or timestamp is narrowed from u128/i128/i64 with `as`, "
+        "which silently keeps the low bits (or wraps a negative value) instead of failing."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        code = _blank(content)
+        fields = self._field_types(code)
+        returns = return_types(code)
+        for fn in self._find_functions(content):
+            body = code[fn["body_start"]:fn["body_start"] + len(fn["body"])]
+            types = dict(fields)
+            types.update(dict(TYPED_RE.findall(fn["params"])))
+            types.update(dict(LET_TYPED_RE.findall(body)))
+            for m in re.finditer(r"[^;{}]+", body):
+                stmt = m.group(0)
+                if FLOAT_RE.search(stmt):
+                    continue
+                let = re.match(r"\s*let\s+(?:mut\s+)?(\w+)\s*=(?!=)\s*(.+)$", stmt, re.S)
+                for cast in CAST_RE.finditer(stmt):
+                    target = cast.group(1)
+                    start = self._operand_start(stmt, cast.start())
+                    if start is None:
+                        continue
+                    operand = stmt[start:cast.start()].strip()
+                    source = self._source_type(operand, types, returns)
+                    if not source:
+                        continue
+                    names = [n for n in re.findall(r"[A-Za-z_]\w*", operand) if n not in ("as", "self")]
+                    bound = self._target(stmt)
+                    if not any(VALUE_RE.search(n) for n in names + [bound, fn["name"]]):
+                        continue
+                    if source == "i64" and (set(names) & NEVER_NEGATIVE or self._compared(names, body[:m.start()])):
+                        continue
+                    if re.search(rf"\b{target}::MAX\b", body[:m.start()] + stmt) and \
+                            any(re.search(rf"\b{re.escape(n)}\b", body[:m.start()]) for n in names):
+                        continue
+                    findings.append(self._finding(
+                        file_path, content,
+                        self._get_line_number(content, fn["body_start"] + m.start() + start),
+                        fn["name"], operand, source, target, returns.get(fn["name"], ""),
+                        bound if let else "",
+                    ))
+                if let:
+                    source = self._source_type(let.group(2).strip(), types, returns)
+                    if source and let.group(1) not in types:
+                        types[let.group(1)] = source
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    @staticmethod
+    def _field_types(code: str) -> dict:
+        types = dict(KNOWN_TYPES)
+        for m in re.finditer(r"\bstruct\s+\w+\s*(?:<[^>]*>)?\s*\{([^{}]*)\}", code):
+            for name, ty in TYPED_RE.findall(m.group(1)):
+                if types.get(name) not in ("u128", "i128"):
+                    types[name] = ty
+        return types
+
+    @staticmethod
+    def _operand_start(stmt: str, end: int):
+        """Start of the operand `as` applies to: a parenthesized group, a call, or a path."""
+        i = end
+        while i > 0 and stmt[i - 1].isspace():
+            i -= 1
+        while True:
+            if i > 0 and stmt[i - 1] == ")":
+                depth = 0
+                for j in range(i - 1, -1, -1):
+                    depth += {")": 1, "(": -1}.get(stmt[j], 0)
+                    if depth == 0:
+                        break
+                else:
+                    return None
+                i = j
+            m = re.search(r"(?:[A-Za-z_]\w*\s*(?:::|\.)\s*)*[A-Za-z_]\w*\s*$|\d[\w]*\s*$", stmt[:i])
+            if m and m.end() == i:
+                i = m.start()
+            # A method chain continues left through `.` (and `?` / `)`)
+            k = i
+            while k > 0 and stmt[k - 1].isspace():
+                k -= 1
+            if k > 0 and stmt[k - 1] == ".":
+                k -= 1
+                while k > 0 and stmt[k - 1] in " \t\n?":
+                    k -= 1
+                i = k
+                continue
+            return i if i < end else None
+
+    def _source_type(self, operand: str, types: dict, returns: dict) -> str:
+        """u128 / i128 / i64 when the operand is of that type, else ""."""
+        inner = re.sub(r"\s+", " ", operand)
+        # A group ending in its own cast takes that cast's type: `(x as u64)`
+        tail = re.search(r"\bas\s+(\w+)\s*\)*\s*$", inner)
+        if tail:
+            return tail.group(1) if tail.group(1) in ("u128", "i128", "i64") else ""
+        wide = WIDE_TYPE_RE.search(inner)
+        if wide:
+            return next(g for g in wide.groups() if g)
+        if re.search(r"\bU(?:128|192|256)\b|\.as_u(?:64|128)\s*\(", inner):
+            return ""
+        found = ""
+        for m in re.finditer(r"(?<![\w:])([A-Za-z_]\w*)((?:\s*(?:\.|::)\s*\w+)*)\s*(\()?", inner):
+            parts = [m.group(1)] + re.findall(r"\w+", m.group(2))
+            name = parts[-1]
+            if m.group(3):
+                ty = returns.get(name, "")
+                ty = re.sub(r"^(?:Result|Option)\s*<\s*([^,>]+).*$", r"\1", ty).strip()
+            else:
+                ty = types.get(name, "")
+            if ty in ("u128", "i128"):
+                return ty
+            if ty == "i64":
+                found = "i64"
+        return found
+
+    @staticmethod
+    def _target(stmt: str) -> str:
+        m = re.match(r"\s*(?:let\s+(?:mut\s+)?(\w+)|([\w.]+)\s*[-+*/]?=(?!=))", stmt)
+        return ((m.group(1) or m.group(2)) if m else "").split(".")[-1]
+
+    @staticmethod
+    def _compared(names: list, before: str) -> bool:
+        """An i64 operand compared earlier in the function (a sign or ordering check)."""
+        return any(re.search(rf"\b{re.escape(n)}\s*(?:<=?|>=?)|(?:<=?|>=?)\s*{re.escape(n)}\b", before)
+                   for n in names)
+
+    def _finding(self, file_path, content, line, fn_name, operand, source, target, returns, bound) -> Finding:
+        text = re.sub(r"\s*\.\s*", ".", " ".join(operand.split()))
+        if source == "i64":
+            effect = ("a negative value wraps to ~1.8e19" if target == "u64" else
+                      f"values outside the {target} range keep only their low {NARROW[target]} bits")
+        else:
+            effect = f"values past {target}::MAX keep only their low {NARROW[target]} bits"
+        expr = text[1:-1] if text.startswith("(") and text.endswith(")") and \
+            self._operand_start(text, len(text)) == 0 else text
+        conversion = f"{target}::try_from({expr})"
+        fix = f"Narrow with a checked conversion:\n  {'let ' + bound + ' = ' if bound else ''}" \
+              f"{conversion}\n      .map_err(|_| ErrorCode::MathOverflow)?;"
+        fix += result_note(fn_name, returns, ", or handle the out-of-range case explicitly")
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=self.severity,
+            file=file_path,
+            line=line,
+            description=(
+                f"In {fn_name}: `{text} as {target}` narrows {'an' if source[0] == 'i' else 'a'} {source} value "
+                f"to {target}; "
+                f"{effect} instead of failing."
+            ),
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=fix,
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "amount_u128 = 2^64 + 5 (reward accrued in u128)",
+                "after": "amount_u128 as u64 = 5",
+                "damage": "The transfer or stored balance is the truncated low bits, not the computed amount.",
+            },
+            impact={
+                "attack_cost": "Inputs or accrued state large enough to leave the narrow range",
+                "exploitability": "Medium — depends on how close the values can get to the narrow type's limit",
+                "breach_cost_context": "Silent narrowing turns an overflow that checked math would reject into a wrong payout.",
+            },
+            anchor_versions_affected="All versions (program-side arithmetic)",
+            ecosystem_recommendations=[
+                "Narrow u128 intermediates with u64::try_from and return the error",
+                "Enable clippy::cast_possible_truncation and clippy::cast_sign_loss in CI",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Narrow with a checked conversion:\n"
+            "  let fee = u64::try_from(lamports as u128 * bp as u128 / MAX_BASIS_POINTS as u128)\n"
+            "      .map_err(|_| ErrorCode::MathOverflow)?;"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "`as` between integer types never fails: narrowing keeps the low "
+            "bits and a signed-to-unsigned cast reinterprets the sign bit. A "
+            "value computed in a wider type is only safe to narrow through "
+            "try_from, which reports the out-of-range case."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. claim computes the reward in u128 from a large accumulated index\n"
+            "2. The result passes u64::MAX after enough accrual or a crafted deposit\n"
+            "3. `reward as u64` keeps the low 64 bits and the handler transfers that\n"
+            "4. The recorded and transferred amounts disagree; the vault's accounting drifts"
+        )
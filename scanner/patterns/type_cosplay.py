@@ -6,16 +6,30 @@ proper type checking. Anchor's Account<T> wrapper automatically verifies the
 8-byte discriminator and program owner, but raw AccountInfo skips these checks
 entirely. An attacker can craft an account from another program whose data
 layout happens to match the expected fields.
+
+Reads of raw account bytes are followed into helpers (raw_reads.py):
+`T::unpack`, `StateWithExtensions::<T>::unpack`, `bytemuck::from_bytes`,
+borsh `try_from_slice`, and `try_borrow_data()` slicing. A field flagged
+above lists the byte ranges read from it; a field let through by a CHECK
+comment or an unrelated constraint is still reported, at the read, when
+nothing checks its owner or pins its address (has_one, key comparison).
 """
 
 import re
+from scanner.patterns.account_types import account_type, field_type
 from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+from scanner.patterns.raw_reads import raw_reads
+from scanner.patterns.validation import DISCRIMINATOR, OWNER, validated_accounts
+
 
 
 class TypeCosplayPattern(VulnerabilityPattern):
     id = "ANCHOR-004"
     name = "Account Type Cosplay — Missing Discriminator Check"
     severity = "Medium"
+    category = "accounts"
+    remediation_effort = "localized"
     description = (
         "Raw AccountInfo used to deserialize account data without verifying "
         "discriminator or program owner. An attacker can substitute a fake "
@@ -31,6 +45,8 @@ class TypeCosplayPattern(VulnerabilityPattern):
 
     def scan(self, file_path: str, content: str) -> list[Finding]:
         findings = []
+        validated = validated_accounts(content, self.max_depth)
+        reads = raw_reads(content, self.max_depth)
 
         for struct_name, struct_body, struct_start in self._find_derive_accounts_structs(content):
             # Find AccountInfo and UncheckedAccount fields using simple line-by-line scan
@@ -38,15 +54,12 @@ class TypeCosplayPattern(VulnerabilityPattern):
             for i, line in enumerate(lines):
                 actual_line = struct_start + i + 1  # +1 for opening brace line
 
-                # Check for AccountInfo<'info> or UncheckedAccount<'info>
-                ai_match = re.search(r"(\w+)\s*:\s*(AccountInfo\s*<)", line)
-                uc_match = re.search(r"(\w+)\s*:\s*(UncheckedAccount\s*<)", line)
-                match = ai_match or uc_match
-                if not match:
+                # Check for AccountInfo<'info> or UncheckedAccount<'info>, boxed or optional
+                match = field_type(line)
+                if not match or not match[1].is_raw:
                     continue
 
-                field_name = match.group(1)
-                type_name = "AccountInfo" if ai_match else "UncheckedAccount"
+                field_name, type_name = match[0], match[1].wrapper
 
                 # Skip known safe field names
                 if field_name.lower().rstrip("_") in self.SAFE_FIELD_NAMES:
@@ -90,68 +103,118 @@ class TypeCosplayPattern(VulnerabilityPattern):
                 if re.search(r"\bconstraint\s*=", context_block):
                     continue
 
+                # Skip if a handler checks the discriminator, itself or through a helper;
+                # an owner check alone still lets another account type of this program through
+                if DISCRIMINATOR in validated.get((struct_name, field_name), ()):
+                    continue
+
                 # UncheckedAccount is a deliberate Anchor choice — lower severity
                 effective_severity = "Low" if type_name == "UncheckedAccount" else self.severity
-
-                snippet = self._extract_snippet(content, actual_line)
-
+                description = (
+                    f"In struct {struct_name}: field '{field_name}' uses "
+                    f"raw {type_name} without owner or discriminator "
+                    f"verification. An attacker can substitute a fake "
+                    f"account from another program."
+                )
+                field_reads = reads.pop((struct_name, field_name), [])
+                if field_reads:
+                    description += f" Its data is read as {self._describe_reads(field_reads)}."
                 findings.append(
-                    Finding(
-                        id=self.id,
-                        name=self.name,
-                        severity=effective_severity,
-                        file=file_path,
-                        line=actual_line,
-                        description=(
-                            f"In struct {struct_name}: field '{field_name}' uses "
-                            f"raw {type_name} without owner or discriminator "
-                            f"verification. An attacker can substitute a fake "
-                            f"account from another program."
-                        ),
-                        root_cause=self.get_root_cause(),
-                        exploit_scenario=self.get_exploit_scenario(),
-                        fix_recommendation=self.get_fix_recommendation(),
-                        code_snippet=snippet,
-                        before_after_state={
-                            "before": (
-                                "Legitimate account: owner=this_program, "
-                                "data=valid_state, discriminator=correct"
-                            ),
-                            "after": (
-                                "Attacker-crafted account: owner=attacker_program, "
-                                "data=malicious_state (matching byte layout), "
-                                "discriminator=wrong (but unchecked)"
-                            ),
-                            "damage": (
-                                "Program operates on attacker-controlled data "
-                                "believing it's a legitimate account. Can lead to "
-                                "arbitrary state manipulation or fund theft."
-                            ),
-                        },
-                        impact={
-                            "attack_cost": "< 0.01 SOL (create fake account + call instruction)",
-                            "exploitability": (
-                                "High — most common vulnerability in Solana programs"
-                            ),
-                            "breach_cost_context": (
-                                "Missing owner/type checks are the #1 finding in "
-                                "professional Solana security audits. Using raw "
-                                "AccountInfo without owner verification allows "
-                                "attackers to substitute crafted accounts."
-                            ),
-                        },
-                        anchor_versions_affected="All versions (developer error, not framework bug)",
-                        ecosystem_recommendations=[
-                            f"Replace {type_name}<'info> with Account<'info, T>",
-                            "If AccountInfo is required, add explicit owner check: "
-                            "constraint = account.owner == &expected_program::ID",
-                            "Add /// CHECK: comment documenting why the raw type is safe",
-                        ],
-                    )
+                    self._finding(file_path, content, actual_line, effective_severity, description, type_name)
                 )
 
+        # Raw reads of fields the checks above let through (a CHECK comment, a constraint)
+        # still need an owner check or a pinned address: the bytes come from whichever
+        # account is passed
+        structs = {s.name: s for s in Facts(content).accounts_structs()}
+        for (struct_name, field_name), field_reads in reads.items():
+            struct = structs.get(struct_name)
+            account = next((a for a in struct.accounts if a.name == field_name), None) if struct else None
+            if account is None or account.is_signer or not account_type(account.type).is_raw:
+                continue
+            if self._owner_or_address_pinned(account, struct):
+                continue
+            if OWNER in validated.get((struct_name, field_name), ()):
+                continue
+            type_name = account.wrapper
+            line = self._get_line_number(content, field_reads[0].offset)
+            description = (
+                f"In struct {struct_name}: field '{field_name}' ({type_name}) has no owner check, "
+                f"but its data is read as {self._describe_reads(field_reads)}. A fake account owned "
+                f"by another program supplies those bytes."
+            )
+            findings.append(self._finding(file_path, content, line, self.severity, description, type_name))
+
+        findings.sort(key=lambda f: f.line)
         return findings
 
+    @staticmethod
+    def _owner_or_address_pinned(account, struct) -> bool:
+        """An owner check, or an address fixed by `address`, `seeds`, a has_one or a key comparison."""
+        if any(c.kind in ("owner", "address", "seeds") or (c.kind == "constraint" and ".owner" in c.value)
+               for c in account.constraints):
+            return True
+        key_ref = rf"\b{re.escape(account.name)}\s*\.\s*key\s*\(\s*\)"
+        return any(
+            (c.kind == "has_one" and c.value.split("@")[0].strip() == account.name)
+            or (c.kind == "constraint" and "==" in c.value and re.search(key_ref, c.value))
+            for other in struct.accounts for c in other.constraints
+        )
+
+    @staticmethod
+    def _describe_reads(field_reads: list) -> str:
+        shown = "; ".join(r.describe() for r in field_reads[:3])
+        return shown + (f" (+{len(field_reads) - 3} more)" if len(field_reads) > 3 else "")
+
+    def _finding(self, file_path, content, line, severity, description, type_name) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=severity,
+            file=file_path,
+            line=line,
+            description=description,
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": (
+                    "Legitimate account: owner=this_program, "
+                    "data=valid_state, discriminator=correct"
+                ),
+                "after": (
+                    "Attacker-crafted account: owner=attacker_program, "
+                    "data=malicious_state (matching byte layout), "
+                    "discriminator=wrong (but unchecked)"
+                ),
+                "damage": (
+                    "Program operates on attacker-controlled data "
+                    "believing it's a legitimate account. Can lead to "
+                    "arbitrary state manipulation or fund theft."
+                ),
+            },
+            impact={
+                "attack_cost": "< 0.01 SOL (create fake account + call instruction)",
+                "exploitability": (
+                    "High — most common vulnerability in Solana programs"
+                ),
+                "breach_cost_context": (
+                    "Missing owner/type checks are the #1 finding in "
+                    "professional Solana security audits. Using raw "
+                    "AccountInfo without owner verification allows "
+                    "attackers to substitute crafted accounts."
+                ),
+            },
+            anchor_versions_affected="All versions (developer error, not framework bug)",
+            ecosystem_recommendations=[
+                f"Replace {type_name}<'info> with Account<'info, T>",
+                "If AccountInfo is required, add explicit owner check: "
+                "constraint = account.owner == &expected_program::ID",
+                "Add /// CHECK: comment documenting why the raw type is safe",
+            ],
+        )
+
     def get_fix_recommendation(self) -> str:
         return (
             "Replace raw AccountInfo with typed Account<'info, T> which "
@@ -0,0 +1,229 @@
+"""
+ANCHOR-046: Unchecked Arithmetic On Balances
+
+Handlers that keep token, lamport or share totals with plain operators:
+
+    user.deposited += amount;
+    pool.total_deposits -= amount;
+    let owed = shares * price_per_share;
+
+Whether that is a bug, and which one, is decided by the workspace's
+Cargo.toml rather than the code. Cargo builds release with
+`overflow-checks = false` unless `[profile.release]` turns it on (Anchor's
+template does):
+
+  - checks off (or not set): an overflow wraps silently — a balance
+    subtracted past zero becomes ~1.8e19, a total added past u64::MAX
+    restarts at zero — and the handler carries on with the wrong value
+  - checks on: the same operation panics, so the worst case is a denial
+    of service, e.g. a pool whose total can no longer be updated
+
+The rule reports (High when the release profile wraps, Medium when it
+panics) each unguarded `+`, `-`, `*`, `+=`, `-=` in an instruction handler
+whose operand or target is a quantity (amount, lamports, balance, shares,
+deposits, supply, debt, fees, rewards, ...), and calls from a handler into
+helpers whose own arithmetic overflows (from math_summary.py), including
+helpers of dependencies pulled in with --scan-deps. Statements using
+checked_/saturating_/wrapping_ arithmetic or widened to u128 are left
+out, as is a subtraction whose operands the handler compared first
+(`require!(user.deposited >= amount)`).
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+from scanner.patterns.math_summary import (
+    FLOAT_RE, GUARDED_RE, KEYWORDS, OVERFLOW, _blank, math_summaries, resolve_call, unit_functions,
+)
+from scanner.patterns.validation import CALL_RE
+
+QUANTITY_RE = re.compile(
+    r"amount|lamports|balance|deposit|borrow|debt|shares?$|supply|reserve|liquidity|collateral|"
+    r"tokens?$|fees?$|fee_|reward|stake|total|owed|principal|minted|burned|locked|vested|payout",
+    re.I,
+)
+CHAIN = r"[A-Za-z_]\w*(?:\s*\.\s*\w+(?:\(\))?)*"
+# Left operand, operator (with `=` for compound assignment), right operand
+OP_RE = re.compile(rf"(?<![\w.])({CHAIN})\s*([-+*])(=?)(?![>=])\s*\(?\s*({CHAIN}|\d[\d_]*\w*)")
+CONSTANT_RE = re.compile(r"\d[\d_]*\w*|[A-Z][A-Z0-9_]*(?:::[A-Z][A-Z0-9_]*)*")
+# Counters, indices and times named after a quantity (reward_count, deposit_time)
+NOT_QUANTITY_RE = re.compile(
+    r"(?:^|_)(?:count|len|index|idx|time|timestamp|ts|slot|epoch|bump|decimals|bps|rate|id|cursor)$", re.I
+)
+COMPARE_RE = re.compile(r"(?:>=?|<=?)")
+
+
+class UncheckedArithmeticPattern(VulnerabilityPattern):
+    id = "ANCHOR-046"
+    name = "Unchecked Arithmetic On Balances"
+    severity = "High"
+    category = "arithmetic"
+    remediation_effort = "mechanical"
+    description = (
+        "A handler adds, subtracts or multiplies token, lamport or share "
+        "quantities with plain operators; release builds wrap silently "
+        "unless overflow-checks is on, and panic (DoS) when it is."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        code = _blank(content)
+        summaries = math_summaries(content, self.max_depth, self.dependencies)
+        keys = {key for key, _, _, _ in unit_functions(content)} | set(summaries)
+        helpers, crossings = {}, {}
+        for ix in Facts(content).instructions():
+            body = code[ix.body_start:ix.body_start + len(ix.body)]
+            for m in re.finditer(r"[^;{}]+", body):
+                stmt = m.group(0)
+                hit = self._unchecked(stmt, body[:m.start()])
+                if hit:
+                    op, qty = hit
+                    findings.append(self._finding(
+                        file_path, content, self._get_line_number(content, ix.body_start + m.start() + stmt.find(op)),
+                        f"In {ix.name}: `{self._text(stmt)}` is unchecked arithmetic on '{qty}'",
+                    ))
+            for call in CALL_RE.finditer(body):
+                callee = resolve_call(body[:call.start(1)], call.group(1), "", ix.accounts_struct, keys)
+                fact = callee != ix.name and callee in summaries and summaries[callee].facts.get(OVERFLOW)
+                if not fact:
+                    continue
+                # The first function of the chain that lives in a dependency, if any
+                crossing = next((n for n in fact.chain if n in summaries and summaries[n].facts[OVERFLOW].file), None)
+                if crossing:
+                    site = self._get_line_number(content, ix.body_start + call.start(1))
+                    crossings.setdefault(crossing, []).append((ix.name, site))
+                else:
+                    helpers.setdefault(fact.chain[-1], []).append(ix.name)
+
+        # Helper arithmetic once, at the helper, with the handlers reaching it
+        for name, handlers in helpers.items():
+            fact = summaries[name].facts[OVERFLOW]
+            hit = self._unchecked(fact.expr, "")
+            if hit:
+                handlers = list(dict.fromkeys(handlers))
+                callers = ", ".join(handlers[:3]) + (f" and {len(handlers) - 3} more" if len(handlers) > 3 else "")
+                findings.append(self._finding(
+                    file_path, content, fact.line,
+                    f"In {name} (called from {callers}): `{fact.expr}` is unchecked "
+                    f"arithmetic on '{hit[1]}'",
+                ))
+
+        # Dependency arithmetic at the first call reaching it, the dependency file named
+        for name, sites in crossings.items():
+            fact = summaries[name].facts[OVERFLOW]
+            hit = self._unchecked(fact.expr, "")
+            if hit:
+                handlers = list(dict.fromkeys(h for h, _ in sites))
+                findings.append(self._finding(
+                    file_path, content, sites[0][1],
+                    f"In {handlers[0]}: calls into {name} ({fact.location}), where `{fact.expr}` "
+                    f"is unchecked arithmetic on '{hit[1]}'",
+                ))
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    @staticmethod
+    def _text(stmt: str) -> str:
+        return re.sub(r"\s*\.\s*", ".", " ".join(stmt.split()))
+
+    @staticmethod
+    def _last(chain: str) -> str:
+        return re.split(r"\s*\.\s*", chain.replace("()", ""))[-1]
+
+    def _is_quantity(self, operand: str) -> bool:
+        name = self._last(operand)
+        return bool(QUANTITY_RE.search(name)) and not NOT_QUANTITY_RE.search(name)
+
+    def _unchecked(self, stmt: str, before: str):
+        """(operator text, quantity) of the statement's first unguarded operation on a quantity, else None."""
+        if GUARDED_RE.search(stmt) or FLOAT_RE.search(stmt) or \
+                re.match(r"\s*(?:const|static|type|use)\b", stmt):
+            return None
+        for m in OP_RE.finditer(stmt):
+            left, op, compound, right = m.group(1), m.group(2), m.group(3), m.group(4)
+            if left.split(".")[0].strip() in KEYWORDS or re.match(r"\s*[(!]", stmt[m.end(1):m.start(2)]):
+                continue
+            if all(CONSTANT_RE.fullmatch(self._last(x)) for x in (left, right)):
+                continue
+            qty = next((self._text(x) for x in (left, right) if self._is_quantity(x)), "")
+            if not qty:
+                continue
+            if op == "-" and self._compared(left, right, before):
+                continue
+            return op + compound, qty
+        return None
+
+    def _compared(self, left: str, right: str, before: str) -> bool:
+        """The handler compared the subtraction's operands before it (require!/if)."""
+        a, b = re.escape(self._text(left)), re.escape(self._text(right))
+        text = self._text(before)
+        return bool(re.search(rf"{a}\s*{COMPARE_RE.pattern}\s*{b}|{b}\s*{COMPARE_RE.pattern}\s*{a}", text))
+
+    def _impact(self) -> tuple[str, str]:
+        """(severity, consequence) under the workspace's release profile."""
+        if self.overflow_checks:
+            return "Medium", (
+                "panic/DoS: the workspace sets overflow-checks = true in [profile.release], so an "
+                "overflow aborts the instruction instead of wrapping; use checked_ arithmetic and "
+                "return an error"
+            )
+        where = "the workspace's [profile.release] does not enable overflow-checks" \
+            if self.overflow_checks is False else "no workspace Cargo.toml enables overflow-checks"
+        return "High", (
+            f"silent wrap: {where}, so release builds wrap on overflow and the handler continues "
+            f"with a corrupted value; use checked_ arithmetic"
+        )
+
+    def _finding(self, file_path, content, line, message) -> Finding:
+        severity, consequence = self._impact()
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=severity,
+            file=file_path,
+            line=line,
+            description=f"{message} — {consequence}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "user.deposited = 10, withdraw(amount = 11) with the balance check missing",
+                "after": "user.deposited = 18,446,744,073,709,551,615 (wrapped), or the instruction panics",
+                "damage": "With wrapping, the account holds a near-infinite balance; with checks on, the update is blocked.",
+            },
+            impact={
+                "attack_cost": "One instruction with a chosen amount",
+                "exploitability": "High when release wraps silently, Medium (DoS) when overflow-checks is on",
+                "breach_cost_context": "Wrapped balances are a recurring cause of drained Solana vaults.",
+            },
+            anchor_versions_affected="All versions (program-side arithmetic)",
+            ecosystem_recommendations=[
+                "Set overflow-checks = true in [profile.release] of the workspace Cargo.toml",
+                "Use checked_add/checked_sub/checked_mul with an error on None for every balance update",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Use checked arithmetic and surface overflow as an error:\n"
+            "  user.deposited = user.deposited.checked_add(amount).ok_or(ErrorCode::Overflow)?;\n"
+            "and keep overflow-checks = true in [profile.release] as a backstop."
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Rust's `+`, `-` and `*` on integers wrap in builds without "
+            "overflow-checks, which is Cargo's default for release. Whether a "
+            "balance update wraps silently or panics depends on the workspace "
+            "profile, not on the handler's code."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. withdraw subtracts amount from user.deposited with `-=`\n"
+            "2. The handler's balance check is missing or compares the wrong field\n"
+            "3. Attacker withdraws one more than deposited; the balance wraps to u64::MAX\n"
+            "4. Subsequent withdrawals drain the vault (with overflow-checks on, the call only panics)"
+        )
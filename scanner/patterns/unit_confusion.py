@@ -0,0 +1,292 @@
+"""
+ANCHOR-012: Lamport / Token Unit Confusion
+
+Detects arithmetic and comparisons that combine a lamport-denominated value
+with an SPL token amount or a share count in one expression, without an
+explicit conversion (`msol_to_sol(..)`, `shares_to_lamports(..)`, scaling by
+`10^decimals`). Liquid staking and vault programs hold both SOL and a
+derivative token; adding or comparing the two raw integers is only correct
+while the exchange rate happens to be 1:1.
+
+Units are inferred heuristically from:
+  - naming conventions (`*_lamports`, `*_sol`, `rent`, `*_shares`, `msol_*`, `*_supply`)
+  - field provenance (`.lamports()`, `.amount` of a TokenAccount, `.supply`
+    of a Mint, and `let` bindings that carry the unit forward)
+  - decimals metadata (expressions scaled by `decimals` / `LAMPORTS_PER_SOL`
+    are treated as explicit conversions; token accounts of the native mint
+    hold lamports)
+"""
+
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+
+LAMPORTS, TOKENS, SHARES = "lamports", "token amount", "shares"
+
+
+class UnitConfusionPattern(VulnerabilityPattern):
+    id = "ANCHOR-012"
+    name = "Lamport / Token Unit Confusion"
+    severity = "Medium"
+    category = "arithmetic"
+    remediation_effort = "localized"
+    cost = "expensive"
+    description = (
+        "Expression mixes lamport-denominated values with token amounts or "
+        "share counts without an explicit conversion."
+    )
+
+    _CALL = r"(?:\s*\((?:[^()]|\([^()]*\))*\))"
+    OPERAND_RE = re.compile(
+        r"\**&?(?:\w+\s*::\s*)*\w+" + _CALL + r"?(?:\s*\.\s*\w+" + _CALL + r"?)*"
+    )
+    LEFT_OPERAND_RE = re.compile(
+        r"(\**&?(?:\w+\s*::\s*)*\w+" + _CALL + r"?(?:\s*\.\s*\w+" + _CALL + r"?)*)\s*$"
+    )
+
+    # `-` excludes `->`; `>` excludes `=>` / `->`
+    OPERATOR_RE = re.compile(r"\+=|-=|>=|<=|==|!=|\+|-(?!>)|(?<![=\-])>|<")
+    METHOD_OP_RE = re.compile(r"\.\s*(?:checked|saturating|wrapping)_(?:add|sub)\s*\(")
+    LET_RE = re.compile(r"^\s*let\s+(?:mut\s+)?(\w+)\s*(?::[^=]+)?=(?!=)(.*)$", re.S)
+
+    CONVERSION_RE = re.compile(r"\b(\w+?)_(?:to|into|as)_(\w+)\s*\(")
+    SCALED_RE = re.compile(r"\bdecimals\b|\bpow\s*\(|\bLAMPORTS_PER_SOL\b|\b(?:\w+_)?(?:price|rate|ratio)(?:_\w+)?\b")
+    ARITH_METHOD_RE = re.compile(r"^(?:checked|saturating|wrapping|overflowing)_(?:add|sub|mul|div)$")
+
+    NATIVE_MINT_RE = re.compile(r"native_mint|NATIVE_MINT|wsol|WSOL")
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        code = self._strip_comments(content)
+        accounts = self._account_types(content)
+        findings = []
+        reported = set()
+
+        for fn in self._find_functions(code):
+            env = {}
+            body = fn["body"]
+            for stmt_start, stmt in self._statements(body):
+                mix = self._check_statement(stmt, env, accounts)
+                let = self.LET_RE.match(stmt)
+                if let:
+                    env[let.group(1)] = self._expr_unit(let.group(2), env, accounts)
+                if not mix:
+                    continue
+                offset, left, lu, right, ru = mix
+                line = self._get_line_number(content, fn["body_start"] + stmt_start + offset)
+                if line in reported:
+                    continue
+                reported.add(line)
+                findings.append(Finding(
+                    id=self.id,
+                    name=self.name,
+                    severity=self.severity,
+                    file=file_path,
+                    line=line,
+                    description=(
+                        f"In fn {fn['name']}: '{self._compact(left)}' ({lu}) is combined "
+                        f"with '{self._compact(right)}' ({ru}) without a conversion. "
+                        f"The result is only correct while the exchange rate is 1:1."
+                    ),
+                    root_cause=self.get_root_cause(),
+                    exploit_scenario=self.get_exploit_scenario(),
+                    fix_recommendation=self.get_fix_recommendation(),
+                    code_snippet=self._extract_snippet(content, line),
+                    before_after_state={
+                        "before": "1 mSOL = 1.2 SOL; pool holds 1,200 SOL backing 1,000 mSOL",
+                        "after": "Handler treats 1,000 mSOL as 1,000 lamports-worth of SOL",
+                        "damage": "Deposits, withdrawals or caps are off by the exchange rate.",
+                    },
+                    impact={
+                        "attack_cost": "None — any user waits for the rate to drift from 1:1",
+                        "exploitability": "Medium — grows with the exchange rate's distance from 1:1",
+                        "breach_cost_context": "Unit mix-ups between SOL and LST amounts let users mint or redeem at the wrong price.",
+                    },
+                    anchor_versions_affected="All versions (developer-side pattern)",
+                    ecosystem_recommendations=[
+                        "Name every amount with its unit (`_lamports`, `_msol`, `_shares`)",
+                        "Route every cross-unit operation through one conversion function",
+                        "Consider newtypes (Lamports, Shares) so mixing fails to compile",
+                    ],
+                ))
+        return findings
+
+    # ── statement analysis ──────────────────────────────────────────────
+
+    def _check_statement(self, stmt: str, env: dict, accounts: dict):
+        """Return (offset, left, unit, right, unit) for the first unit mix."""
+        if self.SCALED_RE.search(stmt):
+            return None
+        let = self.LET_RE.match(stmt)
+        expr_start = let.start(2) if let else 0
+        expr = stmt[expr_start:]
+
+        for m in self.METHOD_OP_RE.finditer(expr):
+            left = self.LEFT_OPERAND_RE.search(expr[:m.start()])
+            close = self._paren_end(expr, m.end() - 1)
+            if not left or close is None:
+                continue
+            right = expr[m.end():close - 1]
+            lu = self._operand_unit(left.group(1), env, accounts)
+            ru = self._expr_unit(right, env, accounts)
+            if self._is_mix(lu, ru):
+                return expr_start + m.start(), left.group(1), lu, right, ru
+
+        for m in self.OPERATOR_RE.finditer(expr):
+            if self._inside_parens(expr, m.start()):
+                continue
+            left = self.LEFT_OPERAND_RE.search(expr[:m.start()])
+            rest = expr[m.end():]
+            right = self.OPERAND_RE.match(rest.lstrip())
+            if not left or not right:
+                continue
+            before = expr[:left.start(1)].rstrip()
+            after = rest.lstrip()[right.end():].lstrip()
+            # Operands of a product/quotient are scaled, not raw amounts
+            if before.endswith(("*", "/")) or after.startswith(("*", "/")):
+                continue
+            lu = self._operand_unit(left.group(1), env, accounts)
+            ru = self._operand_unit(right.group(0), env, accounts)
+            if self._is_mix(lu, ru):
+                return expr_start + m.start(), left.group(1), lu, right.group(0), ru
+        return None
+
+    @staticmethod
+    def _is_mix(a, b) -> bool:
+        return bool(a and b and LAMPORTS in (a, b) and a != b)
+
+    def _expr_unit(self, expr: str, env: dict, accounts: dict):
+        """Unit of an expression, or None when it is scaled or mixed."""
+        expr = expr.strip()
+        if re.match(r"(?:if|match|loop|unsafe)\b", expr):
+            return None
+        conv = list(self.CONVERSION_RE.finditer(expr))
+        if conv:
+            return self._name_unit(conv[-1].group(2))
+        if self.SCALED_RE.search(expr) or re.search(r"[*/%]|checked_(?:mul|div)", expr):
+            return None
+        units = set()
+        for m in self.OPERAND_RE.finditer(expr):
+            if m.group(0) in ("as", "u64", "u128", "i64", "i128"):
+                continue
+            unit = self._operand_unit(m.group(0), env, accounts)
+            if unit:
+                units.add(unit)
+        return units.pop() if len(units) == 1 else None
+
+    def _operand_unit(self, operand: str, env: dict, accounts: dict):
+        operand = re.sub(r"\s+", "", operand).lstrip("*&")
+        conv = list(self.CONVERSION_RE.finditer(operand))
+        if conv:
+            return self._name_unit(conv[-1].group(2))
+        # Drop argument lists, then trailing add/sub/unwrap calls on the receiver
+        chain = re.sub(r"\((?:[^()]|\([^()]*\))*\)", "", operand)
+        parts = re.split(r"\.|::", chain)
+        while len(parts) > 1 and (self.ARITH_METHOD_RE.match(parts[-1]) or
+                                  parts[-1] in ("unwrap", "into", "try_into", "borrow", "clone")):
+            if parts[-1].startswith(("checked_mul", "checked_div", "saturating_mul", "wrapping_mul")):
+                return None
+            parts.pop()
+        last = parts[-1]
+        if len(parts) == 1 and env.get(last):
+            return env[last]
+        if len(parts) >= 2 and parts[-2] in accounts:
+            owner = accounts[parts[-2]]
+            if last == "amount" and owner in ("token", "native"):
+                return LAMPORTS if owner == "native" else TOKENS
+            if last == "supply" and owner == "mint":
+                return TOKENS
+        return self._name_unit(last)
+
+    @staticmethod
+    def _name_unit(name: str):
+        name = name.lower()
+        if re.search(r"(?:^|_)shares?(?:_|$)|^lp_(?:tokens?|supply|amount)$", name):
+            return SHARES
+        if re.search(r"(?:^|_)(?:tokens?|msol|stsol|jitosol|lst|supply)(?:_|$)|^msol", name):
+            return TOKENS
+        if re.search(r"(?:^|_)(?:lamports?|sol|rent)(?:_|$)|^rent_exempt|minimum_balance", name):
+            return LAMPORTS
+        return None
+
+    # ── helpers ─────────────────────────────────────────────────────────
+
+    def _account_types(self, content: str) -> dict:
+        """Map accounts-struct field name -> 'token' | 'native' | 'mint'."""
+        kinds = {}
+        for _, body, start_line in self._find_derive_accounts_structs(content):
+            for field in self._parse_struct_fields(body, start_line):
+                if re.search(r"\bTokenAccount\b", field["type"]):
+                    native = self.NATIVE_MINT_RE.search(field["attrs"])
+                    kinds[field["name"]] = "native" if native else "token"
+                elif re.search(r"\bMint\b", field["type"]):
+                    kinds[field["name"]] = "mint"
+        return kinds
+
+    @staticmethod
+    def _statements(body: str):
+        start = 0
+        for i, ch in enumerate(body):
+            if ch in ";{}":
+                if body[start:i].strip():
+                    yield start, body[start:i]
+                start = i + 1
+        if body[start:].strip():
+            yield start, body[start:]
+
+    @staticmethod
+    def _strip_comments(content: str) -> str:
+        return re.sub(r"//[^\n]*", lambda m: " " * len(m.group(0)), content)
+
+    @staticmethod
+    def _paren_end(text: str, open_pos: int):
+        depth = 0
+        for i in range(open_pos, len(text)):
+            if text[i] == "(":
+                depth += 1
+            elif text[i] == ")":
+                depth -= 1
+                if depth == 0:
+                    return i + 1
+        return None
+
+    def _inside_parens(self, text: str, pos: int) -> bool:
+        """True for operators inside a call's argument list (handled per call)."""
+        depth = 0
+        for ch in text[:pos]:
+            if ch == "(":
+                depth += 1
+            elif ch == ")":
+                depth -= 1
+        # require!(a >= b, ..) and if (a > b) are still comparisons we want
+        head = text[:pos].lstrip()
+        if depth == 1 and re.match(rf"(?:{self._guards()}!|if)\s*\(", head):
+            return False
+        return depth > 0
+
+    @staticmethod
+    def _compact(text: str) -> str:
+        text = re.sub(r"\s+", "", text)
+        return text if len(text) <= 60 else text[:57] + "..."
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Convert to a common unit before combining values:\n"
+            "  let msol_value_lamports = state.msol_to_sol(ctx.accounts.user_msol.amount)?;\n"
+            "  let total_lamports = state.available_lamports\n"
+            "      .checked_add(msol_value_lamports)\n"
+            "      .ok_or(ErrorCode::Overflow)?;"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Lamports, token base units and share counts are all u64, so the "
+            "compiler cannot tell them apart. Code written while the exchange "
+            "rate is 1:1 passes tests and stays wrong once rewards accrue."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. Pool's exchange rate drifts above 1:1 as staking rewards accrue\n"
+            "2. Handler adds the user's mSOL amount to a lamport total (or compares them)\n"
+            "3. Attacker deposits/withdraws through that handler\n"
+            "4. Attacker is credited at the raw 1:1 rate and captures the difference"
+        )
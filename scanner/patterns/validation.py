@@ -0,0 +1,188 @@
+"""
+Interprocedural owner/discriminator validation of raw accounts.
+
+Programs like Marinade keep account validation in helpers (checks.rs):
+
+    pub fn check_owner_program<'info, A: ToAccountInfo<'info>>(
+        account: &A, owner: &Pubkey, field_name: &str,
+    ) -> Result<()> {
+        let actual_owner = account.to_account_info().owner;
+        if actual_owner == owner { Ok(()) } else { Err(...) }
+    }
+
+    check_owner_program(&ctx.accounts.stake_account, &stake::program::ID, "stake")?;
+
+validated_accounts() summarizes, for every function in a source unit, which
+parameters it validates — directly, or by passing them on to a function
+that does — and then resolves the `ctx.accounts.<field>` / `self.<field>`
+arguments of handlers and accounts-struct methods against those summaries.
+Raw-AccountInfo detectors use the result to drop fields the program checks
+outside the accounts struct.
+
+Checks recognized on a value:
+  - owner:          `<v>[.to_account_info()].owner` compared with ==/!= (also
+                    through a `let` alias), or require_keys_eq!-style macros
+  - discriminator:  `T::try_deserialize(..v..)`, `try_from(..v..)` or a
+                    comparison against a `DISCRIMINATOR` constant
+
+`Account::try_from` / `AccountLoader::try_from` count as both. Resolution is
+by function or method name within the unit; helpers in other files are not
+seen.
+"""
+
+import re
+
+from scanner.budget import DEFAULT_DEPTH
+from scanner.patterns.base import VulnerabilityPattern
+from scanner.patterns.facts import Facts
+
+OWNER, DISCRIMINATOR = "owner", "discriminator"
+
+COMPARISON_RE = re.compile(r"==|!=|\brequire_keys_(?:eq|neq)!|\bassert_eq!|\bassert_ne!")
+# try_from_slice is borsh and checks nothing
+DISCRIMINATOR_RE = re.compile(r"\btry_deserialize\w*\s*\(|::\s*try_from(?:_unchecked)?\s*\(|\bDISCRIMINATOR\b")
+TYPED_LOAD_RE = re.compile(r"\b(?:Account|AccountLoader|InterfaceAccount)\s*(?:::\s*<[^>]*>)?\s*::\s*try_from\s*\(")
+DATA_TYPE_RE = re.compile(r"\b(?:TokenAccount|Mint|(?:Interface)?Account\s*<)")
+CALL_RE = re.compile(r"(?<!\w)(\w+)\s*(?:::\s*<[^>]*>\s*)?\(")
+
+
+def validated_accounts(content: str, depth: int = DEFAULT_DEPTH) -> dict:
+    """{(accounts struct, field): {"owner", "discriminator"}} for fields checked in code,
+    directly or by a helper at most `depth` calls away."""
+    functions = VulnerabilityPattern._find_functions(content)
+    summaries = _summaries(functions, depth)
+    impls = VulnerabilityPattern._find_impl_blocks(content)
+    structs = {name for name, _, _ in VulnerabilityPattern._find_derive_accounts_structs(content)}
+
+    facts = Facts(content)
+
+    result = {}
+    for fn in functions:
+        ix = facts.instruction_of(fn)
+        if ix:
+            struct, base = ix.accounts_struct, r"ctx\s*\.\s*accounts"
+        else:
+            struct = next((name for s, e, name in impls if s <= fn["body_start"] < e), "")
+            base = r"self"
+        if struct not in structs:
+            continue
+        for field in set(re.findall(base + r"\s*\.\s*(\w+)", fn["body"])):
+            subject = base + r"\s*\.\s*" + re.escape(field) + r"\b"
+            kinds = _checks_on(subject, fn["body"], summaries)
+            if kinds:
+                result.setdefault((struct, field), set()).update(kinds)
+    return result
+
+
+def _summaries(functions: list, depth: int = DEFAULT_DEPTH) -> dict:
+    """{function name: {param index: kinds}}, iterated over calls to a fixed point.
+
+    Each round sees the previous round's summaries, so after round r a
+    summary covers checks r - 1 calls below its function; `depth` rounds
+    leave a handler's argument resolved at most `depth` calls away.
+    """
+    params = {fn["name"]: _param_names(fn["params"]) for fn in functions}
+    summaries = {name: {} for name in params}
+    for _ in range(min(len(functions) + 1, depth)):
+        changed = False
+        previous = {name: dict(summary) for name, summary in summaries.items()}
+        for fn in functions:
+            for i, (name, type_) in enumerate(params[fn["name"]]):
+                # `.owner` of a deserialized token account is its authority, not the program owner
+                data_owner = bool(DATA_TYPE_RE.search(type_))
+                kinds = _checks_on(rf"\b{re.escape(name)}\b", fn["body"], previous, data_owner)
+                known = summaries[fn["name"]].get(i, set())
+                if kinds - known:
+                    summaries[fn["name"]][i] = known | kinds
+                    changed = True
+        if not changed:
+            break
+    return summaries
+
+
+def _param_names(params: str) -> list:
+    """[(name, type)] of a function's parameters, without self."""
+    names = []
+    for part in _split_top(params, generics=True):
+        m = re.match(r"\s*(?:mut\s+)?(\w+)\s*:(.*)", part, re.DOTALL)
+        if m and m.group(1) != "self":
+            names.append((m.group(1), m.group(2).strip()))
+    return names
+
+
+def _checks_on(subject: str, body: str, summaries: dict, data_owner: bool = False) -> set:
+    """Kinds of validation `body` applies to the value matched by `subject`.
+
+    With data_owner, only `.to_account_info().owner` is the program owner.
+    """
+    kinds = set()
+    aliases = [subject]
+    # Only plain references alias the account; `let t = T::unpack(&acc.data...)` is its data
+    for m in re.finditer(r"let\s+(?:mut\s+)?(\w+)\s*(?::[^=]+)?=\s*&?\s*(?:mut\s+)?([^;]+);", body):
+        if re.fullmatch(subject + r"(?:\s*\.\s*to_account_info\s*\(\s*\))?\s*", m.group(2)):
+            aliases.append(rf"\b{re.escape(m.group(1))}\b")
+    subjects = "(?:" + "|".join(aliases) + ")"
+
+    chain = r"(?:\s*\.\s*\w+\s*\(\s*\))*"
+    if data_owner:
+        chain += r"\s*\.\s*to_account_info\s*\(\s*\)"
+    owner_re = re.compile(subjects + chain + r"\s*\.\s*owner\b")
+    owner_aliases = [
+        m.group(1) for m in re.finditer(r"let\s+(?:mut\s+)?(\w+)\s*(?::[^=]+)?=\s*([^;]+);", body)
+        if owner_re.search(m.group(2))
+    ]
+    for stmt in _statements(body):
+        mentions = re.search(subjects, stmt)
+        if COMPARISON_RE.search(stmt) and (
+            owner_re.search(stmt) or any(re.search(rf"\b{a}\b", stmt) for a in owner_aliases)
+        ):
+            kinds.add(OWNER)
+        if mentions and DISCRIMINATOR_RE.search(stmt):
+            kinds.add(DISCRIMINATOR)
+            if TYPED_LOAD_RE.search(stmt):
+                kinds.add(OWNER)
+        if not mentions:
+            continue
+        for call in CALL_RE.finditer(stmt):
+            summary = summaries.get(call.group(1))
+            if not summary:
+                continue
+            args = _split_top(_balanced(stmt, call.end() - 1))
+            for i, arg in enumerate(args):
+                if i in summary and re.search(subjects, arg):
+                    kinds |= summary[i]
+    return kinds
+
+
+def _statements(body: str) -> list:
+    return [s for s in re.split(r";|\{|\}", body) if s.strip()]
+
+
+def _balanced(text: str, open_pos: int) -> str:
+    depth = 0
+    for i in range(open_pos, len(text)):
+        if text[i] in "([{":
+            depth += 1
+        elif text[i] in ")]}":
+            depth -= 1
+            if depth == 0:
+                return text[open_pos + 1:i]
+    return text[open_pos + 1:]
+
+
+def _split_top(args: str, generics: bool = False) -> list:
+    opening, closing = ("([{<", ")]}>") if generics else ("([{", ")]}")
+    parts, depth, current = [], 0, ""
+    for ch in args:
+        if ch in opening:
+            depth += 1
+        elif ch in closing:
+            depth -= 1
+        if ch == "," and depth == 0:
+            parts.append(current.strip())
+            current = ""
+        else:
+            current += ch
+    if current.strip():
+        parts.append(current.strip())
+    return parts
@@ -0,0 +1,233 @@
+"""
+ANCHOR-017: In-Handler Vault Creation Not Pinned
+
+Raydium-style pools create their token vaults inside the handler instead of
+with `#[account(init, token::mint = .., token::authority = ..)]`:
+
+    /// CHECK: Token_0 vault for the pool, initialized in contract
+    #[account(mut, seeds = [POOL_VAULT_SEED.as_bytes(), pool_state.key().as_ref(),
+              token_mint_0.key().as_ref()], bump)]
+    pub token_vault_0: UncheckedAccount<'info>,
+
+    create_token_vault_account(&payer, &pool_state, &token_vault_0, &token_mint_0,
+                               &system_program, &token_program_0, seeds)?;
+
+Anchor checks nothing about such a vault beyond what the accounts struct
+states, and later instructions commonly re-derive it from the same seeds and
+trust its contents. For every creation over an AccountInfo/UncheckedAccount
+vault (the `create_token_vault_account` helper, or an `InitializeAccount*`
+CPI) this rule requires:
+
+  - seeds:   the vault field carries `seeds` + `bump`, so its address is fixed
+  - mint:    the mint passed to the creation is one of those seeds
+  - authority: the token authority is a seed of the vault or a PDA of this
+             program — not an account the caller chooses
+  - owner program: the token program passed is typed Program/Interface or
+             pinned with `address =` / `mint::token_program`
+
+Accounts structs in the same file that accept the vault by the same seeds
+without `token::mint`/`token::authority` are listed in the finding, since
+they are the ones relying on the creation being pinned.
+"""
+
+import re
+from scanner.patterns.account_types import account_type
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.facts import Facts
+
+
+class VaultCreationPattern(VulnerabilityPattern):
+    id = "ANCHOR-017"
+    name = "In-Handler Vault Creation Not Pinned"
+    severity = "Medium"
+    category = "init"
+    remediation_effort = "mechanical"
+    description = (
+        "A token vault created inside the handler over an unchecked account "
+        "does not pin its seeds, mint, authority and token program, while "
+        "later instructions trust it by seeds alone."
+    )
+
+    HELPER_RE = re.compile(r"\bcreate_token_vault_account\s*\(")
+    INIT_CPI_RE = re.compile(r"\bInitializeAccount\d?\s*\{")
+    CPI_PROGRAM_RE = re.compile(r"CpiContext\s*::\s*new(?:_with_signer)?\s*\(\s*([^,]+),")
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        facts = Facts(content)
+
+        for ix in facts.instructions():
+            accounts = {a.name: a for a in facts.accounts_of(ix)}
+            for offset, roles in self._creations(ix.body, accounts):
+                vault = accounts.get(roles.get("vault"))
+                if vault is None or not account_type(vault.type).is_raw:
+                    continue
+                missing = self._unpinned(vault, roles, accounts)
+                if not missing:
+                    continue
+                line = self._get_line_number(content, ix.body_start + offset)
+                trusting = self._trusting_instructions(facts, vault, ix.accounts_struct)
+                severity = "High" if any(m.startswith("authority") for m in missing) else self.severity
+                findings.append(self._finding(file_path, content, ix, vault, missing, trusting, line, severity))
+
+        return findings
+
+    def _creations(self, body: str, accounts: dict) -> list:
+        """[(offset, {"vault", "mint", "authority", "token_program"})] per creation call."""
+        result = []
+        for m in self.HELPER_RE.finditer(body):
+            args = self._split_args(self._balanced(body, m.end() - 1))
+            names = [self._account_name(a, accounts) for a in args]
+            if len(names) >= 6:
+                result.append((m.start(), {
+                    "authority": names[1], "vault": names[2], "mint": names[3], "token_program": names[5],
+                }))
+        for m in self.INIT_CPI_RE.finditer(body):
+            fields = {}
+            for arg in self._split_args(self._balanced(body, m.end() - 1)):
+                key, _, value = arg.partition(":")
+                fields[key.strip()] = self._account_name(value, accounts)
+            program = None
+            for cpi in self.CPI_PROGRAM_RE.finditer(body[:m.start()]):
+                program = self._account_name(cpi.group(1), accounts)
+            result.append((m.start(), {
+                "authority": fields.get("authority"), "vault": fields.get("account"),
+                "mint": fields.get("mint"), "token_program": program,
+            }))
+        return result
+
+    def _unpinned(self, vault, roles: dict, accounts: dict) -> list:
+        seeds = " ".join(c.value for c in vault.constraints if c.kind == "seeds")
+        missing = []
+        if not seeds or not vault.has("bump"):
+            missing.append("seeds (the vault field has no seeds/bump constraint)")
+        mint = roles.get("mint")
+        if seeds and mint and not re.search(rf"\b{re.escape(mint)}\b", seeds):
+            missing.append(f"mint ({mint} is not one of the vault's seeds)")
+        authority = roles.get("authority")
+        if authority and not re.search(rf"\b{re.escape(authority)}\b", seeds):
+            owner = accounts.get(authority)
+            if owner is None or not owner.has("seeds"):
+                missing.append(f"authority ({authority} is neither a vault seed nor a PDA of this program)")
+        program = accounts.get(roles.get("token_program"))
+        if program is not None and not account_type(program.type).is_program and not program.has("address"):
+            pinned_by_mint = any(
+                c.kind == "mint::token_program" and c.value == program.name
+                for a in accounts.values() for c in a.constraints
+            )
+            if not pinned_by_mint:
+                missing.append(f"owner program ({program.name} is typed {program.wrapper} without an address check)")
+        return missing
+
+    def _trusting_instructions(self, facts, vault, creating_struct: str) -> list:
+        """Fields of other accounts structs derived from the same seeds, without token checks."""
+        seeds = "".join("".join(c.value.split()) for c in vault.constraints if c.kind == "seeds")
+        if not seeds:
+            return []
+        trusting = []
+        for struct in facts.accounts_structs():
+            if struct.name == creating_struct:
+                continue
+            for account in struct.accounts:
+                other = "".join("".join(c.value.split()) for c in account.constraints if c.kind == "seeds")
+                if other != seeds:
+                    continue
+                if not (account.has("token::mint") and account.has("token::authority")):
+                    trusting.append(f"{struct.name}.{account.name}")
+        return trusting
+
+    @staticmethod
+    def _account_name(expr: str, accounts: dict):
+        m = re.search(r"ctx\s*\.\s*accounts\s*\.\s*(\w+)", expr)
+        if m:
+            return m.group(1)
+        return next((w for w in re.findall(r"\w+", expr) if w in accounts), None)
+
+    @staticmethod
+    def _balanced(text: str, open_pos: int) -> str:
+        depth = 0
+        for i in range(open_pos, len(text)):
+            if text[i] in "([{":
+                depth += 1
+            elif text[i] in ")]}":
+                depth -= 1
+                if depth == 0:
+                    return text[open_pos + 1:i]
+        return text[open_pos + 1:]
+
+    @staticmethod
+    def _split_args(args: str) -> list:
+        parts, depth, current = [], 0, ""
+        for ch in args:
+            if ch in "([{":
+                depth += 1
+            elif ch in ")]}":
+                depth -= 1
+            if ch == "," and depth == 0:
+                parts.append(current.strip())
+                current = ""
+            else:
+                current += ch
+        if current.strip():
+            parts.append(current.strip())
+        return parts
+
+    def _finding(self, file_path, content, ix, vault, missing, trusting, line, severity) -> Finding:
+        relied_on = f" Trusted by seeds alone in {', '.join(trusting)}." if trusting else ""
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=severity,
+            file=file_path,
+            line=line,
+            description=(
+                f"Instruction '{ix.name}' creates token vault '{vault.name}' in the handler "
+                f"without pinning: {'; '.join(missing)}.{relied_on}"
+            ),
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": f"{vault.name} expected: pool PDA authority, pool mint, SPL Token owner",
+                "after": f"{vault.name} created with attacker-chosen {missing[0].split(' ')[0]}",
+                "damage": "Later instructions move pool funds through a vault the pool does not control.",
+            },
+            impact={
+                "attack_cost": "Rent for one token account",
+                "exploitability": "Medium — needs a fork that loosened the upstream vault derivation",
+                "breach_cost_context": "Vault substitution lets an attacker redirect every deposit into the pool.",
+            },
+            anchor_versions_affected="All versions (developer-side pattern)",
+            ecosystem_recommendations=[
+                "Prefer #[account(init, token::mint = .., token::authority = ..)] over in-handler creation",
+                "Derive vault seeds from the pool and the mint, and make the pool PDA the authority",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Pin every property of the vault in the accounts struct:\n"
+            "  #[account(mut, seeds = [POOL_VAULT_SEED.as_bytes(), pool_state.key().as_ref(),\n"
+            "            token_mint_0.key().as_ref()], bump)]\n"
+            "  pub token_vault_0: UncheckedAccount<'info>,\n"
+            "  #[account(mint::token_program = token_program_0)]\n"
+            "  pub token_mint_0: InterfaceAccount<'info, Mint>,\n"
+            "and pass the pool PDA as the vault authority."
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "An UncheckedAccount vault is only as constrained as its accounts-struct "
+            "attributes. Creating it in the handler skips Anchor's token::mint / "
+            "token::authority checks, so anything the seeds do not fix is chosen by "
+            "the caller."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. Attacker creates a pool passing their own mint, authority or token program\n"
+            "2. The handler creates the vault with those values\n"
+            "3. Later instructions derive the vault from the same seeds and trust it\n"
+            "4. Deposits land in a vault the attacker controls"
+        )
@@ -0,0 +1,197 @@
+"""
+ANCHOR-055: Wrapping Arithmetic On Accounting Field
+
+`wrapping_add` / `wrapping_sub` compute modulo 2^N on purpose. That is the
+idiom for accumulators read only through differences — the fee and reward
+growth of concentrated-liquidity pools, where
+
+    fee_growth_inside_latest.wrapping_sub(fee_growth_inside_last)
+
+stays correct across a wrap. On a balance or a supply it is an overflow
+check switched off by hand:
+
+    vault.total_deposits = vault.total_deposits.wrapping_add(amount);   // wraps to ~0
+
+Each wrapping operation is classified by the value it computes (receiver
+and assignment target) and what it adds:
+
+  - intentional: growth and cumulative accumulators, checkpoints, hashes,
+    nonces, sequence numbers and ring-buffer indices — not reported;
+  - suspicious (High): a balance, supply, deposit, debt, shares, reserve,
+    liquidity, fee total or amount;
+  - unclassified (Low): any other state field (`account.field`), to be
+    named like one of the above or accepted in anchor-shield.toml.
+
+Accepted idioms are configured per rule and per path, so vendored targets
+that wrap by design stay quiet without silencing the rule elsewhere. Names
+are globs; `"*"` accepts every wrapping operation under the path:
+
+    [rules."ANCHOR-055"]
+    intentional = ["observation_*"]
+
+    [rules."ANCHOR-055".paths]
+    "vendor/whirlpools/**" = ["*"]
+    "programs/amm/src/oracle.rs" = ["tick_cumulative*"]
+
+The fix is checked arithmetic with an explicit error.
+"""
+
+import fnmatch
+import re
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.math_summary import _blank
+from scanner.patterns.saturating_sub import ACCOUNTING_RE, SaturatingSubPattern
+from scanner.patterns.signatures import result_note, return_types
+from scanner.patterns.truncating_cast import TruncatingCastPattern
+
+WRAPPING_RE = re.compile(r"\.\s*wrapping_(add|sub)\s*\(")
+INTENTIONAL_RE = re.compile(
+    r"growth|cumulative|accumulat|checkpoint|seconds_per|per_liquidity|hash|seed|rng|random|nonce|"
+    r"sequence|_seq$|^seq$|counter|crc|checksum|index|idx|cursor|head$|tail$",
+    re.I,
+)
+# `a.b` not followed by a call: a field of an account or of self
+FIELD_RE = re.compile(r"\w\s*\.\s*([A-Za-z_]\w*)\b(?!\s*[(!])")
+
+
+class WrappingArithmeticPattern(VulnerabilityPattern):
+    id = "ANCHOR-055"
+    name = "Wrapping Arithmetic On Accounting Field"
+    severity = "High"
+    category = "arithmetic"
+    remediation_effort = "mechanical"
+    description = (
+        "wrapping_add / wrapping_sub on a balance or supply turns an overflow into "
+        "a silent wrap; reserved for accumulators read through differences."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        code = _blank(content)
+        returns = return_types(code)
+        accepted = self._accepted(file_path)
+        if "*" in accepted:
+            return []
+        for fn in self._find_functions(content):
+            body = code[fn["body_start"]:fn["body_start"] + len(fn["body"])]
+            for m in re.finditer(r"[^;{}]+", body):
+                stmt = m.group(0)
+                for call in WRAPPING_RE.finditer(stmt):
+                    start = TruncatingCastPattern._operand_start(stmt, call.start())
+                    if start is None:
+                        continue
+                    receiver = SaturatingSubPattern._text(stmt[start:call.start()])
+                    argument = SaturatingSubPattern._text(SaturatingSubPattern._argument(stmt, call.end() - 1))
+                    binding = SaturatingSubPattern._binding(stmt)
+                    kind = self.classify(receiver, argument, binding, accepted)
+                    if kind == "intentional":
+                        continue
+                    line = self._get_line_number(content, fn["body_start"] + m.start() + call.start())
+                    findings.append(self._finding(
+                        file_path, content, line, fn["name"], call.group(1), receiver, argument, binding,
+                        kind, returns.get(fn["name"], ""),
+                    ))
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    def _accepted(self, file_path: str) -> list:
+        """Name globs accepted as intentional for this file: global plus per-path options."""
+        accepted = [str(n) for n in self.options.get("intentional", [])]
+        path = file_path.replace("\\", "/")
+        for glob, names in (self.options.get("paths") or {}).items():
+            if fnmatch.fnmatch(path, glob) or fnmatch.fnmatch(path, f"*/{glob}"):
+                accepted.extend(str(n) for n in ([names] if isinstance(names, str) else names))
+        return accepted
+
+    @staticmethod
+    def classify(receiver: str, argument: str, binding: str, accepted: tuple = ()) -> str:
+        """intentional, suspicious or unclassified; locals that name nothing either way are left
+        as intentional."""
+        target = re.split(r"[\s.]", binding[:-3])[-1] if binding else ""
+        values = SaturatingSubPattern._names(receiver)[-1:] + ([target] if target else [])
+        if any(fnmatch.fnmatchcase(n, glob) for n in values for glob in accepted):
+            return "intentional"
+        if any(INTENTIONAL_RE.search(n) for n in values):
+            return "intentional"
+        names = values + SaturatingSubPattern._names(argument)
+        if any(ACCOUNTING_RE.search(n) for n in names):
+            return "suspicious"
+        if FIELD_RE.search(receiver) or "." in binding:
+            return "unclassified"
+        return "intentional"
+
+    def _finding(self, file_path, content, line, fn_name, op, receiver, argument, binding, kind,
+                 returns) -> Finding:
+        expr = f"{receiver}.wrapping_{op}({argument})"
+        fix = (
+            "Fail on the overflow instead of wrapping:\n"
+            f"  {binding}{receiver}\n      .checked_{op}({argument})\n      .ok_or(ErrorCode::MathOverflow)?;"
+        )
+        fix += result_note(fn_name, returns)
+        accept = (f"\nIf the value is an accumulator read only through differences, list it under "
+                  f"`intentional` (or under `paths` for this file) in [rules.\"{self.id}\"] of anchor-shield.toml.")
+        if kind == "suspicious":
+            severity = self.severity
+            description = (
+                f"In {fn_name}: `{expr}` wraps an accounting value modulo 2^N; an overflow or "
+                f"underflow records a balance near 0 or near the type's maximum instead of failing."
+            )
+        else:
+            severity = "Low"
+            description = (
+                f"In {fn_name}: `{expr}` wraps a state field whose name does not say it is an "
+                f"accumulator; confirm the wrap is intended or use checked arithmetic."
+            )
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=severity,
+            file=file_path,
+            line=line,
+            description=description,
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=fix + accept,
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "user.deposited = 10, withdraw(amount = 11)",
+                "after": "user.deposited = 18446744073709551615",
+                "damage": "The record wraps to u64::MAX; every later check against it passes.",
+            },
+            impact={
+                "attack_cost": "One call with an amount past the recorded balance",
+                "exploitability": "High — the wrapped value is read back as a valid balance",
+                "breach_cost_context": "Wrapped balances and supplies are the classic overflow drain, "
+                                       "with the runtime overflow check bypassed by the code itself.",
+            },
+            anchor_versions_affected="All versions (program-side arithmetic)",
+            ecosystem_recommendations=[
+                "Use checked_ arithmetic with a named error on balances, supplies and fee totals",
+                "Keep wrapping_ for accumulators consumed through differences, and name them so",
+            ],
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Fail on the overflow instead of wrapping:\n"
+            "  vault.total_deposits = vault.total_deposits\n"
+            "      .checked_add(amount)\n"
+            "      .ok_or(ErrorCode::MathOverflow)?;"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "wrapping_ arithmetic never fails, even with overflow-checks on. For "
+            "an accumulator consumed through differences that is correct; for "
+            "an absolute quantity the wrapped result is a wrong value the rest "
+            "of the program trusts."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. withdraw subtracts amount from user.deposited with wrapping_sub\n"
+            "2. Attacker withdraws one token more than they deposited\n"
+            "3. deposited wraps to u64::MAX\n"
+            "4. Every later withdrawal passes the balance check until the vault is empty"
+        )
@@ -0,0 +1,527 @@
+"""
+ANCHOR-047: Reachable Division By Zero
+
+Integer division by zero panics in Rust whatever the build profile, and a
+panic aborts the instruction. Handlers divide by balances, supplies and
+debts that start at zero, or reach zero after a full withdrawal:
+
+    let interest = user.borrowed * pool.interest_rate as u64 * pool.total_borrows;
+    let health = user.deposited * 100 / (user.borrowed + interest);
+
+`liquidate` panics for every user without debt — here harmless — but the
+same shape in a share price (`amount * supply / total_assets`) blocks the
+first deposit or the last withdrawal for everyone.
+
+The rule runs a small value-range analysis over each instruction handler.
+For the denominator of each `/`, `%`, `/=`, `div_ceil`/`saturating_div`
+and `mul_div*` helper it derives when the value is zero, substituting
+let-bound locals:
+
+  - `a + b` is zero when both are; `a * b` when either is
+  - `a - b` when a == b; `a / b` when a < b
+  - literals other than 0, SCREAMING_CASE constants, `x.max(1)` and
+    `10u64.pow(n)` never are
+
+and discards the cases ruled out on the way to the division: `require!`
+and `require_gt!`-style guards, `if x == 0 { return ... }` early exits, and
+the conditions of the `if`/`else` branches enclosing it (`x > 0`,
+`x != 0`, `a > b`, `x >= 1`). Whatever survives is reported (Medium) with
+the zero condition and the branch conditions on the path, which the
+finding also carries as `details` (type `zero-division`).
+`checked_div`/`checked_rem`, which return None, and float arithmetic are
+not divisions here.
+"""
+
+import re
+from typing import Optional
+
+from scanner.patterns.base import VulnerabilityPattern, Finding
+from scanner.patterns.details import ZeroDivision
+from scanner.patterns.facts import Facts
+from scanner.patterns.math_summary import FLOAT_RE, _blank
+
+CAST_RE = re.compile(r"\s+as\s+[iu](?:8|16|32|64|128|size)\b")
+WIDEN_RE = re.compile(r"\b(?:U\d+|u\d+|i\d+)\s*::\s*from\s*\(")
+DIV_OP_RE = re.compile(r"(?<![/*])(/|%)(=?)(?![/*=])")
+DIV_METHOD_RE = re.compile(r"\.\s*(?:saturating_div|wrapping_div|div_euclid|rem_euclid|div_ceil|div_floor)\s*\(")
+MUL_DIV_RE = re.compile(r"\bmul_div\w*\s*\(")
+LET_RE = re.compile(r"^\s*let\s+(?:mut\s+)?(\w+)\s*(?::\s*[^=]+)?=(?!=)\s*(.+)$", re.S)
+ASSIGN_RE = re.compile(r"^\s*(\w+)\s*=(?!=)\s*(.+)$", re.S)
+ALIAS_RE = re.compile(r"^&\s*(?:mut\s+)?([\w.]+)$")
+CONSTANT_RE = re.compile(r"^(?:[\w:]*::)?[A-Z][A-Z0-9_]*$")
+NUMBER_RE = re.compile(r"^(\d[\d_]*)(?:_?[iu](?:8|16|32|64|128|size))?$")
+NONZERO_CALL_RE = re.compile(r"\.\s*max\s*\(\s*(?:[1-9][\d_]*|[A-Z][A-Z0-9_]*)\w*\s*\)$|"
+                             r"^(?:\w+::)*max\s*\(.*,\s*(?:[1-9][\d_]*|[A-Z][A-Z0-9_]*)\w*\s*\)$|"
+                             r"^[1-9][\d_]*\w*\s*\.\s*(?:pow|checked_pow)\s*\(")
+EXIT_RE = re.compile(r"\breturn\b|\berr!\s*\(|\berror!\s*\(|\bpanic!\s*\(|\bErr\s*\(")
+NEGATION = {"==": "!=", "!=": "==", "<": ">=", "<=": ">", ">": "<=", ">=": "<"}
+RELATION_RE = re.compile(r"^(.+?)\s*(==|!=|<=|>=|<|>)\s*(.+)$", re.S)
+
+
+class ZeroDivisionPattern(VulnerabilityPattern):
+    id = "ANCHOR-047"
+    name = "Reachable Division By Zero"
+    severity = "Medium"
+    category = "arithmetic"
+    remediation_effort = "localized"
+    cost = "expensive"
+    description = (
+        "A handler divides by a value that is zero on some path — an empty "
+        "pool, a user without debt — and nothing before the division rules "
+        "it out, so the instruction panics."
+    )
+
+    def scan(self, file_path: str, content: str) -> list[Finding]:
+        findings = []
+        code = _blank(content)
+        guard_re = re.compile(rf"\b({self._guards()})!\s*\(")
+        for ix in Facts(content).instructions():
+            body = code[ix.body_start:ix.body_start + len(ix.body)]
+            for pos, stmt, den, conjunction, path in self._walk(body, guard_re):
+                line = self._get_line_number(content, ix.body_start + pos)
+                den_text = self._text(den)
+                path_text = f" on the path where {' && '.join(path)}" if path else ""
+                findings.append(self._finding(
+                    file_path, content, line,
+                    f"In {ix.name}: `{self._text(stmt)}` divides by `{den_text}`, which is zero when "
+                    f"{' && '.join(conjunction)}{path_text}; nothing before the division rules that out, "
+                    f"so the instruction panics",
+                    ZeroDivision(ix.name, line, self._text(stmt), den_text, list(conjunction), list(path)),
+                ))
+
+        findings.sort(key=lambda f: f.line)
+        return findings
+
+    # ── Walking a handler ────────────────────────────────────────────
+
+    def _walk(self, body: str, guard_re) -> list:
+        """(offset, statement, denominator, zero condition, path) of each division that can be zero."""
+        found = []
+        env, aliases = {}, {}
+        # Each scope: what holds inside it, the branch condition that opened
+        # it, the earlier branches of its if/else chain it negates, and the
+        # chain's conditions so far
+        scopes = [{"facts": _Facts(aliases), "cond": None, "negated": [], "chain": [], "start": 0}]
+        closed_chain = []
+        for m in re.finditer(r"[^;{}]*[;{}]|[^;{}]+$", body):
+            segment = m.group(0)
+            end = segment[-1] if segment[-1] in ";{}" else ""
+            stmt = segment[:-1] if end else segment
+            offset = m.start() + len(stmt) - len(stmt.lstrip())
+            facts = [s["facts"] for s in scopes]
+            path = [c for s in scopes for c in ([s["cond"]] if s["cond"] else []) + s["negated"]]
+
+            for den in self._denominators(stmt):
+                conjunction = self._reachable_zero(den, env, aliases, facts)
+                if conjunction:
+                    found.append((offset, stmt, den, conjunction, path))
+            self._learn(stmt, guard_re, env, aliases, scopes[-1]["facts"])
+
+            if end == "{":
+                header = stmt.strip()
+                branch = re.match(r"^(else\s+)?if\s+(?!let\b)(.+)$", header, re.S)
+                scope = {"facts": _Facts(aliases), "cond": None, "negated": [], "chain": [], "start": m.end()}
+                if (branch and branch.group(1)) or header == "else":
+                    for c in closed_chain:
+                        scope["facts"].assume(c, negate=True)
+                        scope["negated"].append(f"!({self._text(c)})")
+                if branch:
+                    scope["cond"] = self._text(branch.group(2))
+                    scope["facts"].assume(branch.group(2))
+                    scope["chain"] = (closed_chain if branch.group(1) else []) + [branch.group(2)]
+                scopes.append(scope)
+                closed_chain = []
+            elif end == "}" and len(scopes) > 1:
+                closed = scopes.pop()
+                closed_chain = closed["chain"]
+                # `if x == 0 { return ... }`: the rest of the enclosing block has x != 0
+                if len(closed_chain) == 1 and EXIT_RE.search(body[closed["start"]:m.end()]) \
+                        and not re.match(r"\s*else\b", body[m.end():]):
+                    scopes[-1]["facts"].assume(closed_chain[0], negate=True)
+        return found
+
+    def _learn(self, stmt: str, guard_re, env: dict, aliases: dict, facts: "_Facts") -> None:
+        """Record guards, let-bound values and reference aliases of one statement."""
+        text = stmt.strip()
+        for g in guard_re.finditer(text):
+            args = self._args(text, g.end() - 1)
+            if not args:
+                continue
+            macro = g.group(1)
+            relation = re.search(r"_(eq|neq|ne|gt|gte|ge|lt|lte|le)$", macro)
+            if relation and len(args) >= 2:
+                op = {"eq": "==", "neq": "!=", "ne": "!=", "gt": ">", "gte": ">=", "ge": ">=",
+                      "lt": "<", "lte": "<=", "le": "<="}[relation.group(1)]
+                facts.assume(f"{args[0]} {op} {args[1]}")
+            elif not relation:
+                facts.assume(args[0])
+        let = LET_RE.match(text) or ASSIGN_RE.match(text)
+        if let:
+            name, value = let.group(1), let.group(2).strip()
+            alias = ALIAS_RE.match(value)
+            if alias:
+                aliases[name] = alias.group(1)
+            else:
+                env[name] = value
+
+    @staticmethod
+    def _args(text: str, open_pos: int) -> list:
+        """Top-level comma-separated arguments of the call whose `(` is at open_pos."""
+        depth, start, args = 0, open_pos + 1, []
+        for i in range(open_pos, len(text)):
+            ch = text[i]
+            if ch in "([":
+                depth += 1
+            elif ch in ")]":
+                depth -= 1
+                if depth == 0:
+                    args.append(text[start:i].strip())
+                    return [a for a in args if a]
+            elif ch == "," and depth == 1:
+                args.append(text[start:i].strip())
+                start = i + 1
+        return []
+
+    # ── Denominators ─────────────────────────────────────────────────
+
+    def _denominators(self, stmt: str) -> list:
+        """Denominator expressions of a statement's integer divisions."""
+        if FLOAT_RE.search(stmt):
+            return []
+        dens = []
+        for m in DIV_OP_RE.finditer(stmt):
+            den = self._operand(stmt, m.end())
+            if den:
+                dens.append(den)
+        for m in DIV_METHOD_RE.finditer(stmt):
+            args = self._args(stmt, m.end() - 1)
+            if args:
+                dens.append(args[-1])
+        for m in MUL_DIV_RE.finditer(stmt):
+            args = self._args(stmt, m.end() - 1)
+            # a.mul_div_floor(b, den) or mul_div(a, b, den)
+            is_method = stmt[:m.start()].rstrip().endswith(".")
+            if len(args) == (2 if is_method else 3):
+                dens.append(args[-1])
+        return dens
+
+    @staticmethod
+    def _operand(text: str, start: int) -> str:
+        """The operand starting at `start`: a parenthesized group or a path/field/call chain."""
+        i = start
+        while i < len(text) and text[i].isspace():
+            i += 1
+        begin = i
+        if i < len(text) and text[i] == "(":
+            i = _close(text, i) + 1
+        while i < len(text):
+            dot = re.match(r"\s*\.\s*(?=[A-Za-z_])", text[i:])
+            if text[i].isalnum() or text[i] in "_:":
+                i += 1
+            elif text[i] == "(" and i > begin:
+                i = _close(text, i) + 1
+            elif dot:
+                i += dot.end()
+            else:
+                break
+        return text[begin:i].strip()
+
+    # ── Value ranges ─────────────────────────────────────────────────
+
+    def _reachable_zero(self, den: str, env: dict, aliases: dict, facts: list) -> Optional[list]:
+        """The smallest zero condition of the denominator not ruled out by the facts, or None."""
+        node = _parse(self._normalize(den))
+        if node is None:
+            return None
+        cases = self._zero(node, env, 0)
+        live = [c for c in cases if not any(f.rules_out(atom, aliases) for f in facts for atom in c)]
+        if not live:
+            return None
+        return sorted(min(live, key=len))
+
+    def _zero(self, node, env: dict, depth: int) -> list:
+        """DNF of when the node is zero: a list of conjunctions (frozensets of atoms)."""
+        kind = node[0]
+        if kind == "num":
+            return [frozenset()] if node[1] == 0 else []
+        if kind == "name":
+            name = node[1]
+            if CONSTANT_RE.match(name.split(".")[-1]) or NONZERO_CALL_RE.search(name):
+                return []
+            if name in env and depth < 4:
+                value = _parse(self._normalize(env[name]))
+                if value is not None and value[0] in ("num", "bin"):
+                    return self._zero(value, env, depth + 1)
+            return [frozenset({f"{name} == 0"})]
+        op, left, right = node[1], node[2], node[3]
+        if op == "+":
+            return _absorb([a | b for a in self._zero(left, env, depth) for b in self._zero(right, env, depth)])
+        if op == "*":
+            return _absorb(self._zero(left, env, depth) + self._zero(right, env, depth))
+        if op == "-":
+            if right[0] == "num" and right[1] == 0:
+                return self._zero(left, env, depth)
+            if left[0] == "num":
+                return [frozenset({f"{_show(right)} == {_show(left)}"})]
+            return [frozenset({f"{_show(left)} == {_show(right)}"})]
+        if op == "/":
+            return _absorb(self._zero(left, env, depth) + [frozenset({f"{_show(left)} < {_show(right)}"})])
+        return [frozenset({f"{_show(left)} % {_show(right)} == 0"})]
+
+    @staticmethod
+    def _normalize(expr: str) -> str:
+        """Casts, widening and references stripped: `U128::from(a as u64)` is `(a)`."""
+        expr = CAST_RE.sub("", expr)
+        expr = WIDEN_RE.sub("(", expr)
+        expr = re.sub(r"\s*\.\s*(?:into|clone|unwrap)\s*\(\s*\)", "", expr)
+        expr = re.sub(r"\s*\.\s*", ".", expr).replace("&", " ")
+        # A `*` not following an operand is a dereference
+        expr = re.sub(r"(^|[(,=+\-*/%]\s*)\*", r"\1", expr)
+        return " ".join(expr.split())
+
+    @staticmethod
+    def _text(text: str) -> str:
+        return re.sub(r"\s*\.\s*", ".", " ".join(text.split()))
+
+    def _finding(self, file_path, content, line, message, details) -> Finding:
+        return Finding(
+            id=self.id,
+            name=self.name,
+            severity=self.severity,
+            file=file_path,
+            line=line,
+            description=f"{message}.",
+            root_cause=self.get_root_cause(),
+            exploit_scenario=self.get_exploit_scenario(),
+            fix_recommendation=self.get_fix_recommendation(),
+            code_snippet=self._extract_snippet(content, line),
+            before_after_state={
+                "before": "Vault with total_shares = 0 after the last holder withdrew",
+                "after": "deposit computes amount * supply / total_shares and panics",
+                "damage": "The instruction is unusable until an admin seeds the value; liquidations or deposits stall.",
+            },
+            impact={
+                "attack_cost": "Reaching the zero state, often one withdrawal",
+                "exploitability": "Medium — denial of service, not theft",
+                "breach_cost_context": "Stalled liquidations turn into bad debt for lending pools.",
+            },
+            anchor_versions_affected="All versions (program-side arithmetic)",
+            ecosystem_recommendations=[
+                "Guard every denominator with require!(den > 0, ...) or handle the zero case explicitly",
+                "Use checked_div and map None to a program error",
+            ],
+            details=details,
+        )
+
+    def get_fix_recommendation(self) -> str:
+        return (
+            "Rule the zero case out before dividing, or use checked_div:\n"
+            "  let debt = user.borrowed.checked_add(interest).ok_or(ErrorCode::Overflow)?;\n"
+            "  require!(debt > 0, ErrorCode::NoDebt);\n"
+            "  let health = user.deposited.checked_mul(100).ok_or(ErrorCode::Overflow)? / debt;"
+        )
+
+    def get_root_cause(self) -> str:
+        return (
+            "Integer division and remainder panic on a zero divisor in every "
+            "build profile. Denominators built from balances, supplies and debts "
+            "are zero in an empty pool or for a user who holds nothing, and the "
+            "handler divides without checking for that state."
+        )
+
+    def get_exploit_scenario(self) -> str:
+        return (
+            "1. The pool's total_shares reaches 0 when the last holder withdraws\n"
+            "2. deposit computes shares as amount * total_shares / total_assets\n"
+            "3. Every deposit panics, so the pool can never be used again\n"
+            "4. In a lending market the same shape blocks liquidations and leaves bad debt"
+        )
+
+
+class _Facts:
+    """What the handler knows at a point: nonzero values and strict/non-strict orderings."""
+
+    def __init__(self, aliases: Optional[dict] = None):
+        self.nonzero = set()
+        self.different = set()
+        self.at_least = set()
+        self.aliases = aliases if aliases is not None else {}
+
+    def canonical(self, text: str, aliases: Optional[dict] = None) -> str:
+        """Normalized text with a leading reference alias resolved: `pool.total` is `ctx.accounts.pool.total`."""
+        text = ZeroDivisionPattern._normalize(_unparen(text))
+        aliases = aliases if aliases is not None else self.aliases
+        head = text.split(".")[0]
+        if head in aliases and "." in text:
+            text = aliases[head].lstrip("&") + text[len(head):]
+        return text
+
+    def assume(self, condition: str, negate: bool = False) -> None:
+        """Record a condition known to hold (or, with negate, known not to)."""
+        condition = _unparen(condition)
+        parts = re.split(r"\|\|", condition) if negate else re.split(r"&&", condition)
+        if negate and "&&" in condition and "||" not in condition:
+            return
+        for part in parts:
+            m = RELATION_RE.match(_unparen(part))
+            if not m:
+                continue
+            left, op, right = m.group(1), m.group(2), m.group(3)
+            if negate:
+                op = NEGATION[op]
+            self._relation(self.canonical(left), op, self.canonical(right))
+
+    def _relation(self, a: str, op: str, b: str) -> None:
+        if op == "<":
+            a, op, b = b, ">", a
+        elif op == "<=":
+            a, op, b = b, ">=", a
+        if op == ">":
+            self.nonzero.add(a)
+            self.different |= {(a, b), (b, a)}
+            self.at_least.add((a, b))
+        elif op == ">=":
+            self.at_least.add((a, b))
+            if _positive(b):
+                self.nonzero.add(a)
+        elif op == "!=":
+            if _is_zero(b):
+                self.nonzero.add(a)
+            elif _is_zero(a):
+                self.nonzero.add(b)
+            self.different |= {(a, b), (b, a)}
+        elif op == "==":
+            if _positive(b):
+                self.nonzero.add(a)
+            elif _positive(a):
+                self.nonzero.add(b)
+
+    def rules_out(self, atom: str, aliases: dict) -> bool:
+        m = re.match(r"^(.+) (==|<) (.+)$", atom)
+        if not m:
+            return False
+        a, op, b = self.canonical(m.group(1), aliases), m.group(2), self.canonical(m.group(3), aliases)
+        if op == "==" and _is_zero(b):
+            return a in self.nonzero
+        if op == "==":
+            return (a, b) in self.different
+        return (a, b) in self.at_least
+
+
+def _is_zero(text: str) -> bool:
+    m = NUMBER_RE.match(text.strip())
+    return bool(m) and int(m.group(1).replace("_", "")) == 0
+
+
+def _positive(text: str) -> bool:
+    m = NUMBER_RE.match(text.strip())
+    if m:
+        return int(m.group(1).replace("_", "")) > 0
+    return bool(CONSTANT_RE.match(text.strip()))
+
+
+def _close(text: str, open_pos: int) -> int:
+    """Offset of the `)` closing the `(` at open_pos, or the last offset when unbalanced."""
+    depth = 0
+    for i in range(open_pos, len(text)):
+        depth += {"(": 1, ")": -1}.get(text[i], 0)
+        if depth == 0:
+            return i
+    return len(text) - 1
+
+
+def _unparen(text: str) -> str:
+    """Text without whitespace and parentheses enclosing all of it."""
+    text = text.strip()
+    while text.startswith("(") and _close(text, 0) == len(text) - 1:
+        text = text[1:-1].strip()
+    return text
+
+
+TOKEN_RE = re.compile(
+    r"\s*(?:(\d[\d_]*(?:_?[iu](?:8|16|32|64|128|size))?)(?![\w.])|"
+    r"((?:\d[\d_]*\w*(?=\s*\.)|(?:[A-Za-z_]\w*::)*[A-Za-z_]\w*(?:\s*\([^()]*(?:\([^()]*\)[^()]*)*\))?)"
+    r"(?:\s*\.\s*\w+(?:\s*\([^()]*(?:\([^()]*\)[^()]*)*\))?)*)|"
+    r"([-+*/%()]))"
+)
+
+
+def _parse(expr: str):
+    """Expression tree of + - * / % over operands: ("num", n), ("name", text), ("bin", op, l, r)."""
+    tokens, pos = [], 0
+    expr = expr.strip()
+    while pos < len(expr):
+        m = TOKEN_RE.match(expr, pos)
+        if not m or m.end() == pos:
+            return None
+        if m.group(1):
+            tokens.append(("num", int(NUMBER_RE.match(m.group(1)).group(1).replace("_", ""))))
+        elif m.group(2):
+            # Paths, field chains and calls, including 10u64.pow(n)
+            tokens.append(("name", re.sub(r"\s+", "", m.group(2))))
+        else:
+            tokens.append(("op", m.group(3)))
+        pos = m.end()
+        while pos < len(expr) and expr[pos].isspace():
+            pos += 1
+    node, rest = _sum(tokens)
+    return node if node is not None and not rest else None
+
+
+def _sum(tokens):
+    node, tokens = _product(tokens)
+    while node is not None and tokens and tokens[0] in (("op", "+"), ("op", "-")):
+        op = tokens[0][1]
+        right, tokens = _product(tokens[1:])
+        if right is None:
+            return None, tokens
+        node = ("bin", op, node, right)
+    return node, tokens
+
+
+def _product(tokens):
+    node, tokens = _factor(tokens)
+    while node is not None and tokens and tokens[0] in (("op", "*"), ("op", "/"), ("op", "%")):
+        op = tokens[0][1]
+        right, tokens = _factor(tokens[1:])
+        if right is None:
+            return None, tokens
+        node = ("bin", op, node, right)
+    return node, tokens
+
+
+def _factor(tokens):
+    if not tokens:
+        return None, tokens
+    head = tokens[0]
+    if head == ("op", "("):
+        node, rest = _sum(tokens[1:])
+        if node is None or not rest or rest[0] != ("op", ")"):
+            return None, rest
+        return node, rest[1:]
+    if head[0] in ("num", "name"):
+        return head, tokens[1:]
+    return None, tokens
+
+
+def _show(node) -> str:
+    if node[0] == "num":
+        return str(node[1])
+    if node[0] == "name":
+        return node[1]
+    left, right = _show(node[2]), _show(node[3])
+    if node[2][0] == "bin" and node[1] in "*/%" and node[2][1] in "+-":
+        left = f"({left})"
+    if node[3][0] == "bin":
+        right = f"({right})"
+    return f"{left} {node[1]} {right}"
+
+
+def _absorb(cases: list) -> list:
+    """Drop conjunctions implied by a smaller one: (a) or (a and b) is (a)."""
+    unique = sorted(set(cases), key=lambda c: (len(c), sorted(c)))
+    kept = []
+    for case in unique:
+        if not any(k <= case for k in kept):
+            kept.append(case)
+    return kept
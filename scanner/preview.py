@@ -0,0 +1,109 @@
+"""
+Dry-run of one rule against a target's current results.
+
+Turning on a stricter rule, or one that was skipped so far, changes what
+every later scan reports. Running it blind either floods the team's results
+with findings nobody asked for or hides them in a one-off run nobody can
+compare. preview_rule() scans the target twice with the same configuration
+(skipped rules, baseline, guard macros, precedence, depth):
+
+  - current:    the rules as configured
+  - candidate:  the same rules plus the previewed one
+
+and reports the difference by finding fingerprint:
+
+  - added:      findings the candidate reports and the current scan does
+                not; these are what enabling the rule would surface
+  - merged:     findings of the rule folded into a finding already reported
+                at the same line (scanner/dedup.py), which only gain a
+                `supporting` entry
+  - superseded: current findings the rule's findings would absorb
+  - baselined:  findings of the rule hidden by the baseline or inline
+                suppressions
+
+Nothing is written: the baseline, triage store and results are untouched.
+A rule that is already enabled previews as no change.
+"""
+
+import copy
+import json
+import os
+from collections import Counter
+from dataclasses import dataclass, field
+
+from scanner.engine import AnchorShieldEngine, select_patterns
+from scanner.patterns import ALL_PATTERNS
+
+
+@dataclass
+class RulePreview:
+    rule: str
+    name: str
+    target: str
+    # Whether the current configuration already runs the rule
+    enabled: bool = False
+    current: int = 0
+    added: list = field(default_factory=list)
+    merged: list = field(default_factory=list)
+    superseded: list = field(default_factory=list)
+    baselined: int = 0
+
+    def to_dict(self) -> dict:
+        return {
+            "rule": self.rule,
+            "name": self.name,
+            "target": self.target,
+            "enabled": self.enabled,
+            "current": self.current,
+            "added": [f.to_dict() for f in self.added],
+            "merged": [f.to_dict() for f in self.merged],
+            "superseded": [f.to_dict() for f in self.superseded],
+            "baselined": self.baselined,
+        }
+
+    def to_json(self, indent: int = 2) -> str:
+        return json.dumps(self.to_dict(), indent=indent)
+
+
+def resolve_rule(selector: str):
+    """The one pattern a rule selector names; ValueError for none or several."""
+    matched = select_patterns([PatternClass() for PatternClass in ALL_PATTERNS], only=[selector])
+    if len(matched) > 1:
+        raise ValueError(f"'{selector}' matches {len(matched)} rules; preview one rule at a time")
+    return matched[0]
+
+
+def preview_rule(selector: str, target: str, engine: AnchorShieldEngine = None) -> RulePreview:
+    """Scan target with engine's configuration, with and without the rule, and diff the findings."""
+    rule = resolve_rule(selector)
+    engine = engine or AnchorShieldEngine()
+    candidate = copy.deepcopy(engine)
+    enabled = any(p.id == rule.id for p in engine.patterns)
+    if not enabled:
+        order = {cls.__name__: i for i, cls in enumerate(ALL_PATTERNS)}
+        candidate.patterns = sorted(candidate.patterns + [rule], key=lambda p: order[type(p).__name__])
+
+    path = os.path.abspath(target)
+    current = engine.scan_directory(path)
+    after = candidate.scan_directory(path)
+
+    preview = RulePreview(rule.id, rule.name, path, enabled, current=len(current.findings))
+    before = Counter(f.fingerprint() for f in current.findings)
+    remaining = Counter(before)
+    for finding in after.findings:
+        fp = finding.fingerprint()
+        if remaining[fp] > 0:
+            remaining[fp] -= 1
+            if not enabled and any(s["id"] == rule.id for s in finding.supporting):
+                preview.merged.append(finding)
+        else:
+            preview.added.append(finding)
+    kept = Counter(f.fingerprint() for f in after.findings)
+    for finding in current.findings:
+        fp = finding.fingerprint()
+        if kept[fp] > 0:
+            kept[fp] -= 1
+        else:
+            preview.superseded.append(finding)
+    preview.baselined = after.summary.get("suppressed", 0) - current.summary.get("suppressed", 0)
+    return preview
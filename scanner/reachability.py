@@ -0,0 +1,497 @@
+"""
+Reachability from program entrypoints to dangerous sinks.
+
+Builds a name-resolved call graph over every function in a target, then for
+each dangerous sink — a PDA-signed CPI, a write to an authority-like field,
+an account close or a direct lamport debit — lists the `#[program]`
+entrypoints that reach it and the validation predicates on the way:
+Accounts-struct constraints (signers, has_one, constraint, seeds, address),
+`#[access_control]`, and `require!`/`assert!`/`if ... return Err` checks
+that precede the call in each function along the path.
+
+The result answers "who can make this vault pay out, and what stops them?".
+Besides Context<T> functions in `#[program]`, the roots of non-Anchor
+dispatch are entrypoints too: the program's `fallback`, the function named
+by `entrypoint!(..)` or marked `#[entrypoint]`, and a native
+`process_instruction(program_id, accounts, data)`; handlers they route to
+by hand are reached through the call graph (see entrypoints.py).
+
+Resolution is by name and is intentionally approximate: ambiguous calls are
+narrowed by receiver type or module path, and left unresolved otherwise.
+CPIs (including declare_program! and #[interface] clients, see cpi.py) are
+kept as edges to external programs; a CPI whose target program cannot be
+resolved is itself reported as an "arbitrary-cpi" sink.
+"""
+
+import json
+import os
+import re
+from collections import deque
+from dataclasses import dataclass, field
+from typing import Optional
+
+from scanner.budget import DEFAULT_DEPTH, DEGRADED_DEPTH, ScanBudget
+from scanner.cpi import declared_programs, find_cpi_calls, interface_modules
+from scanner.engine import AnchorShieldEngine
+from scanner.patterns.base import VulnerabilityPattern
+from scanner.patterns.facts import Facts
+from scanner.patterns.guards import BUILTIN_GUARDS, guard_alternation, local_guard_macros
+
+SINK_PATTERNS = [
+    ("pda-signed-cpi", re.compile(r"\binvoke_signed\s*\(|\bnew_with_signer\s*\(|\.\s*with_signer\s*\(")),
+    ("authority-write", re.compile(
+        r"\.\s*(\w*(?:authority|admin|owner|operator|manager)\w*)\s*=(?!=)"
+    )),
+    ("close", re.compile(r"\.\s*close\s*\(\s*[\w.]*\s*(?:\.to_account_info\(\))?\s*\)")),
+    ("lamport-debit", re.compile(
+        r"lamports\.borrow_mut\(\)\s*(?:-=|=\s*0\b)|try_borrow_mut_lamports\(\)\?\s*-="
+    )),
+]
+
+ENTRYPOINT_MACRO_RE = re.compile(r"\bentrypoint!\s*\(\s*(\w+)\s*\)")
+# (program_id: &Pubkey, accounts: &[AccountInfo], instruction_data: &[u8])
+NATIVE_PARAMS_RE = re.compile(r"&\s*Pubkey\b.*&\s*(?:'\w+\s+)?\[\s*AccountInfo\b.*&\s*\[\s*u8\s*\]", re.DOTALL)
+
+NOT_CALLS = {
+    "if", "while", "for", "match", "loop", "return", "Some", "Ok", "Err", "Box",
+    "fn", "let", "in", "as", "move", "drop", "from", "into", "clone", "unwrap",
+    "key", "to_account_info", "as_ref", "len", "iter", "map", "ok_or", "borrow",
+    "borrow_mut", "checked_add", "checked_sub", "checked_mul", "checked_div",
+}
+
+
+@dataclass
+class FunctionNode:
+    """One function in the target, with the context needed for resolution."""
+
+    key: str
+    name: str
+    file: str
+    line: int
+    body: str
+    body_start: int
+    content: str
+    impl_type: str = ""
+    context_struct: str = ""
+    is_entrypoint: bool = False
+    # "anchor", "fallback" or "native" for entrypoints
+    entry_kind: str = ""
+    attrs: str = ""
+    cpis: list = field(default_factory=list)
+
+
+@dataclass
+class Sink:
+    kind: str
+    file: str
+    line: int
+    function: str
+    text: str
+
+    def to_dict(self) -> dict:
+        return {
+            "kind": self.kind, "file": self.file, "line": self.line,
+            "function": self.function, "text": self.text,
+        }
+
+
+@dataclass
+class EntrypointPath:
+    entrypoint: str
+    file: str
+    line: int
+    path: list
+    predicates: list
+
+    def to_dict(self) -> dict:
+        return {
+            "entrypoint": self.entrypoint, "file": self.file, "line": self.line,
+            "path": self.path, "predicates": self.predicates,
+        }
+
+
+@dataclass
+class SinkReach:
+    sink: Sink
+    entrypoints: list = field(default_factory=list)
+
+    def to_dict(self) -> dict:
+        return {
+            "sink": self.sink.to_dict(),
+            "entrypoints": [e.to_dict() for e in self.entrypoints],
+        }
+
+
+@dataclass
+class ReachabilityReport:
+    target: str
+    entrypoints: int = 0
+    functions: int = 0
+    sinks: list = field(default_factory=list)
+    # Outgoing CPI edges: {"function", "file", "line", **CpiCall.to_dict()}
+    cpi_edges: list = field(default_factory=list)
+    # Entrypoint files traversed at reduced depth after the budget ran out
+    degraded: list = field(default_factory=list)
+
+    def to_dict(self) -> dict:
+        return {
+            "target": self.target,
+            "entrypoints": self.entrypoints,
+            "functions": self.functions,
+            "sinks": [s.to_dict() for s in self.sinks],
+            "cpi_edges": self.cpi_edges,
+            "degraded": self.degraded,
+        }
+
+    def to_json(self, indent: int = 2) -> str:
+        return json.dumps(self.to_dict(), indent=indent)
+
+
+def _collapse(text: str, limit: int = 90) -> str:
+    text = " ".join(text.split())
+    return text if len(text) <= limit else text[:limit - 1] + "…"
+
+
+def _balanced(text: str, open_pos: int) -> str:
+    """Contents of the parenthesis group opening at open_pos."""
+    depth = 0
+    for i in range(open_pos, len(text)):
+        if text[i] == "(":
+            depth += 1
+        elif text[i] == ")":
+            depth -= 1
+            if depth == 0:
+                return text[open_pos + 1:i]
+    return text[open_pos + 1:]
+
+
+def body_predicates(body: str, upto: Optional[int] = None, guards: str = BUILTIN_GUARDS) -> list[str]:
+    """Validation checks in a function body that precede offset `upto`.
+
+    `guards` is the alternation of assertion macro names (patterns/guards.py).
+    """
+    text = body if upto is None else body[:upto]
+    preds = []
+    for m in re.finditer(rf"\b({guards})!\s*\(", text):
+        preds.append(f"{m.group(1)}!({_collapse(_balanced(text, m.end() - 1), 70)})")
+    for m in re.finditer(r"\bif\s+([^{]+?)\s*\{", text):
+        block_end = VulnerabilityPattern._find_block_end(body, m.end() - 1)
+        block = body[m.end():block_end] if block_end != -1 else ""
+        if re.search(r"\breturn\s+Err\b|\berr!\s*\(|\berror!\s*\(|\bpanic!\s*\(", block):
+            preds.append(f"if {_collapse(m.group(1), 70)} → Err")
+    return preds
+
+
+def struct_predicates(fields: list[dict]) -> list[str]:
+    """Account-level guards declared on an Accounts struct."""
+    preds = []
+    for f in fields:
+        if re.match(r"Signer\s*<", f["type"]):
+            preds.append(f"signer: {f['name']}")
+        for clause in VulnerabilityPattern._account_clauses(f["attrs"]):
+            clause = clause.strip()
+            m = re.match(r"(has_one|constraint|address|owner)\s*=\s*(.+)", clause, re.DOTALL)
+            if m:
+                preds.append(f"{m.group(1)}: {f['name']} ← {_collapse(m.group(2), 60)}")
+            elif clause.startswith("seeds"):
+                preds.append(f"seeds: {f['name']}")
+    return preds
+
+
+class ReachabilityAnalyzer:
+    """Call-graph reachability from #[program] entrypoints to sinks."""
+
+    def __init__(self, sources: list[tuple[str, str]], guard_macros=()):
+        self.functions: list[FunctionNode] = []
+        self.by_name: dict[str, list[FunctionNode]] = {}
+        self.structs: dict[str, list[dict]] = {}
+        self.struct_lines: dict[str, tuple[str, int]] = {}
+        # declare_program!/#[interface] usually live in lib.rs, calls elsewhere
+        self.declared = set()
+        self.interfaces = {}
+        # Functions named by entrypoint!(..) in any file
+        self.native_roots = set()
+        guards = set(guard_macros)
+        for _, content in sources:
+            self.native_roots |= set(ENTRYPOINT_MACRO_RE.findall(content))
+            self.declared |= declared_programs(content)
+            self.interfaces.update(interface_modules(content))
+            guards |= local_guard_macros(content)
+        self.guards = guard_alternation(guards)
+        for path, content in sources:
+            self._index(path, content)
+
+    def _index(self, path: str, content: str):
+        program_ranges = []
+        for m in re.finditer(r"#\[program\]\s*(?:pub\s+)?mod\s+\w+\s*\{", content):
+            end = VulnerabilityPattern._find_block_end(content, m.end() - 1)
+            if end != -1:
+                program_ranges.append((m.end(), end))
+        impls = VulnerabilityPattern._find_impl_blocks(content)
+        facts = Facts(content)
+
+        for fn in VulnerabilityPattern._find_functions(content):
+            ctx = facts.instruction_of(fn)
+            impl_type = ""
+            for start, end, name in impls:
+                if start <= fn["body_start"] < end:
+                    impl_type = name
+            fn_start = content.rfind("\n", 0, content.rfind("fn " + fn["name"], 0, fn["body_start"]))
+            attrs_start = fn_start
+            # Collect attribute/doc lines directly above the fn
+            while True:
+                prev = content.rfind("\n", 0, attrs_start)
+                line = content[prev + 1:attrs_start].strip()
+                if prev == -1 or not (line.startswith("#[") or line.startswith("///")):
+                    break
+                attrs_start = prev
+            in_program = any(s <= fn["body_start"] < e for s, e in program_ranges)
+            attrs = content[attrs_start:fn_start]
+            kind = self._entry_kind(fn, bool(ctx), in_program, attrs)
+            node = FunctionNode(
+                key=f"{path}:{fn['line']}:{fn['name']}",
+                name=fn["name"],
+                file=path,
+                line=fn["line"],
+                body=fn["body"],
+                body_start=fn["body_start"],
+                content=content,
+                impl_type=impl_type,
+                context_struct=ctx.accounts_struct if ctx else "",
+                is_entrypoint=bool(kind),
+                entry_kind=kind,
+                attrs=attrs,
+                cpis=find_cpi_calls(fn["body"], self.declared, self.interfaces),
+            )
+            self.functions.append(node)
+            self.by_name.setdefault(node.name, []).append(node)
+
+        for name, body, start in VulnerabilityPattern._find_derive_accounts_structs(content):
+            self.structs[name] = VulnerabilityPattern._parse_struct_fields(body, start)
+            self.struct_lines[name] = (path, start)
+
+    def _entry_kind(self, fn: dict, has_context: bool, in_program: bool, attrs: str) -> str:
+        if has_context:
+            return "anchor" if in_program else ""
+        if fn["name"] == "fallback" and in_program:
+            return "fallback"
+        if fn["name"] in self.native_roots or re.search(r"#\[\s*entrypoint\s*\]", attrs):
+            return "native"
+        if fn["name"] == "process_instruction" and NATIVE_PARAMS_RE.search(fn["params"]):
+            return "native"
+        return ""
+
+    # ─── Call resolution ────────────────────────────────────────────
+
+    def calls(self, fn: FunctionNode) -> list[tuple[int, FunctionNode]]:
+        """Resolved (offset in body, callee) pairs for a function."""
+        resolved = []
+        for m in re.finditer(r"((?:\w+\s*::\s*)*)(\.\s*)?\b(\w+)\s*(?:::\s*<[^>]*>)?\s*\(", fn.body):
+            qualifier, is_method, name = m.group(1), bool(m.group(2)), m.group(3)
+            if name in NOT_CALLS or name not in self.by_name:
+                continue
+            # Generated CPI clients share names with local fns but leave the program
+            if any(c.start <= m.start() < c.end for c in fn.cpis):
+                continue
+            candidates = [c for c in self.by_name[name] if c is not fn]
+            if not candidates:
+                continue
+            if len(candidates) > 1:
+                candidates = self._narrow(fn, candidates, qualifier, is_method, m.start())
+            for callee in candidates:
+                resolved.append((m.start(), callee))
+        return resolved
+
+    def _narrow(self, caller, candidates, qualifier, is_method, pos):
+        if qualifier:
+            segments = [s.strip() for s in qualifier.split("::") if s.strip()]
+            for seg in reversed(segments):
+                by_type = [c for c in candidates if c.impl_type == seg]
+                if by_type:
+                    return by_type
+                by_path = [c for c in candidates if re.search(rf"(?:^|/){re.escape(seg)}(?:/|\.rs$)", c.file)]
+                if by_path:
+                    return by_path
+            return []
+        if is_method:
+            receiver = caller.body[max(0, pos - 40):pos]
+            if re.search(r"ctx\s*\.\s*accounts\s*$", receiver) and caller.context_struct:
+                return [c for c in candidates if c.impl_type == caller.context_struct]
+            if re.search(r"\bself\s*$", receiver) and caller.impl_type:
+                return [c for c in candidates if c.impl_type == caller.impl_type]
+            return []
+        same_file = [c for c in candidates if c.file == caller.file and not c.impl_type]
+        return same_file if len(same_file) == 1 else []
+
+    # ─── Sinks ──────────────────────────────────────────────────────
+
+    def sinks(self) -> list[tuple[Sink, Optional[FunctionNode], Optional[str]]]:
+        """All sinks as (sink, containing function or None, accounts struct or None)."""
+        found = []
+        for fn in self.functions:
+            for kind, regex in SINK_PATTERNS:
+                for m in regex.finditer(fn.body):
+                    line = fn.content.count("\n", 0, fn.body_start + m.start()) + 1
+                    text = fn.content.split("\n")[line - 1].strip()
+                    found.append((
+                        Sink(kind=kind, file=fn.file, line=line, function=fn.name, text=_collapse(text)),
+                        fn, m.start(),
+                    ))
+            for cpi in fn.cpis:
+                if cpi.resolved:
+                    continue
+                line = fn.content.count("\n", 0, fn.body_start + cpi.start) + 1
+                found.append((
+                    Sink(kind="arbitrary-cpi", file=fn.file, line=line, function=fn.name,
+                         text=_collapse(f"{cpi.program} via {cpi.via}: {cpi.text}")),
+                    fn, cpi.start,
+                ))
+        for name, fields in self.structs.items():
+            path, _ = self.struct_lines[name]
+            for f in fields:
+                for clause in VulnerabilityPattern._account_clauses(f["attrs"]):
+                    m = re.match(r"\s*close\s*=\s*(\w+)", clause)
+                    if m:
+                        found.append((
+                            Sink(kind="close", file=path, line=f["line"], function=name,
+                                 text=f"#[account(close = {m.group(1)})] {f['name']}"),
+                            None, name,
+                        ))
+        return found
+
+    # ─── Traversal ──────────────────────────────────────────────────
+
+    def analyze(self, target: str = "", budget: Optional[ScanBudget] = None,
+                max_depth: int = DEFAULT_DEPTH) -> ReachabilityReport:
+        """Traverse from every entrypoint, max_depth calls deep; past the budget, DEGRADED_DEPTH."""
+        budget = budget or ScanBudget()
+        budget.start()
+        mark = len(budget.skipped)
+        entrypoints = [f for f in self.functions if f.is_entrypoint]
+        report = ReachabilityReport(
+            target=target, entrypoints=len(entrypoints), functions=len(self.functions),
+        )
+
+        # BFS once per entrypoint: function key -> (path, predicates)
+        reach = {}
+        degraded_files = set()
+        for ep in entrypoints:
+            depth_limit = max_depth
+            if budget.exhausted() and max_depth > DEGRADED_DEPTH:
+                depth_limit = DEGRADED_DEPTH
+                if ep.file not in degraded_files:
+                    degraded_files.add(ep.file)
+                    budget.record(ep.file, [f"interprocedural depth > {DEGRADED_DEPTH}"])
+            base = self._entry_predicates(ep)
+            seen = {ep.key: ([ep.name], base)}
+            queue = deque([(ep, 0)])
+            while queue:
+                fn, depth = queue.popleft()
+                if depth >= depth_limit:
+                    continue
+                path, preds = seen[fn.key]
+                for offset, callee in self.calls(fn):
+                    if callee.key in seen:
+                        continue
+                    seen[callee.key] = (
+                        path + [callee.name],
+                        preds + body_predicates(fn.body, offset, self.guards),
+                    )
+                    queue.append((callee, depth + 1))
+            reach[ep.key] = (ep, seen)
+
+        for sink, fn, where in self.sinks():
+            entry = SinkReach(sink=sink)
+            for ep, seen in reach.values():
+                if fn is not None and fn.key in seen:
+                    path, preds = seen[fn.key]
+                    entry.entrypoints.append(EntrypointPath(
+                        entrypoint=ep.name, file=ep.file, line=ep.line, path=path,
+                        predicates=_dedupe(preds + body_predicates(fn.body, where, self.guards)),
+                    ))
+                elif fn is None and ep.context_struct == where:
+                    entry.entrypoints.append(EntrypointPath(
+                        entrypoint=ep.name, file=ep.file, line=ep.line, path=[ep.name],
+                        predicates=self._entry_predicates(ep),
+                    ))
+            report.sinks.append(entry)
+        report.sinks.sort(key=lambda s: (s.sink.file, s.sink.line))
+
+        for fn in self.functions:
+            for cpi in fn.cpis:
+                report.cpi_edges.append({
+                    "function": fn.name,
+                    "file": fn.file,
+                    "line": fn.content.count("\n", 0, fn.body_start + cpi.start) + 1,
+                    **cpi.to_dict(),
+                })
+        report.degraded = budget.skipped[mark:]
+        return report
+
+    def _entry_predicates(self, ep: FunctionNode) -> list[str]:
+        preds = []
+        for m in re.finditer(r"#\[access_control\((.*)\)\]", ep.attrs):
+            preds.append(f"access_control: {_collapse(m.group(1), 70)}")
+        preds += struct_predicates(self.structs.get(ep.context_struct, []))
+        return preds
+
+
+def _dedupe(items: list) -> list:
+    return list(dict.fromkeys(items))
+
+
+def analyze_target(path: str, budget: Optional[ScanBudget] = None,
+                   guard_macros=(), max_depth: int = DEFAULT_DEPTH) -> ReachabilityReport:
+    """Load sources under path (splitting concatenated files) and analyze."""
+    engine = AnchorShieldEngine(split_concatenated=True)
+    path = os.path.abspath(path)
+    if os.path.isfile(path):
+        with open(path, "r", encoding="utf-8", errors="ignore") as fh:
+            sources = engine._sources(os.path.basename(path), fh.read())
+    else:
+        sources = engine.load_sources(path)
+    analyzer = ReachabilityAnalyzer(sources, guard_macros)
+    return analyzer.analyze(target=path, budget=budget, max_depth=max_depth)
+
+
+def render_markdown(report: ReachabilityReport) -> str:
+    """Per-sink table: which entrypoints reach it, and through which guards."""
+    lines = [
+        "# Sink Reachability",
+        "",
+        f"Target: {report.target}  ",
+        f"Entrypoints: {report.entrypoints} · Functions: {report.functions} · Sinks: {len(report.sinks)}",
+        "",
+        "| Sink | Kind | Entrypoint | Path | Guards |",
+        "|------|------|------------|------|--------|",
+    ]
+    for reach in report.sinks:
+        sink = f"`{reach.sink.file}:{reach.sink.line}`"
+        if not reach.entrypoints:
+            lines.append(f"| {sink} | {reach.sink.kind} | — (unreached) | | |")
+            continue
+        for ep in reach.entrypoints:
+            guards = "<br>".join(p.replace("|", "\\|") for p in ep.predicates) or "**none**"
+            lines.append(
+                f"| {sink} | {reach.sink.kind} | {ep.entrypoint} | "
+                f"{' → '.join(ep.path)} | {guards} |"
+            )
+    if report.cpi_edges:
+        lines += [
+            "",
+            "## Cross-Program Calls",
+            "",
+            "| Caller | Program | Instruction | Via | Signed |",
+            "|--------|---------|-------------|-----|--------|",
+        ]
+        for e in report.cpi_edges:
+            program = e["program"] if e["resolved"] else f"**{e['program']}**"
+            lines.append(
+                f"| `{e['file']}:{e['line']}` {e['function']} | {program} | "
+                f"{e['instruction'] or '—'} | {e['via']} | {'yes' if e['signed'] else 'no'} |"
+            )
+    if report.degraded:
+        lines += ["", f"> Incomplete: {report.degraded[0]['reason']}; traversal from these files stopped early:", ""]
+        lines += [f"- `{d['file']}`: {', '.join(d['skipped'])}" for d in report.degraded]
+    lines.append("")
+    return "\n".join(lines)
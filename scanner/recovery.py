@@ -0,0 +1,156 @@
+"""
+Item-level recovery for sources that do not parse as a whole.
+
+Detectors find functions, impls and accounts structs by matching braces. One
+unbalanced brace — a `'{'` literal, a `"}"` in a format string, a truncated
+snapshot, a leftover merge-conflict hunk — shifts every block after it, and
+the `#[program]` module containing it never closes, so the rest of the file
+is silently lost.
+
+recover() returns the text the detectors should see instead:
+
+  1. Braces inside string/char literals and comments are blanked. This alone
+     fixes most real-world files and loses nothing.
+  2. If the code itself is still unbalanced, the file is split into items by
+     indentation (rustfmt layout: an item starts at its attributes or keyword
+     and ends at the `}` at the same indent). Balanced items are kept; an
+     unbalanced `mod`/`impl`/`trait` is descended into so its well-formed
+     items survive; any other unbalanced item is blanked.
+
+Blanked items keep their line breaks, so line numbers are unchanged, and each
+becomes an unparsed region ({"lines": [start, end], "reason"}) the report
+lists explicitly.
+"""
+
+import re
+from dataclasses import dataclass, field
+
+LITERAL_RE = re.compile(
+    r"(?<!\w)b?r(#*)\".*?\"\1"
+    r"|b?\"(?:[^\"\\]|\\.)*\""
+    r"|b?'(?:\\u\{[0-9a-fA-F]{1,6}\}|\\.|[^'\\\n])'"
+    r"|//[^\n]*|/\*.*?\*/",
+    re.S,
+)
+ITEM_RE = re.compile(
+    r"(?:#\[|(?:pub(?:\s*\([^)]*\))?\s+)?(?:(?:async|unsafe|const|default|extern\s+\"\w+\")\s+)*"
+    r"(?:fn|struct|enum|union|impl|mod|trait|const|static|type|use|macro_rules!)\b)"
+)
+# Items whose body is made of items; their well-formed children are kept
+CONTAINER_RE = re.compile(r"(?:^|\s)(?:mod|impl|trait)\b")
+
+
+@dataclass
+class Recovery:
+    """Text to analyze, with the line ranges that had to be blanked."""
+
+    text: str
+    # {"lines": [first, last], "reason"} per blanked region, 1-based
+    regions: list = field(default_factory=list)
+
+
+def recover(content: str) -> Recovery:
+    """Make `content` brace-balanced for the detectors, blanking what cannot be."""
+    if _balanced(content):
+        return Recovery(content)
+    masked_chars = list(content)
+    text_chars = list(content)
+    for m in LITERAL_RE.finditer(content):
+        for i in range(m.start(), m.end()):
+            if content[i] != "\n":
+                masked_chars[i] = " "
+            if content[i] in "{}":
+                text_chars[i] = " "
+    text = "".join(text_chars)
+    masked = "".join(masked_chars)
+    if _balanced(masked):
+        return Recovery(text)
+
+    lines = text.split("\n")
+    masked_lines = masked.split("\n")
+    regions = []
+    _recover_items(masked_lines, lines, 0, len(lines), regions)
+    regions.sort(key=lambda r: r["lines"][0])
+    return Recovery("\n".join(lines), regions)
+
+
+def _recover_items(masked: list, lines: list, lo: int, hi: int, regions: list) -> None:
+    """Keep or blank each item in masked[lo:hi]; descend into broken containers."""
+    indent = _item_indent(masked, lo, hi)
+    for start, end in _item_spans(masked, lo, hi, indent):
+        if _depth("\n".join(masked[start:end])) == 0:
+            continue
+        body = _container_body(masked, start, end, indent)
+        if body:
+            before = len(regions)
+            _recover_items(masked, lines, body[0], body[1], regions)
+            depth = _depth("\n".join(masked[start:end]))
+            if depth == 1 and body[1] == end:
+                # Truncated container: close it after its last item
+                last = max(i for i in range(start, end) if masked[i].strip())
+                masked[last] += " }"
+                lines[last] += " }"
+                if len(regions) == before:
+                    regions.append({"lines": [last + 1, last + 1], "reason": "missing closing brace"})
+                continue
+            if depth == 0:
+                continue
+            del regions[before:]
+        last = max((i for i in range(start, end) if masked[i].strip()), default=start)
+        for i in range(start, end):
+            masked[i] = lines[i] = ""
+        regions.append({"lines": [start + 1, last + 1], "reason": "unbalanced braces"})
+
+
+def _item_indent(masked: list, lo: int, hi: int):
+    for i in range(lo, hi):
+        stripped = masked[i].lstrip()
+        if ITEM_RE.match(stripped):
+            return len(masked[i]) - len(stripped)
+    return None
+
+
+def _item_spans(masked: list, lo: int, hi: int, indent) -> list:
+    """(start, end) line ranges of the items at `indent`; attributes open their item."""
+    starts = [lo]
+    attribute = False
+    for i in range(lo, hi):
+        stripped = masked[i].lstrip()
+        if not stripped or indent is None or len(masked[i]) - len(stripped) != indent:
+            continue
+        if ITEM_RE.match(stripped) and not attribute and i > lo:
+            starts.append(i)
+        attribute = stripped.startswith("#[")
+    return [(s, e) for s, e in zip(starts, starts[1:] + [hi]) if s < e]
+
+
+def _container_body(masked: list, start: int, end: int, indent):
+    """Inner line range of a multi-line `mod`/`impl`/`trait` item, to its end if unclosed."""
+    header = next((i for i in range(start, end) if "{" in masked[i]), None)
+    if header is None or not CONTAINER_RE.search(masked[header]):
+        return None
+    closing = next(
+        (i for i in range(end - 1, header, -1)
+         if masked[i].strip() in ("}", "};") and len(masked[i]) - len(masked[i].lstrip()) == indent),
+        None,
+    )
+    if masked[header].count("{") != 1 or masked[header].count("}"):
+        return None
+    return header + 1, end if closing is None else closing
+
+
+def _balanced(text: str) -> bool:
+    return _depth(text) == 0
+
+
+def _depth(text: str) -> int:
+    """Unclosed `{` count at the end of text, or -1 if a `}` closes nothing."""
+    depth = 0
+    for ch in text:
+        if ch == "{":
+            depth += 1
+        elif ch == "}":
+            depth -= 1
+            if depth < 0:
+                return -1
+    return depth
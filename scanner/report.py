@@ -1,8 +1,14 @@
 """Report generation for scan results."""
 
+import copy
+import hashlib
+import html
 import json
 from typing import Optional
 from scanner.engine import ScanReport
+from scanner.flows import render_mermaid, render_svg
+from scanner.messages import MessageCatalog
+from scanner.value_at_risk import risk_rank
 
 
 SEVERITY_COLORS = {
@@ -15,24 +21,54 @@ RESET = "\033[0m"
 BOLD = "\033[1m"
 DIM = "\033[2m"
 
+# How much source each severity keeps under --redact:
+#   "full" — unchanged, "line" — only the flagged line, "hash" — digest only
+REDACTION_POLICY = {
+    "Critical": "full",
+    "High": "full",
+    "Medium": "line",
+    "Low": "hash",
+}
+
+
+def _labeler(catalog: Optional[MessageCatalog]):
+    return catalog.label if catalog else (lambda text: text)
+
+
+def _priority(finding) -> tuple:
+    """Sort key putting hand-written, applicable findings first, then (scan --rpc)
+    the highest severity-plus-value rank."""
+    rank = -risk_rank(finding) if finding.value_at_risk is not None else 0
+    return bool(finding.generated), bool(finding.inapplicable), rank
+
 
-def format_terminal_report(report: ScanReport) -> str:
+def _hidden_generated(report: ScanReport) -> list:
+    """Generated units whose findings were left out of the report."""
+    shown = {f.file for f in report.findings if f.generated}
+    return [entry for entry in report.generated if entry["file"] not in shown]
+
+
+def format_terminal_report(report: ScanReport, catalog: Optional[MessageCatalog] = None) -> str:
     """Format scan report for terminal output."""
+    t = _labeler(catalog)
     lines = []
 
     # Header
     lines.append("")
-    lines.append(f"{BOLD}anchor-shield-v2 Scan Report{RESET}")
+    lines.append(f"{BOLD}anchor-shield-v2 {t('Scan Report')}{RESET}")
     lines.append("=" * 60)
-    lines.append(f"Target:           {report.target}")
-    lines.append(f"Files scanned:    {report.files_scanned}")
-    lines.append(f"Patterns checked: {report.patterns_checked}")
-    lines.append(f"Scan time:        {report.scan_time:.2f}s")
+    lines.append(f"{t('Target') + ':':<18}{report.target}")
+    lines.append(f"{t('Files scanned') + ':':<18}{report.files_scanned}")
+    lines.append(f"{t('Patterns checked') + ':':<18}{report.patterns_checked}")
+    lines.append(f"{t('Scan time') + ':':<18}{report.scan_time:.2f}s")
 
     if report.anchor_version:
-        lines.append(f"Anchor version:   {report.anchor_version}")
+        lines.append(f"{t('Anchor version') + ':':<18}{report.anchor_version}")
+    if report.ruleset:
+        version, digest = report.ruleset.get("version"), report.ruleset.get("hash", "")[:12]
+        lines.append(f"{t('Rule set') + ':':<18}{version + ' (' + digest + ')' if version else digest}")
 
-    lines.append(f"Security score:   {_colorize_score(report.security_score)}")
+    lines.append(f"{t('Security score') + ':':<18}{_colorize_score(report.security_score)}")
     lines.append("")
 
     # Summary bar
@@ -50,27 +86,119 @@ def format_terminal_report(report: ScanReport) -> str:
             f"\033[93mMedium: {medium}{RESET}  "
             f"\033[92mLow: {low}{RESET}"
         )
+        effort = summary.get("by_effort", {})
+        if any(effort.values()):
+            lines.append(
+                f"  {DIM}Remediation effort — "
+                + "  ".join(f"{level}: {count}" for level, count in effort.items())
+                + f"{RESET}"
+            )
+        if summary.get("suppressed") or summary.get("expired_suppressions"):
+            lines.append(
+                f"  {DIM}Suppressed: {summary.get('suppressed', 0)}  "
+                f"Expired suppressions: {summary.get('expired_suppressions', 0)}{RESET}"
+            )
+        if summary.get("inapplicable"):
+            lines.append(
+                f"  {DIM}Inapplicable to target cluster: {summary['inapplicable']} "
+                f"(not counted above){RESET}"
+            )
+        if summary.get("generated"):
+            lines.append(
+                f"  {DIM}In generated code: {summary['generated']} (listed last, not counted above){RESET}"
+            )
+        lines.append("")
+
+    # Passes skipped after the resource budget ran out
+    if report.degraded:
+        lines.append(
+            f"\033[93m{t('Incomplete scan')}: {report.degraded[0]['reason']}; "
+            f"{len(report.degraded)} file(s) scanned without:{RESET}"
+        )
+        for entry in report.degraded:
+            lines.append(f"  {DIM}{entry['file']}: {', '.join(entry['skipped'])}{RESET}")
+        lines.append("")
+
+    # Regions that did not parse; the rest of each file was still scanned
+    if report.unparsed:
+        lines.append(
+            f"\033[93m{t('Partially analyzed')}: {len(report.unparsed)} region(s) "
+            f"could not be parsed and were not scanned:{RESET}"
+        )
+        for entry in report.unparsed:
+            lines.append(f"  {DIM}{_region(entry)} ({entry['reason']}){RESET}")
+        lines.append("")
+
+    # Generated units; their findings are only listed with --include-generated
+    hidden = _hidden_generated(report)
+    if hidden:
+        lines.append(
+            f"{DIM}{t('Generated code')}: {sum(e['findings'] for e in hidden)} finding(s) in "
+            f"{len(hidden)} generated file(s) not shown (--include-generated lists them):{RESET}"
+        )
+        for entry in hidden:
+            lines.append(f"  {DIM}{entry['file']}: {entry['findings']} ({entry['reason']}){RESET}")
+        lines.append("")
+
+    # What the scan covered (scan --stats)
+    if report.stats:
+        lines.extend(_format_stats(report.stats, t))
+
+    # Findings per author (scan --blame)
+    if report.owners:
+        lines.append(f"{BOLD}{t('Owners')}:{RESET}")
+        for owner in report.owners:
+            lines.append(f"  {_owner(owner)}")
         lines.append("")
 
     # Findings
     if not report.findings:
-        lines.append(f"\033[92mNo vulnerabilities detected.{RESET}")
+        lines.append(f"\033[92m{t('No vulnerabilities detected.')}{RESET}")
         lines.append("")
         lines.append(f"{DIM}Scanned {report.files_scanned} files against "
                      f"{report.patterns_checked} detection patterns.{RESET}")
     else:
-        lines.append(f"{BOLD}Findings ({len(report.findings)}):{RESET}")
+        lines.append(f"{BOLD}{t('Findings')} ({len(report.findings)}):{RESET}")
         lines.append("-" * 60)
 
-        for i, finding in enumerate(report.findings, 1):
+        # Findings in generated code, and those the target cluster cannot trigger, go last
+        ordered = sorted(report.findings, key=_priority)
+        for i, finding in enumerate(ordered, 1):
             color = SEVERITY_COLORS.get(finding.severity, "")
             lines.append("")
             lines.append(
                 f"  {color}{BOLD}[{finding.severity.upper()}]{RESET} "
                 f"{BOLD}{finding.id}{RESET} — {finding.name}"
             )
-            lines.append(f"  File: {finding.file}:{finding.line}")
+            location = f"  {t('File')}: {finding.file}:{finding.line}"
+            if finding.remediation_effort:
+                location += f"  {DIM}(effort: {finding.remediation_effort}){RESET}"
+            lines.append(location)
+            if finding.suppression and finding.suppression.get("expired"):
+                lines.append(
+                    f"  \033[93mExpired suppression ({finding.suppression['source']}, "
+                    f"expired {finding.suppression['expires']}){RESET}"
+                )
+            if finding.inapplicable:
+                lines.append(
+                    f"  {DIM}{t('Inapplicable')}: target cluster lacks "
+                    f"{', '.join(finding.inapplicable)}{RESET}"
+                )
+            if finding.generated:
+                lines.append(f"  {DIM}{t('Generated code')}: {finding.generated}{RESET}")
+            if finding.blame:
+                lines.append(f"  {DIM}{t('Last changed')}: {_blame(finding.blame)}{RESET}")
+            if finding.value_at_risk and finding.value_at_risk["accounts"]:
+                lines.append(f"  {DIM}{t('Value at risk')}: {_value(finding.value_at_risk)}{RESET}")
+            if finding.supporting:
+                also = ", ".join(f"{s['id']} ({s['severity']})" for s in finding.supporting)
+                lines.append(f"  {DIM}{t('Also flagged by')}: {also}{RESET}")
             lines.append(f"  {finding.description}")
+            if finding.narrative:
+                lines.append("")
+                lines.append(f"  {BOLD}{t('Exploit path')}:{RESET}")
+                for n, step in enumerate(finding.narrative, 1):
+                    lines.append(f"    {n}. {step}")
 
             if finding.code_snippet:
                 lines.append("")
@@ -78,8 +206,8 @@ def format_terminal_report(report: ScanReport) -> str:
                     lines.append(f"    {snip_line}")
 
             lines.append("")
-            lines.append(f"  {BOLD}Fix:{RESET} {finding.fix_recommendation.split(chr(10))[0]}")
-            lines.append(f"  {DIM}Reference: {finding.reference}{RESET}")
+            lines.append(f"  {BOLD}{t('Fix')}:{RESET} {finding.fix_recommendation.split(chr(10))[0]}")
+            lines.append(f"  {DIM}{t('Reference')}: {finding.reference}{RESET}")
 
             if i < len(report.findings):
                 lines.append("  " + "-" * 56)
@@ -91,19 +219,83 @@ def format_terminal_report(report: ScanReport) -> str:
     return "\n".join(lines)
 
 
+def _region(entry: dict) -> str:
+    """file:first-last for an unparsed region, or just the file for a whole unit."""
+    if "lines" not in entry:
+        return entry["file"]
+    first, last = entry["lines"]
+    return f"{entry['file']}:{first}" if first == last else f"{entry['file']}:{first}-{last}"
+
+
+def _blame(blame: dict) -> str:
+    if blame.get("uncommitted"):
+        return "not committed yet"
+    return f"{blame['commit'][:10]} by {blame['author']} ({blame['date']})"
+
+
+def _value(value: dict) -> str:
+    names = ", ".join(a["name"] for a in value["accounts"])
+    if value["empty"]:
+        return f"empty ({names}, {value['network']})"
+    usd = f"${value['usd']:,.0f}" if value["usd"] is not None else "unpriced holdings"
+    return f"{usd} ({names}, {value['network']})"
+
+
+def _owner(owner: dict) -> str:
+    by_severity = ", ".join(f"{sev}: {n}" for sev, n in owner["by_severity"].items())
+    return f"{owner['author']} <{owner['email']}>: {owner['findings']} finding(s) ({by_severity})"
+
+
+def _format_stats(stats: dict, t) -> list:
+    ix = stats["instructions"]
+    lines = [
+        f"{BOLD}{t('Scanned surface')}:{RESET}",
+        f"  {stats['files']} files, {stats['loc']} LOC, {stats['programs']} programs, "
+        f"{ix['entrypoints']} instructions (+{ix['handlers']} handlers), "
+        f"{stats['accounts_structs']} accounts structs, {stats['cpis']} CPIs",
+        f"  {DIM}Rules executed: {len(stats['rules_executed'])}{RESET}",
+    ]
+    # Unparsed regions are listed under "Partially analyzed"
+    for entry in stats["unparsed"]:
+        if "lines" not in entry:
+            lines.append(f"  \033[93mNot parsed: {entry['file']} ({entry['reason']}){RESET}")
+    for entry in stats["rules_skipped"]:
+        lines.append(f"  {DIM}Skipped {entry['rule']}: {entry['reason']}{RESET}")
+    lines.append("")
+    return lines
+
+
 def format_json_report(report: ScanReport, indent: int = 2) -> str:
     """Format scan report as JSON."""
     return report.to_json(indent=indent)
 
 
-def format_html_report(report: ScanReport) -> str:
+def format_html_report(report: ScanReport, catalog: Optional[MessageCatalog] = None) -> str:
     """Format scan report as standalone HTML."""
+    t = _labeler(catalog)
+    locale = catalog.locale if catalog else "en"
     summary = report.summary or {}
     sev = summary.get("by_severity", {})
 
     findings_html = ""
-    for finding in report.findings:
+    for finding in sorted(report.findings, key=_priority):
         sev_class = finding.severity.lower()
+        if finding.inapplicable or finding.generated:
+            sev_class += " inapplicable-finding"
+        expired = ""
+        if finding.suppression and finding.suppression.get("expired"):
+            expired = f'<span class="expired">expired suppression ({finding.suppression["expires"]})</span>'
+        if finding.inapplicable:
+            expired += f'<span class="inapplicable">{t("Inapplicable")}: {", ".join(finding.inapplicable)}</span>'
+        if finding.generated:
+            expired += f'<span class="inapplicable">{t("Generated code")}: {finding.generated}</span>'
+        if finding.supporting:
+            also = ", ".join(f"{s['id']} ({s['severity']})" for s in finding.supporting)
+            expired += f'<span class="supporting">{t("Also flagged by")}: {also}</span>'
+        if finding.blame:
+            expired += f'<span class="supporting">{t("Last changed")}: {html.escape(_blame(finding.blame))}</span>'
+        if finding.value_at_risk and finding.value_at_risk["accounts"]:
+            expired += f'<span class="supporting">{t("Value at risk")}: {html.escape(_value(finding.value_at_risk))}</span>'
         findings_html += f"""
         <div class="finding {sev_class}">
             <div class="finding-header">
@@ -112,16 +304,20 @@ def format_html_report(report: ScanReport) -> str:
             </div>
             <div class="finding-meta">
                 <code>{finding.file}:{finding.line}</code>
+                <span class="effort">{finding.remediation_effort}</span>
+                {expired}
             </div>
             <p>{finding.description}</p>
+            {_render_narrative(finding.narrative, t)}
+            {_render_flow(finding.flow, t)}
             <details>
-                <summary>Details & Fix</summary>
+                <summary>{t("Details & Fix")}</summary>
                 <div class="details-content">
-                    <h4>Root Cause</h4>
+                    <h4>{t("Root Cause")}</h4>
                     <p>{finding.root_cause}</p>
-                    <h4>Exploit Scenario</h4>
+                    <h4>{t("Exploit Scenario")}</h4>
                     <pre>{finding.exploit_scenario}</pre>
-                    <h4>Fix Recommendation</h4>
+                    <h4>{t("Fix Recommendation")}</h4>
                     <pre>{finding.fix_recommendation}</pre>
                     {_render_code_snippet(finding.code_snippet)}
                 </div>
@@ -129,12 +325,51 @@ def format_html_report(report: ScanReport) -> str:
         </div>
         """
 
+    degraded_html = ""
+    if report.degraded:
+        rows = "".join(
+            f"<li><code>{entry['file']}</code>: {', '.join(entry['skipped'])}</li>"
+            for entry in report.degraded
+        )
+        degraded_html = (
+            f"<div class='degraded'><strong>{t('Incomplete scan')}</strong>: "
+            f"{report.degraded[0]['reason']}<ul>{rows}</ul></div>"
+        )
+
+    unparsed_html = ""
+    if report.unparsed:
+        rows = "".join(
+            f"<li><code>{_region(entry)}</code>: {entry['reason']}</li>"
+            for entry in report.unparsed
+        )
+        unparsed_html = (
+            f"<div class='degraded'><strong>{t('Partially analyzed')}</strong>: "
+            f"regions that could not be parsed were not scanned<ul>{rows}</ul></div>"
+        )
+
+    generated_html = ""
+    hidden = _hidden_generated(report)
+    if hidden:
+        rows = "".join(
+            f"<li><code>{entry['file']}</code>: {entry['findings']} ({entry['reason']})</li>"
+            for entry in hidden
+        )
+        generated_html = (
+            f"<div class='degraded'><strong>{t('Generated code')}</strong>: "
+            f"findings in generated files not shown (--include-generated lists them)<ul>{rows}</ul></div>"
+        )
+
+    owners_html = ""
+    if report.owners:
+        rows = "".join(f"<li>{html.escape(_owner(owner))}</li>" for owner in report.owners)
+        owners_html = f"<div class='degraded'><strong>{t('Owners')}</strong>: findings by last author<ul>{rows}</ul></div>"
+
     return f"""<!DOCTYPE html>
-<html lang="en">
+<html lang="{locale}">
 <head>
     <meta charset="UTF-8">
     <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>anchor-shield-v2 Scan Report</title>
+    <title>anchor-shield-v2 {t("Scan Report")}</title>
     <style>
         * {{ margin: 0; padding: 0; box-sizing: border-box; }}
         body {{ font-family: 'Inter', -apple-system, BlinkMacSystemFont, sans-serif;
@@ -166,6 +401,14 @@ def format_html_report(report: ScanReport) -> str:
         .severity-badge.medium {{ background: #FFA50033; color: #FFA500; }}
         .severity-badge.low {{ background: #00C85333; color: #00C853; }}
         .finding-meta {{ color: #888; font-size: 0.85rem; margin-bottom: 0.5rem; }}
+        .effort {{ margin-left: 1rem; font-size: 0.75rem; color: #14F195; }}
+        .expired {{ margin-left: 1rem; font-size: 0.75rem; color: #ffaa00; }}
+        .inapplicable {{ margin-left: 1rem; font-size: 0.75rem; color: #888; }}
+        .supporting {{ margin-left: 1rem; font-size: 0.75rem; color: #888; }}
+        .finding.inapplicable-finding {{ opacity: 0.6; }}
+        .degraded {{ margin-bottom: 2rem; padding: 1rem; border-radius: 8px;
+                    background: #2a2210; color: #ffaa00; font-size: 0.85rem; }}
+        .degraded ul {{ margin: 0.5rem 0 0 1.2rem; color: #ccc; }}
         details {{ margin-top: 0.8rem; }}
         summary {{ cursor: pointer; color: #9945FF; font-size: 0.9rem; }}
         .details-content {{ margin-top: 1rem; padding: 1rem; background: #0F1117;
@@ -174,17 +417,21 @@ def format_html_report(report: ScanReport) -> str:
         pre {{ background: #0a0c12; padding: 0.8rem; border-radius: 4px;
               overflow-x: auto; font-size: 0.85rem; color: #ccc; white-space: pre-wrap; }}
         code {{ font-family: 'JetBrains Mono', 'Fira Code', monospace; font-size: 0.85rem; }}
+        .narrative {{ margin: 0.6rem 0 0 1.4rem; font-size: 0.9rem; color: #ccc; }}
+        .narrative-title {{ color: #FF4444; font-size: 0.85rem; margin-top: 0.6rem; }}
+        .flow-title {{ color: #14F195; font-size: 0.85rem; margin-top: 0.6rem; }}
+        .flow {{ display: block; margin-top: 0.4rem; max-width: 100%; height: auto; }}
         .no-findings {{ text-align: center; padding: 2rem; color: #00C853; font-size: 1.1rem; }}
         .score {{ font-size: 1.2rem; font-weight: bold; }}
     </style>
 </head>
 <body>
     <div class="container">
-        <h1>anchor-shield-v2 Scan Report</h1>
+        <h1>anchor-shield-v2 {t("Scan Report")}</h1>
         <p class="subtitle">Automated security scanner for Solana Anchor programs</p>
 
         <div class="meta">
-            <span>Target: <strong>{report.target}</strong></span>
+            <span>{t("Target")}: <strong>{report.target}</strong></span>
             <span>Files: <strong>{report.files_scanned}</strong></span>
             <span>Patterns: <strong>{report.patterns_checked}</strong></span>
             <span>Time: <strong>{report.scan_time:.2f}s</strong></span>
@@ -198,12 +445,99 @@ def format_html_report(report: ScanReport) -> str:
             <div class="summary-item low"><div class="count">{sev.get('Low', 0)}</div><div class="label">Low</div></div>
         </div>
 
-        {"<div class='no-findings'>No vulnerabilities detected. Scanned " + str(report.files_scanned) + " files against " + str(report.patterns_checked) + " patterns.</div>" if not report.findings else findings_html}
+        <div class="meta">
+            <span>Remediation effort:</span>
+            {"".join(f"<span>{level}: <strong>{count}</strong></span>" for level, count in summary.get("by_effort", {}).items())}
+        </div>
+
+        {degraded_html}
+        {unparsed_html}
+        {generated_html}
+        {owners_html}
+
+        {"<div class='no-findings'>" + t("No vulnerabilities detected.") + " Scanned " + str(report.files_scanned) + " files against " + str(report.patterns_checked) + " patterns.</div>" if not report.findings else findings_html}
     </div>
 </body>
 </html>"""
 
 
+def format_markdown_report(report: ScanReport, catalog: Optional[MessageCatalog] = None) -> str:
+    """Format scan report as Markdown, with Mermaid account flows."""
+    t = _labeler(catalog)
+    sev = (report.summary or {}).get("by_severity", {})
+    lines = [
+        f"# anchor-shield-v2 {t('Scan Report')}",
+        "",
+        f"- **{t('Target')}:** `{report.target}`",
+        f"- **{t('Files scanned')}:** {report.files_scanned}",
+        f"- **{t('Patterns checked')}:** {report.patterns_checked}",
+        f"- **{t('Security score')}:** {report.security_score}",
+        "",
+        "| Critical | High | Medium | Low |",
+        "|---:|---:|---:|---:|",
+        f"| {sev.get('Critical', 0)} | {sev.get('High', 0)} | {sev.get('Medium', 0)} | {sev.get('Low', 0)} |",
+        "",
+    ]
+    if not report.findings:
+        lines.append(t("No vulnerabilities detected."))
+        return "\n".join(lines) + "\n"
+
+    lines.append(f"## {t('Findings')} ({len(report.findings)})")
+    for finding in sorted(report.findings, key=_priority):
+        lines.append("")
+        lines.append(f"### [{finding.severity.upper()}] {finding.id} — {finding.name}")
+        lines.append("")
+        lines.append(f"`{finding.file}:{finding.line}`" +
+                     (f" · effort: {finding.remediation_effort}" if finding.remediation_effort else ""))
+        if finding.value_at_risk and finding.value_at_risk["accounts"]:
+            lines.append("")
+            lines.append(f"**{t('Value at risk')}:** {_value(finding.value_at_risk)}")
+        lines.append("")
+        lines.append(finding.description)
+        if finding.narrative:
+            lines.append("")
+            lines.append(f"**{t('Exploit path')}:**")
+            lines.append("")
+            lines.extend(f"{n}. {step}" for n, step in enumerate(finding.narrative, 1))
+        if finding.flow:
+            lines.append("")
+            lines.append(f"**{t('Account flow')}:**")
+            lines.append("")
+            lines.append(render_mermaid(finding.flow))
+        if finding.code_snippet:
+            lines.append("")
+            lines.append("```rust")
+            lines.append(finding.code_snippet)
+            lines.append("```")
+        lines.append("")
+        lines.append(f"**{t('Fix')}:** {finding.fix_recommendation.split(chr(10))[0]}")
+    return "\n".join(lines) + "\n"
+
+
+def redact_snippet(snippet: str, severity: str) -> str:
+    """Reduce a code snippet according to REDACTION_POLICY for its severity."""
+    mode = REDACTION_POLICY.get(severity, "hash")
+    if not snippet or mode == "full":
+        return snippet
+    if mode == "line":
+        flagged = [l for l in snippet.split("\n") if l.startswith(">>>")]
+        return flagged[0] if flagged else ""
+    digest = hashlib.sha256(snippet.encode()).hexdigest()[:16]
+    return f"[redacted: {len(snippet.splitlines())} lines, sha256 {digest}]"
+
+
+def redact_report(report: ScanReport) -> ScanReport:
+    """Return a copy of the report safe to share outside the team.
+
+    Critical/High findings keep full context; lower severities are reduced to
+    the flagged line or a content hash so unreleased source is not exported.
+    """
+    redacted = copy.deepcopy(report)
+    for finding in redacted.findings:
+        finding.code_snippet = redact_snippet(finding.code_snippet, finding.severity)
+    return redacted
+
+
 def _colorize_score(score: str) -> str:
     """Add color to security score."""
     if score in ("A", "A+"):
@@ -218,5 +552,19 @@ def _render_code_snippet(snippet: str) -> str:
     """Render code snippet as HTML."""
     if not snippet:
         return ""
-    import html
     return f"<h4>Code</h4><pre><code>{html.escape(snippet)}</code></pre>"
+
+
+def _render_narrative(steps: list, t) -> str:
+    """Render an exploit narrative as an ordered list."""
+    if not steps:
+        return ""
+    items = "".join(f"<li>{html.escape(step)}</li>" for step in steps)
+    return f'<div class="narrative-title">{t("Exploit path")}</div><ol class="narrative">{items}</ol>'
+
+
+def _render_flow(flow: list, t) -> str:
+    """Render a handler's token/lamport legs as an inline SVG diagram."""
+    if not flow:
+        return ""
+    return f'<div class="flow-title">{t("Account flow")}</div>{render_svg(flow)}'
@@ -0,0 +1,87 @@
+"""
+Rounding-site inventory (`rounding TARGET`).
+
+ANCHOR-052 reports the divisions that round in the user's favor; an audit
+of a vault or AMM wants every division in its value math with the
+direction it takes and the direction it should take:
+
+    | Site       | Function | Value      | Paid by / to | Rounds | Should | Favors   |
+    |------------|----------|------------|--------------|--------|--------|----------|
+    | lib.rs:9   | swap     | fee        | by user      | floor  | ceil   | **user** |
+    | lib.rs:18  | deposit  | shares     | to user      | floor  | floor  | program  |
+
+Sites are classified as in patterns/rounding_direction.py; divisions
+whose beneficiary cannot be told from the value or instruction name are
+left out. The report is informational; ANCHOR-052 produces the findings.
+"""
+
+import json
+import os
+from dataclasses import dataclass, field
+
+from scanner.engine import AnchorShieldEngine
+from scanner.patterns.rounding_direction import RoundingDirectionPattern
+
+
+@dataclass
+class RoundingReport:
+    target: str
+    sites: list = field(default_factory=list)
+
+    @property
+    def favoring_user(self) -> list:
+        return [s for s in self.sites if s.favors_user]
+
+    def to_dict(self) -> dict:
+        return {
+            "target": self.target,
+            "sites": [s.to_dict() for s in self.sites],
+            "favoring_user": len(self.favoring_user),
+        }
+
+    def to_json(self, indent: int = 2) -> str:
+        return json.dumps(self.to_dict(), indent=indent)
+
+
+def analyze_sources(sources: list, target: str = "") -> RoundingReport:
+    """Rounding sites of (path, content) units."""
+    pattern = RoundingDirectionPattern()
+    report = RoundingReport(target=target)
+    for path, content in sources:
+        report.sites.extend(pattern.sites(path, content))
+    return report
+
+
+def analyze_target(path: str, engine: AnchorShieldEngine = None) -> RoundingReport:
+    """Rounding sites of a directory or .rs file."""
+    engine = engine or AnchorShieldEngine(split_concatenated=True)
+    path = os.path.abspath(path)
+    if os.path.isfile(path):
+        with open(path, "r", encoding="utf-8", errors="ignore") as fh:
+            sources = engine._sources(os.path.basename(path), fh.read())
+    else:
+        sources = engine.load_sources(path)
+    return analyze_sources(sources, path)
+
+
+def render_markdown(report: RoundingReport) -> str:
+    lines = [
+        "# Rounding Sites",
+        "",
+        f"Target: {report.target}  ",
+        f"Sites: {len(report.sites)} · Favoring the user: {len(report.favoring_user)}",
+        "",
+        "Amounts paid by the user should round up (ceil), amounts paid to the user down (floor). "
+        "A division subtracted from the value should round the other way.",
+        "",
+        "| Site | Function | Expression | Value | Paid | Rounds | Should | Favors |",
+        "|------|----------|------------|-------|------|--------|--------|--------|",
+    ]
+    for s in report.sites:
+        paid = ("by user" if s.beneficiary == "protocol" else "to user") + (", subtracted" if s.subtracted else "")
+        favors = "**user**" if s.favors_user else "program"
+        expression = s.expression.replace("|", "\\|")
+        lines.append(f"| {s.file}:{s.line} | {s.function} | `{expression}` | `{s.value}` | {paid} | "
+                     f"{s.rounds} | {s.should} | {favors} |")
+    lines.append("")
+    return "\n".join(lines)
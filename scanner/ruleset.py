@@ -0,0 +1,145 @@
+"""
+Rule-set identity, so an audit report can be reproduced months later.
+
+The rule set is the code that decides what is reported (RULESET_SOURCES):
+the rules and their helpers (scanner/patterns/), the shared analyses they
+call (cpi.py, budget.py), the source preparation in front of them (macro
+expansion, concatenated-file splitting, parse recovery, generated-code
+skipping), the per-project rule options and skipped packs (config.py),
+the dependency and feature-gate handling, the precedence table merging
+their findings (dedup.py) and the engine running them. Output formats,
+the CLI and the analysis commands beside the scan are left out.
+ruleset_hash() digests those sources; reports and baselines record it as
+
+    "ruleset": {"version": "0.1.0", "hash": "3be1..."}
+
+where `version` names the hash in the bundled manifest (rulesets.json, one
+entry per released rule set, added with `rules ruleset --record`) and is
+null for an unreleased one.
+
+`scan --ruleset <version|hash>` pins a scan to a rule set: a version from
+the manifest, or a hash (at least MIN_HASH_PREFIX characters of it) taken
+from an earlier report or baseline. The scanner only has its installed
+rules to run, so a pin naming any other rule set is refused rather than
+answered with results the pinned audit never saw.
+
+A baseline records the rule set it was created under. Suppressing findings
+with a baseline from another rule set is refused the same way until the
+scan is given `--migrate-baseline`, which drops the entries of rules the
+installed set no longer has and re-stamps the file; see
+suppression.migrate_baseline().
+"""
+
+import hashlib
+import json
+import os
+import re
+from dataclasses import dataclass, field
+from typing import Optional
+
+SCANNER_DIR = os.path.dirname(os.path.abspath(__file__))
+RULESET_MANIFEST = os.path.join(SCANNER_DIR, "rulesets.json")
+RULESET_SOURCES = (
+    "patterns", "cpi.py", "budget.py", "macros.py", "concat.py", "recovery.py", "generated.py",
+    "config.py", "dependencies.py", "features.py", "dedup.py", "engine.py",
+)
+MIN_HASH_PREFIX = 8
+
+
+class RulesetError(ValueError):
+    """A pin or baseline names a rule set other than the installed one."""
+
+
+@dataclass
+class Ruleset:
+    hash: str
+    # Manifest version of the hash, "" for an unreleased rule set
+    version: str = ""
+    rules: list = field(default_factory=list)
+
+    @property
+    def label(self) -> str:
+        return f"{self.version} ({self.hash[:12]})" if self.version else f"unreleased ({self.hash[:12]})"
+
+    def to_dict(self) -> dict:
+        return {"version": self.version or None, "hash": self.hash}
+
+
+def ruleset_hash() -> str:
+    """Digest of the rule sources: any change to what rules report changes it."""
+    digest = hashlib.sha256()
+    paths = []
+    for source in RULESET_SOURCES:
+        full = os.path.join(SCANNER_DIR, source)
+        if os.path.isfile(full):
+            paths.append(full)
+            continue
+        for root, dirs, files in os.walk(full):
+            dirs[:] = sorted(d for d in dirs if d != "__pycache__")
+            paths += [os.path.join(root, name) for name in sorted(files) if name.endswith(".py")]
+    for path in paths:
+        digest.update(os.path.relpath(path, SCANNER_DIR).replace(os.sep, "/").encode())
+        with open(path, "rb") as fh:
+            digest.update(fh.read())
+    return digest.hexdigest()
+
+
+def bundled_rulesets(path: str = RULESET_MANIFEST) -> list[dict]:
+    """Manifest entries, {"version", "hash", "rules"}, oldest first."""
+    if not os.path.exists(path):
+        return []
+    with open(path) as fh:
+        return json.load(fh).get("rulesets", [])
+
+
+def installed_ruleset(rule_ids: list, manifest: str = RULESET_MANIFEST) -> Ruleset:
+    current = ruleset_hash()
+    version = next((e["version"] for e in bundled_rulesets(manifest) if e.get("hash") == current), "")
+    return Ruleset(current, version, list(rule_ids))
+
+
+def resolve(spec: str, manifest: str = RULESET_MANIFEST) -> dict:
+    """The rule set a --ruleset value names: {"version", "hash"}; the hash may be a prefix."""
+    spec = spec.strip()
+    for entry in bundled_rulesets(manifest):
+        if entry.get("version") == spec:
+            return {"version": entry["version"], "hash": entry["hash"]}
+    if re.fullmatch(r"[0-9a-fA-F]+", spec) and len(spec) >= MIN_HASH_PREFIX:
+        return {"version": None, "hash": spec.lower()}
+    known = ", ".join(e["version"] for e in bundled_rulesets(manifest)) or "none"
+    raise RulesetError(
+        f"unknown rule set '{spec}': give a bundled version ({known}) "
+        f"or at least {MIN_HASH_PREFIX} characters of a recorded hash"
+    )
+
+
+def pin(spec: str, installed: Ruleset, manifest: str = RULESET_MANIFEST) -> None:
+    """Raise RulesetError unless `spec` names the installed rule set."""
+    wanted = resolve(spec, manifest)
+    if installed.hash.startswith(wanted["hash"]):
+        return
+    name = wanted["version"] or wanted["hash"][:12]
+    raise RulesetError(
+        f"scan pinned to rule set {name}, but the installed rule set is {installed.label}; "
+        f"run the anchor-shield release that ships {name} to reproduce that audit"
+    )
+
+
+def record(version: str, installed: Ruleset, manifest: str = RULESET_MANIFEST) -> bool:
+    """Add the installed rule set to the manifest as `version`. False if already recorded."""
+    entries = bundled_rulesets(manifest)
+    for entry in entries:
+        if entry.get("version") == version and entry.get("hash") != installed.hash:
+            raise RulesetError(f"version {version} is already recorded with hash {entry['hash'][:12]}")
+        if entry.get("hash") == installed.hash:
+            return False
+    entries.append({"version": version, "hash": installed.hash, "rules": sorted(installed.rules)})
+    with open(manifest, "w") as fh:
+        json.dump({"rulesets": entries}, fh, indent=2)
+        fh.write("\n")
+    return True
+
+
+def same_ruleset(recorded: Optional[dict], installed: Ruleset) -> bool:
+    """Whether a recorded {"version", "hash"} stamp is the installed rule set. Unstamped counts as same."""
+    return not recorded or recorded.get("hash") == installed.hash
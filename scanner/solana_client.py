@@ -17,6 +17,51 @@ RPC_ENDPOINTS = {
 
 # BPF Upgradeable Loader program ID
 BPF_LOADER_UPGRADEABLE = "BPFLoaderUpgradeab1e11111111111111111111111"
+TOKEN_PROGRAMS = (
+    "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+    "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb",
+)
+ASSOCIATED_TOKEN_PROGRAM = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+
+BASE58_ALPHABET = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz"
+# ed25519 field prime and curve constant, for the off-curve check of PDAs
+ED25519_P = 2 ** 255 - 19
+ED25519_D = -121665 * pow(121666, ED25519_P - 2, ED25519_P) % ED25519_P
+
+
+def b58decode(text: str) -> bytes:
+    n = 0
+    for char in text:
+        n = n * 58 + BASE58_ALPHABET.index(char)
+    raw = n.to_bytes((n.bit_length() + 7) // 8, "big") if n else b""
+    return b"\0" * (len(text) - len(text.lstrip("1"))) + raw
+
+
+def b58encode(data: bytes) -> str:
+    n = int.from_bytes(data, "big")
+    text = ""
+    while n:
+        n, digit = divmod(n, 58)
+        text = BASE58_ALPHABET[digit] + text
+    return "1" * (len(data) - len(data.lstrip(b"\0"))) + text
+
+
+def is_on_curve(key: bytes) -> bool:
+    """Whether 32 bytes decompress to an ed25519 point (a PDA must not)."""
+    p = ED25519_P
+    y = (int.from_bytes(key, "little") & ((1 << 255) - 1)) % p
+    x2 = (y * y - 1) * pow(ED25519_D * y * y + 1, p - 2, p) % p
+    return x2 == 0 or pow(x2, (p - 1) // 2, p) == 1
+
+
+def find_program_address(seeds: list, program_id: str) -> tuple[str, int]:
+    """Canonical PDA and bump for the seeds under program_id, as Pubkey::find_program_address."""
+    program = b58decode(program_id)
+    for bump in range(255, -1, -1):
+        digest = hashlib.sha256(b"".join(seeds) + bytes([bump]) + program + b"ProgramDerivedAddress").digest()
+        if not is_on_curve(digest):
+            return b58encode(digest), bump
+    raise ValueError("no viable bump for the seeds")
 
 
 @dataclass
@@ -72,7 +117,9 @@ class SolanaChecker:
 
     def __init__(self, network: str = "mainnet-beta"):
         self.network = network
-        self.rpc_url = RPC_ENDPOINTS.get(network, RPC_ENDPOINTS["mainnet-beta"])
+        # A network name, or the URL of any RPC endpoint
+        custom = network if network.startswith(("http://", "https://")) else None
+        self.rpc_url = RPC_ENDPOINTS.get(network, custom or RPC_ENDPOINTS["mainnet-beta"])
         self.session = requests.Session()
         self.session.headers["Content-Type"] = "application/json"
 
@@ -208,6 +255,35 @@ class SolanaChecker:
 
         return assessment
 
+    def get_multiple_accounts(self, addresses: list) -> dict:
+        """{address: balance} for the accounts that exist; see _balance."""
+        accounts = {}
+        for i in range(0, len(addresses), 100):
+            chunk = addresses[i:i + 100]
+            result = self._rpc_call("getMultipleAccounts", [
+                chunk,
+                {"encoding": "jsonParsed", "commitment": "confirmed"},
+            ])
+            for address, value in zip(chunk, (result or {}).get("value") or []):
+                if value:
+                    accounts[address] = _balance(value)
+        return accounts
+
+    def get_token_accounts_by_owner(self, owner: str) -> list:
+        """[{"address", "mint", "amount", "decimals"}] of the token accounts an address owns."""
+        holdings = []
+        for program in TOKEN_PROGRAMS:
+            result = self._rpc_call("getTokenAccountsByOwner", [
+                owner,
+                {"programId": program},
+                {"encoding": "jsonParsed", "commitment": "confirmed"},
+            ])
+            for item in (result or {}).get("value") or []:
+                token = _balance(item["account"])["token"]
+                if token:
+                    holdings.append({"address": item["pubkey"], **token})
+        return holdings
+
     def _fetch_upgrade_authority(self, info: ProgramInfo):
         """Fetch upgrade authority from program data account."""
         try:
@@ -274,3 +350,28 @@ class SolanaChecker:
         except requests.exceptions.RequestException:
             pass
         return None
+
+
+def _balance(value: dict) -> dict:
+    """{"lamports", "owner", "space", "token"} of a jsonParsed account; token is
+    {"mint", "amount", "decimals"} for SPL token accounts, else None."""
+    data = value.get("data")
+    token = None
+    if isinstance(data, dict):
+        parsed = data.get("parsed") or {}
+        info = parsed.get("info") or {}
+        if parsed.get("type") == "account" and "tokenAmount" in info:
+            token = {
+                "mint": info.get("mint", ""),
+                "amount": int(info["tokenAmount"].get("amount", 0)),
+                "decimals": int(info["tokenAmount"].get("decimals", 0)),
+            }
+    space = value.get("space")
+    if space is None and isinstance(data, dict):
+        space = data.get("space", 0)
+    return {
+        "lamports": int(value.get("lamports", 0)),
+        "owner": value.get("owner", ""),
+        "space": int(space or 0),
+        "token": token,
+    }
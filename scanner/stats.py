@@ -0,0 +1,102 @@
+"""
+Scanned-surface statistics (`scan --stats`).
+
+A clean report means little if half the target was never analyzed: a file
+whose braces do not balance yields no functions or structs, a rule that
+raises on a file is skipped silently, and `--only` or a spent budget leave
+rules out. SurfaceStats records what the scan actually covered:
+
+  - files, lines of code (non-blank, non-comment), `#[program]` modules,
+    instructions (program entrypoints and other Context<T> handlers),
+    accounts structs, and CPI call sites (scanner/cpi.py)
+  - source regions left out because they do not parse (scanner/recovery.py)
+  - rules executed, and rules skipped with the reason: deselected by
+    `--only`/`--skip`, skipped by the resource budget, or raising an error
+
+Consumers compare these numbers with what they expected to scan.
+"""
+
+import re
+from dataclasses import dataclass, field
+
+from scanner.cpi import declared_programs, find_cpi_calls, interface_modules
+from scanner.patterns.base import VulnerabilityPattern
+from scanner.patterns.facts import Facts
+from scanner.recovery import Recovery
+
+PROGRAM_RE = re.compile(r"#\[program\]\s*(?:pub\s+)?mod\s+\w+")
+# Literals first, so `//` in a URL or `/*` in a glob is not taken for a comment
+TOKEN_RE = re.compile(r"'(?:[^'\\\n]|\\.)'|\"(?:[^\"\\]|\\.)*\"|//[^\n]*|/\*.*?\*/", re.S)
+
+
+@dataclass
+class SurfaceStats:
+    """What one scan analyzed; filled per source unit by the engine."""
+
+    files: int = 0
+    loc: int = 0
+    programs: int = 0
+    entrypoints: int = 0
+    handlers: int = 0
+    accounts_structs: int = 0
+    cpis: int = 0
+    # {"file", "lines", "reason"} per region left out; no "lines" for a whole unit
+    unparsed: list = field(default_factory=list)
+    # {"file", "rule", "error"} per rule that raised on a file
+    rule_errors: list = field(default_factory=list)
+
+    def add_unit(self, path: str, content: str, recovery: Recovery) -> None:
+        """Count one source unit; the surface is what recovery left to analyze."""
+        self.files += 1
+        code = TOKEN_RE.sub(lambda m: '""' if m.group(0)[0] in "'\"" else "", content)
+        self.loc += sum(1 for line in code.splitlines() if line.strip())
+        self.unparsed.extend({"file": path, **region} for region in recovery.regions)
+        if not code.strip():
+            self.unparsed.append({"file": path, "reason": "no code"})
+            return
+        content = recovery.text
+        facts = Facts(content, path)
+        self.programs += len(PROGRAM_RE.findall(TOKEN_RE.sub("", content)))
+        instructions = facts.instructions()
+        self.entrypoints += sum(1 for ix in instructions if ix.is_entrypoint)
+        self.handlers += sum(1 for ix in instructions if not ix.is_entrypoint)
+        self.accounts_structs += len(facts.accounts_structs())
+        declared, interfaces = declared_programs(content), interface_modules(content)
+        for fn in VulnerabilityPattern._find_functions(content):
+            self.cpis += len(find_cpi_calls(fn["body"], declared, interfaces))
+
+    def add_error(self, path: str, rule: str, error: Exception) -> None:
+        self.rule_errors.append({"file": path, "rule": rule, "error": f"{type(error).__name__}: {error}"})
+
+    def to_dict(self, all_rules: list, selected: list, degraded: list) -> dict:
+        """Counts plus per-rule execution status for the finished scan."""
+        budget_skipped = {}
+        for entry in degraded:
+            for rule in entry["skipped"]:
+                budget_skipped.setdefault(rule, []).append(entry["file"])
+        errored = {}
+        for entry in self.rule_errors:
+            errored.setdefault(entry["rule"], []).append(entry["file"])
+
+        skipped = [{"rule": rule, "reason": "deselected by --only/--skip"}
+                   for rule in all_rules if rule not in selected]
+        for rule, files in budget_skipped.items():
+            skipped.append({"rule": rule, "reason": f"resource budget spent ({len(files)} file(s))"})
+        for rule, files in errored.items():
+            skipped.append({"rule": rule, "reason": f"raised an error ({len(files)} file(s))"})
+        executed = [
+            rule for rule in selected
+            if len(budget_skipped.get(rule, [])) + len(errored.get(rule, [])) < self.files
+        ]
+        return {
+            "files": self.files,
+            "loc": self.loc,
+            "programs": self.programs,
+            "instructions": {"entrypoints": self.entrypoints, "handlers": self.handlers},
+            "accounts_structs": self.accounts_structs,
+            "cpis": self.cpis,
+            "unparsed": self.unparsed,
+            "rules_executed": executed,
+            "rules_skipped": skipped,
+            "rule_errors": self.rule_errors,
+        }
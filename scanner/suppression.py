@@ -0,0 +1,213 @@
+"""
+Finding suppressions: inline comments and baseline files.
+
+Inline, on the flagged line or the line above it:
+
+    // anchor-shield: ignore ANCHOR-005 expires = "2025-12-01" reason = "migrating in v2"
+
+Rules are IDs or namespace globs (`shield::cpi::*`), comma-separated.
+
+Baseline (`.anchor-shield/baseline.json`), keyed by finding fingerprint:
+
+    {"version": 1, "ruleset": {"version": "0.1.0", "hash": "..."},
+     "entries": [{"fingerprint": "...", "id": "ANCHOR-005", "file": "src/lib.rs",
+      "scope": "struct Deposit", "expires": "2025-12-01", "reason": "..."}]}
+
+The fingerprint covers the finding's enclosing item (Finding.fingerprint),
+so an entry hides that one finding: the same line text added to another
+struct or function is reported as new.
+
+`ruleset` is the rule set the baseline was created under (scanner/ruleset.py).
+The engine refuses a baseline from another rule set unless asked to migrate
+it; baselines written before rule sets were recorded carry no stamp and are
+accepted as they are.
+
+`expires` is optional on both. Once the date has passed the suppression no
+longer hides the finding: it is reported again with an "expired suppression"
+marker, so a temporary risk acceptance cannot quietly become permanent.
+"""
+
+import json
+import os
+import re
+from dataclasses import dataclass
+from datetime import date
+from typing import Optional
+
+DEFAULT_BASELINE_PATH = os.path.join(".anchor-shield", "baseline.json")
+
+INLINE_RE = re.compile(
+    r"//\s*anchor-shield:\s*ignore\s+(?P<rules>[\w:*\-]+(?:\s*,\s*[\w:*\-]+)*)"
+    r"(?P<meta>(?:\s+\w+\s*=\s*\"[^\"]*\")*)"
+)
+META_RE = re.compile(r"(\w+)\s*=\s*\"([^\"]*)\"")
+
+
+@dataclass
+class Suppression:
+    """One inline comment or baseline entry."""
+
+    source: str  # "inline" | "baseline"
+    rules: tuple = ()
+    fingerprint: str = ""
+    expires: Optional[date] = None
+    reason: str = ""
+    line: int = 0
+
+    def is_expired(self, today: date) -> bool:
+        return self.expires is not None and self.expires < today
+
+    def marker(self, expired: bool) -> dict:
+        return {
+            "source": self.source,
+            "expires": self.expires.isoformat() if self.expires else None,
+            "reason": self.reason,
+            "expired": expired,
+        }
+
+
+def parse_expiry(value: str) -> Optional[date]:
+    """Parse an ISO date; raises ValueError so bad dates are not silently permanent."""
+    if not value:
+        return None
+    return date.fromisoformat(value.strip())
+
+
+def inline_suppressions(content: str) -> dict[int, list[Suppression]]:
+    """Map line number -> suppressions that apply to findings on that line.
+
+    A comment applies to its own line and, when it is the only thing on its
+    line, to the next line of code.
+    """
+    result = {}
+    lines = content.split("\n")
+    for i, text in enumerate(lines, 1):
+        m = INLINE_RE.search(text)
+        if not m:
+            continue
+        meta = dict(META_RE.findall(m.group("meta")))
+        try:
+            expires = parse_expiry(meta.get("expires", ""))
+        except ValueError:
+            # An unreadable date must not suppress forever
+            expires = date.min
+        sup = Suppression(
+            source="inline",
+            rules=tuple(r.strip() for r in m.group("rules").split(",")),
+            expires=expires,
+            reason=meta.get("reason", ""),
+            line=i,
+        )
+        result.setdefault(i, []).append(sup)
+        if not text[:m.start()].strip():
+            target = i + 1
+            while target <= len(lines) and lines[target - 1].strip().startswith("//"):
+                target += 1
+            result.setdefault(target, []).append(sup)
+    return result
+
+
+def load_baseline(path: str) -> dict[str, Suppression]:
+    """Load a baseline file as fingerprint -> Suppression. Missing file -> {}."""
+    if not path or not os.path.exists(path):
+        return {}
+    with open(path) as f:
+        data = json.load(f)
+    entries = {}
+    for entry in data.get("entries", []):
+        fp = entry.get("fingerprint")
+        if not fp:
+            continue
+        try:
+            expires = parse_expiry(entry.get("expires") or "")
+        except ValueError:
+            expires = date.min
+        entries[fp] = Suppression(
+            source="baseline",
+            rules=(entry.get("id", ""),),
+            fingerprint=fp,
+            expires=expires,
+            reason=entry.get("reason", ""),
+        )
+    return entries
+
+
+def baseline_ruleset(path: str) -> Optional[dict]:
+    """The {"version", "hash"} rule set a baseline was created under, None if unstamped or missing."""
+    if not path or not os.path.exists(path):
+        return None
+    with open(path) as f:
+        return json.load(f).get("ruleset")
+
+
+def migrate_baseline(path: str, ruleset) -> tuple[int, int]:
+    """Carry a baseline over to `ruleset`: drop entries of rules it lacks, re-stamp. Returns (kept, dropped)."""
+    with open(path) as f:
+        data = json.load(f)
+    entries = data.get("entries", [])
+    kept = [e for e in entries if e.get("id") in ruleset.rules]
+    data["ruleset"] = ruleset.to_dict()
+    data["entries"] = kept
+    with open(path, "w") as fh:
+        json.dump(data, fh, indent=2)
+        fh.write("\n")
+    return len(kept), len(entries) - len(kept)
+
+
+def write_baseline(findings: list, path: str, expires: Optional[date] = None,
+                   reason: str = "", ruleset=None) -> int:
+    """Write every finding to a baseline file, stamped with `ruleset` when given. Returns the entry count."""
+    entries = []
+    seen = set()
+    for f in findings:
+        # Findings folded into this one by deduplication are suppressed
+        # before they are folded on the next scan, so they need entries too
+        absorbed = [(s["fingerprint"], s["id"]) for s in f.supporting if "fingerprint" in s]
+        for fp, rule in [(f.fingerprint(), f.id)] + absorbed:
+            if fp in seen:
+                continue
+            seen.add(fp)
+            entry = {"fingerprint": fp, "id": rule, "file": f.file, "line": f.line}
+            if f.scope:
+                entry["scope"] = f.scope
+            if expires:
+                entry["expires"] = expires.isoformat()
+            if reason:
+                entry["reason"] = reason
+            entries.append(entry)
+    os.makedirs(os.path.dirname(path) or ".", exist_ok=True)
+    with open(path, "w") as fh:
+        data = {"version": 1}
+        if ruleset is not None:
+            data["ruleset"] = ruleset.to_dict()
+        data["entries"] = entries
+        json.dump(data, fh, indent=2)
+        fh.write("\n")
+    return len(entries)
+
+
+def apply_suppressions(pattern, findings: list, inline: dict, baseline: dict,
+                       today: date) -> tuple[list, list]:
+    """Split findings into (reported, suppressed).
+
+    Findings under an expired suppression stay reported and carry a
+    `suppression` marker with expired=True.
+    """
+    reported, suppressed = [], []
+    for finding in findings:
+        matching = [s for s in inline.get(finding.line, [])
+                    if any(pattern.matches(rule) for rule in s.rules)]
+        base = baseline.get(finding.fingerprint())
+        if base:
+            matching.append(base)
+        if not matching:
+            reported.append(finding)
+            continue
+        active = [s for s in matching if not s.is_expired(today)]
+        if active:
+            finding.suppression = active[0].marker(expired=False)
+            suppressed.append(finding)
+        else:
+            finding.suppression = matching[0].marker(expired=True)
+            reported.append(finding)
+    return reported, suppressed
@@ -0,0 +1,236 @@
+"""
+Triage store and audit-sheet interchange.
+
+Dispositions (confirmed, false positive, accepted risk, ...) are stored per
+finding fingerprint (rule, file, enclosing item and flagged line text; see
+Finding.fingerprint) in a small JSON file so they survive re-scans. Findings
+and their dispositions can be exported to CSV/JSON for audit tracking sheets,
+and edited sheets can be imported back into the store.
+"""
+
+import csv
+import io
+import json
+import os
+from dataclasses import dataclass, field
+from datetime import datetime, timezone
+from typing import Optional
+
+DEFAULT_STORE_PATH = os.path.join(".anchor-shield", "triage.json")
+
+STATUSES = (
+    "open", "confirmed", "false_positive", "accepted_risk", "fixed", "wont_fix",
+)
+
+# Column order for exported sheets
+EXPORT_COLUMNS = [
+    "fingerprint", "rule", "severity", "title", "file", "line",
+    "status", "owner", "note", "description",
+]
+
+# Header spellings commonly found in audit tracking sheets
+HEADER_ALIASES = {
+    "id": "fingerprint",
+    "finding_id": "fingerprint",
+    "notes": "note",
+    "comment": "note",
+    "comments": "note",
+    "disposition": "status",
+    "assignee": "owner",
+    "rule_id": "rule",
+    "name": "title",
+}
+
+# Free-text status values mapped onto the store's vocabulary
+STATUS_ALIASES = {
+    "fp": "false_positive",
+    "false positive": "false_positive",
+    "accepted": "accepted_risk",
+    "acknowledged": "accepted_risk",
+    "risk accepted": "accepted_risk",
+    "won't fix": "wont_fix",
+    "wont fix": "wont_fix",
+    "resolved": "fixed",
+    "new": "open",
+    "tp": "confirmed",
+    "true positive": "confirmed",
+}
+
+
+@dataclass
+class TriageEntry:
+    """Disposition recorded for one finding fingerprint."""
+
+    fingerprint: str
+    rule: str = ""
+    file: str = ""
+    line: int = 0
+    status: str = "open"
+    owner: str = ""
+    note: str = ""
+    updated_at: str = ""
+
+    def to_dict(self) -> dict:
+        return {
+            "rule": self.rule,
+            "file": self.file,
+            "line": self.line,
+            "status": self.status,
+            "owner": self.owner,
+            "note": self.note,
+            "updated_at": self.updated_at,
+        }
+
+
+@dataclass
+class TriageStore:
+    """JSON-backed map of finding fingerprint -> TriageEntry."""
+
+    path: str = DEFAULT_STORE_PATH
+    entries: dict = field(default_factory=dict)
+
+    @classmethod
+    def load(cls, path: str = DEFAULT_STORE_PATH) -> "TriageStore":
+        store = cls(path=path)
+        if os.path.isfile(path):
+            with open(path) as fh:
+                data = json.load(fh)
+            for fp, entry in data.get("entries", {}).items():
+                store.entries[fp] = TriageEntry(fingerprint=fp, **entry)
+        return store
+
+    def save(self) -> None:
+        directory = os.path.dirname(self.path)
+        if directory:
+            os.makedirs(directory, exist_ok=True)
+        data = {
+            "version": 1,
+            "entries": {fp: e.to_dict() for fp, e in sorted(self.entries.items())},
+        }
+        with open(self.path, "w") as fh:
+            json.dump(data, fh, indent=2)
+
+    def status_of(self, fingerprint: str) -> str:
+        entry = self.entries.get(fingerprint)
+        return entry.status if entry else "open"
+
+    def set(self, fingerprint: str, status: str, note: str = "", owner: str = "",
+            rule: str = "", file: str = "", line: int = 0) -> TriageEntry:
+        status = normalize_status(status)
+        entry = self.entries.get(fingerprint) or TriageEntry(fingerprint=fingerprint)
+        entry.status = status
+        entry.note = note or entry.note
+        entry.owner = owner or entry.owner
+        entry.rule = rule or entry.rule
+        entry.file = file or entry.file
+        entry.line = line or entry.line
+        entry.updated_at = datetime.now(timezone.utc).strftime("%Y-%m-%dT%H:%M:%SZ")
+        self.entries[fingerprint] = entry
+        return entry
+
+
+def normalize_status(status: str) -> str:
+    """Map a sheet's status text onto STATUSES; raises ValueError if unknown."""
+    value = (status or "open").strip().lower()
+    value = STATUS_ALIASES.get(value, value).replace(" ", "_").replace("-", "_")
+    if value not in STATUSES:
+        raise ValueError(f"Unknown triage status '{status}' (expected one of {', '.join(STATUSES)})")
+    return value
+
+
+def export_rows(findings: list, store: TriageStore) -> list[dict]:
+    """Build one sheet row per finding, merged with stored dispositions."""
+    rows = []
+    for f in findings:
+        fp = f.fingerprint()
+        entry = store.entries.get(fp)
+        rows.append({
+            "fingerprint": fp,
+            "rule": f.id,
+            "severity": f.severity,
+            "title": f.name,
+            "file": f.file,
+            "line": f.line,
+            "status": entry.status if entry else "open",
+            "owner": entry.owner if entry else "",
+            "note": entry.note if entry else "",
+            "description": f.description,
+        })
+    return rows
+
+
+def export_findings(findings: list, store: TriageStore, fmt: str = "csv") -> str:
+    """Serialize findings + triage state as CSV or JSON."""
+    rows = export_rows(findings, store)
+    if fmt == "json":
+        return json.dumps({"version": 1, "findings": rows}, indent=2)
+    buf = io.StringIO()
+    writer = csv.DictWriter(buf, fieldnames=EXPORT_COLUMNS)
+    writer.writeheader()
+    writer.writerows(rows)
+    return buf.getvalue()
+
+
+def parse_sheet(text: str, fmt: Optional[str] = None) -> list[dict]:
+    """Parse a CSV or JSON sheet into rows with canonical column names."""
+    stripped = text.lstrip()
+    if fmt == "json" or (fmt is None and stripped.startswith(("{", "["))):
+        data = json.loads(text)
+        raw_rows = data.get("findings", []) if isinstance(data, dict) else data
+    else:
+        raw_rows = list(csv.DictReader(io.StringIO(text)))
+
+    rows = []
+    for raw in raw_rows:
+        row = {}
+        for key, value in raw.items():
+            if key is None:
+                continue
+            canon = key.strip().lower().replace(" ", "_")
+            canon = HEADER_ALIASES.get(canon, canon)
+            row[canon] = value.strip() if isinstance(value, str) else value
+        if "location" in row and "file" not in row:
+            loc = str(row["location"])
+            file_part, _, line_part = loc.rpartition(":")
+            if line_part.isdigit():
+                row["file"], row["line"] = file_part, int(line_part)
+            else:
+                row["file"] = loc
+        rows.append(row)
+    return rows
+
+
+def import_dispositions(text: str, store: TriageStore, fmt: Optional[str] = None) -> dict:
+    """Apply dispositions from an edited sheet to the store.
+
+    Rows without a fingerprint are skipped. Returns counts of updated,
+    unchanged, and skipped rows plus any per-row errors.
+    """
+    result = {"updated": 0, "unchanged": 0, "skipped": 0, "errors": []}
+    for i, row in enumerate(parse_sheet(text, fmt), start=1):
+        fp = str(row.get("fingerprint") or "").strip()
+        if not fp:
+            result["skipped"] += 1
+            continue
+        try:
+            status = normalize_status(str(row.get("status") or "open"))
+        except ValueError as e:
+            result["errors"].append(f"row {i}: {e}")
+            continue
+        existing = store.entries.get(fp)
+        note = str(row.get("note") or "")
+        owner = str(row.get("owner") or "")
+        if existing and existing.status == status and existing.note == note and existing.owner == owner:
+            result["unchanged"] += 1
+            continue
+        if not existing and status == "open" and not note and not owner:
+            result["unchanged"] += 1
+            continue
+        line = row.get("line") or 0
+        store.set(
+            fp, status, note=note, owner=owner,
+            rule=str(row.get("rule") or ""), file=str(row.get("file") or ""),
+            line=int(line) if str(line).isdigit() else 0,
+        )
+        result["updated"] += 1
+    return result
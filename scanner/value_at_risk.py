@@ -0,0 +1,273 @@
+"""
+Value at risk from on-chain vault balances (`scan --rpc NETWORK`).
+
+Severity says how bad a bug is; it does not say what the bug guards. A
+Critical finding on an empty devnet vault and a High finding on a vault
+holding nine figures on mainnet rank very differently once the balances
+are known, so with --rpc each finding's vault accounts are looked up:
+
+    "value_at_risk": {"network": "mainnet-beta", "usd": 182000000.0, "empty": false,
+                      "accounts": [{"name": "vault", "address": "9xQe...",
+                                    "lamports": 2039280, "tokens": [{"mint": "EPjF...",
+                                    "amount": 182000000000000, "decimals": 6}], "usd": 182000000.0}],
+                      "unresolved": ["user_vault"]}
+
+A finding's vault accounts are the token accounts, and the accounts named
+like a vault (vault, treasury, escrow, reserve, pool, custody, fee, ...),
+of the instructions whose body or accounts struct holds the flagged line.
+Their addresses come from the source alone: `address = ...` constraints,
+`seeds = [...]` under the program's declare_id! whose seeds are literals,
+byte-string constants or the keys of other resolvable accounts, and
+associated token accounts of a resolvable mint and authority. Seeds taken
+from instruction input or per-user accounts cannot be resolved and are
+listed under `unresolved`. A resolved PDA that owns token accounts (a vault
+authority) counts what it owns.
+
+Holdings are valued with the prices given (--price MINT=USD, `SOL` for
+lamports and wrapped SOL); USDC and USDT count at 1.0 by default, other
+mints without a price are listed but not valued, and rent-exempt reserves
+are not value. Test tokens have no market value: on devnet and testnet
+every holding is worth 0. The report orders findings by risk_rank —
+severity weight plus a value tier — so value moves a finding about two
+severity levels at most.
+"""
+
+import os
+import re
+from typing import Optional
+
+from scanner.narrative import _instructions_at
+from scanner.patterns.facts import Facts
+from scanner.solana_client import ASSOCIATED_TOKEN_PROGRAM, TOKEN_PROGRAMS, b58decode, find_program_address
+
+SEVERITY_WEIGHT = {"Critical": 4, "High": 3, "Medium": 2, "Low": 1}
+STABLECOINS = {
+    "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v": 1.0,  # USDC
+    "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB": 1.0,  # USDT
+}
+WRAPPED_SOL = "So11111111111111111111111111111111111111112"
+TEST_NETWORKS = ("devnet", "testnet")
+VAULT_NAME_RE = re.compile(r"vault|treasury|escrow|reserve|pool|custody|collateral|fee|bank|authority", re.I)
+TOKEN_TYPE_RE = re.compile(r"\bTokenAccount\b")
+DECLARE_ID_RE = re.compile(r"\bdeclare_id!\s*\(\s*\"(\w+)\"\s*\)")
+PUBKEY_RE = re.compile(r"(?:pubkey!\s*\(\s*\"(\w{32,44})\"\s*\)|Pubkey::from_str\s*\(\s*\"(\w{32,44})\"\s*\))")
+BYTES_CONST_RE = re.compile(r"\bconst\s+(\w+)\s*:\s*&(?:'static\s+)?(?:\[u8\]|str)\s*=\s*b?\"([^\"]*)\"\s*;")
+PUBKEY_CONST_RE = re.compile(rf"\bconst\s+(\w+)\s*:\s*Pubkey\s*=\s*{PUBKEY_RE.pattern}\s*;")
+# Lamports a rent-exempt account keeps: (128-byte overhead + data) * 3480 lamports/byte-year * 2 years
+RENT_PER_BYTE = 3480 * 2
+RENT_OVERHEAD = 128
+
+
+def assess_value_at_risk(report, client, prices: Optional[dict] = None) -> int:
+    """Set `value_at_risk` on the report's findings and order them by risk_rank; returns
+    how many findings had at least one vault balance."""
+    base = report.target if os.path.isdir(report.target) else os.path.dirname(report.target)
+    prices = {**STABLECOINS, **(prices or {})}
+    if "SOL" in prices:
+        prices.setdefault(WRAPPED_SOL, prices["SOL"])
+    network = getattr(client, "network", "")
+    sources, balances, owned = {}, {}, {}
+    assessed = 0
+    for finding in report.findings:
+        path = os.path.join(base, finding.file)
+        if path not in sources:
+            sources[path] = _read(path)
+        content = sources[path]
+        vaults, unresolved = vault_accounts(content, finding.line) if content else ({}, [])
+        missing = [a for a in vaults.values() if a not in balances]
+        if missing:
+            found = client.get_multiple_accounts(missing)
+            for address in missing:
+                balances[address] = found.get(address)
+        accounts = []
+        empty = True
+        for name, address in vaults.items():
+            balance = balances.get(address)
+            if balance is None:
+                unresolved.append(name)
+                continue
+            tokens = [balance["token"]] if balance["token"] else []
+            if not tokens and balance["owner"] not in TOKEN_PROGRAMS:
+                if address not in owned:
+                    owned[address] = client.get_token_accounts_by_owner(address)
+                tokens = [{k: t[k] for k in ("mint", "amount", "decimals")} for t in owned[address]]
+            lamports = balance["lamports"] if not balance["token"] else 0
+            free = max(lamports - (RENT_OVERHEAD + balance["space"]) * RENT_PER_BYTE, 0)
+            empty = empty and not free and not any(t["amount"] for t in tokens)
+            accounts.append({
+                "name": name,
+                "address": address,
+                "lamports": balance["lamports"],
+                "tokens": tokens,
+                "usd": _usd(free, tokens, prices, network),
+            })
+        finding.value_at_risk = {
+            "network": network,
+            "usd": _total(accounts),
+            # Resolved vaults hold nothing beyond rent
+            "empty": bool(accounts) and empty,
+            "accounts": accounts,
+            "unresolved": sorted(set(unresolved)),
+        }
+        assessed += bool(accounts)
+    report.findings.sort(key=lambda f: -risk_rank(f))
+    return assessed
+
+
+def risk_rank(finding) -> int:
+    """Severity weight plus the value tier of the finding's vaults."""
+    return SEVERITY_WEIGHT.get(finding.severity, 0) + value_tier(finding.value_at_risk)
+
+
+def value_tier(value: Optional[dict]) -> int:
+    """-2 empty, -1 under $1k, 0 unknown or under $1M, +1 under $100M, +2 above."""
+    if not value or not value["accounts"]:
+        return 0
+    usd = value["usd"]
+    if value["empty"] or usd == 0:
+        return -2
+    if usd is None:
+        return 0
+    if usd < 1e3:
+        return -1
+    if usd < 1e6:
+        return 0
+    return 1 if usd < 1e8 else 2
+
+
+def vault_accounts(content: str, line: int) -> tuple[dict, list]:
+    """({account name: address}, [unresolved names]) of the vaults of the instructions at the line."""
+    facts = Facts(content)
+    program = DECLARE_ID_RE.search(content)
+    consts = {name: value.encode() for name, value in BYTES_CONST_RE.findall(content)}
+    keys = {m.group(1): m.group(2) or m.group(3) for m in PUBKEY_CONST_RE.finditer(content)}
+    vaults, unresolved = {}, []
+    for ix in _instructions_at(facts, content, line):
+        accounts = {a.name: a for a in facts.accounts_of(ix)}
+        resolver = _Resolver(accounts, program.group(1) if program else "", consts, keys)
+        for account in accounts.values():
+            if not (TOKEN_TYPE_RE.search(account.type) or VAULT_NAME_RE.search(account.name)):
+                continue
+            if account.is_signer or account.name in vaults:
+                continue
+            address = resolver.address(account.name)
+            if address:
+                vaults[account.name] = address
+            else:
+                unresolved.append(account.name)
+    return vaults, [n for n in unresolved if n not in vaults]
+
+
+class _Resolver:
+    """Addresses of an accounts struct's fields that follow from the source alone."""
+
+    def __init__(self, accounts: dict, program: str, consts: dict, keys: dict):
+        self.accounts = accounts
+        self.program = program
+        self.consts = consts
+        self.keys = keys
+        self.resolving = set()
+
+    def address(self, name: str) -> Optional[str]:
+        account = self.accounts.get(name)
+        if account is None or name in self.resolving:
+            return None
+        self.resolving.add(name)
+        try:
+            return self._address(account)
+        finally:
+            self.resolving.discard(name)
+
+    def _address(self, account) -> Optional[str]:
+        fixed = next((c.value for c in account.constraints if c.kind == "address"), None)
+        if fixed:
+            return self._pubkey(fixed)
+        mint = next((c.value for c in account.constraints if c.kind == "associated_token::mint"), None)
+        authority = next((c.value for c in account.constraints if c.kind == "associated_token::authority"), None)
+        if mint and authority:
+            mint_key, owner_key = self._key(mint), self._key(authority)
+            if mint_key and owner_key:
+                seeds = [b58decode(owner_key), b58decode(TOKEN_PROGRAMS[0]), b58decode(mint_key)]
+                return find_program_address(seeds, ASSOCIATED_TOKEN_PROGRAM)[0]
+            return None
+        pda = account.pda
+        if pda is None or pda.is_foreign or not self.program:
+            return None
+        seeds = [self._seed(s) for s in _split(pda.seeds.strip().strip("[]"))]
+        if not seeds or any(s is None for s in seeds):
+            return None
+        return find_program_address(seeds, self.program)[0]
+
+    def _pubkey(self, expr: str) -> Optional[str]:
+        m = PUBKEY_RE.search(expr)
+        if m:
+            return m.group(1) or m.group(2)
+        return self.keys.get(expr.strip().split("::")[-1])
+
+    def _key(self, expr: str) -> Optional[str]:
+        """Address of `other`, `other.key()` or a Pubkey constant."""
+        name = re.sub(r"\s*\.\s*key\s*\(\s*\)\s*$", "", expr.strip())
+        return self.address(name) if name in self.accounts else self._pubkey(expr)
+
+    def _seed(self, seed: str) -> Optional[bytes]:
+        seed = re.sub(r"(?:\s*\.\s*(?:as_ref|as_bytes|to_le_bytes|as_slice)\s*\(\s*\))+\s*$", "", seed.strip())
+        seed = seed.lstrip("&").strip()
+        literal = re.fullmatch(r"b?\"([^\"]*)\"", seed)
+        if literal:
+            return literal.group(1).encode()
+        name = seed.split("::")[-1]
+        if name in self.consts:
+            return self.consts[name]
+        key = re.fullmatch(r"(\w+)\s*\.\s*key\s*\(\s*\)", seed)
+        if key:
+            address = self.address(key.group(1))
+            return b58decode(address) if address else None
+        return None
+
+
+def _split(seeds: str) -> list:
+    """Top-level comma-separated items of a seeds list."""
+    items, depth, current = [], 0, ""
+    for char in seeds:
+        depth += {"(": 1, "[": 1, ")": -1, "]": -1}.get(char, 0)
+        if char == "," and depth == 0:
+            items.append(current)
+            current = ""
+        else:
+            current += char
+    if current.strip():
+        items.append(current)
+    return [i for i in items if i.strip()]
+
+
+def _usd(lamports: int, tokens: list, prices: dict, network: str) -> Optional[float]:
+    """Priced value of the holdings; None when some nonzero holding has no price."""
+    if network in TEST_NETWORKS:
+        return 0.0
+    total = 0.0
+    if lamports:
+        if "SOL" not in prices:
+            return None
+        total += lamports / 1e9 * prices["SOL"]
+    for token in tokens:
+        if not token["amount"]:
+            continue
+        if token["mint"] not in prices:
+            return None
+        total += token["amount"] / 10 ** token["decimals"] * prices[token["mint"]]
+    return total
+
+
+def _total(accounts: list) -> Optional[float]:
+    values = [a["usd"] for a in accounts]
+    if not values or any(v is None for v in values):
+        return None
+    return sum(values)
+
+
+def _read(path: str) -> str:
+    try:
+        with open(path, "r", encoding="utf-8", errors="ignore") as fh:
+            return fh.read()
+    except OSError:
+        return ""
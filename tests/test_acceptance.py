@@ -0,0 +1,86 @@
+"""Tests for acceptance tests generated from confirmed bankrun exploits."""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from agent.acceptance import workspace_tests_dir, write_acceptance_tests
+
+ROOT = os.path.dirname(os.path.dirname(os.path.abspath(__file__)))
+EXPLOITS = os.path.join(ROOT, "exploits")
+
+
+# Program IDs and binary names the checked-in exploits load
+ESCROW_ID = "9EXGN6eddRjL2EkS6dTqdGThykrGojQzsYTpyG5aJEYH"
+STAKING_BINARY = "skinflip_staking"
+
+
+def _workspace(tmp_path, name, program_id="11111111111111111111111111111111", binary=None):
+    """Anchor workspace with one program declaring `program_id` and, given `binary`, its .so."""
+    (tmp_path / "Anchor.toml").write_text("[programs.localnet]\n")
+    program = tmp_path / "programs" / name
+    (program / "src").mkdir(parents=True)
+    (program / "src" / "lib.rs").write_text(
+        f'use anchor_lang::prelude::*;\n\ndeclare_id!("{program_id}");\n'
+    )
+    if binary:
+        (program / f"{binary}.so").write_bytes(b"\x7fELF")
+    return str(program)
+
+
+def _result(name: str, status: str = "CONFIRMED") -> dict:
+    with open(os.path.join(EXPLOITS, f"{name}_execution.log")) as fh:
+        output = fh.read()
+    return {"file": os.path.join(EXPLOITS, f"{name}.ts"), "title": name, "status": status, "output": output}
+
+
+def test_confirmed_exploits_of_the_target_only(tmp_path):
+    target = _workspace(tmp_path, "escrow", ESCROW_ID)
+    results = [
+        _result("bankrun_exploit_escrow_001_cancel_without_signer"),
+        _result("bankrun_exploit_multisig_001_zero_threshold"),
+        _result("bankrun_exploit_tictactoe_001_inverted_constraint", status="FAILED"),
+    ]
+    tests = write_acceptance_tests(results, target, workspace_tests_dir(target))
+    assert [os.path.relpath(t.path, tmp_path) for t in tests] == [
+        os.path.join("tests", "acceptance", "escrow_001_cancel_without_signer.ts")
+    ]
+    test = tests[0]
+    assert test.asserts == "no_longer_fails"
+    assert test.errors == ["instruction requires an initialized account"]
+
+    generated = open(test.path).read()
+    assert "BanksClient.prototype.processTransaction" in generated
+    assert '["instruction requires an initialized account"]' in generated
+    assert "async function main()" in generated
+    assert "main().catch" not in generated
+    assert "await main();" in generated
+    assert "later runs keep this file as it is" in generated
+
+
+def test_exploit_that_succeeded_must_now_be_rejected(tmp_path):
+    target = _workspace(tmp_path, "staking", binary=STAKING_BINARY)
+    tests = write_acceptance_tests([_result("bankrun_exploit_staking_002_missing_signer")],
+                                   target, workspace_tests_dir(target))
+    assert [t.asserts for t in tests] == ["rejected"]
+    generated = open(tests[0].path).read()
+    assert 'it("exploit transaction is rejected"' in generated
+    assert "exploit transaction still succeeds" in generated
+
+
+def test_no_workspace_outside_anchor_toml(tmp_path):
+    program = _workspace(tmp_path, "escrow", ESCROW_ID)
+    os.remove(tmp_path / "Anchor.toml")
+    assert workspace_tests_dir(program) is None
+
+
+def test_existing_test_is_not_overwritten(tmp_path):
+    target = _workspace(tmp_path, "escrow", ESCROW_ID)
+    existing = tmp_path / "tests" / "acceptance" / "escrow_001_cancel_without_signer.ts"
+    existing.parent.mkdir(parents=True)
+    existing.write_text("// hand-edited\n")
+    tests = write_acceptance_tests([_result("bankrun_exploit_escrow_001_cancel_without_signer")],
+                                   target, workspace_tests_dir(target))
+    assert [t.written for t in tests] == [False]
+    assert existing.read_text() == "// hand-edited\n"
@@ -0,0 +1,79 @@
+"""Tests for account wrapper normalization."""
+
+import collections
+import glob
+import os
+import re
+import sys
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.engine import AnchorShieldEngine
+from scanner.patterns.account_types import AccountType, account_type, field_type
+from scanner.patterns.facts import Facts
+
+ROOT = os.path.dirname(os.path.dirname(os.path.abspath(__file__)))
+# One-line account fields of the corpus, wrapped in Box<..> by the corpus test
+FIELD_RE = re.compile(
+    r"(^\s*(?:pub\s+)?\w+\s*:\s*)"
+    r"((?:Interface)?Account\s*<\s*'info\s*,\s*[\w:]+\s*>|AccountLoader\s*<\s*'info\s*,\s*\w+\s*>|"
+    r"(?:Signer|UncheckedAccount|AccountInfo|SystemAccount)\s*<\s*'info\s*>|"
+    r"(?:Program|Sysvar)\s*<\s*'info\s*,\s*\w+\s*>)(?=\s*,)",
+    re.M,
+)
+
+
+class TestAccountType:
+    def test_wrappers(self):
+        assert account_type("Account<'info, Vault>") == AccountType("Account", "Vault")
+        boxed = account_type("Box<Account<'info, state::Vault>>")
+        assert (boxed.wrapper, boxed.inner, boxed.boxed, boxed.is_typed) == ("Account", "Vault", True, True)
+        loader = account_type("AccountLoader<'info, Pool>")
+        assert (loader.wrapper, loader.inner, loader.is_typed) == ("AccountLoader", "Pool", True)
+        mint = account_type("Box<InterfaceAccount<'info, Mint>>")
+        assert (mint.wrapper, mint.inner) == ("InterfaceAccount", "Mint")
+        optional = account_type("Option<Box<UncheckedAccount<'info>>>")
+        assert (optional.wrapper, optional.boxed, optional.optional, optional.is_raw) == (
+            "UncheckedAccount", True, True, True)
+        assert account_type("Program<'info, System>").is_program
+        assert account_type("Signer<'info>").is_signer
+
+    def test_field_line(self):
+        assert field_type("    pub source: Box<AccountInfo<'info>>, // raw") == (
+            "source", AccountType("AccountInfo", boxed=True))
+        assert field_type("let x = 5;") is None
+
+    def test_facts_inner_type(self):
+        facts = Facts(
+            "#[derive(Accounts)]\n"
+            "pub struct Swap<'info> {\n"
+            "    #[account(mut)]\n"
+            "    pub pool: AccountLoader<'info, Pool>,\n"
+            "    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,\n"
+            "    pub data: Box<AccountInfo<'info>>,\n"
+            "}\n"
+        )
+        assert facts.account("Swap", "pool").inner_type == "Pool"
+        assert facts.account("Swap", "vault").inner_type == "TokenAccount"
+        assert facts.account("Swap", "data").inner_type == ""
+        assert facts.account("Swap", "data").wrapper == "AccountInfo"
+
+
+class TestBoxedCorpus:
+    def test_boxing_fields_keeps_findings(self):
+        """Boxing every account field of the real-world targets leaves each rule's findings unchanged."""
+        engine = AnchorShieldEngine()
+        changed = []
+        files = sorted(glob.glob(os.path.join(ROOT, "real-world-targets", "**", "*.rs"), recursive=True))
+        for path in files:
+            with open(path, encoding="utf-8", errors="ignore") as fh:
+                content = fh.read()
+            boxed = FIELD_RE.sub(lambda m: f"{m.group(1)}Box<{m.group(2)}>", content)
+            if boxed == content:
+                continue
+            before = collections.Counter((f.id, f.line) for f in engine.scan_source(path, content))
+            after = collections.Counter((f.id, f.line) for f in engine.scan_source(path, boxed))
+            if before != after:
+                changed.append((os.path.relpath(path, ROOT), sorted((before - after) + (after - before))))
+        assert files
+        assert changed == []
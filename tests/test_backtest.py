@@ -0,0 +1,66 @@
+"""Tests for backtesting rules against exploited snapshots."""
+
+import os
+import shutil
+import subprocess
+import sys
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.backtest import DEFAULT_CASES, BacktestCase, load_cases, run_backtests, run_case
+from scanner.bench import render_markdown, run_bench
+
+TEST_PATTERNS = os.path.join(os.path.dirname(__file__), "test_patterns")
+VULNERABLE = os.path.join(TEST_PATTERNS, "vulnerable", "raw_account_info_no_owner.rs")
+
+
+def _git(repo, *args):
+    subprocess.run(
+        ["git", "-C", str(repo), "-c", "user.name=t", "-c", "user.email=t@example.com", *args],
+        check=True, capture_output=True,
+    )
+
+
+class TestBacktest:
+    def test_bundled_cases(self):
+        report = run_backtests(load_cases(DEFAULT_CASES))
+        caught = {r.case.name for r in report.results if r.caught}
+        assert "sealevel-2-owner-checks" in caught
+        assert all(r.error is None for r in report.results)
+        assert "sealevel-6-duplicate-mutable-accounts" in caught
+        # Known gaps stay visible in the metric rather than being dropped
+        assert "sealevel-9-closing-accounts" not in caught
+        assert report.coverage == len(caught) / len(report.results)
+
+    def test_checks_out_pre_exploit_commit(self, tmp_path):
+        repo = tmp_path / "protocol"
+        repo.mkdir()
+        shutil.copy(VULNERABLE, repo / "lib.rs")
+        _git(repo, "init", "-q")
+        _git(repo, "add", "-A")
+        _git(repo, "commit", "-qm", "vulnerable")
+        (repo / "lib.rs").write_text("// fixed\n")
+        _git(repo, "commit", "-qam", "fix")
+
+        case = BacktestCase.from_dict({
+            "name": "owner", "target": str(repo), "commit": "HEAD~1",
+            "category": "accounts", "root_cause": {"file": "lib.rs", "lines": [24, 24]},
+        })
+        result = run_case(case)
+        assert result.caught
+        assert {m["id"] for m in result.matched} == {"ANCHOR-004", "ANCHOR-006"}
+
+        case.commit = None
+        assert not run_case(case).caught
+
+    def test_unknown_category_is_an_error(self):
+        result = run_case(BacktestCase(name="x", target=TEST_PATTERNS, category=["shield::nope::*"]))
+        assert result.error and not result.caught
+        assert run_backtests([BacktestCase(name="x", target=TEST_PATTERNS, category=["nope"])]).coverage is None
+
+    def test_bench_publishes_coverage(self):
+        cases = [BacktestCase(name="fixtures", target=TEST_PATTERNS, category=["ANCHOR-003"])]
+        report = run_bench([TEST_PATTERNS], cases)
+        assert report.history.coverage == 1.0
+        assert "## Coverage of History" in render_markdown(report)
+        assert report.to_dict()["history"]["caught"] == 1
@@ -0,0 +1,69 @@
+"""Tests for the corpus-wide rule precision benchmark."""
+
+import json
+import os
+import sys
+import tempfile
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.bench import (
+    load_annotations, load_expectations, run_bench, render_markdown, write_bench_report,
+)
+
+TEST_PATTERNS = os.path.join(os.path.dirname(__file__), "test_patterns")
+
+
+class TestLabels:
+    def test_expectations_are_per_finding(self):
+        expectations = load_expectations()
+        assert expectations[("tests/test_patterns/vulnerable/realloc_no_signer.rs", "ANCHOR-003", 20)] == "tp"
+        # A finding in a vulnerable fixture is not a true positive by location alone
+        assert expectations[("tests/test_patterns/vulnerable/signed_time_cast.rs", "ANCHOR-018", 20)] == "fp"
+        assert not any(f.startswith("real-world-targets/anchor-escrow/") for f, _, _ in expectations)
+
+    def test_true_positives_in_secure_fixtures_say_why(self):
+        with open(os.path.join(os.path.dirname(__file__), "bench_expectations.json")) as fh:
+            entries = json.load(fh)
+        secure = [e for e in entries if os.path.basename(e["file"]).startswith("secure")]
+        assert secure
+        assert [e for e in secure if e["label"] == "tp" and not e.get("reason")] == []
+
+    def test_annotations_override(self):
+        tmp = tempfile.mkdtemp()
+        with open(os.path.join(tmp, "annotations.json"), "w") as fh:
+            json.dump([{"file": "lib.rs", "rule": "ANCHOR-006", "line": 12, "label": "FP"}], fh)
+        assert load_annotations(tmp) == {("lib.rs", "ANCHOR-006", 12): "fp"}
+
+
+class TestBenchReport:
+    def test_precision_over_test_patterns(self):
+        report = run_bench([TEST_PATTERNS])
+        assert len(report.targets) == 2
+        realloc = report.rules["ANCHOR-003"]
+        assert realloc.tp >= 1 and realloc.fp == 0
+        assert realloc.precision == 1.0
+        assert report.rules["ANCHOR-018"].fp >= 1
+
+    def test_unlisted_findings_are_unlabeled_and_missing_ones_missed(self):
+        listed = ("tests/test_patterns/vulnerable/realloc_no_signer.rs", "ANCHOR-003", 20)
+        gone = ("tests/test_patterns/vulnerable/realloc_no_signer.rs", "ANCHOR-003", 1)
+        report = run_bench([TEST_PATTERNS], expectations={listed: "tp", gone: "tp"})
+        realloc = report.rules["ANCHOR-003"]
+        assert (realloc.tp, realloc.fp, realloc.missed) == (1, 0, 1)
+        unchecked = report.rules["ANCHOR-046"]
+        assert unchecked.tp == 0 and unchecked.unlabeled >= 1
+
+    def test_output_is_reproducible(self):
+        first = render_markdown(run_bench([TEST_PATTERNS]))
+        assert first == render_markdown(run_bench([TEST_PATTERNS]))
+        assert "Generated" not in first
+
+    def test_writes_dashboard(self):
+        report = run_bench([TEST_PATTERNS])
+        out = tempfile.mkdtemp()
+        paths = write_bench_report(report, out)
+        assert sorted(os.path.basename(p) for p in paths) == [
+            "precision.html", "precision.json", "precision.md",
+        ]
+        assert "| ANCHOR-003 |" in render_markdown(report)
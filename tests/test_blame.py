@@ -0,0 +1,82 @@
+"""Tests for attributing findings to commits with git blame."""
+
+import json
+import os
+import shutil
+import subprocess
+import sys
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.blame import attribute_findings
+from scanner.engine import AnchorShieldEngine
+from scanner.report import format_html_report, format_terminal_report
+
+TEST_DIR = os.path.join(os.path.dirname(__file__), "test_patterns", "vulnerable")
+FIXTURE = "vault_creation_unpinned.rs"
+
+
+def _git(repo, *args, author=("Ana Ruiz", "ana@example.com")):
+    env = dict(os.environ, GIT_AUTHOR_NAME=author[0], GIT_AUTHOR_EMAIL=author[1],
+               GIT_AUTHOR_DATE="2024-05-02T10:00:00+02:00",
+               GIT_COMMITTER_NAME=author[0], GIT_COMMITTER_EMAIL=author[1])
+    subprocess.run(["git", "-C", str(repo), *args], check=True, capture_output=True, env=env)
+
+
+def _repo(tmp_path):
+    """Fixture committed by Ana, then line 23 rewritten by Bo."""
+    src = tmp_path / "programs" / "amm" / "src"
+    src.mkdir(parents=True)
+    shutil.copy(os.path.join(TEST_DIR, FIXTURE), src / "lib.rs")
+    _git(tmp_path, "init", "-q")
+    _git(tmp_path, "add", "-A")
+    _git(tmp_path, "commit", "-q", "-m", "Add amm program")
+    lines = (src / "lib.rs").read_text().split("\n")
+    lines[22] += "  "
+    (src / "lib.rs").write_text("\n".join(lines))
+    _git(tmp_path, "commit", "-q", "-am", "Tidy vault accounts", author=("Bo Chen", "bo@example.com"))
+    return tmp_path
+
+
+class TestBlame:
+    def test_findings_attributed_to_last_commit(self, tmp_path):
+        repo = _repo(tmp_path)
+        report = AnchorShieldEngine(only=["ANCHOR-017"]).scan_directory(str(repo))
+        assert attribute_findings(report) == 2
+        by_line = {f.line: f.blame for f in report.findings}
+        assert by_line[11]["author"] == "Ana Ruiz" and by_line[11]["summary"] == "Add amm program"
+        assert by_line[11]["date"] == "2024-05-02" and len(by_line[11]["commit"]) == 40
+        assert by_line[23]["author"] == "Bo Chen" and by_line[23]["email"] == "bo@example.com"
+        assert report.owners == [
+            {"author": "Ana Ruiz", "email": "ana@example.com", "findings": 1, "by_severity": {"Medium": 1}},
+            {"author": "Bo Chen", "email": "bo@example.com", "findings": 1, "by_severity": {"High": 1}},
+        ]
+        data = json.loads(report.to_json())
+        assert data["findings"][0]["blame"]["author"] == "Ana Ruiz" and len(data["owners"]) == 2
+
+    def test_reports_show_blame(self, tmp_path):
+        repo = _repo(tmp_path)
+        report = AnchorShieldEngine(only=["ANCHOR-017"]).scan_directory(str(repo))
+        attribute_findings(report)
+        commit = report.findings[1].blame["commit"][:10]
+        text = format_terminal_report(report)
+        assert f"Last changed: {commit} by Bo Chen (2024-05-02)" in text
+        assert "Bo Chen <bo@example.com>: 1 finding(s) (High: 1)" in text
+        assert "Bo Chen &lt;bo@example.com&gt;: 1 finding(s)" in format_html_report(report)
+
+    def test_uncommitted_and_untracked(self, tmp_path):
+        repo = _repo(tmp_path)
+        lib = repo / "programs" / "amm" / "src" / "lib.rs"
+        lib.write_text(lib.read_text().replace("create_token_vault_account(", "create_token_vault_account( ")
+                     .replace("InitializeAccount3 {", "InitializeAccount3  {"))
+        report = AnchorShieldEngine(only=["ANCHOR-017"]).scan_file(str(lib))
+        attribute_findings(report)
+        assert report.findings and all(f.blame == {"commit": "", "uncommitted": True} for f in report.findings)
+        assert report.owners == []
+
+        # Outside a git work tree the findings are left unattributed
+        outside = tmp_path.parent / (tmp_path.name + "-plain")
+        outside.mkdir()
+        shutil.copy(os.path.join(TEST_DIR, FIXTURE), outside / "lib.rs")
+        report = AnchorShieldEngine(only=["ANCHOR-017"]).scan_directory(str(outside))
+        assert attribute_findings(report) == 0 and all(f.blame is None for f in report.findings)
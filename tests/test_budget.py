@@ -0,0 +1,102 @@
+"""Tests for scan-time resource budgets and degraded mode."""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.budget import ScanBudget
+from scanner.engine import AnchorShieldEngine
+from scanner.reachability import ReachabilityAnalyzer
+from scanner.report import format_terminal_report
+
+TEST_DIR = os.path.join(os.path.dirname(__file__), "test_patterns", "vulnerable")
+
+
+class FakeClock:
+    def __init__(self):
+        self.now = 0.0
+
+    def __call__(self):
+        return self.now
+
+
+def _read(name):
+    with open(os.path.join(TEST_DIR, name)) as fh:
+        return fh.read()
+
+
+class TestScanBudget:
+    def test_spent_budget_stays_spent(self):
+        clock = FakeClock()
+        budget = ScanBudget(time_limit=10, clock=clock)
+        budget.start()
+        clock.now = 5
+        assert budget.exhausted() is None
+        clock.now = 11
+        assert "time budget 10s exceeded" in budget.exhausted()
+        assert budget.exhausted() is not None
+
+    def test_memory_limit(self):
+        budget = ScanBudget(memory_limit=512, memory=lambda: 900.0)
+        assert "memory budget 512 MiB exceeded (900 MiB)" == budget.exhausted()
+
+    def test_degraded_scan_skips_expensive_patterns_and_finishes(self):
+        budget = ScanBudget(memory_limit=1, memory=lambda: 2.0)
+        engine = AnchorShieldEngine(budget=budget)
+        content = _read("lamport_token_unit_mix.rs")
+
+        full = AnchorShieldEngine().scan_content(content, "lib.rs")
+        assert any(f.id == "ANCHOR-012" for f in full.findings)
+
+        report = engine.scan_content(content, "lib.rs")
+        assert not any(f.id == "ANCHOR-012" for f in report.findings)
+        assert report.degraded == [{
+            "file": "lib.rs",
+            "skipped": ["ANCHOR-007", "ANCHOR-012", "ANCHOR-013", "ANCHOR-047", "ANCHOR-056", "ANCHOR-058",
+                        "ANCHOR-062", "ANCHOR-063"],
+            "reason": "memory budget 1 MiB exceeded (2 MiB)",
+        }]
+        assert report.to_dict()["degraded"] == report.degraded
+        assert "Incomplete scan" in format_terminal_report(report)
+
+    def test_within_budget_records_nothing(self):
+        report = AnchorShieldEngine(budget=ScanBudget(time_limit=3600)).scan_content(
+            _read("lamport_token_unit_mix.rs"), "lib.rs",
+        )
+        assert report.degraded == []
+
+    def test_reachability_depth_capped(self):
+        content = '''
+#[program]
+pub mod deep {
+    use super::*;
+    pub fn entry(ctx: Context<Run>) -> Result<()> { a(&ctx) }
+}
+fn a(ctx: &Context<Run>) -> Result<()> { b(ctx) }
+fn b(ctx: &Context<Run>) -> Result<()> { c(ctx) }
+fn c(ctx: &Context<Run>) -> Result<()> {
+    ctx.accounts.config.authority = Pubkey::default();
+    Ok(())
+}
+#[derive(Accounts)]
+pub struct Run<'info> {
+    #[account(mut)]
+    pub config: Account<'info, Config>,
+}
+'''
+        full = ReachabilityAnalyzer([("lib.rs", content)]).analyze()
+        assert [e.path for e in full.sinks[0].entrypoints] == [["entry", "a", "b", "c"]]
+
+        budget = ScanBudget(time_limit=0, clock=FakeClock())
+        budget.start()
+        budget.clock.now = 1
+        report = ReachabilityAnalyzer([("lib.rs", content)]).analyze(budget=budget)
+        assert report.sinks[0].entrypoints == []
+        assert report.degraded[0]["skipped"] == ["interprocedural depth > 2"]
+
+        # A configured depth below the degraded one is not a degradation
+        report = ReachabilityAnalyzer([("lib.rs", content)]).analyze(max_depth=2)
+        assert report.sinks[0].entrypoints == [] and report.degraded == []
+        report = ReachabilityAnalyzer([("lib.rs", content)]).analyze(max_depth=3)
+        assert [e.path for e in report.sinks[0].entrypoints] == [["entry", "a", "b", "c"]]
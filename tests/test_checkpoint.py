@@ -0,0 +1,117 @@
+"""Tests for scan checkpoints and resuming interrupted scans."""
+
+import json
+import os
+import shutil
+import sys
+
+import pytest
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.budget import ScanBudget
+from scanner.engine import AnchorShieldEngine
+
+TEST_DIR = os.path.join(os.path.dirname(__file__), "test_patterns", "vulnerable")
+FILES = ["pda_seed_collision.rs", "reward_group_loop.rs", "authority_init_unprotected.rs"]
+
+
+def _target(tmp_path):
+    target = tmp_path / "target_src"
+    target.mkdir()
+    for name in FILES:
+        shutil.copy(os.path.join(TEST_DIR, name), target / name)
+    return target
+
+
+def _counting(engine):
+    """Count the units the engine actually scans."""
+    scanned = []
+    scan_source = engine.scan_source
+
+    def counted(path, content, suppressed=None):
+        scanned.append(path)
+        return scan_source(path, content, suppressed)
+
+    engine.scan_source = counted
+    return scanned
+
+
+class TestCheckpoint:
+    def test_resumed_scan_reuses_unchanged_units(self, tmp_path):
+        target = _target(tmp_path)
+        checkpoint = str(tmp_path / "scan.checkpoint.json")
+        first = AnchorShieldEngine(checkpoint=checkpoint).scan_directory(str(target))
+
+        engine = AnchorShieldEngine(checkpoint=checkpoint)
+        scanned = _counting(engine)
+        second = engine.scan_directory(str(target))
+        assert scanned == []
+        assert engine.checkpoint.resumed == len(FILES)
+        assert [f.to_dict() for f in second.findings] == [f.to_dict() for f in first.findings]
+        assert second.summary == first.summary
+        # Structured details survive the round trip
+        assert any(f["details"] for f in json.loads(second.to_json())["findings"])
+
+    def test_changed_unit_is_rescanned(self, tmp_path):
+        target = _target(tmp_path)
+        checkpoint = str(tmp_path / "scan.checkpoint.json")
+        AnchorShieldEngine(checkpoint=checkpoint).scan_directory(str(target))
+        with open(target / "reward_group_loop.rs", "a") as fh:
+            fh.write("\n// touched\n")
+
+        engine = AnchorShieldEngine(checkpoint=checkpoint)
+        scanned = _counting(engine)
+        engine.scan_directory(str(target))
+        assert scanned == ["reward_group_loop.rs"]
+        assert engine.checkpoint.resumed == len(FILES) - 1
+
+    def test_other_configuration_ignores_checkpoint(self, tmp_path):
+        """Results recorded with different rules are not mixed into this scan."""
+        target = _target(tmp_path)
+        checkpoint = str(tmp_path / "scan.checkpoint.json")
+        AnchorShieldEngine(checkpoint=checkpoint).scan_directory(str(target))
+
+        engine = AnchorShieldEngine(checkpoint=checkpoint, skip=["ANCHOR-031"])
+        scanned = _counting(engine)
+        report = engine.scan_directory(str(target))
+        assert sorted(scanned) == sorted(FILES)
+        assert not any(f.id == "ANCHOR-031" for f in report.findings)
+
+    def test_interrupted_scan_resumes_after_last_completed_unit(self, tmp_path):
+        target = _target(tmp_path)
+        checkpoint = str(tmp_path / "scan.checkpoint.json")
+        engine = AnchorShieldEngine(checkpoint=checkpoint)
+        scan_source = engine.scan_source
+        done = []
+
+        def interrupted(path, content, suppressed=None):
+            if done:
+                raise KeyboardInterrupt
+            done.append(path)
+            return scan_source(path, content, suppressed)
+
+        engine.scan_source = interrupted
+        with pytest.raises(KeyboardInterrupt):
+            engine.scan_directory(str(target))
+
+        resumed = AnchorShieldEngine(checkpoint=checkpoint)
+        scanned = _counting(resumed)
+        report = resumed.scan_directory(str(target))
+        assert resumed.checkpoint.resumed == 1
+        assert sorted(scanned + done) == sorted(FILES)
+        full = AnchorShieldEngine().scan_directory(str(target))
+        assert [f.to_dict() for f in report.findings] == [f.to_dict() for f in full.findings]
+
+    def test_degraded_units_are_not_recorded(self, tmp_path):
+        """Units scanned after the budget ran out are scanned in full on resume."""
+        target = _target(tmp_path)
+        checkpoint = str(tmp_path / "scan.checkpoint.json")
+        spent = ScanBudget(time_limit=0, clock=lambda: 1.0)
+        spent.reason = "time budget 0s exceeded"
+        AnchorShieldEngine(checkpoint=checkpoint, budget=spent).scan_directory(str(target))
+
+        engine = AnchorShieldEngine(checkpoint=checkpoint)
+        scanned = _counting(engine)
+        engine.scan_directory(str(target))
+        assert sorted(scanned) == sorted(FILES)
@@ -0,0 +1,47 @@
+"""Tests for splitting concatenated multi-file sources."""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.concat import is_concatenated, split_concatenated
+from scanner.engine import AnchorShieldEngine
+
+NFT_STAKING = os.path.join(
+    os.path.dirname(os.path.dirname(os.path.abspath(__file__))),
+    "real-world-targets", "nft-staking-unaudited",
+)
+
+
+class TestSplit:
+    def test_marker_styles(self):
+        content = (
+            "// ========== lib.rs ==========\n"
+            "use anchor_lang::prelude::*;\n"
+            "// ═══ FILE: state/mod.rs ═══\n"
+            "pub mod pool;\n"
+            "// ===== FILE: utils/math.rs =====\n"
+            "pub fn f() {}\n"
+        )
+        parts = split_concatenated(content, "target/lib.rs")
+        assert [p.path for p in parts] == ["target/lib.rs", "target/state/mod.rs", "target/utils/math.rs"]
+        assert [p.start_line for p in parts] == [2, 4, 6]
+        assert parts[2].content == "pub fn f() {}\n"
+
+    def test_single_marker_is_not_concatenation(self):
+        content = "// ===== FILE: lib.rs =====\nuse anchor_lang::prelude::*;\n"
+        assert not is_concatenated(content)
+        assert split_concatenated(content, "lib.rs") == []
+
+
+class TestEngineSplit:
+    def test_findings_use_original_paths(self):
+        whole = AnchorShieldEngine().scan_directory(NFT_STAKING)
+        split = AnchorShieldEngine(split_concatenated=True).scan_directory(NFT_STAKING)
+        assert whole.files_scanned == 1
+        assert split.files_scanned > 10
+        assert len(split.findings) == len(whole.findings)
+        assert {f.file for f in whole.findings} == {"lib.rs"}
+        assert all(f.file != "lib.rs" for f in split.findings)
+        assert any(f.file.startswith("instructions/") for f in split.findings)
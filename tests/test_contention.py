@@ -0,0 +1,92 @@
+"""Tests for the write-lock contention report."""
+
+import json
+import os
+import sys
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.contention import analyze_sources, analyze_target, render_markdown
+
+LENDING = os.path.join(os.path.dirname(os.path.dirname(os.path.abspath(__file__))),
+                       "examples", "vulnerable-lending")
+
+SOURCE = '''
+#[program]
+pub mod market {
+    use super::*;
+    pub fn place(ctx: Context<Place>, size: u64) -> Result<()> { Ok(()) }
+    pub fn cancel(ctx: Context<Cancel>) -> Result<()> { Ok(()) }
+    pub fn settle(ctx: Context<Settle>) -> Result<()> { Ok(()) }
+    pub fn set_fee(ctx: Context<SetFee>, fee: u16) -> Result<()> { Ok(()) }
+}
+
+#[derive(Accounts)]
+pub struct Place<'info> {
+    #[account(mut, seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [b"order", owner.key().as_ref()], bump)]
+    pub order_slot: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub open_orders: Account<'info, OpenOrders>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Cancel<'info> {
+    #[account(mut, seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub open_orders: Account<'info, OpenOrders>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Settle<'info> {
+    #[account(mut)]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub user_token: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetFee<'info> {
+    #[account(has_one = admin)]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[account]
+pub struct Config { pub admin: Pubkey, pub fee: u16 }
+
+#[account]
+pub struct OpenOrders { pub owner: Pubkey, pub size: u64 }
+'''
+
+
+class TestContention:
+    def test_matrix_marks_global_hot_account(self):
+        report = analyze_sources([("programs/market/src/lib.rs", SOURCE)])
+        assert report.programs == {"market": ["place", "cancel", "settle", "set_fee"]}
+        [config] = report.accounts
+        assert (config.account, config.scope) == ("Config", "global")
+        assert config.writers == ["place", "cancel", "settle"]
+        assert config.readers == ["set_fee"]
+        assert config.hot
+
+    def test_per_user_and_unidentified_accounts_are_left_out(self):
+        """Signer-seeded PDAs, owner-keyed positions, token accounts and programs do not contend."""
+        report = analyze_sources([("lib.rs", SOURCE)])
+        assert [a.account for a in report.accounts] == ["Config"]
+
+    def test_lending_pool_and_vault(self):
+        report = analyze_target(LENDING)
+        rows = {a.account: (a.scope, a.writers, a.readers, a.hot) for a in report.accounts}
+        assert rows == {
+            "Pool": ("per instance", ["initialize", "deposit", "borrow", "withdraw"], ["liquidate"], True),
+            'vault [b"vault",pool]': ("per pool", ["initialize", "deposit", "borrow", "withdraw"], [], True),
+        }
+        markdown = render_markdown(report)
+        assert "| `Pool` | per instance | W | W | W | W | r | 4/5 **hot** |" in markdown
+        assert json.loads(report.to_json())["hot"] == ["Pool", 'vault [b"vault",pool]']
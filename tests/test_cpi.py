@@ -0,0 +1,68 @@
+"""Tests for the CPI front-end (declare_program!, #[interface], SPL helpers)."""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.cpi import declared_programs, find_cpi_calls, interface_modules
+from scanner.reachability import ReachabilityAnalyzer
+
+LIB = '''
+declare_program!(amm);
+
+#[interface]
+pub trait RealizeLock<'info, T: Accounts<'info>> {
+    fn is_realized(ctx: Context<T>, v: Vesting) -> Result<()>;
+}
+'''
+
+BODY = '''
+    let cpi_ctx = CpiContext::new_with_signer(program, amm::cpi::accounts::Swap { pool }, seeds);
+    amm::cpi::swap(cpi_ctx, amount_in, min_out)?;
+    realize_lock::is_realized(CpiContext::new(p, accs), vesting)?;
+    token::transfer(ctx.accounts.transfer_ctx(), amount)?;
+    invoke(&spl_token::instruction::transfer(a, b, c, d, &[], amount)?, &infos)?;
+    let ix = Instruction { program_id: *ctx.accounts.target.key, accounts, data };
+    invoke_signed(&ix, &infos, signer)?;
+'''
+
+
+class TestCpiFrontEnd:
+    def test_recognizes_generated_clients(self):
+        declared = declared_programs(LIB)
+        interfaces = interface_modules(LIB)
+        assert declared == {"amm"}
+        assert interfaces == {"realize_lock": "RealizeLock"}
+
+        calls = [c.to_dict() for c in find_cpi_calls(BODY, declared, interfaces)]
+        assert calls[0] == {"program": "amm", "instruction": "swap", "via": "declare_program",
+                            "signed": True, "resolved": True}
+        assert calls[1]["via"] == "interface" and calls[1]["instruction"] == "is_realized"
+        assert not calls[1]["resolved"]
+        assert (calls[2]["program"], calls[2]["via"]) == ("spl_token", "spl-helper")
+        assert (calls[3]["program"], calls[3]["instruction"], calls[3]["resolved"]) == (
+            "spl_token", "transfer", True)
+        assert (calls[4]["program"], calls[4]["resolved"], calls[4]["signed"]) == (
+            "<unresolved>", False, True)
+        assert len(calls) == 5
+
+    def test_client_calls_are_not_local_calls(self):
+        """amm::cpi::swap must not resolve to a local fn named swap."""
+        program = LIB + '''
+#[program]
+pub mod router {
+    use super::*;
+    pub fn route(ctx: Context<Route>, amount: u64) -> Result<()> {
+        amm::cpi::swap(ctx.accounts.swap_ctx(), amount)
+    }
+    pub fn swap(ctx: Context<Route>, amount: u64) -> Result<()> {
+        ctx.accounts.pool.authority = ctx.accounts.user.key();
+        Ok(())
+    }
+}
+'''
+        report = ReachabilityAnalyzer([("lib.rs", program)]).analyze()
+        write = next(s for s in report.sinks if s.sink.kind == "authority-write")
+        assert [e.entrypoint for e in write.entrypoints] == ["swap"]
+        assert report.cpi_edges[0]["via"] == "declare_program"
@@ -0,0 +1,55 @@
+"""Tests for merging findings several rules report at one location."""
+
+import json
+import os
+import sys
+
+import pytest
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.dedup import load_precedence
+from scanner.engine import AnchorShieldEngine
+from scanner.report import format_terminal_report
+
+TEST_DIR = os.path.join(os.path.dirname(__file__), "test_patterns", "vulnerable")
+
+
+def _read(name: str) -> str:
+    with open(os.path.join(TEST_DIR, name)) as f:
+        return f.read()
+
+
+class TestDeduplication:
+    def test_unrelated_rules_stay_separate_by_default(self):
+        """Owner and discriminator checks are distinct fixes; the default table keeps both."""
+        report = AnchorShieldEngine(only=["ANCHOR-004", "ANCHOR-006"]).scan_content(
+            _read("type_cosplay_no_discriminator.rs"), "lib.rs",
+        )
+        assert sorted((f.id, f.line) for f in report.findings) == [("ANCHOR-004", 25), ("ANCHOR-006", 25)]
+
+    def test_preferred_rule_keeps_location_and_highest_severity(self):
+        engine = AnchorShieldEngine(
+            only=["ANCHOR-004", "ANCHOR-006"], precedence={"ANCHOR-004": ["shield::accounts::*"]},
+        )
+        report = engine.scan_content(_read("type_cosplay_no_discriminator.rs"), "lib.rs")
+        assert [(f.id, f.line, f.severity) for f in report.findings] == [("ANCHOR-004", 25, "High")]
+        supporting = report.findings[0].supporting
+        assert [(s["id"], s["severity"]) for s in supporting] == [("ANCHOR-006", "High")]
+        assert report.summary["by_pattern"] == {"ANCHOR-004": 1}
+        assert "Also flagged by: ANCHOR-006 (High)" in format_terminal_report(report)
+        assert json.loads(report.to_json())["findings"][0]["supporting"][0]["id"] == "ANCHOR-006"
+
+    def test_load_precedence(self, tmp_path):
+        toml = tmp_path / "precedence.toml"
+        toml.write_text('[precedence]\n"ANCHOR-018" = "ANCHOR-012"\n')
+        assert load_precedence(str(toml)) == {"ANCHOR-018": ["ANCHOR-012"]}
+
+        empty = tmp_path / "none.json"
+        empty.write_text('{"precedence": {}}')
+        assert load_precedence(str(empty)) == {}
+
+        typo = tmp_path / "typo.json"
+        typo.write_text('{"ANCHOR-018": ["ANCHOR-912"]}')
+        with pytest.raises(ValueError, match="ANCHOR-912"):
+            load_precedence(str(typo))
@@ -0,0 +1,129 @@
+"""Tests for pulling dependency sources into the scan scope (scan --scan-deps)."""
+
+import os
+import subprocess
+import sys
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.dependencies import load_dependencies
+from scanner.engine import AnchorShieldEngine
+
+PROGRAM = '''use anchor_lang::prelude::*;
+use amm_math::mul_div_floor;
+
+#[program]
+pub mod pool {
+    use super::*;
+
+    pub fn swap(ctx: Context<Swap>, amount: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let out = mul_div_floor(amount, pool.price, PRICE_SCALE);
+        pool.reserve = pool.reserve.checked_sub(out).unwrap();
+        Ok(())
+    }
+}
+'''
+
+MATH = '''pub fn mul_div_floor(amount: u64, num: u64, den: u64) -> u64 {
+    amount * num / den
+}
+'''
+
+LOCK_REGISTRY = '''version = 3
+
+[[package]]
+name = "fixed-math"
+version = "0.3.1"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+'''
+
+
+def _write(path, text):
+    path.parent.mkdir(parents=True, exist_ok=True)
+    path.write_text(text)
+
+
+def _workspace(tmp_path, dependencies, lock=""):
+    """Workspace with programs/pool and a path-dependency math crate under libs/."""
+    _write(tmp_path / "Cargo.toml", '[workspace]\nmembers = ["programs/*"]\n\n'
+           '[workspace.dependencies]\namm-math = { path = "libs/amm-math" }\n')
+    _write(tmp_path / "programs" / "pool" / "Cargo.toml",
+           f'[package]\nname = "pool"\nversion = "0.1.0"\n\n[dependencies]\n{dependencies}')
+    _write(tmp_path / "programs" / "pool" / "src" / "lib.rs", PROGRAM)
+    _write(tmp_path / "libs" / "amm-math" / "Cargo.toml", '[package]\nname = "amm-math"\nversion = "0.1.0"\n')
+    _write(tmp_path / "libs" / "amm-math" / "src" / "lib.rs", MATH)
+    if lock:
+        _write(tmp_path / "Cargo.lock", lock)
+    return tmp_path / "programs" / "pool"
+
+
+def _git(repo, *args):
+    env = dict(os.environ, GIT_AUTHOR_NAME="Ana Ruiz", GIT_AUTHOR_EMAIL="ana@example.com",
+               GIT_COMMITTER_NAME="Ana Ruiz", GIT_COMMITTER_EMAIL="ana@example.com")
+    return subprocess.run(["git", "-C", str(repo), *args], check=True, capture_output=True, text=True, env=env)
+
+
+class TestDependencies:
+    def test_path_dependency_helpers_are_followed(self, tmp_path):
+        program = _workspace(tmp_path, 'anchor-lang = "0.29.0"\namm-math = { workspace = true }\n')
+        opaque = AnchorShieldEngine(only=["ANCHOR-046"]).scan_directory(str(program))
+        assert opaque.findings == [] and opaque.dependencies == []
+
+        report = AnchorShieldEngine(only=["ANCHOR-046"], scan_deps=[]).scan_directory(str(program))
+        assert [f.line for f in report.findings] == [10]
+        assert "calls into mul_div_floor (amm-math/src/lib.rs:2), where `amount * num / den`" \
+            in report.findings[0].description
+        assert report.dependencies == [{
+            "name": "amm-math", "kind": "path", "source": str(tmp_path / "libs" / "amm-math"),
+            "pinned": "", "files": 1, "error": "",
+        }]
+        assert report.to_dict()["dependencies"] == report.dependencies
+
+    def test_registry_crates_only_when_named(self, tmp_path):
+        program = _workspace(tmp_path, 'fixed-math = "0.3"\n', LOCK_REGISTRY)
+        home = tmp_path / "cargo"
+        _write(home / "registry" / "src" / "index.crates.io-6f17d22bba15001f" / "fixed-math-0.3.1" / "src"
+               / "lib.rs", "pub fn scale(amount: u64) -> u64 { amount * 1000 }\n")
+        previous = os.environ.get("CARGO_HOME")
+        os.environ["CARGO_HOME"] = str(home)
+        try:
+            assert load_dependencies(str(program)).dependencies == []
+            scope = load_dependencies(str(program), ["fixed-math", "spl-math"], cache_dir=str(tmp_path / "cache"))
+        finally:
+            if previous is None:
+                del os.environ["CARGO_HOME"]
+            else:
+                os.environ["CARGO_HOME"] = previous
+        fixed, missing = scope.dependencies
+        assert (fixed.pinned, fixed.files, fixed.error) == ("0.3.1", 1, "")
+        assert [path for path, _ in scope.sources] == ["fixed-math/src/lib.rs"]
+        assert missing.error == "not a dependency of the target"
+        assert "scale" in scope.summaries(3)
+
+    def test_registry_crate_without_lock_entry(self, tmp_path):
+        program = _workspace(tmp_path, 'fixed-math = "0.3"\n')
+        scope = load_dependencies(str(program), ["fixed-math"], cache_dir=str(tmp_path / "cache"))
+        assert scope.dependencies[0].error == "no Cargo.lock entry pins the version"
+        assert scope.sources == []
+
+    def test_git_dependency_at_locked_commit(self, tmp_path):
+        upstream = tmp_path / "upstream"
+        _write(upstream / "Cargo.toml", '[workspace]\nmembers = ["crates/*"]\n')
+        _write(upstream / "crates" / "amm-math" / "Cargo.toml", '[package]\nname = "amm-math"\nversion = "0.2.0"\n')
+        _write(upstream / "crates" / "amm-math" / "src" / "lib.rs", MATH)
+        _git(upstream, "init", "-q")
+        _git(upstream, "add", "-A")
+        _git(upstream, "commit", "-q", "-m", "Add amm-math")
+        locked = _git(upstream, "rev-parse", "HEAD").stdout.strip()
+        checked = MATH.replace("amount * num", "amount.checked_mul(num).unwrap()")
+        (upstream / "crates" / "amm-math" / "src" / "lib.rs").write_text(checked)
+        _git(upstream, "commit", "-q", "-am", "Check the product")
+
+        program = _workspace(tmp_path / "ws", f'amm-math = {{ git = "{upstream}", branch = "main" }}\n',
+                             f'version = 3\n\n[[package]]\nname = "amm-math"\nversion = "0.2.0"\n'
+                             f'source = "git+{upstream}?branch=main#{locked}"\n')
+        scope = load_dependencies(str(program), cache_dir=str(tmp_path / "cache"))
+        dep = scope.dependencies[0]
+        assert (dep.kind, dep.pinned, dep.files) == ("git", locked, 1)
+        assert scope.sources == [("amm-math/src/lib.rs", MATH)]
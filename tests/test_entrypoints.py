@@ -0,0 +1,147 @@
+"""Tests for the entry-point inventory, including dispatch outside Anchor."""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.entrypoints import InventoryBuilder, dispatch_arms, render_markdown
+from scanner.reachability import ReachabilityAnalyzer
+
+MIXED = '''
+use anchor_lang::prelude::*;
+
+#[program]
+pub mod vault {
+    use super::*;
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        require!(amount > 0, VaultError::Zero);
+        Ok(())
+    }
+
+    pub fn fallback<'info>(program_id: &Pubkey, accounts: &'info [AccountInfo<'info>], data: &[u8]) -> Result<()> {
+        match data[0] {
+            0 => legacy_withdraw(program_id, accounts, &data[1..]),
+            1 => {
+                let admin = &accounts[0];
+                if !admin.is_signer {
+                    return Err(ProgramError::MissingRequiredSignature.into());
+                }
+                legacy_set_fee(accounts, &data[1..])
+            }
+            _ => Err(ProgramError::InvalidInstructionData.into()),
+        }
+    }
+}
+
+fn legacy_withdraw(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> Result<()> {
+    let vault = &accounts[0];
+    **vault.try_borrow_mut_lamports()? -= 1_000;
+    Ok(())
+}
+
+fn legacy_set_fee(accounts: &[AccountInfo], data: &[u8]) -> Result<()> {
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+    pub owner: Signer<'info>,
+}
+'''
+
+NATIVE = '''
+use solana_program::entrypoint;
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+    Processor::process(program_id, accounts, instruction_data)
+}
+
+pub struct Processor;
+
+impl Processor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        if input[..8] == CLOSE_DISCRIMINATOR {
+            return Self::process_close(accounts);
+        }
+        let instruction = VaultInstruction::try_from_slice(input)?;
+        match instruction {
+            VaultInstruction::Initialize { fee } => Self::process_initialize(accounts, fee),
+            VaultInstruction::Withdraw { amount } => {
+                Self::process_withdraw(accounts, amount)
+            }
+        }
+    }
+
+    fn process_initialize(accounts: &[AccountInfo], fee: u64) -> ProgramResult {
+        Ok(())
+    }
+
+    fn process_withdraw(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+        let authority = next_account_info(&mut accounts.iter())?;
+        require!(authority.is_signer, VaultError::Unauthorized);
+        Ok(())
+    }
+
+    fn process_close(accounts: &[AccountInfo]) -> ProgramResult {
+        Ok(())
+    }
+}
+'''
+
+
+class TestDispatchArms:
+    def test_match_arms_and_discriminator_ifs(self):
+        body = NATIVE[NATIVE.index("if input"):NATIVE.index("fn process_initialize")]
+        arms = [d for _, d, _, _ in dispatch_arms(body)]
+        assert arms == [
+            "input[..8] == CLOSE_DISCRIMINATOR",
+            "VaultInstruction::Initialize { fee }",
+            "VaultInstruction::Withdraw { amount }",
+        ]
+
+    def test_error_only_arm_is_not_an_entry(self):
+        body = MIXED[MIXED.index("match data[0]"):MIXED.index("fn legacy_withdraw")]
+        assert [d for _, d, _, _ in dispatch_arms(body)] == ["0", "1"]
+
+
+class TestInventory:
+    def test_fallback_routes_are_listed_with_guards(self):
+        inventory = InventoryBuilder([("lib.rs", MIXED)]).build()
+        rows = [(e.name, e.kind, e.discriminator, e.root) for e in inventory.entries]
+        assert rows == [
+            ("deposit", "anchor", 'sighash("global:deposit")', ""),
+            ("fallback", "fallback", "any data no handler matches", ""),
+            ("legacy_withdraw", "routed", "0", "fallback"),
+            ("legacy_set_fee", "routed", "1", "fallback"),
+        ]
+        by_name = {e.name: e for e in inventory.entries}
+        assert "signer: owner" in by_name["deposit"].guards
+        assert by_name["legacy_withdraw"].guards == []
+        assert by_name["legacy_set_fee"].guards == ["if !admin.is_signer → Err"]
+        assert inventory.to_dict()["unguarded"] == ["fallback", "legacy_withdraw"]
+        assert "| **none** |" in render_markdown(inventory)
+
+    def test_native_entrypoint_dispatch_in_callee(self):
+        inventory = InventoryBuilder([("lib.rs", NATIVE)]).build()
+        rows = [(e.name, e.kind, e.discriminator) for e in inventory.entries]
+        assert rows == [
+            ("process_instruction", "native", ""),
+            ("process_close", "routed", "input[..8] == CLOSE_DISCRIMINATOR"),
+            ("process_initialize", "routed", "VaultInstruction::Initialize { fee }"),
+            ("process_withdraw", "routed", "VaultInstruction::Withdraw { amount }"),
+        ]
+        withdraw = inventory.entries[-1]
+        assert withdraw.root == "process_instruction"
+        assert withdraw.guards == ["require!(authority.is_signer, VaultError::Unauthorized)"]
+
+    def test_reachability_follows_fallback(self):
+        report = ReachabilityAnalyzer([("lib.rs", MIXED)]).analyze()
+        assert report.entrypoints == 2
+        debit = next(s for s in report.sinks if s.sink.kind == "lamport-debit")
+        assert [(e.entrypoint, e.path) for e in debit.entrypoints] == [("fallback", ["fallback", "legacy_withdraw"])]
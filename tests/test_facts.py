@@ -0,0 +1,103 @@
+"""Tests for the fact-query API used by rule authors."""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.patterns.base import VulnerabilityPattern
+from scanner.patterns.facts import Facts
+
+PROGRAM = '''
+#[program]
+pub mod vault {
+    use super::*;
+
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64, memo: String) -> Result<()> {
+        let fee = amount / 100;
+        let net = amount - fee;
+        msg!("{}", memo);
+        invoke_signed(&transfer(&ctx.accounts.vault.key(), net), &[], &[seeds])?;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    /// Pays for the resize
+    #[account(mut)]
+    pub payer: AccountInfo<'info>,
+    #[account(
+        mut,
+        has_one = authority,
+        realloc = 8 + 64,
+        realloc::payer = payer,
+        realloc::zero = false,
+    )]
+    pub vault: Account<'info, Vault>,
+    pub authority: Signer<'info>,
+}
+'''
+
+
+class TestFacts:
+    def setup_method(self):
+        self.facts = Facts(PROGRAM)
+
+    def test_instructions_and_accounts(self):
+        [ix] = self.facts.instructions()
+        assert (ix.name, ix.accounts_struct, ix.is_entrypoint) == ("withdraw", "Withdraw", True)
+        assert ix.args == ["amount", "memo"]
+        accounts = self.facts.accounts_of(ix)
+        assert [a.name for a in accounts] == ["payer", "vault", "authority"]
+        assert accounts[0].docs == ["Pays for the resize"]
+        assert accounts[1].inner_type == "Vault"
+        assert accounts[2].is_signer and not accounts[0].is_signer
+
+    def test_instruction_of_a_function(self):
+        helper = PROGRAM.replace("#[derive(Accounts)]", "fn fee(amount: u64) -> u64 { amount / 100 }\n\n#[derive(Accounts)]")
+        facts = Facts(helper)
+        withdraw, fee = VulnerabilityPattern._find_functions(helper)
+        assert facts.instruction_of(withdraw).accounts_struct == "Withdraw"
+        assert facts.instruction_of(fee) is None
+
+    def test_constraints_with_lines(self):
+        vault = self.facts.account("Withdraw", "vault")
+        assert [c.kind for c in self.facts.constraints(vault)] == [
+            "mut", "has_one", "realloc", "realloc::payer", "realloc::zero",
+        ]
+        [payer] = self.facts.constraints(vault, "realloc::payer")
+        assert payer.value == "payer"
+        assert PROGRAM.split("\n")[payer.line - 1].strip() == "realloc::payer = payer,"
+
+    def test_taint_paths_follow_bindings(self):
+        [path] = self.facts.taint_paths("amount", r"invoke_signed\(")
+        assert path.instruction == "withdraw"
+        assert [text for _, text in path.steps] == [
+            "let fee = amount / 100",
+            "let net = amount - fee",
+            "invoke_signed(&transfer(&ctx.accounts.vault.key(), net), &[], &[seeds])?",
+        ]
+        assert self.facts.taint_paths("memo", r"invoke_signed\(") == []
+
+    def test_pda_reads_seeds_bump_and_program(self):
+        facts = Facts('''
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    #[account(seeds = [b"pool", mint.key().as_ref()], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    /// CHECK: Metaplex metadata PDA
+    #[account(
+        seeds = [b"metadata", Metadata::id().as_ref(), mint.key().as_ref()],
+        seeds::program = Metadata::id(),
+        bump
+    )]
+    pub metadata: UncheckedAccount<'info>,
+    pub mint: Account<'info, Mint>,
+}
+''')
+        pool, metadata, mint = facts.accounts_of("Stake")
+        assert (pool.pda.program, pool.pda.bump, pool.pda.is_foreign) == ("", "pool.bump", False)
+        assert metadata.pda.seeds == '[b"metadata", Metadata::id().as_ref(), mint.key().as_ref()]'
+        assert (metadata.pda.program, metadata.pda.bump, metadata.pda.is_foreign) == ("Metadata::id()", "", True)
+        assert mint.pda is None
@@ -0,0 +1,68 @@
+"""Tests for feature-gate tagging and cluster applicability."""
+
+import os
+import sys
+
+import pytest
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.engine import AnchorShieldEngine
+from scanner.features import detect_features, load_cluster_features
+from scanner.report import format_terminal_report
+
+HOOKED = '''
+pub fn process_data(ctx: Context<ProcessData>) -> Result<()> {
+    let data = ctx.accounts.data_source.try_borrow_data()?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ProcessData<'info> {
+    pub authority: Signer<'info>,
+    pub data_source: AccountInfo<'info>, // transfer_hook extra account meta list
+}
+'''
+
+
+class TestFeatureGates:
+    def test_extension_implies_token_2022(self):
+        assert detect_features("spl_token_2022::extension::transfer_hook::get_program_id") == {
+            "token-2022", "token-2022:transfer-hook",
+        }
+        assert detect_features("stake::instruction::redelegate(") == {"stake:redelegate"}
+        assert detect_features("token::transfer(cpi_ctx, amount)") == set()
+
+    def test_finding_inapplicable_without_feature(self):
+        everywhere = AnchorShieldEngine(only=["ANCHOR-006"]).scan_content(HOOKED, "lib.rs")
+        assert everywhere.findings[0].feature_gates == ["token-2022", "token-2022:transfer-hook"]
+        assert everywhere.findings[0].inapplicable == []
+
+        engine = AnchorShieldEngine(only=["ANCHOR-006"], cluster_features={"token-2022"})
+        report = engine.scan_content(HOOKED, "lib.rs")
+        assert report.findings[0].inapplicable == ["token-2022:transfer-hook"]
+        assert report.summary["total"] == 0
+        assert report.summary["inapplicable"] == 1
+        assert report.security_score == "A"
+        assert "target cluster lacks token-2022:transfer-hook" in format_terminal_report(report)
+
+        active = {"token-2022", "token-2022:transfer-hook"}
+        report = AnchorShieldEngine(only=["ANCHOR-006"], cluster_features=active).scan_content(
+            HOOKED, "lib.rs",
+        )
+        assert report.findings[0].inapplicable == []
+        assert report.summary["total"] == 1
+
+    def test_load_cluster_features(self, tmp_path):
+        text = tmp_path / "cluster.txt"
+        text.write_text("# localnet\ntoken-2022\nStake:Redelegate  # enabled in test validator\n")
+        assert load_cluster_features(str(text)) == {"token-2022", "stake:redelegate"}
+
+        bundled = os.path.join(os.path.dirname(os.path.dirname(__file__)),
+                               "examples", "cluster-features", "mainnet-beta.toml")
+        assert "token-2022:transfer-hook" in load_cluster_features(bundled)
+
+        typo = tmp_path / "typo.json"
+        typo.write_text('{"features": ["token-2022:transfer-hooks"]}')
+        with pytest.raises(ValueError, match="unknown feature"):
+            load_cluster_features(str(typo))
@@ -0,0 +1,98 @@
+"""Tests for account flow diagrams on findings that move value."""
+
+import json
+import os
+import sys
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.engine import AnchorShieldEngine
+from scanner.flows import flow_legs, render_mermaid
+from scanner.patterns.facts import Facts
+from scanner.report import format_html_report, format_markdown_report
+
+LENDING = os.path.join(os.path.dirname(os.path.dirname(os.path.abspath(__file__))),
+                       "examples", "vulnerable-lending")
+
+
+class TestFlows:
+    def test_lending_withdraw_leg(self):
+        report = AnchorShieldEngine().scan_directory(LENDING)
+        withdraw = next(f for f in report.findings if f.id == "ANCHOR-027" and f.line == 156)
+        assert withdraw.flow == [{
+            "kind": "transfer", "source": "vault", "destination": "withdrawer", "authority": "vault",
+            "control": "program", "amount": "amount", "line": 80, "seeds": '[b"vault", pool.key().as_ref()]',
+            "source_role": "PDA", "destination_role": "signer",
+        }]
+        deposit = next(f for f in report.findings if f.id == "ANCHOR-027" and f.line == 128)
+        assert deposit.flow[0]["control"] == "signer" and deposit.flow[0]["source"] == "depositor"
+
+    def test_cpi_struct_builder_and_lamport_legs(self):
+        content = """
+        pub fn settle(ctx: Context<Settle>, amount: u64) -> Result<()> {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.user_tokens.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.delegate.to_account_info(),
+            };
+            token::transfer(CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts), amount)?;
+            invoke_signed(
+                &spl_token::instruction::mint_to(&spl_token::ID, &mint.key(), &ctx.accounts.user_tokens.key(),
+                    &ctx.accounts.mint_authority.key(), &[], amount)?,
+                &[],
+                &[&seeds[..]],
+            )?;
+            let dest_starting = ctx.accounts.receiver.lamports();
+            **ctx.accounts.receiver.lamports.borrow_mut() = dest_starting.checked_add(ctx.accounts.ticket.lamports()).unwrap();
+            **ctx.accounts.ticket.to_account_info().lamports.borrow_mut() = 0;
+            Ok(())
+        }
+
+        #[derive(Accounts)]
+        pub struct Settle<'info> {
+            #[account(mut)]
+            pub user_tokens: Account<'info, TokenAccount>,
+            pub delegate: UncheckedAccount<'info>,
+            #[account(mut, seeds = [b"vault"], bump)]
+            pub vault: Account<'info, TokenAccount>,
+            pub mint_authority: UncheckedAccount<'info>,
+            #[account(mut)]
+            pub receiver: Signer<'info>,
+            #[account(mut)]
+            pub ticket: Account<'info, Ticket>,
+        }
+        """
+        facts = Facts(content)
+        legs = [(leg.kind, leg.source, leg.destination, leg.authority, leg.control, leg.amount)
+                for leg in flow_legs(facts, content, facts.instructions()[0])]
+        assert legs == [
+            ("transfer", "user_tokens", "vault", "delegate", "transaction", "amount"),
+            ("mint", "mint", "user_tokens", "mint_authority", "program", "amount"),
+            ("lamports", "ticket", "receiver", "ticket", "owner", "all lamports"),
+        ]
+
+    def test_rendered_in_html_markdown_and_json(self):
+        report = AnchorShieldEngine().scan_directory(LENDING)
+        page = format_html_report(report)
+        assert '<div class="flow-title">Account flow</div><svg class="flow"' in page
+        assert "vault: program signs [b&quot;vault&quot;, pool.key().as_ref()]" in page
+        markdown = format_markdown_report(report)
+        assert '    n0["vault (PDA)"] -- "transfer amount · vault: program signs ' \
+               "[b'vault', pool.key().as_ref()]\" --> n1[\"withdrawer (signer)\"]" in markdown
+        assert markdown.count("```mermaid") == sum(1 for f in report.findings if f.flow)
+        exported = json.loads(report.to_json())["findings"]
+        assert any(f["flow"] for f in exported)
+
+    def test_mermaid_shares_nodes_across_legs(self):
+        legs = [
+            {"kind": "transfer", "source": "user", "destination": "vault", "authority": "user",
+             "control": "signer", "amount": "amount_in", "line": 3, "seeds": "",
+             "source_role": "signer", "destination_role": "PDA"},
+            {"kind": "transfer", "source": "vault", "destination": "user", "authority": "vault",
+             "control": "program", "amount": "amount_out", "line": 9, "seeds": "",
+             "source_role": "PDA", "destination_role": "signer"},
+        ]
+        assert render_mermaid(legs).splitlines()[2:4] == [
+            '    n0["user (signer)"] -- "transfer amount_in · user: caller signs" --> n1["vault (PDA)"]',
+            '    n1["vault (PDA)"] -- "transfer amount_out · vault: program signs" --> n0["user (signer)"]',
+        ]
@@ -0,0 +1,84 @@
+"""Tests for fork-vs-upstream constraint diffing."""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.forkdiff import compare_sources, compare_targets, render_markdown
+
+RAYDIUM = os.path.join(os.path.dirname(os.path.dirname(os.path.abspath(__file__))),
+                       "real-world-targets", "raydium-clmm")
+
+UPSTREAM = '''
+#[program]
+pub mod vault {
+    use super::*;
+
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        require!(amount <= ctx.accounts.vault.balance, VaultError::Insufficient);
+        require_keys_eq!(ctx.accounts.vault.mint, ctx.accounts.mint.key(), VaultError::Mint);
+        ctx.accounts.vault.balance -= amount;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut, has_one = authority, seeds = [b"vault", authority.key().as_ref()], bump)]
+    pub vault: Account<'info, Vault>,
+    pub authority: Signer<'info>,
+    pub mint: Account<'info, Mint>,
+}
+'''
+
+FORK = UPSTREAM.replace(
+    "        require!(amount <= ctx.accounts.vault.balance, VaultError::Insufficient);\n", "",
+).replace(
+    'has_one = authority, seeds = [b"vault", authority.key().as_ref()], bump',
+    'seeds = [b"vault"], bump',
+).replace(
+    "pub authority: Signer<'info>", "pub authority: AccountInfo<'info>",
+).replace(
+    "        Ok(())\n    }\n}",
+    "        Ok(())\n    }\n\n"
+    "    pub fn set_fee_authority(ctx: Context<Withdraw>, new: Pubkey) -> Result<()> {\n"
+    "        ctx.accounts.vault.fee_authority = new;\n"
+    "        Ok(())\n    }\n}",
+)
+
+
+class TestForkDiff:
+    def test_identical_trees_have_no_changes(self):
+        assert compare_sources([("lib.rs", UPSTREAM)], [("src/lib.rs", UPSTREAM)]).changes == []
+        assert compare_targets(RAYDIUM, RAYDIUM).changes == []
+
+    def test_reports_dropped_and_added_security_logic(self):
+        report = compare_sources([("lib.rs", FORK)], [("lib.rs", UPSTREAM)])
+        got = {(c.kind, c.location, c.upstream) for c in report.changes}
+        assert ("removed-check", "fn withdraw",
+                "require!(amount <= ctx.accounts.vault.balance, VaultError::Insufficient);") in got
+        assert ("loosened-constraint", "Withdraw.vault", "has_one = authority") in got
+        assert ("loosened-constraint", "Withdraw.vault", 'seeds = [b"vault", authority.key().as_ref()]') in got
+        assert ("weakened-type", "Withdraw.authority", "Signer<'info>") in got
+        [admin] = [c for c in report.changes if c.kind == "added-admin-path"]
+        assert admin.location == "fn set_fee_authority"
+        assert "writes fee_authority" in admin.detail
+        # The unchanged require_keys_eq! is not reported
+        assert len([c for c in report.changes if c.kind == "removed-check"]) == 1
+        assert "| High | removed-check |" in render_markdown(report)
+
+    def test_weakened_type_through_box_and_option(self):
+        upstream = UPSTREAM.replace("pub mint: Account<'info, Mint>", "pub mint: Option<Box<Account<'info, Mint>>>")
+        fork = upstream.replace("Option<Box<Account<'info, Mint>>>", "Option<UncheckedAccount<'info>>")
+        [change] = compare_sources([("lib.rs", fork)], [("lib.rs", upstream)]).changes
+        assert (change.kind, change.location) == ("weakened-type", "Withdraw.mint")
+
+    def test_raydium_fork_without_mint_ordering(self):
+        with open(os.path.join(RAYDIUM, "lib.rs")) as fh:
+            upstream = fh.read()
+        fork = upstream.replace("        constraint = token_mint_0.key() < token_mint_1.key(),\n", "", 1)
+        [change] = compare_sources([("lib.rs", fork)], [("lib.rs", upstream)]).changes
+        assert change.kind == "loosened-constraint"
+        assert change.location == "CreatePool.token_mint_0"
+        assert change.upstream == "constraint = token_mint_0.key() < token_mint_1.key()"
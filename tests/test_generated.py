@@ -0,0 +1,64 @@
+"""Tests for pruning findings in generated code."""
+
+import os
+import shutil
+import sys
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.engine import AnchorShieldEngine
+from scanner.generated import generated_reason, linguist_generated
+from scanner.report import format_terminal_report
+
+TEST_DIR = os.path.join(os.path.dirname(__file__), "test_patterns", "vulnerable")
+HEADER = "// This file was automatically generated by Codama. DO NOT EDIT.\n"
+
+
+def _read(name: str) -> str:
+    with open(os.path.join(TEST_DIR, name)) as f:
+        return f.read()
+
+
+class TestGenerated:
+    def test_markers_and_paths(self):
+        content = _read("vault_creation_unpinned.rs")
+        assert generated_reason("src/lib.rs", content) == ""
+        assert generated_reason("src/lib.rs", HEADER + content) == "header marker 'automatically generated'"
+        assert generated_reason("src/lib.rs", "// @generated\n" + content) == "header marker '@generated'"
+        assert generated_reason("src/ffi.rs", "pub type __bindgen_ty_1 = u32;\n") == "rust-bindgen output"
+        assert generated_reason("clients/generated/amm.rs", content) == "generated path"
+        assert generated_reason("src/idl_generated.rs", content) == "generated path"
+        # Only the header counts: a comment further down mentioning a generator is hand-written code
+        assert generated_reason("src/lib.rs", content + "\n// generated by the keeper bot\n") == ""
+
+    def test_findings_hidden_unless_included(self):
+        content = HEADER + _read("vault_creation_unpinned.rs")
+        report = AnchorShieldEngine(only=["ANCHOR-017"]).scan_content(content, "lib.rs")
+        assert report.findings == [] and report.security_score == "A"
+        assert report.generated == [
+            {"file": "lib.rs", "reason": "header marker 'automatically generated'", "findings": 2},
+        ]
+        assert "2 finding(s) in 1 generated file(s) not shown" in format_terminal_report(report)
+
+        report = AnchorShieldEngine(only=["ANCHOR-017"], include_generated=True).scan_content(content, "lib.rs")
+        assert [f.line for f in report.findings] == [12, 24]
+        assert all(f.generated == "header marker 'automatically generated'" for f in report.findings)
+        assert report.security_score == "A"
+        assert report.summary["total"] == 0 and report.summary["generated"] == 2
+        text = format_terminal_report(report)
+        assert "In generated code: 2" in text and "not shown" not in text
+
+    def test_gitattributes_linguist_generated(self, tmp_path):
+        (tmp_path / "programs" / "amm" / "src").mkdir(parents=True)
+        (tmp_path / "clients").mkdir()
+        shutil.copy(os.path.join(TEST_DIR, "vault_creation_unpinned.rs"), tmp_path / "programs" / "amm" / "src" / "lib.rs")
+        shutil.copy(os.path.join(TEST_DIR, "vault_creation_unpinned.rs"), tmp_path / "clients" / "amm.rs")
+        (tmp_path / ".gitattributes").write_text("*.rs text eol=lf\n/clients/ linguist-generated=true\n")
+        assert linguist_generated(str(tmp_path)) == ("clients/*",)
+
+        report = AnchorShieldEngine(only=["ANCHOR-017"]).scan_directory(str(tmp_path))
+        assert {f.file for f in report.findings} == {os.path.join("programs", "amm", "src", "lib.rs")}
+        assert report.generated == [
+            {"file": os.path.join("clients", "amm.rs"),
+             "reason": ".gitattributes linguist-generated (clients/*)", "findings": 2},
+        ]
@@ -0,0 +1,91 @@
+"""Tests for the manual-review hotspot ranking."""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.hotspots import _complexity, rank_sources, rank_target, render_markdown
+from scanner.patterns.base import Finding
+
+LENDING = os.path.join(os.path.dirname(os.path.dirname(os.path.abspath(__file__))),
+                       "examples", "vulnerable-lending")
+
+SOURCE = '''
+#[program]
+pub mod amm {
+    use super::*;
+
+    pub fn swap(ctx: Context<Swap>, amount_in: u64, min_out: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let out = if pool.a_to_b { amount_in * pool.rate } else { amount_in / pool.rate };
+        match pool.curve {
+            Curve::Constant => pool.reserve_a += amount_in,
+            Curve::Stable => pool.reserve_b += amount_in,
+            Curve::Weighted => pool.reserve_a -= amount_in,
+        }
+        if out < min_out || out == 0 {
+            return err!(AmmError::Slippage);
+        }
+        token::transfer(ctx.accounts.transfer_ctx(), out)?;
+        Ok(())
+    }
+
+    pub fn set_fee(ctx: Context<SetFee>, fee: u16) -> Result<()> {
+        ctx.accounts.pool.fee = fee;
+        Ok(())
+    }
+
+    pub fn ping(_ctx: Context<Ping>) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn swap_rounds_down() {
+        for i in 0..10 { if i > 5 && i < 8 { assert!(true); } }
+    }
+}
+'''
+
+
+def _finding(line):
+    return Finding(id="ANCHOR-016", name="x", severity="High", file="lib.rs", line=line,
+                   description="", root_cause="", exploit_scenario="", fix_recommendation="",
+                   code_snippet="")
+
+
+class TestHotspots:
+    def test_complexity_counts_branches_and_match_arms(self):
+        # 2 ifs, ||, and 3 arms (2 decisions)
+        assert _complexity(SOURCE.split("pub fn set_fee")[0]) == 6
+        assert _complexity("Ok(())") == 1
+
+    def test_ranks_complex_clean_functions_first(self):
+        report = rank_sources([("lib.rs", SOURCE)], [])
+        assert [h.function for h in report.hotspots] == ["swap", "set_fee"]
+        swap = report.hotspots[0]
+        assert (swap.complexity, swap.cpis, swap.clean) == (6, 1, True)
+        assert swap.taint_paths == 1
+        assert swap.reasons()[:2] == ["complexity 6", "1 CPI"]
+        # ping is trivial and the #[test] function is not ranked
+        assert report.functions == 3
+
+    def test_findings_rank_a_function_down(self):
+        clean = rank_sources([("lib.rs", SOURCE)], []).hotspots[0].score
+        flagged = rank_sources([("lib.rs", SOURCE)], [_finding(10), _finding(12)])
+        swap = next(h for h in flagged.hotspots if h.function == "swap")
+        assert swap.findings == 2 and swap.score == round(clean / 3, 2)
+        assert [h.function for h in rank_sources([("lib.rs", SOURCE)], [_finding(10)],
+                                                 clean_only=True).hotspots] == ["set_fee"]
+
+    def test_target_scan_and_rendering(self):
+        report = rank_target(LENDING, limit=2)
+        assert len(report.hotspots) == 2 and report.findings > 0
+        # withdraw carries ANCHOR-061 on top of ANCHOR-046 and ranks below deposit
+        assert [h["function"] for h in report.to_dict()["hotspots"]] == ["deposit", "withdraw"]
+        markdown = render_markdown(report)
+        assert "| 1 | deposit | `programs/vulnerable-lending/src/lib.rs:19` |" in markdown
+        assert "| 2 | withdraw | `programs/vulnerable-lending/src/lib.rs:66` |" in markdown
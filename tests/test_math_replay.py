@@ -0,0 +1,109 @@
+"""Tests for the native AMM math replay mode."""
+
+import os
+import shutil
+import sys
+import tempfile
+
+import pytest
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.math_replay import extract_pure_functions, replay_target, boundary_values
+
+ROOT = os.path.dirname(os.path.dirname(os.path.abspath(__file__)))
+
+MATH_SOURCE = """
+pub const MIN_TICK: i32 = -443636;
+pub const MAX_TICK: i32 = -MIN_TICK;
+
+pub fn mul_div_floor(a: u64, b: u64, denom: u64) -> u64 {
+    a * b / denom
+}
+
+pub fn sqrt_price_at_tick(tick: i32) -> u128 {
+    let abs = tick.unsigned_abs() as u128;
+    if tick >= 0 { (1u128 << 64) + abs * 1000 } else { (1u128 << 64) - abs * 1000 }
+}
+
+pub fn price_from_tick(tick: i32) -> u64 {
+    let t = (tick as i64 + 500_000) as u64;
+    if t == 500_010 { 0 } else { t * 10 }
+}
+
+pub fn handler(ctx: Context<Swap>, amount: u64) -> Result<()> {
+    Ok(())
+}
+"""
+
+# Tick math in the shape of raydium-clmm's libraries/tick_math.rs, which the
+# checked-in snapshot (the dispatcher and instruction modules) leaves out
+TICK_MATH_SOURCE = """
+pub const MIN_TICK: i32 = -443636;
+pub const MAX_TICK: i32 = -MIN_TICK;
+pub const MAX_SQRT_PRICE_X64: u128 = 79226673521066979257578248091;
+
+pub fn get_sqrt_price_at_tick(tick: i32) -> Result<u128, anchor_lang::error::Error> {
+    let abs_tick = tick.unsigned_abs();
+    require!(abs_tick <= MAX_TICK as u32, ErrorCode::TickUpperOverflow);
+    let step = (abs_tick as u128) << 40;
+    Ok(if tick >= 0 { (1u128 << 64) + step } else { (1u128 << 64) - step })
+}
+
+pub fn get_tick_at_sqrt_price(sqrt_price_x64: u128) -> Result<i32> {
+    require!(sqrt_price_x64 < MAX_SQRT_PRICE_X64, ErrorCode::SqrtPriceX64);
+    Ok(((sqrt_price_x64 - (1u128 << 64)) >> 40) as i32)
+}
+"""
+
+
+class TestExtraction:
+    def test_extracts_integer_functions_only(self):
+        names = [f.name for f in extract_pure_functions("math.rs", MATH_SOURCE)]
+        assert names == ["mul_div_floor", "sqrt_price_at_tick", "price_from_tick"]
+
+    def test_extracts_result_returning_functions(self):
+        functions = extract_pure_functions("tick_math.rs", TICK_MATH_SOURCE)
+        assert [(f.name, f.ret) for f in functions] == [
+            ("get_sqrt_price_at_tick", "Result<u128, anchor_lang::error::Error>"),
+            ("get_tick_at_sqrt_price", "Result<i32>"),
+        ]
+
+    def test_boundary_values_include_tick_bounds(self):
+        values = boundary_values("i32", {"MIN_TICK": -443636, "MAX_TICK": 443636})
+        assert -443636 in values and 443637 in values
+        assert -2**31 in values and 2**31 - 1 in values
+
+
+class TestReplay:
+    def setup_method(self):
+        if not shutil.which("rustc"):
+            pytest.skip("rustc not available")
+        self.tmp = tempfile.mkdtemp()
+        with open(os.path.join(self.tmp, "math.rs"), "w") as fh:
+            fh.write(MATH_SOURCE)
+
+    def teardown_method(self):
+        shutil.rmtree(self.tmp, ignore_errors=True)
+
+    def test_reports_overflow_and_monotonicity(self):
+        result = replay_target(self.tmp, iterations=20)
+        assert result.error is None
+        kinds = {(i.function, i.kind) for i in result.issues}
+        assert ("mul_div_floor", "overflow") in kinds
+        assert ("price_from_tick", "monotonicity") in kinds
+        assert not any(i.function == "sqrt_price_at_tick" for i in result.issues)
+
+    def test_replays_result_returning_tick_math_in_raydium_clmm(self):
+        target = os.path.join(self.tmp, "raydium-clmm")
+        shutil.copytree(os.path.join(ROOT, "real-world-targets", "raydium-clmm"), target)
+        with open(os.path.join(target, "tick_math.rs"), "w") as fh:
+            fh.write(TICK_MATH_SOURCE)
+
+        result = replay_target(target, iterations=20)
+        assert result.error is None
+        assert [f.name for f in result.functions_replayed] == [
+            "get_sqrt_price_at_tick", "get_tick_at_sqrt_price",
+        ]
+        kinds = {(i.function, i.kind) for i in result.issues}
+        assert kinds == {("get_tick_at_sqrt_price", "overflow")}
@@ -0,0 +1,184 @@
+"""Tests for arithmetic summaries of math helpers and the interprocedural depth setting."""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.engine import AnchorShieldEngine
+from scanner.patterns.math_summary import OVERFLOW, ROUNDS_DOWN, ROUNDS_UP, math_summaries
+from scanner.patterns.type_cosplay import TypeCosplayPattern
+from scanner.patterns.unchecked_arithmetic import UncheckedArithmeticPattern
+
+POOL = '''
+#[program]
+pub mod pool {
+    use super::*;
+    pub fn swap(ctx: Context<Swap>, amount_in: u64) -> Result<()> {
+        let out = compute_swap_step(ctx.accounts.pool.liquidity, amount_in)?;
+        ctx.accounts.pool.fees += out;
+        Ok(())
+    }
+}
+
+fn compute_swap_step(liquidity: u128, amount_in: u64) -> Result<u64> {
+    let out = get_amount_out(liquidity, amount_in)?;
+    Ok(out)
+}
+
+fn get_amount_out(liquidity: u128, amount_in: u64) -> Result<u64> {
+    let fee = fee_amount(amount_in);
+    let out = U256::from(liquidity).mul_div_floor(U256::from(amount_in - fee), U256::from(liquidity)).unwrap();
+    Ok(out.as_u64())
+}
+
+fn fee_amount(amount: u64) -> u64 {
+    // rounded up in the pool's favour
+    (amount * FEE_RATE + FEE_DENOMINATOR - 1) / FEE_DENOMINATOR
+}
+
+pub fn shares_for(deposit: u64, total_shares: u64, total_value: u64) -> u64 {
+    shares_from_value(deposit, total_value, total_shares)
+}
+
+const SPACE: usize = 8 + 32 + 8;
+'''
+
+# validator_list is deserialized two calls below the handler
+NESTED_CHECK = '''
+#[program]
+pub mod staking {
+    pub fn deposit_stake(ctx: Context<DepositStake>) -> Result<()> {
+        ctx.accounts.load_validator(&ctx.accounts.validator_list)?;
+        Ok(())
+    }
+}
+
+impl<'info> DepositStake<'info> {
+    pub fn load_validator(&self, list: &AccountInfo<'info>) -> Result<ValidatorList> {
+        read_list(list)
+    }
+}
+
+fn read_list(info: &AccountInfo) -> Result<ValidatorList> {
+    ValidatorList::try_deserialize(&mut &info.data.borrow()[..])
+}
+
+#[derive(Accounts)]
+pub struct DepositStake<'info> {
+    pub validator_list: AccountInfo<'info>,
+}
+'''
+
+# Two accounts structs with a `process` method each, like marinade's handlers
+SAME_NAMED_METHODS = '''
+#[program]
+pub mod staking {
+    pub fn deposit(ctx: Context<Deposit>, lamports: u64) -> Result<()> {
+        ctx.accounts.process(lamports)
+    }
+    pub fn add_validator(ctx: Context<AddValidator>, score: u32) -> Result<()> {
+        ctx.accounts.process(score)
+    }
+}
+
+impl<'info> Deposit<'info> {
+    pub fn process(&mut self, lamports: u64) -> Result<()> {
+        self.state.check_staking_cap(lamports)
+    }
+}
+
+impl<'info> AddValidator<'info> {
+    pub fn process(&mut self, score: u32) -> Result<()> {
+        self.state.total_score = self.state.total_score.checked_add(score).unwrap();
+        Ok(())
+    }
+}
+
+impl State {
+    pub fn check_staking_cap(&self, lamports: u64) -> Result<()> {
+        let total = self.available_reserve_balance + lamports;
+        require!(total <= self.staking_sol_cap, StakingError::Cap);
+        Ok(())
+    }
+}
+'''
+
+
+class TestMathSummaries:
+    def test_leaf_facts_propagate_to_callers(self):
+        summaries = math_summaries(POOL)
+        fee = summaries["fee_amount"]
+        assert set(fee.facts) == {OVERFLOW, ROUNDS_UP}
+        assert fee.facts[OVERFLOW].chain == ["fee_amount"] and fee.facts[OVERFLOW].line == 25
+
+        amount_out = summaries["get_amount_out"].facts
+        assert set(amount_out) == {OVERFLOW, ROUNDS_DOWN, ROUNDS_UP}
+        assert amount_out[ROUNDS_DOWN].chain == ["get_amount_out", "mul_div_floor"]
+        assert amount_out[ROUNDS_UP].chain == ["get_amount_out", "fee_amount"]
+
+        swap = summaries["swap"].facts
+        assert swap[ROUNDS_DOWN].chain == ["swap", "compute_swap_step", "get_amount_out", "mul_div_floor"]
+        assert swap[ROUNDS_DOWN].line == 6
+        # The handler's own `+=` is reported before anything inherited
+        assert swap[OVERFLOW].chain == ["swap"] and swap[OVERFLOW].line == 7
+        assert swap[ROUNDS_UP].describe() == (
+            "rounds-up via swap -> compute_swap_step -> get_amount_out -> fee_amount: "
+            "`(amount * FEE_RATE + FEE_DENOMINATOR - 1) / FEE_DENOMINATOR` (line 6)"
+        )
+
+    def test_library_helpers_and_constant_arithmetic(self):
+        """Uncalled-library leaves come from LIBRARY_HELPERS; `8 + 32 + 8` is not arithmetic to track."""
+        summaries = math_summaries(POOL)
+        assert set(summaries["shares_for"].facts) == {ROUNDS_DOWN}
+        assert summaries["shares_for"].facts[ROUNDS_DOWN].chain == ["shares_for", "shares_from_value"]
+        # Library helpers themselves are not functions of the unit
+        assert "mul_div_floor" not in summaries and "shares_from_value" not in summaries
+
+    def test_checked_and_widened_arithmetic_is_not_overflow(self):
+        summaries = math_summaries('''
+fn total(a: u64, b: u64) -> Option<u64> {
+    a.checked_mul(b)?.checked_add(1)
+}
+fn widened(a: u64, b: u64) -> u128 {
+    a as u128 * b as u128
+}
+fn deref(x: &mut u64, y: &u64) {
+    *x = *y;
+}
+''')
+        assert summaries == {}
+
+    def test_depth_bounds_propagation(self):
+        shallow = math_summaries(POOL, depth=1)
+        assert shallow["get_amount_out"].has(ROUNDS_DOWN)
+        assert "compute_swap_step" not in shallow
+        assert set(shallow["swap"].facts) == {OVERFLOW}
+        assert math_summaries(POOL, depth=2)["compute_swap_step"].has(ROUNDS_DOWN)
+        assert not math_summaries(POOL, depth=2)["swap"].has(ROUNDS_DOWN)
+
+    def test_methods_are_keyed_by_impl_type(self):
+        """`ctx.accounts.process` resolves through the handler's Accounts struct."""
+        summaries = math_summaries(SAME_NAMED_METHODS)
+        assert set(summaries) == {"deposit", "Deposit::process", "State::check_staking_cap"}
+        assert summaries["deposit"].facts[OVERFLOW].chain == ["deposit", "Deposit::process", "State::check_staking_cap"]
+        findings = UncheckedArithmeticPattern().scan("lib.rs", SAME_NAMED_METHODS)
+        assert [f.description.split(": ")[0] for f in findings] == [
+            "In State::check_staking_cap (called from deposit)"
+        ]
+
+
+class TestInterproceduralDepth:
+    def test_engine_sets_depth_on_patterns(self):
+        engine = AnchorShieldEngine(interprocedural_depth=3)
+        engine.scan_content(POOL, "lib.rs")
+        assert {p.max_depth for p in engine.patterns} == {3}
+        assert AnchorShieldEngine().interprocedural_depth == 8
+
+    def test_helper_validation_beyond_depth_is_not_followed(self):
+        pattern = TypeCosplayPattern()
+        assert pattern.scan("lib.rs", NESTED_CHECK) == []
+        pattern.max_depth = 1
+        assert [f.line for f in pattern.scan("lib.rs", NESTED_CHECK)] == [22]
+        pattern.max_depth = 2
+        assert pattern.scan("lib.rs", NESTED_CHECK) == []
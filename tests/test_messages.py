@@ -0,0 +1,46 @@
+"""Tests for the report message catalog (translations / terminology)."""
+
+import os
+import sys
+
+import pytest
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.engine import AnchorShieldEngine
+from scanner.messages import MessageCatalog, localize_report
+from scanner.report import format_html_report, format_terminal_report
+
+ROOT = os.path.dirname(os.path.dirname(os.path.abspath(__file__)))
+VULN_FILE = os.path.join(ROOT, "tests", "test_patterns", "vulnerable", "raw_account_info_no_owner.rs")
+
+
+class TestMessageCatalog:
+    def test_terms_keep_capitalization(self):
+        catalog = MessageCatalog(terms={"oracle account": "observation account"})
+        assert catalog.apply_terms("Oracle account is stale; re-read the oracle account.") == (
+            "Observation account is stale; re-read the observation account."
+        )
+
+    def test_example_catalog_localizes_report(self):
+        catalog = MessageCatalog.load(os.path.join(ROOT, "examples", "messages", "es.toml"))
+        report = AnchorShieldEngine(only=["ANCHOR-006"]).scan_file(VULN_FILE)
+        localized = localize_report(report, catalog)
+
+        finding = localized.findings[0]
+        assert finding.name == "Falta verificación de propietario"
+        assert finding.description.startswith("Cuenta sin verificación de propietario. ")
+        assert finding.description.endswith(report.findings[0].description)
+        # The original report is untouched
+        assert report.findings[0].name != finding.name
+
+        terminal = format_terminal_report(localized, catalog)
+        assert "Hallazgos (" in terminal and "Corrección:" in terminal
+        html = format_html_report(localized, catalog)
+        assert '<html lang="es">' in html and "Causa raíz" in html
+
+    def test_rejects_unknown_rule_field(self, tmp_path):
+        path = tmp_path / "bad.json"
+        path.write_text('{"rules": {"ANCHOR-006": {"title": "x"}}}')
+        with pytest.raises(ValueError, match="unknown field"):
+            MessageCatalog.load(str(path))
@@ -0,0 +1,64 @@
+"""Tests for exploit narratives on critical findings."""
+
+import json
+import os
+import sys
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.engine import AnchorShieldEngine
+from scanner.narrative import exploit_narrative
+from scanner.patterns.facts import Facts
+from scanner.report import format_html_report, format_terminal_report
+
+LENDING = os.path.join(os.path.dirname(os.path.dirname(os.path.abspath(__file__))),
+                       "examples", "vulnerable-lending")
+
+
+class TestNarrative:
+    def test_lending_withdraw_drain(self):
+        report = AnchorShieldEngine().scan_directory(LENDING)
+        withdraw = next(f for f in report.findings if f.id == "ANCHOR-027" and f.line == 156)
+        assert withdraw.narrative == [
+            "Call `withdraw(amount)` signing as `withdrawer`, with `user_account` set to an account the "
+            "attacker chooses (the flagged account, line 156); `vault` is the PDA [b\"vault\", pool.key().as_ref()]",
+            "Choose `amount` so the only check on it passes: "
+            "require!(user.deposited >= amount, LendingError::InsufficientBalance) (line 72)",
+            "State changes: user_account.deposited decreases by amount (line 74); "
+            "pool.total_deposits decreases by amount (line 75)",
+            "`vault` transfers amount to `withdrawer` signed by the program (line 80)",
+            "The attacker gains up to user_account.deposited per call out of `vault`, "
+            "into `withdrawer`, its own account",
+        ]
+
+    def test_deposits_and_lower_severities_are_not_narrated(self):
+        """The signer paying in is not an attack; Medium findings get no narrative."""
+        report = AnchorShieldEngine().scan_directory(LENDING)
+        deposit = next(f for f in report.findings if f.id == "ANCHOR-027" and f.line == 128)
+        assert deposit.narrative == []
+        assert all(f.narrative == [] for f in report.findings if f.severity == "Medium")
+
+    def test_no_taint_path_no_narrative(self):
+        content = """
+        pub fn sweep(ctx: Context<Sweep>) -> Result<()> {
+            ctx.accounts.vault.amount = 0;
+            Ok(())
+        }
+
+        #[derive(Accounts)]
+        pub struct Sweep<'info> {
+            #[account(mut)]
+            pub vault: Account<'info, Vault>,
+        }
+        """
+        assert exploit_narrative(Facts(content), content, 10) is None
+
+    def test_rendered_in_finding_body(self):
+        report = AnchorShieldEngine().scan_directory(LENDING)
+        terminal = format_terminal_report(report)
+        assert "Exploit path:" in terminal
+        assert "    4. `vault` transfers amount to `withdrawer` signed by the program (line 80)" in terminal
+        page = format_html_report(report)
+        assert '<ol class="narrative"><li>Call `withdraw(amount)`' in page
+        exported = json.loads(report.to_json())["findings"]
+        assert any(f["narrative"] for f in exported)
@@ -0,0 +1,134 @@
+"""Tests for onboarding: domain detection, rule packs, config, baseline and plan."""
+
+import json
+import os
+import shutil
+import sys
+
+import pytest
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.config import load_skipped_packs
+from scanner.dependencies import declared_dependencies
+from scanner.engine import AnchorShieldEngine
+from scanner.onboard import (
+    detect_domains, onboard, plan_remediation, propose_packs, render_config, render_markdown,
+)
+from scanner.patterns.base import Finding
+
+ROOT = os.path.dirname(os.path.dirname(os.path.abspath(__file__)))
+LENDING = os.path.join(ROOT, "examples", "vulnerable-lending")
+
+
+def _domains(target):
+    engine = AnchorShieldEngine()
+    return detect_domains(engine.load_sources(target), [d.name for d in declared_dependencies(target)])
+
+
+def _finding(rule, severity, effort, line=1):
+    return Finding(id=rule, name=rule, severity=severity, file="lib.rs", line=line, description="",
+                   root_cause="", exploit_scenario="", remediation_effort=effort,
+                   fix_recommendation="Check the owner:\n  owner = program_id")
+
+
+class TestDomains:
+    def test_detects_amm(self):
+        domains = _domains(os.path.join(ROOT, "real-world-targets", "raydium-clmm"))
+        assert [d.name for d in domains if d.detected] == ["amm"]
+
+    def test_detects_staking(self):
+        domains = _domains(os.path.join(ROOT, "real-world-targets", "solana-staking"))
+        assert [d.name for d in domains if d.detected] == ["staking"]
+
+    def test_protocol_crate_alone_detects_domain(self, tmp_path):
+        (tmp_path / "Cargo.toml").write_text(
+            '[dependencies]\nanchor-lang = "0.29.0"\nwhirl = { package = "whirlpool", path = "../whirlpool" }\n'
+        )
+        amm = _domains(str(tmp_path))[0]
+        assert (amm.name, amm.detected, amm.evidence()) == ("amm", True, "whirlpool crate")
+
+    def test_detects_lending(self):
+        domains = _domains(LENDING)
+        assert [d.name for d in domains if d.detected] == ["lending"]
+        assert domains[0].evidence() == "UserAccount state; borrowed, interest_rate, total_borrows fields"
+
+    def test_lending_workspace_skips_nft_pack(self):
+        packs = {p.selector: p for p in propose_packs(_domains(LENDING), AnchorShieldEngine().patterns)}
+        assert packs["shield::nft::*"].status == "skipped"
+        assert packs["shield::lending::*"].status == "focus"
+        assert packs["shield::accounts::*"].status == "enabled"
+
+
+class TestConfig:
+    def test_config_round_trips(self, tmp_path):
+        domains = _domains(LENDING)
+        config = render_config(domains, propose_packs(domains, AnchorShieldEngine().patterns))
+        assert config.startswith("# Generated by `anchor-shield-v2 onboard`")
+        (tmp_path / "anchor-shield.toml").write_text(config)
+        assert load_skipped_packs(str(tmp_path)) == ["shield::nft::*"]
+
+    def test_engine_honors_skipped_packs(self, tmp_path):
+        shutil.copytree(LENDING, tmp_path / "lending")
+        target = tmp_path / "lending"
+        (target / "anchor-shield.toml").write_text('[packs]\nskip = ["shield::lending::*"]\n')
+        engine = AnchorShieldEngine()
+        lending = {p.id for p in engine.patterns if p.category == "lending"}
+        assert {f.id for f in engine.scan_directory(LENDING).findings} & lending
+        report = engine.scan_directory(str(target))
+        assert report.findings
+        assert not {f.id for f in report.findings} & lending
+
+    def test_unknown_skip_selector_is_rejected(self, tmp_path):
+        (tmp_path / "anchor-shield.toml").write_text('[packs]\nskip = ["shield::defi::*"]\n')
+        with pytest.raises(ValueError, match="shield::defi::"):
+            AnchorShieldEngine().scan_directory(str(tmp_path))
+
+
+class TestPlan:
+    def test_orders_by_severity_then_effort_and_defers_low(self):
+        findings = [
+            _finding("ANCHOR-001", "Medium", "mechanical"),
+            _finding("ANCHOR-002", "High", "architectural"),
+            _finding("ANCHOR-003", "High", "mechanical"),
+            _finding("ANCHOR-004", "Low", "mechanical"),
+            _finding("ANCHOR-003", "High", "mechanical", line=9),
+        ]
+        plan = plan_remediation(findings)
+        assert [(i.rule, i.day) for i in plan] == [
+            ("ANCHOR-003", 1), ("ANCHOR-002", 2), ("ANCHOR-001", 3), ("ANCHOR-004", 0),
+        ]
+        assert plan[0].findings == ["lib.rs:1", "lib.rs:9"]
+        assert plan[0].fix == "Check the owner"
+
+    def test_week_holds_five_days(self):
+        plan = plan_remediation([_finding(f"ANCHOR-{n:03}", "High", "architectural") for n in range(7)])
+        assert [i.day for i in plan] == [1, 2, 3, 4, 5, 0, 0]
+
+
+class TestOnboard:
+    def test_writes_config_and_baseline_once(self, tmp_path):
+        shutil.copytree(LENDING, tmp_path / "lending")
+        target = str(tmp_path / "lending")
+        baseline = str(tmp_path / "baseline.json")
+
+        report = onboard(target, baseline)
+        assert report.config_written
+        assert report.baselined and report.week
+        assert "shield::nft::*" in open(report.config).read()
+        assert json.loads(report.to_json())["domains"][0]["name"] == "lending"
+        assert "## First Week" in render_markdown(report)
+
+        # The count is of the findings planned, not of the baseline's entries
+        assert report.baselined == sum(len(item.findings) for item in report.plan)
+        # Every baselined finding stays hidden, including ones deduplication folds away
+        assert AnchorShieldEngine(baseline=baseline).scan_directory(target).findings == []
+
+        with open(report.config, "a") as fh:
+            fh.write("# tuned by hand\n")
+        again = onboard(target, baseline)
+        assert not again.config_written and again.baselined is None
+        assert open(report.config).read().endswith("# tuned by hand\n")
+
+        forced = onboard(target, baseline, force=True)
+        assert forced.config_written and forced.baselined == report.baselined
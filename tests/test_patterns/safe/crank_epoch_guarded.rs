@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod reward_pool {
+    use super::*;
+
+    // SAFE: the crank can run at most once per epoch.
+    pub fn update_rewards(ctx: Context<UpdateRewards>) -> Result<()> {
+        let clock = Clock::get()?;
+        let pool = &mut ctx.accounts.pool;
+        require!(pool.last_update_epoch < clock.epoch, PoolError::AlreadyUpdated);
+        let epoch_rewards = pool.reward_rate.checked_mul(pool.total_staked).ok_or(PoolError::MathOverflow)? / 10_000;
+        pool.acc_reward_per_share = pool.acc_reward_per_share.checked_add(epoch_rewards).ok_or(PoolError::MathOverflow)?;
+        pool.last_update_epoch = clock.epoch;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct UpdateRewards<'info> {
+    #[account(mut, seeds = [b"pool"], bump = pool.bump)]
+    pub pool: Account<'info, RewardPool>,
+}
+
+#[account]
+pub struct RewardPool {
+    pub bump: u8,
+    pub reward_rate: u64,
+    pub total_staked: u64,
+    pub acc_reward_per_share: u64,
+    pub last_update_epoch: u64,
+}
+
+#[error_code]
+pub enum PoolError {
+    #[msg("Rewards already updated this epoch")]
+    AlreadyUpdated,
+    #[msg("Math overflow")]
+    MathOverflow,
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod packed_vault {
+    use super::*;
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let mut vault = ctx.accounts.vault.load_mut()?;
+        let total = vault.total_deposits;
+        vault.total_deposits = total.checked_add(amount).ok_or(VaultError::MathOverflow)?;
+        let history = vault.history;
+        let slot = history.iter().position(|h| *h == 0).unwrap_or(0);
+        vault.history[slot] = amount;
+        msg!("total deposits: {}", total);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut, has_one = authority)]
+    pub vault: AccountLoader<'info, Vault>,
+    pub authority: Signer<'info>,
+}
+
+#[account(zero_copy(unsafe))]
+#[repr(C, packed)]
+#[derive(Default)]
+pub struct Vault {
+    pub authority: Pubkey,
+    pub total_deposits: u64,
+    pub history: [u64; 8],
+    pub reserved: [u8; 16],
+}
+
+#[account(zero_copy)]
+pub struct Config {
+    pub fee_bps: u64,
+    pub admin: Pubkey,
+}
+
+#[error_code]
+pub enum VaultError {
+    MathOverflow,
+}
@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod fee_config {
+    use super::*;
+
+    #[access_control(only_admin(&ctx))]
+    pub fn set_fee(ctx: Context<SetFee>, fee_bps: u16) -> Result<()> {
+        ctx.accounts.config.fee_bps = fee_bps;
+        Ok(())
+    }
+
+    #[access_control(valid_flag(paused))]
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.paused = paused;
+        Ok(())
+    }
+
+    pub fn set_admin(ctx: Context<SetAdmin>, new_admin: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.admin = new_admin;
+        Ok(())
+    }
+}
+
+fn only_admin(ctx: &Context<SetFee>) -> Result<()> {
+    msg!("fee update by {}", ctx.accounts.admin.key());
+    Ok(())
+}
+
+fn valid_flag(paused: bool) -> Result<()> {
+    require!(paused || !paused, ConfigError::InvalidFlag);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetFee<'info> {
+    #[account(mut)]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(mut)]
+    pub config: Account<'info, Config>,
+    pub operator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetAdmin<'info> {
+    #[account(mut)]
+    pub config: Account<'info, Config>,
+    pub caller: Signer<'info>,
+}
+
+#[account]
+pub struct Config {
+    pub admin: Pubkey,
+    pub fee_bps: u16,
+    pub paused: bool,
+}
+
+#[error_code]
+pub enum ConfigError {
+    InvalidFlag,
+}
@@ -0,0 +1,125 @@
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+pub const MAX_NAME_LEN: usize = 32;
+
+#[program]
+pub mod exchange {
+    use super::*;
+
+    pub fn init_config(ctx: Context<InitConfig>, fee_bps: u16) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.admin.key();
+        config.fee_bps = fee_bps;
+        config.bump = ctx.bumps.config;
+        Ok(())
+    }
+
+    pub fn create_profile(ctx: Context<CreateProfile>, name: String) -> Result<()> {
+        ctx.accounts.profile.name = name;
+        Ok(())
+    }
+
+    pub fn init_market(ctx: Context<InitMarket>) -> Result<()> {
+        let mut market = ctx.accounts.market.load_init()?;
+        market.authority = ctx.accounts.admin.key();
+        Ok(())
+    }
+
+    pub fn place_order(ctx: Context<PlaceOrder>, limit: Option<u64>) -> Result<()> {
+        ctx.accounts.order.limit = limit;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Config::LEN,
+        seeds = [b"config"],
+        bump,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateProfile<'info> {
+    #[account(init, payer = user, space = UserProfile::INIT_SPACE)]
+    pub profile: Account<'info, UserProfile>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitMarket<'info> {
+    #[account(init, payer = admin, space = std::mem::size_of::<Market>())]
+    pub market: AccountLoader<'info, Market>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PlaceOrder<'info> {
+    #[account(init, payer = owner, space = Order::LEN)]
+    pub order: Account<'info, Order>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+pub struct Config {
+    pub admin: Pubkey,
+    pub fee_bps: u16,
+    pub treasury: Pubkey,
+    pub paused: bool,
+    pub bump: u8,
+}
+
+impl Config {
+    // admin + fee_bps + treasury + paused
+    pub const LEN: usize = 32 + 2 + 32 + 1;
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct UserProfile {
+    pub owner: Pubkey,
+    #[max_len(MAX_NAME_LEN)]
+    pub name: String,
+    pub created_at: i64,
+}
+
+#[account(zero_copy)]
+pub struct Market {
+    pub authority: Pubkey,
+    pub base_lots: u64,
+    pub quote_lots: u64,
+    pub bids: [u64; 32],
+}
+
+#[account]
+pub struct Order {
+    pub owner: Pubkey,
+    pub side: Side,
+    pub limit: Option<u64>,
+    pub filled: u64,
+}
+
+impl Order {
+    pub const LEN: usize = 8 + 32 + 1 + (1 + 8) + 8;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Bid,
+    Ask,
+}
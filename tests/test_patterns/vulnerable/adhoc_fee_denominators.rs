@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod fee_market {
+    use super::*;
+
+    // VULNERABLE: config.fee is documented as basis points, but swap treats
+    // it as a percentage while withdraw treats it as basis points.
+    pub fn swap(ctx: Context<Trade>, amount: u64) -> Result<()> {
+        let fee = amount * ctx.accounts.config.fee / 100;
+        ctx.accounts.config.collected += fee;
+        Ok(())
+    }
+
+    pub fn withdraw(ctx: Context<Trade>, amount: u64) -> Result<()> {
+        let fee = amount
+            .checked_mul(ctx.accounts.config.fee)
+            .unwrap()
+            .checked_div(10_000)
+            .unwrap();
+        ctx.accounts.config.collected += fee;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Trade<'info> {
+    #[account(mut)]
+    pub config: Account<'info, Config>,
+    pub user: Signer<'info>,
+}
+
+#[account]
+pub struct Config {
+    /// Fee in basis points
+    pub fee: u64,
+    pub collected: u64,
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod fee_pool {
+    use super::*;
+
+    pub fn set_fee(ctx: Context<SetFee>, trade_fee_rate: u32) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        require!(trade_fee_rate <= 100_000, PoolError::FeeTooHigh);
+        pool.trade_fee_rate = trade_fee_rate;
+        Ok(())
+    }
+
+    pub fn pause(ctx: Context<Pause>) -> Result<()> {
+        ctx.accounts.pool.paused = true;
+        Ok(())
+    }
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        ctx.accounts.pool.total_deposits += amount;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct SetFee<'info> {
+    #[account(mut, has_one = authority)]
+    pub pool: Account<'info, Pool>,
+    /// CHECK: matched against pool.authority
+    pub authority: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Pause<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, Pool>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, Pool>,
+}
+
+#[account]
+pub struct Pool {
+    pub authority: Pubkey,
+    pub trade_fee_rate: u32,
+    pub paused: bool,
+    pub total_deposits: u64,
+}
+
+#[error_code]
+pub enum PoolError {
+    FeeTooHigh,
+}
@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+// Redeployed under a new address, but Anchor.toml still lists the old one.
+declare_id!("RwdPoo1111111111111111111111111111111111111");
+
+#[program]
+pub mod reward_pool {
+    use super::*;
+
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        ctx.accounts.pool.bump = ctx.bumps.pool;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(init, payer = payer, space = 8 + 1, seeds = [b"pool"], bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+pub struct Pool {
+    pub bump: u8,
+}
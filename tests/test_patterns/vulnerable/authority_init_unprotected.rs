@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod launchpad {
+    use super::*;
+
+    // VULNERABLE: the caller names the admin; the config PDA is a singleton
+    pub fn init_config(ctx: Context<InitConfig>, admin: Pubkey, fee_bps: u16) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.admin = admin;
+        config.fee_bps = fee_bps;
+        Ok(())
+    }
+
+    // VULNERABLE: the treasury authority is an account that never signs
+    pub fn init_treasury(ctx: Context<InitTreasury>) -> Result<()> {
+        ctx.accounts.treasury.authority = ctx.accounts.treasury_authority.key();
+        ctx.accounts.treasury.bump = ctx.bumps.treasury;
+        Ok(())
+    }
+
+    // VULNERABLE: first caller becomes the operator and nothing records it
+    pub fn init_registry(ctx: Context<InitRegistry>) -> Result<()> {
+        let registry = &mut ctx.accounts.registry;
+        registry.operator = ctx.accounts.payer.key();
+        registry.count = 0;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitConfig<'info> {
+    #[account(init, payer = payer, space = 8 + 32 + 2, seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitTreasury<'info> {
+    #[account(init, payer = payer, space = 8 + 32 + 1, seeds = [b"treasury"], bump)]
+    pub treasury: Account<'info, Treasury>,
+    /// CHECK: stored as the treasury authority
+    pub treasury_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitRegistry<'info> {
+    #[account(init, payer = payer, space = 8 + 32 + 8, seeds = [b"registry"], bump)]
+    pub registry: Account<'info, Registry>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+pub struct Config {
+    pub admin: Pubkey,
+    pub fee_bps: u16,
+}
+
+#[account]
+pub struct Treasury {
+    pub authority: Pubkey,
+    pub bump: u8,
+}
+
+#[account]
+pub struct Registry {
+    pub operator: Pubkey,
+    pub count: u64,
+}
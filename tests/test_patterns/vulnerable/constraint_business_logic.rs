@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+#[program]
+pub mod capped_pool {
+    use super::*;
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        ctx.accounts.pool.total_deposits += amount;
+        Ok(())
+    }
+
+    pub fn claim(ctx: Context<Claim>) -> Result<()> {
+        ctx.accounts.position.claimed = true;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64)]
+pub struct Deposit<'info> {
+    // Cap arithmetic, clock read and pause flag in one expression
+    #[account(
+        mut,
+        has_one = vault,
+        constraint = pool.total_deposits + amount <= pool.cap
+            && Clock::get()?.unix_timestamp >= pool.start_ts
+            && !pool.paused
+    )]
+    pub pool: Account<'info, Pool>,
+    // A relation between accounts: what constraints are for
+    #[account(mut, constraint = vault.mint == pool.mint @ PoolError::WrongMint)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut, constraint = user_token.owner == user.key())]
+    pub user_token: Account<'info, TokenAccount>,
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    pub pool: Account<'info, Pool>,
+    // Fee math inside the validation, with its own error code
+    #[account(
+        mut,
+        has_one = owner,
+        constraint = position.amount * pool.reward_rate / 10_000 > 0 @ PoolError::NothingToClaim
+    )]
+    pub position: Account<'info, Position>,
+    pub owner: Signer<'info>,
+}
+
+#[account]
+pub struct Pool {
+    pub vault: Pubkey,
+    pub mint: Pubkey,
+    pub total_deposits: u64,
+    pub cap: u64,
+    pub start_ts: i64,
+    pub paused: bool,
+    pub reward_rate: u64,
+}
+
+#[account]
+pub struct Position {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub claimed: bool,
+}
+
+#[error_code]
+pub enum PoolError {
+    #[msg("Wrong mint")]
+    WrongMint,
+    #[msg("Nothing to claim")]
+    NothingToClaim,
+}
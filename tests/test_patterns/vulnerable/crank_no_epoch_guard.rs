@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod reward_pool {
+    use super::*;
+
+    // VULNERABLE: anyone can crank, and every call adds the epoch's rewards
+    // again. last_update_epoch is written but never checked.
+    pub fn update_rewards(ctx: Context<UpdateRewards>) -> Result<()> {
+        let clock = Clock::get()?;
+        let pool = &mut ctx.accounts.pool;
+        let epoch_rewards = pool.reward_rate * pool.total_staked / 10_000;
+        pool.acc_reward_per_share += epoch_rewards;
+        pool.last_update_epoch = clock.epoch;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct UpdateRewards<'info> {
+    #[account(mut, seeds = [b"pool"], bump = pool.bump)]
+    pub pool: Account<'info, RewardPool>,
+}
+
+#[account]
+pub struct RewardPool {
+    pub bump: u8,
+    pub reward_rate: u64,
+    pub total_staked: u64,
+    pub acc_reward_per_share: u64,
+    pub last_update_epoch: u64,
+}
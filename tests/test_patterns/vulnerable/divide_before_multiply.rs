@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+use crate::libraries::full_math::MulDiv;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+const BPS_DENOMINATOR: u64 = 10_000;
+const LOT_SIZE: u64 = 1_000;
+
+#[program]
+pub mod reward_pool {
+    use super::*;
+
+    pub fn claim(ctx: Context<Claim>) -> Result<()> {
+        let pool = &ctx.accounts.pool;
+        let user = &mut ctx.accounts.user;
+        // VULNERABLE: the per-share quotient is truncated before scaling
+        let per_share = pool.rewards / pool.total_shares;
+        let owed = per_share * user.shares;
+        user.pending = user.pending.checked_add(owed).unwrap();
+        Ok(())
+    }
+
+    pub fn accrue(ctx: Context<Claim>, elapsed: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        // VULNERABLE: divides in the middle of the expression
+        pool.emitted = pool.rate / BPS_DENOMINATOR * elapsed;
+        // VULNERABLE: checked, but still in the wrong order
+        let bonus = pool.rate.checked_div(pool.total_shares).unwrap().checked_mul(elapsed).unwrap();
+        pool.rewards = pool.rewards.checked_add(bonus).unwrap();
+        Ok(())
+    }
+
+    pub fn charge(ctx: Context<Claim>, amount: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        // VULNERABLE: amount * fee_bps is formed in u64
+        let fee = amount * pool.fee_bps / BPS_DENOMINATOR;
+        pool.fees = pool.fees.checked_add(fee).unwrap();
+        Ok(())
+    }
+
+    pub fn safe(ctx: Context<Claim>, amount: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        // SAFE: widened multiply-then-divide
+        let fee = U128::from(amount).mul_div_ceil(U128::from(pool.fee_bps), U128::from(BPS_DENOMINATOR)).unwrap().as_u64();
+        // SAFE: rounding down to a whole lot is intended
+        let lots = amount / LOT_SIZE * LOT_SIZE;
+        // SAFE: narrow factors fit in u64
+        let scaled = (pool.decimals as u64) * (pool.tier as u64) / 2;
+        pool.fees = pool.fees.checked_add(fee + lots + scaled).unwrap();
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut)]
+    pub user: Account<'info, UserInfo>,
+}
+
+#[account]
+pub struct Pool {
+    pub rewards: u64,
+    pub total_shares: u64,
+    pub rate: u64,
+    pub emitted: u64,
+    pub fee_bps: u16,
+    pub fees: u64,
+    pub decimals: u8,
+    pub tier: u8,
+}
+
+#[account]
+pub struct UserInfo {
+    pub shares: u64,
+    pub pending: u64,
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod forked_amm {
+    use super::*;
+
+    pub fn create_pool(ctx: Context<CreatePool>, fee_rate: u32) -> Result<()> {
+        let pool = &mut ctx.accounts.pool_state;
+        pool.token_mint_0 = ctx.accounts.token_mint_0.key();
+        pool.token_mint_1 = ctx.accounts.token_mint_1.key();
+        pool.fee_rate = fee_rate;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct CreatePool<'info> {
+    /// Address paying to create the pool
+    #[account(mut)]
+    pub pool_creator: Signer<'info>,
+
+    /// Token_0 mint, the key must be smaller then token_1 mint.
+    pub token_mint_0: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Token_1 mint
+    pub token_mint_1: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Fee vault, must belong to the pool creator.
+    #[account(mut)]
+    pub fee_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(init, payer = pool_creator, space = 8 + PoolState::LEN)]
+    pub pool_state: Account<'info, PoolState>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+pub struct PoolState {
+    pub token_mint_0: Pubkey,
+    pub token_mint_1: Pubkey,
+    pub fee_rate: u32,
+}
+
+impl PoolState {
+    pub const LEN: usize = 32 + 32 + 4;
+}
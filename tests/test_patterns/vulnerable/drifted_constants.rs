@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+pub mod constants {
+    pub const MAX_FEE_BPS: u16 = 1_000;
+    pub const MIN_STAKE_LAMPORTS: u64 = 1_000_000;
+    pub const USER_STATS_SEED: &[u8] = b"user_stats";
+}
+
+pub mod config {
+    use super::*;
+
+    // Copied from constants.rs when the config instruction was split out
+    pub const MAX_FEE_BPS: u16 = 10_000;
+    pub const MIN_STAKE_LAMPORTS: u64 = 1_000_000;
+
+    pub fn check_fee(fee_bps: u16) -> Result<()> {
+        require!(fee_bps <= MAX_FEE_BPS, StakingError::FeeTooHigh);
+        Ok(())
+    }
+}
+
+#[program]
+pub mod staking {
+    use super::*;
+
+    pub fn set_fee(ctx: Context<SetFee>, fee_bps: u16) -> Result<()> {
+        config::check_fee(fee_bps)?;
+        ctx.accounts.pool.fee_bps = fee_bps;
+        Ok(())
+    }
+
+    pub fn register(ctx: Context<Register>) -> Result<()> {
+        ctx.accounts.user_stats.owner = ctx.accounts.owner.key();
+        Ok(())
+    }
+
+    pub fn claim(_ctx: Context<Claim>) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct SetFee<'info> {
+    pub admin: Signer<'info>,
+    #[account(mut, has_one = admin)]
+    pub pool: Account<'info, Pool>,
+}
+
+#[derive(Accounts)]
+pub struct Register<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(init, payer = owner, space = 8 + 32,
+              seeds = [constants::USER_STATS_SEED, owner.key().as_ref()], bump)]
+    pub user_stats: Account<'info, UserStats>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    pub owner: Signer<'info>,
+    #[account(seeds = [b"user-stats", owner.key().as_ref()], bump, has_one = owner)]
+    pub user_stats: Account<'info, UserStats>,
+}
+
+#[account]
+pub struct Pool {
+    pub admin: Pubkey,
+    pub fee_bps: u16,
+}
+
+#[account]
+pub struct UserStats {
+    pub owner: Pubkey,
+}
+
+#[error_code]
+pub enum StakingError {
+    FeeTooHigh,
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+#[program]
+pub mod rewards {
+    use super::*;
+
+    pub fn claim(ctx: Context<Claim>, tier_index: u8) -> Result<()> {
+        claim_handler(ctx, tier_index)
+    }
+
+    pub fn settle(ctx: Context<Settle>, slot: usize) -> Result<()> {
+        let book = &ctx.accounts.book;
+        require!(slot < book.orders.len(), ErrorCode::InvalidSlot);
+        let order = book.orders[slot];
+        let bump = *ctx.bumps.get("book").unwrap();
+        let data = ctx.accounts.oracle.try_borrow_data()?;
+        let price = u64::from_le_bytes(data[40..48].try_into().unwrap());
+        emit!(Settled { order, price, bump });
+        Ok(())
+    }
+}
+
+pub fn claim_handler(ctx: Context<Claim>, tier_index: u8) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let tier = config.tiers[tier_index as usize];
+    let reward = calc_reward(config.rate, tier, tier_index as usize)?;
+    ctx.accounts.user.pending = reward;
+    Ok(())
+}
+
+pub fn calc_reward(rate: u64, tier: u64, index: usize) -> Result<u64> {
+    let clock = Clock::get().unwrap();
+    let multipliers = [1u64, 2, 3, 5];
+    let elapsed = clock.unix_timestamp as u64;
+    let bonus = multipliers[index % multipliers.len()] + MULTIPLIERS[index];
+    Ok(rate * tier * elapsed * bonus)
+}
+
+pub fn unused(values: Vec<u64>, i: usize) -> u64 {
+    values[i] + values.first().copied().unwrap()
+}
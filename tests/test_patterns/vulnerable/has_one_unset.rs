@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+
+#[program]
+pub mod fees {
+    use super::*;
+
+    pub fn initialize(ctx: Context<Initialize>, fee_bps: u16) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.fee_bps = fee_bps;
+        pool.bump = ctx.bumps.pool;
+        Ok(())
+    }
+
+    pub fn set_operator(ctx: Context<SetOperator>, operator: Pubkey) -> Result<()> {
+        ctx.accounts.pool.operator = operator;
+        Ok(())
+    }
+
+    pub fn withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.collected = pool.collected.checked_sub(amount).unwrap();
+        Ok(())
+    }
+
+    pub fn crank(ctx: Context<Crank>) -> Result<()> {
+        ctx.accounts.pool.collected = 0;
+        Ok(())
+    }
+
+    pub fn open_vault(ctx: Context<OpenVault>) -> Result<()> {
+        ctx.accounts.vault.set_inner(Vault { owner: ctx.accounts.owner.key(), balance: 0 });
+        Ok(())
+    }
+
+    pub fn transfer_vault(ctx: Context<TransferVault>, new_owner: Pubkey) -> Result<()> {
+        ctx.accounts.vault.owner = new_owner;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(init, payer = payer, space = 8 + Pool::INIT_SPACE, seeds = [b"pool"], bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetOperator<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, Pool>,
+    pub payer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    #[account(mut, has_one = authority)]
+    pub pool: Account<'info, Pool>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Crank<'info> {
+    #[account(mut, constraint = pool.operator == operator.key())]
+    pub pool: Account<'info, Pool>,
+    pub operator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OpenVault<'info> {
+    #[account(init, payer = owner, space = 8 + Vault::INIT_SPACE)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct TransferVault<'info> {
+    #[account(mut, has_one = owner)]
+    pub vault: Account<'info, Vault>,
+    pub owner: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    pub authority: Pubkey,
+    pub operator: Pubkey,
+    pub fee_bps: u16,
+    pub collected: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    pub owner: Pubkey,
+    pub balance: u64,
+}
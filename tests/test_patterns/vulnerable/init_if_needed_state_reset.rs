@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod lending_market {
+    use super::*;
+
+    pub fn open_obligation(ctx: Context<OpenObligation>) -> Result<()> {
+        let obligation = &mut ctx.accounts.obligation;
+        obligation.market = ctx.accounts.market.key();
+        obligation.owner = ctx.accounts.payer.key();
+        obligation.borrowed_amount = 0;
+        obligation.deposited_amount = 0;
+        Ok(())
+    }
+
+    pub fn register(ctx: Context<Register>) -> Result<()> {
+        let profile = &mut ctx.accounts.profile;
+        profile.owner = ctx.accounts.user.key();
+        profile.bump = ctx.bumps.profile;
+        Ok(())
+    }
+
+    pub fn join_market(ctx: Context<JoinMarket>) -> Result<()> {
+        ctx.accounts.process()
+    }
+}
+
+#[derive(Accounts)]
+pub struct OpenObligation<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub market: Account<'info, Market>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + Obligation::LEN,
+        seeds = [b"obligation", market.key().as_ref()],
+        bump,
+    )]
+    pub obligation: Account<'info, Obligation>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Register<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + 33,
+        seeds = [b"profile", user.key().as_ref()],
+        bump,
+    )]
+    pub profile: Account<'info, Profile>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct JoinMarket<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub market: Account<'info, Market>,
+    #[account(init_if_needed, payer = user, space = 8 + 48)]
+    pub member: Account<'info, Member>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> JoinMarket<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        require!(!self.member.initialized, LendingError::AlreadyMember);
+        self.member.initialized = true;
+        self.member.authority = self.user.key();
+        self.member.reward_debt = 0;
+        Ok(())
+    }
+}
+
+#[account]
+pub struct Market {
+    pub admin: Pubkey,
+}
+
+#[account]
+pub struct Obligation {
+    pub market: Pubkey,
+    pub owner: Pubkey,
+    pub borrowed_amount: u64,
+    pub deposited_amount: u64,
+}
+
+impl Obligation {
+    pub const LEN: usize = 32 + 32 + 8 + 8;
+}
+
+#[account]
+pub struct Profile {
+    pub owner: Pubkey,
+    pub bump: u8,
+}
+
+#[account]
+pub struct Member {
+    pub initialized: bool,
+    pub authority: Pubkey,
+    pub reward_debt: u64,
+}
+
+#[error_code]
+pub enum LendingError {
+    AlreadyMember,
+}
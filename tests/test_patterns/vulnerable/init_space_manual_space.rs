@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+#[program]
+pub mod vaults {
+    use super::*;
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    pub owner: Pubkey,
+    #[max_len(32)]
+    pub label: String,
+    #[max_len(4)]
+    pub members: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+#[account]
+pub struct Config {
+    pub admin: Pubkey,
+    pub fee_bps: u16,
+}
+
+impl Space for Config {
+    const INIT_SPACE: usize = 8 + 32 + 2;
+}
+
+#[derive(Accounts)]
+pub struct CreateVault<'info> {
+    #[account(init, payer = owner, space = 8 + 32 + 4 + 32 + 4 + 4 * 32)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateVaultPadded<'info> {
+    #[account(init, payer = owner, space = 8 + 32 + 36 + 132 + 1 + 64)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateVaultDerived<'info> {
+    #[account(init, payer = owner, space = 8 + Vault::INIT_SPACE)]
+    pub vault: Account<'info, Vault>,
+    #[account(init, payer = owner, space = 8 + Config::INIT_SPACE)]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
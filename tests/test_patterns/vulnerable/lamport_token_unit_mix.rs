@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod stake_pool {
+    use super::*;
+
+    // VULNERABLE: user_msol.amount is mSOL, total_lamports is SOL.
+    pub fn deposit_msol(ctx: Context<DepositMsol>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let incoming = ctx.accounts.user_msol.amount;
+        pool.total_lamports = pool.total_lamports.checked_add(incoming).unwrap();
+        Ok(())
+    }
+
+    // VULNERABLE: compares a share count against a lamport balance.
+    pub fn withdraw(ctx: Context<DepositMsol>, shares: u64) -> Result<()> {
+        let vault_lamports = ctx.accounts.pool_vault.lamports();
+        require!(shares <= vault_lamports, ErrorCode::InsufficientFunds);
+        Ok(())
+    }
+
+    // SAFE: converted through the pool's exchange rate first.
+    pub fn deposit_msol_checked(ctx: Context<DepositMsol>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let value = pool.msol_to_sol(ctx.accounts.user_msol.amount)?;
+        pool.total_lamports = pool.total_lamports.checked_add(value).unwrap();
+        let needed = ctx.accounts.msol_mint.supply * pool.msol_price / PRICE_DENOMINATOR;
+        require!(pool.total_lamports >= needed, ErrorCode::InsufficientFunds);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct DepositMsol<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut)]
+    pub user_msol: Account<'info, TokenAccount>,
+    pub msol_mint: Account<'info, Mint>,
+    /// CHECK: SOL vault PDA
+    #[account(mut)]
+    pub pool_vault: AccountInfo<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[account]
+pub struct Pool {
+    pub total_lamports: u64,
+    pub msol_price: u64,
+}
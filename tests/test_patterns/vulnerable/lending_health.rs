@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+const MAX_LTV_BPS: u64 = 8_000;
+const LIQUIDATION_LTV_BPS: u64 = 9_000;
+
+#[program]
+pub mod lending_health {
+    use super::*;
+
+    pub fn borrow(ctx: Context<Update>, amount: u64) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        position.debt += amount;
+        let health = position.collateral * 100 / (position.debt + position.accrued_interest);
+        require!(health >= 100, LendingError::Unhealthy);
+        Ok(())
+    }
+
+    // VULNERABLE: the LTV leaves out the accrued interest
+    pub fn withdraw(ctx: Context<Update>, amount: u64) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        position.collateral -= amount;
+        let ltv = position.debt.checked_mul(10_000).unwrap() / position.collateral;
+        require!(ltv <= MAX_LTV_BPS, LendingError::Unhealthy);
+        Ok(())
+    }
+
+    // VULNERABLE: liquidates only positions below the liquidation LTV
+    pub fn liquidate(ctx: Context<Update>, repay: u64) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        let owed = position.debt + position.accrued_interest;
+        let ltv = owed * 10_000 / position.collateral;
+        require!(ltv < LIQUIDATION_LTV_BPS, LendingError::Healthy);
+        position.debt -= repay;
+        Ok(())
+    }
+}
+
+// VULNERABLE: the price converts the collateral only
+pub fn health_factor(position: &Position, price: u64) -> u64 {
+    position.collateral * price / (position.debt + position.accrued_interest)
+}
+
+#[derive(Accounts)]
+pub struct Update<'info> {
+    #[account(mut, has_one = owner)]
+    pub position: Account<'info, Position>,
+    pub owner: Signer<'info>,
+}
+
+#[account]
+pub struct Position {
+    pub owner: Pubkey,
+    pub collateral: u64,
+    pub debt: u64,
+    pub accrued_interest: u64,
+}
+
+#[error_code]
+pub enum LendingError {
+    Unhealthy,
+    Healthy,
+}
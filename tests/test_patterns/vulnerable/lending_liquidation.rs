@@ -0,0 +1,147 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+const BONUS_BPS: u64 = 500;
+
+#[program]
+pub mod lending_liquidation {
+    use super::*;
+
+    pub fn borrow(ctx: Context<Update>, amount: u64) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        require!(position.collateral * 8_000 / 10_000 >= position.debt + amount, LendingError::Unhealthy);
+        position.debt += amount;
+        Ok(())
+    }
+
+    // VULNERABLE: checks health, changes nothing
+    pub fn liquidate_check(ctx: Context<Inspect>) -> Result<()> {
+        let position = &ctx.accounts.position;
+        require!(position.collateral < position.debt, LendingError::Healthy);
+        Ok(())
+    }
+
+    // VULNERABLE: anyone writes the collateral down, nothing is transferred
+    pub fn liquidate_write_off(ctx: Context<Update>, repay: u64) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        require!(position.collateral < position.debt, LendingError::Healthy);
+        position.debt -= repay;
+        position.collateral -= repay;
+        Ok(())
+    }
+
+    // VULNERABLE: anyone may call it, the collateral goes to the treasury
+    pub fn liquidate_to_treasury(ctx: Context<LiquidateToTreasury>, repay: u64) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        require!(position.collateral < position.debt, LendingError::Healthy);
+        position.debt -= repay;
+        position.collateral -= repay;
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.treasury.to_account_info(),
+                    authority: ctx.accounts.pool.to_account_info(),
+                },
+            ),
+            repay,
+        )?;
+        Ok(())
+    }
+
+    pub fn liquidate(ctx: Context<Liquidate>, repay: u64) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        require!(position.collateral < position.debt, LendingError::Healthy);
+        let seized = repay * (10_000 + BONUS_BPS) / 10_000;
+        position.debt -= repay;
+        position.collateral -= seized;
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.liquidator_collateral.to_account_info(),
+                    authority: ctx.accounts.pool.to_account_info(),
+                },
+            ),
+            seized,
+        )?;
+        Ok(())
+    }
+
+    pub fn liquidate_by_keeper(ctx: Context<LiquidateByKeeper>, repay: u64) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        require!(position.collateral < position.debt, LendingError::Healthy);
+        position.debt -= repay;
+        position.collateral -= repay;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Update<'info> {
+    #[account(mut)]
+    pub position: Account<'info, Position>,
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Inspect<'info> {
+    pub position: Account<'info, Position>,
+    pub liquidator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct LiquidateToTreasury<'info> {
+    pub pool: Account<'info, Pool>,
+    #[account(mut)]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut, address = pool.treasury)]
+    pub treasury: Account<'info, TokenAccount>,
+    pub liquidator: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Liquidate<'info> {
+    pub pool: Account<'info, Pool>,
+    #[account(mut)]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut, token::authority = liquidator)]
+    pub liquidator_collateral: Account<'info, TokenAccount>,
+    pub liquidator: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct LiquidateByKeeper<'info> {
+    #[account(has_one = keeper)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut)]
+    pub position: Account<'info, Position>,
+    pub keeper: Signer<'info>,
+}
+
+#[account]
+pub struct Pool {
+    pub keeper: Pubkey,
+    pub treasury: Pubkey,
+}
+
+#[account]
+pub struct Position {
+    pub owner: Pubkey,
+    pub collateral: u64,
+    pub debt: u64,
+}
+
+#[error_code]
+pub enum LendingError {
+    Unhealthy,
+    Healthy,
+}
@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod lending {
+    use super::*;
+
+    pub fn open_obligation(ctx: Context<OpenObligation>) -> Result<()> {
+        let obligation = &mut ctx.accounts.obligation;
+        obligation.owner = ctx.accounts.owner.key();
+        obligation.market = ctx.accounts.market.key();
+        Ok(())
+    }
+
+    pub fn deposit_collateral(ctx: Context<DepositCollateral>, amount: u64) -> Result<()> {
+        ctx.accounts.obligation.collateral += amount;
+        Ok(())
+    }
+
+    pub fn borrow(ctx: Context<Borrow>, amount: u64) -> Result<()> {
+        let obligation = &mut ctx.accounts.obligation;
+        require!(obligation.collateral * 75 / 100 >= obligation.debt + amount, LendingError::Undercollateralized);
+        obligation.debt += amount;
+        Ok(())
+    }
+
+    pub fn withdraw_collateral(ctx: Context<WithdrawCollateral>, amount: u64) -> Result<()> {
+        let obligation = &mut ctx.accounts.obligation;
+        require_keys_eq!(obligation.owner, ctx.accounts.owner.key(), LendingError::WrongOwner);
+        obligation.collateral -= amount;
+        Ok(())
+    }
+
+    pub fn liquidate(ctx: Context<Liquidate>, repay: u64) -> Result<()> {
+        ctx.accounts.obligation.debt -= repay;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct OpenObligation<'info> {
+    pub market: Account<'info, Market>,
+    #[account(init, payer = owner, space = 8 + 32 + 32 + 8 + 8)]
+    pub obligation: Account<'info, Obligation>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositCollateral<'info> {
+    #[account(mut, has_one = owner)]
+    pub obligation: Account<'info, Obligation>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Borrow<'info> {
+    pub market: Account<'info, Market>,
+    #[account(mut)]
+    pub obligation: Account<'info, Obligation>,
+    pub borrower: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawCollateral<'info> {
+    #[account(mut)]
+    pub obligation: Account<'info, Obligation>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Liquidate<'info> {
+    #[account(mut)]
+    pub obligation: Account<'info, Obligation>,
+    pub liquidator: Signer<'info>,
+}
+
+#[account]
+pub struct Market {
+    pub authority: Pubkey,
+    pub total_deposits: u64,
+}
+
+#[account]
+pub struct Obligation {
+    pub owner: Pubkey,
+    pub market: Pubkey,
+    pub collateral: u64,
+    pub debt: u64,
+}
+
+#[error_code]
+pub enum LendingError {
+    Undercollateralized,
+    WrongOwner,
+}
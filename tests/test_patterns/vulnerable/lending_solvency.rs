@@ -0,0 +1,97 @@
+use anchor_lang::prelude::*;
+
+const LTV_BPS: u64 = 7_500;
+
+#[program]
+pub mod lending {
+    use super::*;
+
+    pub fn deposit(ctx: Context<Update>, amount: u64) -> Result<()> {
+        ctx.accounts.position.collateral += amount;
+        Ok(())
+    }
+
+    // VULNERABLE: existing debt left out
+    pub fn borrow(ctx: Context<Update>, amount: u64) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        require!(position.collateral >= amount, LendingError::Undercollateralized);
+        position.debt += amount;
+        Ok(())
+    }
+
+    // VULNERABLE: debt included, lent at 100% of the collateral
+    pub fn borrow_max(ctx: Context<Update>, amount: u64) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        let new_debt = position.debt + amount;
+        if new_debt > position.collateral {
+            return err!(LendingError::Undercollateralized);
+        }
+        position.debt = new_debt;
+        Ok(())
+    }
+
+    pub fn borrow_checked(ctx: Context<Update>, amount: u64) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        position.debt += amount;
+        check_solvency(position)
+    }
+
+    // VULNERABLE: never reads the debt
+    pub fn withdraw(ctx: Context<Update>, amount: u64) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        require!(position.collateral >= amount, LendingError::InsufficientBalance);
+        position.collateral -= amount;
+        Ok(())
+    }
+
+    pub fn withdraw_checked(ctx: Context<Update>, amount: u64) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        position.collateral = position.collateral.checked_sub(amount).ok_or(LendingError::InsufficientBalance)?;
+        check_solvency(position)
+    }
+
+    pub fn withdraw_repaid(ctx: Context<WithdrawRepaid>, amount: u64) -> Result<()> {
+        ctx.accounts.position.collateral -= amount;
+        Ok(())
+    }
+
+    pub fn liquidate(ctx: Context<Update>, repay: u64, seized: u64) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        position.debt -= repay;
+        position.collateral -= seized;
+        Ok(())
+    }
+}
+
+fn check_solvency(position: &Position) -> Result<()> {
+    require!(position.collateral * LTV_BPS / 10_000 >= position.debt, LendingError::Undercollateralized);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Update<'info> {
+    #[account(mut, has_one = owner)]
+    pub position: Account<'info, Position>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawRepaid<'info> {
+    #[account(mut, has_one = owner, constraint = position.debt == 0 @ LendingError::OpenLoan)]
+    pub position: Account<'info, Position>,
+    pub owner: Signer<'info>,
+}
+
+#[account]
+pub struct Position {
+    pub owner: Pubkey,
+    pub collateral: u64,
+    pub debt: u64,
+}
+
+#[error_code]
+pub enum LendingError {
+    Undercollateralized,
+    InsufficientBalance,
+    OpenLoan,
+}
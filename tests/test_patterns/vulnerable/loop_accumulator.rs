@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+#[program]
+pub mod rewards {
+    use super::*;
+
+    pub fn set_rate(ctx: Context<Admin>, rate: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.rates.push(rate);
+        if pool.tiers.len() < MAX_TIERS {
+            pool.tiers.push(rate);
+        }
+        Ok(())
+    }
+
+    pub fn claim(ctx: Context<Claim>) -> Result<()> {
+        let pool = &ctx.accounts.pool;
+        let owed = accrued(&pool.rates, ctx.accounts.position.amount);
+        let mut bonus: u64 = 0;
+        for tier in pool.tiers.iter() {
+            bonus = bonus.checked_add(tier * ctx.accounts.position.amount).ok_or(ErrorCode::Overflow)?;
+        }
+        let mut weight: u64 = 0;
+        for i in 0..pool.caps.len() {
+            weight += pool.caps[i] * 2;
+        }
+        let mut count = 0;
+        for _rate in pool.rates.iter() {
+            count += 1;
+        }
+        Ok(())
+    }
+
+    pub fn settle(ctx: Context<Claim>, price: u64) -> Result<()> {
+        let mut total: u64 = 0;
+        for info in ctx.remaining_accounts.iter() {
+            let value = info.lamports().checked_mul(price).ok_or(ErrorCode::Overflow)?;
+            total = total.checked_add(value).ok_or(ErrorCode::Overflow)?;
+        }
+        Ok(())
+    }
+}
+
+fn accrued(rates: &Vec<u64>, amount: u64) -> u64 {
+    let mut sum = 0;
+    for rate in rates.iter() {
+        sum += rate * amount;
+    }
+    sum
+}
+
+#[account]
+pub struct Pool {
+    pub rates: Vec<u64>,
+    pub tiers: Vec<u64>,
+    #[max_len(8)]
+    pub caps: Vec<u64>,
+}
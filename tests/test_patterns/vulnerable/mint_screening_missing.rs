@@ -0,0 +1,134 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+#[program]
+pub mod lending_market {
+    use super::*;
+
+    pub fn create_reserve(ctx: Context<CreateReserve>) -> Result<()> {
+        let reserve = &mut ctx.accounts.reserve;
+        reserve.collateral_mint = ctx.accounts.collateral_mint.key();
+        reserve.vault = ctx.accounts.vault.key();
+        Ok(())
+    }
+
+    pub fn create_farm(ctx: Context<CreateFarm>) -> Result<()> {
+        // Mint authority screened, freeze authority never
+        require!(
+            ctx.accounts.reward_mint.mint_authority.is_none(),
+            LendingError::MintableReward
+        );
+        ctx.accounts.farm.reward_mint = ctx.accounts.reward_mint.key();
+        Ok(())
+    }
+
+    pub fn create_pool(ctx: Context<CreatePool>) -> Result<()> {
+        check_freeze_authority(&ctx.accounts.pool_mint, "pool_mint")?;
+        ctx.accounts.pool.mint = ctx.accounts.pool_mint.key();
+        Ok(())
+    }
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        ctx.accounts.reserve.deposited += amount;
+        Ok(())
+    }
+}
+
+pub fn check_freeze_authority(mint: &Mint, field_name: &str) -> Result<()> {
+    if mint.freeze_authority.is_none() {
+        Ok(())
+    } else {
+        msg!("Mint {} must have freeze authority not set", field_name);
+        Err(LendingError::FreezableMint.into())
+    }
+}
+
+#[derive(Accounts)]
+pub struct CreateReserve<'info> {
+    #[account(init, payer = payer, space = 8 + Reserve::INIT_SPACE)]
+    pub reserve: Account<'info, Reserve>,
+    // Any mint, freeze and mint authority unchecked
+    pub collateral_mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = payer,
+        token::mint = collateral_mint,
+        token::authority = reserve,
+        seeds = [b"vault", collateral_mint.key().as_ref()],
+        bump,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateFarm<'info> {
+    #[account(init, payer = payer, space = 8 + Farm::INIT_SPACE)]
+    pub farm: Account<'info, Farm>,
+    pub reward_mint: Account<'info, Mint>,
+    #[account(init, payer = payer, token::mint = reward_mint, token::authority = farm)]
+    pub reward_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreatePool<'info> {
+    #[account(init, payer = payer, space = 8 + Pool::INIT_SPACE)]
+    pub pool: Account<'info, Pool>,
+    // Screened through check_freeze_authority
+    pub pool_mint: Account<'info, Mint>,
+    #[account(init, payer = payer, token::mint = pool_mint, token::authority = pool)]
+    pub pool_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    // The reserve's own mint, pinned by has_one
+    #[account(mut, has_one = collateral_mint, has_one = vault)]
+    pub reserve: Account<'info, Reserve>,
+    pub collateral_mint: Account<'info, Mint>,
+    #[account(mut, token::mint = collateral_mint, token::authority = reserve)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = collateral_mint, token::authority = user)]
+    pub user_token: Account<'info, TokenAccount>,
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Reserve {
+    pub collateral_mint: Pubkey,
+    pub vault: Pubkey,
+    pub deposited: u64,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Farm {
+    pub reward_mint: Pubkey,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    pub mint: Pubkey,
+}
+
+#[error_code]
+pub enum LendingError {
+    #[msg("Reward mint can still be minted")]
+    MintableReward,
+    #[msg("Mint has a freeze authority")]
+    FreezableMint,
+}
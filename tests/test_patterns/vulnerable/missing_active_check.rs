@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod lending_pool {
+    use super::*;
+
+    pub fn fund_pool(ctx: Context<AdminPool>, reward_rate: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.reward_rate = reward_rate;
+        pool.is_active = true;
+        Ok(())
+    }
+
+    pub fn deposit(ctx: Context<UserPool>, amount: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        require!(pool.is_active, ErrorCode::PoolNotActive);
+        pool.total_deposits += amount;
+        Ok(())
+    }
+
+    pub fn borrow(ctx: Context<UserPool>, amount: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        if !pool.is_active {
+            return err!(ErrorCode::PoolNotActive);
+        }
+        pool.total_borrows += amount;
+        Ok(())
+    }
+
+    // VULNERABLE: stake runs before fund_pool has set the reward rate.
+    pub fn stake(ctx: Context<UserPool>, amount: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.total_staked += amount;
+        pool.reward_index = pool.reward_rate * pool.total_staked;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct AdminPool<'info> {
+    #[account(mut, has_one = admin)]
+    pub pool: Account<'info, Pool>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UserPool<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, Pool>,
+    pub user: Signer<'info>,
+}
+
+#[account]
+pub struct Pool {
+    pub admin: Pubkey,
+    pub is_active: bool,
+    pub reward_rate: u64,
+    pub reward_index: u64,
+    pub total_deposits: u64,
+    pub total_borrows: u64,
+    pub total_staked: u64,
+}
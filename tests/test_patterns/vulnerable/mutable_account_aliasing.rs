@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod points {
+    use super::*;
+
+    pub fn transfer_points(ctx: Context<TransferPoints>, amount: u64) -> Result<()> {
+        let from = &mut ctx.accounts.from;
+        let to = &mut ctx.accounts.to;
+        from.points = from.points.checked_sub(amount).unwrap();
+        to.points = to.points.checked_add(amount).unwrap();
+        Ok(())
+    }
+
+    pub fn airdrop(ctx: Context<Airdrop>, amount: u64) -> Result<()> {
+        ctx.accounts.treasury.points -= amount * ctx.remaining_accounts.len() as u64;
+        for info in ctx.remaining_accounts.iter() {
+            let mut member = Account::<Member>::try_from(info)?;
+            member.points += amount;
+            member.exit(&crate::ID)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct TransferPoints<'info> {
+    #[account(mut, has_one = owner)]
+    pub from: Account<'info, Member>,
+    #[account(mut)]
+    pub to: Account<'info, Member>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Airdrop<'info> {
+    #[account(mut, has_one = admin)]
+    pub treasury: Account<'info, Member>,
+    pub admin: Signer<'info>,
+}
+
+#[account]
+pub struct Member {
+    pub owner: Pubkey,
+    pub admin: Pubkey,
+    pub points: u64,
+}
@@ -0,0 +1,131 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    metadata::{MasterEditionAccount, Metadata, MetadataAccount},
+    token::{self, Mint, Token, TokenAccount, Transfer},
+};
+
+#[program]
+pub mod nft_vault {
+    use super::*;
+
+    pub fn stake(ctx: Context<Stake>) -> Result<()> {
+        let metadata = &ctx.accounts.nft_metadata;
+        // Collection key compared, `verified` never checked
+        require_keys_eq!(
+            metadata.collection.as_ref().unwrap().key,
+            ctx.accounts.vault.collection,
+            VaultError::InvalidCollection
+        );
+        token::transfer(ctx.accounts.transfer_ctx(), 1)?;
+        Ok(())
+    }
+
+    pub fn unstake(ctx: Context<Unstake>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn deposit(ctx: Context<Deposit>) -> Result<()> {
+        require!(ctx.accounts.nft_mint.supply == 1, VaultError::NotNft);
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.owner_nft.to_account_info(),
+                    to: ctx.accounts.vault_nft.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            1,
+        )?;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+    // No decimals or supply check: any token with a metadata account passes
+    pub nft_mint: Account<'info, Mint>,
+    // Not derived from nft_mint: any metadata account is accepted
+    pub nft_metadata: Box<Account<'info, MetadataAccount>>,
+    #[account(mut, associated_token::mint = nft_mint, associated_token::authority = staker)]
+    pub nft_token: Account<'info, TokenAccount>,
+    // Custody owned by the staker, not by the program
+    #[account(
+        init_if_needed,
+        payer = staker,
+        associated_token::mint = nft_mint,
+        associated_token::authority = staker,
+    )]
+    pub nft_custody: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub staker: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Stake<'info> {
+    pub fn transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.nft_token.to_account_info(),
+                to: self.nft_custody.to_account_info(),
+                authority: self.staker.to_account_info(),
+            },
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct Unstake<'info> {
+    pub vault: Account<'info, Vault>,
+    // decimals checked, supply never
+    #[account(mint::decimals = 0)]
+    pub nft_mint: Account<'info, Mint>,
+    // Derived under this program instead of Token Metadata
+    #[account(
+        seeds = [b"metadata", Metadata::id().as_ref(), nft_mint.key().as_ref()],
+        bump,
+        constraint = nft_metadata.collection.as_ref().unwrap().verified,
+    )]
+    pub nft_metadata: Box<Account<'info, MetadataAccount>>,
+    // Derivation program taken from an unchecked account
+    #[account(
+        seeds = [b"metadata", metadata_program.key().as_ref(), nft_mint.key().as_ref(), b"edition"],
+        seeds::program = metadata_program.key(),
+        bump,
+    )]
+    pub nft_edition: Box<Account<'info, MasterEditionAccount>>,
+    /// CHECK: token metadata program
+    pub metadata_program: UncheckedAccount<'info>,
+    pub staker: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mint::decimals = 0)]
+    pub nft_mint: Account<'info, Mint>,
+    #[account(mut, token::mint = nft_mint, token::authority = owner)]
+    pub owner_nft: Account<'info, TokenAccount>,
+    // Custody with no owner constraint at all
+    #[account(mut, token::mint = nft_mint)]
+    pub vault_nft: Account<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[account]
+pub struct Vault {
+    pub authority: Pubkey,
+    pub collection: Pubkey,
+}
+
+#[error_code]
+pub enum VaultError {
+    #[msg("Invalid collection")]
+    InvalidCollection,
+    #[msg("Not an NFT")]
+    NotNft,
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod packed_vault {
+    use super::*;
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let mut vault = ctx.accounts.vault.load_mut()?;
+        let total = &mut vault.total_deposits;
+        *total += amount;
+        vault.record(amount);
+        msg!("total deposits: {}", vault.total_deposits);
+        Ok(())
+    }
+
+    pub fn report(ctx: Context<Report>) -> Result<()> {
+        let vault = ctx.accounts.vault.load()?;
+        let bump = vault.bump;
+        let authority = &vault.authority;
+        msg!("bump {} authority {}", bump, authority);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut, has_one = authority)]
+    pub vault: AccountLoader<'info, Vault>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Report<'info> {
+    pub vault: AccountLoader<'info, Vault>,
+}
+
+#[account(zero_copy(unsafe))]
+#[repr(C, packed)]
+pub struct Vault {
+    pub bump: u8,
+    pub authority: Pubkey,
+    pub total_deposits: u64,
+    pub history: [u64; 8],
+    pub reserved: [u8; 64],
+}
+
+impl Vault {
+    pub fn record(&mut self, amount: u64) {
+        let slot = self.history.iter().position(|h| *h == 0).unwrap_or(0);
+        self.history[slot] = amount;
+    }
+}
@@ -0,0 +1,100 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+pub const POOL_SEED: &[u8] = b"pool";
+
+#[program]
+pub mod reward_pool {
+    use super::*;
+
+    pub fn claim(ctx: Context<Claim>, amount: u64) -> Result<()> {
+        pay_rewards(&ctx.accounts.pool, &ctx.accounts.pool_vault, &ctx.accounts.recipient, &ctx.accounts.token_program, amount)
+    }
+
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        ctx.accounts.position.amount -= amount;
+        transfer_from_pool_vault_to_user(
+            &ctx.accounts.pool,
+            &ctx.accounts.pool_vault,
+            &ctx.accounts.owner_token,
+            &ctx.accounts.token_program,
+            amount,
+        )
+    }
+}
+
+pub fn pay_rewards<'info>(
+    pool: &Account<'info, Pool>,
+    vault: &Account<'info, TokenAccount>,
+    recipient: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, PoolError::ZeroAmount);
+    transfer_from_pool_vault_to_user(pool, vault, recipient, token_program, amount)
+}
+
+pub fn transfer_from_pool_vault_to_user<'info>(
+    pool: &Account<'info, Pool>,
+    vault: &Account<'info, TokenAccount>,
+    to: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[POOL_SEED, &[pool.bump]]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Transfer {
+                from: vault.to_account_info(),
+                to: to.to_account_info(),
+                authority: pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )
+}
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    #[account(seeds = [POOL_SEED], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut, token::authority = pool)]
+    pub pool_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub recipient: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(seeds = [POOL_SEED], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut, token::authority = pool)]
+    pub pool_vault: Account<'info, TokenAccount>,
+    #[account(mut, has_one = owner)]
+    pub position: Account<'info, Position>,
+    pub owner: Signer<'info>,
+    #[account(mut, token::authority = owner)]
+    pub owner_token: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[account]
+pub struct Pool {
+    pub bump: u8,
+}
+
+#[account]
+pub struct Position {
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+#[error_code]
+pub enum PoolError {
+    ZeroAmount,
+}
@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+
+pub const POSITION_SEED: &[u8] = b"pos";
+
+#[program]
+pub mod lending {
+    use super::*;
+
+    pub fn open_position(ctx: Context<OpenPosition>) -> Result<()> {
+        ctx.accounts.position.owner = ctx.accounts.owner.key();
+        Ok(())
+    }
+
+    pub fn create_pool(ctx: Context<CreatePool>, fee_bps: u16) -> Result<()> {
+        ctx.accounts.pool_config.fee_bps = fee_bps;
+        Ok(())
+    }
+
+    pub fn create_market(ctx: Context<CreateMarket>, name: String) -> Result<()> {
+        ctx.accounts.market.name = name;
+        Ok(())
+    }
+
+    pub fn open_order(ctx: Context<OpenOrder>, order_id: u64) -> Result<()> {
+        ctx.accounts.order.id = order_id;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct OpenPosition<'info> {
+    #[account(init, payer = owner, space = 8 + 32, seeds = [POSITION_SEED, owner.key().as_ref()], bump)]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreatePool<'info> {
+    // Same prefix, same 32-byte key: the pool's key passed as `owner` derives this address
+    #[account(init, payer = admin, space = 8 + 2, seeds = [b"pos", pool.key().as_ref()], bump)]
+    pub pool_config: Account<'info, PoolConfig>,
+    /// CHECK: any pool
+    pub pool: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct CreateMarket<'info> {
+    // A string seed after the prefix can spell out any 32 bytes
+    #[account(init, payer = admin, space = 8 + 64, seeds = [b"pos", name.as_bytes()], bump)]
+    pub market: Account<'info, Market>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(order_id: u64)]
+pub struct OpenOrder<'info> {
+    // Prefix plus 32 + 8 bytes never lines up with prefix plus 32
+    #[account(init, payer = owner, space = 8 + 8,
+              seeds = [b"pos", owner.key().as_ref(), order_id.to_le_bytes().as_ref()], bump)]
+    pub order: Account<'info, Order>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+pub struct Position {
+    pub owner: Pubkey,
+}
+
+#[account]
+pub struct PoolConfig {
+    pub fee_bps: u16,
+}
+
+#[account]
+pub struct Market {
+    pub name: String,
+}
+
+#[account]
+pub struct Order {
+    pub id: u64,
+}
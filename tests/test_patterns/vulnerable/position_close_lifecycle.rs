@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod positions {
+    use super::*;
+
+    pub fn open_position(ctx: Context<OpenPosition>) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        position.owner = ctx.accounts.owner.key();
+        position.payer = ctx.accounts.owner.key();
+        Ok(())
+    }
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        ctx.accounts.position.deposited += amount;
+        Ok(())
+    }
+
+    pub fn close_position(ctx: Context<ClosePosition>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn close_expired(ctx: Context<CloseExpired>) -> Result<()> {
+        require_keys_eq!(ctx.accounts.payer.key(), ctx.accounts.position.payer);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct OpenPosition<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(init, payer = owner, space = 8 + 72, seeds = [b"position", owner.key().as_ref()], bump)]
+    pub position: Account<'info, Position>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + 72,
+        seeds = [b"position", owner.key().as_ref()],
+        bump,
+    )]
+    pub position: Account<'info, Position>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClosePosition<'info> {
+    pub owner: Signer<'info>,
+    #[account(mut, has_one = owner, seeds = [b"position", owner.key().as_ref()], bump, close = receiver)]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub receiver: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseExpired<'info> {
+    pub cranker: Signer<'info>,
+    #[account(mut, close = payer)]
+    pub position: Account<'info, Position>,
+    #[account(mut)]
+    pub payer: SystemAccount<'info>,
+}
+
+#[account]
+pub struct Position {
+    pub owner: Pubkey,
+    pub payer: Pubkey,
+    pub deposited: u64,
+}
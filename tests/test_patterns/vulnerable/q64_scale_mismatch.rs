@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+pub mod fixed_point_64 {
+    pub const Q64: u128 = 1u128 << 64;
+}
+
+#[program]
+pub mod farm {
+    use super::*;
+
+    // VULNERABLE: the fee growth delta is Q64.64, fees_owed is a token amount.
+    pub fn collect_fees(ctx: Context<Collect>) -> Result<()> {
+        let pool = &ctx.accounts.pool;
+        let position = &mut ctx.accounts.position;
+        let growth_delta = pool.fee_growth_global_x64.wrapping_sub(position.fee_growth_inside_last_x64);
+        let fees_owed = growth_delta * position.liquidity as u128;
+        position.tokens_owed += fees_owed as u64;
+        Ok(())
+    }
+
+    // VULNERABLE: a plain token amount added to a Q64.64 accumulator.
+    pub fn fund_rewards(ctx: Context<Collect>, reward_amount: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.reward_growth_global_x64 = pool
+            .reward_growth_global_x64
+            .checked_add(reward_amount as u128)
+            .unwrap();
+        Ok(())
+    }
+
+    // VULNERABLE: the documented Q64.64 price compared with a plain limit.
+    pub fn swap(ctx: Context<Collect>, min_price: u128) -> Result<()> {
+        require!(ctx.accounts.pool.price >= min_price, ErrorCode::PriceLimit);
+        Ok(())
+    }
+
+    // SAFE: converted through Q64, and growth that stays Q64.64.
+    pub fn collect_fees_checked(ctx: Context<Collect>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let position = &mut ctx.accounts.position;
+        let growth_delta = pool.fee_growth_global_x64.wrapping_sub(position.fee_growth_inside_last_x64);
+        let fees_owed = growth_delta
+            .checked_mul(position.liquidity as u128)
+            .unwrap()
+            / fixed_point_64::Q64;
+        position.tokens_owed += fees_owed as u64;
+        let reward_growth_delta = mul_div_floor(pool.emissions_per_second_x64, 60, pool.liquidity as u128);
+        pool.reward_growth_global_x64 = pool.reward_growth_global_x64.checked_add(reward_growth_delta).unwrap();
+        let doubled_x64 = growth_delta * 2;
+        pool.fee_growth_global_x64 = doubled_x64;
+        Ok(())
+    }
+}
+
+fn mul_div_floor(a: u128, b: u128, c: u128) -> u128 {
+    a * b / c
+}
+
+#[derive(Accounts)]
+pub struct Collect<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut, has_one = pool)]
+    pub position: Account<'info, Position>,
+    pub vault: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[account]
+pub struct Pool {
+    /// Current price as Q64.64.
+    pub price: u128,
+    pub liquidity: u64,
+    pub fee_growth_global_x64: u128,
+    pub reward_growth_global_x64: u128,
+    pub emissions_per_second_x64: u128,
+}
+
+#[account]
+pub struct Position {
+    pub pool: Pubkey,
+    pub liquidity: u64,
+    pub fee_growth_inside_last_x64: u128,
+    pub tokens_owed: u64,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    PriceLimit,
+}
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use arrayref::array_ref;
+use spl_token_2022::extension::StateWithExtensions;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod fee_sweeper {
+    use super::*;
+
+    pub fn sweep(ctx: Context<Sweep>) -> Result<()> {
+        check_vault_balance(&ctx.accounts.pool, &ctx.accounts.fee_vault.to_account_info())?;
+        let oracle_price = read_price(&ctx.accounts.oracle)?;
+        let pool = &mut ctx.accounts.pool;
+        pool.last_price = oracle_price;
+        Ok(())
+    }
+}
+
+pub fn check_vault_balance(pool: &Account<Pool>, fee_vault: &AccountInfo) -> Result<()> {
+    let amount = StateWithExtensions::<spl_token_2022::state::Account>::unpack(
+        fee_vault.try_borrow_data()?.deref(),
+    )?
+    .base
+    .amount;
+    require!(amount >= pool.unclaimed_fees, SweepError::VaultShort);
+    Ok(())
+}
+
+pub fn read_price(oracle: &AccountInfo) -> Result<u64> {
+    let data = oracle.try_borrow_data()?;
+    let feed: &PriceFeed = bytemuck::from_bytes(&data[8..8 + 48]);
+    let expo = u32::from_le_bytes(*array_ref![data, 56, 4]);
+    Ok(feed.price >> expo)
+}
+
+#[derive(Accounts)]
+pub struct Sweep<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, Pool>,
+    /// CHECK: the pool's fee vault, balance checked in check_vault_balance
+    pub fee_vault: UncheckedAccount<'info>,
+    /// CHECK: price feed, parsed in read_price
+    pub oracle: AccountInfo<'info>,
+    pub cranker: Signer<'info>,
+}
+
+#[account]
+pub struct Pool {
+    pub fee_vault: Pubkey,
+    pub unclaimed_fees: u64,
+    pub last_price: u64,
+}
+
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+#[repr(C)]
+pub struct PriceFeed {
+    pub price: u64,
+    pub conf: u64,
+    pub padding: [u64; 4],
+}
+
+#[error_code]
+pub enum SweepError {
+    VaultShort,
+}
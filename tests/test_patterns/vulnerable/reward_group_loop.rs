@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, TokenAccount, TokenInterface, TransferChecked};
+
+pub const REWARD_NUM: usize = 3;
+
+#[program]
+pub mod farm {
+    use super::*;
+
+    pub fn claim_rewards(ctx: Context<Claim>) -> Result<()> {
+        let farm = &mut ctx.accounts.farm;
+        let remaining_accounts = ctx.remaining_accounts;
+        let group_count = remaining_accounts.len() / 2;
+        let mut accounts = remaining_accounts.iter();
+        for i in 0..group_count {
+            let reward_vault = InterfaceAccount::<TokenAccount>::try_from(accounts.next().unwrap())?;
+            let recipient = InterfaceAccount::<TokenAccount>::try_from(accounts.next().unwrap())?;
+            let owed = farm.reward_infos[i].owed;
+            pay(&ctx.accounts.token_program, &reward_vault, &recipient, owed)?;
+            farm.reward_infos[i].owed = 0;
+        }
+        Ok(())
+    }
+
+    pub fn claim_rewards_chunked(ctx: Context<Claim>) -> Result<()> {
+        let farm = &mut ctx.accounts.farm;
+        for (i, group) in ctx.remaining_accounts.chunks(2).enumerate() {
+            let vault = InterfaceAccount::<TokenAccount>::try_from(&group[0])?;
+            let recipient = InterfaceAccount::<TokenAccount>::try_from(&group[1])?;
+            require_keys_eq!(vault.key(), farm.reward_infos[i].vault);
+            pay(&ctx.accounts.token_program, &vault, &recipient, farm.reward_infos[i].owed)?;
+            farm.reward_infos[i].owed = 0;
+        }
+        Ok(())
+    }
+
+    pub fn claim_rewards_skipping(ctx: Context<Claim>) -> Result<()> {
+        let farm = &mut ctx.accounts.farm;
+        let remaining_accounts = ctx.remaining_accounts;
+        require!(remaining_accounts.len() == farm.reward_count() * 2, FarmError::AccountCount);
+        let mut accounts = remaining_accounts.iter();
+        for i in 0..remaining_accounts.len() / 2 {
+            let reward_vault = InterfaceAccount::<TokenAccount>::try_from(accounts.next().unwrap())?;
+            require_keys_eq!(reward_vault.key(), farm.reward_infos[i].vault);
+            if farm.reward_infos[i].owed == 0 {
+                continue;
+            }
+            let recipient = InterfaceAccount::<TokenAccount>::try_from(accounts.next().unwrap())?;
+            pay(&ctx.accounts.token_program, &reward_vault, &recipient, farm.reward_infos[i].owed)?;
+            farm.reward_infos[i].owed = 0;
+        }
+        Ok(())
+    }
+}
+
+fn pay<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    from: &InterfaceAccount<'info, TokenAccount>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    amount: u64,
+) -> Result<()> {
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    #[account(mut, has_one = owner)]
+    pub farm: Account<'info, Farm>,
+    pub owner: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[account]
+pub struct Farm {
+    pub owner: Pubkey,
+    pub reward_infos: [RewardInfo; REWARD_NUM],
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct RewardInfo {
+    pub vault: Pubkey,
+    pub owed: u64,
+}
+
+impl Farm {
+    pub fn reward_count(&self) -> usize {
+        self.reward_infos.iter().filter(|r| r.vault != Pubkey::default()).count()
+    }
+}
+
+#[error_code]
+pub enum FarmError {
+    AccountCount,
+}
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+#[program]
+pub mod vault {
+    use super::*;
+
+    pub fn swap(ctx: Context<Swap>, amount_in: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let fee = amount_in * pool.fee_bps / 10_000;
+        let amount_out = amount_in - amount_in * pool.fee_bps / 10_000;
+        pool.protocol_fees += fee;
+        pool.reserve = pool.reserve.checked_sub(amount_out).unwrap();
+        Ok(())
+    }
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let shares = amount * pool.total_shares / pool.total_assets;
+        pool.total_shares += shares;
+        Ok(())
+    }
+
+    pub fn withdraw(ctx: Context<Withdraw>, shares: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let amount = (shares * pool.total_assets + pool.total_shares - 1) / pool.total_shares;
+        pool.total_assets -= amount;
+        Ok(())
+    }
+
+    pub fn withdraw_exact(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let shares = U128::from(amount)
+            .mul_div_ceil(U128::from(pool.total_shares), U128::from(pool.total_assets))
+            .unwrap()
+            .as_u64();
+        pool.total_shares -= shares;
+        Ok(())
+    }
+
+    pub fn borrow(ctx: Context<Borrow>, amount: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let debt_shares = amount.checked_mul(pool.debt_shares).unwrap().checked_div(pool.total_debt).unwrap();
+        pool.debt_shares += debt_shares;
+        Ok(())
+    }
+}
+
+pub fn calculate_fee(amount: u64, fee_bps: u64) -> u64 {
+    amount.checked_mul(fee_bps).unwrap().div_ceil(10_000)
+}
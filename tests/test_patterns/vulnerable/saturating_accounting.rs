@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+#[program]
+pub mod bank {
+    use super::*;
+
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        let user = &mut ctx.accounts.user;
+        user.deposited = user.deposited.saturating_sub(amount);
+        ctx.accounts.bank.total_deposits = ctx.accounts.bank.total_deposits.saturating_sub(amount);
+        Ok(())
+    }
+
+    pub fn collect(ctx: Context<Collect>) -> Result<()> {
+        let bank = &mut ctx.accounts.bank;
+        let elapsed = Clock::get()?.unix_timestamp.saturating_sub(bank.last_update);
+        let spread = bank.max_fee_bps.saturating_sub(bank.min_fee_bps);
+        // fees may round past the collected amount; clamp at zero
+        let left = bank.collected_fees.saturating_sub(bank.paid_fees);
+        bank.last_update += elapsed;
+        bank.spread = spread;
+        bank.left = left;
+        Ok(())
+    }
+}
+
+impl Bank {
+    pub fn available(&self) -> u64 {
+        self.reserve_balance.saturating_sub(self.borrowed)
+    }
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+#[program]
+pub mod vesting {
+    use super::*;
+
+    pub fn claim(ctx: Context<Claim>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let grant = &mut ctx.accounts.grant;
+        let elapsed = (now - grant.start_ts) as u64;
+        let vested = grant.total * elapsed / (grant.end_ts - grant.start_ts) as u64;
+        grant.claimed = vested;
+        Ok(())
+    }
+
+    pub fn claim_checked(ctx: Context<Claim>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let grant = &mut ctx.accounts.grant;
+        require!(now >= grant.start_ts, VestingError::NotStarted);
+        let elapsed = (now - grant.start_ts) as u64;
+        let remaining = u64::try_from(grant.end_ts - now).map_err(|_| VestingError::Ended)?;
+        grant.claimed = elapsed + remaining;
+        Ok(())
+    }
+
+    pub fn rate(ctx: Context<Claim>) -> Result<()> {
+        let grant = &mut ctx.accounts.grant;
+        let per_second = grant.total as i64 / (grant.end_ts - grant.last_update);
+        grant.rate = per_second;
+        Ok(())
+    }
+}
+
+impl Grant {
+    pub fn weight(&self, stake_time: i64) -> Result<u128> {
+        let span = self.end_ts
+            .checked_sub(stake_time)
+            .ok_or(VestingError::Overflow)? as u128; // can't be negative
+        let idle = self.end_ts - self.last_update;
+        Ok(span + idle as u128)
+    }
+
+    pub fn remaining(&self, now: i64) -> u64 {
+        (self.end_ts - now).max(0) as u64
+    }
+}
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    #[account(mut)]
+    pub grant: Account<'info, Grant>,
+}
+
+#[account]
+pub struct Grant {
+    pub start_ts: i64,
+    pub end_ts: i64,
+    pub last_update: i64,
+    pub total: u64,
+    pub claimed: u64,
+    pub rate: i64,
+}
+
+#[error_code]
+pub enum VestingError {
+    NotStarted,
+    Ended,
+    Overflow,
+}
@@ -0,0 +1,151 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+pub const VAULT_SEED: &[u8] = b"vault";
+
+#[program]
+pub mod vaults {
+    use super::*;
+
+    pub fn withdraw(ctx: Context<Withdraw>, owner: Pubkey, amount: u64) -> Result<()> {
+        // The caller names whose vault the program signs for
+        let seeds = &[VAULT_SEED, owner.as_ref(), &[ctx.accounts.vault.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault_tokens.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: ctx.accounts.vault.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            amount,
+        )
+    }
+
+    pub fn sweep(ctx: Context<Sweep>, amount: u64) -> Result<()> {
+        let data = ctx.accounts.config.try_borrow_data()?;
+        let config = Config::try_from_slice(&data[8..])?;
+        let owner_key = config.owner;
+        let seeds = &[VAULT_SEED, owner_key.as_ref(), &[config.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault_tokens.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: ctx.accounts.vault.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            amount,
+        )
+    }
+
+    pub fn close_order(ctx: Context<CloseOrder>) -> Result<()> {
+        let owner = ctx.accounts.owner.key();
+        // "orders" where every accounts struct declares b"order"
+        let seeds = &[b"orders".as_ref(), owner.as_ref(), &[ctx.accounts.order.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.order_tokens.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: ctx.accounts.order.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            ctx.accounts.order_tokens.amount,
+        )
+    }
+
+    pub fn settle_order(ctx: Context<SettleOrder>, order_id: u64) -> Result<()> {
+        // order_id is also the seed Anchor derived the order account from
+        let id = order_id.to_le_bytes();
+        let owner = ctx.accounts.owner.key();
+        let seeds = &[b"order".as_ref(), owner.as_ref(), id.as_ref(), &[ctx.accounts.order.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.order_tokens.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: ctx.accounts.order.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            ctx.accounts.order_tokens.amount,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(seeds = [VAULT_SEED, user.key().as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub vault_tokens: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub destination: Account<'info, TokenAccount>,
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Sweep<'info> {
+    /// CHECK: deserialized by hand
+    pub config: UncheckedAccount<'info>,
+    #[account(seeds = [VAULT_SEED, admin.key().as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub vault_tokens: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub destination: Account<'info, TokenAccount>,
+    pub admin: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CloseOrder<'info> {
+    #[account(seeds = [b"order", owner.key().as_ref()], bump = order.bump)]
+    pub order: Account<'info, Order>,
+    #[account(mut)]
+    pub order_tokens: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub destination: Account<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(order_id: u64)]
+pub struct SettleOrder<'info> {
+    #[account(seeds = [b"order", owner.key().as_ref(), order_id.to_le_bytes().as_ref()], bump = order.bump)]
+    pub order: Account<'info, Order>,
+    #[account(mut)]
+    pub order_tokens: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub destination: Account<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[account]
+pub struct Vault {
+    pub owner: Pubkey,
+    pub bump: u8,
+}
+
+#[account]
+pub struct Order {
+    pub owner: Pubkey,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct Config {
+    pub owner: Pubkey,
+    pub bump: u8,
+}
@@ -0,0 +1,97 @@
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+pub const BPS_DENOMINATOR: u64 = 1_000;
+
+#[program]
+pub mod liquid_staking {
+    use super::*;
+
+    pub fn initialize(ctx: Context<Initialize>, params: PoolParams) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.admin = ctx.accounts.admin.key();
+        pool.liq = LiqPool {
+            lp_liquidity_target: params.lp_liquidity_target,
+            lp_min_fee_bps: params.lp_min_fee_bps,
+            lp_max_fee_bps: params.lp_max_fee_bps,
+            treasury_cut_bps: params.treasury_cut_bps,
+        };
+        pool.liq.validate()?;
+        Ok(())
+    }
+
+    pub fn update_fees(ctx: Context<UpdatePool>, params: PoolParams) -> Result<()> {
+        let liq = &mut ctx.accounts.pool.liq;
+        require_lte!(params.lp_max_fee_bps, LiqPool::MAX_FEE_BPS, StakingError::FeeTooHigh);
+        liq.lp_min_fee_bps = params.lp_min_fee_bps;
+        liq.lp_max_fee_bps = params.lp_max_fee_bps;
+        liq.treasury_cut_bps = params.treasury_cut_bps;
+        liq.lp_liquidity_target = params.lp_liquidity_target;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(init, payer = admin, space = 8 + 32 + 32)]
+    pub pool: Account<'info, Pool>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePool<'info> {
+    pub admin: Signer<'info>,
+    #[account(mut, has_one = admin)]
+    pub pool: Account<'info, Pool>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PoolParams {
+    pub lp_liquidity_target: u64,
+    pub lp_min_fee_bps: u16,
+    pub lp_max_fee_bps: u16,
+    pub treasury_cut_bps: u16,
+}
+
+#[account]
+pub struct Pool {
+    pub admin: Pubkey,
+    pub liq: LiqPool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LiqPool {
+    pub lp_liquidity_target: u64,
+    pub lp_min_fee_bps: u16,
+    pub lp_max_fee_bps: u16,
+    pub treasury_cut_bps: u16,
+}
+
+impl LiqPool {
+    pub const MAX_FEE_BPS: u16 = 1_000;
+    pub const MAX_TREASURY_CUT_BPS: u16 = 7_500;
+    pub const MIN_LIQUIDITY_TARGET: u64 = 50 * 1_000_000_000;
+
+    pub fn validate(&self) -> Result<()> {
+        require_lte!(self.lp_max_fee_bps, Self::MAX_FEE_BPS, StakingError::FeeTooHigh);
+        require_gte!(self.lp_max_fee_bps, self.lp_min_fee_bps, StakingError::FeesWrongWayRound);
+        require_gte!(self.lp_liquidity_target, Self::MIN_LIQUIDITY_TARGET, StakingError::TargetTooLow);
+        require_lte!(self.treasury_cut_bps, Self::MAX_TREASURY_CUT_BPS, StakingError::CutTooHigh);
+        Ok(())
+    }
+
+    pub fn fee_for(&self, amount: u64) -> u64 {
+        amount * self.lp_max_fee_bps as u64 / BPS_DENOMINATOR
+    }
+}
+
+#[error_code]
+pub enum StakingError {
+    FeeTooHigh,
+    FeesWrongWayRound,
+    TargetTooLow,
+    CutTooHigh,
+}
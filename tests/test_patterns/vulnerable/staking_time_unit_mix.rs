@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod time_staking {
+    use super::*;
+
+    pub fn deactivate(ctx: Context<Deactivate>) -> Result<()> {
+        let clock = Clock::get()?;
+        let stake = &mut ctx.accounts.stake;
+        stake.unlock_epoch = clock.epoch + ctx.accounts.config.cooldown_secs;
+        Ok(())
+    }
+
+    pub fn unstake(ctx: Context<Unstake>) -> Result<()> {
+        let clock = Clock::get()?;
+        require!(clock.slot >= ctx.accounts.stake.unlock_epoch, StakeError::StillLocked);
+        Ok(())
+    }
+
+    pub fn claim_rewards(ctx: Context<Claim>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp as u64;
+        let stake = &mut ctx.accounts.stake;
+        let elapsed = now.checked_sub(stake.last_claim_ts).ok_or(StakeError::Overflow)?;
+        stake.pending = stake.pending + elapsed * ctx.accounts.config.reward_per_second;
+        stake.last_claim_ts = now;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Deactivate<'info> {
+    #[account(mut, has_one = owner)]
+    pub stake: Account<'info, StakeEntry>,
+    pub config: Account<'info, Config>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Unstake<'info> {
+    #[account(mut, has_one = owner)]
+    pub stake: Account<'info, StakeEntry>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    #[account(mut, has_one = owner)]
+    pub stake: Account<'info, StakeEntry>,
+    pub config: Account<'info, Config>,
+    pub owner: Signer<'info>,
+}
+
+#[account]
+pub struct StakeEntry {
+    pub owner: Pubkey,
+    pub unlock_epoch: u64,
+    pub last_claim_ts: u64,
+    pub pending: u64,
+}
+
+#[account]
+pub struct Config {
+    pub cooldown_secs: u64,
+    pub reward_per_second: u64,
+}
+
+#[error_code]
+pub enum StakeError {
+    StillLocked,
+    Overflow,
+}
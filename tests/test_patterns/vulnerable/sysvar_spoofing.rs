@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::solana_program::sysvar::instructions::load_instruction_at;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod bridge {
+    use super::*;
+
+    pub fn verify_signatures(ctx: Context<VerifySignatures>) -> Result<()> {
+        let secp_ix = load_instruction_at(0, &ctx.accounts.instruction_acc)?;
+        require_keys_eq!(secp_ix.program_id, secp256k1_program::ID);
+        ctx.accounts.signature_set.verified = true;
+        Ok(())
+    }
+
+    pub fn claim(ctx: Context<Claim>) -> Result<()> {
+        let clock: Clock = bincode::deserialize(&ctx.accounts.clock.data.borrow()).unwrap();
+        require!(clock.unix_timestamp >= ctx.accounts.vesting.unlock_ts, BridgeError::Locked);
+        let schedule = EpochSchedule::from_account_info(&ctx.accounts.epoch_schedule)?;
+        msg!("slots per epoch {}", schedule.slots_per_epoch);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct VerifySignatures<'info> {
+    #[account(mut)]
+    pub signature_set: Account<'info, SignatureSet>,
+    /// CHECK: instruction introspection
+    pub instruction_acc: UncheckedAccount<'info>,
+    #[account(address = sysvar::rent::ID)]
+    /// CHECK: pinned to the rent sysvar
+    pub rent: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    pub vesting: Account<'info, Vesting>,
+    /// CHECK: clock sysvar
+    pub clock: AccountInfo<'info>,
+    /// CHECK: read with EpochSchedule::from_account_info
+    pub epoch_schedule: AccountInfo<'info>,
+}
+
+#[account]
+pub struct SignatureSet {
+    pub verified: bool,
+}
+
+#[account]
+pub struct Vesting {
+    pub unlock_ts: i64,
+}
+
+#[error_code]
+pub enum BridgeError {
+    Locked,
+}
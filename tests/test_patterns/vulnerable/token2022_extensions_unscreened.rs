@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use spl_token_2022::extension::{
+    permanent_delegate::PermanentDelegate, transfer_hook::TransferHook, BaseStateWithExtensions,
+    StateWithExtensions,
+};
+
+#[program]
+pub mod amm {
+    use super::*;
+
+    pub fn create_pool(ctx: Context<CreatePool>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.mint = ctx.accounts.mint.key();
+        pool.vault = ctx.accounts.vault.key();
+        Ok(())
+    }
+
+    pub fn create_market(ctx: Context<CreateMarket>) -> Result<()> {
+        check_mint_extensions(&ctx.accounts.base_mint.to_account_info())?;
+        ctx.accounts.market.base_mint = ctx.accounts.base_mint.key();
+        Ok(())
+    }
+
+    pub fn swap(ctx: Context<Swap>, amount: u64) -> Result<()> {
+        ctx.accounts.pool.volume += amount;
+        Ok(())
+    }
+}
+
+// Rejects the delegate and hook extensions, not confidential transfers or a frozen default state
+pub fn check_mint_extensions(mint: &AccountInfo) -> Result<()> {
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    require!(state.get_extension::<PermanentDelegate>().is_err(), AmmError::UnsupportedMint);
+    require!(state.get_extension::<TransferHook>().is_err(), AmmError::UnsupportedMint);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreatePool<'info> {
+    #[account(init, payer = payer, space = 8 + Pool::INIT_SPACE)]
+    pub pool: Account<'info, Pool>,
+    // Any Token-2022 mint, extensions unchecked
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = payer,
+        token::mint = mint,
+        token::authority = pool,
+        token::token_program = token_program,
+        seeds = [b"vault", pool.key().as_ref()],
+        bump,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateMarket<'info> {
+    #[account(init, payer = payer, space = 8 + Market::INIT_SPACE)]
+    pub market: Account<'info, Market>,
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        init,
+        payer = payer,
+        token::mint = base_mint,
+        token::authority = market,
+        seeds = [b"base_vault", market.key().as_ref()],
+        bump,
+    )]
+    pub base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    #[account(mut, has_one = mint, has_one = vault)]
+    pub pool: Account<'info, Pool>,
+    // Pinned by the pool: screened when the pool was created
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub user: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub volume: u64,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Market {
+    pub base_mint: Pubkey,
+}
+
+#[error_code]
+pub enum AmmError {
+    #[msg("Mint extension not supported")]
+    UnsupportedMint,
+}
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod market {
+    use super::*;
+
+    pub fn swap(ctx: Context<Swap>, amount: u64) -> Result<()> {
+        let fee = amount / 100;
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.user_token.to_account_info(),
+                    to: ctx.accounts.fee_vault.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            fee,
+        )?;
+        Ok(())
+    }
+
+    pub fn claim(ctx: Context<Claim>) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    #[account(mut, token::authority = user)]
+    pub user_token: Account<'info, TokenAccount>,
+    // VULNERABLE: fees go wherever the caller says
+    #[account(mut)]
+    pub fee_vault: Account<'info, TokenAccount>,
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    // VULNERABLE: no associated_token::authority
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = reward_mint,
+    )]
+    pub reward_ata: Account<'info, TokenAccount>,
+    // VULNERABLE: no associated_token::mint
+    #[account(
+        init,
+        payer = user,
+        associated_token::authority = user,
+    )]
+    pub receipt_ata: Account<'info, TokenAccount>,
+    pub reward_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod perp_vault {
+    use super::*;
+
+    pub fn claim(ctx: Context<Claim>) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        let reward = (position.shares as u128) * position.reward_index / PRECISION;
+        // VULNERABLE: u128 reward narrowed with `as`
+        position.pending = reward as u64;
+        Ok(())
+    }
+
+    pub fn settle(ctx: Context<Claim>, pnl: i64) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        // VULNERABLE: a loss wraps to a huge payout
+        position.pending = position.pending.checked_add(pnl as u64).unwrap();
+        // VULNERABLE: the lock duration is truncated to 32 bits
+        position.lock_secs = (position.unlock_ts - position.opened_ts) as u32;
+        let now = Clock::get()?.unix_timestamp;
+        // SAFE: the clock is never negative
+        position.last_update = now as u64;
+        Ok(())
+    }
+
+    pub fn safe(ctx: Context<Claim>, pnl: i64) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        let reward = (position.shares as u128) * position.reward_index / PRECISION;
+        // SAFE: checked conversion
+        position.pending = u64::try_from(reward).map_err(|_| ErrorCode::MathOverflow)?;
+        // SAFE: compared before the cast
+        require!(pnl >= 0, ErrorCode::NegativePnl);
+        position.pending = position.pending.checked_add(pnl as u64).unwrap();
+        // SAFE: bounded by u64::MAX first
+        let capped = reward.min(u64::MAX as u128);
+        position.pending = capped as u64;
+        Ok(())
+    }
+}
+
+pub struct Fee {
+    pub basis_points: u32,
+}
+
+impl Fee {
+    pub const MAX_BASIS_POINTS: u32 = 10_000;
+
+    pub fn apply(&self, lamports: u64) -> u64 {
+        // VULNERABLE: Marinade's shape, narrowed without a check
+        (lamports as u128 * self.basis_points as u128 / Self::MAX_BASIS_POINTS as u128) as u64
+    }
+}
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    #[account(mut)]
+    pub position: Account<'info, Position>,
+}
+
+#[account]
+pub struct Position {
+    pub shares: u64,
+    pub reward_index: u128,
+    pub pending: u64,
+    pub unlock_ts: i64,
+    pub opened_ts: i64,
+    pub lock_secs: u32,
+    pub last_update: u64,
+}
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+pub const FEE_BPS: u64 = 10_000;
+pub const SECONDS_PER_YEAR: u64 = 31_536_000;
+
+#[program]
+pub mod rewards {
+    use super::*;
+
+    // VULNERABLE: amount and multiplier are full u64, FEE_BPS adds 14 bits.
+    pub fn charge(ctx: Context<Charge>, amount: u64) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        let fee = amount * FEE_BPS * market.multiplier / FEE_BPS / FEE_BPS;
+        market.fees += fee;
+        Ok(())
+    }
+
+    // SAFE: u16 basis points and a literal stay far below 64 bits.
+    pub fn set_rate(ctx: Context<Charge>, bps: u16) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        market.scaled_rate = bps as u64 * market.rate_bps as u64 * 100;
+        Ok(())
+    }
+
+    // SAFE: widened to u128 and checked.
+    pub fn accrue_checked(ctx: Context<Charge>, elapsed: u64) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        let wide = (market.principal as u128) * (market.multiplier as u128) * (elapsed as u128);
+        let checked = market.principal.checked_mul(market.multiplier).and_then(|v| v.checked_mul(elapsed));
+        market.fees = u64::try_from(wide).unwrap() + checked.unwrap_or(0);
+        Ok(())
+    }
+}
+
+// VULNERABLE: three u64 parameters multiplied before the division.
+fn accrue(principal: u64, rate: u64, elapsed: u64) -> u64 {
+    principal * rate * elapsed / SECONDS_PER_YEAR
+}
+
+#[derive(Accounts)]
+pub struct Charge<'info> {
+    #[account(mut)]
+    pub market: Account<'info, Market>,
+}
+
+#[account]
+pub struct Market {
+    pub multiplier: u64,
+    pub principal: u64,
+    pub rate_bps: u16,
+    pub scaled_rate: u64,
+    pub fees: u64,
+}
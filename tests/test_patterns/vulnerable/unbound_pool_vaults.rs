@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+declare_id!("Bnd1111111111111111111111111111111111111111");
+
+#[program]
+pub mod unbound_pool {
+    use super::*;
+
+    pub fn swap(ctx: Context<Swap>, amount_in: u64) -> Result<()> {
+        let pool = ctx.accounts.pool_state.load()?;
+        require!(pool.open, PoolError::Closed);
+        drop(pool);
+        token::transfer(ctx.accounts.deposit_ctx(), amount_in)?;
+        Ok(())
+    }
+
+    pub fn collect_fees(ctx: Context<CollectFees>) -> Result<()> {
+        let pool = ctx.accounts.pool_state.load()?;
+        require_keys_eq!(ctx.accounts.fee_vault.key(), pool.fee_vault, PoolError::WrongVault);
+        Ok(())
+    }
+
+    pub fn set_fee_vault(ctx: Context<SetFeeVault>) -> Result<()> {
+        let mut pool = ctx.accounts.pool_state.load_mut()?;
+        pool.fee_vault = ctx.accounts.fee_vault.key();
+        Ok(())
+    }
+}
+
+#[account(zero_copy)]
+pub struct PoolState {
+    pub token_mint_0: Pubkey,
+    pub token_vault_0: Pubkey,
+    pub token_vault_1: Pubkey,
+    pub fee_vault: Pubkey,
+    pub admin: Pubkey,
+    pub open: bool,
+}
+
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+    #[account(mut, token::mint = token_mint_0)]
+    pub input_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub token_vault_0: Box<Account<'info, TokenAccount>>,
+    #[account(mut, constraint = token_vault_1.key() == pool_state.load()?.token_vault_1)]
+    pub token_vault_1: Box<Account<'info, TokenAccount>>,
+    #[account(address = pool_state.load()?.token_mint_0)]
+    pub token_mint_0: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> Swap<'info> {
+    fn deposit_ctx(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.input_account.to_account_info(),
+                to: self.token_vault_0.to_account_info(),
+                authority: self.payer.to_account_info(),
+            },
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct CollectFees<'info> {
+    pub pool_state: AccountLoader<'info, PoolState>,
+    #[account(mut)]
+    pub fee_vault: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeVault<'info> {
+    pub admin: Signer<'info>,
+    #[account(mut, has_one = admin)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+    pub fee_vault: Account<'info, TokenAccount>,
+}
+
+#[error_code]
+pub enum PoolError {
+    Closed,
+    WrongVault,
+}
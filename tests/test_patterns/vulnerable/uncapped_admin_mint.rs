@@ -0,0 +1,100 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount, Transfer};
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod rewards {
+    use super::*;
+
+    // VULNERABLE: only the admin can mint, but any amount in one call.
+    pub fn admin_mint(ctx: Context<AdminMint>, amount: u64) -> Result<()> {
+        let seeds = &[b"mint_authority".as_ref(), &[ctx.accounts.config.bump]];
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: ctx.accounts.mint_authority.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            amount,
+        )
+    }
+
+    // VULNERABLE: the treasury PDA pays out whatever the caller asks for.
+    pub fn sweep_treasury(ctx: Context<Sweep>, lamports: u64) -> Result<()> {
+        let payout = lamports;
+        let seeds = &[b"treasury".as_ref(), &[ctx.accounts.config.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.treasury.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: ctx.accounts.treasury_authority.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            payout,
+        )
+    }
+
+    // SAFE: bounded by the per-instruction cap kept in Config.
+    pub fn admin_mint_capped(ctx: Context<AdminMint>, amount: u64) -> Result<()> {
+        require!(amount <= ctx.accounts.config.max_mint_per_ix, ErrorCode::CapExceeded);
+        let seeds = &[b"mint_authority".as_ref(), &[ctx.accounts.config.bump]];
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: ctx.accounts.mint_authority.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            amount,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct AdminMint<'info> {
+    #[account(has_one = admin)]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+    #[account(mut)]
+    pub mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub destination: Account<'info, TokenAccount>,
+    /// CHECK: PDA mint authority
+    pub mint_authority: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Sweep<'info> {
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub treasury: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub destination: Account<'info, TokenAccount>,
+    /// CHECK: PDA treasury authority
+    pub treasury_authority: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[account]
+pub struct Config {
+    pub admin: Pubkey,
+    pub max_mint_per_ix: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    CapExceeded,
+}
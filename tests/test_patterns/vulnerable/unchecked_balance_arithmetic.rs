@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod vault {
+    use super::*;
+
+    // VULNERABLE: plain `+=` on the user's and the pool's balances.
+    pub fn deposit(ctx: Context<Update>, amount: u64) -> Result<()> {
+        ctx.accounts.user.deposited += amount;
+        ctx.accounts.pool.total_deposits = ctx.accounts.pool.total_deposits.checked_add(amount).unwrap();
+        Ok(())
+    }
+
+    // SAFE: the subtraction's operands are compared first.
+    pub fn withdraw(ctx: Context<Update>, amount: u64) -> Result<()> {
+        let user = &mut ctx.accounts.user;
+        require!(user.deposited >= amount, VaultError::InsufficientBalance);
+        user.deposited -= amount;
+        Ok(())
+    }
+
+    // VULNERABLE: the pool total is never compared with the amount.
+    pub fn emergency_withdraw(ctx: Context<Update>, amount: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.total_deposits -= amount;
+        Ok(())
+    }
+
+    // VULNERABLE: the helper adds rewards unchecked; counters are not quantities.
+    pub fn claim(ctx: Context<Update>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.claim_count = pool.claim_count + 1;
+        accrue(pool, 10)?;
+        Ok(())
+    }
+}
+
+fn accrue(pool: &mut Pool, pending: u64) -> Result<()> {
+    pool.total_rewards = pool.total_rewards + pending;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Update<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut, has_one = owner)]
+    pub user: Account<'info, UserAccount>,
+    pub owner: Signer<'info>,
+}
+
+#[account]
+pub struct Pool {
+    pub total_deposits: u64,
+    pub total_rewards: u64,
+    pub claim_count: u64,
+}
+
+#[account]
+pub struct UserAccount {
+    pub owner: Pubkey,
+    pub deposited: u64,
+}
+
+#[error_code]
+pub enum VaultError {
+    InsufficientBalance,
+}
@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::Token;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod vault_factory {
+    use super::*;
+
+    // VULNERABLE: reads the config PDA before creating it. Until
+    // create_account runs, the address is system-owned and may hold
+    // whatever an attacker pre-funded there.
+    pub fn open_config(ctx: Context<OpenConfig>, space: u64, lamports: u64) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let existing = config.try_borrow_data()?;
+        require!(existing.iter().all(|b| *b == 0), ErrorCode::AlreadyOpen);
+        drop(existing);
+
+        system_program::create_account(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::CreateAccount {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: ctx.accounts.config.to_account_info(),
+                },
+            ),
+            lamports,
+            space,
+            ctx.program_id,
+        )?;
+        Ok(())
+    }
+
+    // VULNERABLE: the vault is created as a token account, then the
+    // program writes its own state into it.
+    pub fn open_vault(ctx: Context<OpenConfig>) -> Result<()> {
+        create_token_vault_account(
+            &ctx.accounts.payer,
+            &ctx.accounts.vault,
+            &ctx.accounts.system_program,
+            &ctx.accounts.token_program,
+        )?;
+        let mut data = ctx.accounts.vault.try_borrow_mut_data()?;
+        data[0] = 1;
+        Ok(())
+    }
+
+    // SAFE: created first, then initialized.
+    pub fn open_config_ordered(ctx: Context<OpenConfig>, space: u64, lamports: u64) -> Result<()> {
+        system_program::create_account(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::CreateAccount {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: ctx.accounts.config.to_account_info(),
+                },
+            ),
+            lamports,
+            space,
+            ctx.program_id,
+        )?;
+        let mut data = ctx.accounts.config.try_borrow_mut_data()?;
+        data[0] = 1;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct OpenConfig<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: created in the handler
+    #[account(mut)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: created in the handler
+    #[account(mut)]
+    pub vault: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, InitializeAccount3, Mint};
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod forked_clmm {
+    use super::*;
+
+    pub fn create_pool(ctx: Context<CreatePool>) -> Result<()> {
+        create_token_vault_account(
+            &ctx.accounts.pool_creator,
+            &ctx.accounts.pool_state.to_account_info(),
+            &ctx.accounts.token_vault_0,
+            &ctx.accounts.token_mint_0,
+            &ctx.accounts.system_program,
+            &ctx.accounts.token_program_0,
+            &[POOL_VAULT_SEED.as_bytes(), ctx.accounts.pool_state.key().as_ref(), &[ctx.bumps.token_vault_0][..]],
+        )?;
+
+        token_interface::initialize_account3(CpiContext::new(
+            ctx.accounts.token_program_1.to_account_info(),
+            InitializeAccount3 {
+                account: ctx.accounts.token_vault_1.to_account_info(),
+                mint: ctx.accounts.token_mint_1.to_account_info(),
+                authority: ctx.accounts.pool_creator.to_account_info(),
+            },
+        ))?;
+        Ok(())
+    }
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct CreatePool<'info> {
+    #[account(mut)]
+    pub pool_creator: Signer<'info>,
+    #[account(init, seeds = [POOL_SEED.as_bytes(), token_mint_0.key().as_ref()], bump, payer = pool_creator, space = 8 + 128)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+    pub token_mint_0: Box<InterfaceAccount<'info, Mint>>,
+    pub token_mint_1: Box<InterfaceAccount<'info, Mint>>,
+    /// CHECK: initialized in contract
+    #[account(mut, seeds = [POOL_VAULT_SEED.as_bytes(), pool_state.key().as_ref()], bump)]
+    pub token_vault_0: UncheckedAccount<'info>,
+    /// CHECK: initialized in contract
+    #[account(mut, seeds = [POOL_VAULT_SEED.as_bytes(), pool_state.key().as_ref(), token_mint_1.key().as_ref()], bump)]
+    pub token_vault_1: UncheckedAccount<'info>,
+    /// CHECK: passed through to the vault creation
+    pub token_program_0: UncheckedAccount<'info>,
+    pub token_program_1: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    pub pool_state: AccountLoader<'info, PoolState>,
+    #[account(mut, seeds = [POOL_VAULT_SEED.as_bytes(), pool_state.key().as_ref()], bump)]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+}
+
+#[account(zero_copy)]
+pub struct PoolState {
+    pub token_vault_0: Pubkey,
+    pub token_vault_1: Pubkey,
+}
@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+
+#[program]
+pub mod pool {
+    use super::*;
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.total_deposits = vault.total_deposits.wrapping_add(amount);
+        ctx.accounts.user.balance = ctx.accounts.user.balance.wrapping_sub(amount);
+        vault.fee_growth_global = vault.fee_growth_global.wrapping_add(amount << 64);
+        vault.observation_index = vault.observation_index.wrapping_add(1) % 64;
+        vault.epoch_marker = vault.epoch_marker.wrapping_add(1);
+        Ok(())
+    }
+}
+
+pub fn fees_owed(fee_growth_inside: u128, fee_growth_last: u128, liquidity: u128) -> u64 {
+    let delta = fee_growth_inside.wrapping_sub(fee_growth_last);
+    let mixed = delta.wrapping_mul(liquidity);
+    (mixed >> 64) as u64
+}
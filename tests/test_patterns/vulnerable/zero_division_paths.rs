@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+const PRECISION: u64 = 1_000_000;
+
+#[program]
+pub mod share_vault {
+    use super::*;
+
+    // VULNERABLE: total_assets is zero before the first deposit.
+    pub fn deposit(ctx: Context<Update>, amount: u64) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        let shares = amount * vault.total_shares / vault.total_assets;
+        vault.total_shares += shares;
+        Ok(())
+    }
+
+    // VULNERABLE: zero when the fee equals the whole amount, only in the fee branch.
+    pub fn swap(ctx: Context<Update>, amount: u64, fee: u64) -> Result<()> {
+        let vault = &ctx.accounts.vault;
+        if vault.fee_enabled {
+            let net = amount - fee;
+            let price = vault.total_assets * PRECISION / net;
+            msg!("price {}", price);
+        } else {
+            let price = vault.total_assets * PRECISION / amount;
+            msg!("price {}", price);
+        }
+        Ok(())
+    }
+
+    // SAFE: every denominator is ruled out before the division.
+    pub fn withdraw(ctx: Context<Update>, shares: u64) -> Result<()> {
+        let vault = &ctx.accounts.vault;
+        require!(vault.total_shares > 0, VaultError::Empty);
+        let assets = shares * vault.total_assets / vault.total_shares;
+        if shares == 0 {
+            return err!(VaultError::Empty);
+        }
+        let per_share = assets / shares;
+        let capped = assets / vault.total_assets.max(1);
+        let scaled = assets / PRECISION;
+        require_gt!(vault.total_assets, assets, VaultError::Empty);
+        let rest = assets / (vault.total_assets - assets);
+        msg!("{} {} {} {}", per_share, capped, scaled, rest);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Update<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+    pub owner: Signer<'info>,
+}
+
+#[account]
+pub struct Vault {
+    pub total_shares: u64,
+    pub total_assets: u64,
+    pub fee_enabled: bool,
+}
+
+#[error_code]
+pub enum VaultError {
+    Empty,
+}
@@ -0,0 +1,71 @@
+"""Tests for previewing a rule against a target's current results."""
+
+import os
+import shutil
+import sys
+
+import pytest
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.engine import AnchorShieldEngine
+from scanner.preview import preview_rule, resolve_rule
+from scanner.suppression import write_baseline
+
+TEST_DIR = os.path.join(os.path.dirname(__file__), "test_patterns", "vulnerable")
+
+
+def _target(tmp_path, name: str) -> str:
+    shutil.copy(os.path.join(TEST_DIR, name), tmp_path / "lib.rs")
+    return str(tmp_path)
+
+
+class TestRulePreview:
+    def test_skipped_rule_lists_added_findings(self, tmp_path):
+        target = _target(tmp_path, "pda_seed_collision.rs")
+        engine = AnchorShieldEngine(skip=["ANCHOR-031"])
+        result = preview_rule("ANCHOR-031", target, engine)
+        assert not result.enabled
+        assert [(f.id, f.line) for f in result.added] == [
+            ("ANCHOR-031", 43), ("ANCHOR-031", 55), ("ANCHOR-031", 55), ("ANCHOR-031", 67),
+        ]
+        assert result.current == len(engine.scan_directory(target).findings)
+        assert result.merged == result.superseded == [] and result.baselined == 0
+        # The configured engine is left as it was
+        assert not any(p.id == "ANCHOR-031" for p in engine.patterns)
+        assert sorted(os.listdir(tmp_path)) == ["lib.rs"]
+
+    def test_enabled_rule_changes_nothing(self, tmp_path):
+        target = _target(tmp_path, "pda_seed_collision.rs")
+        result = preview_rule("shield::lifecycle::ANCHOR-031", target, AnchorShieldEngine())
+        assert result.enabled and result.added == [] and result.current > 0
+        assert result.to_dict()["rule"] == "ANCHOR-031"
+
+    def test_precedence_merges_and_supersedes(self, tmp_path):
+        """A preferred rule absorbs current findings; a superseded one folds into them."""
+        target = _target(tmp_path, "type_cosplay_no_discriminator.rs")
+        precedence = {"ANCHOR-004": ["ANCHOR-006"]}
+        result = preview_rule("ANCHOR-004", target, AnchorShieldEngine(only=["ANCHOR-006"], precedence=precedence))
+        assert [(f.id, f.line) for f in result.added] == [("ANCHOR-004", 25)]
+        assert [(f.id, f.line) for f in result.superseded] == [("ANCHOR-006", 25)]
+
+        result = preview_rule("ANCHOR-006", target, AnchorShieldEngine(only=["ANCHOR-004"], precedence=precedence))
+        assert result.added == [] and result.superseded == []
+        assert [(f.id, f.supporting[0]["id"]) for f in result.merged] == [("ANCHOR-004", "ANCHOR-006")]
+
+    def test_baselined_findings_are_not_added(self, tmp_path):
+        target = _target(tmp_path, "pda_seed_collision.rs")
+        findings = AnchorShieldEngine(only=["ANCHOR-031"]).scan_directory(target).findings
+        baseline = tmp_path / "baseline.json"
+        write_baseline(findings[:1], str(baseline))
+        engine = AnchorShieldEngine(skip=["ANCHOR-031"], baseline=str(baseline))
+        result = preview_rule("ANCHOR-031", target, engine)
+        assert result.baselined == 1
+        assert [f.line for f in result.added] == [55, 55, 67]
+
+    def test_selector_must_name_one_rule(self):
+        assert resolve_rule("anchor-031").id == "ANCHOR-031"
+        with pytest.raises(ValueError, match="preview one rule at a time"):
+            resolve_rule("shield::*")
+        with pytest.raises(ValueError, match="No rule matches"):
+            resolve_rule("ANCHOR-999")
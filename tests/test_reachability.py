@@ -0,0 +1,90 @@
+"""Tests for entrypoint-to-sink reachability."""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.reachability import ReachabilityAnalyzer, render_markdown
+
+PROGRAM = '''
+use anchor_lang::prelude::*;
+
+#[program]
+pub mod vault {
+    use super::*;
+
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        require!(amount > 0, VaultError::Zero);
+        helpers::pay_out(&ctx, amount)
+    }
+
+    pub fn set_admin(ctx: Context<SetAdmin>, new_admin: Pubkey) -> Result<()> {
+        ctx.accounts.config.admin = new_admin;
+        Ok(())
+    }
+
+    pub fn close_vault(ctx: Context<CloseVault>) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut, has_one = owner)]
+    pub vault: Account<'info, Vault>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetAdmin<'info> {
+    #[account(mut)]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct CloseVault<'info> {
+    #[account(mut, close = receiver, seeds = [b"vault"], bump)]
+    pub vault: Account<'info, Vault>,
+    pub receiver: SystemAccount<'info>,
+}
+'''
+
+HELPERS = '''
+pub fn pay_out(ctx: &Context<Withdraw>, amount: u64) -> Result<()> {
+    if ctx.accounts.vault.frozen {
+        return Err(VaultError::Frozen.into());
+    }
+    token::transfer(CpiContext::new_with_signer(p, accounts, signer_seeds), amount)
+}
+'''
+
+
+def _analyze():
+    return ReachabilityAnalyzer([("lib.rs", PROGRAM), ("helpers.rs", HELPERS)]).analyze("vault")
+
+
+class TestReachability:
+    def test_pda_transfer_reached_through_helper(self):
+        report = _analyze()
+        assert report.entrypoints == 3
+        transfer = next(s for s in report.sinks if s.sink.kind == "pda-signed-cpi")
+        assert transfer.sink.file == "helpers.rs"
+        [ep] = transfer.entrypoints
+        assert ep.entrypoint == "withdraw"
+        assert ep.path == ["withdraw", "pay_out"]
+        assert "signer: owner" in ep.predicates
+        assert "has_one: vault ← owner" in ep.predicates
+        assert "require!(amount > 0, VaultError::Zero)" in ep.predicates
+        assert any(p.startswith("if ctx.accounts.vault.frozen") for p in ep.predicates)
+
+    def test_unguarded_authority_write_and_struct_close(self):
+        report = _analyze()
+        admin = next(s for s in report.sinks if s.sink.kind == "authority-write")
+        assert [e.entrypoint for e in admin.entrypoints] == ["set_admin"]
+        assert admin.entrypoints[0].predicates == []
+
+        close = next(s for s in report.sinks if s.sink.kind == "close")
+        assert [e.entrypoint for e in close.entrypoints] == ["close_vault"]
+        assert "seeds: vault" in close.entrypoints[0].predicates
+        assert "**none**" in render_markdown(report)
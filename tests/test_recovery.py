@@ -0,0 +1,67 @@
+"""Tests for item-level recovery of sources that do not parse as a whole."""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.engine import AnchorShieldEngine
+from scanner.recovery import recover
+from scanner.report import format_terminal_report
+
+TEST_DIR = os.path.join(os.path.dirname(__file__), "test_patterns", "vulnerable")
+
+BROKEN_FN = (
+    "    pub fn broken(ctx: Context<CreatePool>) -> Result<()> {\n"
+    "        if ctx.accounts.pool_state.key() == Pubkey::default() {\n"
+    "        Ok(())\n"
+    "    }\n"
+)
+
+
+def _read(name: str) -> str:
+    with open(os.path.join(TEST_DIR, name)) as f:
+        return f.read()
+
+
+def _scan(content: str):
+    report = AnchorShieldEngine(only=["ANCHOR-017"]).scan_content(content, "lib.rs")
+    return report, [(f.id, f.line) for f in report.findings]
+
+
+class TestRecovery:
+    def test_braces_in_literals_are_not_code(self):
+        content = _read("vault_creation_unpinned.rs").replace(
+            "-> Result<()> {\n", "-> Result<()> {\n        let _open = '{';\n        msg!(\"vault {\");\n", 1,
+        )
+        recovery = recover(content)
+        assert recovery.regions == [] and recovery.text.count("\n") == content.count("\n")
+        report, found = _scan(content)
+        assert found == [("ANCHOR-017", 13), ("ANCHOR-017", 25)]
+        assert report.unparsed == []
+        # Snippets show the source, not the recovered text
+        assert "'{'" in report.findings[0].code_snippet
+
+    def test_broken_item_is_left_out_and_reported(self):
+        content = _read("vault_creation_unpinned.rs").replace(
+            "    use super::*;\n", "    use super::*;\n" + BROKEN_FN, 1,
+        )
+        report, found = _scan(content)
+        assert found == [("ANCHOR-017", 15), ("ANCHOR-017", 27)]
+        assert report.unparsed == [{"file": "lib.rs", "lines": [9, 12], "reason": "unbalanced braces"}]
+        assert report.to_dict()["unparsed"] == report.unparsed
+        text = format_terminal_report(report)
+        assert "Partially analyzed" in text and "lib.rs:9-12 (unbalanced braces)" in text
+
+    def test_truncated_module_keeps_complete_items(self):
+        lines = _read("vault_creation_unpinned.rs").split("\n")
+        content = "\n".join(lines[:30]) + "\n\n" + BROKEN_FN.rsplit("    }\n", 1)[0]
+        report = AnchorShieldEngine(stats=True).scan_content(content, "lib.rs")
+        assert report.unparsed == [{"file": "lib.rs", "lines": [32, 34], "reason": "unbalanced braces"}]
+        # create_pool is still seen as an entrypoint of the unclosed #[program] module
+        assert report.stats["instructions"] == {"entrypoints": 1, "handlers": 0}
+        assert report.stats["unparsed"] == report.unparsed
+
+        recovery = recover("\n".join(lines[:30]) + "\n")
+        assert recovery.regions == [{"lines": [30, 30], "reason": "missing closing brace"}]
+        assert recovery.text.rstrip().endswith("} }")
@@ -0,0 +1,37 @@
+"""Tests for report rendering and redaction."""
+
+import json
+import os
+import sys
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.engine import AnchorShieldEngine
+from scanner.report import format_json_report, redact_report, redact_snippet
+
+VULN_DIR = os.path.join(os.path.dirname(__file__), "test_patterns", "vulnerable")
+
+SNIPPET = "      9 | #[derive(Accounts)]\n>>>   10 |     pub vault: AccountInfo<'info>,\n     11 | }"
+
+
+class TestRedaction:
+    def test_policy_by_severity(self):
+        assert redact_snippet(SNIPPET, "Critical") == SNIPPET
+        assert redact_snippet(SNIPPET, "High") == SNIPPET
+        assert redact_snippet(SNIPPET, "Medium") == ">>>   10 |     pub vault: AccountInfo<'info>,"
+        low = redact_snippet(SNIPPET, "Low")
+        assert low.startswith("[redacted: 3 lines, sha256 ")
+        assert "vault" not in low
+
+    def test_redacted_report_leaves_original_intact(self):
+        report = AnchorShieldEngine().scan_directory(VULN_DIR)
+        shared = redact_report(report)
+        assert len(shared.findings) == len(report.findings)
+        for original, redacted in zip(report.findings, shared.findings):
+            if original.severity in ("Critical", "High"):
+                assert redacted.code_snippet == original.code_snippet
+            else:
+                assert len(redacted.code_snippet) <= len(original.code_snippet)
+        assert any(f.code_snippet != r.code_snippet
+                   for f, r in zip(report.findings, shared.findings))
+        assert json.loads(format_json_report(shared))["findings"]
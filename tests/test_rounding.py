@@ -0,0 +1,37 @@
+"""Tests for the rounding-site inventory."""
+
+import json
+import os
+import sys
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.rounding import analyze_sources, analyze_target, render_markdown
+
+FIXTURE = os.path.join(os.path.dirname(os.path.abspath(__file__)),
+                       "test_patterns", "vulnerable", "rounding_direction.rs")
+
+SOURCE = '''
+pub fn redeem(ctx: Context<Redeem>, shares: u64) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let amount = shares * vault.assets / vault.supply;
+    let fee = amount * vault.fee_bps / 10_000;
+    vault.assets -= amount;
+    Ok(())
+}
+'''
+
+
+def test_sites_with_direction():
+    report = analyze_sources([("lib.rs", SOURCE)])
+    rows = [(s.line, s.value, s.rounds, s.should, s.favors_user) for s in report.sites]
+    assert rows == [(4, "amount", "floor", "floor", False), (5, "fee", "floor", "ceil", True)]
+    assert json.loads(report.to_json())["favoring_user"] == 1
+
+
+def test_markdown_table():
+    report = analyze_target(FIXTURE)
+    assert len(report.sites) == 7 and len(report.favoring_user) == 4
+    md = render_markdown(report)
+    assert "| rounding_direction.rs:9 | swap | `amount_in * pool.fee_bps / 10_000` | `fee` | by user | floor | ceil | **user** |" in md
+    assert "| rounding_direction.rs:18 | deposit |" in md and "| to user | floor | floor | program |" in md
@@ -0,0 +1,115 @@
+"""Tests for rule-set pinning and baselines recorded under another rule set."""
+
+import json
+import os
+import sys
+from datetime import date
+
+import pytest
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.engine import AnchorShieldEngine
+from scanner.ruleset import (
+    RULESET_SOURCES, SCANNER_DIR, Ruleset, RulesetError, bundled_rulesets, pin, record, ruleset_hash,
+)
+from scanner.suppression import write_baseline
+
+TODAY = date(2025, 6, 1)
+
+VULN = '''
+pub fn process_data(ctx: Context<ProcessData>) -> Result<()> {
+    let data = ctx.accounts.data_source.try_borrow_data()?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ProcessData<'info> {
+    pub authority: Signer<'info>,
+    pub data_source: AccountInfo<'info>,
+}
+'''
+
+
+class TestPin:
+    def test_report_records_installed_ruleset(self):
+        engine = AnchorShieldEngine(only=["ANCHOR-006"], ruleset=ruleset_hash()[:12])
+        report = engine.scan_content(VULN, "lib.rs")
+        assert report.to_dict()["ruleset"]["hash"] == ruleset_hash()
+        assert len(report.findings) == 1
+
+    def test_other_hash_and_unknown_version_are_refused(self):
+        with pytest.raises(RulesetError):
+            AnchorShieldEngine(ruleset="0" * 64)
+        with pytest.raises(RulesetError):
+            AnchorShieldEngine(ruleset="no-such-version")
+
+    def test_bundled_version_pins_its_hash(self, tmp_path):
+        manifest = str(tmp_path / "rulesets.json")
+        installed = Ruleset(ruleset_hash(), rules=["ANCHOR-006"])
+        assert record("9.9", installed, manifest)
+        assert not record("9.9", installed, manifest)
+        pin("9.9", installed, manifest)
+
+        with open(manifest) as fh:
+            data = json.load(fh)
+        data["rulesets"].append({"version": "9.8", "hash": "ab" * 32, "rules": []})
+        with open(manifest, "w") as fh:
+            json.dump(data, fh)
+        with pytest.raises(RulesetError):
+            pin("9.8", installed, manifest)
+
+    def test_sources_exist(self):
+        """A renamed source would silently drop out of the hash."""
+        assert all(os.path.exists(os.path.join(SCANNER_DIR, s)) for s in RULESET_SOURCES)
+
+
+class TestManifest:
+    def test_versions_and_hashes_are_unique(self):
+        entries = bundled_rulesets()
+        assert entries
+        assert len({e["version"] for e in entries}) == len(entries)
+        assert len({e["hash"] for e in entries}) == len(entries)
+
+    def test_released_rule_sets_other_than_installed_are_refused(self):
+        """A report pinned to an older release must not be answered by the current rules."""
+        older = [e for e in bundled_rulesets() if e["hash"] != ruleset_hash()]
+        assert older
+        for entry in older:
+            with pytest.raises(RulesetError):
+                AnchorShieldEngine(ruleset=entry["version"])
+            with pytest.raises(RulesetError):
+                AnchorShieldEngine(ruleset=entry["hash"][:12])
+
+
+class TestBaselineRuleset:
+    def _old_baseline(self, tmp_path):
+        findings = AnchorShieldEngine(only=["ANCHOR-006"], today=TODAY).scan_content(VULN, "lib.rs").findings
+        path = str(tmp_path / "baseline.json")
+        old = Ruleset("ab" * 32, "0.0.1", ["ANCHOR-006"])
+        write_baseline(findings, path, ruleset=old)
+        # An entry of a rule the installed rule set no longer has
+        with open(path) as fh:
+            data = json.load(fh)
+        data["entries"].append({"fingerprint": "f" * 16, "id": "ANCHOR-999", "file": "lib.rs", "line": 1})
+        with open(path, "w") as fh:
+            json.dump(data, fh)
+        return path
+
+    def test_baseline_from_other_ruleset_is_refused(self, tmp_path):
+        path = self._old_baseline(tmp_path)
+        with pytest.raises(RulesetError):
+            AnchorShieldEngine(only=["ANCHOR-006"], baseline=path, today=TODAY)
+
+    def test_migrate_drops_removed_rules_and_restamps(self, tmp_path):
+        path = self._old_baseline(tmp_path)
+        engine = AnchorShieldEngine(only=["ANCHOR-006"], baseline=path, today=TODAY, migrate=True)
+        assert engine.baseline_migration == (1, 1)
+        assert engine.scan_content(VULN, "lib.rs").findings == []
+
+        with open(path) as fh:
+            data = json.load(fh)
+        assert data["ruleset"]["hash"] == ruleset_hash()
+        assert [e["id"] for e in data["entries"]] == ["ANCHOR-006"]
+        # Migrated: accepted without the flag from now on
+        AnchorShieldEngine(only=["ANCHOR-006"], baseline=path, today=TODAY)
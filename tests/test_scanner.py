@@ -5,6 +5,7 @@ Each pattern has:
   - True negative: safe code that MUST NOT be flagged
 """
 
+import json
 import os
 import sys
 import pytest
@@ -12,13 +13,72 @@ import pytest
 # Add project root to path
 sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
 
+from scanner.config import load_overflow_checks
 from scanner.engine import AnchorShieldEngine
+from scanner.patterns.base import EFFORT_LEVELS
 from scanner.patterns.init_if_needed import InitIfNeededPattern
 from scanner.patterns.duplicate_mutable import DuplicateMutablePattern
 from scanner.patterns.realloc_payer import ReallocPayerPattern
 from scanner.patterns.type_cosplay import TypeCosplayPattern
 from scanner.patterns.close_reinit import CloseReinitPattern
 from scanner.patterns.missing_owner import MissingOwnerPattern
+from scanner.patterns.cpi_toctou import CpiToctouPattern
+from scanner.patterns.stale_constraint_ref import StaleConstraintRefPattern
+from scanner.patterns.crank_idempotency import CrankIdempotencyPattern
+from scanner.patterns.anchor_toml import AnchorTomlPattern
+from scanner.patterns.adhoc_percentage import AdHocPercentagePattern
+from scanner.patterns.unit_confusion import UnitConfusionPattern
+from scanner.patterns.ownership_transition import OwnershipTransitionPattern
+from scanner.patterns.sequencing_flag import SequencingFlagPattern
+from scanner.patterns.doc_constraint import DocConstraintPattern
+from scanner.patterns.privileged_signer import PrivilegedSignerPattern
+from scanner.patterns.vault_creation import VaultCreationPattern
+from scanner.patterns.time_units import TimeUnitPattern
+from scanner.patterns.account_aliasing import AccountAliasingPattern
+from scanner.patterns.constraint_anomaly import ConstraintAnomalyPattern
+from scanner.patterns.sysvar_spoofing import SysvarSpoofingPattern
+from scanner.patterns.state_binding import StateBindingPattern
+from scanner.patterns.state_reinit import StateReinitPattern
+from scanner.patterns.economic_params import EconomicParamsPattern
+from scanner.patterns.close_destination import CloseDestinationPattern
+from scanner.patterns.constant_drift import ConstantDriftPattern
+from scanner.patterns.position_binding import PositionBindingPattern
+from scanner.patterns.packed_zero_copy import PackedZeroCopyPattern
+from scanner.patterns.account_space import AccountSpacePattern
+from scanner.patterns.access_control import AccessControlPattern
+from scanner.patterns.pda_collision import PdaSeedCollisionPattern
+from scanner.patterns.signer_seeds import SignerSeedsPattern
+from scanner.patterns.reward_groups import RewardGroupPattern
+from scanner.patterns.authority_init import AuthorityInitPattern
+from scanner.patterns.token_constraints import TokenConstraintsPattern
+from scanner.patterns.nft_mint import NftMintPattern
+from scanner.patterns.nft_metadata import NftMetadataPattern
+from scanner.patterns.nft_custody import NftCustodyPattern
+from scanner.patterns.mint_screening import MintScreeningPattern
+from scanner.patterns.constraint_logic import ConstraintLogicPattern
+from scanner.patterns.token_extensions import TokenExtensionsPattern
+from scanner.patterns.client_enforcement import ClientEnforcementPattern
+from scanner.patterns.token_program_mix import TokenProgramMixPattern
+from scanner.patterns.check_comment import CheckCommentPattern
+from scanner.patterns.fixed_point import FixedPointPattern
+from scanner.patterns.unchecked_arithmetic import UncheckedArithmeticPattern
+from scanner.patterns.zero_division import ZeroDivisionPattern
+from scanner.patterns.amount_caps import AmountCapsPattern
+from scanner.patterns.mul_chain import MulChainPattern
+from scanner.patterns.operation_order import OperationOrderPattern
+from scanner.patterns.truncating_cast import TruncatingCastPattern
+from scanner.patterns.rounding_direction import RoundingDirectionPattern
+from scanner.patterns.has_one_binding import HasOneBindingPattern
+from scanner.patterns.saturating_sub import SaturatingSubPattern
+from scanner.patterns.wrapping_arithmetic import WrappingArithmeticPattern
+from scanner.patterns.handler_panic import HandlerPanicPattern
+from scanner.patterns.checked_unwrap import CheckedUnwrapPattern
+from scanner.patterns.loop_accumulator import LoopAccumulatorPattern
+from scanner.patterns.signed_time import SignedTimeCastPattern
+from scanner.patterns.lending_borrow import BorrowCollateralCheckPattern
+from scanner.patterns.lending_withdraw import WithdrawDebtCheckPattern
+from scanner.patterns.lending_health import HealthFormulaPattern
+from scanner.patterns.lending_liquidation import LiquidationIncompletePattern
 
 TEST_DIR = os.path.join(os.path.dirname(__file__), "test_patterns")
 VULN_DIR = os.path.join(TEST_DIR, "vulnerable")
@@ -31,6 +91,53 @@ def read_test_file(subdir, filename):
         return f.read()
 
 
+HELPER_CHECKED_SOURCE = """
+#[program]
+pub mod staking {
+    pub fn deposit_stake(ctx: Context<DepositStake>) -> Result<()> {
+        checks::check_owner_program(&ctx.accounts.stake_account, &stake::program::ID, "stake_account")?;
+        ctx.accounts.load_validator(&ctx.accounts.validator_list)?;
+        log_account(&ctx.accounts.reserve);
+        Ok(())
+    }
+}
+
+impl<'info> DepositStake<'info> {
+    pub fn load_validator(&self, list: &AccountInfo<'info>) -> Result<ValidatorList> {
+        read_list(list)
+    }
+}
+
+fn read_list(info: &AccountInfo) -> Result<ValidatorList> {
+    ValidatorList::try_deserialize(&mut &info.data.borrow()[..])
+}
+
+fn log_account(info: &AccountInfo) {
+    msg!("{}", info.key());
+}
+
+pub fn check_owner_program<'info, A: ToAccountInfo<'info>>(
+    account: &A,
+    owner: &Pubkey,
+    field_name: &str,
+) -> Result<()> {
+    let actual_owner = account.to_account_info().owner;
+    if actual_owner == owner {
+        Ok(())
+    } else {
+        Err(ProgramError::InvalidArgument.into())
+    }
+}
+
+#[derive(Accounts)]
+pub struct DepositStake<'info> {
+    pub stake_account: AccountInfo<'info>,
+    pub validator_list: AccountInfo<'info>,
+    pub reserve: AccountInfo<'info>,
+}
+"""
+
+
 # ─── ANCHOR-001: init_if_needed Incomplete Field Validation ─────────
 
 class TestAnchor001:
@@ -149,6 +256,33 @@ class TestAnchor004:
         anchor_004_findings = [f for f in findings if f.id == "ANCHOR-004"]
         assert len(anchor_004_findings) == 0
 
+    def test_discriminator_checked_in_helper(self):
+        """A try_deserialize in a helper counts; an owner check alone does not stop cosplay."""
+        findings = self.pattern.scan("test.rs", HELPER_CHECKED_SOURCE)
+        assert sorted(f.description.split("'")[1] for f in findings) == ["reserve", "stake_account"]
+
+    def test_raw_byte_reads_in_helpers_report_ranges(self):
+        """CHECK comments do not cover bytes a helper unpacks, casts or slices without an owner check."""
+        content = read_test_file("vulnerable", "raw_bytes_helper_reads.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [21, 32]
+        assert "'fee_vault' (UncheckedAccount) has no owner check" in findings[0].description
+        assert "all bytes via StateWithExtensions::<Account>::unpack in sweep -> check_vault_balance()" \
+            in findings[0].description
+        assert "bytes [8..8 + 48] via bytemuck::from_bytes in sweep -> read_price(); " \
+            "bytes [56..60] via array_ref! in sweep -> read_price()" in findings[1].description
+
+    def test_raw_byte_reads_of_pinned_accounts_are_safe(self):
+        content = read_test_file("vulnerable", "raw_bytes_helper_reads.rs").replace(
+            "    #[account(mut)]\n    pub pool", "    #[account(mut, has_one = fee_vault)]\n    pub pool",
+        ).replace(
+            "pub fn read_price(oracle: &AccountInfo) -> Result<u64> {\n",
+            "pub fn read_price(oracle: &AccountInfo) -> Result<u64> {\n"
+            "    require_keys_eq!(*oracle.owner, pyth::ID, SweepError::VaultShort);\n",
+        )
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
 
 # ─── ANCHOR-005: Close + Reinit Lifecycle ───────────────────────────
 
@@ -176,6 +310,31 @@ class TestAnchor005:
         findings = self.pattern.scan("test.rs", content)
         assert len(findings) == 0
 
+    def test_seeds_decide_whether_the_address_is_revived(self):
+        """Same seeds are called out; a different seed prefix derives another address."""
+        content = read_test_file("vulnerable", "position_close_lifecycle.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [51]
+        assert 'same seeds [b"position",owner]' in findings[0].description
+        assert "Lifecycle: init (OpenPosition) -> init_if_needed (Deposit)" in findings[0].description
+        other = content.replace(
+            'seeds = [b"position", owner.key().as_ref()],\n        bump,',
+            'seeds = [b"deposit", owner.key().as_ref()],\n        bump,',
+        ).replace("    #[account(mut, close = payer)]", "    #[account(mut)]")
+        assert self.pattern.scan("test.rs", other) == []
+
+    def test_details_carry_both_seed_recipes(self):
+        """The JSON payload names the closing and the re-creating field with their seeds."""
+        content = read_test_file("vulnerable", "position_close_lifecycle.rs")
+        details = self.pattern.scan("test.rs", content)[0].to_dict()["details"]
+        assert details["type"] == "pda-collision" and details["account_type"] == "Position"
+        assert details["same_address"] is True
+        assert details["closed"] == {
+            "struct": "ClosePosition", "field": "position", "line": 59,
+            "seeds": '[b"position", owner.key().as_ref()]', "components": ['b"position"', "owner"],
+        }
+        assert (details["recreated"]["struct"], details["recreated"]["line"]) == ("Deposit", 51)
+
 
 # ─── ANCHOR-006: Missing Owner Validation ───────────────────────────
 
@@ -210,60 +369,2667 @@ class TestAnchor006:
         anchor_006_findings = [f for f in findings if f.id == "ANCHOR-006"]
         assert len(anchor_006_findings) == 0
 
+    def test_owner_checked_in_helper(self):
+        """Accounts passed to a checks.rs-style owner helper are not reported; others still are."""
+        findings = self.pattern.scan("test.rs", HELPER_CHECKED_SOURCE)
+        assert sorted(f.description.split("'")[1] for f in findings) == ["reserve", "validator_list"]
 
-# ─── Integration: Full engine scan ──────────────────────────────────
+    def test_ignores_cross_program_pda(self):
+        """A PDA derived under seeds::program (Metaplex metadata) is a checked address."""
+        content = """
+        #[derive(Accounts)]
+        pub struct Stake<'info> {
+            #[account(
+                seeds = [
+                    b"metadata",
+                    Metadata::id().as_ref(),
+                    nft_mint.key().as_ref()
+                ],
+                seeds::program = Metadata::id(),
+                bump
+            )]
+            pub nft_metadata: AccountInfo<'info>,
+            pub nft_mint: Account<'info, Mint>,
+        }
+        """
+        assert self.pattern.scan("test.rs", content) == []
 
-class TestEngineIntegration:
+
+# ─── ANCHOR-007: Stale Account Data Across CPI ──────────────────────
+
+class TestAnchor007:
     def setup_method(self):
-        self.engine = AnchorShieldEngine()
+        self.pattern = CpiToctouPattern()
 
-    def test_scan_vulnerable_directory(self):
-        """Engine should find multiple vulnerabilities in vulnerable test files."""
-        report = self.engine.scan_directory(VULN_DIR)
-        assert report.files_scanned > 0
-        assert len(report.findings) > 0
-        assert report.security_score != "A"
+    def test_detects_balance_captured_before_cpi(self):
+        """Scanner must detect a vault balance captured before a CPI and used after."""
+        content = read_test_file("vulnerable", "stale_balance_after_cpi.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 1
+        assert findings[0].id == "ANCHOR-007"
+        assert "vault_balance" in findings[0].description
 
-        # Check all pattern IDs are represented
-        found_ids = {f.id for f in report.findings}
-        assert "ANCHOR-001" in found_ids
-        assert "ANCHOR-003" in found_ids
+    def test_ignores_reload_after_cpi(self):
+        """Reading the balance after reload() is safe."""
+        content = """
+        pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+            token::transfer(ctx.accounts.transfer_ctx(), amount)?;
+            ctx.accounts.vault.reload()?;
+            let vault_balance = ctx.accounts.vault.amount;
+            ctx.accounts.pool.total = vault_balance;
+            Ok(())
+        }
+        """
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
 
-    def test_scan_safe_directory(self):
-        """Engine should find minimal or no issues in safe test files."""
-        report = self.engine.scan_directory(SAFE_DIR)
-        assert report.files_scanned > 0
-        # Safe files should have very few findings
-        critical_high = [f for f in report.findings if f.severity in ("Critical", "High")]
-        # Acceptable: some patterns may still trigger on safe files
-        # but the specific patterns should not
-        anchor_001 = [f for f in report.findings if f.id == "ANCHOR-001"]
-        anchor_003 = [f for f in report.findings if f.id == "ANCHOR-003"]
-        assert len(anchor_001) == 0
-        assert len(anchor_003) == 0
+    def test_ignores_values_only_logged(self):
+        """Pre-CPI values emitted in events are not relied on."""
+        content = """
+        pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+            let before = ctx.accounts.vault.amount;
+            token::transfer(CpiContext::new(p, Transfer { to: ctx.accounts.vault.to_account_info() }), amount)?;
+            emit!(DepositEvent {
+                before,
+                amount,
+            });
+            Ok(())
+        }
+        """
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
 
-    def test_report_json_serialization(self):
-        """Scan report should serialize to valid JSON."""
-        report = self.engine.scan_directory(VULN_DIR)
-        import json
-        json_str = report.to_json()
-        parsed = json.loads(json_str)
-        assert "findings" in parsed
-        assert "summary" in parsed
-        assert parsed["files_scanned"] > 0
+    def test_details_resolve_cpi_through_wrapper(self):
+        """The target of a local transfer_* wrapper is resolved from the wrapper's own CPI."""
+        content = """
+        pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+            let before = ctx.accounts.vault.amount;
+            transfer_from_pool_vault_to_user(&ctx.accounts.vault, &ctx.accounts.user_tokens, amount)?;
+            ctx.accounts.pool.total = before - amount;
+            Ok(())
+        }
 
-    def test_security_score_computation(self):
-        """Security score should reflect severity of findings."""
-        report = self.engine.scan_directory(VULN_DIR)
-        # With multiple findings, score should be worse than A
-        assert report.security_score != "A"
+        fn transfer_from_pool_vault_to_user(vault: &Account<TokenAccount>, to: &Account<TokenAccount>, amount: u64) -> Result<()> {
+            token_interface::transfer_checked(CpiContext::new_with_signer(p, accounts, seeds), amount, 6)
+        }
+        """
+        details = self.pattern.scan("test.rs", content)[0].to_dict()["details"]
+        assert details == {
+            "type": "cpi-target", "program": "spl_token_interface", "instruction": "transfer_checked",
+            "via": "spl-helper", "resolved": True, "signed": True,
+            "chain": ["withdraw", "transfer_from_pool_vault_to_user"],
+            "call": "transfer_from_pool_vault_to_user(&ctx.accounts.vault, &ctx.accounts.user_tokens, amount)",
+        }
+        direct = self.pattern.scan("test.rs", read_test_file("vulnerable", "stale_balance_after_cpi.rs"))
+        assert direct[0].details.chain == ["deposit"] and direct[0].details.via == "spl-helper"
 
-    def test_empty_file_no_crash(self):
-        """Engine should handle empty files gracefully."""
-        report = self.engine.scan_content("", "empty.rs")
-        assert report.files_scanned == 1
-        assert len(report.findings) == 0
-        assert report.security_score == "A"
+
+# ─── ANCHOR-008: Copy-Pasted Constraint With Stale Identifier ───────
+
+class TestAnchor008:
+    def setup_method(self):
+        self.pattern = StaleConstraintRefPattern()
+
+    def test_detects_wrong_sibling_index(self):
+        """token_account_1 constrained against token_vault_0 must be flagged."""
+        content = """
+        #[derive(Accounts)]
+        pub struct IncreaseLiquidity<'info> {
+            #[account(mut, token::mint = token_vault_0.mint)]
+            pub token_account_0: Box<Account<'info, TokenAccount>>,
+            #[account(mut, token::mint = token_vault_0.mint)]
+            pub token_account_1: Box<Account<'info, TokenAccount>>,
+            #[account(mut, constraint = token_vault_1.key() == pool_state.load()?.token_vault_0)]
+            pub token_vault_1: Box<Account<'info, TokenAccount>>,
+        }
+        """
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [7, 9]
+        assert findings[0].id == "ANCHOR-008"
+
+    def test_detects_bump_from_unrelated_account(self):
+        """bump taken from an account that is not part of the seeds."""
+        content = """
+        #[derive(Accounts)]
+        pub struct Withdraw<'info> {
+            #[account(mut, seeds = [b"vault", market.key().as_ref()], bump = pool.bump)]
+            pub vault: SystemAccount<'info>,
+        }
+        """
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 1
+        assert "'pool'" in findings[0].description
+
+    def test_ignores_ordering_and_matching_constraints(self):
+        """Cross-index ordering checks and same-index references are fine."""
+        content = """
+        #[derive(Accounts)]
+        pub struct CreatePool<'info> {
+            #[account(constraint = token_mint_0.key() < token_mint_1.key())]
+            pub token_mint_0: Box<InterfaceAccount<'info, Mint>>,
+            #[account(mut, seeds = [b"vault", pool.key().as_ref()], bump = pool.bump)]
+            pub vault: SystemAccount<'info>,
+        }
+        """
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+
+# ─── ANCHOR-009: Non-Idempotent Crank Instruction ───────────────────
+
+class TestAnchor009:
+    def setup_method(self):
+        self.pattern = CrankIdempotencyPattern()
+
+    def test_detects_unguarded_crank(self):
+        """Crank that writes last_update_epoch but never checks it."""
+        content = read_test_file("vulnerable", "crank_no_epoch_guard.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 1
+        assert findings[0].id == "ANCHOR-009"
+        assert "acc_reward_per_share" in findings[0].description
+
+    def test_ignores_epoch_guarded_crank(self):
+        """require!(last_update_epoch < clock.epoch) makes the crank idempotent."""
+        content = read_test_file("safe", "crank_epoch_guarded.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+    def test_ignores_elapsed_time_accrual_and_signed_cranks(self):
+        """Accrual from elapsed time adds zero on repeat; signer-gated cranks are admin-only."""
+        content = """
+        pub fn accrue_interest(ctx: Context<Accrue>) -> Result<()> {
+            let now = Clock::get()?.unix_timestamp;
+            let elapsed = now - ctx.accounts.market.last_accrual_ts;
+            ctx.accounts.market.total_interest += elapsed as u64 * RATE;
+            ctx.accounts.market.last_accrual_ts = now;
+            Ok(())
+        }
+
+        pub fn distribute(ctx: Context<Distribute>, amount: u64) -> Result<()> {
+            ctx.accounts.pool.total_rewards += amount;
+            Ok(())
+        }
+
+        #[derive(Accounts)]
+        pub struct Distribute<'info> {
+            #[account(mut, has_one = admin)]
+            pub pool: Account<'info, Pool>,
+            pub admin: Signer<'info>,
+        }
+        """
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+
+# ─── ANCHOR-010: Anchor.toml Configuration Inconsistency ────────────
+
+class TestAnchor010:
+    def setup_method(self):
+        self.pattern = AnchorTomlPattern()
+
+    def test_detects_misconfigured_workspace(self):
+        """seeds = false, committed wallet, mainnet tests and a stale program ID."""
+        findings = self.pattern.scan_workspace(os.path.join(VULN_DIR, "anchor_workspace"))
+        assert {f.line for f in findings} == {5, 9, 12, 13}
+        assert all(f.file == "Anchor.toml" for f in findings)
+        by_line = {f.line: f for f in findings}
+        assert by_line[13].severity == "High" and "deployer-keypair.json" in by_line[13].description
+        assert "RwdPoo" in by_line[9].description
+
+    def test_ignores_consistent_workspace(self, tmp_path):
+        """Localnet cluster, home-directory wallet and matching IDs are fine."""
+        program_id = "3ARMH9zfVCnU2TKiphU4xcEyWdA45fc1sjKEtYMdf3gr"
+        (tmp_path / "Anchor.toml").write_text(
+            '[programs.localnet]\n'
+            f'vault = "{program_id}"\n\n'
+            '[provider]\ncluster = "localnet"\nwallet = "~/.config/solana/id.json"\n\n'
+            '[scripts]\ntest = "anchor run mocha"\n'
+        )
+        src = tmp_path / "programs" / "vault" / "src"
+        src.mkdir(parents=True)
+        (src / "lib.rs").write_text(
+            f'declare_id!("{program_id}");\n'
+            'fn check(ctx: &Context<Deposit>) -> Result<()> {\n'
+            '    require!(check_id(ctx.program_id), ErrorCode::InvalidProgramId);\n'
+            f'    require_keys_eq!(*ctx.program_id, pubkey!("{program_id}"));\n'
+            '    Ok(())\n'
+            '}\n'
+        )
+        deploy = tmp_path / "target" / "deploy"
+        deploy.mkdir(parents=True)
+        (deploy / "vault-keypair.json").write_text(json.dumps(list(range(64))))
+        assert self.pattern.scan_workspace(str(tmp_path)) == []
+
+    def test_detects_placeholder_id_and_stale_keypair(self, tmp_path):
+        """Template ID in a shipped program, and a deploy keypair for another address."""
+        placeholder = "Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS"
+        (tmp_path / "Anchor.toml").write_text(f'[programs.localnet]\nvault = "{placeholder}"\n')
+        src = tmp_path / "programs" / "vault" / "src"
+        src.mkdir(parents=True)
+        (src / "lib.rs").write_text(f'use anchor_lang::prelude::*;\ndeclare_id!("{placeholder}");\n')
+        deploy = tmp_path / "target" / "deploy"
+        deploy.mkdir(parents=True)
+        (deploy / "vault-keypair.json").write_text(json.dumps(list(range(64))))
+        findings = self.pattern.scan_workspace(str(tmp_path))
+        assert [(f.file, f.line) for f in findings] == [(os.path.join("programs", "vault", "src", "lib.rs"), 2)] * 2
+        assert "template ID" in findings[0].description
+        assert "3ARMH9zfVCnU2TKiphU4xcEyWdA45fc1sjKEtYMdf3gr" in findings[1].description
+
+    def test_detects_guard_on_foreign_id(self, tmp_path):
+        """check_id of another module's ID and a hard-coded ID that is not declare_id!."""
+        (tmp_path / "Anchor.toml").write_text("[provider]\ncluster = \"localnet\"\n")
+        src = tmp_path / "programs" / "vault" / "src"
+        src.mkdir(parents=True)
+        (src / "lib.rs").write_text(
+            'declare_id!("Vau1t11111111111111111111111111111111111111");\n'
+            'pub mod legacy {\n'
+            '    declare_id!("LegacyVau1t1111111111111111111111111111111");\n'
+            '}\n'
+            'fn check(ctx: &Context<Deposit>) -> Result<()> {\n'
+            '    require!(legacy::check_id(ctx.program_id), ErrorCode::InvalidProgramId);\n'
+            '    if *ctx.program_id != pubkey!("Stag1ngVau1t111111111111111111111111111111") {\n'
+            '        return err!(ErrorCode::InvalidProgramId);\n'
+            '    }\n'
+            '    Ok(())\n'
+            '}\n'
+        )
+        findings = self.pattern.scan_workspace(str(tmp_path))
+        assert [(f.line, f.severity) for f in findings] == [(6, "High"), (7, "High")]
+        assert "LegacyVau1t" in findings[0].description
+
+    def test_ignores_placeholder_in_test_workspace(self, tmp_path):
+        """Programs under tests/ are fixtures; the template ID is expected there."""
+        workspace = tmp_path / "tests" / "escrow"
+        src = workspace / "programs" / "escrow" / "src"
+        src.mkdir(parents=True)
+        (workspace / "Anchor.toml").write_text("[provider]\ncluster = \"localnet\"\n")
+        (src / "lib.rs").write_text('declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");\n')
+        assert self.pattern.scan_workspace(str(tmp_path)) == []
+
+    def test_engine_reports_workspace_findings(self):
+        """Directory scans include Anchor.toml findings alongside Rust findings."""
+        report = AnchorShieldEngine().scan_directory(VULN_DIR)
+        toml = [f for f in report.findings if f.id == "ANCHOR-010"]
+        assert len(toml) == 4
+        assert all(f.file == os.path.join("anchor_workspace", "Anchor.toml") for f in toml)
+
+
+# ─── ANCHOR-011: Ad-Hoc Percentage Math With Bare Denominators ──────
+
+class TestAnchor011:
+    def setup_method(self):
+        self.pattern = AdHocPercentagePattern()
+
+    def test_detects_mixed_denominators(self):
+        """Percent in one handler and basis points in another must be flagged."""
+        content = read_test_file("vulnerable", "adhoc_fee_denominators.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [12, 21]
+        assert "100, 10000" in findings[0].description
+
+    def test_ignores_typed_fee_abstraction(self):
+        """Math inside a Fee type's impl is the shared abstraction itself."""
+        content = """
+        impl Fee {
+            pub fn apply(&self, amount: u64) -> u64 {
+                amount * self.basis_points as u64 / 10_000
+            }
+            pub fn apply_pct(&self, amount: u64) -> u64 {
+                amount * self.pct as u64 / 100
+            }
+        }
+        pub fn swap(ctx: Context<Swap>, amount: u64) -> Result<()> {
+            let fee = ctx.accounts.config.fee.apply(amount);
+            Ok(())
+        }
+        """
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+    def test_ignores_single_site(self):
+        """One literal-denominator site in one function is not a spread pattern."""
+        content = """
+        pub fn swap(ctx: Context<Swap>, amount: u64) -> Result<()> {
+            let fee = amount * FEE_BPS / 10_000;
+            let ms = now_ms / 1000;
+            Ok(())
+        }
+        """
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+
+# ─── ANCHOR-012: Lamport / Token Unit Confusion ─────────────────────────────
+
+class TestAnchor012:
+    def setup_method(self):
+        self.pattern = UnitConfusionPattern()
+
+    def test_detects_lamport_token_mix(self):
+        """mSOL amounts added to lamport totals and shares compared to lamports."""
+        content = read_test_file("vulnerable", "lamport_token_unit_mix.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [14, 21]
+        assert "(token amount)" in findings[0].description
+        assert "(shares)" in findings[1].description
+
+    def test_ignores_explicit_conversion(self):
+        """Conversion helpers and decimals scaling make the units explicit."""
+        content = """
+        pub fn deposit(ctx: Context<Deposit>, msol_amount: u64) -> Result<()> {
+            let value_lamports = state.msol_to_sol(msol_amount)?;
+            state.total_lamports += value_lamports;
+            let scaled = msol_amount * LAMPORTS_PER_SOL / 10u64.pow(ctx.accounts.mint.decimals as u32);
+            let total = state.available_lamports + scaled;
+            Ok(())
+        }
+        """
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+    def test_native_mint_token_account_holds_lamports(self):
+        """wSOL token account balances are lamports; adding them to SOL is fine."""
+        content = """
+        pub fn sweep(ctx: Context<Sweep>) -> Result<()> {
+            let total_lamports = ctx.accounts.vault.lamports() + ctx.accounts.wsol.amount;
+            Ok(())
+        }
+        #[derive(Accounts)]
+        pub struct Sweep<'info> {
+            #[account(mut, token::mint = native_mint::ID)]
+            pub wsol: Account<'info, TokenAccount>,
+            pub vault: AccountInfo<'info>,
+        }
+        """
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+
+# ─── ANCHOR-013: Account Used Under Unexpected Owner ────────────────────────
+
+class TestAnchor013:
+    def setup_method(self):
+        self.pattern = OwnershipTransitionPattern()
+
+    def test_detects_access_under_wrong_owner(self):
+        """Read before create_account, and a program write into a token vault."""
+        content = read_test_file("vulnerable", "use_before_create.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [16, 44]
+        assert "System Program" in findings[0].description
+        assert "Line 38 hands it to the Token Program" in findings[1].description
+
+    def test_invoke_create_account_with_program_owner(self):
+        """system_instruction::create_account(.., program_id) makes later writes valid."""
+        content = """
+        pub fn open(ctx: Context<Open>, lamports: u64, space: u64) -> Result<()> {
+            let state = ctx.accounts.state.to_account_info();
+            invoke(
+                &system_instruction::create_account(
+                    ctx.accounts.payer.key, state.key, lamports, space, ctx.program_id,
+                ),
+                &[ctx.accounts.payer.to_account_info(), state.clone()],
+            )?;
+            let mut data = state.try_borrow_mut_data()?;
+            data[0] = 1;
+            let parsed = TokenAccount::try_deserialize(&mut &state.data.borrow()[..])?;
+            Ok(())
+        }
+        #[derive(Accounts)]
+        pub struct Open<'info> {
+            #[account(mut)]
+            pub payer: Signer<'info>,
+            /// CHECK: created here
+            #[account(mut)]
+            pub state: AccountInfo<'info>,
+        }
+        """
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 1
+        assert "deserialized as TokenAccount" in findings[0].description
+
+    def test_ignores_handlers_without_transitions(self):
+        """Typed accounts alone never produce findings without a create in the handler."""
+        content = read_test_file("safe", "proper_account_type.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+
+# ─── ANCHOR-014: Missing Instruction-Order Check ────────────────────────────
+
+class TestAnchor014:
+    def setup_method(self):
+        self.pattern = SequencingFlagPattern()
+
+    def test_detects_handler_skipping_flag(self):
+        """stake mutates Pool while deposit/borrow wait for is_active."""
+        content = read_test_file("vulnerable", "missing_active_check.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [33]
+        assert "'is_active'" in findings[0].description
+        assert "set by fund_pool" in findings[0].description
+
+    def test_constraint_counts_as_check(self):
+        """A constraint in the accounts struct covers every handler using it."""
+        content = read_test_file("vulnerable", "missing_active_check.rs").replace(
+            "pub struct UserPool<'info> {\n    #[account(mut)]",
+            "pub struct UserPool<'info> {\n    #[account(mut, constraint = pool.is_active)]",
+        )
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+    def test_ignores_flag_checked_by_minority(self):
+        """A flag gating one handler out of many is not a program-wide sequencing rule."""
+        content = """
+        pub fn open(ctx: Context<Admin>, t: u64) -> Result<()> {
+            ctx.accounts.pool.open_time = t;
+            Ok(())
+        }
+        pub fn swap(ctx: Context<Use>, amount: u64) -> Result<()> {
+            require!(now > ctx.accounts.pool.open_time, E::NotOpen);
+            ctx.accounts.pool.volume += amount;
+            Ok(())
+        }
+        pub fn add_liquidity(ctx: Context<Use>, amount: u64) -> Result<()> {
+            ctx.accounts.pool.liquidity += amount;
+            Ok(())
+        }
+        pub fn remove_liquidity(ctx: Context<Use>, amount: u64) -> Result<()> {
+            ctx.accounts.pool.liquidity -= amount;
+            Ok(())
+        }
+        #[derive(Accounts)]
+        pub struct Admin<'info> {
+            #[account(mut)]
+            pub pool: Account<'info, Pool>,
+        }
+        #[derive(Accounts)]
+        pub struct Use<'info> {
+            #[account(mut)]
+            pub pool: Account<'info, Pool>,
+        }
+        #[account]
+        pub struct Pool {
+            pub open_time: u64,
+            pub volume: u64,
+            pub liquidity: u64,
+        }
+        """
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+
+# ─── ANCHOR-015: Documented Requirement Not Enforced ────────────────────────
+
+class TestAnchor015:
+    def setup_method(self):
+        self.pattern = DocConstraintPattern()
+
+    def test_detects_documented_ordering_and_relation(self):
+        """Mint ordering and fee vault ownership are documented but never checked."""
+        content = read_test_file("vulnerable", "doc_unenforced_constraint.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [26, 33]
+        assert "token_mint_0 with token_mint_1" in findings[0].description
+        assert "fee_vault to pool_creator" in findings[1].description
+
+    def test_details_carry_the_violated_expression(self):
+        """JSON consumers get the requirement and the condition that would enforce it."""
+        content = read_test_file("vulnerable", "doc_unenforced_constraint.rs")
+        report = AnchorShieldEngine(only=["ANCHOR-015"]).scan_content(content, "lib.rs")
+        details = [f["details"] for f in json.loads(report.to_json())["findings"]]
+        assert details[0] == {
+            "type": "invariant", "struct": "CreatePool", "field": "token_mint_0", "kind": "ordering",
+            "requirement": "must be smaller then token_1 mint",
+            "expression": "token_mint_0.key() < token_mint_1.key()", "related": "token_mint_1",
+        }
+        assert (details[1]["kind"], details[1]["related"]) == ("relation", "pool_creator")
+
+    def test_constraint_satisfies_documentation(self):
+        """The raydium-style ordering constraint and a token::authority clause enforce the docs."""
+        content = read_test_file("vulnerable", "doc_unenforced_constraint.rs").replace(
+            "    /// Token_0 mint, the key must be smaller then token_1 mint.\n",
+            "    /// Token_0 mint, the key must be smaller then token_1 mint.\n"
+            "    #[account(constraint = token_mint_0.key() < token_mint_1.key())]\n",
+        ).replace(
+            "    #[account(mut)]\n    pub fee_vault",
+            "    #[account(mut, token::authority = pool_creator)]\n    pub fee_vault",
+        )
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+    def test_ignores_unresolvable_and_plain_docs(self):
+        """Docs that name no field of the struct, or state no requirement, are skipped."""
+        content = """
+        #[derive(Accounts)]
+        pub struct Swap<'info> {
+            /// The user performing the swap
+            pub user: Signer<'info>,
+            /// Must match the mint configured off-chain
+            pub input_mint: Account<'info, Mint>,
+            /// Must be a signer
+            pub authority: Signer<'info>,
+        }
+        """
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+
+# ─── ANCHOR-016: Privileged Instruction Without Authority Signer ────────────
+
+class TestAnchor016:
+    def setup_method(self):
+        self.pattern = PrivilegedSignerPattern()
+
+    def test_detects_admin_writes_without_signer(self):
+        """Fee written with an unsigned has_one authority; pause flag with no signer at all."""
+        content = read_test_file("vulnerable", "admin_write_no_signer.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [12, 17]
+        assert "Pool.authority is passed as UncheckedAccount" in findings[0].description
+        assert "takes no signer (expected Pool.authority)" in findings[1].description
+
+    def test_signed_authority_is_safe(self):
+        """Signer<'info> tied to the stored authority guards both writes."""
+        content = read_test_file("vulnerable", "admin_write_no_signer.rs").replace(
+            "    /// CHECK: matched against pool.authority\n    pub authority: UncheckedAccount<'info>,",
+            "    pub authority: Signer<'info>,",
+        ).replace(
+            "pub struct Pause<'info> {\n    #[account(mut)]\n    pub pool: Account<'info, Pool>,\n",
+            "pub struct Pause<'info> {\n    #[account(mut, has_one = authority)]\n    pub pool: Account<'info, Pool>,\n"
+            "    pub authority: Signer<'info>,\n",
+        )
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+    def test_ignores_initialization_and_user_fields(self):
+        """Creating the pool sets its authority; non-admin fields are not privileged."""
+        content = """
+        #[program]
+        pub mod p {
+            pub fn create(ctx: Context<Create>, fee_rate: u32) -> Result<()> {
+                let pool = &mut ctx.accounts.pool;
+                pool.authority = ctx.accounts.creator.key();
+                pool.fee_rate = fee_rate;
+                Ok(())
+            }
+        }
+
+        #[derive(Accounts)]
+        pub struct Create<'info> {
+            #[account(init, payer = creator, space = 8 + 40)]
+            pub pool: Account<'info, Pool>,
+            pub creator: UncheckedAccount<'info>,
+        }
+        """
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+    def test_detects_pda_signed_payout_two_calls_deep(self):
+        """claim reaches the pool-signed transfer through pay_rewards; withdraw has an owner signer."""
+        content = read_test_file("vulnerable", "pda_helper_no_signer.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [13]
+        assert "pay_rewards → transfer_from_pool_vault_to_user" in findings[0].description
+        assert "signs with &[&[POOL_SEED, &[pool.bump]]]" in findings[0].description
+
+    def test_signer_in_nested_accounts_struct_gates_payout(self):
+        content = read_test_file("vulnerable", "pda_helper_no_signer.rs").replace(
+            "pub struct Claim<'info> {\n",
+            "pub struct Claim<'info> {\n    pub auth: ClaimAuth<'info>,\n",
+        ) + "\n#[derive(Accounts)]\npub struct ClaimAuth<'info> {\n    pub claimant: Signer<'info>,\n}\n"
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+
+# ─── ANCHOR-017: In-Handler Vault Creation Not Pinned ───────────────────────
+
+class TestAnchor017:
+    def setup_method(self):
+        self.pattern = VaultCreationPattern()
+
+    def test_detects_unpinned_vault_creation(self):
+        """Helper creation without the mint in seeds; CPI creation with a caller-chosen authority."""
+        content = read_test_file("vulnerable", "vault_creation_unpinned.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [(f.line, f.severity) for f in findings] == [(11, "Medium"), (23, "High")]
+        assert "mint (token_mint_0 is not one of the vault's seeds)" in findings[0].description
+        assert "owner program (token_program_0" in findings[0].description
+        assert "Trusted by seeds alone in Deposit.token_vault_0" in findings[0].description
+        assert "authority (pool_creator" in findings[1].description
+
+    def test_raydium_vault_creation_is_pinned(self):
+        """Upstream raydium pins seeds, mint, pool PDA authority and mint::token_program."""
+        content = read_test_file("vulnerable", "vault_creation_unpinned.rs").replace(
+            "seeds = [POOL_VAULT_SEED.as_bytes(), pool_state.key().as_ref()], bump)]\n    pub token_vault_0: Unchecked",
+            "seeds = [POOL_VAULT_SEED.as_bytes(), pool_state.key().as_ref(), token_mint_0.key().as_ref()], bump)]\n"
+            "    pub token_vault_0: Unchecked",
+        ).replace(
+            "    pub token_mint_0: Box<",
+            "    #[account(mint::token_program = token_program_0)]\n    pub token_mint_0: Box<",
+        ).replace(
+            "authority: ctx.accounts.pool_creator.to_account_info()",
+            "authority: ctx.accounts.pool_state.to_account_info()",
+        )
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+    def test_ignores_typed_vaults(self):
+        """Vaults declared with init + token::* are checked by Anchor, not by this rule."""
+        content = """
+        #[program]
+        pub mod p {
+            pub fn open(ctx: Context<Open>) -> Result<()> {
+                token_interface::initialize_account3(CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    InitializeAccount3 {
+                        account: ctx.accounts.vault.to_account_info(),
+                        mint: ctx.accounts.mint.to_account_info(),
+                        authority: ctx.accounts.user.to_account_info(),
+                    },
+                ))?;
+                Ok(())
+            }
+        }
+
+        #[derive(Accounts)]
+        pub struct Open<'info> {
+            pub user: Signer<'info>,
+            pub vault: InterfaceAccount<'info, TokenAccount>,
+            pub mint: InterfaceAccount<'info, Mint>,
+            pub token_program: Interface<'info, TokenInterface>,
+        }
+        """
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+
+# ─── ANCHOR-018: Epoch / Slot / Timestamp Unit Confusion ────────────────────
+class TestAnchor018:
+    def setup_method(self):
+        self.pattern = TimeUnitPattern()
+
+    def test_detects_time_unit_mix_and_unchecked_cast(self):
+        """Epoch + seconds, slot vs epoch, and unix_timestamp cast to u64 in reward code."""
+        content = read_test_file("vulnerable", "staking_time_unit_mix.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [12, 18, 23]
+        assert "'clock.epoch' (epochs)" in findings[0].description
+        assert "(seconds)" in findings[0].description
+        assert "'clock.slot' (slots)" in findings[1].description
+        assert "casts i64 timestamp" in findings[2].description
+
+    def test_explicit_conversions_are_clean(self):
+        """*_PER_* factors, conversion helpers and try_from are not reported."""
+        content = """
+        pub fn claim_rewards(ctx: Context<Claim>) -> Result<()> {
+            let clock = Clock::get()?;
+            let now = u64::try_from(clock.unix_timestamp).unwrap();
+            let unlock_slot = ctx.accounts.stake.unlock_epoch * SLOTS_PER_EPOCH;
+            require!(clock.slot >= unlock_slot, StakeError::StillLocked);
+            require!(clock.epoch >= slots_to_epochs(ctx.accounts.stake.start_slot), StakeError::StillLocked);
+            let elapsed = now - ctx.accounts.stake.last_claim_ts;
+            Ok(())
+        }
+        """
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+    def test_guarded_timestamp_cast_outside_time_logic(self):
+        """A non-negative guard clears the cast; casts outside reward/cooldown code are ignored."""
+        content = """
+        pub fn claim_rewards(ctx: Context<Claim>) -> Result<()> {
+            let ts = Clock::get()?.unix_timestamp;
+            require!(ts >= 0, StakeError::InvalidClock);
+            let now = ts as u64;
+            Ok(())
+        }
+
+        pub fn log_event(ctx: Context<Log>) -> Result<()> {
+            emit!(Logged { at: Clock::get()?.unix_timestamp as u64 });
+            Ok(())
+        }
+        """
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+
+# ─── ANCHOR-019: Mutable Account Aliasing ───────────────────────────────────
+class TestAnchor019:
+    def setup_method(self):
+        self.pattern = AccountAliasingPattern()
+
+    def test_detects_aliasable_fields_and_remaining_accounts(self):
+        content = read_test_file("vulnerable", "mutable_account_aliasing.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [33, 20]
+        assert "'from' and 'to' both hold Member" in findings[0].description
+        assert "remaining account as Member" in findings[1].description
+        assert "treasury" in findings[1].description
+
+    def test_distinguished_pairs_are_clean(self):
+        """Key constraint, handler comparison and distinct seeds all rule out aliasing."""
+        content = read_test_file("vulnerable", "mutable_account_aliasing.rs").replace(
+            "#[account(mut)]\n    pub to:",
+            "#[account(mut, constraint = to.key() != from.key())]\n    pub to:",
+        ).replace(
+            "member.points += amount;",
+            "require_keys_neq!(member.key(), ctx.accounts.treasury.key());\n            member.points += amount;",
+        )
+        assert self.pattern.scan("test.rs", content) == []
+
+        seeded = """
+        #[derive(Accounts)]
+        pub struct Swap<'info> {
+            #[account(mut, seeds = [b"pool", mint_a.key().as_ref()], bump)]
+            pub pool_a: Account<'info, Pool>,
+            #[account(mut, seeds = [b"pool", mint_b.key().as_ref()], bump)]
+            pub pool_b: Account<'info, Pool>,
+        }
+        """
+        assert self.pattern.scan("test.rs", seeded) == []
+
+    def test_ignores_token_accounts_and_loaders(self):
+        """SPL CPIs act on the live account; aliased load_mut() fails at runtime."""
+        content = """
+        #[derive(Accounts)]
+        pub struct Transfer<'info> {
+            #[account(mut)]
+            pub from: Account<'info, TokenAccount>,
+            #[account(mut)]
+            pub to: Account<'info, TokenAccount>,
+            #[account(mut)]
+            pub tick_lower: AccountLoader<'info, TickArray>,
+            #[account(mut)]
+            pub tick_upper: AccountLoader<'info, TickArray>,
+        }
+        """
+        assert self.pattern.scan("test.rs", content) == []
+
+    def test_optional_and_boxed_pairs(self):
+        """Option<..> and Box<..> around the same Account type alias like the bare spelling."""
+        content = """
+        #[derive(Accounts)]
+        pub struct Rebalance<'info> {
+            #[account(mut)]
+            pub source: Option<Account<'info, Vault>>,
+            #[account(mut)]
+            pub destination: Option<Box<Account<'info, Vault>>>,
+        }
+        """
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 1
+        assert "'source' and 'destination' both hold Vault" in findings[0].description
+
+
+# ─── ANCHOR-020: Inconsistent Account Validation ────────────────────────────
+
+class TestAnchor020:
+    def setup_method(self):
+        self.pattern = ConstraintAnomalyPattern()
+
+    def test_detects_struct_missing_the_usual_check(self):
+        """vault is pinned to pool.vault in four structs and left open in EmergencyExit."""
+        content = read_test_file("vulnerable", "inconsistent_vault_validation.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [79]
+        assert "in 4 of 5 accounts structs" in findings[0].description
+        assert "but not in EmergencyExit" in findings[0].description
+
+    def test_boxed_and_optional_spellings_are_the_same_account(self):
+        content = read_test_file("vulnerable", "inconsistent_vault_validation.rs").replace(
+            "    pub vault: Account<'info, TokenAccount>,\n    pub user: Signer<'info>,\n    pub token_program",
+            "    pub vault: Option<Box<Account<'info, TokenAccount>>>,\n    pub user: Signer<'info>,\n"
+            "    pub token_program",
+            3,
+        )
+        assert [f.line for f in self.pattern.scan("test.rs", content)] == [79]
+
+    def test_consistent_validation_is_safe(self):
+        content = read_test_file("vulnerable", "inconsistent_vault_validation.rs").replace(
+            "pub struct EmergencyExit<'info> {\n    #[account(mut)]",
+            "pub struct EmergencyExit<'info> {\n    #[account(mut, has_one = vault)]",
+        )
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+    def test_needs_consensus_and_skips_creation(self):
+        """No habit when only half the structs check; initialization sets the relation up."""
+        content = read_test_file("vulnerable", "inconsistent_vault_validation.rs")
+        split = content.replace("#[account(mut, has_one = vault)]", "#[account(mut)]")
+        assert self.pattern.scan("test.rs", split) == []
+
+        creation = content.replace(
+            "pub struct EmergencyExit<'info> {\n    #[account(mut)]",
+            "pub struct EmergencyExit<'info> {\n    #[account(init, payer = user, space = 8 + 40)]",
+        )
+        assert self.pattern.scan("test.rs", creation) == []
+
+
+# ─── ANCHOR-021: Sysvar Account Spoofing ────────────────────────────────────
+
+class TestAnchor021:
+    def setup_method(self):
+        self.pattern = SysvarSpoofingPattern()
+
+    def test_detects_unpinned_sysvar_accounts(self):
+        """Unchecked load_instruction_at on an oddly named field; clock deserialized by hand."""
+        content = read_test_file("vulnerable", "sysvar_spoofing.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [32, 42]
+        assert "'instruction_acc' stands for the Instructions sysvar" in findings[0].description
+        assert "address = sysvar::clock::ID" in findings[1].fix_recommendation
+
+    def test_checked_apis_and_sysvar_type_are_safe(self):
+        content = read_test_file("vulnerable", "sysvar_spoofing.rs").replace(
+            "load_instruction_at(0,", "load_instruction_at_checked(0,",
+        ).replace(
+            "    /// CHECK: clock sysvar\n    pub clock: AccountInfo<'info>,",
+            "    pub clock: Sysvar<'info, Clock>,",
+        )
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+    def test_key_check_and_cpi_forwarding_are_safe(self):
+        """The handler compares the key with the sysvar ID; rent is only handed to a CPI."""
+        content = """
+        pub fn handler(ctx: Context<Tick>) -> Result<()> {
+            require_keys_eq!(ctx.accounts.clock.key(), sysvar::clock::ID);
+            let cpi_accounts = InitializeAccount {
+                account: ctx.accounts.vault.to_account_info(),
+                rent: ctx.accounts.rent.to_account_info(),
+            };
+            Ok(())
+        }
+
+        #[derive(Accounts)]
+        pub struct Tick<'info> {
+            pub clock: AccountInfo<'info>,
+            pub rent: AccountInfo<'info>,
+            #[account(mut)]
+            pub vault: Account<'info, TokenAccount>,
+        }
+        """
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+
+# ─── ANCHOR-022: Account Trusted By Name Only ───
+
+class TestAnchor022:
+    def setup_method(self):
+        self.pattern = StateBindingPattern()
+
+    def test_detects_vault_trusted_by_name(self):
+        """token_vault_0 is stored in PoolState but Swap never ties it to the stored key."""
+        content = read_test_file("vulnerable", "unbound_pool_vaults.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [49]
+        assert "stores PoolState.token_vault_0" in findings[0].description
+        assert "has_one = token_vault_0" in findings[0].fix_recommendation
+
+    def test_constraint_handler_check_and_setter_are_safe(self):
+        """token_vault_1 has a key constraint, fee_vault a require_keys_eq!, SetFeeVault writes the key."""
+        content = read_test_file("vulnerable", "unbound_pool_vaults.rs").replace(
+            "    #[account(mut)]\n    pub token_vault_0:",
+            "    #[account(mut, constraint = token_vault_0.key() == pool_state.load()?.token_vault_0)]\n"
+            "    pub token_vault_0:",
+        )
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+    def test_has_one_and_program_accounts_are_safe(self):
+        content = """
+        #[account]
+        pub struct Officer {
+            pub treasury: Pubkey,
+            pub dex_program: Pubkey,
+        }
+
+        #[derive(Accounts)]
+        pub struct Sweep<'info> {
+            #[account(has_one = treasury)]
+            pub officer: Account<'info, Officer>,
+            #[account(mut)]
+            pub treasury: Account<'info, TokenAccount>,
+            pub dex_program: Program<'info, Dex>,
+        }
+        """
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+        optional = content.replace("Program<'info, Dex>", "Option<Box<Program<'info, Dex>>>").replace(
+            "Account<'info, Officer>", "Box<Account<'info, Officer>>",
+        )
+        assert self.pattern.scan("test.rs", optional) == []
+
+
+# ─── ANCHOR-023: init_if_needed State Reinitialization ───
+
+class TestAnchor023:
+    def setup_method(self):
+        self.pattern = StateReinitPattern()
+
+    def test_detects_unguarded_state_reset(self):
+        """open_obligation resets owner and balances of an existing obligation."""
+        content = read_test_file("vulnerable", "init_if_needed_state_reset.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [12]
+        assert "overwrites owner, borrowed_amount, deposited_amount" in findings[0].description
+        assert "require!(!obligation.is_initialized" in findings[0].fix_recommendation
+
+    def test_guarded_and_seed_bound_writes_are_safe(self):
+        """A require! on the account before the writes; a profile owner re-set to the seeding signer."""
+        content = read_test_file("vulnerable", "init_if_needed_state_reset.rs").replace(
+            "        let obligation = &mut ctx.accounts.obligation;\n",
+            "        let obligation = &mut ctx.accounts.obligation;\n"
+            "        require!(obligation.owner == Pubkey::default(), LendingError::AlreadyMember);\n",
+        )
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+    def test_token_accounts_and_set_inner(self):
+        """Token accounts are left to ANCHOR-001; replacing the whole account is a reset."""
+        assert self.pattern.scan("test.rs", read_test_file("safe", "init_if_needed_with_constraints.rs")) == []
+        content = read_test_file("vulnerable", "init_if_needed_state_reset.rs").replace(
+            "        obligation.market = ctx.accounts.market.key();\n",
+            "        obligation.set_inner(Obligation::default());\n",
+        )
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [11]
+        assert "overwrites all fields" in findings[0].description
+
+
+# ─── ANCHOR-024: Unchecked Economic Parameter ───
+
+class TestAnchor024:
+    def setup_method(self):
+        self.pattern = EconomicParamsPattern()
+
+    def test_detects_unchecked_parameter_updates(self):
+        """update_fees bounds only the max fee; initialize goes through LiqPool::validate."""
+        content = read_test_file("vulnerable", "stake_pool_params_unchecked.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [5, 27, 28, 29, 30]
+        assert "BPS_DENOMINATOR is 1000" in findings[0].description
+        assert "'lp_max_fee_bps'" in findings[2].description
+        assert "never compared with lp_min_fee_bps" in findings[2].description
+        assert "floor parameter 'lp_liquidity_target'" in findings[4].description
+
+    def test_validate_call_covers_all_parameters(self):
+        content = read_test_file("vulnerable", "stake_pool_params_unchecked.rs").replace(
+            "        liq.lp_liquidity_target = params.lp_liquidity_target;\n",
+            "        liq.lp_liquidity_target = params.lp_liquidity_target;\n        liq.validate()?;\n",
+        ).replace("BPS_DENOMINATOR: u64 = 1_000;", "BPS_DENOMINATOR: u64 = 10_000;")
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+    def test_denominator_needs_nonzero_check(self):
+        content = """
+        pub fn set_price(ctx: Context<SetPrice>, numerator: u64, denominator: u64) -> Result<()> {
+            require!(numerator > 0, ErrorCode::Zero);
+            ctx.accounts.oracle.price_numerator = numerator;
+            ctx.accounts.oracle.price_denominator = denominator;
+            Ok(())
+        }
+        """
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [5]
+        assert "is never checked to be non-zero" in findings[0].description
+        guarded = content.replace("numerator > 0", "denominator != 0")
+        assert self.pattern.scan("test.rs", guarded) == []
+
+    def test_basis_point_rates_and_fee_constants(self):
+        """Small-int and bps rates need an upper bound; the u64 exchange rate and the checked bps field do not."""
+        content = read_test_file("vulnerable", "bps_rates_unchecked.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [4, 5, 6, 15, 16]
+        assert "PROTOCOL_FEE_BPS is 15000 basis points, a fee above 100%" in findings[0].description
+        assert "REFERRAL_FEE_PERCENT is 120 percent" in findings[1].description
+        assert "LATE_FEE is 25000 and is applied in basis points" in findings[2].description
+        assert "rate parameter 'interest_rate'" in findings[3].description
+        assert "upper bound such as MAX_BASIS_POINTS" in findings[3].description
+        assert "'liquidation_bp_cents'" in findings[4].description
+
+
+# ─── ANCHOR-025: Close Destination Not Constrained ───
+
+class TestAnchor025:
+    def setup_method(self):
+        self.pattern = CloseDestinationPattern()
+
+    def test_detects_unconstrained_close_destination(self):
+        """ClosePosition refunds to any receiver; CloseExpired checks the stored payer in the handler."""
+        content = read_test_file("vulnerable", "position_close_lifecycle.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [59]
+        assert "closed to 'receiver', which nothing constrains" in findings[0].description
+        assert "has_one = receiver, close = receiver" in findings[0].fix_recommendation
+
+    def test_signer_and_has_one_destinations_are_safe(self):
+        content = read_test_file("vulnerable", "position_close_lifecycle.rs").replace(
+            "close = receiver)]", "close = owner)]",
+        ).replace(
+            "        require_keys_eq!(ctx.accounts.payer.key(), ctx.accounts.position.payer);\\n", "",
+        ).replace("    #[account(mut, close = payer)]", "    #[account(mut, has_one = payer, close = payer)]")
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+    def test_struct_without_signer_is_left_out(self):
+        content = """
+        #[derive(Accounts)]
+        pub struct Close<'info> {
+            #[account(mut, close = destination)]
+            account: Account<'info, Data>,
+            #[account(mut)]
+            destination: AccountInfo<'info>,
+        }
+        """
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+
+# ─── ANCHOR-026: Diverging Constant Definitions ───
+
+class TestAnchor026:
+    def setup_method(self):
+        self.pattern = ConstantDriftPattern()
+
+    def test_detects_drifted_limit_and_seed_typo(self):
+        """config re-declares MAX_FEE_BPS with another value; Claim derives from b"user-stats"."""
+        content = read_test_file("vulnerable", "drifted_constants.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [15, 64]
+        assert "MAX_FEE_BPS is 10_000 here, but 1_000 in test.rs:6 (mod constants)" in findings[0].description
+        assert 'b"user-stats" differs only in case or separators from b"user_stats"' in findings[1].description
+
+    def test_namespaced_and_gated_constants_are_safe(self):
+        content = """
+        impl Vault { pub const SEED_PREFIX: &'static [u8] = b"vault"; }
+        impl Pool { pub const SEED_PREFIX: &'static [u8] = b"pool"; }
+        mod fees { pub const MAX: u64 = 100; }
+        mod slots { pub const MAX: u64 = 50; }
+        #[cfg(feature = "devnet")]
+        pub const UNSTAKE_DELAY_SLOTS: u64 = 10;
+        #[cfg(not(feature = "devnet"))]
+        pub const UNSTAKE_DELAY_SLOTS: u64 = 432_000;
+        mod a { pub const MIN_STAKE: u64 = 1_000_000; }
+        mod b { pub const MIN_STAKE: u64 = 1000000u64; }
+        #[account(seeds = [b"vault_a", pool.key().as_ref()], bump)]
+        #[account(seeds = [b"vault_b", pool.key().as_ref()], bump)]
+        """
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+    def test_compares_files_of_one_crate_only(self, tmp_path):
+        for crate, fee in (("amm", "1_000"), ("amm-v2", "500")):
+            src = tmp_path / "programs" / crate / "src"
+            src.mkdir(parents=True)
+            (tmp_path / "programs" / crate / "Cargo.toml").write_text(f'[package]\nname = "{crate}"\n')
+            (src / "constants.rs").write_text(f"pub const MAX_FEE_BPS: u16 = {fee};\n")
+        (tmp_path / "programs" / "amm" / "src" / "swap.rs").write_text(
+            "use crate::*;\n\nconst MAX_FEE_BPS: u16 = 10_000;\n"
+        )
+        findings = self.pattern.scan_workspace(str(tmp_path))
+        assert [(f.file, f.line) for f in findings] == [(os.path.join("programs", "amm", "src", "swap.rs"), 3)]
+        assert "but 1_000 in " + os.path.join("programs", "amm", "src", "constants.rs") + ":1" \
+            in findings[0].description
+
+
+# ─── ANCHOR-027: Per-User Position Not Bound To User ───
+
+class TestAnchor027:
+    def setup_method(self):
+        self.pattern = PositionBindingPattern()
+
+    def test_detects_unbound_and_keypair_positions(self):
+        """Borrow takes any obligation; OpenObligation creates them at keypair addresses."""
+        content = read_test_file("vulnerable", "lending_positions_unbound.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [45, 62]
+        assert "created at a caller-chosen keypair address" in findings[0].description
+        assert "nothing checks obligation.owner against borrower.key()" in findings[1].description
+
+    def test_pda_positions_are_safe(self):
+        content = read_test_file("vulnerable", "lending_positions_unbound.rs").replace(
+            "#[account(init, payer = owner, space = 8 + 32 + 32 + 8 + 8)]",
+            "#[account(init, payer = owner, space = 8 + 32 + 32 + 8 + 8,\n"
+            "              seeds = [b\"obligation\", market.key().as_ref(), owner.key().as_ref()], bump)]",
+        ).replace(
+            "    #[account(mut)]\n    pub obligation: Account<'info, Obligation>,\n    pub borrower",
+            "    #[account(mut, seeds = [b\"obligation\", market.key().as_ref(), borrower.key().as_ref()], bump)]\n"
+            "    pub obligation: Account<'info, Obligation>,\n    pub borrower",
+        )
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+    def test_flags_vulnerable_lending_user_account(self):
+        path = os.path.join(os.path.dirname(__file__), os.pardir, "examples", "vulnerable-lending",
+                            "programs", "vulnerable-lending", "src", "lib.rs")
+        with open(path) as f:
+            findings = self.pattern.scan("lib.rs", f.read())
+        assert [f.description.split(":")[0] for f in findings] == [
+            "In struct Deposit", "In struct Borrow", "In struct Withdraw",
+        ]
+        assert all("'user_account' (UserAccount)" in f.description for f in findings)
+
+    def test_detects_user_balance_pda_without_user_seed(self):
+        """UserStake is derived from the pool alone; stake and unstake write its balance for the signer."""
+        content = read_test_file("vulnerable", "user_balance_shared_seeds.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [45, 60]
+        assert "PDA of [b\"user_stake\",pool], with no user key in its seeds" in findings[0].description
+        assert "writes staked_amount for user" in findings[1].description
+
+    def test_user_mint_and_signer_bound_seeds_are_safe(self):
+        """The user's key, a mint, or a has_one naming the signer make the PDA per-key."""
+        content = read_test_file("vulnerable", "user_balance_shared_seeds.rs")
+        with_user = content.replace("pool.key().as_ref()]", "pool.key().as_ref(), user.key().as_ref()]")
+        assert self.pattern.scan("test.rs", with_user) == []
+        per_mint = content.replace("pub user_stake: Account<'info, UserStake>,",
+                                   "pub user_stake: Account<'info, UserStake>,\n    pub stake_mint: Account<'info, Mint>,")
+        per_mint = per_mint.replace("pool.key().as_ref()]", "stake_mint.key().as_ref()]")
+        assert self.pattern.scan("test.rs", per_mint) == []
+        # has_one = user on Unstake ties that account to its signer
+        bound = content.replace("seeds = [b\"user_stake\", pool.key().as_ref()], bump)]",
+                                "seeds = [b\"user_stake\", pool.key().as_ref()], bump, has_one = user)]")
+        assert [f.line for f in self.pattern.scan("test.rs", bound)] == [45]
+
+
+# ─── ANCHOR-028: Unsafe Packed Zero-Copy Account ───
+
+class TestAnchor028:
+    def setup_method(self):
+        self.pattern = PackedZeroCopyPattern()
+
+    def test_detects_unaligned_references(self):
+        """&mut of a packed u64, msg! of it, and iter() on a [u64; 8] through self."""
+        content = read_test_file("vulnerable", "packed_zero_copy_refs.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [(f.line, f.severity) for f in findings] == [
+            (11, "Medium"), (14, "Medium"), (41, "Low"), (46, "Low"), (51, "Medium"),
+        ]
+        assert "takes a reference to `total_deposits: u64` at offset 33" in findings[0].description
+        assert "passes to msg!" in findings[1].description
+        assert "total_deposits: u64 at offset 33, history: [u64; 8] at offset 41" in findings[2].description
+        assert "no `Default` that zeroes them" in findings[3].description
+        assert "calls `iter()` (by reference) on `history" in findings[4].description
+        # Pubkey has alignment 1: `&vault.authority` is fine
+        assert not any(f.line == 20 for f in findings)
+
+    def test_by_value_reads_are_safe(self):
+        content = read_test_file("safe", "packed_zero_copy_by_value.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+    def test_suggests_checked_zero_copy_when_aligned(self):
+        content = read_test_file("safe", "packed_zero_copy_by_value.rs").replace(
+            "let total = vault.total_deposits;", "let total = *&vault.total_deposits;"
+        )
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [11]
+        assert "the checked form lays out the same bytes" in findings[0].fix_recommendation
+
+    def test_raydium_pool_state_layout(self):
+        path = os.path.join(os.path.dirname(__file__), os.pardir, "real-world-targets", "raydium-clmm", "lib.rs")
+        with open(path) as f:
+            findings = self.pattern.scan("lib.rs", f.read())
+        # Raydium copies packed fields out before use; only the frozen layouts are reported
+        assert [(f.line, f.severity) for f in findings] == [(1087, "Low"), (1657, "Low")]
+        assert "tick_spacing: u16 at offset 227" in findings[0].description
+
+
+# ─── ANCHOR-029: Account Space Mismatch ───
+
+class TestAnchor029:
+    def setup_method(self):
+        self.pattern = AccountSpacePattern()
+
+    def test_detects_stale_len_and_missing_discriminator(self):
+        content = read_test_file("vulnerable", "account_space_mismatch.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [41, 53, 62, 89]
+        assert "is 75 bytes, 1 byte(s) less than 8 + 68 needed" in findings[0].description
+        # INIT_SPACE of a String with #[max_len] and size_of::<T>() of a zero-copy type
+        assert "UserProfile::INIT_SPACE` for 'profile' (UserProfile) is 76 bytes, exactly" in findings[1].description
+        assert "is 304 bytes, exactly the size of Market's fields" in findings[2].description
+        assert "Config::LEN is 67, but the fields of Config serialize to 68 bytes" in findings[3].description
+
+    def test_correct_sizes_are_safe(self):
+        content = read_test_file("vulnerable", "account_space_mismatch.rs").replace(
+            "pub const LEN: usize = 32 + 2 + 32 + 1;", "pub const LEN: usize = 32 + 2 + 32 + 1 + 1;"
+        ).replace(
+            "space = UserProfile::INIT_SPACE", "space = 8 + UserProfile::INIT_SPACE"
+        ).replace(
+            "space = std::mem::size_of::<Market>()", "space = 8 + std::mem::size_of::<Market>()"
+        )
+        findings = self.pattern.scan("test.rs", content)
+        assert len(findings) == 0
+
+    def test_raydium_pool_state_len_matches_layout(self):
+        """PoolState::LEN, hand-summed over 22 terms, equals 8 + the packed layout's 1536 bytes."""
+        path = os.path.join(os.path.dirname(__file__), os.pardir, "real-world-targets", "raydium-clmm", "lib.rs")
+        with open(path) as f:
+            content = f.read()
+        assert self.pattern.scan("lib.rs", content) == []
+        findings = self.pattern.scan("lib.rs", content.replace("        + 8 * 16\n        + 512;", "        + 512;"))
+        assert [f.line for f in findings] == [29, 1174]
+        assert "PoolState::LEN is 1416" in findings[1].description
+
+    def test_hand_written_space_diverges_from_init_space(self):
+        """With #[derive(InitSpace)], hand-summed space is compared in both directions, #[max_len] included."""
+        content = read_test_file("vulnerable", "init_space_manual_space.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [(f.line, f.severity) for f in findings] == [(26, "Low"), (31, "Medium"), (40, "Low")]
+        assert "Config::INIT_SPACE is 42, but the fields of Config serialize to 34 bytes: " \
+               "the discriminator counted twice" in findings[0].description
+        assert "is 208 bytes, 1 byte(s) less than 8 + 201 needed; Vault derives InitSpace" in findings[1].description
+        assert "8 + Vault::INIT_SPACE is 209 (counting label, members at #[max_len]): 64 byte(s)" \
+            in findings[2].description
+        # Without the derive, extra space is headroom and not reported
+        underived = content.replace("#[derive(InitSpace)]\n", "")
+        assert [f.line for f in self.pattern.scan("test.rs", underived)] == [25, 30]
+
+
+# ─── ANCHOR-030: Bypassable Access Control ───
+
+class TestAnchor030:
+    def setup_method(self):
+        self.pattern = AccessControlPattern()
+
+    def test_detects_weak_guards_and_unbound_signers(self):
+        """A guard that never fails, one that checks only instruction data, and no guard at all."""
+        content = read_test_file("vulnerable", "access_control_bypass.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [(f.line, f.severity) for f in findings] == [(9, "Medium"), (11, "High"), (18, "High"), (24, "High")]
+        assert "`only_admin(&ctx)` on 'set_fee' never returns an error" in findings[0].description
+        assert "`valid_flag(paused)` (arguments-only)" in findings[2].description
+        assert "no #[access_control] guard, and its signer caller" in findings[3].description
+        # ANCHOR-016 covers missing and unsigned authorities; these take a signer
+        assert PrivilegedSignerPattern().scan("test.rs", content) == []
+
+    def test_binding_guard_constraint_and_inline_check_are_safe(self):
+        """A guard comparing the signer, has_one on the config, or a key check in a helper."""
+        content = read_test_file("vulnerable", "access_control_bypass.rs").replace(
+            '    msg!("fee update by {}", ctx.accounts.admin.key());',
+            "    require_keys_eq!(ctx.accounts.admin.key(), ctx.accounts.config.admin);",
+        ).replace(
+            "pub struct SetPaused<'info> {\n    #[account(mut)]",
+            "pub struct SetPaused<'info> {\n    #[account(mut, constraint = config.admin == operator.key())]",
+        ).replace(
+            "        config.admin = new_admin;",
+            "        check_admin(config, &ctx.accounts.caller)?;\n        config.admin = new_admin;",
+        ) + """
+fn check_admin(config: &Config, caller: &Signer) -> Result<()> {
+    if config.admin != caller.key() {
+        return err!(ConfigError::InvalidFlag);
+    }
+    Ok(())
+}
+"""
+        assert self.pattern.scan("test.rs", content) == []
+
+    def test_guard_failing_through_helper_and_pda_signer(self):
+        """A guard that fails via `?` on a checking helper; a PDA signer derived from the state."""
+        content = """
+        #[program]
+        pub mod multisig {
+            #[access_control(phase_open(&ctx.accounts.sale))]
+            pub fn buy(ctx: Context<Buy>) -> Result<()> { Ok(()) }
+
+            pub fn set_owners(ctx: Context<Auth>, owners: Vec<Pubkey>) -> Result<()> {
+                ctx.accounts.multisig.owner_set = owners;
+                ctx.accounts.multisig.owner = owners[0];
+                Ok(())
+            }
+        }
+
+        fn phase_open(sale: &Sale) -> Result<()> {
+            check_time(sale.end)?;
+            Ok(())
+        }
+
+        fn check_time(end: i64) -> Result<()> {
+            require!(Clock::get()?.unix_timestamp < end, ErrorCode::Closed);
+            Ok(())
+        }
+
+        #[derive(Accounts)]
+        pub struct Auth<'info> {
+            #[account(mut)]
+            multisig: Account<'info, Multisig>,
+            #[account(signer, seeds = [multisig.key().as_ref()], bump = multisig.nonce)]
+            multisig_signer: AccountInfo<'info>,
+        }
+        """
+        assert self.pattern.scan("test.rs", content) == []
+        self.pattern.max_depth = 1
+        assert [f.line for f in self.pattern.scan("test.rs", content)] == [4]
+
+
+# ─── ANCHOR-031: PDA Seed Collision Across Account Types ───
+
+class TestAnchor031:
+    def setup_method(self):
+        self.pattern = PdaSeedCollisionPattern()
+
+    def test_detects_shared_prefix_and_string_seed(self):
+        """Same prefix and key length, and a string seed that can spell out any suffix."""
+        content = read_test_file("vulnerable", "pda_seed_collision.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [43, 55, 55, 67]
+        assert "'Position' (OpenPosition.position, line 33) and 'PoolConfig'" in findings[0].description
+        assert "the same seed shape" in findings[0].description
+        assert findings[0].details.to_dict()["identical"] is True
+        assert findings[0].details.first.seeds == "[POSITION_SEED, owner.key().as_ref()]"
+        assert "[b\"pos\", <pubkey>, <64-bit int>]" in findings[3].description
+
+    def test_distinct_prefixes_and_lengths_are_safe(self):
+        """Different leading literals, and a fixed-length suffix that cannot line up."""
+        content = read_test_file("vulnerable", "pda_seed_collision.rs")
+        content = content.replace('seeds = [b"pos", name.as_bytes()]', 'seeds = [b"market", name.as_bytes()]')
+        content = content.replace('seeds = [b"pos", pool.key().as_ref()]', 'seeds = [b"pool", pool.key().as_ref()]')
+        assert self.pattern.scan("test.rs", content) == []
+
+    def test_same_type_and_external_types_are_skipped(self):
+        """Recipes of one type, token accounts, and seeds::program PDAs are not compared."""
+        content = """
+        #[derive(Accounts)]
+        pub struct Deposit<'info> {
+            #[account(mut, seeds = [b"vault", pool.key().as_ref()], bump)]
+            pub vault: Account<'info, TokenAccount>,
+            #[account(mut, seeds = [b"vault", owner.key().as_ref()], bump)]
+            pub state: Account<'info, Vault>,
+            #[account(seeds = [b"vault", user.key().as_ref()], bump)]
+            pub other: Account<'info, Vault>,
+            #[account(seeds = [b"vault", mint.key().as_ref()], bump, seeds::program = meta_program.key())]
+            pub metadata: Account<'info, Record>,
+        }
+        """
+        assert self.pattern.scan("test.rs", content) == []
+
+
+# ─── ANCHOR-032: Unverified Signer Seeds ───
+
+class TestAnchor032:
+    def setup_method(self):
+        self.pattern = SignerSeedsPattern()
+
+    def test_detects_argument_raw_data_and_undeclared_seeds(self):
+        """Seeds from an argument, from an unchecked account's bytes, and a recipe nothing declares."""
+        content = read_test_file("vulnerable", "signer_seeds_unverified.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [(f.line, f.severity) for f in findings] == [(14, "High"), (33, "High"), (51, "Medium")]
+        assert "`owner.as_ref()` comes from instruction argument owner" in findings[0].description
+        assert "read from the data of 'config', which nothing validates" in findings[1].description
+        assert 'recipe [b"orders".as_ref(), owner.as_ref()] matches no PDA' in findings[2].description
+
+    def test_validated_seeds_are_safe(self):
+        """Seeds from a signer, a typed account, and an #[instruction] argument the PDA was derived from."""
+        content = read_test_file("vulnerable", "signer_seeds_unverified.rs")
+        content = content.replace("VAULT_SEED, owner.as_ref(), &[ctx.accounts.vault.bump]",
+                                  "VAULT_SEED, ctx.accounts.user.key.as_ref(), &[ctx.accounts.vault.bump]")
+        content = content.replace("pub config: UncheckedAccount<'info>,", "pub config: Account<'info, Config>,")
+        content = content.replace('b"orders".as_ref()', 'b"order".as_ref()')
+        assert self.pattern.scan("test.rs", content) == []
+
+    def test_find_program_address_counts_as_declared(self):
+        """A recipe derived in the handler, as anchor-escrow does, and a method-built one left alone."""
+        content = """
+        pub const ESCROW_PDA_SEED: &[u8] = b"escrow";
+
+        pub fn exchange(ctx: Context<Exchange>) -> Result<()> {
+            let (_pda, bump_seed) = Pubkey::find_program_address(&[ESCROW_PDA_SEED], ctx.program_id);
+            let seeds = &[&ESCROW_PDA_SEED[..], &[bump_seed]];
+            token::transfer(ctx.accounts.into_transfer_context().with_signer(&[&seeds[..]]), 1)?;
+            token::transfer(ctx.accounts.into_transfer_context().with_signer(&[&ctx.accounts.pool.seeds()]), 1)
+        }
+
+        #[derive(Accounts)]
+        pub struct Exchange<'info> {
+            #[account(seeds = [b"state"], bump)]
+            pub state: Account<'info, State>,
+        }
+        """
+        assert self.pattern.scan("test.rs", content) == []
+
+
+# ─── ANCHOR-033: Misaligned Reward Account Groups ───
+
+class TestAnchor033:
+    def setup_method(self):
+        self.pattern = RewardGroupPattern()
+
+    def test_detects_unbound_vault_unchecked_count_and_skew(self):
+        content = read_test_file("vulnerable", "reward_group_loop.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [(f.line, f.severity) for f in findings] == [(15, "High"), (27, "Medium"), (42, "High")]
+        assert "'reward_vault' taken for reward i is never compared by key" in findings[0].description
+        assert "len / 2 drops a partial group" in findings[0].description
+        assert "chunks(2) yields a short last group" in findings[1].description
+        assert "`continue` before the group's last account is taken" in findings[2].description
+
+    def test_bound_validated_aligned_loops_are_safe(self):
+        """Key check, a length check in a helper, and continue after the whole group is taken."""
+        content = read_test_file("vulnerable", "reward_group_loop.rs")
+        content = content.replace(
+            "            let owed = farm.reward_infos[i].owed;",
+            "            require_keys_eq!(reward_vault.key(), farm.reward_infos[i].vault);\n"
+            "            let owed = farm.reward_infos[i].owed;",
+        ).replace(
+            "        let group_count = remaining_accounts.len() / 2;",
+            "        check_length(remaining_accounts, 2)?;\n        let group_count = remaining_accounts.len() / 2;",
+        ).replace(
+            "        for (i, group) in ctx.remaining_accounts.chunks(2).enumerate() {",
+            "        check_length(ctx.remaining_accounts, 2)?;\n"
+            "        for (i, group) in ctx.remaining_accounts.chunks(2).enumerate() {",
+        ).replace(
+            "            if farm.reward_infos[i].owed == 0 {\n                continue;\n            }\n"
+            "            let recipient = InterfaceAccount::<TokenAccount>::try_from(accounts.next().unwrap())?;",
+            "            let recipient = InterfaceAccount::<TokenAccount>::try_from(accounts.next().unwrap())?;\n"
+            "            if farm.reward_infos[i].owed == 0 {\n                continue;\n            }",
+        ) + """
+fn check_length(accounts: &[AccountInfo], group: usize) -> Result<()> {
+    if accounts.len() % group != 0 {
+        return err!(FarmError::AccountCount);
+    }
+    Ok(())
+}
+"""
+        assert self.pattern.scan("test.rs", content) == []
+
+    def test_loops_without_reward_index_are_ignored(self):
+        """Iterating remaining accounts for something other than per-index rewards."""
+        content = """
+        pub fn refresh(ctx: Context<Refresh>) -> Result<()> {
+            let count = ctx.remaining_accounts.len() / 2;
+            for i in 0..count {
+                let oracle = &ctx.remaining_accounts[i * 2];
+                msg!("{}", oracle.key());
+            }
+            Ok(())
+        }
+        """
+        assert self.pattern.scan("test.rs", content) == []
+
+
+# ─── ANCHOR-034: Unprotected Authority Initialization ───────────────
+
+class TestAnchor034:
+    def setup_method(self):
+        self.pattern = AuthorityInitPattern()
+
+    def test_detects_caller_chosen_and_unrecorded_authorities(self):
+        content = read_test_file("vulnerable", "authority_init_unprotected.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [(f.line, f.severity) for f in findings] == [(12, "High"), (19, "High"), (27, "Medium")]
+        assert "from instruction argument 'admin'" in findings[0].description
+        assert "'treasury_authority', an account that never signs" in findings[1].description
+        assert "without emitting or logging it" in findings[2].description
+
+    def test_gated_or_recorded_initializers_are_safe(self):
+        """Upgrade-authority gate, an address-pinned signer, and an emitted operator."""
+        content = read_test_file("vulnerable", "authority_init_unprotected.rs")
+        content = content.replace(
+            "    pub config: Account<'info, Config>,\n",
+            "    pub config: Account<'info, Config>,\n"
+            "    #[account(constraint = program_data.upgrade_authority_address == Some(payer.key()))]\n"
+            "    pub program_data: Account<'info, ProgramData>,\n",
+        ).replace(
+            "    /// CHECK: stored as the treasury authority\n",
+            "    #[account(address = ADMIN)]\n",
+        ).replace(
+            "        registry.count = 0;\n",
+            "        registry.count = 0;\n"
+            "        emit!(RegistryInitialized { operator: registry.operator });\n",
+        )
+        assert self.pattern.scan("test.rs", content) == []
+
+    def test_signer_owned_accounts_are_ignored(self):
+        """A user opening their own position is not a global authority."""
+        content = read_test_file("vulnerable", "position_close_lifecycle.rs")
+        assert self.pattern.scan("test.rs", content) == []
+
+    def test_vulnerable_lending_initialize(self):
+        """Reference case: any signer initializes the pool as its authority, unrecorded."""
+        path = os.path.join(os.path.dirname(__file__), os.pardir, "examples", "vulnerable-lending",
+                            "programs", "vulnerable-lending", "src", "lib.rs")
+        with open(path) as fh:
+            findings = self.pattern.scan("lib.rs", fh.read())
+        assert [(f.line, f.severity) for f in findings] == [(11, "Medium")]
+        assert "'initialize' creates 'pool'" in findings[0].description
+
+
+# ─── ANCHOR-035: Incomplete Token Account Constraints ───────────────
+
+class TestAnchor035:
+    def setup_method(self):
+        self.pattern = TokenConstraintsPattern()
+
+    def test_detects_half_ata_and_unpinned_fee_vault(self):
+        content = read_test_file("vulnerable", "token_constraints_incomplete.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [(f.line, f.severity) for f in findings] == [(38, "Medium"), (51, "High"), (58, "High")]
+        assert "'fee_vault' receives transfers" in findings[0].description
+        assert "associated_token::mint but no associated_token::authority" in findings[1].description
+        assert "associated_token::authority but no associated_token::mint" in findings[2].description
+
+    def test_complete_constraints_are_safe(self):
+        """Both ATA halves, and a fee vault pinned by mint and authority."""
+        content = read_test_file("vulnerable", "token_constraints_incomplete.rs")
+        content = content.replace(
+            "    #[account(mut)]\n    pub fee_vault",
+            "    #[account(mut, token::mint = usdc_mint, token::authority = config)]\n    pub fee_vault",
+        ).replace(
+            "        associated_token::mint = reward_mint,\n    )]",
+            "        associated_token::mint = reward_mint,\n        associated_token::authority = user,\n    )]",
+        ).replace(
+            "        associated_token::authority = user,\n    )]\n    pub receipt_ata",
+            "        associated_token::mint = reward_mint,\n        associated_token::authority = user,\n"
+            "    )]\n    pub receipt_ata",
+        )
+        assert self.pattern.scan("test.rs", content) == []
+
+    def test_handler_check_and_user_destination_are_safe(self):
+        """A handler key check pins the vault; a user's own destination is theirs to choose."""
+        content = """
+        pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+            require_keys_eq!(ctx.accounts.treasury.key(), ctx.accounts.config.treasury);
+            token::transfer(CpiContext::new(p, Transfer {
+                from: ctx.accounts.user_token.to_account_info(),
+                to: ctx.accounts.treasury.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            }), amount)?;
+            token::transfer(CpiContext::new(p, Transfer {
+                from: ctx.accounts.treasury.to_account_info(),
+                to: ctx.accounts.user_token.to_account_info(),
+                authority: ctx.accounts.config.to_account_info(),
+            }), amount)?;
+            Ok(())
+        }
+
+        #[derive(Accounts)]
+        pub struct Withdraw<'info> {
+            pub config: Account<'info, Config>,
+            #[account(mut)]
+            pub treasury: Account<'info, TokenAccount>,
+            #[account(mut)]
+            pub user_token: Account<'info, TokenAccount>,
+            pub user: Signer<'info>,
+        }
+        """
+        assert self.pattern.scan("test.rs", content) == []
+
+
+# ─── ANCHOR-036: NFT Mint Not Checked As NFT ───
+
+class TestAnchor036:
+    def setup_method(self):
+        self.pattern = NftMintPattern()
+
+    def test_detects_unchecked_decimals_and_supply(self):
+        content = read_test_file("vulnerable", "nft_validation_gaps.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [(f.line, f.severity) for f in findings] == [(49, "High"), (86, "Medium")]
+        assert "never checked for decimals = 0 or supply == 1" in findings[0].description
+        assert "never checked for supply == 1" in findings[1].description
+
+    def test_checked_mint_and_master_edition_are_safe(self):
+        """decimals and supply on the mint, or a master edition derived under Token Metadata."""
+        content = read_test_file("vulnerable", "nft_validation_gaps.rs")
+        content = content.replace(
+            "    pub nft_mint: Account<'info, Mint>,\n    // Not derived",
+            "    #[account(mint::decimals = 0, constraint = nft_mint.supply == 1)]\n"
+            "    pub nft_mint: Account<'info, Mint>,\n    // Not derived",
+        ).replace("seeds::program = metadata_program.key()", "seeds::program = Metadata::id()")
+        assert self.pattern.scan("test.rs", content) == []
+
+    def test_collection_mint_stored_as_identifier_is_safe(self):
+        """A mint no token or metadata account of the struct is for is not held as an NFT."""
+        content = """
+        #[derive(Accounts)]
+        pub struct InitStaking<'info> {
+            #[account(mint::decimals = 0)]
+            pub collection_address: Account<'info, Mint>,
+            #[account(mut)]
+            pub admin: Signer<'info>,
+        }
+        """
+        assert self.pattern.scan("test.rs", content) == []
+
+
+# ─── ANCHOR-037: Unverified NFT Metadata ───
+
+class TestAnchor037:
+    def setup_method(self):
+        self.pattern = NftMetadataPattern()
+
+    def test_detects_underived_metadata_and_unverified_collection(self):
+        content = read_test_file("vulnerable", "nft_validation_gaps.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [(f.line, f.severity) for f in findings] == [(51, "High"), (93, "Medium"), (100, "High")]
+        assert "is not derived from the NFT's mint" in findings[0].description
+        assert "without checking collection.verified" in findings[0].description
+        assert "no seeds::program = Metadata::id()" in findings[1].description
+        assert "seeds::program = metadata_program.key(), which nothing pins" in findings[2].description
+
+    def test_metaplex_derivation_and_verified_collection_are_safe(self):
+        content = """
+        #[derive(Accounts)]
+        pub struct Stake<'info> {
+            pub nft_mint: Account<'info, Mint>,
+            #[account(
+                seeds = [b"metadata", Metadata::id().as_ref(), nft_mint.key().as_ref()],
+                seeds::program = Metadata::id(),
+                bump,
+                constraint = nft_metadata.collection.as_ref().unwrap().verified,
+                constraint = nft_metadata.collection.as_ref().unwrap().key == stake_details.collection
+            )]
+            pub nft_metadata: Box<Account<'info, MetadataAccount>>,
+            #[account(
+                seeds = [b"metadata", metadata_program.key().as_ref(), nft_mint.key().as_ref(), b"edition"],
+                seeds::program = metadata_program.key(),
+                bump,
+            )]
+            pub nft_edition: Box<Account<'info, MasterEditionAccount>>,
+            pub metadata_program: Program<'info, Metadata>,
+        }
+        """
+        assert self.pattern.scan("test.rs", content) == []
+
+    def test_unchecked_metadata_passed_to_metaplex_is_skipped(self):
+        """Metaplex validates the metadata accounts handed to its own CPIs."""
+        content = """
+        #[derive(Accounts)]
+        pub struct MintNft<'info> {
+            /// CHECK: created by the create_metadata_accounts_v3 CPI
+            #[account(mut)]
+            pub metadata: UncheckedAccount<'info>,
+            pub mint: Account<'info, Mint>,
+        }
+        """
+        assert self.pattern.scan("test.rs", content) == []
+
+
+# ─── ANCHOR-038: NFT Custody Not Program-Owned ───
+
+class TestAnchor038:
+    def setup_method(self):
+        self.pattern = NftCustodyPattern()
+
+    def test_detects_signer_owned_and_unowned_custody(self):
+        content = read_test_file("vulnerable", "nft_validation_gaps.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [(f.line, f.severity) for f in findings] == [(61, "High"), (114, "High")]
+        assert "owned by the signer 'staker'" in findings[0].description
+        assert "has no token::authority or associated_token::authority" in findings[1].description
+
+    def test_pda_owned_custody_is_safe(self):
+        content = read_test_file("vulnerable", "nft_validation_gaps.rs")
+        content = content.replace(
+            "        associated_token::authority = staker,\n    )]\n    pub nft_custody",
+            "        associated_token::authority = nft_authority,\n    )]\n    pub nft_custody",
+        ).replace(
+            "    #[account(mut)]\n    pub staker: Signer<'info>,",
+            "    /// CHECK: custody authority\n"
+            "    #[account(seeds = [b\"nft-authority\", vault.key().as_ref()], bump)]\n"
+            "    pub nft_authority: UncheckedAccount<'info>,\n"
+            "    #[account(mut)]\n    pub staker: Signer<'info>,",
+        ).replace(
+            "    #[account(mut, token::mint = nft_mint)]\n    pub vault_nft",
+            "    #[account(mut, token::mint = nft_mint, seeds = [b\"vault\", nft_mint.key().as_ref()], bump)]\n"
+            "    pub vault_nft",
+        )
+        assert self.pattern.scan("test.rs", content) == []
+
+    def test_custody_owned_by_foreign_account_is_medium(self):
+        content = read_test_file("vulnerable", "nft_validation_gaps.rs").replace(
+            "        associated_token::authority = staker,\n    )]\n    pub nft_custody",
+            "        associated_token::authority = vault,\n    )]\n    pub nft_custody",
+        )
+        findings = self.pattern.scan("test.rs", content)
+        assert [(f.line, f.severity) for f in findings] == [(61, "Medium"), (114, "High")]
+        assert "not a PDA of this program" in findings[0].description
+
+
+# ─── ANCHOR-039: Unscreened Mint Accepted ───
+
+class TestAnchor039:
+    def setup_method(self):
+        self.pattern = MintScreeningPattern()
+
+    def test_detects_vaults_of_unscreened_mints(self):
+        content = read_test_file("vulnerable", "mint_screening_missing.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [(f.line, f.severity) for f in findings] == [(51, "Medium"), (71, "Medium")]
+        assert "'collateral_mint'" in findings[0].description
+        assert "(nor its mint_authority)" in findings[0].description
+        assert "'reward_mint'" in findings[1].description
+        assert "mint_authority" not in findings[1].description
+
+    def test_screened_and_pinned_mints_are_safe(self):
+        """A freeze_authority constraint, a helper reading it, or an address pin."""
+        content = read_test_file("vulnerable", "mint_screening_missing.rs").replace(
+            "    // Any mint, freeze and mint authority unchecked\n",
+            "    #[account(constraint = collateral_mint.freeze_authority.is_none())]\n",
+        ).replace(
+            "    pub reward_mint: Account<'info, Mint>,",
+            "    #[cfg_attr(not(feature = \"test\"), account(address = mint::USDC))]\n"
+            "    pub reward_mint: Account<'info, Mint>,",
+        )
+        assert self.pattern.scan("test.rs", content) == []
+
+    def test_signer_derived_vault_and_nft_mint_are_safe(self):
+        """A vault derived from the signer holds only the signer's tokens; NFTs are frozen by their edition."""
+        content = """
+        #[derive(Accounts)]
+        pub struct InitializeVault<'info> {
+            #[account(mut)]
+            owner: Signer<'info>,
+            mint: Account<'info, Mint>,
+            #[account(init, payer = owner, space = Vault::LEN,
+                      seeds = [b"vault".as_ref(), owner.key().as_ref(), mint.key().as_ref()], bump)]
+            vault: Account<'info, Vault>,
+            #[account(init, payer = owner, token::mint = mint, token::authority = vault,
+                      seeds = [b"tokens".as_ref(), vault.key().as_ref()], bump)]
+            vault_token_account: Account<'info, TokenAccount>,
+        }
+
+        #[derive(Accounts)]
+        pub struct Stake<'info> {
+            pub pool: Account<'info, Pool>,
+            #[account(mint::decimals = 0, constraint = nft_mint.supply == 1)]
+            pub nft_mint: Account<'info, Mint>,
+            #[account(init_if_needed, payer = staker,
+                      associated_token::mint = nft_mint, associated_token::authority = pool)]
+            pub nft_custody: Account<'info, TokenAccount>,
+            #[account(mut)]
+            pub staker: Signer<'info>,
+        }
+        """
+        assert self.pattern.scan("test.rs", content) == []
+
+
+# ─── ANCHOR-040: Business Logic In Constraint ───
+
+class TestAnchor040:
+    def setup_method(self):
+        self.pattern = ConstraintLogicPattern()
+
+    def test_detects_arithmetic_clock_and_combined_conditions(self):
+        content = read_test_file("vulnerable", "constraint_business_logic.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [(f.line, f.severity) for f in findings] == [(25, "Low"), (47, "Low")]
+        assert "does arithmetic, reads the clock, raw account data or return data and combines 3 conditions" \
+            in findings[0].description
+        assert "generic ConstraintRaw error" in findings[0].description
+        assert "ConstraintRaw" not in findings[1].description
+
+    def test_account_relations_are_safe(self):
+        """Key and field comparisons, including zero-copy loads, are what constraints are for."""
+        content = """
+        #[derive(Accounts)]
+        pub struct Swap<'info> {
+            #[account(constraint = tick_array.load()?.pool_id == pool_state.key())]
+            pub tick_array: AccountLoader<'info, TickArrayState>,
+            #[account(constraint = vault.key() == pool_state.load()?.token_vault_0 @ ErrorCode::InvalidVault)]
+            pub vault: Account<'info, TokenAccount>,
+            #[account(constraint = game.game_state != 0 && game.player_x != Pubkey::default())]
+            pub game: Account<'info, Game>,
+            #[account(constraint = *escrow.to_account_info().key == state.escrow)]
+            pub escrow: Account<'info, Escrow>,
+        }
+        """
+        assert self.pattern.scan("test.rs", content) == []
+
+
+# ─── ANCHOR-041: Unscreened Token-2022 Extensions ───
+
+class TestAnchor041:
+    def setup_method(self):
+        self.pattern = TokenExtensionsPattern()
+
+    def test_detects_interface_mints_with_unscreened_extensions(self):
+        content = read_test_file("vulnerable", "token2022_extensions_unscreened.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [(f.line, f.severity) for f in findings] == [(45, "High"), (66, "Medium")]
+        assert "permanent delegate, transfer hook, confidential transfers or default account state" \
+            in findings[0].description
+        # The helper screens the delegate and the hook only
+        assert "screened for confidential transfers or default account state." in findings[1].description
+
+    def test_allowlist_and_supported_mint_check_are_safe(self):
+        """An allowlist over get_extension_types(), or an unresolved is_supported_mint(mint) call."""
+        content = read_test_file("vulnerable", "token2022_extensions_unscreened.rs").replace(
+            "        pool.vault = ctx.accounts.vault.key();\n",
+            "        pool.vault = ctx.accounts.vault.key();\n"
+            "        require!(util::is_supported_mint(&ctx.accounts.mint)?, AmmError::UnsupportedMint);\n",
+        ).replace(
+            "    require!(state.get_extension::<PermanentDelegate>().is_err(), AmmError::UnsupportedMint);\n"
+            "    require!(state.get_extension::<TransferHook>().is_err(), AmmError::UnsupportedMint);\n",
+            "    for ext in state.get_extension_types()? {\n"
+            "        require!(ALLOWED_EXTENSIONS.contains(&ext), AmmError::UnsupportedMint);\n"
+            "    }\n",
+        )
+        assert self.pattern.scan("test.rs", content) == []
+
+    def test_mints_without_program_custody_are_safe(self):
+        """A pass-through transfer between the caller's own accounts holds nothing for the program."""
+        content = """
+        #[derive(Accounts)]
+        pub struct ProxyTransfer<'info> {
+            pub authority: Signer<'info>,
+            pub mint: InterfaceAccount<'info, Mint>,
+            #[account(mut)]
+            pub from: InterfaceAccount<'info, TokenAccount>,
+            #[account(mut)]
+            pub to: InterfaceAccount<'info, TokenAccount>,
+            pub token_program: Interface<'info, TokenInterface>,
+        }
+        """
+        assert self.pattern.scan("test.rs", content) == []
+
+
+# ─── ANCHOR-042: Enforcement Left To Client ───
+
+class TestAnchor042:
+    def setup_method(self):
+        self.pattern = ClientEnforcementPattern()
+
+    def test_detects_client_claims_and_commented_out_constraints(self):
+        content = read_test_file("vulnerable", "client_side_enforcement.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [8, 47, 65, 69]
+        assert "frontend validates amount" in findings[0].description
+        assert "'amount'" in findings[0].description
+        assert "`constraint = vault.owner == pool.key()`" in findings[1].description
+        assert "'position'" in findings[2].description
+        assert "'config'" in findings[3].description
+        # The deposit doc claims an off-chain cap, but the handler requires it too
+        assert all(f.line != 25 for f in findings)
+
+    def test_on_chain_counterparts_are_safe(self):
+        """A require on the argument, a live constraint on the account, a commented
+        account validated from remaining_accounts."""
+        content = read_test_file("vulnerable", "client_side_enforcement.rs").replace(
+            "    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {\n",
+            "    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {\n"
+            "        require!(amount <= ctx.accounts.pool.total, VaultError::CapExceeded);\n",
+        ).replace(
+            "        mut,\n        // constraint = vault.owner == pool.key(),\n",
+            "        mut,\n        // constraint = vault.owner == pool.key(),\n"
+            "        constraint = vault.owner == pool.key(),\n",
+        ).replace(
+            "    #[account(mut)]\n    pub position: Account<'info, Position>,",
+            "    #[account(mut, has_one = owner)]\n    pub position: Account<'info, Position>,",
+        ).replace(
+            "        let reward = ctx.accounts.position.pending;\n",
+            "        let config = Account::<Config>::try_from(&ctx.remaining_accounts[0])?;\n"
+            "        require!(!config.paused, VaultError::CapExceeded);\n"
+            "        let reward = ctx.accounts.position.pending;\n",
+        )
+        assert self.pattern.scan("test.rs", content) == []
+
+
+# ─── ANCHOR-043: Mixed Token Programs ───
+
+class TestAnchor043:
+    def setup_method(self):
+        self.pattern = TokenProgramMixPattern()
+
+    def test_detects_legacy_program_accounts_and_cpis(self):
+        content = read_test_file("vulnerable", "token_program_mix.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [29, 64, 74]
+        assert "`spl_token::instruction::transfer`" in findings[0].description
+        assert "'token_program' is the legacy Program<'info, Token>" in findings[1].description
+        # Imported as LegacyTokenAccount
+        assert "'user_account' is a legacy Account<'info, TokenAccount>" in findings[2].description
+
+    def test_interface_end_to_end_is_safe(self):
+        """Interface types throughout; an owner comparison with spl_token::ID only tells the programs apart."""
+        content = read_test_file("vulnerable", "token_program_mix.rs").replace(
+            "    pub token_program: Program<'info, Token>,", "    pub token_program: Interface<'info, TokenInterface>,"
+        ).replace(
+            "    pub user_account: Account<'info, LegacyTokenAccount>,",
+            "    pub user_account: Account<'info, TokenAccount>,",
+        ).replace("&spl_token::instruction::transfer(\n                &spl_token::ID,",
+                  "&spl_token_2022::instruction::transfer(\n                ctx.accounts.token_program.key,")
+        assert self.pattern.scan("test.rs", content) == []
+
+
+# ─── ANCHOR-044: Weak CHECK Justification ───
+
+class TestAnchor044:
+    def setup_method(self):
+        self.pattern = CheckCommentPattern()
+
+    def test_detects_empty_generic_and_unspecific_checks(self):
+        content = read_test_file("vulnerable", "check_comment_weak.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [22, 25, 28]
+        assert "is empty" in findings[0].description
+        assert "generic (\"PDA\")" in findings[1].description
+        assert "does not say which constraint" in findings[2].description
+
+    def test_required_tag_must_name_a_constraint_or_function(self):
+        content = read_test_file("vulnerable", "check_comment_weak.rs").replace(
+            "/// CHECK: escrow signer", "/// CHECK(validated-by=seeds): escrow signer"
+        ).replace(
+            "/// CHECK: validated in", "/// CHECK(validated-by=check_oracle, owner): validated in"
+        )
+        self.pattern.options = {"require-tag": True}
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [22, 25, 28, 30, 33]
+        assert "validated-by=owner, but the field has no `owner` constraint" in findings[3].description
+        assert all("has no `CHECK(validated-by=...)` tag" in findings[i].description for i in (0, 1, 2, 4))
+
+    def test_project_config_is_read_from_target_root(self, tmp_path):
+        (tmp_path / "lib.rs").write_text(read_test_file("vulnerable", "check_comment_weak.rs"))
+        (tmp_path / "anchor-shield.toml").write_text(
+            '[rules."ANCHOR-044"]\ngeneric = ["escrow signer, address checked by seeds"]\n'
+        )
+        report = AnchorShieldEngine(only=["ANCHOR-044"]).scan_directory(str(tmp_path))
+        assert sorted(f.line for f in report.findings) == [22, 25, 28, 36]
+        # Without the file the defaults apply
+        assert len(AnchorShieldEngine(only=["ANCHOR-044"]).scan_content(
+            read_test_file("vulnerable", "check_comment_weak.rs"), "lib.rs").findings) == 3
+
+
+# ─── ANCHOR-045: Q64.64 Scale Mismatch ─────────────────────────────
+
+class TestAnchor045:
+    def setup_method(self):
+        self.pattern = FixedPointPattern()
+
+    def test_detects_q64_mixed_with_plain_integers(self):
+        content = read_test_file("vulnerable", "q64_scale_mismatch.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [19, 27, 36]
+        assert "into plain 'fees_owed'" in findings[0].description
+        assert "adds plain integer 'reward_amount'" in findings[1].description
+        assert "compares plain integer 'min_price'" in findings[2].description
+
+    def test_q64_conversion_and_scalar_multiples_are_safe(self):
+        content = """
+        pub fn accrue(pool: &mut Pool, time_delta: u64) {
+            let growth_x64 = U256::from(time_delta)
+                .mul_div_floor(U256::from(pool.emissions_per_second_x64), U256::from(pool.liquidity))
+                .unwrap();
+            pool.reward_growth_global_x64 = pool.reward_growth_global_x64.checked_add(growth_x64.as_u128()).unwrap();
+            let owed = (growth_x64.as_u128() * pool.liquidity as u128) >> 64;
+            let half_x64 = pool.sqrt_price_x64 / 2;
+        }
+        """
+        assert self.pattern.scan("test.rs", content) == []
+
+
+# ─── ANCHOR-046: Unchecked Arithmetic On Balances ──────────────────
+
+class TestAnchor046:
+    def setup_method(self):
+        self.pattern = UncheckedArithmeticPattern()
+
+    def test_detects_unchecked_balance_updates_as_silent_wrap(self):
+        content = read_test_file("vulnerable", "unchecked_balance_arithmetic.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [11, 27, 41]
+        assert all(f.severity == "High" and "silent wrap" in f.description for f in findings)
+        assert "In accrue (called from claim)" in findings[2].description
+
+    def test_overflow_checks_downgrade_to_panic(self):
+        content = read_test_file("vulnerable", "unchecked_balance_arithmetic.rs")
+        self.pattern.overflow_checks = True
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [11, 27, 41]
+        assert all(f.severity == "Medium" and "panic/DoS" in f.description for f in findings)
+
+    def test_profile_is_read_from_workspace_manifest(self, tmp_path):
+        src = tmp_path / "programs" / "vault" / "src"
+        src.mkdir(parents=True)
+        (src / "lib.rs").write_text(read_test_file("vulnerable", "unchecked_balance_arithmetic.rs"))
+        (tmp_path / "programs" / "vault" / "Cargo.toml").write_text('[package]\nname = "vault"\n')
+        (tmp_path / "Cargo.toml").write_text(
+            '[workspace]\nmembers = ["programs/*"]\n\n[profile.release]\noverflow-checks = true\n'
+        )
+        assert load_overflow_checks(str(src / "lib.rs")) is True
+        report = AnchorShieldEngine(only=["ANCHOR-046"]).scan_directory(str(tmp_path))
+        assert {f.severity for f in report.findings} == {"Medium"}
+        (tmp_path / "Cargo.toml").write_text('[workspace]\nmembers = ["programs/*"]\n')
+        report = AnchorShieldEngine(only=["ANCHOR-046"]).scan_file(str(src / "lib.rs"))
+        assert {f.severity for f in report.findings} == {"High"}
+        assert "[profile.release] does not enable overflow-checks" in report.findings[0].description
+
+
+# ─── ANCHOR-047: Reachable Division By Zero ────────────────────────
+
+class TestAnchor047:
+    def setup_method(self):
+        self.pattern = ZeroDivisionPattern()
+
+    def test_reports_zero_condition_and_path(self):
+        content = read_test_file("vulnerable", "zero_division_paths.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [14, 24, 27]
+        assert "which is zero when vault.total_assets == 0;" in findings[0].description
+        assert findings[1].details.to_dict() == {
+            "type": "zero-division", "function": "swap", "line": 24,
+            "expression": "let price = vault.total_assets * PRECISION / net", "denominator": "net",
+            "zero_condition": ["amount == fee"], "path_condition": ["vault.fee_enabled"],
+        }
+        assert findings[2].details.path_condition == ["!(vault.fee_enabled)"]
+
+    def test_substitutes_locals_into_the_denominator(self):
+        content = read_test_file("vulnerable", "zero_division_paths.rs").replace(
+            "let shares = amount * vault.total_shares / vault.total_assets;",
+            "let interest = vault.total_shares * 5 * amount;\n"
+            "        let shares = amount * 100 / (vault.total_shares + interest);",
+        )
+        findings = self.pattern.scan("test.rs", content)
+        assert findings[0].details.zero_condition == ["vault.total_shares == 0"]
+        guarded = content.replace("let interest", "require_neq!(vault.total_shares, 0);\n        let interest")
+        assert [f.line for f in self.pattern.scan("test.rs", guarded)] == [26, 29]
+
+
+# ─── ANCHOR-048: Uncapped Privileged Amount ─────────────────────────
+
+class TestAnchor048:
+    def setup_method(self):
+        self.pattern = AmountCapsPattern()
+
+    def test_reports_admin_and_pda_signed_amounts(self):
+        content = read_test_file("vulnerable", "uncapped_admin_mint.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [(f.line, f.severity) for f in findings] == [(13, "Medium"), (31, "Medium")]
+        assert "only 'admin' can call it" in findings[0].description
+        assert "argument 'lamports'" in findings[1].description
+        assert "such as Config.max_mint_per_ix" in findings[1].description
+
+    def test_program_without_any_cap(self):
+        content = read_test_file("vulnerable", "uncapped_admin_mint.rs").replace(
+            "pub max_mint_per_ix: u64,", ""
+        ).replace("require!(amount <= ctx.accounts.config.max_mint_per_ix, ErrorCode::CapExceeded);", "")
+        deposit = (
+            "pub fn deposit(ctx: Context<Sweep>, amount: u64) -> Result<()> {\n"
+            "        token::transfer(CpiContext::new(ctx.accounts.token_program.to_account_info(), Transfer {\n"
+            "            from: ctx.accounts.destination.to_account_info(),\n"
+            "            to: ctx.accounts.treasury.to_account_info(),\n"
+            "            authority: ctx.accounts.treasury_authority.to_account_info(),\n"
+            "        }), amount)\n    }\n\n    "
+        )
+        content = content.replace("// SAFE:", deposit + "// SAFE:")
+        findings = self.pattern.scan("test.rs", content)
+        assert findings[0].line == 7 and findings[0].severity == "Low"
+        assert "deposits in deposit; PDA-signed payouts in admin_mint, sweep_treasury" in findings[0].description
+        assert "add a cap field to state" in findings[1].description
+        assert len([f for f in findings if f.severity == "Medium"]) == 3
+
+
+# ─── ANCHOR-049: u64 Multiplication Chain Overflow ──────────────────
+
+class TestAnchor049:
+    def setup_method(self):
+        self.pattern = MulChainPattern()
+
+    def test_reports_chains_past_64_bits(self):
+        content = read_test_file("vulnerable", "u64_mul_chain.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [15, 39]
+        assert "(amount: 64 bits, FEE_BPS = 10000: 14 bits, market.multiplier: 64 bits) " \
+               "whose product can reach 2^142" in findings[0].description
+        assert "let fee = u64::try_from((amount as u128) * (FEE_BPS as u128) * " \
+               "(market.multiplier as u128))" in findings[0].fix_recommendation
+        assert ".and_then(|v| v.checked_mul(elapsed))" in findings[1].fix_recommendation
+
+    def test_narrow_casts_bound_the_product(self):
+        content = read_test_file("vulnerable", "u64_mul_chain.rs").replace(
+            "pub multiplier: u64,", "pub multiplier: u16,"
+        )
+        findings = self.pattern.scan("test.rs", content)
+        # amount (64) + FEE_BPS (14) + multiplier (16) still overflows; the u16 rate chain never did
+        assert [f.line for f in findings] == [15, 39]
+        # u32 amount: 32 + 14 + 16 = 62 bits
+        content = content.replace("amount: u64)", "amount: u32)")
+        assert [f.line for f in self.pattern.scan("test.rs", content)] == [39]
+
+    def test_lending_interest_bug(self):
+        path = os.path.join(os.path.dirname(__file__), os.pardir, "examples", "vulnerable-lending",
+                            "programs", "vulnerable-lending", "src", "lib.rs")
+        with open(path) as f:
+            findings = self.pattern.scan("lib.rs", f.read())
+        assert [f.line for f in findings] == [101]
+        assert "pool.interest_rate: 16 bits" in findings[0].description
+
+
+# ─── ANCHOR-050: Operation Order Precision Loss ─────────────────────
+
+class TestAnchor050:
+    def setup_method(self):
+        self.pattern = OperationOrderPattern()
+
+    def test_reports_both_orders(self):
+        content = read_test_file("vulnerable", "divide_before_multiply.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [18, 26, 28, 36]
+        assert "`per_share` is `pool.rewards / pool.total_shares` (line 17)" in findings[0].description
+        assert "checked_div(pool.total_shares)…checked_mul(elapsed)" in findings[2].description
+        assert "can reach 2^80" in findings[3].description
+
+    def test_rounding_follows_the_payer(self):
+        content = read_test_file("vulnerable", "divide_before_multiply.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert ".mul_div_floor(U128::from(user.shares), U128::from(pool.total_shares))" \
+            in findings[0].fix_recommendation
+        assert "let fee = U128::from(amount)\n      .mul_div_ceil(" in findings[3].fix_recommendation
+        assert "this program's MulDiv (full_math)" in findings[3].fix_recommendation
+        content = content.replace("use crate::libraries::full_math::MulDiv;\n", "")
+        fix = self.pattern.scan("test.rs", content)[3].fix_recommendation
+        assert "raydium-clmm libraries::full_math" in fix
+
+    def test_declared_u128_operands_are_widened(self):
+        content = "fn mul_div_floor(a: u128, b: u128, c: u128) -> u128 {\n    a * b / c\n}\n"
+        assert self.pattern.scan("test.rs", content) == []
+        findings = self.pattern.scan("test.rs", content.replace("u128", "u64"))
+        assert [f.line for f in findings] == [2]
+
+
+# ─── ANCHOR-051: Truncating Value Cast ──────────────────────────────
+
+class TestAnchor051:
+    def setup_method(self):
+        self.pattern = TruncatingCastPattern()
+
+    def test_reports_narrowing_casts(self):
+        content = read_test_file("vulnerable", "truncating_casts.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [13, 20, 22, 53]
+        assert "`reward as u64` narrows a u128 value to u64" in findings[0].description
+        assert "a negative value wraps to ~1.8e19" in findings[1].description
+        assert "u32::try_from(position.unlock_ts - position.opened_ts)" in findings[2].fix_recommendation
+
+    def test_marinade_fee_apply(self):
+        path = os.path.join(os.path.dirname(__file__), os.pardir, "real-world-targets", "marinade-staking", "lib.rs")
+        with open(path) as f:
+            findings = self.pattern.scan("lib.rs", f.read())
+        assert [f.line for f in findings] == [745, 811]
+        fix = findings[0].fix_recommendation
+        assert "u64::try_from(lamports as u128 * self.basis_points as u128 / Self::MAX_BASIS_POINTS as u128)" in fix
+        assert "apply returns u64: make it return Result<u64>" in fix
+
+
+# ─── ANCHOR-052: Rounding Direction Favors User ─────────────────────
+
+class TestAnchor052:
+    def setup_method(self):
+        self.pattern = RoundingDirectionPattern()
+
+    def test_reports_user_favoring_rounding(self):
+        content = read_test_file("vulnerable", "rounding_direction.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [9, 10, 25, 42]
+        assert "`fee` is paid by the user" in findings[0].description
+        assert "mul_div_ceil(U128::from(pool.fee_bps), U128::from(10_000))" in findings[0].fix_recommendation
+        assert "is subtracted from `amount_out` and rounds down" in findings[1].description
+        assert "(shares * pool.total_assets) / pool.total_shares" in findings[2].fix_recommendation
+        assert "div_ceil(pool.total_debt)" in findings[3].fix_recommendation
+
+    def test_classifies_share_math_by_instruction(self):
+        content = read_test_file("vulnerable", "rounding_direction.rs")
+        sites = {(s.function, s.value): s for s in self.pattern.sites("test.rs", content)}
+        assert (sites["deposit", "shares"].beneficiary, sites["deposit", "shares"].should) == ("user", "floor")
+        assert (sites["withdraw_exact", "shares"].beneficiary, sites["withdraw_exact", "shares"].should) == \
+            ("protocol", "ceil")
+        assert not sites["withdraw_exact", "shares"].favors_user
+
+    def test_lp_fee_growth_is_received(self):
+        path = os.path.join(os.path.dirname(__file__), os.pardir, "real-world-targets", "raydium-clmm", "lib.rs")
+        with open(path) as f:
+            assert self.pattern.scan("lib.rs", f.read()) == []
+
+
+# ─── ANCHOR-053: has_one Field Never Set Or Freely Overwritten ──────
+
+class TestAnchor053:
+    def setup_method(self):
+        self.pattern = HasOneBindingPattern()
+
+    def test_reports_unset_and_overwritable_fields(self):
+        content = read_test_file("vulnerable", "has_one_unset.rs")
+        findings = self.pattern.scan("test.rs", content)
+        assert [f.line for f in findings] == [59, 66]
+        assert "no instruction creating Pool accounts (initialize) sets Pool.authority" in findings[0].description
+        assert "WithdrawFees can never succeed" in findings[0].description
+        operator = findings[1]
+        assert "sets Pool.operator" in operator.description
+        assert "set_operator (line 15) rewrites Pool.operator without checking the caller" in operator.description
+        assert "Gate set_operator on the current operator" in operator.fix_recommendation
+
+    def test_fields_set_through_constructors_and_gated_writes(self):
+        content = read_test_file("vulnerable", "has_one_unset.rs")
+        findings = self.pattern.scan("test.rs", content)
+        # Vault.owner is set with set_inner(Vault { owner, .. }) and rewritten under has_one = owner
+        assert not any("Vault.owner" in f.description for f in findings)
+        path = os.path.join(os.path.dirname(__file__), os.pardir, "real-world-targets",
+                            "nft-staking-unaudited", "lib.rs")
+        with open(path) as f:
+            # Details::init(creator, reward_mint, ..) shares its name with NftRecord::init
+            assert self.pattern.scan("lib.rs", f.read()) == []
+
+
+# ─── ANCHOR-054: Saturating Subtraction On Accounting Field ─────────
+
+class TestAnchor054:
+    def setup_method(self):
+        self.pattern = SaturatingSubPattern()
+
+    def test_reports_clamped_balances(self):
+        content = read_test_file("vulnerable", "saturating_accounting.rs")
+        findings = self.pattern.scan("test.rs", content)
+        # elapsed time, the bps spread and the commented clamp are left out
+        assert [f.line for f in findings] == [9, 10, 29]
+        assert "`user.deposited.saturating_sub(amount)` clamps deposited at zero" in findings[0].description
+        assert "user.deposited = user.deposited\n      .checked_sub(amount)" in findings[0].fix_recommendation
+        assert "available returns u64: make it return Result<u64>" in findings[2].fix_recommendation
+
+    def test_allowlist(self):
+        self.pattern.options = {"allow": ["available"]}
+        content = read_test_file("vulnerable", "saturating_accounting.rs")
+        assert [f.line for f in self.pattern.scan("test.rs", content)] == [9, 10]
+        path = os.path.join(os.path.dirname(__file__), os.pardir, "real-world-targets", "marinade-staking", "lib.rs")
+        with open(path) as f:
+            # total_virtual_staked_lamports clamps on slashing by design
+            assert self.pattern.scan("lib.rs", f.read()) == []
+
+
+# ─── ANCHOR-055: Wrapping Arithmetic On Accounting Field ────────────
+
+class TestAnchor055:
+    def setup_method(self):
+        self.pattern = WrappingArithmeticPattern()
+
+    def test_classifies_wrapping_operations(self):
+        content = read_test_file("vulnerable", "wrapping_accounting.rs")
+        findings = self.pattern.scan("test.rs", content)
+        # fee growth, the ring-buffer index and the fee_growth delta are intentional
+        assert [(f.line, f.severity) for f in findings] == [(9, "High"), (10, "High"), (13, "Low")]
+        assert "`vault.total_deposits.wrapping_add(amount)` wraps an accounting value" in findings[0].description
+        assert "vault.total_deposits = vault.total_deposits\n      .checked_add(amount)" in findings[0].fix_recommendation
+        path = os.path.join(os.path.dirname(__file__), os.pardir, "real-world-targets", "raydium-clmm", "lib.rs")
+        with open(path) as f:
+            assert self.pattern.scan("lib.rs", f.read()) == []
+
+    def test_per_path_configuration(self):
+        content = read_test_file("vulnerable", "wrapping_accounting.rs")
+        self.pattern.options = {"intentional": ["epoch_*"], "paths": {"vendor/pool/**": ["*"]}}
+        assert [f.line for f in self.pattern.scan("programs/pool/src/lib.rs", content)] == [9, 10]
+        assert self.pattern.scan("vendor/pool/src/lib.rs", content) == []
+        self.pattern.options = {"paths": {"programs/pool/src/lib.rs": ["total_*", "balance"]}}
+        assert [f.line for f in self.pattern.scan("programs/pool/src/lib.rs", content)] == [13]
+
+
+# ─── ANCHOR-056: Panic In Instruction Handler ───────────────────────
+
+class TestAnchor056:
+    def setup_method(self):
+        self.pattern = HandlerPanicPattern()
+
+    def test_reports_panics_reachable_from_handlers(self):
+        content = read_test_file("vulnerable", "handler_panic.rs")
+        findings = self.pattern.scan("test.rs", content)
+        # bounds-checked and `% len` indexes, bumps.get and the unreachable helper are left out
+        assert [f.line for f in findings] == [17, 25, 32, 35]
+        assert "`data[40..48]` panics when the data is shorter" in findings[0].description
+        assert "data.get(40..48).ok_or(ErrorCode::InvalidAccountData)?" in findings[0].fix_recommendation
+        assert findings[1].description.startswith("In claim_handler, reached from claim: `config.tiers[tier_index as usize]`")
+        assert findings[2].description.startswith("In calc_reward, reached from claim → claim_handler: `Clock::get().unwrap()`")
+        assert "`Clock::get()?` for a Result" in findings[2].fix_recommendation
+
+    def test_calc_reward_clock_unwrap(self):
+        path = os.path.join(os.path.dirname(__file__), os.pardir, "real-world-targets", "nft-staking-unaudited", "lib.rs")
+        with open(path) as f:
+            findings = self.pattern.scan("lib.rs", f.read())
+        assert any(f.line == 325 and "reached from withdraw_reward → withdraw_reward_handler" in f.description
+                   for f in findings)
+        path = os.path.join(os.path.dirname(__file__), os.pardir, "real-world-targets", "anchor-tictactoe", "lib.rs")
+        with open(path) as f:
+            findings = self.pattern.scan("lib.rs", f.read())
+        # the board rows are literal ranges of a [u8; 9]; the move index reaches the #[access_control] guard
+        assert [f.line for f in findings] == [47, 95]
+
+
+# ─── ANCHOR-057: Checked Arithmetic Unwrapped ───────────────────────
+
+class TestAnchor057:
+    def setup_method(self):
+        self.pattern = CheckedUnwrapPattern()
+
+    def test_reports_unwrapped_checked_ops_with_abortable_instructions(self):
+        content = read_test_file("vulnerable", "checked_unwrap.rs")
+        findings = self.pattern.scan("test.rs", content)
+        # ok_or(..)? and unwrap_or(..) are left out
+        assert [(f.line, f.severity) for f in findings] == [(9, "Medium"), (20, "Low"), (27, "Medium")]
+        assert "vault.total_deposits.checked_add(amount).ok_or(VaultError::Overflow)?" in findings[0].fix_recommendation
+        assert "operands from program state only" in findings[1].description
+        assert findings[2].description.startswith("In credit, reached from deposit: `vault.points.checked_mul(amount).expect(..)`")
+        assert "2 of the 2 instruction(s) reaching it pass a caller-chosen operand and can be aborted at will: " \
+               "deposit, withdraw" in findings[2].description
+        assert "credit returns (): make it return Result<()>" in findings[2].fix_recommendation
+
+    def test_supersedes_handler_panic(self):
+        path = os.path.join(os.path.dirname(__file__), os.pardir, "real-world-targets", "anchor-swap", "lib.rs")
+        with open(path) as f:
+            findings = AnchorShieldEngine().scan_source("lib.rs", f.read())
+        at = [f for f in findings if f.line == 389 and f.id in ("ANCHOR-056", "ANCHOR-057")]
+        # coin_lots is shared by buy and sell, both taking the caller's size
+        assert [f.id for f in at] == ["ANCHOR-057"]
+        assert "2 of the 2 instruction(s)" in at[0].description
+        assert "ErrorCode::MathOverflow" in at[0].fix_recommendation
+
+
+# ─── ANCHOR-058: Loop Accumulator Overflow ──────────────────────────
+
+class TestAnchor058:
+    def setup_method(self):
+        self.pattern = LoopAccumulatorPattern()
+
+    def test_bounds_iterations_from_account_data(self):
+        content = read_test_file("vulnerable", "loop_accumulator.rs")
+        findings = self.pattern.scan("test.rs", content)
+        # the counter loop adds no product and is left out
+        assert [(f.line, f.severity) for f in findings] == [(21, "Low"), (25, "Low"), (38, "Low"), (47, "High")]
+        assert "bounded by Pool.tiers, length-checked before every push" in findings[0].description
+        assert "bounded by Pool.caps, #[max_len(8)]" in findings[1].description
+        assert "chosen by the caller: remaining_accounts" in findings[2].description
+        assert findings[3].description.startswith("In accrued: the loop over rates sums `rate * amount` into sum")
+        assert "Pool.rates, which grows by one entry on every call to set_rate with no length cap" in findings[3].description
+
+    def test_calc_reward_schedule_loop(self):
+        path = os.path.join(os.path.dirname(__file__), os.pardir, "real-world-targets", "nft-staking-unaudited", "lib.rs")
+        with open(path) as f:
+            findings = self.pattern.scan("lib.rs", f.read())
+        assert [(f.line, f.severity) for f in findings] == [(358, "Medium")]
+        assert "sums `rewardable_time.checked_mul(reward_emission[ix])` into reward_tokens" in findings[0].description
+        assert "Details.reward_change_time, which grows by one entry on every call to change_reward" \
+               in findings[0].description
+
+
+# ─── ANCHOR-059: Signed Timestamp Cast To Unsigned ──────────────────
+
+class TestAnchor059:
+    def setup_method(self):
+        self.pattern = SignedTimeCastPattern()
+
+    def test_unordered_timestamp_differences(self):
+        content = read_test_file("vulnerable", "signed_time_cast.rs")
+        findings = self.pattern.scan("test.rs", content)
+        # the require!-ordered cast, try_from and .max(0) are left out
+        assert [(f.line, f.severity) for f in findings] == [
+            (10, "Medium"), (11, "Medium"), (28, "Low"), (36, "Medium"), (40, "Medium")]
+        assert "nothing in claim establishes grant.end_ts >= grant.start_ts" in findings[1].description
+        assert "subtracts two stored timestamps whose ordering rate never checks" in findings[2].description
+        assert "a negative i64 becomes ~3.4e38 as u128" in findings[3].description
+        assert "The comment (\"can't be negative\") assumes it" in findings[3].description
+        assert "bound to `idle` (line 39) and cast `idle as u128`" in findings[4].description
+
+    def test_staked_weight_u128_casts(self):
+        path = os.path.join(os.path.dirname(__file__), os.pardir, "real-world-targets", "nft-staking-unaudited", "lib.rs")
+        with open(path) as f:
+            findings = self.pattern.scan("lib.rs", f.read())
+        assert [(f.line, f.severity) for f in findings] == [(205, "Medium"), (211, "Medium")]
+        assert "`self.staking_ends_at - weight_time` is cast `as u128`" in findings[1].description
+        assert "require!(self.staking_ends_at >= weight_time" in findings[1].fix_recommendation
+
+
+# ─── ANCHOR-060: Borrow Check Ignores Debt Or LTV ───────────────────
+
+class TestAnchor060:
+    def setup_method(self):
+        self.pattern = BorrowCollateralCheckPattern()
+
+    def test_borrow_checks_without_debt_or_ltv(self):
+        content = read_test_file("vulnerable", "lending_solvency.rs")
+        findings = self.pattern.scan("test.rs", content)
+        # borrow_checked re-checks through check_solvency, with LTV_BPS
+        assert [(f.line, f.severity) for f in findings] == [(17, "Critical"), (26, "High")]
+        assert "includes neither the existing debt nor an LTV factor; Position.debt is never part of it" \
+               in findings[0].description
+        assert "includes Position.debt but applies no LTV factor" in findings[1].description
+
+    def test_vulnerable_lending_bug_1(self):
+        path = os.path.join(os.path.dirname(__file__), os.pardir, "examples", "vulnerable-lending",
+                            "programs", "vulnerable-lending", "src", "lib.rs")
+        with open(path) as f:
+            findings = self.pattern.scan("lib.rs", f.read())
+        assert [(f.line, f.severity) for f in findings] == [(44, "Critical")]
+        assert findings[0].description.startswith("In borrow: the collateral check compares UserAccount.deposited")
+        assert "shield::lending::ANCHOR-060" == self.pattern.qualified_id
+
+    def test_debt_aware_check_is_safe(self):
+        content = read_test_file("vulnerable", "lending_positions_unbound.rs")
+        assert self.pattern.scan("test.rs", content) == []
+
+
+# ─── ANCHOR-061: Withdraw Ignores Outstanding Debt ──────────────────
+
+class TestAnchor061:
+    def setup_method(self):
+        self.pattern = WithdrawDebtCheckPattern()
+
+    def test_withdraw_without_debt_check(self):
+        content = read_test_file("vulnerable", "lending_solvency.rs")
+        findings = self.pattern.scan("test.rs", content)
+        # withdraw_checked (helper), withdraw_repaid (constraint) and liquidate (repays) are left out
+        assert [(f.line, f.severity) for f in findings] == [(42, "Critical")]
+        assert "checks only that Position.collateral covers the amount; Position.debt is never read" \
+               in findings[0].description
+
+    def test_vulnerable_lending_bug_2(self):
+        path = os.path.join(os.path.dirname(__file__), os.pardir, "examples", "vulnerable-lending",
+                            "programs", "vulnerable-lending", "src", "lib.rs")
+        with open(path) as f:
+            findings = self.pattern.scan("lib.rs", f.read())
+        assert [(f.line, f.severity) for f in findings] == [(72, "Critical")]
+        assert findings[0].description.startswith("In withdraw: the withdrawal checks only that UserAccount.deposited")
+
+    def test_debt_never_borrowed_is_not_outstanding(self):
+        content = read_test_file("vulnerable", "lending_solvency.rs").replace("position.debt += amount;", "")
+        content = content.replace("let new_debt = position.debt + amount;", "let new_debt = amount;")
+        assert self.pattern.scan("test.rs", content) == []
+
+
+# ─── ANCHOR-062: Health Factor Formula Inconsistent ─────────────────
+
+class TestAnchor062:
+    def setup_method(self):
+        self.pattern = HealthFormulaPattern()
+
+    def test_formula_issues(self):
+        content = read_test_file("vulnerable", "lending_health.rs")
+        findings = self.pattern.scan("test.rs", content)
+        # borrow's health (with interest, >= 100) is consistent and not reported
+        assert [(f.line, f.severity) for f in findings] == [(22, "Medium"), (31, "High"), (40, "High")]
+        assert "has no interest term although the program tracks Position.accrued_interest" \
+               in findings[0].description
+        assert "`ltv < LIQUIDATION_LTV_BPS` lets liquidation proceed only for healthy positions" \
+               in findings[1].description
+        assert "only the collateral side is converted with a price" in findings[2].description
+        assert findings[1].details.to_dict() == {
+            "type": "health-formula",
+            "function": "liquidate",
+            "variable": "ltv",
+            "line": 31,
+            "formula": "(position.debt + position.accrued_interest) * 10000 / position.collateral",
+            "orientation": "debt/collateral",
+            "scale": "10000",
+            "comparisons": ["ltv < LIQUIDATION_LTV_BPS"],
+            "issues": [findings[1].details.issues[0]],
+        }
+
+    def test_vulnerable_lending_health(self):
+        path = os.path.join(os.path.dirname(__file__), os.pardir, "examples", "vulnerable-lending",
+                            "programs", "vulnerable-lending", "src", "lib.rs")
+        with open(path) as f:
+            findings = self.pattern.scan("lib.rs", f.read())
+        assert [(f.line, f.severity) for f in findings] == [(104, "High")]
+        issues = findings[0].details.issues
+        assert len(issues) == 3
+        assert issues[0].startswith("`health < 75` puts the threshold below 100")
+        assert "(amount²)" in issues[1]
+        assert "interest_rate is in basis points but the formula never divides by 10000" in issues[2]
+
+    def test_no_lending_position(self):
+        content = read_test_file("vulnerable", "lending_health.rs").replace("pub debt: u64,", "pub owed: u64,")
+        assert self.pattern.scan("test.rs", content) == []
+
+
+# ─── ANCHOR-063: Liquidation Incomplete Or Unincentivized ───────────
+
+class TestAnchor063:
+    def setup_method(self):
+        self.pattern = LiquidationIncompletePattern()
+
+    def test_liquidation_handlers(self):
+        content = read_test_file("vulnerable", "lending_liquidation.rs")
+        findings = self.pattern.scan("test.rs", content)
+        # liquidate pays the liquidator, liquidate_by_keeper is restricted to the pool's keeper
+        assert [(f.line, f.severity) for f in findings] == [(18, "High"), (25, "High"), (34, "Medium")]
+        assert "checks `position.collateral < position.debt` and then changes nothing" in findings[0].description
+        assert "none of its accounts is mut" in findings[0].description
+        assert "writes down Position.collateral without transferring the collateral anywhere" \
+               in findings[1].description
+        assert "transfers nothing to the liquidator and any signer (liquidator) may call it" \
+               in findings[2].description
+
+    def test_vulnerable_lending_liquidate_is_no_op(self):
+        path = os.path.join(os.path.dirname(__file__), os.pardir, "examples", "vulnerable-lending",
+                            "programs", "vulnerable-lending", "src", "lib.rs")
+        with open(path) as f:
+            findings = self.pattern.scan("lib.rs", f.read())
+        assert [(f.line, f.severity) for f in findings] == [(94, "High")]
+        assert findings[0].description.startswith("In liquidate: the liquidation checks `health < 75`")
+
+    def test_liquidator_paid_by_lamports(self):
+        content = read_test_file("vulnerable", "lending_liquidation.rs").replace(
+            "        position.collateral -= repay;\n        Ok(())\n    }\n\n    // VULNERABLE: anyone may",
+            "        position.collateral -= repay;\n"
+            "        ctx.accounts.caller.add_lamports(repay)?;\n"
+            "        Ok(())\n    }\n\n    // VULNERABLE: anyone may",
+        )
+        assert [f.line for f in self.pattern.scan("test.rs", content)] == [18, 35]
+
+    def test_liquidator_paid_by_ledger_credit(self):
+        """Crediting the liquidator's own position pays it; crediting the liquidated one does not."""
+        content = read_test_file("vulnerable", "lending_liquidation.rs").replace(
+            "        position.collateral -= repay;\n        Ok(())\n    }\n\n    // VULNERABLE: anyone may",
+            "        position.collateral -= repay;\n"
+            "        let liquidator = &mut ctx.accounts.liquidator_position;\n"
+            "        liquidator.collateral = liquidator.collateral.checked_add(repay).unwrap();\n"
+            "        Ok(())\n    }\n\n    // VULNERABLE: anyone may",
+        ).replace(
+            "    pub caller: Signer<'info>,\n",
+            "    pub caller: Signer<'info>,\n"
+            "    #[account(mut)]\n"
+            "    pub liquidator_position: Account<'info, Position>,\n",
+        )
+        assert [f.line for f in self.pattern.scan("test.rs", content)] == [18, 36]
+        credited_self = content.replace("ctx.accounts.liquidator_position;", "ctx.accounts.position;")
+        assert [f.line for f in self.pattern.scan("test.rs", credited_self)] == [18, 25, 36]
+
+    def test_state_comes_from_the_handler(self):
+        content = read_test_file("vulnerable", "lending_liquidation.rs")
+        no_op, write_off, treasury = self.pattern.scan("test.rs", content)
+        assert no_op.before_after_state["before"].startswith("`position.collateral < position.debt` holds")
+        assert write_off.before_after_state["after"].startswith(
+            "liquidate_write_off() by any signer (caller): `position.collateral -= repay`"
+        )
+        assert "moves funds to treasury" in treasury.before_after_state["after"]
+        assert "-=" not in self.pattern.get_fix_recommendation()
+
+
+# ─── Integration: Full engine scan ──────────────────────────────────
+
+class TestEngineIntegration:
+    def setup_method(self):
+        self.engine = AnchorShieldEngine()
+
+    def test_scan_vulnerable_directory(self):
+        """Engine should find multiple vulnerabilities in vulnerable test files."""
+        report = self.engine.scan_directory(VULN_DIR)
+        assert report.files_scanned > 0
+        assert len(report.findings) > 0
+        assert report.security_score != "A"
+
+        # Check all pattern IDs are represented
+        found_ids = {f.id for f in report.findings}
+        assert "ANCHOR-001" in found_ids
+        assert "ANCHOR-003" in found_ids
+
+    def test_scan_safe_directory(self):
+        """Engine should find minimal or no issues in safe test files."""
+        report = self.engine.scan_directory(SAFE_DIR)
+        assert report.files_scanned > 0
+        # Safe files should have very few findings
+        critical_high = [f for f in report.findings if f.severity in ("Critical", "High")]
+        # Acceptable: some patterns may still trigger on safe files
+        # but the specific patterns should not
+        anchor_001 = [f for f in report.findings if f.id == "ANCHOR-001"]
+        anchor_003 = [f for f in report.findings if f.id == "ANCHOR-003"]
+        assert len(anchor_001) == 0
+        assert len(anchor_003) == 0
+
+    def test_safe_corpus_has_no_findings(self):
+        """Every rule stays silent on every file of the safe corpus."""
+        report = self.engine.scan_directory(SAFE_DIR)
+        assert [(os.path.basename(f.file), f.line, f.id) for f in report.findings] == []
+
+    def test_report_json_serialization(self):
+        """Scan report should serialize to valid JSON."""
+        report = self.engine.scan_directory(VULN_DIR)
+        import json
+        json_str = report.to_json()
+        parsed = json.loads(json_str)
+        assert "findings" in parsed
+        assert "summary" in parsed
+        assert parsed["files_scanned"] > 0
+
+    def test_security_score_computation(self):
+        """Security score should reflect severity of findings."""
+        report = self.engine.scan_directory(VULN_DIR)
+        # With multiple findings, score should be worse than A
+        assert report.security_score != "A"
+
+    def test_empty_file_no_crash(self):
+        """Engine should handle empty files gracefully."""
+        report = self.engine.scan_content("", "empty.rs")
+        assert report.files_scanned == 1
+        assert len(report.findings) == 0
+        assert report.security_score == "A"
+
+    def test_rule_selection(self):
+        """--only/--skip selectors accept IDs and namespace globs."""
+        only_cpi = AnchorShieldEngine(only=["shield::cpi::*"])
+        assert [p.id for p in only_cpi.patterns] == ["ANCHOR-007"]
+
+        skipped = AnchorShieldEngine(skip=["anchor-00[1-3]"])
+        ids = {p.id for p in skipped.patterns}
+        assert "ANCHOR-001" not in ids and "ANCHOR-004" in ids
+
+        report = AnchorShieldEngine(only=["ANCHOR-003"]).scan_directory(VULN_DIR)
+        assert {f.id for f in report.findings} == {"ANCHOR-003"}
+
+        with pytest.raises(ValueError, match="shield::typo"):
+            AnchorShieldEngine(only=["shield::typo::*"])
+
+    def test_remediation_effort_rollup(self):
+        """Every finding carries an effort class and the summary rolls them up."""
+        report = self.engine.scan_directory(VULN_DIR)
+        assert all(f.remediation_effort in EFFORT_LEVELS for f in report.findings)
+        assert sum(report.summary["by_effort"].values()) == len(report.findings)
+
+        realloc = next(f for f in report.findings if f.id == "ANCHOR-003")
+        assert realloc.remediation_effort == "mechanical"
+        stale = [f for f in report.findings if f.id == "ANCHOR-007"]
+        assert {f.remediation_effort for f in stale} <= {"mechanical", "localized"}
 
 
 if __name__ == "__main__":
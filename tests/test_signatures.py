@@ -0,0 +1,26 @@
+"""Tests for return-type parsing and the Result note of fix texts."""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.patterns.signatures import result_note, return_types
+
+
+def test_return_types_of_a_unit():
+    code = '''
+pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> { Ok(()) }
+fn fee<T: Copy>(amount: T) -> u64 where T: Into<u64> { 0 }
+fn log(msg: &str) { }
+'''
+    assert return_types(code) == {"deposit": "Result<()>", "fee": "u64"}
+
+
+def test_result_note():
+    assert result_note("deposit", "Result<()>") == ""
+    assert result_note("fee", "u64") == (
+        "\nfee returns u64: make it return Result<u64> so the error reaches the instruction."
+    )
+    assert result_note("log", "", ", or handle it").endswith("Result<()> so the error reaches the instruction, "
+                                                            "or handle it.")
@@ -0,0 +1,53 @@
+"""Tests for scanned-surface statistics (scan --stats)."""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.engine import AnchorShieldEngine
+from scanner.report import format_terminal_report
+
+TEST_DIR = os.path.join(os.path.dirname(__file__), "test_patterns", "vulnerable")
+
+
+def _read(name: str) -> str:
+    with open(os.path.join(TEST_DIR, name)) as f:
+        return f.read()
+
+
+class TestSurfaceStats:
+    def test_counts_analyzed_surface(self):
+        report = AnchorShieldEngine(stats=True).scan_content(_read("vault_creation_unpinned.rs"), "lib.rs")
+        stats = report.stats
+        assert (stats["files"], stats["loc"], stats["programs"]) == (1, 57, 1)
+        assert stats["instructions"] == {"entrypoints": 2, "handlers": 0}
+        assert (stats["accounts_structs"], stats["cpis"]) == (2, 1)
+        assert stats["unparsed"] == [] and stats["rules_skipped"] == []
+        assert "ANCHOR-017" in stats["rules_executed"]
+        assert report.to_dict()["stats"] == stats
+
+    def test_disabled_by_default(self):
+        report = AnchorShieldEngine().scan_content(_read("vault_creation_unpinned.rs"), "lib.rs")
+        assert report.stats is None
+        assert "Scanned surface" not in format_terminal_report(report)
+
+    def test_reports_unparsed_files_and_skipped_rules(self):
+        engine = AnchorShieldEngine(stats=True, skip=["ANCHOR-010"])
+        broken = next(p for p in engine.patterns if p.id == "ANCHOR-017")
+
+        def fail(path, content):
+            raise RuntimeError("boom")
+        broken.scan = fail
+
+        report = engine.scan_content("pub fn truncated(ctx: Context<X>) -> Result<()> {\n    Ok(())\n", "lib.rs")
+        stats = report.stats
+        assert stats["unparsed"] == [{"file": "lib.rs", "lines": [1, 2], "reason": "unbalanced braces"}]
+        assert {"rule": "ANCHOR-010", "reason": "deselected by --only/--skip"} in stats["rules_skipped"]
+        assert {"rule": "ANCHOR-017", "reason": "raised an error (1 file(s))"} in stats["rules_skipped"]
+        assert stats["rule_errors"] == [{"file": "lib.rs", "rule": "ANCHOR-017", "error": "RuntimeError: boom"}]
+        assert "ANCHOR-017" not in stats["rules_executed"]
+
+        text = format_terminal_report(report)
+        assert "lib.rs:1-2 (unbalanced braces)" in text
+        assert "Skipped ANCHOR-017: raised an error" in text
@@ -0,0 +1,117 @@
+"""Tests for inline/baseline suppressions and their expiry."""
+
+import os
+import sys
+from datetime import date
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.engine import AnchorShieldEngine
+from scanner.report import format_terminal_report
+from scanner.suppression import inline_suppressions, write_baseline
+
+TODAY = date(2025, 6, 1)
+
+VULN = '''
+pub fn process_data(ctx: Context<ProcessData>) -> Result<()> {
+    let data = ctx.accounts.data_source.try_borrow_data()?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ProcessData<'info> {
+    pub authority: Signer<'info>,
+    {comment}
+    pub data_source: AccountInfo<'info>,
+}
+'''
+
+
+def _scan(content, baseline=None, today=TODAY):
+    engine = AnchorShieldEngine(only=["ANCHOR-006"], baseline=baseline, today=today)
+    return engine.scan_content(content, "lib.rs")
+
+
+class TestInlineSuppression:
+    def test_comment_covers_next_line(self):
+        sups = inline_suppressions(
+            "// anchor-shield: ignore ANCHOR-006, shield::cpi::* expires = \"2025-12-01\"\n"
+            "// unrelated note\n"
+            "pub vault: AccountInfo<'info>,\n"
+        )
+        assert set(sups) == {1, 3}
+        assert sups[3][0].rules == ("ANCHOR-006", "shield::cpi::*")
+        assert sups[3][0].expires == date(2025, 12, 1)
+
+    def test_active_suppression_hides_finding(self):
+        plain = _scan(VULN.replace("{comment}", ""))
+        assert len(plain.findings) == 1
+
+        content = VULN.replace(
+            "{comment}",
+            '// anchor-shield: ignore shield::accounts::* expires = "2025-12-01" reason = "read-only"',
+        )
+        report = _scan(content)
+        assert report.findings == []
+        assert report.summary["suppressed"] == 1
+
+    def test_expired_suppression_resurfaces(self):
+        content = VULN.replace(
+            "{comment}", '// anchor-shield: ignore ANCHOR-006 expires = "2025-05-01"',
+        )
+        report = _scan(content)
+        assert len(report.findings) == 1
+        marker = report.findings[0].suppression
+        assert marker == {"source": "inline", "expires": "2025-05-01", "reason": "", "expired": True}
+        assert report.summary["expired_suppressions"] == 1
+        assert "Expired suppression" in format_terminal_report(report)
+
+    def test_unparseable_expiry_is_treated_as_expired(self):
+        content = VULN.replace(
+            "{comment}", '// anchor-shield: ignore ANCHOR-006 expires = "next quarter"',
+        )
+        assert len(_scan(content).findings) == 1
+
+
+class TestBaselineSuppression:
+    def test_baseline_expiry(self, tmp_path):
+        content = VULN.replace("{comment}", "")
+        findings = _scan(content).findings
+        path = str(tmp_path / "baseline.json")
+        write_baseline(findings, path, expires=date(2025, 9, 30), reason="audit pending")
+
+        assert _scan(content, baseline=path).findings == []
+
+        later = _scan(content, baseline=path, today=date(2025, 10, 1))
+        assert len(later.findings) == 1
+        assert later.findings[0].suppression["source"] == "baseline"
+        assert later.findings[0].suppression["reason"] == "audit pending"
+
+    def test_baseline_does_not_hide_new_duplicate_text(self, tmp_path):
+        content = VULN.replace("{comment}", "")
+        path = str(tmp_path / "baseline.json")
+        write_baseline(_scan(content).findings, path)
+
+        # The same declaration in a new accounts struct is a new finding
+        grown = content + (
+            "\n#[derive(Accounts)]\npub struct ProcessMore<'info> {\n"
+            "    pub data_source: AccountInfo<'info>,\n}\n"
+        )
+        reported = _scan(grown, baseline=path).findings
+        assert [(f.scope, f.suppression) for f in reported] == [("struct ProcessMore", None)]
+
+    def test_baseline_covers_findings_folded_by_deduplication(self, tmp_path):
+        """Suppression runs before merging, so the absorbed finding needs its own entry."""
+        with open(os.path.join(os.path.dirname(__file__), "test_patterns", "vulnerable",
+                               "type_cosplay_no_discriminator.rs")) as fh:
+            content = fh.read()
+        engine = AnchorShieldEngine(only=["ANCHOR-004", "ANCHOR-006"],
+                                    precedence={"ANCHOR-004": ["ANCHOR-006"]}, today=TODAY)
+        findings = engine.scan_content(content, "lib.rs").findings
+        assert [(f.id, [s["id"] for s in f.supporting]) for f in findings] == [("ANCHOR-004", ["ANCHOR-006"])]
+        path = str(tmp_path / "baseline.json")
+        assert write_baseline(findings, path) == 2
+
+        engine = AnchorShieldEngine(only=["ANCHOR-004", "ANCHOR-006"], precedence={"ANCHOR-004": ["ANCHOR-006"]},
+                                    baseline=path, today=TODAY)
+        assert engine.scan_content(content, "lib.rs").findings == []
@@ -0,0 +1,140 @@
+"""Tests for ranking findings by the on-chain balances of the vaults they guard (scan --rpc)."""
+
+import os
+import sys
+
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from scanner.engine import ScanReport
+from scanner.patterns.base import Finding
+from scanner.report import format_markdown_report, format_terminal_report
+from scanner.solana_client import b58decode, find_program_address, is_on_curve
+from scanner.value_at_risk import assess_value_at_risk, vault_accounts
+
+PROGRAM_ID = "Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS"
+USDC = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"
+TREASURY = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM"
+
+SOURCE = f'''use anchor_lang::prelude::*;
+
+declare_id!("{PROGRAM_ID}");
+
+const POOL_SEED: &[u8] = b"pool";
+
+#[program]
+pub mod vaults {{
+    use super::*;
+
+    pub fn sweep(ctx: Context<Sweep>) -> Result<()> {{
+        Ok(())
+    }}
+
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {{
+        Ok(())
+    }}
+}}
+
+#[derive(Accounts)]
+pub struct Sweep<'info> {{
+    #[account(mut, address = pubkey!("{TREASURY}"))]
+    pub treasury: SystemAccount<'info>,
+    pub admin: Signer<'info>,
+}}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {{
+    #[account(seeds = [POOL_SEED], bump)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut, seeds = [b"vault", pool.key().as_ref()], bump)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [b"user", owner.key().as_ref()], bump)]
+    pub user_vault: Account<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+}}
+'''
+
+
+class FakeClient:
+    network = "mainnet-beta"
+
+    def __init__(self, accounts, owned=None):
+        self.accounts = accounts
+        self.owned = owned or {}
+        self.requests = []
+
+    def get_multiple_accounts(self, addresses):
+        self.requests.append(list(addresses))
+        return {a: self.accounts[a] for a in addresses if a in self.accounts}
+
+    def get_token_accounts_by_owner(self, owner):
+        return self.owned.get(owner, [])
+
+
+def _finding(severity, line, rule):
+    return Finding(id=rule, name=rule, severity=severity, file="lib.rs", line=line, description="",
+                   root_cause="", exploit_scenario="", fix_recommendation="Fix it", code_snippet="")
+
+
+def _report(tmp_path):
+    (tmp_path / "lib.rs").write_text(SOURCE)
+    return ScanReport(target=str(tmp_path), findings=[
+        _finding("Critical", 11, "ANCHOR-001"),   # sweep: empty treasury
+        _finding("High", 15, "ANCHOR-002"),       # withdraw: funded vault
+    ])
+
+
+def _addresses():
+    pool = find_program_address([b"pool"], PROGRAM_ID)[0]
+    vault = find_program_address([b"vault", b58decode(pool)], PROGRAM_ID)[0]
+    return pool, vault
+
+
+class TestValueAtRisk:
+    def test_program_derived_addresses(self):
+        # Solana's documented example: seeds ["helloWorld"] under the system program
+        assert find_program_address([b"helloWorld"], "11111111111111111111111111111111") == \
+            ("46GZzzetjCURsdFPb7rcnspbEMnCBXe9kpjrsZAkKb6X", 254)
+        assert is_on_curve(b58decode("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"))
+
+    def test_vaults_resolved_from_source(self):
+        pool, vault = _addresses()
+        assert vault_accounts(SOURCE, 15) == ({"pool": pool, "vault": vault}, ["user_vault"])
+        assert vault_accounts(SOURCE, 11) == ({"treasury": TREASURY}, [])
+
+    def test_funded_high_outranks_empty_critical(self, tmp_path):
+        pool, vault = _addresses()
+        client = FakeClient({
+            TREASURY: {"lamports": 890880, "owner": "11111111111111111111111111111111", "space": 0, "token": None},
+            # Rent-exempt minimum for 100 bytes: not value
+            pool: {"lamports": 1_586_880, "owner": PROGRAM_ID, "space": 100, "token": None},
+            vault: {"lamports": 2_039_280, "owner": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA", "space": 165,
+                    "token": {"mint": USDC, "amount": 250_000_000 * 10 ** 6, "decimals": 6}},
+        })
+        report = _report(tmp_path)
+        assert assess_value_at_risk(report, client) == 2
+        assert [f.id for f in report.findings] == ["ANCHOR-002", "ANCHOR-001"]
+        high, critical = report.findings
+        assert high.value_at_risk["usd"] == 250_000_000.0 and high.value_at_risk["unresolved"] == ["user_vault"]
+        assert critical.value_at_risk["empty"] and critical.value_at_risk["usd"] == 0.0
+        assert "Value at risk: $250,000,000 (pool, vault, mainnet-beta)" in format_terminal_report(report)
+        assert "**Value at risk:** empty (treasury, mainnet-beta)" in format_markdown_report(report)
+        assert high.to_dict()["value_at_risk"]["accounts"][1]["tokens"][0]["mint"] == USDC
+
+    def test_unpriced_and_test_network_holdings(self, tmp_path):
+        pool, vault = _addresses()
+        client = FakeClient({
+            pool: {"lamports": 5_000_000_000, "owner": PROGRAM_ID, "space": 100, "token": None},
+            vault: {"lamports": 2_039_280, "owner": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA", "space": 165,
+                    "token": {"mint": USDC, "amount": 10 ** 6, "decimals": 6}},
+        })
+        report = _report(tmp_path)
+        assess_value_at_risk(report, client)
+        withdraw = next(f for f in report.findings if f.id == "ANCHOR-002")
+        # Lamports without a SOL price leave the total unpriced
+        assert withdraw.value_at_risk["usd"] is None
+        assess_value_at_risk(report, client, {"SOL": 100.0})
+        assert round(withdraw.value_at_risk["usd"], 2) == round((5_000_000_000 - 228 * 6960) / 1e9 * 100 + 1, 2)
+        client.network = "devnet"
+        assess_value_at_risk(report, client, {"SOL": 100.0})
+        assert withdraw.value_at_risk["usd"] == 0.0
+        assert [f.id for f in report.findings] == ["ANCHOR-001", "ANCHOR-002"]